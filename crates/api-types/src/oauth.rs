@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
 use uuid::Uuid;
@@ -28,6 +29,36 @@ pub struct HandoffRedeemResponse {
     pub refresh_token: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+pub struct DeviceInitRequest {
+    pub provider: String,
+    pub app_challenge: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+pub struct DeviceInitResponse {
+    pub handoff_id: Uuid,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_at: DateTime<Utc>,
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+pub struct DevicePollRequest {
+    pub handoff_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum DevicePollResponse {
+    Pending,
+    Authorized { app_code: String },
+    Denied,
+    Expired,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, TS)]
 pub struct LocalLoginRequest {
     pub email: String,
@@ -16,6 +16,9 @@ pub struct Workspace {
     pub files_changed: Option<i32>,
     pub lines_added: Option<i32>,
     pub lines_removed: Option<i32>,
+    /// Milliseconds spent running agents and scripts in the local workspace.
+    #[ts(type = "number | null")]
+    pub time_spent_ms: Option<i64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::Workspace;
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct DeleteWorkspaceRequest {
     pub local_workspace_id: Uuid,
@@ -21,6 +23,8 @@ pub struct CreateWorkspaceRequest {
     pub lines_added: Option<i32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lines_removed: Option<i32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_spent_ms: Option<i64>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -36,4 +40,30 @@ pub struct UpdateWorkspaceRequest {
     pub lines_added: Option<Option<i32>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub lines_removed: Option<Option<i32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_spent_ms: Option<Option<i64>>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ListWorkspaceChangesQuery {
+    pub project_id: Uuid,
+    /// `cursor` from the previous response. Omit for a full sync.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub since: Option<i64>,
+}
+
+/// Workspaces in a project that changed since a sync cursor.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct WorkspaceChangesResponse {
+    /// Workspaces created or updated since the cursor.
+    pub workspaces: Vec<Workspace>,
+    /// Workspaces deleted since the cursor. Always empty on a full sync.
+    pub deleted_workspace_ids: Vec<Uuid>,
+    /// Set when `workspaces` is the whole project, either because no cursor
+    /// was given or because it predates the retained deletions. Replace the
+    /// local copy instead of merging.
+    pub full_sync: bool,
+    /// Pass as `since` on the next request. Writes still in flight are at or
+    /// above it, so a workspace may be returned twice across requests.
+    pub cursor: i64,
 }
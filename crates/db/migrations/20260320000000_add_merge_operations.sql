@@ -0,0 +1,19 @@
+-- Tracks git merge/rebase operations while they run so that operations cut
+-- short by a server restart can be detected and recovered at startup.
+CREATE TABLE merge_operations (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB NOT NULL,
+    repo_id         BLOB NOT NULL,
+    operation       TEXT NOT NULL CHECK (operation IN ('merge', 'rebase')),
+    status          TEXT NOT NULL DEFAULT 'in_progress'
+                        CHECK (status IN ('in_progress', 'interrupted')),
+    base_head       TEXT NOT NULL,
+    target_branch   TEXT NOT NULL,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE,
+    FOREIGN KEY (repo_id) REFERENCES repos(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_merge_operations_workspace_id ON merge_operations(workspace_id);
+CREATE INDEX idx_merge_operations_status ON merge_operations(status);
@@ -0,0 +1,28 @@
+-- Full-text index over prompts and final assistant messages for audit search.
+-- External-content table kept in sync by triggers so indexing happens as
+-- turns are written rather than at query time.
+CREATE VIRTUAL TABLE coding_agent_turns_fts USING fts5(
+    prompt,
+    summary,
+    content = 'coding_agent_turns',
+    content_rowid = 'rowid'
+);
+
+INSERT INTO coding_agent_turns_fts(coding_agent_turns_fts) VALUES ('rebuild');
+
+CREATE TRIGGER coding_agent_turns_fts_insert AFTER INSERT ON coding_agent_turns BEGIN
+    INSERT INTO coding_agent_turns_fts(rowid, prompt, summary)
+    VALUES (new.rowid, new.prompt, new.summary);
+END;
+
+CREATE TRIGGER coding_agent_turns_fts_delete AFTER DELETE ON coding_agent_turns BEGIN
+    INSERT INTO coding_agent_turns_fts(coding_agent_turns_fts, rowid, prompt, summary)
+    VALUES ('delete', old.rowid, old.prompt, old.summary);
+END;
+
+CREATE TRIGGER coding_agent_turns_fts_update AFTER UPDATE OF prompt, summary ON coding_agent_turns BEGIN
+    INSERT INTO coding_agent_turns_fts(coding_agent_turns_fts, rowid, prompt, summary)
+    VALUES ('delete', old.rowid, old.prompt, old.summary);
+    INSERT INTO coding_agent_turns_fts(rowid, prompt, summary)
+    VALUES (new.rowid, new.prompt, new.summary);
+END;
@@ -0,0 +1,2 @@
+-- Optional cap on coding agent runs that may execute against a repo at once.
+ALTER TABLE repos ADD COLUMN max_concurrent_attempts INTEGER;
@@ -0,0 +1,3 @@
+-- Peak memory and CPU time sampled from an execution's process group.
+ALTER TABLE execution_processes ADD COLUMN peak_rss_bytes INTEGER;
+ALTER TABLE execution_processes ADD COLUMN cpu_time_ms INTEGER;
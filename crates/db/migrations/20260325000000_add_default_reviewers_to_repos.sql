@@ -0,0 +1,3 @@
+-- Add default_reviewers column to repos table
+-- JSON array of reviewer handles requested on every PR created for the repo
+ALTER TABLE repos ADD COLUMN default_reviewers TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,3 @@
+-- Add default_pr_labels column to repos table
+-- JSON array of labels applied to every PR created for the repo
+ALTER TABLE repos ADD COLUMN default_pr_labels TEXT NOT NULL DEFAULT '[]';
@@ -0,0 +1,4 @@
+-- Optional cap on live worktrees kept for a repo, and whether reaching it
+-- prunes the oldest idle worktrees (TRUE) or rejects the new workspace.
+ALTER TABLE repos ADD COLUMN max_worktrees INTEGER;
+ALTER TABLE repos ADD COLUMN prune_worktrees_at_limit BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,3 @@
+-- Template for a note added to the next follow-up after the user denies a
+-- tool call. NULL leaves follow-ups unchanged.
+ALTER TABLE repos ADD COLUMN denied_tool_note_template TEXT;
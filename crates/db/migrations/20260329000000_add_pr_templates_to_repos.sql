@@ -0,0 +1,4 @@
+-- Templates for the title and body of pull requests created for the repo.
+-- NULL uses the built-in defaults.
+ALTER TABLE repos ADD COLUMN pr_title_template TEXT;
+ALTER TABLE repos ADD COLUMN pr_body_template TEXT;
@@ -0,0 +1,2 @@
+-- Optional cap on coding agent runs a single workspace may start against a repo.
+ALTER TABLE repos ADD COLUMN max_attempts_per_workspace INTEGER;
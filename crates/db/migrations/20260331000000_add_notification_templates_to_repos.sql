@@ -0,0 +1,4 @@
+-- Templates for the completion notification sent for workspaces of the repo.
+-- NULL uses the built-in defaults.
+ALTER TABLE repos ADD COLUMN notification_title_template TEXT;
+ALTER TABLE repos ADD COLUMN notification_body_template TEXT;
@@ -0,0 +1,4 @@
+-- How workspaces of the repo are merged into their target branch when the
+-- merge request doesn't say. One of 'merge', 'squash' or 'rebase'; NULL
+-- means squash.
+ALTER TABLE repos ADD COLUMN default_merge_method TEXT;
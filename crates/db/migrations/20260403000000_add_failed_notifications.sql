@@ -0,0 +1,16 @@
+-- Notifications that could not be delivered after retries, kept so they can
+-- be inspected and sent again instead of being lost.
+CREATE TABLE failed_notifications (
+    id              BLOB PRIMARY KEY,
+    workspace_id    BLOB,
+    channel         TEXT NOT NULL CHECK (channel IN ('push')),
+    title           TEXT NOT NULL,
+    message         TEXT NOT NULL,
+    error           TEXT NOT NULL,
+    attempts        INTEGER NOT NULL DEFAULT 1,
+    created_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    updated_at      TEXT NOT NULL DEFAULT (datetime('now', 'subsec')),
+    FOREIGN KEY (workspace_id) REFERENCES workspaces(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_failed_notifications_created_at ON failed_notifications(created_at);
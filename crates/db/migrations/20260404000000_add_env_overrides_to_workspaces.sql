@@ -0,0 +1,3 @@
+-- Environment variables set for a single workspace when it was created. They
+-- take precedence over profile and config env for every run in the workspace.
+ALTER TABLE workspaces ADD COLUMN env_overrides TEXT NOT NULL DEFAULT '{}';
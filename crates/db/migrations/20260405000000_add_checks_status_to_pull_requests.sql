@@ -0,0 +1,3 @@
+-- Combined CI result for the PR's head commit: 'pending', 'success' or
+-- 'failure'. NULL when the host doesn't report checks or none have run.
+ALTER TABLE pull_requests ADD COLUMN checks_status TEXT;
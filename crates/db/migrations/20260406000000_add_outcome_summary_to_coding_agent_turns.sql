@@ -0,0 +1,3 @@
+-- JSON summary of the finished run: files edited, test runs with pass/fail
+-- counts and the final status. NULL until the run completes.
+ALTER TABLE coding_agent_turns ADD COLUMN outcome_summary TEXT;
@@ -0,0 +1,3 @@
+-- Why a process ended when the status alone doesn't say, e.g. 'timeout' for
+-- an agent stopped by the inactivity watchdog. NULL otherwise.
+ALTER TABLE execution_processes ADD COLUMN exit_reason TEXT;
@@ -0,0 +1,5 @@
+-- Remotes for fork workflows: branches are pushed to push_remote and pull
+-- requests are opened against pr_base_remote. NULL keeps the remote git
+-- picks for the branch.
+ALTER TABLE repos ADD COLUMN push_remote TEXT;
+ALTER TABLE repos ADD COLUMN pr_base_remote TEXT;
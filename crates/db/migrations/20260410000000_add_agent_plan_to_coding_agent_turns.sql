@@ -0,0 +1,3 @@
+-- JSON of the last plan the agent presented in the run, with its final
+-- status. NULL if the run presented no plan or hasn't finished.
+ALTER TABLE coding_agent_turns ADD COLUMN agent_plan TEXT;
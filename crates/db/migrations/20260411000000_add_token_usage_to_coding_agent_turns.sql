@@ -0,0 +1,3 @@
+-- JSON token usage totals the agent reported for the run. NULL if it
+-- reported none or hasn't finished.
+ALTER TABLE coding_agent_turns ADD COLUMN token_usage TEXT;
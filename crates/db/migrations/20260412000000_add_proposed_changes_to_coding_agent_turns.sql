@@ -0,0 +1,3 @@
+-- JSON of the file changes a preview run proposed, collected before its
+-- worktree is reset. NULL for other runs and while the run is in progress.
+ALTER TABLE coding_agent_turns ADD COLUMN proposed_changes TEXT;
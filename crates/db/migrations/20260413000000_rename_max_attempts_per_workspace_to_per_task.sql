@@ -0,0 +1,3 @@
+-- The attempt cap counts the workspaces started for one task, not the coding
+-- agent runs inside a single workspace.
+ALTER TABLE repos RENAME COLUMN max_attempts_per_workspace TO max_attempts_per_task;
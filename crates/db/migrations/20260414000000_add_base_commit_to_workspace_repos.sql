@@ -0,0 +1,3 @@
+-- Merge-base with the target branch, recorded when a workspace adopts an
+-- existing branch instead of creating one.
+ALTER TABLE workspace_repos ADD COLUMN base_commit TEXT;
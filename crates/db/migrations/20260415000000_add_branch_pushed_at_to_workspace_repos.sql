@@ -0,0 +1,3 @@
+-- When the workspace branch was last pushed for a pull request. Remotes with
+-- no pull request API only ever get this record.
+ALTER TABLE workspace_repos ADD COLUMN branch_pushed_at DATETIME;
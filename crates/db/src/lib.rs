@@ -1,13 +1,26 @@
 use std::{str::FromStr, sync::Arc};
 
 use sqlx::{
-    ConnectOptions, Error, Pool, Sqlite, SqlitePool,
+    ConnectOptions, Error, Pool, Sqlite,
     migrate::MigrateError,
-    sqlite::{SqliteConnectOptions, SqliteConnection, SqliteJournalMode, SqlitePoolOptions},
+    sqlite::{SqliteConnectOptions, SqliteConnection, SqlitePoolOptions},
 };
 use utils::assets::asset_dir;
 
+pub mod maintenance;
 pub mod models;
+pub mod settings;
+
+use settings::SqliteSettings;
+
+/// Options for the app database with the configured pragmas applied.
+fn connect_options(settings: &SqliteSettings) -> Result<SqliteConnectOptions, Error> {
+    let database_url = format!(
+        "sqlite://{}",
+        asset_dir().join("db.v2.sqlite").to_string_lossy()
+    );
+    Ok(settings.apply(SqliteConnectOptions::from_str(&database_url)?))
+}
 
 async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     use std::collections::HashSet;
@@ -67,6 +80,24 @@ async fn run_migrations(pool: &Pool<Sqlite>) -> Result<(), Error> {
     }
 }
 
+/// A migrated in-memory database for tests. One connection, since each
+/// in-memory connection is its own database, and foreign keys are off so rows
+/// can be inserted without their parents.
+#[cfg(test)]
+pub(crate) async fn test_pool() -> Pool<Sqlite> {
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+    sqlx::query("PRAGMA foreign_keys = OFF")
+        .execute(&pool)
+        .await
+        .unwrap();
+    pool
+}
+
 #[derive(Clone)]
 pub struct DBService {
     pub pool: Pool<Sqlite>,
@@ -74,26 +105,19 @@ pub struct DBService {
 
 impl DBService {
     pub async fn new() -> Result<DBService, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.v2.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
-        let pool = SqlitePool::connect_with(options).await?;
+        let settings = SqliteSettings::from_env();
+        let options = connect_options(&settings)?.create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(settings.max_connections)
+            .connect_with(options)
+            .await?;
         run_migrations(&pool).await?;
         Ok(DBService { pool })
     }
 
     pub async fn new_migration_pool() -> Result<Pool<Sqlite>, Error> {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.v2.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
+        let options = connect_options(&SqliteSettings::from_env())?
             .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete)
             .disable_statement_logging();
         SqlitePoolOptions::new()
             .max_connections(64)
@@ -125,16 +149,12 @@ impl DBService {
             + Sync
             + 'static,
     {
-        let database_url = format!(
-            "sqlite://{}",
-            asset_dir().join("db.v2.sqlite").to_string_lossy()
-        );
-        let options = SqliteConnectOptions::from_str(&database_url)?
-            .create_if_missing(true)
-            .journal_mode(SqliteJournalMode::Delete);
+        let settings = SqliteSettings::from_env();
+        let options = connect_options(&settings)?.create_if_missing(true);
+        let pool_options = SqlitePoolOptions::new().max_connections(settings.max_connections);
 
         let pool = if let Some(hook) = after_connect {
-            SqlitePoolOptions::new()
+            pool_options
                 .after_connect(move |conn, _meta| {
                     let hook = hook.clone();
                     Box::pin(async move {
@@ -145,7 +165,7 @@ impl DBService {
                 .connect_with(options)
                 .await?
         } else {
-            SqlitePool::connect_with(options).await?
+            pool_options.connect_with(options).await?
         };
 
         run_migrations(&pool).await?;
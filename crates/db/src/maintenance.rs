@@ -0,0 +1,352 @@
+//! Offline checks for the local SQLite database, used by `vibe-kanban db`.
+//!
+//! [`check`] only reads; [`repair`] applies pending migrations and compacts the
+//! file. Neither can fix a corrupt page; for that the report points users to a
+//! backup. [`applied_migrations`] is also served by the running server for
+//! diagnosing schema state.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Error, Row, SqlitePool};
+use ts_rs::TS;
+
+use crate::settings::SqliteSettings;
+
+/// Outcome of inspecting the database. Empty lists mean nothing was found.
+#[derive(Debug, Default)]
+pub struct DbCheckReport {
+    /// Lines returned by `PRAGMA integrity_check` other than `ok`.
+    pub integrity_errors: Vec<String>,
+    /// Migrations bundled with this build that have not been applied.
+    pub pending_migrations: Vec<(i64, String)>,
+    /// Migrations recorded as started but not completed.
+    pub failed_migrations: Vec<i64>,
+    /// Applied migrations whose stored checksum differs from this build.
+    pub checksum_mismatches: Vec<i64>,
+    /// Applied migrations this build doesn't know about, e.g. from a newer release.
+    pub unknown_migrations: Vec<i64>,
+}
+
+impl DbCheckReport {
+    pub fn is_healthy(&self) -> bool {
+        self.integrity_errors.is_empty()
+            && self.pending_migrations.is_empty()
+            && self.failed_migrations.is_empty()
+            && self.checksum_mismatches.is_empty()
+            && self.unknown_migrations.is_empty()
+    }
+}
+
+/// A row of `_sqlx_migrations`, compared against the migrations in this build.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub description: String,
+    #[ts(type = "Date")]
+    pub installed_on: DateTime<Utc>,
+    /// False if the migration started but never completed.
+    pub success: bool,
+    /// Hex-encoded checksum recorded when the migration ran.
+    pub checksum: String,
+    /// Whether this build bundles a migration with the same version.
+    pub known: bool,
+    /// Whether the recorded checksum matches this build's migration file.
+    pub checksum_matches: bool,
+}
+
+/// Open the database without creating it or running migrations.
+pub async fn connect() -> Result<SqlitePool, Error> {
+    let options = crate::connect_options(&SqliteSettings::from_env())?.create_if_missing(false);
+    SqlitePool::connect_with(options).await
+}
+
+pub async fn check(pool: &SqlitePool) -> Result<DbCheckReport, Error> {
+    let mut report = DbCheckReport {
+        integrity_errors: sqlx::query_scalar::<_, String>("PRAGMA integrity_check")
+            .fetch_all(pool)
+            .await?
+            .into_iter()
+            .filter(|line| line != "ok")
+            .collect(),
+        ..Default::default()
+    };
+
+    let has_migrations_table: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = '_sqlx_migrations')",
+    )
+    .fetch_one(pool)
+    .await?;
+    let mut applied: HashMap<i64, (bool, Vec<u8>)> = HashMap::new();
+    if has_migrations_table {
+        for row in sqlx::query("SELECT version, success, checksum FROM _sqlx_migrations")
+            .fetch_all(pool)
+            .await?
+        {
+            applied.insert(
+                row.try_get("version")?,
+                (row.try_get("success")?, row.try_get("checksum")?),
+            );
+        }
+    }
+
+    let migrator = sqlx::migrate!("./migrations");
+    for migration in migrator.iter() {
+        match applied.remove(&migration.version) {
+            None => report
+                .pending_migrations
+                .push((migration.version, migration.description.to_string())),
+            Some((false, _)) => report.failed_migrations.push(migration.version),
+            Some((true, checksum)) if checksum != *migration.checksum => {
+                report.checksum_mismatches.push(migration.version)
+            }
+            Some(_) => {}
+        }
+    }
+    report.unknown_migrations = applied.into_keys().collect();
+    report.unknown_migrations.sort_unstable();
+
+    Ok(report)
+}
+
+/// List the migrations recorded in the database, oldest first.
+pub async fn applied_migrations(pool: &SqlitePool) -> Result<Vec<AppliedMigration>, Error> {
+    let migrator = sqlx::migrate!("./migrations");
+    let bundled: HashMap<i64, &[u8]> = migrator
+        .iter()
+        .map(|migration| (migration.version, &*migration.checksum))
+        .collect();
+
+    let rows = sqlx::query(
+        "SELECT version, description, installed_on, success, checksum
+         FROM _sqlx_migrations
+         ORDER BY version",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    rows.into_iter()
+        .map(|row| {
+            let version: i64 = row.try_get("version")?;
+            let checksum: Vec<u8> = row.try_get("checksum")?;
+            let bundled_checksum = bundled.get(&version);
+            Ok(AppliedMigration {
+                version,
+                description: row.try_get("description")?,
+                installed_on: row.try_get("installed_on")?,
+                success: row.try_get("success")?,
+                checksum: checksum.iter().map(|byte| format!("{byte:02x}")).collect(),
+                known: bundled_checksum.is_some(),
+                checksum_matches: bundled_checksum.is_some_and(|bundled| **bundled == *checksum),
+            })
+        })
+        .collect()
+}
+
+/// Apply pending migrations and `VACUUM`. Refuses to touch a database that
+/// fails the integrity check, since writing to it can make things worse.
+pub async fn repair(pool: &SqlitePool, report: &DbCheckReport) -> Result<(), Error> {
+    if !report.integrity_errors.is_empty() {
+        return Err(Error::Protocol(
+            "database failed integrity check; restore it from a backup".to_string(),
+        ));
+    }
+    crate::run_migrations(pool).await?;
+    vacuum(pool).await
+}
+
+/// `VACUUM` the database. This can renumber the implicit rowids that the
+/// `coding_agent_turns_fts` search index is keyed on, so the index is rebuilt
+/// afterwards.
+pub async fn vacuum(pool: &SqlitePool) -> Result<(), Error> {
+    sqlx::query("VACUUM").execute(pool).await?;
+    // Absent on databases that predate the index.
+    let has_fts: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'coding_agent_turns_fts')",
+    )
+    .fetch_one(pool)
+    .await?;
+    if has_fts {
+        sqlx::query(
+            "INSERT INTO coding_agent_turns_fts(coding_agent_turns_fts) VALUES ('rebuild')",
+        )
+        .execute(pool)
+        .await?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use sqlx::sqlite::SqlitePoolOptions;
+
+    use super::*;
+
+    /// An empty in-memory database. One connection, since each in-memory
+    /// connection is its own database.
+    async fn empty_pool() -> SqlitePool {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap()
+    }
+
+    /// A migrated in-memory database.
+    async fn test_pool() -> SqlitePool {
+        let pool = empty_pool().await;
+        crate::run_migrations(&pool).await.unwrap();
+        sqlx::query("PRAGMA foreign_keys = OFF")
+            .execute(&pool)
+            .await
+            .unwrap();
+        pool
+    }
+
+    async fn search_prompts(pool: &SqlitePool, term: &str) -> Vec<String> {
+        sqlx::query_scalar(
+            "SELECT t.prompt FROM coding_agent_turns_fts f
+             JOIN coding_agent_turns t ON t.rowid = f.rowid
+             WHERE coding_agent_turns_fts MATCH ?",
+        )
+        .bind(term)
+        .fetch_all(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn vacuum_keeps_turn_search_in_sync() {
+        let pool = test_pool().await;
+        for prompt in ["alpha", "beta", "gamma"] {
+            sqlx::query(
+                "INSERT INTO coding_agent_turns (id, execution_process_id, prompt)
+                 VALUES (randomblob(16), randomblob(16), ?)",
+            )
+            .bind(prompt)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("DELETE FROM coding_agent_turns WHERE prompt = 'alpha'")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        // Compacting renumbers the remaining rows.
+        vacuum(&pool).await.unwrap();
+
+        assert_eq!(search_prompts(&pool, "gamma").await, ["gamma"]);
+        assert_eq!(search_prompts(&pool, "beta").await, ["beta"]);
+    }
+
+    fn bundled_versions() -> Vec<i64> {
+        sqlx::migrate!("./migrations")
+            .iter()
+            .map(|migration| migration.version)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn migrated_database_is_healthy() {
+        let pool = test_pool().await;
+
+        let report = check(&pool).await.unwrap();
+        assert!(report.is_healthy(), "{report:?}");
+
+        let applied = applied_migrations(&pool).await.unwrap();
+        assert_eq!(
+            applied.iter().map(|m| m.version).collect::<Vec<_>>(),
+            bundled_versions()
+        );
+        assert!(
+            applied
+                .iter()
+                .all(|m| m.success && m.known && m.checksum_matches)
+        );
+    }
+
+    #[tokio::test]
+    async fn check_reports_migration_problems() {
+        let pool = test_pool().await;
+        let versions = bundled_versions();
+        let (pending, failed, changed) = (versions[0], versions[1], versions[2]);
+        let unknown = 99_999_999_999_999;
+        sqlx::query("DELETE FROM _sqlx_migrations WHERE version = ?")
+            .bind(pending)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE _sqlx_migrations SET success = 0 WHERE version = ?")
+            .bind(failed)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE _sqlx_migrations SET checksum = X'00' WHERE version = ?")
+            .bind(changed)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO _sqlx_migrations
+                 (version, description, success, checksum, execution_time)
+             VALUES (?, 'from a newer release', 1, X'00', 0)",
+        )
+        .bind(unknown)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let report = check(&pool).await.unwrap();
+
+        assert!(!report.is_healthy());
+        assert!(report.integrity_errors.is_empty());
+        assert_eq!(
+            report
+                .pending_migrations
+                .iter()
+                .map(|(version, _)| *version)
+                .collect::<Vec<_>>(),
+            [pending]
+        );
+        assert_eq!(report.failed_migrations, [failed]);
+        assert_eq!(report.checksum_mismatches, [changed]);
+        assert_eq!(report.unknown_migrations, [unknown]);
+
+        let applied = applied_migrations(&pool).await.unwrap();
+        let changed_row = applied.iter().find(|m| m.version == changed).unwrap();
+        assert!(changed_row.known && !changed_row.checksum_matches);
+        assert_eq!(changed_row.checksum, "00");
+        let unknown_row = applied.iter().find(|m| m.version == unknown).unwrap();
+        assert!(!unknown_row.known);
+    }
+
+    #[tokio::test]
+    async fn repair_applies_pending_migrations() {
+        let pool = empty_pool().await;
+
+        let report = check(&pool).await.unwrap();
+        assert_eq!(report.pending_migrations.len(), bundled_versions().len());
+
+        repair(&pool, &report).await.unwrap();
+
+        let report = check(&pool).await.unwrap();
+        assert!(report.is_healthy(), "{report:?}");
+    }
+
+    #[tokio::test]
+    async fn repair_refuses_a_corrupt_database() {
+        let pool = empty_pool().await;
+        let report = DbCheckReport {
+            integrity_errors: vec!["Page 2: btreeInitPage() returns error code 11".to_string()],
+            ..check(&pool).await.unwrap()
+        };
+
+        assert!(repair(&pool, &report).await.is_err());
+
+        // Nothing was migrated.
+        let report = check(&pool).await.unwrap();
+        assert_eq!(report.pending_migrations.len(), bundled_versions().len());
+    }
+}
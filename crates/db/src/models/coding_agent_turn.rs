@@ -23,6 +23,19 @@ pub struct CreateCodingAgentTurn {
     pub prompt: Option<String>,
 }
 
+/// A coding agent turn matching an audit search, with highlighted snippets.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CodingAgentTurnSearchResult {
+    pub turn_id: Uuid,
+    pub execution_process_id: Uuid,
+    pub session_id: Uuid,
+    pub workspace_id: Uuid,
+    pub workspace_name: Option<String>,
+    pub prompt_snippet: Option<String>,
+    pub summary_snippet: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
 /// Session info from a coding agent turn, used for follow-up requests
 #[derive(Debug)]
 pub struct CodingAgentResumeInfo {
@@ -189,6 +202,146 @@ impl CodingAgentTurn {
         Ok(())
     }
 
+    /// Store the JSON outcome summary of a finished run.
+    pub async fn update_outcome_summary(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        outcome_summary: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET outcome_summary = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            outcome_summary,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The JSON outcome summary of a run, if it has finished.
+    pub async fn find_outcome_summary(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT outcome_summary FROM coding_agent_turns WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.and_then(|row| row.outcome_summary))
+    }
+
+    /// Store the JSON of the last plan a finished run presented.
+    pub async fn update_agent_plan(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        agent_plan: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET agent_plan = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            agent_plan,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The JSON of the last plan a finished run presented, if any.
+    pub async fn find_agent_plan(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT agent_plan FROM coding_agent_turns WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.and_then(|row| row.agent_plan))
+    }
+
+    /// Store the JSON token usage totals of a finished run.
+    pub async fn update_token_usage(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        token_usage: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET token_usage = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            token_usage,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The JSON token usage totals of a finished run, if it reported any.
+    pub async fn find_token_usage(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT token_usage FROM coding_agent_turns WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.and_then(|row| row.token_usage))
+    }
+
+    /// Store the JSON of the file changes a finished preview run proposed.
+    pub async fn update_proposed_changes(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+        proposed_changes: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            r#"UPDATE coding_agent_turns
+               SET proposed_changes = $1, updated_at = $2
+               WHERE execution_process_id = $3"#,
+            proposed_changes,
+            now,
+            execution_process_id
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// The JSON of the file changes a finished preview run proposed.
+    pub async fn find_proposed_changes(
+        pool: &SqlitePool,
+        execution_process_id: Uuid,
+    ) -> Result<Option<String>, sqlx::Error> {
+        let row = sqlx::query!(
+            r#"SELECT proposed_changes FROM coding_agent_turns WHERE execution_process_id = $1"#,
+            execution_process_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.and_then(|row| row.proposed_changes))
+    }
+
     /// Mark a coding agent turn as unseen by execution process ID.
     pub async fn mark_unseen_by_execution_process_id(
         pool: &SqlitePool,
@@ -232,6 +385,48 @@ impl CodingAgentTurn {
         Ok(())
     }
 
+    /// Full-text search over prompts and final assistant messages, best
+    /// matches first. Optionally restricted to workspaces using `repo_id`.
+    pub async fn search(
+        pool: &SqlitePool,
+        query: &str,
+        repo_id: Option<Uuid>,
+        limit: i64,
+    ) -> Result<Vec<CodingAgentTurnSearchResult>, sqlx::Error> {
+        let Some(match_query) = fts5_literal_query(query) else {
+            return Ok(Vec::new());
+        };
+
+        sqlx::query_as!(
+            CodingAgentTurnSearchResult,
+            r#"SELECT cat.id AS "turn_id!: Uuid",
+                      ep.id AS "execution_process_id!: Uuid",
+                      s.id AS "session_id!: Uuid",
+                      w.id AS "workspace_id!: Uuid",
+                      w.name AS "workspace_name",
+                      snippet(coding_agent_turns_fts, 0, '**', '**', '…', 16) AS "prompt_snippet?: String",
+                      snippet(coding_agent_turns_fts, 1, '**', '**', '…', 16) AS "summary_snippet?: String",
+                      cat.created_at AS "created_at!: DateTime<Utc>"
+               FROM coding_agent_turns_fts
+               JOIN coding_agent_turns cat ON cat.rowid = coding_agent_turns_fts.rowid
+               JOIN execution_processes ep ON ep.id = cat.execution_process_id
+               JOIN sessions s ON s.id = ep.session_id
+               JOIN workspaces w ON w.id = s.workspace_id
+               WHERE coding_agent_turns_fts MATCH $1
+                 AND ($2 IS NULL OR EXISTS (
+                     SELECT 1 FROM workspace_repos wr
+                     WHERE wr.workspace_id = w.id AND wr.repo_id = $2
+                 ))
+               ORDER BY bm25(coding_agent_turns_fts)
+               LIMIT $3"#,
+            match_query,
+            repo_id,
+            limit
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     /// Check if a workspace has any unseen coding agent turns
     /// Find all workspaces that have unseen coding agent turns, filtered by archived status
     pub async fn find_workspaces_with_unseen(
@@ -253,3 +448,31 @@ impl CodingAgentTurn {
         Ok(result.into_iter().collect())
     }
 }
+
+/// Quote each whitespace-separated term so user input is matched literally
+/// rather than parsed as FTS5 query syntax.
+fn fts5_literal_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fts5_literal_query;
+
+    #[test]
+    fn quotes_terms_for_fts5() {
+        assert_eq!(
+            fts5_literal_query("fix  login-bug"),
+            Some(r#""fix" "login-bug""#.to_string())
+        );
+        assert_eq!(
+            fts5_literal_query(r#"say "hi""#),
+            Some(r#""say" """hi""""#.to_string())
+        );
+        assert_eq!(fts5_literal_query("   "), None);
+    }
+}
@@ -41,12 +41,26 @@ pub enum ExecutionProcessError {
 #[serde(rename_all = "lowercase")]
 #[ts(use_ts_enum)]
 pub enum ExecutionProcessStatus {
+    /// Waiting for a free coding agent slot.
+    Queued,
     Running,
     Completed,
     Failed,
     Killed,
 }
 
+/// Why a process ended, when the status alone doesn't say.
+#[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
+#[sqlx(type_name = "execution_process_exit_reason", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+#[ts(use_ts_enum)]
+pub enum ExecutionProcessExitReason {
+    /// Stopped by the inactivity watchdog after producing no output.
+    Timeout,
+    /// Cancelled while queued, before it started.
+    Cancelled,
+}
+
 #[derive(Debug, Clone, Type, Serialize, Deserialize, PartialEq, TS)]
 #[sqlx(type_name = "execution_process_run_reason", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
@@ -54,6 +68,7 @@ pub enum ExecutionProcessRunReason {
     SetupScript,
     CleanupScript,
     ArchiveScript,
+    PostMergeScript,
     CodingAgent,
     DevServer,
 }
@@ -67,6 +82,7 @@ pub struct ExecutionProcess {
     pub executor_action: sqlx::types::Json<ExecutorActionField>,
     pub status: ExecutionProcessStatus,
     pub exit_code: Option<i64>,
+    pub exit_reason: Option<ExecutionProcessExitReason>,
     /// dropped: true if this process is excluded from the current
     /// history view (due to restore/trimming). Hidden from logs/timeline;
     /// still listed in the Processes tab.
@@ -77,11 +93,24 @@ pub struct ExecutionProcess {
     pub updated_at: DateTime<Utc>,
 }
 
+/// Peak resource usage sampled from an execution's process group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, TS)]
+pub struct ExecutionProcessResourceUsage {
+    /// Highest resident memory seen across the whole process group, in bytes.
+    #[ts(type = "number | null")]
+    pub peak_rss_bytes: Option<i64>,
+    /// Total user + system CPU time, in milliseconds.
+    #[ts(type = "number | null")]
+    pub cpu_time_ms: Option<i64>,
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct CreateExecutionProcess {
     pub session_id: Uuid,
     pub executor_action: ExecutorAction,
     pub run_reason: ExecutionProcessRunReason,
+    /// `Queued` for runs that have to wait for a concurrency slot, otherwise `Running`.
+    pub status: ExecutionProcessStatus,
 }
 
 #[derive(Debug)]
@@ -132,6 +161,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -206,6 +236,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -233,6 +264,7 @@ impl ExecutionProcess {
                       ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                       ep.status          as "status!: ExecutionProcessStatus",
                       ep.exit_code,
+                      ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
                       ep.dropped as "dropped!: bool",
                       ep.started_at      as "started_at!: DateTime<Utc>",
                       ep.completed_at    as "completed_at?: DateTime<Utc>",
@@ -249,7 +281,7 @@ impl ExecutionProcess {
         .await
     }
 
-    /// Find running execution processes
+    /// Find running and queued execution processes
     pub async fn find_running(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
         sqlx::query_as!(
             ExecutionProcess,
@@ -260,18 +292,19 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
                     ep.created_at as "created_at!: DateTime<Utc>",
                     ep.updated_at as "updated_at!: DateTime<Utc>"
-               FROM execution_processes ep WHERE ep.status = 'running' ORDER BY ep.created_at ASC"#,
+               FROM execution_processes ep WHERE ep.status IN ('queued', 'running') ORDER BY ep.created_at ASC"#,
         )
         .fetch_all(pool)
         .await
     }
 
-    /// Check if there's a running coding agent process for a session
+    /// Check if there's a running or queued coding agent process for a session
     pub async fn has_running_coding_agent_for_session(
         pool: &SqlitePool,
         session_id: Uuid,
@@ -280,7 +313,7 @@ impl ExecutionProcess {
             r#"SELECT COUNT(*) as "count!: i64"
                FROM execution_processes ep
                WHERE ep.session_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('queued', 'running')
                  AND ep.run_reason = 'codingagent'"#,
             session_id
         )
@@ -289,7 +322,33 @@ impl ExecutionProcess {
         Ok(count > 0)
     }
 
-    /// Check if there are running processes (excluding dev servers) for a workspace (across all sessions)
+    /// Finished coding agent runs, optionally limited to one workspace and/or
+    /// to workspaces that include a repo. Oldest first.
+    pub async fn find_finished_coding_agent_ids(
+        pool: &SqlitePool,
+        workspace_id: Option<Uuid>,
+        repo_id: Option<Uuid>,
+    ) -> Result<Vec<Uuid>, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT ep.id as "id!: Uuid"
+               FROM execution_processes ep
+               JOIN sessions s ON ep.session_id = s.id
+               WHERE ep.run_reason = 'codingagent'
+                 AND ep.status NOT IN ('queued', 'running')
+                 AND ($1 IS NULL OR s.workspace_id = $1)
+                 AND ($2 IS NULL OR EXISTS (
+                     SELECT 1 FROM workspace_repos wr
+                     WHERE wr.workspace_id = s.workspace_id AND wr.repo_id = $2
+                 ))
+               ORDER BY ep.created_at ASC"#,
+            workspace_id,
+            repo_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Check if there are running or queued processes (excluding dev servers) for a workspace (across all sessions)
     pub async fn has_running_non_dev_server_processes_for_workspace(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -299,7 +358,7 @@ impl ExecutionProcess {
                FROM execution_processes ep
                JOIN sessions s ON ep.session_id = s.id
                WHERE s.workspace_id = $1
-                 AND ep.status = 'running'
+                 AND ep.status IN ('queued', 'running')
                  AND ep.run_reason != 'devserver'"#,
             workspace_id
         )
@@ -323,6 +382,7 @@ impl ExecutionProcess {
             ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
             ep.status as "status!: ExecutionProcessStatus",
             ep.exit_code,
+            ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
             ep.dropped as "dropped!: bool",
             ep.started_at as "started_at!: DateTime<Utc>",
             ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -357,6 +417,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -398,7 +459,7 @@ impl ExecutionProcess {
             data.session_id,
             data.run_reason,
             executor_action_json,
-            ExecutionProcessStatus::Running,
+            data.status,
             None::<i64>,
             now,
             None::<DateTime<Utc>>,
@@ -427,6 +488,39 @@ impl ExecutionProcess {
         false
     }
 
+    pub async fn find_resource_usage(
+        pool: &SqlitePool,
+        id: Uuid,
+    ) -> Result<Option<ExecutionProcessResourceUsage>, sqlx::Error> {
+        sqlx::query_as!(
+            ExecutionProcessResourceUsage,
+            r#"SELECT peak_rss_bytes, cpu_time_ms
+               FROM execution_processes
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    pub async fn update_resource_usage(
+        pool: &SqlitePool,
+        id: Uuid,
+        usage: ExecutionProcessResourceUsage,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET peak_rss_bytes = $1, cpu_time_ms = $2
+               WHERE id = $3"#,
+            usage.peak_rss_bytes,
+            usage.cpu_time_ms,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     /// Update execution process status and completion info
     pub async fn update_completion(
         pool: &SqlitePool,
@@ -455,6 +549,94 @@ impl ExecutionProcess {
         Ok(())
     }
 
+    /// Move a queued process to running once it has its concurrency slots.
+    /// `started_at` is reset so time spent queued isn't counted as run time.
+    /// Returns `false` if the process was cancelled while queued.
+    pub async fn mark_started(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let started_at = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'running', started_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            started_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Record when the process was actually spawned, so time spent leaves out
+    /// queueing and setup before it.
+    pub async fn mark_spawned(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        let started_at = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET started_at = $1
+               WHERE id = $2 AND status = 'running'"#,
+            started_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark a queued process as cancelled before it started. Returns `false`
+    /// if it already left the queue.
+    pub async fn mark_dequeued(pool: &SqlitePool, id: Uuid) -> Result<bool, sqlx::Error> {
+        let completed_at = Utc::now();
+        let result = sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'killed', exit_reason = 'cancelled', completed_at = $1
+               WHERE id = $2 AND status = 'queued'"#,
+            completed_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Mark a process stopped by the inactivity watchdog as failed.
+    pub async fn mark_timed_out(
+        pool: &SqlitePool,
+        id: Uuid,
+        exit_code: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        let completed_at = Utc::now();
+        sqlx::query!(
+            r#"UPDATE execution_processes
+               SET status = 'failed', exit_code = $1, exit_reason = 'timeout', completed_at = $2
+               WHERE id = $3"#,
+            exit_code,
+            completed_at,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Mark processes left running by a previous server run as failed, in a
+    /// single transaction. Processes that finished in the meantime are skipped.
+    pub async fn mark_orphans_failed(pool: &SqlitePool, ids: &[Uuid]) -> Result<(), sqlx::Error> {
+        let completed_at = Utc::now();
+        let mut tx = pool.begin().await?;
+        for id in ids {
+            sqlx::query!(
+                r#"UPDATE execution_processes
+                   SET status = 'failed', exit_code = NULL, completed_at = $1
+                   WHERE id = $2 AND status IN ('queued', 'running')"#,
+                completed_at,
+                id
+            )
+            .execute(&mut *tx)
+            .await?;
+        }
+        tx.commit().await
+    }
+
     pub fn executor_action(&self) -> Result<&ExecutorAction, anyhow::Error> {
         match &self.executor_action.0 {
             ExecutorActionField::ExecutorAction(action) => Ok(action),
@@ -569,6 +751,7 @@ impl ExecutionProcess {
                     ep.executor_action as "executor_action!: sqlx::types::Json<ExecutorActionField>",
                     ep.status as "status!: ExecutionProcessStatus",
                     ep.exit_code,
+                    ep.exit_reason as "exit_reason?: ExecutionProcessExitReason",
                     ep.dropped as "dropped!: bool",
                     ep.started_at as "started_at!: DateTime<Utc>",
                     ep.completed_at as "completed_at?: DateTime<Utc>",
@@ -679,4 +862,136 @@ impl ExecutionProcess {
 
         Ok(rows.into_iter().collect())
     }
+
+    /// Total time spent running coding agents and scripts per workspace, in
+    /// milliseconds, summed across all sessions. Running processes count up
+    /// to now; queued time, runs cancelled before they started and dev
+    /// servers (which run until stopped) are left out.
+    pub async fn time_spent_for_workspaces(
+        pool: &SqlitePool,
+        archived: bool,
+    ) -> Result<HashMap<Uuid, i64>, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                s.workspace_id as "workspace_id!: Uuid",
+                ep.status as "status!: ExecutionProcessStatus",
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.completed_at as "completed_at?: DateTime<Utc>"
+            FROM execution_processes ep
+            JOIN sessions s ON ep.session_id = s.id
+            JOIN workspaces w ON s.workspace_id = w.id
+            WHERE w.archived = $1
+              AND ep.run_reason IN ('codingagent', 'setupscript', 'cleanupscript')
+              AND (ep.exit_reason IS NULL OR ep.exit_reason != 'cancelled')
+            "#,
+            archived
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let now = Utc::now();
+        let mut totals = HashMap::new();
+        for row in rows {
+            if let Some(elapsed) = elapsed_ms(&row.status, row.started_at, row.completed_at, now) {
+                *totals.entry(row.workspace_id).or_insert(0) += elapsed;
+            }
+        }
+
+        Ok(totals)
+    }
+
+    /// [`Self::time_spent_for_workspaces`] for a single workspace.
+    pub async fn time_spent_for_workspace(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                ep.status as "status!: ExecutionProcessStatus",
+                ep.started_at as "started_at!: DateTime<Utc>",
+                ep.completed_at as "completed_at?: DateTime<Utc>"
+            FROM execution_processes ep
+            JOIN sessions s ON ep.session_id = s.id
+            WHERE s.workspace_id = $1
+              AND ep.run_reason IN ('codingagent', 'setupscript', 'cleanupscript')
+              AND (ep.exit_reason IS NULL OR ep.exit_reason != 'cancelled')
+            "#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await?;
+
+        let now = Utc::now();
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| elapsed_ms(&row.status, row.started_at, row.completed_at, now))
+            .sum())
+    }
+}
+
+/// Wall-clock milliseconds a process ran, up to `now` if it still is. `None`
+/// for processes that never started or never finished cleanly (e.g. the
+/// server restarted mid-run).
+fn elapsed_ms(
+    status: &ExecutionProcessStatus,
+    started_at: DateTime<Utc>,
+    completed_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<i64> {
+    let ended_at = match (completed_at, status) {
+        (_, ExecutionProcessStatus::Queued) => return None,
+        (Some(completed_at), _) => completed_at,
+        (None, ExecutionProcessStatus::Running) => now,
+        (None, _) => return None,
+    };
+    Some((ended_at - started_at).num_milliseconds().max(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_pool;
+
+    async fn insert_process(pool: &SqlitePool, status: &str) -> Uuid {
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO execution_processes (id, session_id, status) VALUES (?, ?, ?)")
+            .bind(id)
+            .bind(Uuid::new_v4())
+            .bind(status)
+            .execute(pool)
+            .await
+            .unwrap();
+        id
+    }
+
+    async fn status_of(pool: &SqlitePool, id: Uuid) -> (String, Option<String>) {
+        sqlx::query_as("SELECT status, completed_at FROM execution_processes WHERE id = ?")
+            .bind(id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn mark_orphans_failed_only_touches_running_processes() {
+        let pool = test_pool().await;
+        let first = insert_process(&pool, "running").await;
+        let second = insert_process(&pool, "running").await;
+        let finished = insert_process(&pool, "completed").await;
+        let untouched = insert_process(&pool, "running").await;
+
+        ExecutionProcess::mark_orphans_failed(&pool, &[first, second, finished])
+            .await
+            .unwrap();
+
+        for id in [first, second] {
+            let (status, completed_at) = status_of(&pool, id).await;
+            assert_eq!(status, "failed");
+            assert!(completed_at.is_some());
+        }
+        assert_eq!(status_of(&pool, finished).await, ("completed".into(), None));
+        assert_eq!(status_of(&pool, untouched).await, ("running".into(), None));
+    }
 }
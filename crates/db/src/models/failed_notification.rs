@@ -0,0 +1,130 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "notification_channel", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum NotificationChannel {
+    Push,
+}
+
+/// A notification that could not be delivered after retries. Kept until it is
+/// re-driven successfully or deleted.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct FailedNotification {
+    pub id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub channel: NotificationChannel,
+    pub title: String,
+    pub message: String,
+    /// Error from the most recent delivery attempt.
+    pub error: String,
+    /// Number of failed deliveries, counting re-drives.
+    #[ts(type = "number")]
+    pub attempts: i64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl FailedNotification {
+    pub async fn create(
+        pool: &SqlitePool,
+        workspace_id: Option<Uuid>,
+        channel: NotificationChannel,
+        title: &str,
+        message: &str,
+        error: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            FailedNotification,
+            r#"INSERT INTO failed_notifications (id, workspace_id, channel, title, message, error)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id: Uuid",
+                         channel AS "channel!: NotificationChannel",
+                         title,
+                         message,
+                         error,
+                         attempts,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            channel,
+            title,
+            message,
+            error
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FailedNotification,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id: Uuid",
+                      channel AS "channel!: NotificationChannel",
+                      title,
+                      message,
+                      error,
+                      attempts,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM failed_notifications
+               WHERE id = $1"#,
+            id
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
+    /// Newest first.
+    pub async fn list(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            FailedNotification,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id: Uuid",
+                      channel AS "channel!: NotificationChannel",
+                      title,
+                      message,
+                      error,
+                      attempts,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM failed_notifications
+               ORDER BY created_at DESC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Record another failed delivery.
+    pub async fn record_failure(
+        pool: &SqlitePool,
+        id: Uuid,
+        error: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE failed_notifications
+               SET error = $1, attempts = attempts + 1, updated_at = datetime('now', 'subsec')
+               WHERE id = $2"#,
+            error,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM failed_notifications WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -16,6 +16,16 @@ pub enum MergeStatus {
     Unknown,
 }
 
+/// Combined CI result for a pull request's head commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS, Type)]
+#[sqlx(type_name = "checks_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum ChecksStatus {
+    Pending,
+    Success,
+    Failure,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum Merge {
@@ -51,6 +61,8 @@ pub struct PullRequestInfo {
     pub status: MergeStatus,
     pub merged_at: Option<chrono::DateTime<chrono::Utc>>,
     pub merge_commit_sha: Option<String>,
+    /// `None` when the host doesn't report CI status or no checks ran.
+    pub checks_status: Option<ChecksStatus>,
 }
 
 /// Row type for direct merges only (PR data now lives in pull_requests).
@@ -105,6 +117,28 @@ impl Merge {
         })
     }
 
+    pub async fn find_direct_by_rowid(
+        pool: &SqlitePool,
+        rowid: i64,
+    ) -> Result<Option<DirectMerge>, sqlx::Error> {
+        let row = sqlx::query_as!(
+            DirectMergeRow,
+            r#"SELECT
+                id AS "id!: Uuid",
+                workspace_id AS "workspace_id!: Uuid",
+                repo_id AS "repo_id!: Uuid",
+                merge_commit,
+                target_branch_name,
+                created_at AS "created_at!: DateTime<Utc>"
+            FROM merges
+            WHERE rowid = ? AND merge_type = 'direct'"#,
+            rowid,
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(row.map(DirectMerge::from))
+    }
+
     /// Find all merges for a workspace (returns both direct merges and PRs).
     /// Direct merges come from the `merges` table, PRs from `pull_requests`.
     pub async fn find_by_workspace_id(
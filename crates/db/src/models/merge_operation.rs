@@ -0,0 +1,242 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, SqlitePool, Type};
+use ts_rs::TS;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "merge_operation_kind", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum MergeOperationKind {
+    Merge,
+    Rebase,
+}
+
+#[derive(Debug, Clone, Copy, Type, Serialize, Deserialize, PartialEq, Eq, TS)]
+#[sqlx(type_name = "merge_operation_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum MergeOperationStatus {
+    InProgress,
+    /// The server stopped before the operation finished and the repository
+    /// could not be restored automatically.
+    Interrupted,
+}
+
+/// A git merge or rebase that is running (or was cut short) for a workspace repo.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize, TS)]
+pub struct MergeOperation {
+    pub id: Uuid,
+    pub workspace_id: Uuid,
+    pub repo_id: Uuid,
+    pub operation: MergeOperationKind,
+    pub status: MergeOperationStatus,
+    /// HEAD of the checkout being modified before the operation started.
+    pub base_head: String,
+    pub target_branch: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MergeOperation {
+    /// Record that an operation is about to modify the repository.
+    pub async fn start(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        operation: MergeOperationKind,
+        base_head: &str,
+        target_branch: &str,
+    ) -> Result<Self, sqlx::Error> {
+        let id = Uuid::new_v4();
+        sqlx::query_as!(
+            MergeOperation,
+            r#"INSERT INTO merge_operations (id, workspace_id, repo_id, operation, base_head, target_branch)
+               VALUES ($1, $2, $3, $4, $5, $6)
+               RETURNING id AS "id!: Uuid",
+                         workspace_id AS "workspace_id!: Uuid",
+                         repo_id AS "repo_id!: Uuid",
+                         operation AS "operation!: MergeOperationKind",
+                         status AS "status!: MergeOperationStatus",
+                         base_head,
+                         target_branch,
+                         created_at AS "created_at!: DateTime<Utc>",
+                         updated_at AS "updated_at!: DateTime<Utc>""#,
+            id,
+            workspace_id,
+            repo_id,
+            operation,
+            base_head,
+            target_branch
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Remove the record once the operation has returned, successfully or not.
+    pub async fn finish(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!("DELETE FROM merge_operations WHERE id = $1", id)
+            .execute(pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn find_in_progress(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeOperation,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      operation AS "operation!: MergeOperationKind",
+                      status AS "status!: MergeOperationStatus",
+                      base_head,
+                      target_branch,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM merge_operations
+               WHERE status = 'in_progress'
+               ORDER BY created_at ASC"#
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    /// Interrupted operations for a workspace that still need user attention.
+    pub async fn find_interrupted_by_workspace_id(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<Vec<Self>, sqlx::Error> {
+        sqlx::query_as!(
+            MergeOperation,
+            r#"SELECT id AS "id!: Uuid",
+                      workspace_id AS "workspace_id!: Uuid",
+                      repo_id AS "repo_id!: Uuid",
+                      operation AS "operation!: MergeOperationKind",
+                      status AS "status!: MergeOperationStatus",
+                      base_head,
+                      target_branch,
+                      created_at AS "created_at!: DateTime<Utc>",
+                      updated_at AS "updated_at!: DateTime<Utc>"
+               FROM merge_operations
+               WHERE workspace_id = $1 AND status = 'interrupted'
+               ORDER BY created_at ASC"#,
+            workspace_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
+    pub async fn mark_interrupted(pool: &SqlitePool, id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"UPDATE merge_operations
+               SET status = 'interrupted', updated_at = datetime('now', 'subsec')
+               WHERE id = $1"#,
+            id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Clear interrupted records for a workspace repo once the user has resolved them.
+    pub async fn clear_interrupted(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM merge_operations
+               WHERE workspace_id = $1 AND repo_id = $2 AND status = 'interrupted'"#,
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_pool;
+
+    async fn start_rebase(pool: &SqlitePool, workspace_id: Uuid, repo_id: Uuid) -> MergeOperation {
+        MergeOperation::start(
+            pool,
+            workspace_id,
+            repo_id,
+            MergeOperationKind::Rebase,
+            "abc123",
+            "main",
+        )
+        .await
+        .unwrap()
+    }
+
+    fn ids(operations: &[MergeOperation]) -> Vec<Uuid> {
+        operations.iter().map(|op| op.id).collect()
+    }
+
+    #[tokio::test]
+    async fn finished_operations_are_not_recovered() {
+        let pool = test_pool().await;
+        let operation = start_rebase(&pool, Uuid::new_v4(), Uuid::new_v4()).await;
+        assert_eq!(operation.status, MergeOperationStatus::InProgress);
+        assert_eq!(
+            ids(&MergeOperation::find_in_progress(&pool).await.unwrap()),
+            [operation.id]
+        );
+
+        MergeOperation::finish(&pool, operation.id).await.unwrap();
+        assert!(
+            MergeOperation::find_in_progress(&pool)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[tokio::test]
+    async fn interrupted_operations_stay_until_cleared_for_their_repo() {
+        let pool = test_pool().await;
+        let workspace_id = Uuid::new_v4();
+        let (repo_a, repo_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let first = start_rebase(&pool, workspace_id, repo_a).await;
+        let second = start_rebase(&pool, workspace_id, repo_b).await;
+
+        MergeOperation::mark_interrupted(&pool, first.id)
+            .await
+            .unwrap();
+        assert_eq!(
+            ids(&MergeOperation::find_in_progress(&pool).await.unwrap()),
+            [second.id]
+        );
+        let interrupted = MergeOperation::find_interrupted_by_workspace_id(&pool, workspace_id)
+            .await
+            .unwrap();
+        assert_eq!(ids(&interrupted), [first.id]);
+        assert_eq!(interrupted[0].status, MergeOperationStatus::Interrupted);
+
+        // Clearing another repo leaves the interruption in place
+        MergeOperation::clear_interrupted(&pool, workspace_id, repo_b)
+            .await
+            .unwrap();
+        assert_eq!(
+            MergeOperation::find_interrupted_by_workspace_id(&pool, workspace_id)
+                .await
+                .unwrap()
+                .len(),
+            1
+        );
+
+        MergeOperation::clear_interrupted(&pool, workspace_id, repo_a)
+            .await
+            .unwrap();
+        assert!(
+            MergeOperation::find_interrupted_by_workspace_id(&pool, workspace_id)
+                .await
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
@@ -2,8 +2,10 @@ pub mod coding_agent_turn;
 pub mod execution_process;
 pub mod execution_process_logs;
 pub mod execution_process_repo_state;
+pub mod failed_notification;
 pub mod file;
 pub mod merge;
+pub mod merge_operation;
 pub mod project;
 pub mod pull_request;
 pub mod repo;
@@ -4,7 +4,7 @@ use chrono::{DateTime, Utc};
 use sqlx::{FromRow, SqlitePool};
 use uuid::Uuid;
 
-use super::merge::{Merge, MergeStatus, PrMerge, PullRequestInfo};
+use super::merge::{ChecksStatus, Merge, MergeStatus, PrMerge, PullRequestInfo};
 
 #[derive(Debug, Clone, FromRow)]
 pub struct PullRequest {
@@ -17,6 +17,7 @@ pub struct PullRequest {
     pub target_branch_name: String,
     pub merged_at: Option<DateTime<Utc>>,
     pub merge_commit_sha: Option<String>,
+    pub checks_status: Option<ChecksStatus>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
     pub synced_at: Option<DateTime<Utc>>,
@@ -89,6 +90,7 @@ impl PullRequest {
                 target_branch_name,
                 merged_at AS "merged_at: DateTime<Utc>",
                 merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
                 created_at AS "created_at!: DateTime<Utc>",
                 updated_at AS "updated_at!: DateTime<Utc>",
                 synced_at AS "synced_at: DateTime<Utc>"
@@ -126,6 +128,40 @@ impl PullRequest {
         Ok(())
     }
 
+    /// Record the latest CI result. Only local display uses it, so unlike
+    /// status changes this doesn't queue a remote sync.
+    pub async fn update_checks_status(
+        pool: &SqlitePool,
+        pr_url: &str,
+        checks_status: Option<ChecksStatus>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE pull_requests SET checks_status = ? WHERE pr_url = ?",
+            checks_status,
+            pr_url,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn update_target_branch(
+        pool: &SqlitePool,
+        pr_url: &str,
+        target_branch_name: &str,
+    ) -> Result<(), sqlx::Error> {
+        let now = Utc::now();
+        sqlx::query!(
+            "UPDATE pull_requests SET target_branch_name = ?, updated_at = ?, synced_at = NULL WHERE pr_url = ?",
+            target_branch_name,
+            now,
+            pr_url,
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn find_by_url(
         pool: &SqlitePool,
         pr_url: &str,
@@ -142,6 +178,7 @@ impl PullRequest {
                 target_branch_name,
                 merged_at AS "merged_at: DateTime<Utc>",
                 merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
                 created_at AS "created_at!: DateTime<Utc>",
                 updated_at AS "updated_at!: DateTime<Utc>",
                 synced_at AS "synced_at: DateTime<Utc>"
@@ -153,6 +190,34 @@ impl PullRequest {
         .await
     }
 
+    pub async fn find_by_rowid(
+        pool: &SqlitePool,
+        rowid: i64,
+    ) -> Result<Option<PullRequest>, sqlx::Error> {
+        sqlx::query_as!(
+            PullRequest,
+            r#"SELECT
+                id,
+                workspace_id AS "workspace_id: Uuid",
+                repo_id AS "repo_id: Uuid",
+                pr_url,
+                pr_number,
+                pr_status AS "pr_status: MergeStatus",
+                target_branch_name,
+                merged_at AS "merged_at: DateTime<Utc>",
+                merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
+                created_at AS "created_at!: DateTime<Utc>",
+                updated_at AS "updated_at!: DateTime<Utc>",
+                synced_at AS "synced_at: DateTime<Utc>"
+            FROM pull_requests
+            WHERE rowid = $1"#,
+            rowid,
+        )
+        .fetch_optional(pool)
+        .await
+    }
+
     pub async fn find_by_workspace_id(
         pool: &SqlitePool,
         workspace_id: Uuid,
@@ -169,6 +234,7 @@ impl PullRequest {
                 target_branch_name,
                 merged_at AS "merged_at: DateTime<Utc>",
                 merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
                 created_at AS "created_at!: DateTime<Utc>",
                 updated_at AS "updated_at!: DateTime<Utc>",
                 synced_at AS "synced_at: DateTime<Utc>"
@@ -198,6 +264,7 @@ impl PullRequest {
                 target_branch_name,
                 merged_at AS "merged_at: DateTime<Utc>",
                 merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
                 created_at AS "created_at!: DateTime<Utc>",
                 updated_at AS "updated_at!: DateTime<Utc>",
                 synced_at AS "synced_at: DateTime<Utc>"
@@ -240,6 +307,7 @@ impl PullRequest {
                 t.target_branch_name,
                 t.merged_at AS "merged_at: DateTime<Utc>",
                 t.merge_commit_sha,
+                t.checks_status AS "checks_status: ChecksStatus",
                 t.created_at AS "created_at!: DateTime<Utc>",
                 t.updated_at AS "updated_at!: DateTime<Utc>",
                 t.synced_at AS "synced_at: DateTime<Utc>"
@@ -278,6 +346,7 @@ impl PullRequest {
                 target_branch_name,
                 merged_at AS "merged_at: DateTime<Utc>",
                 merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
                 created_at AS "created_at!: DateTime<Utc>",
                 updated_at AS "updated_at!: DateTime<Utc>",
                 synced_at AS "synced_at: DateTime<Utc>"
@@ -302,6 +371,7 @@ impl PullRequest {
                 target_branch_name,
                 merged_at AS "merged_at: DateTime<Utc>",
                 merge_commit_sha,
+                checks_status AS "checks_status: ChecksStatus",
                 created_at AS "created_at!: DateTime<Utc>",
                 updated_at AS "updated_at!: DateTime<Utc>",
                 synced_at AS "synced_at: DateTime<Utc>"
@@ -337,6 +407,7 @@ impl PullRequest {
                 status: self.pr_status.clone(),
                 merged_at: self.merged_at,
                 merge_commit_sha: self.merge_commit_sha.clone(),
+                checks_status: self.checks_status,
             },
         }
     }
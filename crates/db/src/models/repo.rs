@@ -42,11 +42,62 @@ pub struct Repo {
     pub setup_script: Option<String>,
     pub cleanup_script: Option<String>,
     pub archive_script: Option<String>,
+    /// Runs in the main repo checkout after a workspace is merged, locally or
+    /// through its pull request.
+    pub post_merge_script: Option<String>,
     pub copy_files: Option<String>,
     pub parallel_setup_script: bool,
     pub dev_server_script: Option<String>,
     pub default_target_branch: Option<String>,
     pub default_working_dir: Option<String>,
+    /// Maximum number of coding agent runs allowed against this repo at once.
+    /// `None` means no per-repo limit.
+    #[ts(type = "number | null")]
+    pub max_concurrent_attempts: Option<i64>,
+    /// Maximum number of workspaces that may keep a worktree of this repo on
+    /// disk. `None` means no limit.
+    #[ts(type = "number | null")]
+    pub max_worktrees: Option<i64>,
+    /// When the worktree limit is reached, prune the least recently used idle
+    /// worktrees instead of refusing to create a new one.
+    pub prune_worktrees_at_limit: bool,
+    /// Maximum number of workspaces a single task may start against this
+    /// repo. Follow-ups inside a workspace don't count. `None` means no limit.
+    #[ts(type = "number | null")]
+    pub max_attempts_per_task: Option<i64>,
+    /// Added to the next follow-up after the user denies a tool call, once per
+    /// denial. `{tool}` and `{reason}` are filled in. `None` disables it.
+    pub denied_tool_note_template: Option<String>,
+    /// Title for pull requests created for this repo. Supports `{task_title}`,
+    /// `{branch}`, `{summary}` and `{attempt_id}`. `None` uses the default.
+    pub pr_title_template: Option<String>,
+    /// Body for pull requests created for this repo, with the same
+    /// placeholders as the title. `None` uses the default.
+    pub pr_body_template: Option<String>,
+    /// Title of the desktop notification sent when a workspace run finishes.
+    /// Supports `{task}`, `{outcome}`, `{duration}`, `{pr_url}`, `{branch}`,
+    /// `{executor}` and `{summary}`. `None` uses the default.
+    pub notification_title_template: Option<String>,
+    /// Body of that notification, with the same placeholders as the title.
+    /// `None` uses the default.
+    pub notification_body_template: Option<String>,
+    /// How workspaces are merged when the merge request doesn't choose.
+    /// `None` means squash.
+    #[ts(type = "MergeMethod | null")]
+    pub default_merge_method: Option<String>,
+    /// Reviewers requested on every pull request created for this repo.
+    #[ts(type = "Array<string>")]
+    pub default_reviewers: sqlx::types::Json<Vec<String>>,
+    /// Labels applied to every pull request created for this repo, in
+    /// addition to any given when the PR is created.
+    #[ts(type = "Array<string>")]
+    pub default_pr_labels: sqlx::types::Json<Vec<String>>,
+    /// Remote workspace branches are pushed to, e.g. a fork. `None` uses the
+    /// branch's upstream or the default remote.
+    pub push_remote: Option<String>,
+    /// Remote pull requests are opened against, e.g. `upstream`. `None` uses
+    /// the target branch's remote, falling back to the push remote.
+    pub pr_base_remote: Option<String>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -87,6 +138,14 @@ pub struct UpdateRepo {
     #[ts(optional, type = "string | null")]
     pub archive_script: Option<Option<String>>,
 
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub post_merge_script: Option<Option<String>>,
+
     #[serde(
         default,
         skip_serializing_if = "Option::is_none",
@@ -126,9 +185,119 @@ pub struct UpdateRepo {
     )]
     #[ts(optional, type = "string | null")]
     pub default_working_dir: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub max_concurrent_attempts: Option<Option<i64>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub max_worktrees: Option<Option<i64>>,
+
+    #[serde(default)]
+    #[ts(optional)]
+    pub prune_worktrees_at_limit: Option<bool>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "number | null")]
+    pub max_attempts_per_task: Option<Option<i64>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub denied_tool_note_template: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub pr_title_template: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub pr_body_template: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub notification_title_template: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub notification_body_template: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "MergeMethod | null")]
+    pub default_merge_method: Option<Option<String>>,
+
+    #[serde(default)]
+    #[ts(optional)]
+    pub default_reviewers: Option<Vec<String>>,
+
+    #[serde(default)]
+    #[ts(optional)]
+    pub default_pr_labels: Option<Vec<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub push_remote: Option<Option<String>>,
+
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "double_option"
+    )]
+    #[ts(optional, type = "string | null")]
+    pub pr_base_remote: Option<Option<String>>,
 }
 
 impl Repo {
+    /// The repo's default PR labels followed by `extra`, de-duplicated.
+    pub fn pr_labels_with(&self, extra: &[String]) -> Vec<String> {
+        normalize_list(
+            self.default_pr_labels
+                .iter()
+                .chain(extra)
+                .map(String::as_str),
+        )
+    }
+
     /// Get repos that still have the migration sentinel as their name.
     /// Used by the startup backfill to fix repo names.
     pub async fn list_needing_name_fix(pool: &SqlitePool) -> Result<Vec<Self>, sqlx::Error> {
@@ -141,11 +310,26 @@ impl Repo {
                       setup_script,
                       cleanup_script,
                       archive_script,
+                      post_merge_script,
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
                       default_working_dir,
+                      max_concurrent_attempts,
+                      max_worktrees,
+                      prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                      max_attempts_per_task,
+                      denied_tool_note_template,
+                      pr_title_template,
+                      pr_body_template,
+                      notification_title_template,
+                      notification_body_template,
+                      default_merge_method,
+                      default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                      default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                      push_remote,
+                      pr_base_remote,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -182,11 +366,26 @@ impl Repo {
                       setup_script,
                       cleanup_script,
                       archive_script,
+                      post_merge_script,
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
                       default_working_dir,
+                      max_concurrent_attempts,
+                      max_worktrees,
+                      prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                      max_attempts_per_task,
+                      denied_tool_note_template,
+                      pr_title_template,
+                      pr_body_template,
+                      notification_title_template,
+                      notification_body_template,
+                      default_merge_method,
+                      default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                      default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                      push_remote,
+                      pr_base_remote,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -240,11 +439,26 @@ impl Repo {
                          setup_script,
                          cleanup_script,
                          archive_script,
+                         post_merge_script,
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
                          default_working_dir,
+                         max_concurrent_attempts,
+                         max_worktrees,
+                         prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                         max_attempts_per_task,
+                         denied_tool_note_template,
+                         pr_title_template,
+                         pr_body_template,
+                         notification_title_template,
+                         notification_body_template,
+                         default_merge_method,
+                         default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                         default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                         push_remote,
+                         pr_base_remote,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             id,
@@ -266,11 +480,26 @@ impl Repo {
                       setup_script,
                       cleanup_script,
                       archive_script,
+                      post_merge_script,
                       copy_files,
                       parallel_setup_script as "parallel_setup_script!: bool",
                       dev_server_script,
                       default_target_branch,
                       default_working_dir,
+                      max_concurrent_attempts,
+                      max_worktrees,
+                      prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                      max_attempts_per_task,
+                      denied_tool_note_template,
+                      pr_title_template,
+                      pr_body_template,
+                      notification_title_template,
+                      notification_body_template,
+                      default_merge_method,
+                      default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                      default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                      push_remote,
+                      pr_base_remote,
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM repos
@@ -292,11 +521,26 @@ impl Repo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.post_merge_script,
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.max_concurrent_attempts,
+                      r.max_worktrees,
+                      r.prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                      r.max_attempts_per_task,
+                      r.denied_tool_note_template,
+                      r.pr_title_template,
+                      r.pr_body_template,
+                      r.notification_title_template,
+                      r.notification_body_template,
+                      r.default_merge_method,
+                      r.default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                      r.default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                      r.push_remote,
+                      r.pr_base_remote,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -369,6 +613,10 @@ impl Repo {
             None => existing.archive_script,
             Some(v) => v.clone(),
         };
+        let post_merge_script = match &payload.post_merge_script {
+            None => existing.post_merge_script,
+            Some(v) => v.clone(),
+        };
         let copy_files = match &payload.copy_files {
             None => existing.copy_files,
             Some(v) => v.clone(),
@@ -389,6 +637,61 @@ impl Repo {
             None => existing.default_working_dir,
             Some(v) => v.clone(),
         };
+        let max_concurrent_attempts = match &payload.max_concurrent_attempts {
+            None => existing.max_concurrent_attempts,
+            Some(v) => v.filter(|limit| *limit > 0),
+        };
+        let max_worktrees = match &payload.max_worktrees {
+            None => existing.max_worktrees,
+            Some(v) => v.filter(|limit| *limit > 0),
+        };
+        let prune_worktrees_at_limit = payload
+            .prune_worktrees_at_limit
+            .unwrap_or(existing.prune_worktrees_at_limit);
+        let max_attempts_per_task = match &payload.max_attempts_per_task {
+            None => existing.max_attempts_per_task,
+            Some(v) => v.filter(|limit| *limit > 0),
+        };
+        let denied_tool_note_template = match &payload.denied_tool_note_template {
+            None => existing.denied_tool_note_template,
+            Some(v) => v.clone(),
+        };
+        let pr_title_template = match &payload.pr_title_template {
+            None => existing.pr_title_template,
+            Some(v) => v.clone(),
+        };
+        let pr_body_template = match &payload.pr_body_template {
+            None => existing.pr_body_template,
+            Some(v) => v.clone(),
+        };
+        let notification_title_template = match &payload.notification_title_template {
+            None => existing.notification_title_template,
+            Some(v) => v.clone(),
+        };
+        let notification_body_template = match &payload.notification_body_template {
+            None => existing.notification_body_template,
+            Some(v) => v.clone(),
+        };
+        let default_merge_method = match &payload.default_merge_method {
+            None => existing.default_merge_method,
+            Some(v) => v.clone(),
+        };
+        let default_reviewers = match &payload.default_reviewers {
+            None => existing.default_reviewers,
+            Some(v) => sqlx::types::Json(normalize_reviewers(v)),
+        };
+        let default_pr_labels = match &payload.default_pr_labels {
+            None => existing.default_pr_labels,
+            Some(v) => sqlx::types::Json(normalize_list(v.iter().map(String::as_str))),
+        };
+        let push_remote = match &payload.push_remote {
+            None => existing.push_remote,
+            Some(v) => v.clone().filter(|name| !name.trim().is_empty()),
+        };
+        let pr_base_remote = match &payload.pr_base_remote {
+            None => existing.pr_base_remote,
+            Some(v) => v.clone().filter(|name| !name.trim().is_empty()),
+        };
 
         sqlx::query_as!(
             Repo,
@@ -397,13 +700,28 @@ impl Repo {
                    setup_script = $2,
                    cleanup_script = $3,
                    archive_script = $4,
-                   copy_files = $5,
-                   parallel_setup_script = $6,
-                   dev_server_script = $7,
-                   default_target_branch = $8,
-                   default_working_dir = $9,
+                   post_merge_script = $5,
+                   copy_files = $6,
+                   parallel_setup_script = $7,
+                   dev_server_script = $8,
+                   default_target_branch = $9,
+                   default_working_dir = $10,
+                   max_concurrent_attempts = $11,
+                   max_worktrees = $12,
+                   prune_worktrees_at_limit = $13,
+                   max_attempts_per_task = $14,
+                   denied_tool_note_template = $15,
+                   pr_title_template = $16,
+                   pr_body_template = $17,
+                   notification_title_template = $18,
+                   notification_body_template = $19,
+                   default_merge_method = $20,
+                   default_reviewers = $21,
+                   default_pr_labels = $22,
+                   push_remote = $23,
+                   pr_base_remote = $24,
                    updated_at = datetime('now', 'subsec')
-               WHERE id = $10
+               WHERE id = $25
                RETURNING id as "id!: Uuid",
                          path,
                          name,
@@ -411,22 +729,52 @@ impl Repo {
                          setup_script,
                          cleanup_script,
                          archive_script,
+                         post_merge_script,
                          copy_files,
                          parallel_setup_script as "parallel_setup_script!: bool",
                          dev_server_script,
                          default_target_branch,
                          default_working_dir,
+                         max_concurrent_attempts,
+                         max_worktrees,
+                         prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                         max_attempts_per_task,
+                         denied_tool_note_template,
+                         pr_title_template,
+                         pr_body_template,
+                         notification_title_template,
+                         notification_body_template,
+                         default_merge_method,
+                         default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                         default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                         push_remote,
+                         pr_base_remote,
                          created_at as "created_at!: DateTime<Utc>",
                          updated_at as "updated_at!: DateTime<Utc>""#,
             display_name,
             setup_script,
             cleanup_script,
             archive_script,
+            post_merge_script,
             copy_files,
             parallel_setup_script,
             dev_server_script,
             default_target_branch,
             default_working_dir,
+            max_concurrent_attempts,
+            max_worktrees,
+            prune_worktrees_at_limit,
+            max_attempts_per_task,
+            denied_tool_note_template,
+            pr_title_template,
+            pr_body_template,
+            notification_title_template,
+            notification_body_template,
+            default_merge_method,
+            default_reviewers,
+            default_pr_labels,
+            push_remote,
+            pr_base_remote,
             id
         )
         .fetch_one(pool)
@@ -434,3 +782,79 @@ impl Repo {
         .map_err(RepoError::from)
     }
 }
+
+/// Trim reviewer handles, drop a leading `@`, and normalize as a list.
+fn normalize_reviewers(reviewers: &[String]) -> Vec<String> {
+    normalize_list(
+        reviewers
+            .iter()
+            .map(|reviewer| reviewer.trim().trim_start_matches('@')),
+    )
+}
+
+/// Trim entries, drop blanks, and de-duplicate while keeping the configured
+/// order.
+fn normalize_list<'a>(values: impl Iterator<Item = &'a str>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for value in values {
+        let value = value.trim();
+        if !value.is_empty() && !normalized.iter().any(|v| v == value) {
+            normalized.push(value.to_string());
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::test_pool;
+
+    async fn insert_repo(pool: &SqlitePool) -> Uuid {
+        let id = Uuid::new_v4();
+        sqlx::query("INSERT INTO repos (id, path, name, display_name) VALUES (?, ?, 'app', 'app')")
+            .bind(id)
+            .bind(format!("/tmp/{id}"))
+            .execute(pool)
+            .await
+            .unwrap();
+        id
+    }
+
+    async fn update(pool: &SqlitePool, id: Uuid, payload: serde_json::Value) -> Repo {
+        let payload: UpdateRepo = serde_json::from_value(payload).unwrap();
+        Repo::update(pool, id, &payload).await.unwrap()
+    }
+
+    #[test]
+    fn reviewer_handles_are_trimmed_and_deduplicated() {
+        let reviewers = [" @alice ", "bob", "alice", "", "@", "org/team"].map(String::from);
+        assert_eq!(
+            normalize_reviewers(&reviewers),
+            ["alice", "bob", "org/team"]
+        );
+    }
+
+    #[tokio::test]
+    async fn default_reviewers_are_kept_until_replaced() {
+        let pool = test_pool().await;
+        let id = insert_repo(&pool).await;
+
+        let repo = update(
+            &pool,
+            id,
+            json!({ "default_reviewers": ["@alice", "bob", "alice"] }),
+        )
+        .await;
+        assert_eq!(repo.default_reviewers.0, ["alice", "bob"]);
+
+        // Updates that don't mention reviewers leave them alone
+        let repo = update(&pool, id, json!({ "display_name": "App" })).await;
+        assert_eq!(repo.default_reviewers.0, ["alice", "bob"]);
+
+        let repo = update(&pool, id, json!({ "default_reviewers": [] })).await;
+        assert!(repo.default_reviewers.0.is_empty());
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use executors::profile::ExecutorConfig;
 use serde::{Deserialize, Serialize};
 use ts_rs::TS;
@@ -35,7 +37,31 @@ pub struct CreateAndStartWorkspaceRequest {
     pub linked_issue: Option<LinkedIssueInfo>,
     pub executor_config: ExecutorConfig,
     pub prompt: String,
+    /// Compose the prompt from the linked issue using the configured issue
+    /// prompt template. A non-empty `prompt` is appended after it.
+    #[serde(default)]
+    #[ts(optional)]
+    pub prompt_from_issue: bool,
     pub attachment_ids: Option<Vec<Uuid>>,
+    /// Environment variables for this workspace's runs only. They override
+    /// config and profile env.
+    #[serde(default)]
+    #[ts(optional)]
+    pub env_overrides: HashMap<String, String>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub mode: WorkspaceStartMode,
+}
+
+/// How the first coding agent run of a new workspace is carried out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkspaceStartMode {
+    #[default]
+    Run,
+    /// Dry run: the agent's file changes are collected as proposed changes
+    /// and then discarded, leaving the worktree untouched.
+    Preview,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
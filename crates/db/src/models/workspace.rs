@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use chrono::{DateTime, Utc};
 use executors::actions::{ExecutorAction, ExecutorActionType};
 use serde::{Deserialize, Serialize};
@@ -85,6 +87,8 @@ pub struct WorkspaceContext {
 pub struct CreateWorkspace {
     pub branch: String,
     pub name: Option<String>,
+    /// Task (linked issue) the workspace was started for.
+    pub task_id: Option<Uuid>,
 }
 
 impl Workspace {
@@ -191,6 +195,38 @@ impl Workspace {
         Ok(())
     }
 
+    /// Set the environment variables applied to every run in the workspace.
+    pub async fn set_env_overrides(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        env_overrides: &HashMap<String, String>,
+    ) -> Result<(), sqlx::Error> {
+        let env_overrides = sqlx::types::Json(env_overrides);
+        sqlx::query!(
+            "UPDATE workspaces SET env_overrides = $1 WHERE id = $2",
+            env_overrides,
+            workspace_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_env_overrides(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+    ) -> Result<HashMap<String, String>, sqlx::Error> {
+        let env_overrides = sqlx::query_scalar!(
+            r#"SELECT env_overrides AS "env_overrides!: sqlx::types::Json<HashMap<String, String>>"
+               FROM workspaces
+               WHERE id = $1"#,
+            workspace_id
+        )
+        .fetch_optional(pool)
+        .await?;
+        Ok(env_overrides.map(|json| json.0).unwrap_or_default())
+    }
+
     pub async fn find_by_id(pool: &SqlitePool, id: Uuid) -> Result<Option<Self>, sqlx::Error> {
         sqlx::query_as!(
             Workspace,
@@ -249,6 +285,28 @@ impl Workspace {
         Ok(result.exists)
     }
 
+    /// Whether any workspace already manages `branch` in the given repo.
+    pub async fn branch_in_use(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        branch: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"SELECT EXISTS(
+                   SELECT 1
+                   FROM   workspaces w
+                   JOIN   workspace_repos wr ON wr.workspace_id = w.id
+                   WHERE  wr.repo_id = $1 AND w.branch = $2
+               ) as "exists!: bool""#,
+            repo_id,
+            branch
+        )
+        .fetch_one(pool)
+        .await?;
+
+        Ok(result.exists)
+    }
+
     /// Find workspaces that are expired and eligible for cleanup.
     /// Uses accelerated cleanup (1 hour) for archived workspaces.
     /// Uses standard cleanup (72 hours) for non-archived workspaces.
@@ -308,6 +366,81 @@ impl Workspace {
         .await
     }
 
+    /// Count workspaces other than `exclude_id` that have a worktree of the
+    /// repo on disk.
+    pub async fn count_with_worktree_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        exclude_id: Uuid,
+    ) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64"
+               FROM workspaces w
+               JOIN workspace_repos wr ON wr.workspace_id = w.id
+               WHERE wr.repo_id = $1
+                 AND w.id != $2
+                 AND w.container_ref IS NOT NULL
+                 AND w.worktree_deleted = FALSE"#,
+            repo_id,
+            exclude_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Count workspaces started for a task, including archived ones.
+    pub async fn count_for_task(pool: &SqlitePool, task_id: Uuid) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar!(
+            r#"SELECT COUNT(*) as "count!: i64" FROM workspaces WHERE task_id = $1"#,
+            task_id
+        )
+        .fetch_one(pool)
+        .await
+    }
+
+    /// Find workspaces other than `exclude_id` whose worktree of the repo can
+    /// be pruned: not pinned and with no running execution processes.
+    /// Least recently updated first.
+    pub async fn find_prunable_with_worktree_for_repo(
+        pool: &SqlitePool,
+        repo_id: Uuid,
+        exclude_id: Uuid,
+    ) -> Result<Vec<Workspace>, sqlx::Error> {
+        sqlx::query_as!(
+            Workspace,
+            r#"SELECT
+                w.id as "id!: Uuid",
+                w.task_id as "task_id: Uuid",
+                w.container_ref,
+                w.branch as "branch!",
+                w.setup_completed_at as "setup_completed_at: DateTime<Utc>",
+                w.created_at as "created_at!: DateTime<Utc>",
+                w.updated_at as "updated_at!: DateTime<Utc>",
+                w.archived as "archived!: bool",
+                w.pinned as "pinned!: bool",
+                w.name,
+                w.worktree_deleted as "worktree_deleted!: bool"
+            FROM workspaces w
+            JOIN workspace_repos wr ON wr.workspace_id = w.id
+            WHERE wr.repo_id = $1
+                AND w.id != $2
+                AND w.container_ref IS NOT NULL
+                AND w.worktree_deleted = FALSE
+                AND w.pinned = FALSE
+                AND w.id NOT IN (
+                    SELECT DISTINCT s.workspace_id
+                    FROM sessions s
+                    JOIN execution_processes ep ON s.id = ep.session_id
+                    WHERE ep.completed_at IS NULL
+                )
+            ORDER BY w.updated_at ASC"#,
+            repo_id,
+            exclude_id
+        )
+        .fetch_all(pool)
+        .await
+    }
+
     pub async fn create(
         pool: &SqlitePool,
         data: &CreateWorkspace,
@@ -319,7 +452,7 @@ impl Workspace {
                VALUES ($1, $2, $3, $4, $5, $6)
                RETURNING id as "id!: Uuid", task_id as "task_id: Uuid", container_ref, branch, setup_completed_at as "setup_completed_at: DateTime<Utc>", created_at as "created_at!: DateTime<Utc>", updated_at as "updated_at!: DateTime<Utc>", archived as "archived!: bool", pinned as "pinned!: bool", name, worktree_deleted as "worktree_deleted!: bool""#,
             id,
-            Option::<Uuid>::None,
+            data.task_id,
             Option::<String>::None,
             data.branch,
             Option::<DateTime<Utc>>::None,
@@ -520,7 +653,7 @@ impl Workspace {
                     FROM sessions s
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
-                      AND ep.status = 'running'
+                      AND ep.status IN ('queued', 'running')
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
@@ -614,7 +747,7 @@ impl Workspace {
                     FROM sessions s
                     JOIN execution_processes ep ON ep.session_id = s.id
                     WHERE s.workspace_id = w.id
-                      AND ep.status = 'running'
+                      AND ep.status IN ('queued', 'running')
                       AND ep.run_reason IN ('setupscript','cleanupscript','codingagent')
                     LIMIT 1
                 ) THEN 1 ELSE 0 END AS "is_running!: i64",
@@ -14,6 +14,12 @@ pub struct WorkspaceRepo {
     pub workspace_id: Uuid,
     pub repo_id: Uuid,
     pub target_branch: String,
+    /// Merge-base with the target branch when the workspace was created from
+    /// an existing branch. `None` for workspaces that created their branch.
+    pub base_commit: Option<String>,
+    /// Last time the workspace branch was pushed for a pull request, including
+    /// to remotes with no pull request API.
+    pub branch_pushed_at: Option<DateTime<Utc>>,
     #[ts(type = "Date")]
     pub created_at: DateTime<Utc>,
     #[ts(type = "Date")]
@@ -68,6 +74,8 @@ impl WorkspaceRepo {
                              workspace_id as "workspace_id!: Uuid",
                              repo_id as "repo_id!: Uuid",
                              target_branch,
+                             base_commit,
+                             branch_pushed_at as "branch_pushed_at: DateTime<Utc>",
                              created_at as "created_at!: DateTime<Utc>",
                              updated_at as "updated_at!: DateTime<Utc>""#,
                 id,
@@ -94,6 +102,8 @@ impl WorkspaceRepo {
                       workspace_id as "workspace_id!: Uuid",
                       repo_id as "repo_id!: Uuid",
                       target_branch,
+                      base_commit,
+                      branch_pushed_at as "branch_pushed_at: DateTime<Utc>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM workspace_repos
@@ -117,11 +127,26 @@ impl WorkspaceRepo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.post_merge_script,
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.max_concurrent_attempts,
+                      r.max_worktrees,
+                      r.prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                      r.max_attempts_per_task,
+                      r.denied_tool_note_template,
+                      r.pr_title_template,
+                      r.pr_body_template,
+                      r.notification_title_template,
+                      r.notification_body_template,
+                      r.default_merge_method,
+                      r.default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                      r.default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                      r.push_remote,
+                      r.pr_base_remote,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>"
                FROM repos r
@@ -146,11 +171,26 @@ impl WorkspaceRepo {
                       r.setup_script,
                       r.cleanup_script,
                       r.archive_script,
+                      r.post_merge_script,
                       r.copy_files,
                       r.parallel_setup_script as "parallel_setup_script!: bool",
                       r.dev_server_script,
                       r.default_target_branch,
                       r.default_working_dir,
+                      r.max_concurrent_attempts,
+                      r.max_worktrees,
+                      r.prune_worktrees_at_limit as "prune_worktrees_at_limit!: bool",
+                      r.max_attempts_per_task,
+                      r.denied_tool_note_template,
+                      r.pr_title_template,
+                      r.pr_body_template,
+                      r.notification_title_template,
+                      r.notification_body_template,
+                      r.default_merge_method,
+                      r.default_reviewers as "default_reviewers!: sqlx::types::Json<Vec<String>>",
+                      r.default_pr_labels as "default_pr_labels!: sqlx::types::Json<Vec<String>>",
+                      r.push_remote,
+                      r.pr_base_remote,
                       r.created_at as "created_at!: DateTime<Utc>",
                       r.updated_at as "updated_at!: DateTime<Utc>",
                       wr.target_branch
@@ -174,11 +214,26 @@ impl WorkspaceRepo {
                     setup_script: row.setup_script,
                     cleanup_script: row.cleanup_script,
                     archive_script: row.archive_script,
+                    post_merge_script: row.post_merge_script,
                     copy_files: row.copy_files,
                     parallel_setup_script: row.parallel_setup_script,
                     dev_server_script: row.dev_server_script,
                     default_target_branch: row.default_target_branch,
                     default_working_dir: row.default_working_dir,
+                    max_concurrent_attempts: row.max_concurrent_attempts,
+                    max_worktrees: row.max_worktrees,
+                    prune_worktrees_at_limit: row.prune_worktrees_at_limit,
+                    max_attempts_per_task: row.max_attempts_per_task,
+                    denied_tool_note_template: row.denied_tool_note_template,
+                    pr_title_template: row.pr_title_template,
+                    pr_body_template: row.pr_body_template,
+                    notification_title_template: row.notification_title_template,
+                    notification_body_template: row.notification_body_template,
+                    default_merge_method: row.default_merge_method,
+                    default_reviewers: row.default_reviewers,
+                    default_pr_labels: row.default_pr_labels,
+                    push_remote: row.push_remote,
+                    pr_base_remote: row.pr_base_remote,
                     created_at: row.created_at,
                     updated_at: row.updated_at,
                 },
@@ -198,6 +253,8 @@ impl WorkspaceRepo {
                       workspace_id as "workspace_id!: Uuid",
                       repo_id as "repo_id!: Uuid",
                       target_branch,
+                      base_commit,
+                      branch_pushed_at as "branch_pushed_at: DateTime<Utc>",
                       created_at as "created_at!: DateTime<Utc>",
                       updated_at as "updated_at!: DateTime<Utc>"
                FROM workspace_repos
@@ -226,6 +283,38 @@ impl WorkspaceRepo {
         Ok(())
     }
 
+    pub async fn set_base_commit(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+        base_commit: &str,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspace_repos SET base_commit = $1, updated_at = datetime('now') WHERE workspace_id = $2 AND repo_id = $3",
+            base_commit,
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn mark_branch_pushed(
+        pool: &SqlitePool,
+        workspace_id: Uuid,
+        repo_id: Uuid,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            "UPDATE workspace_repos SET branch_pushed_at = datetime('now'), updated_at = datetime('now') WHERE workspace_id = $1 AND repo_id = $2",
+            workspace_id,
+            repo_id
+        )
+        .execute(pool)
+        .await?;
+        Ok(())
+    }
+
     pub async fn update_target_branch_for_children_of_workspace(
         pool: &SqlitePool,
         parent_workspace_id: Uuid,
@@ -0,0 +1,96 @@
+//! SQLite connection settings, overridable through the environment:
+//!
+//! | Variable | Default | Values |
+//! |---|---|---|
+//! | `VK_SQLITE_JOURNAL_MODE` | `wal` | `wal`, `delete`, `truncate`, `persist`, `memory`, `off` |
+//! | `VK_SQLITE_SYNCHRONOUS` | `normal` | `off`, `normal`, `full`, `extra` |
+//! | `VK_SQLITE_BUSY_TIMEOUT_MS` | `5000` | milliseconds to wait on a locked database |
+//! | `VK_SQLITE_MAX_CONNECTIONS` | `10` | size of the server's connection pool |
+//!
+//! Invalid values are logged and replaced by the default.
+
+use std::{str::FromStr, time::Duration};
+
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteSynchronous};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SqliteSettings {
+    pub journal_mode: SqliteJournalMode,
+    pub synchronous: SqliteSynchronous,
+    pub busy_timeout: Duration,
+    pub max_connections: u32,
+}
+
+impl Default for SqliteSettings {
+    fn default() -> Self {
+        Self {
+            journal_mode: SqliteJournalMode::Wal,
+            synchronous: SqliteSynchronous::Normal,
+            busy_timeout: Duration::from_secs(5),
+            max_connections: 10,
+        }
+    }
+}
+
+impl SqliteSettings {
+    pub fn from_env() -> Self {
+        Self::from_lookup(|key| std::env::var(key).ok())
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Self {
+        fn parse<T: FromStr>(lookup: &impl Fn(&str) -> Option<String>, key: &str, default: T) -> T {
+            let Some(value) = lookup(key) else {
+                return default;
+            };
+            value.trim().parse().unwrap_or_else(|_| {
+                tracing::warn!("Ignoring invalid {key}={value:?}, using the default");
+                default
+            })
+        }
+
+        let defaults = Self::default();
+        Self {
+            journal_mode: parse(&lookup, "VK_SQLITE_JOURNAL_MODE", defaults.journal_mode),
+            synchronous: parse(&lookup, "VK_SQLITE_SYNCHRONOUS", defaults.synchronous),
+            busy_timeout: Duration::from_millis(parse(
+                &lookup,
+                "VK_SQLITE_BUSY_TIMEOUT_MS",
+                defaults.busy_timeout.as_millis() as u64,
+            )),
+            max_connections: parse(
+                &lookup,
+                "VK_SQLITE_MAX_CONNECTIONS",
+                defaults.max_connections,
+            )
+            .max(1),
+        }
+    }
+
+    /// Apply the pragmas; sqlx runs them on every new connection.
+    pub fn apply(&self, options: SqliteConnectOptions) -> SqliteConnectOptions {
+        options
+            .journal_mode(self.journal_mode)
+            .synchronous(self.synchronous)
+            .busy_timeout(self.busy_timeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_overrides_and_ignores_invalid_values() {
+        let settings = SqliteSettings::from_lookup(|key| match key {
+            "VK_SQLITE_JOURNAL_MODE" => Some("delete".into()),
+            "VK_SQLITE_SYNCHRONOUS" => Some("sometimes".into()),
+            "VK_SQLITE_BUSY_TIMEOUT_MS" => Some(" 250 ".into()),
+            "VK_SQLITE_MAX_CONNECTIONS" => Some("0".into()),
+            _ => None,
+        });
+        assert_eq!(settings.journal_mode, SqliteJournalMode::Delete);
+        assert_eq!(settings.synchronous, SqliteSynchronous::Normal);
+        assert_eq!(settings.busy_timeout, Duration::from_millis(250));
+        assert_eq!(settings.max_connections, 1);
+    }
+}
@@ -16,6 +16,7 @@ use serde_json::Value;
 use services::services::{
     analytics::AnalyticsService,
     approvals::Approvals,
+    attempt_limiter::AttemptLimiter,
     auth::AuthContext,
     config::{Config, ConfigError},
     container::{ContainerError, ContainerService},
@@ -106,6 +107,8 @@ pub trait Deployment: Clone + Send + Sync + 'static {
 
     fn queued_message_service(&self) -> &QueuedMessageService;
 
+    fn attempt_limiter(&self) -> &AttemptLimiter;
+
     fn auth_context(&self) -> &AuthContext;
 
     fn relay_control(&self) -> &Arc<RelayControl>;
@@ -139,7 +142,20 @@ pub trait Deployment: Clone + Send + Sync + 'static {
     }
 
     async fn track_if_analytics_allowed(&self, event_name: &str, properties: Value) {
-        let analytics_enabled = self.config().read().await.analytics_enabled;
+        let (analytics_enabled, event_allowed) = {
+            let config = self.config().read().await;
+            let event_allowed = match config.analytics_event_allowlist.as_deref() {
+                Some(allowlist) if !allowlist.is_empty() => {
+                    allowlist.iter().any(|name| name == event_name)
+                }
+                _ => true,
+            };
+            (config.analytics_enabled, event_allowed)
+        };
+        if analytics_enabled && !event_allowed {
+            tracing::debug!("Dropping analytics event not on allow-list: {}", event_name);
+            return;
+        }
         // Track events unless user has explicitly opted out
         if analytics_enabled && let Some(analytics) = self.analytics() {
             analytics.track_event(self.user_id(), event_name, Some(properties.clone()));
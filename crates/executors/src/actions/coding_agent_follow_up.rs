@@ -52,6 +52,7 @@ impl Executable for CodingAgentFollowUpRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        let prompt = env.prompt_with_system_context(&self.prompt);
 
         #[cfg(feature = "qa-mode")]
         {
@@ -60,7 +61,7 @@ impl Executable for CodingAgentFollowUpRequest {
             return executor
                 .spawn_follow_up(
                     &effective_dir,
-                    &self.prompt,
+                    &prompt,
                     &self.session_id,
                     self.reset_to_message_id.as_deref(),
                     env,
@@ -83,7 +84,7 @@ impl Executable for CodingAgentFollowUpRequest {
             agent
                 .spawn_follow_up(
                     &effective_dir,
-                    &self.prompt,
+                    &prompt,
                     &self.session_id,
                     self.reset_to_message_id.as_deref(),
                     env,
@@ -24,6 +24,10 @@ pub struct CodingAgentInitialRequest {
     /// If None, uses the container_ref directory directly.
     #[serde(default)]
     pub working_dir: Option<String>,
+    /// Dry run: the agent's file changes are reported but never kept.
+    #[serde(default)]
+    #[ts(optional)]
+    pub preview: bool,
 }
 
 impl CodingAgentInitialRequest {
@@ -49,12 +53,13 @@ impl Executable for CodingAgentInitialRequest {
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
         let effective_dir = self.effective_dir(current_dir);
+        let prompt = env.prompt_with_system_context(&self.prompt);
 
         #[cfg(feature = "qa-mode")]
         {
             tracing::info!("QA mode: using mock executor instead of real agent");
             let executor = crate::executors::qa_mock::QaMockExecutor;
-            return executor.spawn(&effective_dir, &self.prompt, env).await;
+            return executor.spawn(&effective_dir, &prompt, env).await;
         }
 
         #[cfg(not(feature = "qa-mode"))]
@@ -69,7 +74,7 @@ impl Executable for CodingAgentInitialRequest {
             }
             agent.use_approvals(approvals.clone());
 
-            agent.spawn(&effective_dir, &self.prompt, env).await
+            agent.spawn(&effective_dir, &prompt, env).await
         }
     }
 }
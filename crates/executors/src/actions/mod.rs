@@ -69,6 +69,14 @@ impl ExecutorAction {
             ExecutorActionType::ScriptRequest(_) => None,
         }
     }
+
+    /// Whether this is a preview run, whose worktree changes are discarded.
+    pub fn is_preview(&self) -> bool {
+        matches!(
+            self.typ(),
+            ExecutorActionType::CodingAgentInitialRequest(request) if request.preview
+        )
+    }
 }
 
 #[async_trait]
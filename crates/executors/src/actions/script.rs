@@ -1,18 +1,31 @@
-use std::{path::Path, sync::Arc};
+use std::{path::Path, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use tokio::process::Command;
 use ts_rs::TS;
-use workspace_utils::{command_ext::GroupSpawnNoWindowExt, shell::get_shell_command};
+use workspace_utils::{
+    command_ext::GroupSpawnNoWindowExt, env::positive_or, shell::get_shell_command,
+};
 
 use crate::{
     actions::Executable,
     approvals::ExecutorApprovalService,
     env::ExecutionEnv,
-    executors::{ExecutorError, SpawnedChild},
+    executors::{ExecutorError, ExecutorExitResult, SpawnedChild},
 };
 
+const DEFAULT_POST_MERGE_SCRIPT_TIMEOUT_SECS: u64 = 600;
+
+/// Post-merge scripts usually trigger deploys or notifications, so they are
+/// killed if they run too long. Override with `VK_POST_MERGE_SCRIPT_TIMEOUT_SECS`.
+fn post_merge_script_timeout() -> Duration {
+    Duration::from_secs(positive_or(
+        "VK_POST_MERGE_SCRIPT_TIMEOUT_SECS",
+        DEFAULT_POST_MERGE_SCRIPT_TIMEOUT_SECS,
+    ))
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS)]
 pub enum ScriptRequestLanguage {
     Bash,
@@ -23,6 +36,7 @@ pub enum ScriptContext {
     SetupScript,
     CleanupScript,
     ArchiveScript,
+    PostMergeScript,
     DevServer,
     ToolInstallScript,
 }
@@ -38,6 +52,15 @@ pub struct ScriptRequest {
     pub working_dir: Option<String>,
 }
 
+impl ScriptRequest {
+    fn timeout(&self) -> Option<Duration> {
+        match self.context {
+            ScriptContext::PostMergeScript => Some(post_merge_script_timeout()),
+            _ => None,
+        }
+    }
+}
+
 #[async_trait]
 impl Executable for ScriptRequest {
     async fn spawn(
@@ -67,7 +90,24 @@ impl Executable for ScriptRequest {
         env.apply_to_command(&mut command);
 
         let child = command.group_spawn_no_window()?;
+        let mut spawned = SpawnedChild::from(child);
+
+        if let Some(timeout) = self.timeout() {
+            // The container kills the process group and marks the run failed
+            // when this fires; it is dropped unsent if the script exits first.
+            let (mut exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+            tokio::spawn(async move {
+                tokio::select! {
+                    _ = tokio::time::sleep(timeout) => {
+                        tracing::warn!("Script timed out after {}s", timeout.as_secs());
+                        let _ = exit_tx.send(ExecutorExitResult::Failure);
+                    }
+                    _ = exit_tx.closed() => {}
+                }
+            });
+            spawned.exit_signal = Some(exit_rx);
+        }
 
-        Ok(child.into())
+        Ok(spawned)
     }
 }
@@ -1,9 +1,11 @@
 use std::fmt;
 
 use async_trait::async_trait;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
 use workspace_utils::approvals::{ApprovalStatus, QuestionStatus};
 
 /// Errors emitted by executor approval services.
@@ -25,9 +27,62 @@ impl ExecutorApprovalError {
     }
 }
 
+/// A tool call the agent is never allowed to make, denied without asking the
+/// user, ahead of any approval or auto-approval.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS, JsonSchema)]
+pub struct AutoDenyRule {
+    /// Regex matched against the Bash command, or the tool name for other tools
+    pub pattern: String,
+    /// Reason sent to the agent when the rule denies a tool call
+    pub reason: String,
+}
+
+/// Used when a profile doesn't set `auto_deny_rules`.
+const DEFAULT_AUTO_DENY_RULES: &[(&str, &str)] = &[
+    (
+        r"\brm\s+(?:-\S*\s+)*-\S*[rR]\S*\s+(?:-\S+\s+)*(?:/|~/?|\$HOME/?)\*?(?:[\s;&|]|$)",
+        "Recursively deleting the root or home directory is not allowed",
+    ),
+    (
+        r"\b(?:curl|wget)\b[^|;&]*\|\s*(?:sudo\s+)?(?:ba|z|da)?sh\b",
+        "Piping a download straight into a shell is not allowed",
+    ),
+    (
+        r"\bmkfs(?:\.\w+)?\b",
+        "Formatting a filesystem is not allowed",
+    ),
+    (
+        r"\bdd\b.*\bof=/dev/|>\s*/dev/(?:sd|hd|nvme|disk)",
+        "Writing directly to a disk device is not allowed",
+    ),
+    (
+        r":\(\)\s*\{\s*:\s*\|\s*:\s*&\s*\}\s*;\s*:",
+        "Fork bombs are not allowed",
+    ),
+];
+
+pub fn default_auto_deny_rules() -> Vec<AutoDenyRule> {
+    DEFAULT_AUTO_DENY_RULES
+        .iter()
+        .map(|(pattern, reason)| AutoDenyRule {
+            pattern: pattern.to_string(),
+            reason: reason.to_string(),
+        })
+        .collect()
+}
+
 /// Abstraction for executor approval backends.
 #[async_trait]
 pub trait ExecutorApprovalService: Send + Sync {
+    /// Deny tool calls matching `rules` for the rest of the run.
+    fn set_auto_deny_rules(&self, _rules: &[AutoDenyRule]) {}
+
+    /// The reason of the first auto-deny rule matching this tool call, if any.
+    /// A match counts as a denial by the user.
+    fn check_auto_deny(&self, _tool_name: &str, _tool_input: &serde_json::Value) -> Option<String> {
+        None
+    }
+
     /// Creates a tool approval request. Returns the approval_id immediately.
     async fn create_tool_approval(&self, tool_name: &str) -> Result<String, ExecutorApprovalError>;
 
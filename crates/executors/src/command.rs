@@ -18,6 +18,8 @@ pub enum CommandBuildError {
     QuoteError(#[from] shlex::QuoteError),
     #[error("invalid shell parameters: {0}")]
     InvalidShellParams(String),
+    #[error("{0}")]
+    PolicyViolation(String),
 }
 
 #[derive(Debug, Clone)]
@@ -60,6 +62,18 @@ pub struct CmdOverrides {
     )]
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub env: Option<HashMap<String, String>>,
+    #[schemars(
+        title = "External Log Normalizer",
+        description = "Command that reads the raw agent output as JSON lines on stdin and prints normalized entries as JSON lines on stdout, replacing the built-in log parser"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_normalizer: Option<String>,
+    #[schemars(
+        title = "Inactivity Timeout (seconds)",
+        description = "Stop the agent if it produces no output for this many seconds. Off when unset"
+    )]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inactivity_timeout_secs: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, TS, JsonSchema)]
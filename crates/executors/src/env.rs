@@ -75,12 +75,22 @@ impl RepoContext {
 }
 
 /// Environment variables to inject into executor processes
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct ExecutionEnv {
     pub vars: HashMap<String, String>,
+    /// Variables set for this attempt only. They win over every other source,
+    /// including profile env, and their values are never logged.
+    pub attempt_overrides: HashMap<String, String>,
     pub repo_context: RepoContext,
     pub commit_reminder: bool,
     pub commit_reminder_prompt: String,
+    /// Context block appended to the prompt: identity context for a new
+    /// coding agent session, or notes about denied tools for a follow-up.
+    pub system_context: Option<String>,
+    /// Reject executor configurations that skip permission prompts.
+    pub disallow_permission_bypass: bool,
+    /// `disable_api_key` for Claude Code profiles that leave it unset.
+    pub disable_api_key_default: bool,
 }
 
 impl ExecutionEnv {
@@ -91,9 +101,21 @@ impl ExecutionEnv {
     ) -> Self {
         Self {
             vars: HashMap::new(),
+            attempt_overrides: HashMap::new(),
             repo_context,
             commit_reminder,
             commit_reminder_prompt,
+            system_context: None,
+            disallow_permission_bypass: false,
+            disable_api_key_default: false,
+        }
+    }
+
+    /// Append the system context block, if any, to a prompt.
+    pub fn prompt_with_system_context(&self, prompt: &str) -> String {
+        match &self.system_context {
+            Some(context) => format!("{prompt}\n\n{context}"),
+            None => prompt.to_string(),
         }
     }
 
@@ -114,10 +136,19 @@ impl ExecutionEnv {
         self
     }
 
-    /// Return a new env with profile env from CmdOverrides merged in.
+    /// Set the attempt's own variables, which take precedence over all others.
+    pub fn set_attempt_overrides(&mut self, overrides: HashMap<String, String>) {
+        self.merge(&overrides);
+        self.attempt_overrides = overrides;
+    }
+
+    /// Return a new env with profile env from CmdOverrides merged in. Attempt
+    /// overrides still take precedence.
     pub fn with_profile(self, cmd: &CmdOverrides) -> Self {
         if let Some(ref profile_env) = cmd.env {
+            let attempt_overrides = self.attempt_overrides.clone();
             self.with_overrides(profile_env)
+                .with_overrides(&attempt_overrides)
         } else {
             self
         }
@@ -139,6 +170,55 @@ impl ExecutionEnv {
     }
 }
 
+impl std::fmt::Debug for ExecutionEnv {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let vars: HashMap<&str, &str> = self
+            .vars
+            .iter()
+            .map(|(key, value)| {
+                let value = if self.attempt_overrides.contains_key(key) {
+                    "<redacted>"
+                } else {
+                    value.as_str()
+                };
+                (key.as_str(), value)
+            })
+            .collect();
+        f.debug_struct("ExecutionEnv")
+            .field("vars", &vars)
+            .field(
+                "attempt_overrides",
+                &self.attempt_overrides.keys().collect::<Vec<_>>(),
+            )
+            .field("repo_context", &self.repo_context)
+            .field("commit_reminder", &self.commit_reminder)
+            .field("system_context", &self.system_context)
+            .field(
+                "disallow_permission_bypass",
+                &self.disallow_permission_bypass,
+            )
+            .field("disable_api_key_default", &self.disable_api_key_default)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Check that `key` can be used as an environment variable name: ASCII
+/// letters, digits and underscores, not starting with a digit.
+pub fn validate_env_key(key: &str) -> Result<(), String> {
+    let mut chars = key.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid environment variable name '{key}': use letters, digits and underscores, not starting with a digit"
+        ))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -159,4 +239,30 @@ mod tests {
         assert_eq!(merged.vars.get("FOO").unwrap(), "profile"); // overrides
         assert_eq!(merged.vars.get("BAR").unwrap(), "profile");
     }
+
+    #[test]
+    fn attempt_overrides_win_over_profile_and_are_redacted() {
+        let mut base = ExecutionEnv::new(RepoContext::default(), false, String::new());
+        base.insert("FOO", "runtime");
+        base.set_attempt_overrides(HashMap::from([("FOO".to_string(), "secret".to_string())]));
+
+        let cmd = CmdOverrides {
+            env: Some(HashMap::from([("FOO".to_string(), "profile".to_string())])),
+            ..Default::default()
+        };
+        let merged = base.with_profile(&cmd);
+
+        assert_eq!(merged.vars.get("FOO").unwrap(), "secret");
+        assert!(!format!("{merged:?}").contains("secret"));
+    }
+
+    #[test]
+    fn env_key_validation() {
+        assert!(validate_env_key("FEATURE_FLAG_1").is_ok());
+        assert!(validate_env_key("_private").is_ok());
+        assert!(validate_env_key("").is_err());
+        assert!(validate_env_key("1ABC").is_err());
+        assert!(validate_env_key("MY-VAR").is_err());
+        assert!(validate_env_key("A=B").is_err());
+    }
 }
@@ -16,8 +16,9 @@ use super::AcpEvent;
 use crate::{
     approvals::ToolCallMetadata,
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolResult, ToolResultValueType, ToolStatus as LogToolStatus,
+        ActionType, FileChange, LogStream, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
+        ToolStatus as LogToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         stderr_processor::normalize_stderr_logs,
         utils::{ConversationPatch, EntryIndexProvider, shell_command_parsing::CommandCategory},
@@ -688,13 +689,16 @@ fn normalize_acp_stderr_logs(
         let mut stderr = msg_store.stderr_chunked_stream();
 
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content: strip_ansi_escapes::strip_str(&content),
-                metadata: None,
+            .normalized_entry_producer(Box::new(|content: String| {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content: strip_ansi_escapes::strip_str(&content),
+                    metadata: None,
+                }
+                .with_stream(LogStream::Stderr)
             }))
             .time_gap(Duration::from_secs(2))
             .index_provider(entry_index_provider)
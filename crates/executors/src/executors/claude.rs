@@ -8,7 +8,10 @@ use std::{
     collections::HashMap,
     path::{Path, PathBuf},
     process::Stdio,
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::Duration,
 };
 
@@ -23,30 +26,37 @@ use workspace_utils::{
     approvals::{ApprovalStatus, QuestionStatus},
     command_ext::GroupSpawnNoWindowExt,
     diff::create_unified_diff,
+    env::positive_or,
     log_msg::LogMsg,
     msg_store::MsgStore,
     path::make_path_relative,
 };
 
 use self::{
-    client::{AUTO_APPROVE_CALLBACK_ID, ClaudeAgentClient, STOP_GIT_CHECK_CALLBACK_ID},
+    client::{
+        AUTO_APPROVE_CALLBACK_ID, AUTO_DENY_CALLBACK_ID, ClaudeAgentClient,
+        STOP_GIT_CHECK_CALLBACK_ID,
+    },
     protocol::ProtocolPeer,
     types::{ControlRequestType, ControlResponseType, PermissionMode},
 };
 use crate::{
-    approvals::ExecutorApprovalService,
+    approvals::{AutoDenyRule, ExecutorApprovalService, default_auto_deny_rules},
     command::{CmdOverrides, CommandBuildError, CommandBuilder, CommandParts, apply_overrides},
     env::ExecutionEnv,
     executors::{
-        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, SpawnedChild,
-        StandardCodingAgentExecutor, codex::client::LogWriter, utils::reorder_slash_commands,
+        AppendPrompt, AvailabilityInfo, BaseCodingAgent, ExecutorError, ExecutorExitResult,
+        SpawnedChild, StandardCodingAgentExecutor,
+        codex::{client::LogWriter, jsonrpc::ExitSignalSender},
+        utils::reorder_slash_commands,
     },
     logs::{
         ActionType, AnsweredQuestion, AskUserQuestionItem, AskUserQuestionOption, FileChange,
-        NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem, ToolStatus,
+        LogStream, NormalizedEntry, NormalizedEntryError, NormalizedEntryType, TodoItem,
+        TokenUsageTotals, ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
-            EntryIndexProvider,
+            ApprovalEntries, EntryIndexProvider,
             patch::{self, ConversationPatch},
             shell_command_parsing::CommandCategory,
         },
@@ -74,13 +84,16 @@ fn normalize_claude_stderr_logs(
         let mut stderr = msg_store.stderr_chunked_stream();
 
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content: strip_ansi_escapes::strip_str(&content),
-                metadata: None,
+            .normalized_entry_producer(|content: String| {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content: strip_ansi_escapes::strip_str(&content),
+                    metadata: None,
+                }
+                .with_stream(LogStream::Stderr)
             })
             .time_gap(Duration::from_secs(2))
             .index_provider(entry_index_provider)
@@ -115,6 +128,18 @@ pub enum ClaudeEffort {
     Max,
 }
 
+/// Mirror of the server config's `claude_disable_api_key`, for Claude Code
+/// commands run outside an execution, like slash command discovery.
+static DISABLE_API_KEY_DEFAULT: AtomicBool = AtomicBool::new(false);
+
+pub fn set_disable_api_key_default(disable: bool) {
+    DISABLE_API_KEY_DEFAULT.store(disable, Ordering::Relaxed);
+}
+
+pub(crate) fn disable_api_key_default() -> bool {
+    DISABLE_API_KEY_DEFAULT.load(Ordering::Relaxed)
+}
+
 #[derive(Derivative, Clone, Serialize, Deserialize, TS, JsonSchema)]
 #[derivative(Debug, PartialEq)]
 pub struct ClaudeCode {
@@ -134,8 +159,13 @@ pub struct ClaudeCode {
     pub agent: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dangerously_skip_permissions: Option<bool>,
+    /// Unset uses the global default from the server config.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub disable_api_key: Option<bool>,
+    /// Tool calls to deny without asking, checked before any approval.
+    /// Unset uses a built-in list of destructive commands; empty turns it off.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_deny_rules: Option<Vec<AutoDenyRule>>,
     #[serde(flatten)]
     pub cmd: CmdOverrides,
 
@@ -146,7 +176,31 @@ pub struct ClaudeCode {
 }
 
 impl ClaudeCode {
-    async fn build_command_builder(&self) -> Result<CommandBuilder, CommandBuildError> {
+    /// Fails if this configuration would let Claude act without asking for
+    /// permission: `dangerously_skip_permissions`, or neither plan nor
+    /// approvals enabled (which runs in bypass mode).
+    pub fn check_permission_policy(&self) -> Result<(), CommandBuildError> {
+        if self.dangerously_skip_permissions.unwrap_or(false) {
+            return Err(CommandBuildError::PolicyViolation(
+                "dangerously_skip_permissions is disabled by server policy".to_string(),
+            ));
+        }
+        if self.permission_mode() == PermissionMode::BypassPermissions {
+            return Err(CommandBuildError::PolicyViolation(
+                "Claude Code bypass mode is disabled by server policy; enable plan or approvals for this profile".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn build_command_builder(
+        &self,
+        env: &ExecutionEnv,
+    ) -> Result<CommandBuilder, CommandBuildError> {
+        if env.disallow_permission_bypass {
+            self.check_permission_policy()?;
+        }
+
         // If base_command_override is provided and claude_code_router is also set, log a warning
         if self.cmd.base_command_override.is_some() && self.claude_code_router.is_some() {
             tracing::warn!(
@@ -206,6 +260,12 @@ impl ClaudeCode {
         }
     }
 
+    fn auto_deny_rules(&self) -> Vec<AutoDenyRule> {
+        self.auto_deny_rules
+            .clone()
+            .unwrap_or_else(default_auto_deny_rules)
+    }
+
     pub fn get_hooks(&self, commit_reminder: bool) -> Option<serde_json::Value> {
         let mut hooks = serde_json::Map::new();
 
@@ -255,6 +315,23 @@ impl ClaudeCode {
             );
         }
 
+        // Deny rules run on every tool call; Claude lets a deny outrank the
+        // allow/ask decisions of the hooks above.
+        if self
+            .auto_deny_rules
+            .as_ref()
+            .is_none_or(|rules| !rules.is_empty())
+            && let Some(serde_json::Value::Array(pre_tool_use)) = hooks.get_mut("PreToolUse")
+        {
+            pre_tool_use.insert(
+                0,
+                serde_json::json!({
+                    "matcher": ".*",
+                    "hookCallbackIds": [AUTO_DENY_CALLBACK_ID],
+                }),
+            );
+        }
+
         Some(serde_json::Value::Object(hooks))
     }
 
@@ -351,7 +428,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         prompt: &str,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let command_builder = self.build_command_builder().await?;
+        let command_builder = self.build_command_builder(env).await?;
         let command_parts = command_builder.build_initial()?;
         self.spawn_internal(current_dir, prompt, command_parts, env)
             .await
@@ -365,7 +442,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
         reset_to_message_id: Option<&str>,
         env: &ExecutionEnv,
     ) -> Result<SpawnedChild, ExecutorError> {
-        let command_builder = self.build_command_builder().await?;
+        let command_builder = self.build_command_builder(env).await?;
 
         let mut args = vec!["--resume".to_string(), session_id.to_string()];
 
@@ -394,6 +471,7 @@ impl StandardCodingAgentExecutor for ClaudeCode {
             current_dir,
             entry_index_provider.clone(),
             HistoryStrategy::Default,
+            self.disable_api_key,
         );
 
         // Process stderr logs
@@ -641,8 +719,9 @@ impl ClaudeCode {
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
 
-        // Remove ANTHROPIC_API_KEY if disable_api_key is enabled
-        if self.disable_api_key.unwrap_or(false) {
+        // Remove ANTHROPIC_API_KEY if disable_api_key is enabled, either by the
+        // profile or by the global default
+        if self.disable_api_key.unwrap_or(env.disable_api_key_default) {
             command.env_remove("ANTHROPIC_API_KEY");
             tracing::info!("ANTHROPIC_API_KEY removed from environment");
         }
@@ -663,11 +742,22 @@ impl ClaudeCode {
         // Create cancellation token for graceful shutdown
         let cancel = CancellationToken::new();
 
+        // Messages injected while the agent is running are written straight to stdin
+        let (message_tx, mut message_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+
         // Spawn task to handle the SDK client with control protocol
         let prompt_clone = combined_prompt.clone();
         let approvals_clone = self.approvals_service.clone();
+        if let Some(approvals) = &approvals_clone {
+            approvals.set_auto_deny_rules(&self.auto_deny_rules());
+        }
         let repo_context = env.repo_context.clone();
         let commit_reminder_prompt = env.commit_reminder_prompt.clone();
+        let (exit_tx, exit_rx) = tokio::sync::oneshot::channel();
+        // Held by this task as well as the reader: if Claude's output ends
+        // without a result, the sender must outlive it so the container
+        // waits for the real exit status instead of a closed channel.
+        let exit_tx = ExitSignalSender::new(exit_tx);
         let cancel_for_task = cancel.clone();
         tokio::spawn(async move {
             let log_writer = LogWriter::new(new_stdout);
@@ -678,8 +768,13 @@ impl ClaudeCode {
                 commit_reminder_prompt,
                 cancel_for_task.clone(),
             );
-            let protocol_peer =
-                ProtocolPeer::spawn(child_stdin, child_stdout, client.clone(), cancel_for_task);
+            let protocol_peer = ProtocolPeer::spawn(
+                child_stdin,
+                child_stdout,
+                client.clone(),
+                cancel_for_task.clone(),
+                exit_tx.clone(),
+            );
 
             // Initialize control protocol
             if let Err(e) = protocol_peer.initialize(hooks).await {
@@ -687,6 +782,7 @@ impl ClaudeCode {
                 let _ = log_writer
                     .log_raw(&format!("Error: Failed to initialize - {e}"))
                     .await;
+                exit_tx.send_exit_signal(ExecutorExitResult::Failure).await;
                 return;
             }
 
@@ -700,13 +796,31 @@ impl ClaudeCode {
                 let _ = log_writer
                     .log_raw(&format!("Error: Failed to send prompt - {e}"))
                     .await;
+                exit_tx.send_exit_signal(ExecutorExitResult::Failure).await;
+                return;
+            }
+
+            loop {
+                tokio::select! {
+                    _ = cancel_for_task.cancelled() => break,
+                    message = message_rx.recv() => {
+                        let Some(message) = message else {
+                            break;
+                        };
+                        if let Err(e) = protocol_peer.send_user_message(message).await {
+                            tracing::warn!("Failed to inject user message: {e}");
+                            break;
+                        }
+                    }
+                }
             }
         });
 
         Ok(SpawnedChild {
             child,
-            exit_signal: None,
+            exit_signal: Some(exit_rx),
             cancel: Some(cancel),
+            message_tx: Some(message_tx),
         })
     }
 }
@@ -721,12 +835,23 @@ pub enum HistoryStrategy {
 
 /// Default context window for models (used until we get actual value from result)
 const DEFAULT_CLAUDE_CONTEXT_WINDOW: u32 = 200_000;
+const DEFAULT_PARTIAL_LINE_THRESHOLD: usize = 64 * 1024;
+
+/// Bytes of a single unterminated stdout line after which a loading entry is
+/// shown. Override with `VK_LOG_PARTIAL_LINE_THRESHOLD`.
+fn partial_line_threshold() -> usize {
+    positive_or(
+        "VK_LOG_PARTIAL_LINE_THRESHOLD",
+        DEFAULT_PARTIAL_LINE_THRESHOLD,
+    )
+}
 
 /// Handles log processing and interpretation for Claude executor
 pub struct ClaudeLogProcessor {
     model_name: Option<String>,
     // Map tool_use_id -> structured info for follow-up ToolResult replacement
     tool_map: HashMap<String, ClaudeToolCallInfo>,
+    approval_entries: ApprovalEntries,
     // Strategy controlling how to handle history and user messages
     strategy: HistoryStrategy,
     streaming_messages: HashMap<String, StreamingMessageState>,
@@ -736,6 +861,10 @@ pub struct ClaudeLogProcessor {
     main_model_name: Option<String>,
     main_model_context_window: u32,
     context_tokens_used: u32,
+    // Usage summed over every message of the run, subagents included.
+    session_usage: Option<TokenUsageTotals>,
+    // The profile's `disable_api_key`, to name the setting that kept the key.
+    disable_api_key: Option<bool>,
 }
 
 impl ClaudeLogProcessor {
@@ -749,12 +878,15 @@ impl ClaudeLogProcessor {
             model_name: None,
             main_model_name: None,
             tool_map: HashMap::new(),
+            approval_entries: ApprovalEntries::default(),
             strategy,
             streaming_messages: HashMap::new(),
             streaming_message_id: None,
             last_assistant_message: None,
             main_model_context_window: DEFAULT_CLAUDE_CONTEXT_WINDOW,
             context_tokens_used: 0,
+            session_usage: None,
+            disable_api_key: None,
         }
     }
 
@@ -764,6 +896,7 @@ impl ClaudeLogProcessor {
         current_dir: &Path,
         entry_index_provider: EntryIndexProvider,
         strategy: HistoryStrategy,
+        disable_api_key: Option<bool>,
     ) -> tokio::task::JoinHandle<()> {
         let current_dir_clone = current_dir.to_owned();
         tokio::spawn(async move {
@@ -772,8 +905,12 @@ impl ClaudeLogProcessor {
             let worktree_path = current_dir_clone.to_string_lossy().to_string();
             let mut session_id_extracted = false;
             let mut processor = Self::new_with_strategy(strategy);
+            processor.disable_api_key = disable_api_key;
             // Track pending assistant UUID - only committed when we see a Result message
             let mut pending_assistant_uuid: Option<String> = None;
+            let partial_line_threshold = partial_line_threshold();
+            // Loading entry shown while a line longer than the threshold is pending
+            let mut partial_line_placeholder: Option<usize> = None;
 
             while let Some(Ok(msg)) = stream.next().await {
                 let chunk = match msg {
@@ -788,6 +925,28 @@ impl ClaudeLogProcessor {
 
                 buffer.push_str(&chunk);
 
+                // The long line behind the placeholder is complete; drop it
+                // before the parsed entries are added.
+                if chunk.contains('\n')
+                    && let Some(index) = partial_line_placeholder.take()
+                {
+                    if entry_index_provider.release(index) {
+                        msg_store.push_patch(ConversationPatch::remove(index));
+                    } else {
+                        // Other entries were added after it, so removing would
+                        // shift their indices.
+                        msg_store.push_patch(ConversationPatch::replace(
+                            index,
+                            NormalizedEntry {
+                                timestamp: None,
+                                entry_type: NormalizedEntryType::SystemMessage,
+                                content: "Received a large message".to_string(),
+                                metadata: None,
+                            },
+                        ));
+                    }
+                }
+
                 // Process complete JSON lines
                 for line in buffer
                     .split_inclusive('\n')
@@ -869,6 +1028,21 @@ impl ClaudeLogProcessor {
 
                 // Keep the partial line in the buffer
                 buffer = buffer.rsplit('\n').next().unwrap_or("").to_owned();
+
+                // Show progress while a long single line is still streaming in
+                if partial_line_placeholder.is_none() && buffer.len() >= partial_line_threshold {
+                    let index = entry_index_provider.next();
+                    msg_store.push_patch(ConversationPatch::add_normalized_entry(
+                        index,
+                        NormalizedEntry {
+                            timestamp: None,
+                            entry_type: NormalizedEntryType::Loading,
+                            content: String::new(),
+                            metadata: None,
+                        },
+                    ));
+                    partial_line_placeholder = Some(index);
+                }
             }
 
             // Handle any remaining content in buffer
@@ -880,8 +1054,12 @@ impl ClaudeLogProcessor {
                     metadata: None,
                 };
 
-                let patch_id = entry_index_provider.next();
-                let patch = ConversationPatch::add_normalized_entry(patch_id, entry);
+                let patch = match partial_line_placeholder.take() {
+                    Some(index) => ConversationPatch::replace(index, entry),
+                    None => {
+                        ConversationPatch::add_normalized_entry(entry_index_provider.next(), entry)
+                    }
+                };
                 msg_store.push_patch(patch);
             }
         })
@@ -908,8 +1086,9 @@ impl ClaudeLogProcessor {
         }
     }
 
-    /// Generate warning entry if API key source is ANTHROPIC_API_KEY
-    fn warn_if_unmanaged_key(src: &Option<String>) -> Option<NormalizedEntry> {
+    /// Generate warning entry if API key source is ANTHROPIC_API_KEY, naming
+    /// the setting that kept the key
+    fn warn_if_unmanaged_key(&self, src: &Option<String>) -> Option<NormalizedEntry> {
         match src.as_deref() {
             Some("ANTHROPIC_API_KEY") => {
                 tracing::warn!(
@@ -917,9 +1096,13 @@ impl ClaudeLogProcessor {
                 );
                 Some(NormalizedEntry {
                     timestamp: None,
-                    entry_type: NormalizedEntryType::ErrorMessage { error_type: NormalizedEntryError::Other,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
                     },
-                    content: "Claude Code + ANTHROPIC_API_KEY detected. Usage will be billed via Anthropic pay-as-you-go instead of your Claude subscription. If this is unintended, please select the `disable_api_key` checkbox in the conding-agent-configurations settings page.".to_string(),
+                    content: format!(
+                        "Claude Code + ANTHROPIC_API_KEY detected. Usage will be billed via Anthropic pay-as-you-go instead of your Claude subscription. {}",
+                        Self::api_key_setting_hint(self.disable_api_key)
+                    ),
                     metadata: None,
                 })
             }
@@ -927,6 +1110,17 @@ impl ClaudeLogProcessor {
         }
     }
 
+    fn api_key_setting_hint(disable_api_key: Option<bool>) -> &'static str {
+        match disable_api_key {
+            Some(false) => {
+                "This coding agent configuration turns `disable_api_key` off, overriding \"Disable ANTHROPIC_API_KEY for Claude Code\" in General settings. If this is unintended, select the `disable_api_key` checkbox in the coding agent configuration settings."
+            }
+            _ => {
+                "\"Disable ANTHROPIC_API_KEY for Claude Code\" is off in General settings. If this is unintended, turn it on there, or select the `disable_api_key` checkbox in the coding agent configuration settings."
+            }
+        }
+    }
+
     /// Normalize Claude tool_result content to either Markdown string or parsed JSON.
     /// - If content is a string that parses as JSON, return Json with parsed value.
     /// - If content is a string (non-JSON), return Markdown with the raw string.
@@ -1249,7 +1443,7 @@ impl ClaudeLogProcessor {
                 ..
             } => {
                 // emit billing warning if required
-                if let Some(warning) = Self::warn_if_unmanaged_key(api_key_source) {
+                if let Some(warning) = self.warn_if_unmanaged_key(api_key_source) {
                     let idx = entry_index_provider.next();
                     patches.push(ConversationPatch::add_normalized_entry(idx, warning));
                 }
@@ -1770,6 +1964,9 @@ impl ClaudeLogProcessor {
                 }
                 ClaudeStreamEvent::ContentBlockStop { .. } => {}
                 ClaudeStreamEvent::MessageDelta { usage, .. } => {
+                    if let Some(usage) = usage {
+                        *self.session_usage.get_or_insert_default() += usage.totals();
+                    }
                     // do not report context token usage for subagents
                     if parent_tool_use_id.is_none()
                         && let Some(usage) = usage
@@ -1794,6 +1991,7 @@ impl ClaudeLogProcessor {
             ClaudeJson::Result {
                 is_error,
                 model_usage,
+                usage,
                 subtype,
                 result,
                 ..
@@ -1806,7 +2004,6 @@ impl ClaudeLogProcessor {
                         .and_then(|usage| usage.context_window)
                 }) {
                     self.main_model_context_window = context_window;
-                    patches.push(self.add_token_usage_entry(entry_index_provider));
                 }
 
                 if matches!(self.strategy, HistoryStrategy::AmpResume) && is_error.unwrap_or(false)
@@ -1840,10 +2037,25 @@ impl ClaudeLogProcessor {
                     let idx = entry_index_provider.next();
                     patches.push(ConversationPatch::add_normalized_entry(idx, entry));
                 }
+
+                // The run's usage totals come last. Without partial messages
+                // there are no deltas to sum, so use the result's own report.
+                let session_totals = self
+                    .session_usage
+                    .or_else(|| usage.as_ref().map(ClaudeUsage::totals))
+                    .unwrap_or_default();
+                let mut entry = self.token_usage_entry();
+                if let NormalizedEntryType::TokenUsageInfo(info) = &mut entry.entry_type {
+                    info.session_totals = Some(session_totals);
+                }
+                patches.push(ConversationPatch::add_normalized_entry(
+                    entry_index_provider.next(),
+                    entry,
+                ));
             }
             ClaudeJson::ApprovalRequested {
                 tool_call_id,
-                tool_name: _,
+                tool_name,
                 approval_id,
             } => {
                 self.replace_tool_entry_status(
@@ -1854,6 +2066,12 @@ impl ClaudeLogProcessor {
                     worktree_path,
                     &mut patches,
                 );
+                patches.push(self.approval_entries.requested(
+                    tool_call_id,
+                    tool_name,
+                    approval_id,
+                    entry_index_provider,
+                ));
             }
             ClaudeJson::ApprovalResponse {
                 tool_call_id,
@@ -1868,6 +2086,12 @@ impl ClaudeLogProcessor {
                         &mut patches,
                     );
                 }
+                if let Some(patch) = self
+                    .approval_entries
+                    .resolved(tool_call_id, approval_status)
+                {
+                    patches.push(patch);
+                }
 
                 let entry_opt = match approval_status {
                     ApprovalStatus::Pending | ApprovalStatus::Approved => None,
@@ -2059,20 +2283,24 @@ impl ClaudeLogProcessor {
         &mut self,
         entry_index_provider: &EntryIndexProvider,
     ) -> json_patch::Patch {
-        let entry = NormalizedEntry {
+        let idx = entry_index_provider.next();
+        ConversationPatch::add_normalized_entry(idx, self.token_usage_entry())
+    }
+
+    fn token_usage_entry(&self) -> NormalizedEntry {
+        NormalizedEntry {
             timestamp: None,
             entry_type: NormalizedEntryType::TokenUsageInfo(crate::logs::TokenUsageInfo {
                 total_tokens: self.context_tokens_used,
                 model_context_window: self.main_model_context_window,
+                session_totals: None,
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
                 self.context_tokens_used, self.main_model_context_window
             ),
             metadata: None,
-        };
-        let idx = entry_index_provider.next();
-        ConversationPatch::add_normalized_entry(idx, entry)
+        }
     }
 }
 
@@ -2511,6 +2739,17 @@ pub struct ClaudeUsage {
     pub service_tier: Option<String>,
 }
 
+impl ClaudeUsage {
+    fn totals(&self) -> TokenUsageTotals {
+        TokenUsageTotals {
+            input_tokens: self.input_tokens.unwrap_or(0),
+            output_tokens: self.output_tokens.unwrap_or(0),
+            cache_read_input_tokens: self.cache_read_input_tokens.unwrap_or(0),
+            cache_creation_input_tokens: self.cache_creation_input_tokens.unwrap_or(0),
+        }
+    }
+}
+
 /// Per-model usage statistics from result message
 #[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
 #[serde(rename_all = "camelCase")]
@@ -2826,12 +3065,49 @@ mod tests {
         let parsed: ClaudeJson = serde_json::from_str(result_json).unwrap();
 
         let entries = normalize(&parsed, "");
-        assert_eq!(entries.len(), 1);
+        assert_eq!(entries.len(), 2);
         assert!(matches!(
             entries[0].entry_type,
             NormalizedEntryType::AssistantMessage
         ));
         assert_eq!(entries[0].content, "Final result");
+        // No usage was reported, so the run's totals are zero.
+        assert!(matches!(
+            &entries[1].entry_type,
+            NormalizedEntryType::TokenUsageInfo(info)
+                if info.session_totals == Some(TokenUsageTotals::default())
+        ));
+    }
+
+    #[test]
+    fn test_result_reports_session_token_totals() {
+        let mut processor = ClaudeLogProcessor::new();
+        for line in [
+            r#"{"type":"stream_event","event":{"type":"message_delta","delta":{},"usage":{"input_tokens":10,"output_tokens":5,"cache_read_input_tokens":100}}}"#,
+            r#"{"type":"stream_event","parent_tool_use_id":"toolu_1","event":{"type":"message_delta","delta":{},"usage":{"input_tokens":3,"output_tokens":2,"cache_creation_input_tokens":7}}}"#,
+        ] {
+            let parsed: ClaudeJson = serde_json::from_str(line).unwrap();
+            normalize_helper(&mut processor, &parsed, "");
+        }
+
+        // Summed deltas win over the result's own report.
+        let result: ClaudeJson = serde_json::from_str(
+            r#"{"type":"result","subtype":"success","is_error":false,"usage":{"input_tokens":1}}"#,
+        )
+        .unwrap();
+        let entries = normalize_helper(&mut processor, &result, "");
+        let NormalizedEntryType::TokenUsageInfo(info) = &entries.last().unwrap().entry_type else {
+            panic!("expected a token usage entry");
+        };
+        assert_eq!(
+            info.session_totals,
+            Some(TokenUsageTotals {
+                input_tokens: 13,
+                output_tokens: 7,
+                cache_read_input_tokens: 100,
+                cache_creation_input_tokens: 7,
+            })
+        );
     }
 
     #[test]
@@ -2951,9 +3227,12 @@ mod tests {
                 base_command_override: None,
                 additional_params: None,
                 env: None,
+                log_normalizer: None,
+                inactivity_timeout_secs: None,
             },
             approvals_service: None,
             disable_api_key: None,
+            auto_deny_rules: None,
         };
         let msg_store = Arc::new(MsgStore::new());
         let current_dir = std::path::PathBuf::from("/tmp/test-worktree");
@@ -3224,7 +3503,17 @@ mod tests {
         ));
         assert_eq!(
             entries[0].content,
-            "Claude Code + ANTHROPIC_API_KEY detected. Usage will be billed via Anthropic pay-as-you-go instead of your Claude subscription. If this is unintended, please select the `disable_api_key` checkbox in the conding-agent-configurations settings page."
+            "Claude Code + ANTHROPIC_API_KEY detected. Usage will be billed via Anthropic pay-as-you-go instead of your Claude subscription. \"Disable ANTHROPIC_API_KEY for Claude Code\" is off in General settings. If this is unintended, turn it on there, or select the `disable_api_key` checkbox in the coding agent configuration settings."
+        );
+
+        // A profile that turns the setting off explicitly is named instead
+        let mut processor = ClaudeLogProcessor::new();
+        processor.disable_api_key = Some(false);
+        let entries = normalize_helper(&mut processor, &parsed, "");
+        assert!(
+            entries[0]
+                .content
+                .contains("This coding agent configuration turns `disable_api_key` off")
         );
 
         // Test with managed API key source - should not generate warning
@@ -3281,4 +3570,46 @@ mod tests {
         let parsed: ClaudeJson = serde_json::from_str(control_request_json).unwrap();
         assert!(matches!(parsed, ClaudeJson::ControlRequest { .. }));
     }
+
+    fn claude_config(json: serde_json::Value) -> ClaudeCode {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn permission_policy_allows_plan_or_approvals() {
+        for json in [
+            serde_json::json!({ "plan": true }),
+            serde_json::json!({ "approvals": true }),
+            serde_json::json!({ "plan": true, "approvals": true }),
+        ] {
+            assert!(claude_config(json).check_permission_policy().is_ok());
+        }
+    }
+
+    #[test]
+    fn permission_policy_denies_bypass() {
+        for json in [
+            serde_json::json!({}),
+            serde_json::json!({ "plan": false, "approvals": false }),
+            serde_json::json!({ "approvals": true, "dangerously_skip_permissions": true }),
+        ] {
+            assert!(matches!(
+                claude_config(json).check_permission_policy(),
+                Err(CommandBuildError::PolicyViolation(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn permission_policy_only_applies_when_the_server_disallows_bypass() {
+        let bypass = claude_config(serde_json::json!({}));
+        let mut env = ExecutionEnv::new(crate::env::RepoContext::default(), false, String::new());
+        assert!(bypass.build_command_builder(&env).await.is_ok());
+
+        env.disallow_permission_bypass = true;
+        assert!(matches!(
+            bypass.build_command_builder(&env).await,
+            Err(CommandBuildError::PolicyViolation(_))
+        ));
+    }
 }
@@ -24,6 +24,7 @@ const EXIT_PLAN_MODE_NAME: &str = "ExitPlanMode";
 const ASK_USER_QUESTION_NAME: &str = "AskUserQuestion";
 pub const AUTO_APPROVE_CALLBACK_ID: &str = "AUTO_APPROVE_CALLBACK_ID";
 pub const STOP_GIT_CHECK_CALLBACK_ID: &str = "STOP_GIT_CHECK_CALLBACK_ID";
+pub const AUTO_DENY_CALLBACK_ID: &str = "AUTO_DENY_CALLBACK_ID";
 // Prefix for denial messages from the user, mirrors claude code CLI behavior
 const TOOL_DENY_PREFIX: &str = "The user doesn't want to proceed with this tool use. The tool use was rejected (eg. if it was a file edit, the new_string was NOT written to the file). To tell you how to proceed, the user said: ";
 
@@ -324,7 +325,7 @@ impl ClaudeAgentClient {
         &self,
         callback_id: String,
         input: serde_json::Value,
-        _tool_use_id: Option<String>,
+        tool_use_id: Option<String>,
     ) -> Result<serde_json::Value, ExecutorError> {
         // Stop hook git check - uses `decision` (approve/block) and `reason` fields
         if callback_id == STOP_GIT_CHECK_CALLBACK_ID {
@@ -346,6 +347,11 @@ impl ClaudeAgentClient {
             });
         }
 
+        // Checked before auto-approval, so deny rules hold in every mode
+        if callback_id == AUTO_DENY_CALLBACK_ID {
+            return self.check_auto_deny(&input, tool_use_id).await;
+        }
+
         if self.auto_approve {
             Ok(serde_json::json!({
                 "hookSpecificOutput": {
@@ -379,6 +385,47 @@ impl ClaudeAgentClient {
         }
     }
 
+    /// Deny the tool call if it matches one of the approval service's
+    /// auto-deny rules, logging the denial so it shows as user feedback.
+    /// Otherwise leave the decision to the other hooks.
+    async fn check_auto_deny(
+        &self,
+        input: &serde_json::Value,
+        tool_use_id: Option<String>,
+    ) -> Result<serde_json::Value, ExecutorError> {
+        let tool_name = input
+            .get("tool_name")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let tool_input = input.get("tool_input").unwrap_or(&serde_json::Value::Null);
+        let Some(reason) = self
+            .approvals
+            .as_ref()
+            .and_then(|approvals| approvals.check_auto_deny(tool_name, tool_input))
+        else {
+            return Ok(serde_json::json!({}));
+        };
+
+        if let Some(tool_use_id) = tool_use_id {
+            self.log_writer
+                .log_raw(&serde_json::to_string(&ClaudeJson::ApprovalResponse {
+                    tool_call_id: tool_use_id,
+                    tool_name: tool_name.to_string(),
+                    approval_status: ApprovalStatus::Denied {
+                        reason: Some(reason.clone()),
+                    },
+                })?)
+                .await?;
+        }
+        Ok(serde_json::json!({
+            "hookSpecificOutput": {
+                "hookEventName": "PreToolUse",
+                "permissionDecision": "deny",
+                "permissionDecisionReason": reason,
+            }
+        }))
+    }
+
     pub async fn log_message(&self, line: &str) -> Result<(), ExecutorError> {
         self.log_writer.log_raw(line).await
     }
@@ -1,4 +1,7 @@
-use std::sync::Arc;
+use std::sync::{
+    Arc,
+    atomic::{AtomicUsize, Ordering},
+};
 
 use tokio::{
     io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
@@ -11,34 +14,64 @@ use super::types::{CLIMessage, ControlRequestType, ControlResponseMessage, Contr
 use crate::{
     approvals::ExecutorApprovalError,
     executors::{
-        ExecutorError,
+        ExecutorError, ExecutorExitResult,
         claude::{
             client::ClaudeAgentClient,
             types::{Message, PermissionMode, SDKControlRequest, SDKControlRequestType},
         },
+        codex::jsonrpc::ExitSignalSender,
     },
 };
 
+/// User messages Claude hasn't answered with a `result` yet. Messages
+/// injected mid-turn are answered by results of their own, so the run is only
+/// over once none are left.
+#[derive(Clone, Default)]
+struct PendingTurns(Arc<AtomicUsize>);
+
+impl PendingTurns {
+    fn sent(&self) {
+        self.0.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Record a `result`. Returns true when no user message is left unanswered.
+    fn answered(&self) -> bool {
+        let previous = self
+            .0
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                Some(n.saturating_sub(1))
+            })
+            .unwrap_or_default();
+        previous <= 1
+    }
+}
+
 /// Handles bidirectional control protocol communication
 #[derive(Clone)]
 pub struct ProtocolPeer {
     stdin: Arc<Mutex<ChildStdin>>,
+    pending_turns: PendingTurns,
 }
 
 impl ProtocolPeer {
+    /// Claude keeps running on stdin after a turn, so `exit_tx` is resolved
+    /// from the `result` answering the last user message sent to it, to tell
+    /// the container the run is over.
     pub fn spawn(
         stdin: ChildStdin,
         stdout: ChildStdout,
         client: Arc<ClaudeAgentClient>,
         cancel: CancellationToken,
+        exit_tx: ExitSignalSender,
     ) -> Self {
         let peer = Self {
             stdin: Arc::new(Mutex::new(stdin)),
+            pending_turns: PendingTurns::default(),
         };
 
         let reader_peer = peer.clone();
         tokio::spawn(async move {
-            if let Err(e) = reader_peer.read_loop(stdout, client, cancel).await {
+            if let Err(e) = reader_peer.read_loop(stdout, client, cancel, exit_tx).await {
                 tracing::error!("Protocol reader loop error: {}", e);
             }
         });
@@ -51,6 +84,7 @@ impl ProtocolPeer {
         stdout: ChildStdout,
         client: Arc<ClaudeAgentClient>,
         cancel: CancellationToken,
+        exit_tx: ExitSignalSender,
     ) -> Result<(), ExecutorError> {
         let mut reader = BufReader::new(stdout);
         let mut buffer = String::new();
@@ -87,7 +121,22 @@ impl ProtocolPeer {
                                     self.handle_control_request(&client, request_id, request)
                                         .await;
                                 }
-                                Ok(CLIMessage::Result(_)) => {
+                                Ok(CLIMessage::Result(result)) => {
+                                    // An interrupt ends the run whatever is still queued.
+                                    if !self.pending_turns.answered() && !interrupt_sent {
+                                        continue;
+                                    }
+                                    let is_error = result
+                                        .get("is_error")
+                                        .and_then(|v| v.as_bool())
+                                        .unwrap_or(false);
+                                    exit_tx
+                                        .send_exit_signal(if is_error {
+                                            ExecutorExitResult::Failure
+                                        } else {
+                                            ExecutorExitResult::Success
+                                        })
+                                        .await;
                                     break;
                                 }
                                 _ => {}
@@ -197,7 +246,13 @@ impl ProtocolPeer {
 
     pub async fn send_user_message(&self, content: String) -> Result<(), ExecutorError> {
         let message = Message::new_user(content);
-        self.send_json(&message).await
+        // Counted before writing, so a fast `result` can't be read first.
+        self.pending_turns.sent();
+        let sent = self.send_json(&message).await;
+        if sent.is_err() {
+            self.pending_turns.answered();
+        }
+        sent
     }
 
     pub async fn initialize(&self, hooks: Option<serde_json::Value>) -> Result<(), ExecutorError> {
@@ -218,3 +273,24 @@ impl ProtocolPeer {
         .await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn run_ends_after_the_injected_message_is_answered() {
+        let pending = PendingTurns::default();
+        pending.sent();
+        pending.sent();
+
+        assert!(!pending.answered());
+        assert!(pending.answered());
+    }
+
+    #[test]
+    fn unexpected_results_still_end_the_run() {
+        let pending = PendingTurns::default();
+        assert!(pending.answered());
+    }
+}
@@ -233,7 +233,10 @@ impl ClaudeCode {
             .with_profile(&self.cmd)
             .apply_to_command(&mut command);
 
-        if self.disable_api_key.unwrap_or(false) {
+        if self
+            .disable_api_key
+            .unwrap_or_else(super::disable_api_key_default)
+        {
             command.env_remove("ANTHROPIC_API_KEY");
         }
 
@@ -757,6 +757,7 @@ impl Codex {
             child,
             exit_signal: Some(exit_signal_rx),
             cancel: Some(cancel),
+            message_tx: None,
         })
     }
 }
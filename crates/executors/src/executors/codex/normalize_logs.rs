@@ -47,8 +47,9 @@ use crate::{
     approvals::ToolCallMetadata,
     logs::{
         ActionType, AnsweredQuestion, AskUserQuestionItem, AskUserQuestionOption,
-        CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry, NormalizedEntryError,
-        NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType, ToolStatus,
+        CommandExitStatus, CommandRunResult, FileChange, LogStream, NormalizedEntry,
+        NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolResultValueType,
+        ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
             ConversationPatch, EntryIndexProvider,
@@ -753,6 +754,7 @@ fn add_thread_token_usage(
                     .token_usage
                     .model_context_window
                     .unwrap_or_default() as u32,
+                session_totals: None,
             }),
             content: format!(
                 "Tokens used: {} / Context window: {}",
@@ -1458,13 +1460,16 @@ fn normalize_codex_stderr_logs(
     tokio::spawn(async move {
         let mut stderr = msg_store.stderr_chunked_stream();
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content: strip_ansi_escapes::strip_str(&content),
-                metadata: None,
+            .normalized_entry_producer(|content: String| {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content: strip_ansi_escapes::strip_str(&content),
+                    metadata: None,
+                }
+                .with_stream(LogStream::Stderr)
             })
             .time_gap(Duration::from_secs(2))
             .index_provider(entry_index_provider)
@@ -2252,6 +2257,7 @@ pub fn normalize_logs(
                                             .model_context_window
                                             .unwrap_or_default()
                                             as u32,
+                                        session_totals: None,
                                     },
                                 ),
                                 content: format!(
@@ -24,8 +24,8 @@ use crate::{
         StandardCodingAgentExecutor,
     },
     logs::{
-        ActionType, FileChange, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
-        TodoItem, ToolStatus,
+        ActionType, FileChange, LogStream, NormalizedEntry, NormalizedEntryError,
+        NormalizedEntryType, TodoItem, ToolStatus,
         plain_text_processor::PlainTextLogProcessor,
         utils::{
             ConversationPatch, EntryIndexProvider, patch, shell_command_parsing::CommandCategory,
@@ -286,6 +286,7 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         content,
                         metadata: None,
                     }
+                    .with_stream(LogStream::Stderr)
                 }))
                 .time_gap(Duration::from_secs(2))
                 .index_provider(entry_index_provider_stderr.clone())
@@ -301,7 +302,8 @@ impl StandardCodingAgentExecutor for CursorAgent {
                         },
                         content: content.to_string(),
                         metadata: None,
-                    };
+                    }
+                    .with_stream(LogStream::Stderr);
                     let id = entry_index_provider_stderr.next();
                     msg_store_stderr
                         .push_patch(ConversationPatch::add_normalized_entry(id, error_message));
@@ -12,7 +12,7 @@ use workspace_utils::{
 };
 
 use crate::logs::{
-    ActionType, CommandExitStatus, CommandRunResult, FileChange, NormalizedEntry,
+    ActionType, CommandExitStatus, CommandRunResult, FileChange, LogStream, NormalizedEntry,
     NormalizedEntryError, NormalizedEntryType, TodoItem, ToolResult, ToolStatus,
     plain_text_processor::PlainTextLogProcessor,
     utils::{
@@ -679,13 +679,16 @@ fn normalize_stderr_logs(
         let mut stderr = msg_store.stderr_chunked_stream();
 
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content,
-                metadata: None,
+            .normalized_entry_producer(Box::new(|content: String| {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content,
+                    metadata: None,
+                }
+                .with_stream(LogStream::Stderr)
             }))
             .transform_lines(Box::new(|lines| {
                 lines.iter_mut().for_each(|line| {
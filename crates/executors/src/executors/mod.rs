@@ -1,4 +1,4 @@
-use std::{path::Path, sync::Arc};
+use std::{path::Path, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use command_group::AsyncGroupChild;
@@ -19,13 +19,13 @@ use crate::executors::qa_mock::QaMockExecutor;
 use crate::{
     actions::{ExecutorAction, review::RepoReviewContext},
     approvals::ExecutorApprovalService,
-    command::CommandBuildError,
+    command::{CmdOverrides, CommandBuildError},
     env::ExecutionEnv,
     executors::{
         amp::Amp, claude::ClaudeCode, codex::Codex, copilot::Copilot, cursor::CursorAgent,
         droid::Droid, gemini::Gemini, opencode::Opencode, qwen::QwenCode,
     },
-    logs::utils::patch,
+    logs::{external_normalizer, utils::patch},
     mcp_config::McpConfig,
     profile::ExecutorConfig,
 };
@@ -198,6 +198,53 @@ impl CodingAgent {
             Self::QaMock(_) => vec![], // QA mock doesn't need special capabilities
         }
     }
+
+    fn cmd_overrides(&self) -> Option<&CmdOverrides> {
+        match self {
+            Self::ClaudeCode(executor) => Some(&executor.cmd),
+            Self::Amp(executor) => Some(&executor.cmd),
+            Self::Gemini(executor) => Some(&executor.cmd),
+            Self::Codex(executor) => Some(&executor.cmd),
+            Self::Opencode(executor) => Some(&executor.cmd),
+            Self::CursorAgent(executor) => Some(&executor.cmd),
+            Self::QwenCode(executor) => Some(&executor.cmd),
+            Self::Copilot(executor) => Some(&executor.cmd),
+            Self::Droid(executor) => Some(&executor.cmd),
+            #[cfg(feature = "qa-mode")]
+            Self::QaMock(_) => None,
+        }
+    }
+
+    /// How long the agent may go without output before it is stopped, if
+    /// the profile sets a limit.
+    pub fn inactivity_timeout(&self) -> Option<Duration> {
+        self.cmd_overrides()
+            .and_then(|cmd| cmd.inactivity_timeout_secs)
+            .filter(|secs| *secs > 0)
+            .map(|secs| Duration::from_secs(secs.into()))
+    }
+
+    /// Normalize logs with the profile's external log normalizer if one is
+    /// configured, otherwise with the executor's built-in parser.
+    pub fn normalize_logs_with_profile(
+        &self,
+        raw_logs_event_store: Arc<MsgStore>,
+        worktree_path: &Path,
+    ) -> Vec<JoinHandle<()>> {
+        let external = self
+            .cmd_overrides()
+            .and_then(|cmd| cmd.log_normalizer.as_deref())
+            .map(str::trim)
+            .filter(|command| !command.is_empty());
+        match external {
+            Some(command) => external_normalizer::normalize_with_command(
+                command,
+                raw_logs_event_store,
+                worktree_path,
+            ),
+            None => self.normalize_logs(raw_logs_event_store, worktree_path),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -319,6 +366,10 @@ pub type ExecutorExitSignal = tokio::sync::oneshot::Receiver<ExecutorExitResult>
 /// When cancelled, the executor should attempt to cancel gracefully before being killed.
 pub type CancellationToken = tokio_util::sync::CancellationToken;
 
+/// Channel for delivering additional user messages to an executor while it is running.
+/// Only executors with a bidirectional stdin protocol provide one.
+pub type ExecutorMessageSender = tokio::sync::mpsc::UnboundedSender<String>;
+
 #[derive(Debug)]
 pub struct SpawnedChild {
     pub child: AsyncGroupChild,
@@ -326,6 +377,8 @@ pub struct SpawnedChild {
     pub exit_signal: Option<ExecutorExitSignal>,
     /// Container → Executor: signals when container wants to cancel the execution
     pub cancel: Option<CancellationToken>,
+    /// Container → Executor: user messages injected mid-run
+    pub message_tx: Option<ExecutorMessageSender>,
 }
 
 impl From<AsyncGroupChild> for SpawnedChild {
@@ -334,6 +387,7 @@ impl From<AsyncGroupChild> for SpawnedChild {
             child,
             exit_signal: None,
             cancel: None,
+            message_tx: None,
         }
     }
 }
@@ -101,6 +101,7 @@ impl StandardCodingAgentExecutor for QaMockExecutor {
             current_dir,
             entry_index_provider,
             crate::executors::claude::HistoryStrategy::Default,
+            None,
         );
         vec![h1]
     }
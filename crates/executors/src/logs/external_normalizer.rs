@@ -0,0 +1,291 @@
+//! Log normalization by an external command, for agents without a built-in
+//! parser.
+//!
+//! The command is run through the user's shell in the worktree. It receives
+//! the agent's raw output on stdin as JSON lines:
+//!
+//! ```json
+//! {"type": "stdout", "content": "..."}
+//! {"type": "stderr", "content": "..."}
+//! ```
+//!
+//! stdin is closed once the agent has finished. The command writes one JSON
+//! object per line to stdout:
+//!
+//! ```json
+//! {"entry": {"timestamp": null, "entry_type": {"type": "assistant_message"}, "content": "Hi"}}
+//! {"index": 0, "entry": {...}}
+//! ```
+//!
+//! `entry` is a [`NormalizedEntry`]. Without `index` the entry is appended;
+//! with it, the entry replaces the one this command emitted at that position
+//! (counting from 0), which is how in-progress tool calls are updated.
+//!
+//! The command replaces the built-in parser, which is also what extracts the
+//! agent's session id. Follow-ups can only resume the session if the command
+//! reports it:
+//!
+//! ```json
+//! {"session_id": "..."}
+//! ```
+//!
+//! Lines that aren't valid JSON are logged and skipped.
+//!
+//! The process isn't marked finished until the command closes its stdout, so
+//! entries it prints after reading all of its input still make it into the
+//! log.
+
+use std::{path::Path, process::Stdio, sync::Arc};
+
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    process::Command,
+    task::JoinHandle,
+};
+use workspace_utils::{log_msg::LogMsg, msg_store::MsgStore, shell::get_shell_command};
+
+use super::{
+    NormalizedEntry,
+    utils::{
+        EntryIndexProvider,
+        patch::{add_normalized_entry, replace_normalized_entry},
+    },
+};
+
+/// A raw log chunk written to the normalizer's stdin.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", content = "content", rename_all = "lowercase")]
+enum RawLogLine<'a> {
+    Stdout(&'a str),
+    Stderr(&'a str),
+}
+
+/// A line read from the normalizer's stdout.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NormalizerLine {
+    Entry {
+        #[serde(default)]
+        index: Option<usize>,
+        entry: NormalizedEntry,
+    },
+    SessionId {
+        session_id: String,
+    },
+}
+
+/// Pipe the raw logs in `msg_store` through `command` and push the entries it
+/// prints back into the store.
+pub fn normalize_with_command(
+    command: &str,
+    msg_store: Arc<MsgStore>,
+    worktree_path: &Path,
+) -> Vec<JoinHandle<()>> {
+    let (shell, shell_arg) = get_shell_command();
+    let mut child = match Command::new(shell)
+        .arg(shell_arg)
+        .arg(command)
+        .current_dir(worktree_path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(e) => {
+            tracing::error!("Failed to start log normalizer `{command}`: {e}");
+            return vec![];
+        }
+    };
+
+    let (Some(mut stdin), Some(stdout), Some(stderr)) =
+        (child.stdin.take(), child.stdout.take(), child.stderr.take())
+    else {
+        return vec![];
+    };
+
+    // Taken before returning, so the store can't be finished before the
+    // reader has flushed what the command prints at the end of its input.
+    let finish_hold = msg_store.hold_finish();
+
+    let writer = {
+        let mut raw_logs = msg_store.history_plus_stream();
+        let store = msg_store.clone();
+        tokio::spawn(async move {
+            loop {
+                // Raw output pushed before the input was closed is already
+                // queued, so it is written before the close wins.
+                let msg = tokio::select! {
+                    biased;
+                    msg = raw_logs.next() => match msg {
+                        Some(Ok(msg)) => msg,
+                        _ => break,
+                    },
+                    _ = store.input_closed() => break,
+                };
+                let line = match &msg {
+                    LogMsg::Stdout(content) => RawLogLine::Stdout(content),
+                    LogMsg::Stderr(content) => RawLogLine::Stderr(content),
+                    LogMsg::Finished => break,
+                    _ => continue,
+                };
+                let Ok(mut json) = serde_json::to_vec(&line) else {
+                    continue;
+                };
+                json.push(b'\n');
+                if stdin.write_all(&json).await.is_err() {
+                    break;
+                }
+            }
+            // Dropping stdin closes it, telling the normalizer to finish.
+        })
+    };
+
+    let command = command.to_string();
+    let stderr_command = command.clone();
+    let stderr_reader = tokio::spawn(async move {
+        let mut lines = BufReader::new(stderr).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            tracing::debug!("Log normalizer `{stderr_command}`: {line}");
+        }
+    });
+
+    let reader = tokio::spawn(async move {
+        let index_provider = EntryIndexProvider::start_from(&msg_store);
+        let mut indices = Vec::new();
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<NormalizerLine>(&line) {
+                Ok(NormalizerLine::Entry { index: None, entry }) => {
+                    indices.push(add_normalized_entry(&msg_store, &index_provider, entry));
+                }
+                Ok(NormalizerLine::Entry {
+                    index: Some(local),
+                    entry,
+                }) => match indices.get(local) {
+                    Some(&index) => replace_normalized_entry(&msg_store, index, entry),
+                    None => tracing::warn!(
+                        "Log normalizer `{command}` replaced unknown entry {local}, ignoring"
+                    ),
+                },
+                Ok(NormalizerLine::SessionId { session_id }) => {
+                    msg_store.push_session_id(session_id);
+                }
+                Err(e) => {
+                    tracing::warn!("Log normalizer `{command}` printed an invalid line: {e}")
+                }
+            }
+        }
+        drop(finish_hold);
+
+        match child.wait().await {
+            Ok(status) if !status.success() => {
+                tracing::warn!("Log normalizer `{command}` exited with {status}")
+            }
+            Err(e) => tracing::warn!("Failed to wait for log normalizer `{command}`: {e}"),
+            Ok(_) => {}
+        }
+    });
+
+    vec![writer, stderr_reader, reader]
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch};
+
+    /// Prints its entries only once stdin is closed.
+    fn fixture_command() -> String {
+        format!(
+            "sh {}/src/logs/testdata/buffering_normalizer.sh",
+            env!("CARGO_MANIFEST_DIR")
+        )
+    }
+
+    /// Entry contents by index, and whether any patch was pushed after
+    /// `Finished`.
+    fn entries(store: &MsgStore) -> (Vec<String>, bool) {
+        let mut contents = std::collections::BTreeMap::new();
+        let mut finished = false;
+        let mut after_finished = false;
+        for msg in store.get_history() {
+            match msg {
+                LogMsg::Finished => finished = true,
+                LogMsg::JsonPatch(patch) => {
+                    after_finished |= finished;
+                    if let Some((index, entry)) = extract_normalized_entry_from_patch(&patch) {
+                        assert!(matches!(
+                            entry.entry_type,
+                            NormalizedEntryType::AssistantMessage
+                        ));
+                        contents.insert(index, entry.content);
+                    }
+                }
+                _ => {}
+            }
+        }
+        (contents.into_values().collect(), after_finished)
+    }
+
+    #[tokio::test]
+    async fn entries_are_flushed_before_finished() {
+        let store = Arc::new(MsgStore::new());
+        store.push_stdout("one");
+        let handles = normalize_with_command(&fixture_command(), store.clone(), Path::new("."));
+        store.push(LogMsg::Stderr("two".to_string()));
+
+        store.finish(Duration::from_secs(30)).await;
+
+        let (contents, after_finished) = entries(&store);
+        assert_eq!(contents, ["stdout: one (2 lines)", "stderr: two"]);
+        assert!(!after_finished);
+        for handle in handles {
+            handle.await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn normalizes_an_already_finished_store() {
+        let store = Arc::new(MsgStore::new());
+        store.push_stdout("one");
+        store.push_finished();
+
+        for handle in normalize_with_command(&fixture_command(), store.clone(), Path::new(".")) {
+            handle.await.unwrap();
+        }
+
+        let (contents, _) = entries(&store);
+        assert_eq!(contents, ["stdout: one (1 lines)"]);
+    }
+
+    #[tokio::test]
+    async fn reported_session_id_is_pushed() {
+        let store = Arc::new(MsgStore::new());
+        store.push_stdout("one");
+        store.push_finished();
+
+        let command = r#"cat > /dev/null; echo '{"session_id": "abc-123"}'"#;
+        for handle in normalize_with_command(command, store.clone(), Path::new(".")) {
+            handle.await.unwrap();
+        }
+
+        let session_ids: Vec<_> = store
+            .get_history()
+            .into_iter()
+            .filter_map(|msg| match msg {
+                LogMsg::SessionId(id) => Some(id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(session_ids, ["abc-123"]);
+    }
+}
@@ -4,10 +4,15 @@ use workspace_utils::approvals::{ApprovalStatus, QuestionStatus};
 
 use crate::logs::utils::shell_command_parsing::CommandCategory;
 
+pub mod external_normalizer;
 pub mod plain_text_processor;
 pub mod stderr_processor;
 pub mod utils;
 
+/// Bump whenever a normalizer's output changes, so cached replays of
+/// historical processes are rebuilt instead of served stale.
+pub const NORMALIZER_VERSION: u32 = 4;
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ToolResultValueType {
@@ -96,6 +101,12 @@ pub enum NormalizedEntryType {
     UserAnsweredQuestions {
         answers: Vec<AnsweredQuestion>,
     },
+    // The agent is waiting on (or was waiting on) a tool approval.
+    ApprovalRequest {
+        tool_name: String,
+        approval_id: String,
+        status: ApprovalStatus,
+    },
 }
 
 /// A question–answer pair from a completed AskUserQuestion interaction.
@@ -109,6 +120,27 @@ pub struct AnsweredQuestion {
 pub struct TokenUsageInfo {
     pub total_tokens: u32,
     pub model_context_window: u32,
+    /// Tokens billed over the whole run, set on the entry emitted when it ends.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub session_totals: Option<TokenUsageTotals>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct TokenUsageTotals {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub cache_read_input_tokens: u64,
+    pub cache_creation_input_tokens: u64,
+}
+
+impl std::ops::AddAssign for TokenUsageTotals {
+    fn add_assign(&mut self, other: Self) {
+        self.input_tokens += other.input_tokens;
+        self.output_tokens += other.output_tokens;
+        self.cache_read_input_tokens += other.cache_read_input_tokens;
+        self.cache_creation_input_tokens += other.cache_creation_input_tokens;
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -120,6 +152,41 @@ pub struct NormalizedEntry {
     pub metadata: Option<serde_json::Value>,
 }
 
+/// The process output an entry was normalized from, recorded under
+/// `metadata.stream` so a viewer can show just one of them. Entries that no
+/// normalizer tagged, such as the user's own messages, have no stream.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum LogStream {
+    Stdout,
+    Stderr,
+}
+
+impl NormalizedEntry {
+    /// Record the source stream in the entry's metadata, keeping any other
+    /// metadata the normalizer attached.
+    pub fn with_stream(mut self, stream: LogStream) -> Self {
+        let tag = serde_json::to_value(stream).unwrap_or_default();
+        match &mut self.metadata {
+            Some(serde_json::Value::Object(map)) => {
+                map.insert("stream".to_string(), tag);
+            }
+            metadata @ (None | Some(serde_json::Value::Null)) => {
+                *metadata = Some(serde_json::json!({ "stream": tag }));
+            }
+            // Non-object metadata has nowhere to put the tag.
+            Some(_) => {}
+        }
+        self
+    }
+
+    /// The tagged source stream, if any.
+    pub fn stream(&self) -> Option<LogStream> {
+        let tag = self.metadata.as_ref()?.get("stream")?;
+        serde_json::from_value(tag.clone()).ok()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, Default)]
 #[serde(tag = "status", rename_all = "snake_case")]
 pub enum ToolStatus {
@@ -14,7 +14,7 @@ use futures::StreamExt;
 use workspace_utils::msg_store::MsgStore;
 
 use super::{
-    NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
+    LogStream, NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
     plain_text_processor::PlainTextLogProcessor,
 };
 use crate::logs::utils::EntryIndexProvider;
@@ -45,13 +45,16 @@ pub fn normalize_stderr_logs(
 
         // Create a processor with time-based emission for stderr
         let mut processor = PlainTextLogProcessor::builder()
-            .normalized_entry_producer(Box::new(|content: String| NormalizedEntry {
-                timestamp: None,
-                entry_type: NormalizedEntryType::ErrorMessage {
-                    error_type: NormalizedEntryError::Other,
-                },
-                content: strip_ansi_escapes::strip_str(&content),
-                metadata: None,
+            .normalized_entry_producer(Box::new(|content: String| {
+                NormalizedEntry {
+                    timestamp: None,
+                    entry_type: NormalizedEntryType::ErrorMessage {
+                        error_type: NormalizedEntryError::Other,
+                    },
+                    content: strip_ansi_escapes::strip_str(&content),
+                    metadata: None,
+                }
+                .with_stream(LogStream::Stderr)
             }))
             .time_gap(Duration::from_secs(2)) // Break messages if they are 2 seconds apart
             .index_provider(entry_index_provider)
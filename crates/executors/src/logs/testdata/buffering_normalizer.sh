@@ -0,0 +1,30 @@
+#!/bin/sh
+# Test fixture for the external log normalizer. Buffers every raw log line and
+# only prints once stdin is closed: one assistant message per line, an invalid
+# line that must be skipped, then a replacement of the first entry with the
+# line count.
+entries=""
+first=""
+count=0
+while IFS= read -r line; do
+  type=$(printf '%s' "$line" | sed -n 's/.*"type":"\([a-z]*\)".*/\1/p')
+  content=$(printf '%s' "$line" | sed -n 's/.*"content":"\([^"]*\)".*/\1/p')
+  entries="$entries$type: $content
+"
+  if [ "$count" -eq 0 ]; then
+    first="$type: $content"
+  fi
+  count=$((count + 1))
+done
+
+entry() {
+  printf '{"timestamp": null, "entry_type": {"type": "assistant_message"}, "content": "%s"}' "$1"
+}
+
+printf '%s' "$entries" | while IFS= read -r text; do
+  printf '{"entry": %s}\n' "$(entry "$text")"
+done
+echo "not json"
+if [ "$count" -gt 0 ]; then
+  printf '{"index": 0, "entry": %s}\n' "$(entry "$first ($count lines)")"
+fi
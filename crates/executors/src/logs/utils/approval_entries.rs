@@ -0,0 +1,121 @@
+//! Timeline entries that show an agent paused on a tool approval.
+//!
+//! Executors using the approvals service add an entry when a request is made
+//! and replace it in place once the user responds, so the conversation shows
+//! the wait rather than a gap.
+
+use std::collections::HashMap;
+
+use json_patch::Patch;
+use workspace_utils::approvals::ApprovalStatus;
+
+use crate::logs::{
+    NormalizedEntry, NormalizedEntryType,
+    utils::{ConversationPatch, EntryIndexProvider},
+};
+
+struct ApprovalEntry {
+    index: usize,
+    tool_name: String,
+    approval_id: String,
+}
+
+/// Approval entries for one execution, keyed by the executor's tool call id.
+#[derive(Default)]
+pub struct ApprovalEntries {
+    entries: HashMap<String, ApprovalEntry>,
+}
+
+impl ApprovalEntries {
+    /// Add a pending entry for a newly requested approval.
+    pub fn requested(
+        &mut self,
+        tool_call_id: &str,
+        tool_name: &str,
+        approval_id: &str,
+        entry_index_provider: &EntryIndexProvider,
+    ) -> Patch {
+        let index = entry_index_provider.next();
+        self.entries.insert(
+            tool_call_id.to_string(),
+            ApprovalEntry {
+                index,
+                tool_name: tool_name.to_string(),
+                approval_id: approval_id.to_string(),
+            },
+        );
+        ConversationPatch::add_normalized_entry(
+            index,
+            approval_entry(tool_name, approval_id, ApprovalStatus::Pending),
+        )
+    }
+
+    /// Replace the pending entry with the outcome. Returns `None` if no
+    /// request was recorded for `tool_call_id`.
+    pub fn resolved(&mut self, tool_call_id: &str, status: &ApprovalStatus) -> Option<Patch> {
+        let entry = self.entries.remove(tool_call_id)?;
+        Some(ConversationPatch::replace(
+            entry.index,
+            approval_entry(&entry.tool_name, &entry.approval_id, status.clone()),
+        ))
+    }
+}
+
+pub fn approval_entry(
+    tool_name: &str,
+    approval_id: &str,
+    status: ApprovalStatus,
+) -> NormalizedEntry {
+    let content = match &status {
+        ApprovalStatus::Pending => format!("Waiting for approval to use {tool_name}"),
+        ApprovalStatus::Approved => format!("Approved {tool_name}"),
+        ApprovalStatus::Denied { .. } => format!("Denied {tool_name}"),
+        ApprovalStatus::TimedOut => format!("Approval for {tool_name} timed out"),
+    };
+    NormalizedEntry {
+        timestamp: None,
+        entry_type: NormalizedEntryType::ApprovalRequest {
+            tool_name: tool_name.to_string(),
+            approval_id: approval_id.to_string(),
+            status,
+        },
+        content,
+        metadata: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution_replaces_pending_entry() {
+        let provider = EntryIndexProvider::test_new();
+        let mut entries = ApprovalEntries::default();
+
+        let added = entries.requested("call-1", "Bash", "approval-1", &provider);
+        let added = serde_json::to_value(&added).unwrap();
+        assert_eq!(added[0]["op"], "add");
+        assert_eq!(
+            added[0]["value"]["content"]["entry_type"]["status"]["status"],
+            "pending"
+        );
+
+        let resolved = entries
+            .resolved("call-1", &ApprovalStatus::Approved)
+            .unwrap();
+        let resolved = serde_json::to_value(&resolved).unwrap();
+        assert_eq!(resolved[0]["op"], "replace");
+        assert_eq!(resolved[0]["path"], added[0]["path"]);
+        assert_eq!(
+            resolved[0]["value"]["content"]["entry_type"]["status"]["status"],
+            "approved"
+        );
+
+        assert!(
+            entries
+                .resolved("call-1", &ApprovalStatus::Approved)
+                .is_none()
+        );
+    }
+}
@@ -28,6 +28,15 @@ impl EntryIndexProvider {
         self.0.load(Ordering::Relaxed)
     }
 
+    /// Hand back `index` if it is the most recently issued one, so the next
+    /// call to [`Self::next`] returns it again. Returns `false` if another
+    /// index has been issued since.
+    pub fn release(&self, index: usize) -> bool {
+        self.0
+            .compare_exchange(index + 1, index, Ordering::Relaxed, Ordering::Relaxed)
+            .is_ok()
+    }
+
     pub fn reset(&self) {
         self.0.store(0, Ordering::Relaxed);
     }
@@ -110,4 +119,15 @@ mod tests {
         provider.next();
         assert_eq!(provider.current(), 2);
     }
+
+    #[test]
+    fn test_release_only_latest_index() {
+        let provider = EntryIndexProvider::test_new();
+        let first = provider.next();
+        let second = provider.next();
+
+        assert!(!provider.release(first));
+        assert!(provider.release(second));
+        assert_eq!(provider.next(), second);
+    }
 }
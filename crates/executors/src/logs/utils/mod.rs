@@ -1,8 +1,10 @@
 //! Utility modules for executor framework
 
+pub mod approval_entries;
 pub mod entry_index;
 pub mod patch;
 
+pub use approval_entries::ApprovalEntries;
 pub use entry_index::EntryIndexProvider;
 pub use patch::ConversationPatch;
 pub mod shell_command_parsing;
@@ -1,13 +1,13 @@
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet, VecDeque},
     sync::Arc,
 };
 
 use json_patch::Patch;
 use serde::{Deserialize, Serialize};
-use serde_json::{from_value, json, to_value};
+use serde_json::{Value, from_value, json, to_value};
 use ts_rs::TS;
-use workspace_utils::{diff::Diff, msg_store::MsgStore};
+use workspace_utils::{diff::Diff, log_msg::LogMsg, msg_store::MsgStore};
 
 use crate::{
     executor_discovery::ExecutorDiscoveredOptions,
@@ -168,6 +168,18 @@ pub fn extract_normalized_entry_from_patch(patch: &Patch) -> Option<(usize, Norm
     })
 }
 
+/// The latest version of each normalized entry in a log history, by index.
+/// Later patches replace earlier versions of the same entry.
+pub fn latest_entries(messages: &[LogMsg]) -> BTreeMap<usize, NormalizedEntry> {
+    messages
+        .iter()
+        .filter_map(|msg| match msg {
+            LogMsg::JsonPatch(patch) => extract_normalized_entry_from_patch(patch),
+            _ => None,
+        })
+        .collect()
+}
+
 pub fn upsert_normalized_entry(
     msg_store: &Arc<MsgStore>,
     index: usize,
@@ -238,6 +250,122 @@ pub fn fix_patch_ops(mut patch: Patch, sent_paths: &mut HashSet<String>) -> Patc
     patch
 }
 
+/// Split an `/entries/{index}...` path into the entry index and the rest.
+fn split_entry_path(path: &str) -> Option<(usize, &str)> {
+    let rest = path.strip_prefix("/entries/")?;
+    let (index, suffix) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+    Some((index.parse().ok()?, suffix))
+}
+
+/// Limits a normalized-log replay to its last entries. Operations on earlier
+/// entries are dropped and the remaining indices are shifted down, so the
+/// client still sees a contiguous list starting at 0.
+#[derive(Debug, Clone, Copy)]
+pub struct EntriesTail {
+    offset: usize,
+}
+
+impl EntriesTail {
+    /// Keep the last `tail` of the entries created by `history`.
+    pub fn new(history: &[Patch], tail: usize) -> Self {
+        let count = history
+            .iter()
+            .flat_map(|patch| patch.0.iter())
+            .filter_map(|op| split_entry_path(&op.path().to_string()).map(|(index, _)| index + 1))
+            .max()
+            .unwrap_or(0);
+        Self {
+            offset: count.saturating_sub(tail),
+        }
+    }
+
+    /// Re-index `patch` for the tail, or `None` if it only touches entries
+    /// before it.
+    pub fn apply(&self, patch: Patch) -> Option<Patch> {
+        if self.offset == 0 {
+            return Some(patch);
+        }
+        let Ok(Value::Array(mut ops)) = to_value(&patch) else {
+            return Some(patch);
+        };
+        ops.retain_mut(|op| {
+            let shifted = match op
+                .get("path")
+                .and_then(Value::as_str)
+                .and_then(split_entry_path)
+            {
+                None => return true,
+                Some((index, _)) if index < self.offset => return false,
+                Some((index, suffix)) => format!("/entries/{}{suffix}", index - self.offset),
+            };
+            op["path"] = Value::String(shifted);
+            true
+        });
+        if ops.is_empty() {
+            return None;
+        }
+        from_value(Value::Array(ops)).ok()
+    }
+}
+
+/// Collects a replay's patches while keeping only those that still matter for
+/// its last `tail` entries, so a long finished log is never held in full.
+#[derive(Debug)]
+pub struct TailBuffer {
+    tail: usize,
+    count: usize,
+    patches: VecDeque<Patch>,
+}
+
+impl TailBuffer {
+    pub fn new(tail: usize) -> Self {
+        Self {
+            tail,
+            count: 0,
+            patches: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, patch: Patch) {
+        if let Some(last) = entry_indices(&patch).flatten().max() {
+            self.count = self.count.max(last + 1);
+        }
+        let offset = self.count.saturating_sub(self.tail);
+        if !only_before(&patch, offset) {
+            self.patches.push_back(patch);
+        }
+        while self
+            .patches
+            .front()
+            .is_some_and(|patch| only_before(patch, offset))
+        {
+            self.patches.pop_front();
+        }
+    }
+
+    /// The kept patches, with the tail that re-indexes them and any live
+    /// patches that follow.
+    pub fn finish(self) -> (EntriesTail, Vec<Patch>) {
+        let tail = EntriesTail {
+            offset: self.count.saturating_sub(self.tail),
+        };
+        (tail, self.patches.into())
+    }
+}
+
+/// Entry index of each operation, or `None` for operations outside `/entries`.
+fn entry_indices(patch: &Patch) -> impl Iterator<Item = Option<usize>> + '_ {
+    patch
+        .0
+        .iter()
+        .map(|op| split_entry_path(&op.path().to_string()).map(|(index, _)| index))
+}
+
+/// Whether every operation of `patch` touches an entry before `offset`.
+fn only_before(patch: &Patch, offset: usize) -> bool {
+    entry_indices(patch).all(|index| index.is_some_and(|index| index < offset))
+}
+
 pub fn executor_discovered_options(options: ExecutorDiscoveredOptions) -> Patch {
     serde_json::from_value(json!([
         {"op": "replace", "path": "/options", "value": options},
@@ -325,3 +453,91 @@ pub fn discovery_error(error: String) -> Patch {
     ]))
     .unwrap_or_default()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::logs::LogStream;
+
+    fn stdout(index: usize) -> Patch {
+        ConversationPatch::add_stdout(index, format!("line {index}"))
+    }
+
+    fn paths(patch: &Patch) -> Vec<String> {
+        patch.0.iter().map(|op| op.path().to_string()).collect()
+    }
+
+    #[test]
+    fn entries_tail_drops_and_reindexes() {
+        let history: Vec<Patch> = (0..5).map(stdout).collect();
+        let tail = EntriesTail::new(&history, 2);
+
+        assert!(tail.apply(stdout(2)).is_none());
+        assert_eq!(paths(&tail.apply(stdout(3)).unwrap()), ["/entries/0"]);
+        assert_eq!(paths(&tail.apply(stdout(7)).unwrap()), ["/entries/4"]);
+        assert_eq!(
+            paths(&tail.apply(models_loaded()).unwrap()),
+            ["/options/loading_models"]
+        );
+    }
+
+    #[test]
+    fn tail_buffer_only_keeps_patches_for_the_tail() {
+        let mut buffer = TailBuffer::new(2);
+        for index in 0..1000 {
+            buffer.push(stdout(index));
+            assert!(buffer.patches.len() <= 2);
+        }
+        // A late update to an early entry is not kept either.
+        buffer.push(ConversationPatch::remove(3));
+
+        let (tail, patches) = buffer.finish();
+        let replayed: Vec<_> = patches
+            .into_iter()
+            .filter_map(|patch| tail.apply(patch))
+            .map(|patch| paths(&patch))
+            .collect();
+        assert_eq!(replayed, [["/entries/0"], ["/entries/1"]]);
+    }
+
+    #[test]
+    fn latest_entries_keep_the_last_version_of_each_entry() {
+        let entry = |content: &str| NormalizedEntry {
+            timestamp: None,
+            entry_type: crate::logs::NormalizedEntryType::SystemMessage,
+            content: content.into(),
+            metadata: None,
+        };
+        let messages = [
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(0, entry("first"))),
+            LogMsg::JsonPatch(stdout(1)),
+            LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(2, entry("draft"))),
+            LogMsg::JsonPatch(ConversationPatch::replace(2, entry("final"))),
+            LogMsg::Finished,
+        ];
+
+        let latest = latest_entries(&messages);
+        assert_eq!(latest.keys().copied().collect::<Vec<_>>(), [0, 2]);
+        assert_eq!(latest[&2].content, "final");
+    }
+
+    #[test]
+    fn patches_keep_the_stream_tag_as_given() {
+        let entry = |metadata| NormalizedEntry {
+            timestamp: None,
+            entry_type: crate::logs::NormalizedEntryType::SystemMessage,
+            content: "hello".into(),
+            metadata,
+        };
+
+        let patch = ConversationPatch::add_normalized_entry(0, entry(None));
+        let (_, untagged) = extract_normalized_entry_from_patch(&patch).unwrap();
+        assert_eq!(untagged.stream(), None);
+
+        let stderr = entry(Some(json!({ "raw": 1 }))).with_stream(LogStream::Stderr);
+        let patch = ConversationPatch::replace(0, stderr);
+        let (_, tagged) = extract_normalized_entry_from_patch(&patch).unwrap();
+        assert_eq!(tagged.stream(), Some(LogStream::Stderr));
+        assert_eq!(tagged.metadata.unwrap()["raw"], 1);
+    }
+}
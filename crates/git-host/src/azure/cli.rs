@@ -7,15 +7,21 @@ use std::{
     ffi::{OsStr, OsString},
     path::Path,
     process::Command,
+    time::Duration,
 };
 
 use chrono::{DateTime, Utc};
 use db::models::merge::MergeStatus;
 use serde::Deserialize;
+use tempfile::NamedTempFile;
 use thiserror::Error;
-use utils::{command_ext::NoWindowExt, shell::resolve_executable_path_blocking};
+use tokio_util::sync::CancellationToken;
+use utils::shell::resolve_executable_path_blocking;
 
-use crate::types::{CreatePrRequest, PullRequestDetail, UnifiedPrComment};
+use crate::{
+    command::{BoundedRunError, CliLimits, run_bounded},
+    types::{CreatePrRequest, PullRequestDetail, UnifiedPrComment},
+};
 
 #[derive(Debug, Clone)]
 pub struct AzureRepoInfo {
@@ -26,6 +32,15 @@ pub struct AzureRepoInfo {
     pub repo_id: String,
 }
 
+/// A pull request as addressed by its web URL.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AzurePrRef {
+    pub organization_url: String,
+    pub project: String,
+    pub repo_name: String,
+    pub pr_id: i64,
+}
+
 #[derive(Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct AzPrResponse {
@@ -121,14 +136,28 @@ pub enum AzCliError {
     AuthFailed(String),
     #[error("Azure CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("Azure CLI command timed out after {0:?}")]
+    TimedOut(Duration),
+    #[error("Azure CLI output exceeded {0} bytes")]
+    OutputTooLarge(usize),
+    #[error("Azure CLI command was cancelled")]
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct AzCli;
+pub struct AzCli {
+    limits: CliLimits,
+    cancel: CancellationToken,
+}
 
 impl AzCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Kill running commands and refuse new ones once `cancel` fires.
+    pub fn with_cancellation(self, cancel: CancellationToken) -> Self {
+        Self { cancel, ..self }
     }
 
     /// Ensure the Azure CLI binary is discoverable.
@@ -155,10 +184,12 @@ impl AzCli {
         }
         tracing::debug!("Running Azure CLI command: {:?} {:?}", az, cmd.get_args());
 
-        let output = cmd
-            .no_window()
-            .output()
-            .map_err(|err| AzCliError::CommandFailed(err.to_string()))?;
+        let output = run_bounded(&mut cmd, self.limits, &self.cancel).map_err(|err| match err {
+            BoundedRunError::Spawn(err) => AzCliError::CommandFailed(err.to_string()),
+            BoundedRunError::TimedOut(timeout) => AzCliError::TimedOut(timeout),
+            BoundedRunError::OutputTooLarge(limit) => AzCliError::OutputTooLarge(limit),
+            BoundedRunError::Cancelled => AzCliError::Cancelled,
+        })?;
 
         if output.status.success() {
             return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -180,6 +211,16 @@ impl AzCli {
 
         Err(AzCliError::CommandFailed(stderr))
     }
+
+    /// Whether `az` has a signed-in account.
+    pub fn is_logged_in(&self) -> Result<bool, AzCliError> {
+        match self.run(["account", "show", "--output", "none"], None) {
+            Ok(_) => Ok(true),
+            Err(AzCliError::AuthFailed(_) | AzCliError::CommandFailed(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn get_repo_info(
         &self,
         repo_path: &Path,
@@ -340,6 +381,112 @@ impl AzCli {
         Self::parse_pr_response(&raw)
     }
 
+    /// Add an optional reviewer (user email, display name or team) to a PR.
+    pub fn add_pr_reviewer(&self, pr_url: &str, reviewer: &str) -> Result<(), AzCliError> {
+        let pr = Self::require_pr_ref(pr_url)?;
+        self.run(Self::pr_reviewer_args(&pr, reviewer), None)?;
+        Ok(())
+    }
+
+    /// Add a label (shown as a tag in the Azure DevOps UI) to a PR.
+    pub fn add_pr_label(&self, pr_url: &str, label: &str) -> Result<(), AzCliError> {
+        let pr = Self::require_pr_ref(pr_url)?;
+        let body_file = Self::json_body(&serde_json::json!({ "name": label }))?;
+        self.run(
+            Self::pr_invoke_args(&pr, "pullRequestLabels", "POST", body_file.path()),
+            None,
+        )?;
+        Ok(())
+    }
+
+    /// Change a PR's target branch and return its updated details.
+    ///
+    /// `az repos pr update` can't retarget a PR, so this PATCHes the PR
+    /// resource directly.
+    pub fn edit_pr_base(
+        &self,
+        pr_url: &str,
+        new_base: &str,
+    ) -> Result<PullRequestDetail, AzCliError> {
+        let pr = Self::require_pr_ref(pr_url)?;
+        let body_file = Self::json_body(
+            &serde_json::json!({ "targetRefName": format!("refs/heads/{new_base}") }),
+        )?;
+        self.run(
+            Self::pr_invoke_args(&pr, "pullRequests", "PATCH", body_file.path()),
+            None,
+        )?;
+        self.view_pr(pr_url)
+    }
+
+    /// Write a request body to a temp file for `az devops invoke --in-file`.
+    fn json_body(body: &serde_json::Value) -> Result<NamedTempFile, AzCliError> {
+        let mut body_file = NamedTempFile::new()
+            .map_err(|e| AzCliError::CommandFailed(format!("Failed to create temp file: {e}")))?;
+        serde_json::to_writer(&mut body_file, body)
+            .map_err(|e| AzCliError::CommandFailed(format!("Failed to write request body: {e}")))?;
+        Ok(body_file)
+    }
+
+    fn require_pr_ref(pr_url: &str) -> Result<AzurePrRef, AzCliError> {
+        Self::parse_pr_ref(pr_url).ok_or_else(|| {
+            AzCliError::UnexpectedOutput(format!("Could not parse Azure DevOps PR URL: {pr_url}"))
+        })
+    }
+
+    fn pr_reviewer_args(pr: &AzurePrRef, reviewer: &str) -> Vec<OsString> {
+        let pr_id = pr.pr_id.to_string();
+        [
+            "repos",
+            "pr",
+            "reviewer",
+            "add",
+            "--id",
+            &pr_id,
+            "--reviewers",
+            reviewer,
+            "--organization",
+            &pr.organization_url,
+            "--output",
+            "json",
+        ]
+        .into_iter()
+        .map(OsString::from)
+        .collect()
+    }
+
+    /// `az devops invoke` arguments that send the JSON in `body_path` to one
+    /// of the PR's REST resources.
+    fn pr_invoke_args(
+        pr: &AzurePrRef,
+        resource: &str,
+        http_method: &str,
+        body_path: &Path,
+    ) -> Vec<OsString> {
+        let mut args: Vec<OsString> = Vec::with_capacity(20);
+        args.push(OsString::from("devops"));
+        args.push(OsString::from("invoke"));
+        args.push(OsString::from("--area"));
+        args.push(OsString::from("git"));
+        args.push(OsString::from("--resource"));
+        args.push(OsString::from(resource));
+        args.push(OsString::from("--route-parameters"));
+        args.push(OsString::from(format!("project={}", pr.project)));
+        args.push(OsString::from(format!("repositoryId={}", pr.repo_name)));
+        args.push(OsString::from(format!("pullRequestId={}", pr.pr_id)));
+        args.push(OsString::from("--http-method"));
+        args.push(OsString::from(http_method));
+        args.push(OsString::from("--in-file"));
+        args.push(body_path.as_os_str().to_os_string());
+        args.push(OsString::from("--organization"));
+        args.push(OsString::from(&pr.organization_url));
+        args.push(OsString::from("--api-version"));
+        args.push(OsString::from("7.0"));
+        args.push(OsString::from("--output"));
+        args.push(OsString::from("json"));
+        args
+    }
+
     pub fn list_prs_for_branch(
         &self,
         organization_url: &str,
@@ -438,6 +585,34 @@ impl AzCli {
 
         None
     }
+
+    /// Parse a PR URL into the organization, project and repository it
+    /// belongs to. `{org}/_git/{repo}` URLs omit the project because it has
+    /// the repository's name.
+    pub fn parse_pr_ref(url: &str) -> Option<AzurePrRef> {
+        let (organization, pr_id) = Self::parse_pr_url(url)?;
+        let parts: Vec<&str> = url.split('/').collect();
+        let host_idx = parts.iter().position(|p| {
+            let p = p.to_lowercase();
+            p.contains("dev.azure.com") || p.ends_with(".visualstudio.com")
+        })?;
+        // Segments between the host and `_git` that aren't the project
+        let org_segments = usize::from(parts[host_idx].to_lowercase().contains("dev.azure.com"));
+        let git_idx = parts.iter().position(|&p| p == "_git")?;
+        let repo_name = parts.get(git_idx + 1).copied().filter(|r| !r.is_empty())?;
+        let project = if git_idx > host_idx + org_segments + 1 {
+            parts[git_idx - 1]
+        } else {
+            repo_name
+        };
+
+        Some(AzurePrRef {
+            organization_url: format!("https://dev.azure.com/{organization}"),
+            project: urlencoding::decode(project).ok()?.into_owned(),
+            repo_name: urlencoding::decode(repo_name).ok()?.into_owned(),
+            pr_id,
+        })
+    }
 }
 
 impl AzCli {
@@ -477,6 +652,7 @@ impl AzCli {
             status: Self::map_azure_status(status),
             merged_at,
             merge_commit_sha,
+            checks_status: None,
             title: pr.title.unwrap_or_default(),
             base_branch: pr
                 .target_ref_name
@@ -604,6 +780,117 @@ mod tests {
         assert!(AzCli::parse_pr_url("https://dev.azure.com/myorg/myproject/_git/myrepo").is_none());
     }
 
+    #[test]
+    fn test_parse_pr_ref() {
+        let pr = AzCli::parse_pr_ref(
+            "https://dev.azure.com/myorg/My%20Project/_git/my-repo/pullrequest/123",
+        )
+        .unwrap();
+        assert_eq!(
+            pr,
+            AzurePrRef {
+                organization_url: "https://dev.azure.com/myorg".into(),
+                project: "My Project".into(),
+                repo_name: "my-repo".into(),
+                pr_id: 123,
+            }
+        );
+
+        let legacy = AzCli::parse_pr_ref(
+            "https://myorg.visualstudio.com/myproject/_git/myrepo/pullrequest/456",
+        )
+        .unwrap();
+        assert_eq!(legacy.organization_url, "https://dev.azure.com/myorg");
+        assert_eq!(
+            (legacy.project.as_str(), legacy.repo_name.as_str()),
+            ("myproject", "myrepo")
+        );
+    }
+
+    #[test]
+    fn test_parse_pr_ref_without_project() {
+        for url in [
+            "https://dev.azure.com/myorg/_git/myrepo/pullrequest/7",
+            "https://myorg.visualstudio.com/_git/myrepo/pullrequest/7",
+        ] {
+            let pr = AzCli::parse_pr_ref(url).unwrap();
+            assert_eq!(
+                (pr.project.as_str(), pr.repo_name.as_str()),
+                ("myrepo", "myrepo")
+            );
+        }
+        assert!(AzCli::parse_pr_ref("https://github.com/owner/repo/pull/123").is_none());
+    }
+
+    #[test]
+    fn test_pr_invoke_and_reviewer_args() {
+        let pr = AzurePrRef {
+            organization_url: "https://dev.azure.com/myorg".into(),
+            project: "My Project".into(),
+            repo_name: "myrepo".into(),
+            pr_id: 42,
+        };
+
+        let args = AzCli::pr_invoke_args(
+            &pr,
+            "pullRequestLabels",
+            "POST",
+            Path::new("/tmp/label.json"),
+        );
+        let args: Vec<_> = args.iter().map(|a| a.to_str().unwrap()).collect();
+        assert_eq!(
+            args,
+            [
+                "devops",
+                "invoke",
+                "--area",
+                "git",
+                "--resource",
+                "pullRequestLabels",
+                "--route-parameters",
+                "project=My Project",
+                "repositoryId=myrepo",
+                "pullRequestId=42",
+                "--http-method",
+                "POST",
+                "--in-file",
+                "/tmp/label.json",
+                "--organization",
+                "https://dev.azure.com/myorg",
+                "--api-version",
+                "7.0",
+                "--output",
+                "json",
+            ]
+        );
+
+        let args = AzCli::pr_invoke_args(&pr, "pullRequests", "PATCH", Path::new("/tmp/base.json"));
+        assert_eq!(
+            (args[5].to_str(), args[11].to_str()),
+            (Some("pullRequests"), Some("PATCH"))
+        );
+
+        let args = AzCli::pr_reviewer_args(&pr, "dev@example.com");
+        let args: Vec<_> = args.iter().map(|a| a.to_str().unwrap()).collect();
+        assert_eq!(
+            args,
+            [
+                "repos",
+                "pr",
+                "reviewer",
+                "add",
+                "--id",
+                "42",
+                "--reviewers",
+                "dev@example.com",
+                "--organization",
+                "https://dev.azure.com/myorg",
+                "--output",
+                "json",
+            ]
+        );
+    }
+
     #[test]
     fn test_map_azure_status() {
         assert!(matches!(
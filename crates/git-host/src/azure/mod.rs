@@ -9,25 +9,39 @@ use backon::{ExponentialBuilder, Retryable};
 pub use cli::AzCli;
 use cli::{AzCliError, AzureRepoInfo};
 use tokio::task;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
 use crate::{
     GitHostProvider,
-    types::{CreatePrRequest, GitHostError, ProviderKind, PullRequestDetail, UnifiedPrComment},
+    cancel::cancel_on,
+    types::{
+        CreatePrRequest, GitHostError, ProviderCapabilities, ProviderKind, PullRequestDetail,
+        UnifiedPrComment,
+    },
 };
 
 #[derive(Debug, Clone)]
 pub struct AzureDevOpsProvider {
     az_cli: AzCli,
+    cancel: CancellationToken,
 }
 
 impl AzureDevOpsProvider {
     pub fn new() -> Result<Self, GitHostError> {
         Ok(Self {
             az_cli: AzCli::new(),
+            cancel: CancellationToken::new(),
         })
     }
 
+    pub fn with_cancellation(self, cancel: CancellationToken) -> Self {
+        Self {
+            az_cli: self.az_cli.with_cancellation(cancel.clone()),
+            cancel,
+        }
+    }
+
     async fn get_repo_info(
         &self,
         repo_path: &Path,
@@ -52,7 +66,9 @@ impl From<AzCliError> for GitHostError {
             },
             AzCliError::CommandFailed(msg) => {
                 let lower = msg.to_ascii_lowercase();
-                if lower.contains("403") || lower.contains("forbidden") {
+                if let Some(rate_limited) = GitHostError::rate_limited(msg) {
+                    rate_limited
+                } else if lower.contains("403") || lower.contains("forbidden") {
                     GitHostError::InsufficientPermissions(msg.clone())
                 } else if lower.contains("404") || lower.contains("not found") {
                     GitHostError::RepoNotFoundOrNoAccess(msg.clone())
@@ -63,6 +79,9 @@ impl From<AzCliError> for GitHostError {
                 }
             }
             AzCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+            AzCliError::TimedOut(timeout) => GitHostError::TimedOut(*timeout),
+            AzCliError::OutputTooLarge(limit) => GitHostError::ResponseTooLarge(*limit),
+            AzCliError::Cancelled => GitHostError::Cancelled,
         }
     }
 }
@@ -85,78 +104,88 @@ impl GitHostProvider for AzureDevOpsProvider {
 
         let repo_info = self.get_repo_info(repo_path, remote_url).await?;
 
-        (|| async {
-            let cli = self.az_cli.clone();
-            let request_clone = request.clone();
-            let organization_url = repo_info.organization_url.clone();
-            let project = repo_info.project.clone();
-            let repo_name = repo_info.repo_name.clone();
+        let pr = cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = self.az_cli.clone();
+                let request_clone = request.clone();
+                let organization_url = repo_info.organization_url.clone();
+                let project = repo_info.project.clone();
+                let repo_name = repo_info.repo_name.clone();
 
-            let cli_result = task::spawn_blocking(move || {
-                cli.create_pr(&request_clone, &organization_url, &project, &repo_name)
-            })
-            .await
-            .map_err(|err| {
-                GitHostError::PullRequest(format!(
-                    "Failed to execute Azure CLI for PR creation: {err}"
-                ))
-            })?
-            .map_err(GitHostError::from)?;
+                let cli_result = task::spawn_blocking(move || {
+                    cli.create_pr(&request_clone, &organization_url, &project, &repo_name)
+                })
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for PR creation: {err}"
+                    ))
+                })?
+                .map_err(GitHostError::from)?;
 
-            info!(
-                "Created Azure DevOps PR #{} for branch {}",
-                cli_result.number, request.head_branch
-            );
+                info!(
+                    "Created Azure DevOps PR #{} for branch {}",
+                    cli_result.number, request.head_branch
+                );
 
-            Ok(cli_result)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+                Ok(cli_result)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "Azure DevOps API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
-        .await
+        .await?;
+
+        Ok(pr)
     }
 
     async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
-        (|| async {
-            let cli = self.az_cli.clone();
-            let url = pr_url.to_string();
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = self.az_cli.clone();
+                let url = pr_url.to_string();
 
-            let pr = task::spawn_blocking(move || cli.view_pr(&url))
-                .await
-                .map_err(|err| {
-                    GitHostError::PullRequest(format!(
-                        "Failed to execute Azure CLI for viewing PR: {err}"
-                    ))
-                })?;
-            pr.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+                let pr = task::spawn_blocking(move || cli.view_pr(&url))
+                    .await
+                    .map_err(|err| {
+                        GitHostError::PullRequest(format!(
+                            "Failed to execute Azure CLI for viewing PR: {err}"
+                        ))
+                    })?;
+                pr.map_err(GitHostError::from)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|err: &GitHostError| err.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|err: &GitHostError| err.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "Azure DevOps API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 
@@ -168,39 +197,43 @@ impl GitHostProvider for AzureDevOpsProvider {
     ) -> Result<Vec<PullRequestDetail>, GitHostError> {
         let repo_info = self.get_repo_info(repo_path, remote_url).await?;
 
-        (|| async {
-            let cli = self.az_cli.clone();
-            let organization_url = repo_info.organization_url.clone();
-            let project = repo_info.project.clone();
-            let repo_name = repo_info.repo_name.clone();
-            let branch = branch_name.to_string();
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = self.az_cli.clone();
+                let organization_url = repo_info.organization_url.clone();
+                let project = repo_info.project.clone();
+                let repo_name = repo_info.repo_name.clone();
+                let branch = branch_name.to_string();
 
-            let prs = task::spawn_blocking(move || {
-                cli.list_prs_for_branch(&organization_url, &project, &repo_name, &branch)
+                let prs = task::spawn_blocking(move || {
+                    cli.list_prs_for_branch(&organization_url, &project, &repo_name, &branch)
+                })
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for listing PRs: {err}"
+                    ))
+                })?;
+                prs.map_err(GitHostError::from)
             })
-            .await
-            .map_err(|err| {
-                GitHostError::PullRequest(format!(
-                    "Failed to execute Azure CLI for listing PRs: {err}"
-                ))
-            })?;
-            prs.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "Azure DevOps API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 
@@ -212,39 +245,120 @@ impl GitHostProvider for AzureDevOpsProvider {
     ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
         let repo_info = self.get_repo_info(repo_path, remote_url).await?;
 
-        (|| async {
-            let cli = self.az_cli.clone();
-            let organization_url = repo_info.organization_url.clone();
-            let project_id = repo_info.project_id.clone();
-            let repo_id = repo_info.repo_id.clone();
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = self.az_cli.clone();
+                let organization_url = repo_info.organization_url.clone();
+                let project_id = repo_info.project_id.clone();
+                let repo_id = repo_info.repo_id.clone();
 
-            let comments = task::spawn_blocking(move || {
-                cli.get_pr_threads(&organization_url, &project_id, &repo_id, pr_number)
+                let comments = task::spawn_blocking(move || {
+                    cli.get_pr_threads(&organization_url, &project_id, &repo_id, pr_number)
+                })
+                .await
+                .map_err(|err| {
+                    GitHostError::PullRequest(format!(
+                        "Failed to execute Azure CLI for fetching PR comments: {err}"
+                    ))
+                })?;
+                comments.map_err(GitHostError::from)
             })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "Azure DevOps API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
+        )
+        .await
+    }
+
+    async fn request_reviewers(
+        &self,
+        pr_url: &str,
+        reviewers: &[String],
+    ) -> Result<Vec<String>, GitHostError> {
+        let cli = self.az_cli.clone();
+        let url = pr_url.to_string();
+        let reviewers = reviewers.to_vec();
+
+        task::spawn_blocking(move || {
+            reviewers
+                .into_iter()
+                .filter(|reviewer| match cli.add_pr_reviewer(&url, reviewer) {
+                    Ok(()) => false,
+                    Err(err) => {
+                        tracing::warn!("Failed to add reviewer {reviewer} to {url}: {err}");
+                        true
+                    }
+                })
+                .collect()
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute Azure CLI for adding reviewers: {err}"
+            ))
+        })
+    }
+
+    async fn add_labels(
+        &self,
+        pr_url: &str,
+        labels: &[String],
+    ) -> Result<Vec<String>, GitHostError> {
+        let cli = self.az_cli.clone();
+        let url = pr_url.to_string();
+        let labels = labels.to_vec();
+
+        task::spawn_blocking(move || {
+            labels
+                .into_iter()
+                .filter(|label| match cli.add_pr_label(&url, label) {
+                    Ok(()) => false,
+                    Err(err) => {
+                        tracing::warn!("Failed to add label {label:?} to {url}: {err}");
+                        true
+                    }
+                })
+                .collect()
+        })
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute Azure CLI for adding labels: {err}"
+            ))
+        })
+    }
+
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        new_base: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        let cli = self.az_cli.clone();
+        let url = pr_url.to_string();
+        let base = new_base.to_string();
+
+        task::spawn_blocking(move || cli.edit_pr_base(&url, &base))
             .await
             .map_err(|err| {
                 GitHostError::PullRequest(format!(
-                    "Failed to execute Azure CLI for fetching PR comments: {err}"
+                    "Failed to execute Azure CLI for editing PR: {err}"
                 ))
-            })?;
-            comments.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
-        )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "Azure DevOps API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
-        .await
+            })?
+            .map_err(GitHostError::from)
     }
 
     async fn list_open_prs(
@@ -259,4 +373,15 @@ impl GitHostProvider for AzureDevOpsProvider {
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::AzureDevOps
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            draft_prs: true,
+            squash_merge: true,
+            review_threads: true,
+            cross_fork_prs: false,
+            list_open_prs: false,
+            edit_pr_base: true,
+        }
+    }
 }
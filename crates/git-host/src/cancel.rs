@@ -0,0 +1,24 @@
+//! Cancellation for provider calls made on behalf of a workspace.
+//!
+//! Stopping a workspace should not have to wait out a PR request that is
+//! stuck retrying. The CLI runner kills the current process when the token
+//! fires; [`cancel_on`] additionally cuts short any retry backoff.
+
+use std::future::Future;
+
+use tokio_util::sync::CancellationToken;
+
+use crate::types::GitHostError;
+
+/// Run `fut`, resolving to [`GitHostError::Cancelled`] as soon as `cancel`
+/// fires.
+pub(crate) async fn cancel_on<T>(
+    cancel: &CancellationToken,
+    fut: impl Future<Output = Result<T, GitHostError>>,
+) -> Result<T, GitHostError> {
+    tokio::select! {
+        biased;
+        _ = cancel.cancelled() => Err(GitHostError::Cancelled),
+        result = fut => result,
+    }
+}
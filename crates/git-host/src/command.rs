@@ -0,0 +1,202 @@
+//! Bounded execution of provider CLIs.
+//!
+//! A hung host or an unexpectedly large response (e.g. a PR with thousands of
+//! comments) must not stall or exhaust memory in the PR poller, so every CLI
+//! call runs with a timeout and a cap on captured output.
+
+use std::{
+    io::{self, Read},
+    process::{Command, ExitStatus, Stdio},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+
+use tokio_util::sync::CancellationToken;
+use utils::{command_ext::NoWindowExt, env::positive_or};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+const DEFAULT_MAX_OUTPUT_BYTES: usize = 16 * 1024 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Limits applied to each provider CLI invocation, read from
+/// `VK_GIT_HOST_TIMEOUT_SECS` and `VK_GIT_HOST_MAX_OUTPUT_BYTES`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CliLimits {
+    pub timeout: Duration,
+    pub max_output_bytes: usize,
+}
+
+impl Default for CliLimits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(positive_or(
+                "VK_GIT_HOST_TIMEOUT_SECS",
+                DEFAULT_TIMEOUT_SECS,
+            )),
+            max_output_bytes: positive_or("VK_GIT_HOST_MAX_OUTPUT_BYTES", DEFAULT_MAX_OUTPUT_BYTES),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum BoundedRunError {
+    Spawn(io::Error),
+    TimedOut(Duration),
+    OutputTooLarge(usize),
+    Cancelled,
+}
+
+pub(crate) struct BoundedOutput {
+    pub status: ExitStatus,
+    pub stdout: Vec<u8>,
+    pub stderr: Vec<u8>,
+}
+
+/// Run `cmd` to completion, killing it if it exceeds `limits` or `cancel`
+/// fires.
+pub(crate) fn run_bounded(
+    cmd: &mut Command,
+    limits: CliLimits,
+    cancel: &CancellationToken,
+) -> Result<BoundedOutput, BoundedRunError> {
+    if cancel.is_cancelled() {
+        return Err(BoundedRunError::Cancelled);
+    }
+
+    let mut child = cmd
+        .no_window()
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(BoundedRunError::Spawn)?;
+
+    let overflow = Arc::new(AtomicBool::new(false));
+    let stdout = child
+        .stdout
+        .take()
+        .map(|pipe| spawn_reader(pipe, limits.max_output_bytes, overflow.clone()));
+    let stderr = child
+        .stderr
+        .take()
+        .map(|pipe| spawn_reader(pipe, limits.max_output_bytes, overflow.clone()));
+
+    let deadline = Instant::now() + limits.timeout;
+    let status = loop {
+        if let Some(status) = child.try_wait().map_err(BoundedRunError::Spawn)? {
+            break status;
+        }
+        if overflow.load(Ordering::Relaxed) {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(BoundedRunError::OutputTooLarge(limits.max_output_bytes));
+        }
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(BoundedRunError::TimedOut(limits.timeout));
+        }
+        if cancel.is_cancelled() {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(BoundedRunError::Cancelled);
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    let stdout = stdout
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+    let stderr = stderr
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    // The process may exit right after filling the pipe.
+    if overflow.load(Ordering::Relaxed) {
+        return Err(BoundedRunError::OutputTooLarge(limits.max_output_bytes));
+    }
+
+    Ok(BoundedOutput {
+        status,
+        stdout,
+        stderr,
+    })
+}
+
+fn spawn_reader<R>(pipe: R, limit: usize, overflow: Arc<AtomicBool>) -> JoinHandle<Vec<u8>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.take(limit as u64 + 1).read_to_end(&mut buf);
+        if buf.len() > limit {
+            overflow.store(true, Ordering::Relaxed);
+            buf.truncate(limit);
+        }
+        buf
+    })
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn limits(timeout_ms: u64, max_output_bytes: usize) -> CliLimits {
+        CliLimits {
+            timeout: Duration::from_millis(timeout_ms),
+            max_output_bytes,
+        }
+    }
+
+    #[test]
+    fn captures_output_within_limits() {
+        let output = run_bounded(
+            Command::new("sh").args(["-c", "printf hello"]),
+            limits(5_000, 1024),
+            &CancellationToken::new(),
+        )
+        .unwrap();
+        assert!(output.status.success());
+        assert_eq!(output.stdout, b"hello");
+    }
+
+    #[test]
+    fn kills_process_after_timeout() {
+        let result = run_bounded(
+            Command::new("sleep").arg("5"),
+            limits(100, 1024),
+            &CancellationToken::new(),
+        );
+        assert!(matches!(result, Err(BoundedRunError::TimedOut(_))));
+    }
+
+    #[test]
+    fn kills_process_when_cancelled() {
+        let cancel = CancellationToken::new();
+        let trigger = cancel.clone();
+        thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            trigger.cancel();
+        });
+
+        let started = Instant::now();
+        let result = run_bounded(Command::new("sleep").arg("5"), limits(5_000, 1024), &cancel);
+        assert!(matches!(result, Err(BoundedRunError::Cancelled)));
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+
+    #[test]
+    fn rejects_oversized_output() {
+        let result = run_bounded(
+            Command::new("sh").args(["-c", "head -c 4096 /dev/zero"]),
+            limits(5_000, 1024),
+            &CancellationToken::new(),
+        );
+        assert!(matches!(result, Err(BoundedRunError::OutputTooLarge(1024))));
+    }
+}
@@ -36,6 +36,60 @@ pub(crate) fn detect_provider_from_url(url: &str) -> ProviderKind {
     ProviderKind::Unknown
 }
 
+/// Where a remote URL points: the host and the owner/repo path on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteLocation {
+    pub host: String,
+    /// Everything between the host and the repo name, e.g. `owner`, a GitLab
+    /// `group/subgroup`, or an Azure DevOps `org/project`.
+    pub owner: String,
+    pub repo: String,
+}
+
+/// Split a remote URL into host, owner and repo.
+///
+/// Accepts `scheme://[user@]host[:port]/path`, scp-style `[user@]host:path`,
+/// Azure DevOps `/_git/` paths and `ssh.dev.azure.com:v3/org/project/repo`.
+/// Returns `None` for local paths and URLs without an owner and repo.
+pub fn parse_remote_url(url: &str) -> Option<RemoteLocation> {
+    let url = url.trim();
+    let (authority, path) = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?,
+        None => url.split_once(':')?,
+    };
+    let host = authority.rsplit('@').next()?;
+    let host = host.split(':').next()?.to_lowercase();
+    // A single letter before `:` is a Windows drive, not a host
+    if host.len() < 2 || path.contains('\\') {
+        return None;
+    }
+
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let (owner, repo) = if let Some(idx) = segments.iter().position(|s| *s == "_git") {
+        (&segments[..idx], *segments.get(idx + 1)?)
+    } else if segments.first() == Some(&"v3") && segments.len() > 2 {
+        // Azure DevOps SSH: v3/org/project/repo
+        (&segments[1..segments.len() - 1], *segments.last()?)
+    } else {
+        (
+            &segments[..segments.len().checked_sub(1)?],
+            *segments.last()?,
+        )
+    };
+    if owner.is_empty() || repo.is_empty() {
+        return None;
+    }
+
+    Some(RemoteLocation {
+        host,
+        owner: owner.join("/"),
+        repo: repo.to_string(),
+    })
+}
+
 /// Detect the git hosting provider from a PR URL.
 ///
 /// Supports:
@@ -136,6 +190,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_remote_url() {
+        let parse = |url| parse_remote_url(url).map(|loc| (loc.host, loc.owner, loc.repo));
+        let loc = |host: &str, owner: &str, repo: &str| {
+            Some((host.to_string(), owner.to_string(), repo.to_string()))
+        };
+
+        assert_eq!(
+            parse("https://github.com/owner/repo.git"),
+            loc("github.com", "owner", "repo")
+        );
+        assert_eq!(
+            parse("git@github.com:owner/repo.git"),
+            loc("github.com", "owner", "repo")
+        );
+        assert_eq!(
+            parse("ssh://git@github.example.com:2222/owner/repo"),
+            loc("github.example.com", "owner", "repo")
+        );
+        assert_eq!(
+            parse("https://gitlab.com/group/subgroup/repo"),
+            loc("gitlab.com", "group/subgroup", "repo")
+        );
+        assert_eq!(
+            parse("https://org@dev.azure.com/org/project/_git/repo"),
+            loc("dev.azure.com", "org/project", "repo")
+        );
+        assert_eq!(
+            parse("git@ssh.dev.azure.com:v3/org/project/repo"),
+            loc("ssh.dev.azure.com", "org/project", "repo")
+        );
+        assert_eq!(parse("/home/me/repo"), None);
+        assert_eq!(parse("C:\\code\\repo"), None);
+        assert_eq!(parse("https://github.com/owner"), None);
+    }
+
     #[test]
     fn test_unknown_provider() {
         assert_eq!(
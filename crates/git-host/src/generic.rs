@@ -0,0 +1,113 @@
+//! Fallback for plain git remotes (bare repos over SSH, self-hosted servers
+//! without a PR API). Branches are pushed with regular git elsewhere; every
+//! pull request operation is reported as unsupported, and lookups return
+//! nothing so callers treat the branch as having no PR.
+
+use std::path::Path;
+
+use async_trait::async_trait;
+
+use crate::{
+    GitHostProvider,
+    types::{
+        CreatePrRequest, GitHostError, ProviderCapabilities, ProviderKind, PullRequestDetail,
+        UnifiedPrComment,
+    },
+};
+
+#[derive(Debug, Clone, Default)]
+pub struct GenericGitProvider;
+
+impl GenericGitProvider {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn unsupported<T>(operation: &'static str) -> Result<T, GitHostError> {
+        Err(GitHostError::UnsupportedOperation {
+            provider: ProviderKind::Generic,
+            operation,
+        })
+    }
+}
+
+#[async_trait]
+impl GitHostProvider for GenericGitProvider {
+    async fn create_pr(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _request: &CreatePrRequest,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        Self::unsupported("creating pull requests")
+    }
+
+    async fn get_pr_status(&self, _pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
+        Self::unsupported("pull request status")
+    }
+
+    async fn update_pr_base(
+        &self,
+        _pr_url: &str,
+        _new_base: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
+        Self::unsupported("changing a pull request's target branch")
+    }
+
+    async fn request_reviewers(
+        &self,
+        _pr_url: &str,
+        _reviewers: &[String],
+    ) -> Result<Vec<String>, GitHostError> {
+        Self::unsupported("requesting reviewers")
+    }
+
+    async fn add_labels(
+        &self,
+        _pr_url: &str,
+        _labels: &[String],
+    ) -> Result<Vec<String>, GitHostError> {
+        Self::unsupported("adding labels")
+    }
+
+    async fn list_prs_for_branch(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _branch_name: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        Ok(Vec::new())
+    }
+
+    async fn get_pr_comments(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+        _pr_number: i64,
+    ) -> Result<Vec<UnifiedPrComment>, GitHostError> {
+        Self::unsupported("pull request comments")
+    }
+
+    async fn list_open_prs(
+        &self,
+        _repo_path: &Path,
+        _remote_url: &str,
+    ) -> Result<Vec<PullRequestDetail>, GitHostError> {
+        Ok(Vec::new())
+    }
+
+    fn provider_kind(&self) -> ProviderKind {
+        ProviderKind::Generic
+    }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            draft_prs: false,
+            squash_merge: false,
+            review_threads: false,
+            cross_fork_prs: false,
+            list_open_prs: false,
+            edit_pr_base: false,
+        }
+    }
+}
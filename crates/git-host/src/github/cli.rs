@@ -8,19 +8,24 @@ use std::{
     io::Write,
     path::Path,
     process::Command,
+    time::Duration,
 };
 
 use chrono::{DateTime, Utc};
-use db::models::merge::MergeStatus;
+use db::models::merge::{ChecksStatus, MergeStatus};
 use serde::Deserialize;
 use tempfile::NamedTempFile;
 use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 use url::Url;
-use utils::{command_ext::NoWindowExt, shell::resolve_executable_path_blocking};
-
-use crate::types::{
-    CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, PullRequestDetail,
-    ReviewCommentUser,
+use utils::shell::resolve_executable_path_blocking;
+
+use crate::{
+    command::{BoundedRunError, CliLimits, run_bounded},
+    types::{
+        CreatePrRequest, PrComment, PrCommentAuthor, PrReviewComment, PullRequestDetail,
+        ReviewCommentUser,
+    },
 };
 
 #[derive(Debug, Clone)]
@@ -117,6 +122,54 @@ struct GhPrResponse {
     head_ref_name: Option<String>,
     #[serde(default)]
     updated_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    status_check_rollup: Option<Vec<GhCheck>>,
+}
+
+/// One entry of `statusCheckRollup`: a check run (`status`/`conclusion`) or
+/// a commit status context (`state`).
+#[derive(Deserialize)]
+struct GhCheck {
+    #[serde(default)]
+    status: Option<String>,
+    #[serde(default)]
+    conclusion: Option<String>,
+    #[serde(default)]
+    state: Option<String>,
+}
+
+impl GhCheck {
+    fn checks_status(&self) -> ChecksStatus {
+        if let Some(state) = &self.state {
+            return match state.as_str() {
+                "SUCCESS" => ChecksStatus::Success,
+                "FAILURE" | "ERROR" => ChecksStatus::Failure,
+                _ => ChecksStatus::Pending,
+            };
+        }
+        if self.status.as_deref() != Some("COMPLETED") {
+            return ChecksStatus::Pending;
+        }
+        match self.conclusion.as_deref() {
+            Some("SUCCESS" | "NEUTRAL" | "SKIPPED") => ChecksStatus::Success,
+            _ => ChecksStatus::Failure,
+        }
+    }
+}
+
+/// Any failing check fails the rollup; otherwise any unfinished check keeps
+/// it pending.
+fn rollup_checks_status(checks: &[GhCheck]) -> Option<ChecksStatus> {
+    let statuses: Vec<_> = checks.iter().map(GhCheck::checks_status).collect();
+    if statuses.is_empty() {
+        None
+    } else if statuses.contains(&ChecksStatus::Failure) {
+        Some(ChecksStatus::Failure)
+    } else if statuses.contains(&ChecksStatus::Pending) {
+        Some(ChecksStatus::Pending)
+    } else {
+        Some(ChecksStatus::Success)
+    }
 }
 
 #[derive(Debug, Error)]
@@ -129,14 +182,28 @@ pub enum GhCliError {
     AuthFailed(String),
     #[error("GitHub CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("GitHub CLI command timed out after {0:?}")]
+    TimedOut(Duration),
+    #[error("GitHub CLI output exceeded {0} bytes")]
+    OutputTooLarge(usize),
+    #[error("GitHub CLI command was cancelled")]
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Default)]
-pub struct GhCli;
+pub struct GhCli {
+    limits: CliLimits,
+    cancel: CancellationToken,
+}
 
 impl GhCli {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Kill running commands and refuse new ones once `cancel` fires.
+    pub fn with_cancellation(self, cancel: CancellationToken) -> Self {
+        Self { cancel, ..self }
     }
 
     /// Ensure the GitHub CLI binary is discoverable.
@@ -159,10 +226,12 @@ impl GhCli {
         for arg in args {
             cmd.arg(arg);
         }
-        let output = cmd
-            .no_window()
-            .output()
-            .map_err(|err| GhCliError::CommandFailed(err.to_string()))?;
+        let output = run_bounded(&mut cmd, self.limits, &self.cancel).map_err(|err| match err {
+            BoundedRunError::Spawn(err) => GhCliError::CommandFailed(err.to_string()),
+            BoundedRunError::TimedOut(timeout) => GhCliError::TimedOut(timeout),
+            BoundedRunError::OutputTooLarge(limit) => GhCliError::OutputTooLarge(limit),
+            BoundedRunError::Cancelled => GhCliError::Cancelled,
+        })?;
 
         if output.status.success() {
             return Ok(String::from_utf8_lossy(&output.stdout).to_string());
@@ -189,6 +258,16 @@ impl GhCli {
         Err(GhCliError::CommandFailed(stderr))
     }
 
+    /// Whether `gh` has credentials for `host`. Uses `gh auth status`, which
+    /// never prints the token itself.
+    pub fn is_authenticated(&self, host: &str) -> Result<bool, GhCliError> {
+        match self.run(["auth", "status", "--hostname", host], None) {
+            Ok(_) => Ok(true),
+            Err(GhCliError::AuthFailed(_) | GhCliError::CommandFailed(_)) => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
     pub fn get_repo_info(
         &self,
         remote_url: &str,
@@ -268,13 +347,40 @@ impl GhCli {
                 "view",
                 pr_url,
                 "--json",
-                "number,url,state,mergedAt,mergeCommit,title,baseRefName,headRefName",
+                "number,url,state,mergedAt,mergeCommit,title,baseRefName,headRefName,statusCheckRollup",
             ],
             None,
         )?;
         Self::parse_pr_view(&raw)
     }
 
+    /// Change the base branch of a pull request and return its updated details.
+    pub fn edit_pr_base(
+        &self,
+        pr_url: &str,
+        new_base: &str,
+    ) -> Result<PullRequestDetail, GhCliError> {
+        self.run(["pr", "edit", pr_url, "--base", new_base], None)?;
+        self.view_pr(pr_url)
+    }
+
+    /// Add an existing repository label to a pull request.
+    pub fn add_pr_label(&self, pr_url: &str, label: &str) -> Result<(), GhCliError> {
+        self.run(Self::pr_edit_args(pr_url, "--add-label", label), None)?;
+        Ok(())
+    }
+
+    /// Request a review from a user login or `org/team` slug.
+    pub fn add_pr_reviewer(&self, pr_url: &str, reviewer: &str) -> Result<(), GhCliError> {
+        self.run(Self::pr_edit_args(pr_url, "--add-reviewer", reviewer), None)?;
+        Ok(())
+    }
+
+    /// `gh pr edit` arguments that add one label or reviewer.
+    fn pr_edit_args<'a>(pr_url: &'a str, flag: &'a str, value: &'a str) -> [&'a str; 5] {
+        ["pr", "edit", pr_url, flag, value]
+    }
+
     /// List pull requests for a branch (includes closed/merged).
     pub fn list_prs_for_branch(
         &self,
@@ -463,6 +569,7 @@ impl GhCli {
             status: MergeStatus::Open,
             merged_at: None,
             merge_commit_sha: None,
+            checks_status: None,
             title: request.title.clone(),
             base_branch: request.base_branch.clone(),
             head_branch: request.head_branch.clone(),
@@ -504,6 +611,9 @@ impl GhCli {
             },
             merged_at: pr.merged_at,
             merge_commit_sha: pr.merge_commit.and_then(|c| c.oid),
+            checks_status: rollup_checks_status(
+                pr.status_check_rollup.as_deref().unwrap_or_default(),
+            ),
             title: pr.title.unwrap_or_default(),
             base_branch: pr.base_ref_name.unwrap_or_default(),
             head_branch: pr.head_ref_name.unwrap_or_default(),
@@ -566,3 +676,48 @@ impl GhCli {
             .collect())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rolls_up_check_runs_and_status_contexts() {
+        let view = |rollup: &str| {
+            GhCli::parse_pr_view(&format!(
+                r#"{{"number": 1, "url": "u", "state": "OPEN", "statusCheckRollup": {rollup}}}"#
+            ))
+            .unwrap()
+            .checks_status
+        };
+        assert_eq!(view("null"), None);
+        assert_eq!(view("[]"), None);
+        assert_eq!(
+            view(
+                r#"[{"status": "COMPLETED", "conclusion": "SUCCESS"}, {"state": "SUCCESS"}, {"status": "COMPLETED", "conclusion": "SKIPPED"}]"#
+            ),
+            Some(ChecksStatus::Success)
+        );
+        assert_eq!(
+            view(r#"[{"status": "IN_PROGRESS", "conclusion": ""}, {"state": "SUCCESS"}]"#),
+            Some(ChecksStatus::Pending)
+        );
+        assert_eq!(
+            view(r#"[{"status": "IN_PROGRESS"}, {"state": "ERROR"}]"#),
+            Some(ChecksStatus::Failure)
+        );
+    }
+
+    #[test]
+    fn labels_and_reviewers_are_added_with_pr_edit() {
+        let url = "https://github.com/owner/repo/pull/7";
+        assert_eq!(
+            GhCli::pr_edit_args(url, "--add-label", "needs review"),
+            ["pr", "edit", url, "--add-label", "needs review"]
+        );
+        assert_eq!(
+            GhCli::pr_edit_args(url, "--add-reviewer", "org/team"),
+            ["pr", "edit", url, "--add-reviewer", "org/team"]
+        );
+    }
+}
@@ -9,28 +9,39 @@ use backon::{ExponentialBuilder, Retryable};
 pub use cli::GhCli;
 use cli::{GhCliError, GitHubRepoInfo};
 use tokio::task;
+use tokio_util::sync::CancellationToken;
 use tracing::info;
 
 use crate::{
     GitHostProvider,
+    cancel::cancel_on,
     types::{
-        CreatePrRequest, GitHostError, PrComment, PrReviewComment, ProviderKind, PullRequestDetail,
-        UnifiedPrComment,
+        CreatePrRequest, GitHostError, PrComment, PrReviewComment, ProviderCapabilities,
+        ProviderKind, PullRequestDetail, UnifiedPrComment,
     },
 };
 
 #[derive(Debug, Clone)]
 pub struct GitHubProvider {
     gh_cli: GhCli,
+    cancel: CancellationToken,
 }
 
 impl GitHubProvider {
     pub fn new() -> Result<Self, GitHostError> {
         Ok(Self {
             gh_cli: GhCli::new(),
+            cancel: CancellationToken::new(),
         })
     }
 
+    pub fn with_cancellation(self, cancel: CancellationToken) -> Self {
+        Self {
+            gh_cli: self.gh_cli.with_cancellation(cancel.clone()),
+            cancel,
+        }
+    }
+
     async fn get_repo_info(
         &self,
         remote_url: &str,
@@ -56,34 +67,39 @@ impl GitHubProvider {
         let cli = cli.clone();
         let repo_info = repo_info.clone();
 
-        (|| async {
-            let cli = cli.clone();
-            let repo_info = repo_info.clone();
-
-            let comments = task::spawn_blocking(move || cli.get_pr_comments(&repo_info, pr_number))
-                .await
-                .map_err(|err| {
-                    GitHostError::PullRequest(format!(
-                        "Failed to execute GitHub CLI for fetching PR comments: {err}"
-                    ))
-                })?;
-            comments.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = cli.clone();
+                let repo_info = repo_info.clone();
+
+                let comments =
+                    task::spawn_blocking(move || cli.get_pr_comments(&repo_info, pr_number))
+                        .await
+                        .map_err(|err| {
+                            GitHostError::PullRequest(format!(
+                                "Failed to execute GitHub CLI for fetching PR comments: {err}"
+                            ))
+                        })?;
+                comments.map_err(GitHostError::from)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "GitHub API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 
@@ -96,35 +112,39 @@ impl GitHubProvider {
         let cli = cli.clone();
         let repo_info = repo_info.clone();
 
-        (|| async {
-            let cli = cli.clone();
-            let repo_info = repo_info.clone();
-
-            let comments =
-                task::spawn_blocking(move || cli.get_pr_review_comments(&repo_info, pr_number))
-                    .await
-                    .map_err(|err| {
-                        GitHostError::PullRequest(format!(
-                            "Failed to execute GitHub CLI for fetching review comments: {err}"
-                        ))
-                    })?;
-            comments.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = cli.clone();
+                let repo_info = repo_info.clone();
+
+                let comments =
+                    task::spawn_blocking(move || cli.get_pr_review_comments(&repo_info, pr_number))
+                        .await
+                        .map_err(|err| {
+                            GitHostError::PullRequest(format!(
+                                "Failed to execute GitHub CLI for fetching review comments: {err}"
+                            ))
+                        })?;
+                comments.map_err(GitHostError::from)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "GitHub API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 }
@@ -138,7 +158,9 @@ impl From<GhCliError> for GitHostError {
             },
             GhCliError::CommandFailed(msg) => {
                 let lower = msg.to_ascii_lowercase();
-                if lower.contains("403") || lower.contains("forbidden") {
+                if let Some(rate_limited) = GitHostError::rate_limited(msg) {
+                    rate_limited
+                } else if lower.contains("403") || lower.contains("forbidden") {
                     GitHostError::InsufficientPermissions(msg.clone())
                 } else if lower.contains("404") || lower.contains("not found") {
                     GitHostError::RepoNotFoundOrNoAccess(msg.clone())
@@ -149,6 +171,9 @@ impl From<GhCliError> for GitHostError {
                 }
             }
             GhCliError::UnexpectedOutput(msg) => GitHostError::UnexpectedOutput(msg.clone()),
+            GhCliError::TimedOut(timeout) => GitHostError::TimedOut(*timeout),
+            GhCliError::OutputTooLarge(limit) => GitHostError::ResponseTooLarge(*limit),
+            GhCliError::Cancelled => GitHostError::Cancelled,
         }
     }
 }
@@ -179,79 +204,166 @@ impl GitHostProvider for GitHubProvider {
         let mut request_clone = request.clone();
         request_clone.head_branch = head_branch;
 
-        (|| async {
-            let cli = self.gh_cli.clone();
-            let request = request_clone.clone();
-            let target_repo = target_repo_info.clone();
-            let repo_path = repo_path.to_path_buf();
+        let pr = cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = self.gh_cli.clone();
+                let request = request_clone.clone();
+                let target_repo = target_repo_info.clone();
+                let repo_path = repo_path.to_path_buf();
+
+                let cli_result =
+                    task::spawn_blocking(move || cli.create_pr(&request, &target_repo, &repo_path))
+                        .await
+                        .map_err(|err| {
+                            GitHostError::PullRequest(format!(
+                                "Failed to execute GitHub CLI for PR creation: {err}"
+                            ))
+                        })?
+                        .map_err(GitHostError::from)?;
+
+                info!(
+                    "Created GitHub PR #{} for branch {}",
+                    cli_result.number, request_clone.head_branch
+                );
+
+                Ok(cli_result)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
+        )
+        .await?;
 
-            let cli_result =
-                task::spawn_blocking(move || cli.create_pr(&request, &target_repo, &repo_path))
+        Ok(pr)
+    }
+
+    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = cli.clone();
+                let url = url.clone();
+                let pr = task::spawn_blocking(move || cli.view_pr(&url))
                     .await
                     .map_err(|err| {
                         GitHostError::PullRequest(format!(
-                            "Failed to execute GitHub CLI for PR creation: {err}"
+                            "Failed to execute GitHub CLI for viewing PR: {err}"
                         ))
-                    })?
-                    .map_err(GitHostError::from)?;
-
-            info!(
-                "Created GitHub PR #{} for branch {}",
-                cli_result.number, request_clone.head_branch
-            );
-
-            Ok(cli_result)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+                    })?;
+                pr.map_err(GitHostError::from)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|err: &GitHostError| err.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "GitHub API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 
-    async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError> {
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        new_base: &str,
+    ) -> Result<PullRequestDetail, GitHostError> {
         let cli = self.gh_cli.clone();
         let url = pr_url.to_string();
+        let base = new_base.to_string();
+
+        task::spawn_blocking(move || cli.edit_pr_base(&url, &base))
+            .await
+            .map_err(|err| {
+                GitHostError::PullRequest(format!(
+                    "Failed to execute GitHub CLI for editing PR: {err}"
+                ))
+            })?
+            .map_err(GitHostError::from)
+    }
 
-        (|| async {
-            let cli = cli.clone();
-            let url = url.clone();
-            let pr = task::spawn_blocking(move || cli.view_pr(&url))
-                .await
-                .map_err(|err| {
-                    GitHostError::PullRequest(format!(
-                        "Failed to execute GitHub CLI for viewing PR: {err}"
-                    ))
-                })?;
-            pr.map_err(GitHostError::from)
+    async fn request_reviewers(
+        &self,
+        pr_url: &str,
+        reviewers: &[String],
+    ) -> Result<Vec<String>, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+        let reviewers = reviewers.to_vec();
+
+        task::spawn_blocking(move || {
+            reviewers
+                .into_iter()
+                .filter(|reviewer| match cli.add_pr_reviewer(&url, reviewer) {
+                    Ok(()) => false,
+                    Err(err) => {
+                        tracing::warn!("Failed to request review from {reviewer} on {url}: {err}");
+                        true
+                    }
+                })
+                .collect()
         })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
-        )
-        .when(|err: &GitHostError| err.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "GitHub API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
+        .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for requesting reviewers: {err}"
+            ))
+        })
+    }
+
+    async fn add_labels(
+        &self,
+        pr_url: &str,
+        labels: &[String],
+    ) -> Result<Vec<String>, GitHostError> {
+        let cli = self.gh_cli.clone();
+        let url = pr_url.to_string();
+        let labels = labels.to_vec();
+
+        task::spawn_blocking(move || {
+            labels
+                .into_iter()
+                .filter(|label| match cli.add_pr_label(&url, label) {
+                    Ok(()) => false,
+                    Err(err) => {
+                        tracing::warn!("Failed to add label {label:?} to {url}: {err}");
+                        true
+                    }
+                })
+                .collect()
         })
         .await
+        .map_err(|err| {
+            GitHostError::PullRequest(format!(
+                "Failed to execute GitHub CLI for adding labels: {err}"
+            ))
+        })
     }
 
     async fn list_prs_for_branch(
@@ -265,35 +377,40 @@ impl GitHostProvider for GitHubProvider {
         let cli = self.gh_cli.clone();
         let branch = branch_name.to_string();
 
-        (|| async {
-            let cli = cli.clone();
-            let repo_info = repo_info.clone();
-            let branch = branch.clone();
-
-            let prs = task::spawn_blocking(move || cli.list_prs_for_branch(&repo_info, &branch))
-                .await
-                .map_err(|err| {
-                    GitHostError::PullRequest(format!(
-                        "Failed to execute GitHub CLI for listing PRs: {err}"
-                    ))
-                })?;
-            prs.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = cli.clone();
+                let repo_info = repo_info.clone();
+                let branch = branch.clone();
+
+                let prs =
+                    task::spawn_blocking(move || cli.list_prs_for_branch(&repo_info, &branch))
+                        .await
+                        .map_err(|err| {
+                            GitHostError::PullRequest(format!(
+                                "Failed to execute GitHub CLI for listing PRs: {err}"
+                            ))
+                        })?;
+                prs.map_err(GitHostError::from)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "GitHub API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 
@@ -361,39 +478,54 @@ impl GitHostProvider for GitHubProvider {
 
         let cli = self.gh_cli.clone();
 
-        (|| async {
-            let cli = cli.clone();
-            let owner = repo_info.owner.clone();
-            let repo_name = repo_info.repo_name.clone();
-
-            let prs = task::spawn_blocking(move || cli.list_prs(&owner, &repo_name))
-                .await
-                .map_err(|err| {
-                    GitHostError::PullRequest(format!(
-                        "Failed to execute GitHub CLI for listing PRs: {err}"
-                    ))
-                })?;
-            prs.map_err(GitHostError::from)
-        })
-        .retry(
-            &ExponentialBuilder::default()
-                .with_min_delay(Duration::from_secs(1))
-                .with_max_delay(Duration::from_secs(30))
-                .with_max_times(3)
-                .with_jitter(),
+        cancel_on(
+            &self.cancel,
+            (|| async {
+                let cli = cli.clone();
+                let owner = repo_info.owner.clone();
+                let repo_name = repo_info.repo_name.clone();
+
+                let prs = task::spawn_blocking(move || cli.list_prs(&owner, &repo_name))
+                    .await
+                    .map_err(|err| {
+                        GitHostError::PullRequest(format!(
+                            "Failed to execute GitHub CLI for listing PRs: {err}"
+                        ))
+                    })?;
+                prs.map_err(GitHostError::from)
+            })
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_secs(1))
+                    .with_max_delay(Duration::from_secs(30))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .when(|e: &GitHostError| e.should_retry() && !self.cancel.is_cancelled())
+            .adjust(|err: &GitHostError, dur| err.retry_delay(dur))
+            .notify(|err: &GitHostError, dur: Duration| {
+                tracing::warn!(
+                    "GitHub API call failed, retrying after {:.2}s: {}",
+                    dur.as_secs_f64(),
+                    err
+                );
+            }),
         )
-        .when(|e: &GitHostError| e.should_retry())
-        .notify(|err: &GitHostError, dur: Duration| {
-            tracing::warn!(
-                "GitHub API call failed, retrying after {:.2}s: {}",
-                dur.as_secs_f64(),
-                err
-            );
-        })
         .await
     }
 
     fn provider_kind(&self) -> ProviderKind {
         ProviderKind::GitHub
     }
+
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            draft_prs: true,
+            squash_merge: true,
+            review_threads: true,
+            cross_fork_prs: true,
+            list_open_prs: true,
+            edit_pr_base: true,
+        }
+    }
 }
@@ -1,20 +1,30 @@
+mod cancel;
+mod command;
 mod detection;
 mod types;
 
 pub mod azure;
+pub mod generic;
 pub mod github;
 
 use std::path::Path;
 
 use async_trait::async_trait;
 use detection::detect_provider_from_url;
+pub use detection::{RemoteLocation, parse_remote_url};
 use enum_dispatch::enum_dispatch;
+use tokio::task;
+use tokio_util::sync::CancellationToken;
 pub use types::{
-    CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment, ProviderKind,
-    PullRequestDetail, ReviewCommentUser, UnifiedPrComment,
+    CreatePrRequest, GitHostError, PrComment, PrCommentAuthor, PrReviewComment,
+    ProviderCapabilities, ProviderKind, PullRequestDetail, ReviewCommentUser, UnifiedPrComment,
 };
 
-use self::{azure::AzureDevOpsProvider, github::GitHubProvider};
+use self::{
+    azure::{AzCli, AzureDevOpsProvider},
+    generic::GenericGitProvider,
+    github::{GhCli, GitHubProvider},
+};
 
 #[async_trait]
 #[enum_dispatch(GitHostService)]
@@ -28,6 +38,31 @@ pub trait GitHostProvider: Send + Sync {
 
     async fn get_pr_status(&self, pr_url: &str) -> Result<PullRequestDetail, GitHostError>;
 
+    /// Point an open pull request at a different base branch. Providers whose
+    /// API can't do this return [`GitHostError::UnsupportedOperation`].
+    async fn update_pr_base(
+        &self,
+        pr_url: &str,
+        new_base: &str,
+    ) -> Result<PullRequestDetail, GitHostError>;
+
+    /// Request reviews from `reviewers` on an open pull request. Each reviewer
+    /// is requested separately so one unknown handle doesn't block the rest;
+    /// returns the reviewers that could not be requested.
+    async fn request_reviewers(
+        &self,
+        pr_url: &str,
+        reviewers: &[String],
+    ) -> Result<Vec<String>, GitHostError>;
+
+    /// Add `labels` to an open pull request. Like reviewers, each label is
+    /// added separately and the ones the host rejects are returned.
+    async fn add_labels(
+        &self,
+        pr_url: &str,
+        labels: &[String],
+    ) -> Result<Vec<String>, GitHostError>;
+
     async fn list_prs_for_branch(
         &self,
         repo_path: &Path,
@@ -49,12 +84,15 @@ pub trait GitHostProvider: Send + Sync {
     ) -> Result<Vec<PullRequestDetail>, GitHostError>;
 
     fn provider_kind(&self) -> ProviderKind;
+
+    fn capabilities(&self) -> ProviderCapabilities;
 }
 
 #[enum_dispatch]
 pub enum GitHostService {
     GitHub(GitHubProvider),
     AzureDevOps(AzureDevOpsProvider),
+    Generic(GenericGitProvider),
 }
 
 impl GitHostService {
@@ -62,7 +100,92 @@ impl GitHostService {
         match detect_provider_from_url(url) {
             ProviderKind::GitHub => Ok(Self::GitHub(GitHubProvider::new()?)),
             ProviderKind::AzureDevOps => Ok(Self::AzureDevOps(AzureDevOpsProvider::new()?)),
-            ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
+            ProviderKind::Generic | ProviderKind::Unknown => Err(GitHostError::UnsupportedProvider),
         }
     }
+
+    /// Like [`Self::from_url`], but when `generic_fallback` is set, remotes
+    /// that aren't a known host get [`GenericGitProvider`] instead of an error.
+    pub fn from_url_with_config(url: &str, generic_fallback: bool) -> Result<Self, GitHostError> {
+        match Self::from_url(url) {
+            Err(GitHostError::UnsupportedProvider) if generic_fallback => {
+                Ok(Self::Generic(GenericGitProvider::new()))
+            }
+            result => result,
+        }
+    }
+
+    /// The provider a remote URL points at, without constructing a service.
+    pub fn detect_provider(url: &str) -> ProviderKind {
+        detect_provider_from_url(url)
+    }
+
+    /// Whether the provider's CLI is signed in for `host`. `None` when that
+    /// can't be checked: generic or unknown hosts, or the CLI isn't installed.
+    pub async fn is_authenticated(provider: ProviderKind, host: &str) -> Option<bool> {
+        let host = host.to_string();
+        let result = match provider {
+            ProviderKind::GitHub => {
+                task::spawn_blocking(move || GhCli::new().is_authenticated(&host).ok()).await
+            }
+            ProviderKind::AzureDevOps => {
+                task::spawn_blocking(|| AzCli::new().is_logged_in().ok()).await
+            }
+            ProviderKind::Generic | ProviderKind::Unknown => return None,
+        };
+        result.ok().flatten()
+    }
+
+    /// Abort this service's in-flight and retrying requests once `cancel`
+    /// fires; they then fail with [`GitHostError::Cancelled`].
+    pub fn with_cancellation(self, cancel: CancellationToken) -> Self {
+        match self {
+            Self::GitHub(provider) => Self::GitHub(provider.with_cancellation(cancel)),
+            Self::AzureDevOps(provider) => Self::AzureDevOps(provider.with_cancellation(cancel)),
+            Self::Generic(provider) => Self::Generic(provider),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN_REMOTE: &str = "git@git.example.com:team/repo.git";
+
+    #[test]
+    fn plain_remotes_need_the_generic_fallback() {
+        assert!(matches!(
+            GitHostService::from_url_with_config(PLAIN_REMOTE, false),
+            Err(GitHostError::UnsupportedProvider)
+        ));
+        let host = GitHostService::from_url_with_config(PLAIN_REMOTE, true).unwrap();
+        assert_eq!(host.provider_kind(), ProviderKind::Generic);
+    }
+
+    #[tokio::test]
+    async fn generic_provider_has_no_pull_requests() {
+        let host = GitHostService::from_url_with_config(PLAIN_REMOTE, true).unwrap();
+        let request = CreatePrRequest {
+            title: "Add login".into(),
+            body: None,
+            head_branch: "vk/login".into(),
+            base_branch: "main".into(),
+            draft: None,
+            head_repo_url: None,
+        };
+
+        assert!(matches!(
+            host.create_pr(Path::new("."), PLAIN_REMOTE, &request).await,
+            Err(GitHostError::UnsupportedOperation {
+                provider: ProviderKind::Generic,
+                ..
+            })
+        ));
+        let prs = host
+            .list_prs_for_branch(Path::new("."), PLAIN_REMOTE, "vk/login")
+            .await
+            .unwrap();
+        assert!(prs.is_empty());
+    }
 }
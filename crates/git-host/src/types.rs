@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use db::models::merge::{MergeStatus, PullRequestInfo};
+use db::models::merge::{ChecksStatus, MergeStatus, PullRequestInfo};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use ts_rs::TS;
@@ -9,6 +9,8 @@ use ts_rs::TS;
 pub enum ProviderKind {
     GitHub,
     AzureDevOps,
+    // A plain git remote with no pull request API
+    Generic,
     Unknown,
 }
 
@@ -17,11 +19,24 @@ impl std::fmt::Display for ProviderKind {
         match self {
             ProviderKind::GitHub => write!(f, "GitHub"),
             ProviderKind::AzureDevOps => write!(f, "Azure DevOps"),
+            ProviderKind::Generic => write!(f, "Generic git"),
             ProviderKind::Unknown => write!(f, "Unknown"),
         }
     }
 }
 
+/// Features supported by a git hosting provider, so clients can hide options
+/// the resolved provider cannot honour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct ProviderCapabilities {
+    pub draft_prs: bool,
+    pub squash_merge: bool,
+    pub review_threads: bool,
+    pub cross_fork_prs: bool,
+    pub list_open_prs: bool,
+    pub edit_pr_base: bool,
+}
+
 #[derive(Debug, Clone)]
 pub struct CreatePrRequest {
     pub title: String,
@@ -51,8 +66,25 @@ pub enum GitHostError {
     NotAGitRepository(String),
     #[error("Unsupported git hosting provider")]
     UnsupportedProvider,
+    #[error("{provider} does not support {operation}")]
+    UnsupportedOperation {
+        provider: ProviderKind,
+        operation: &'static str,
+    },
     #[error("CLI returned unexpected output: {0}")]
     UnexpectedOutput(String),
+    #[error("Request to git host timed out after {0:?}")]
+    TimedOut(std::time::Duration),
+    #[error("Git host response exceeded {0} bytes")]
+    ResponseTooLarge(usize),
+    #[error("Git host request was cancelled")]
+    Cancelled,
+    #[error("Rate limited by git host: {message}")]
+    RateLimited {
+        message: String,
+        /// How long the host asked us to wait, when it said.
+        retry_after: Option<std::time::Duration>,
+    },
 }
 
 impl GitHostError {
@@ -65,8 +97,70 @@ impl GitHostError {
                 | GitHostError::CliNotInstalled { .. }
                 | GitHostError::NotAGitRepository(_)
                 | GitHostError::UnsupportedProvider
+                | GitHostError::UnsupportedOperation { .. }
+                | GitHostError::ResponseTooLarge(_)
+                | GitHostError::Cancelled
         )
     }
+
+    /// Recognise a CLI failure caused by an exhausted rate limit (HTTP 429,
+    /// or GitHub's 403 "API rate limit exceeded").
+    pub(crate) fn rate_limited(msg: &str) -> Option<Self> {
+        let lower = msg.to_ascii_lowercase();
+        let limited = lower.contains("rate limit")
+            || lower.contains("too many requests")
+            || lower.contains("http 429");
+        limited.then(|| GitHostError::RateLimited {
+            message: msg.to_string(),
+            retry_after: parse_retry_after(msg, Utc::now().timestamp()),
+        })
+    }
+
+    /// Delay before the next retry: the host's `Retry-After` when rate
+    /// limited, otherwise the exponential backoff. The CLIs rarely echo the
+    /// reset time, so a rate limit that doesn't say waits at least
+    /// [`RATE_LIMIT_MIN_DELAY`]; a short backoff would only retry into the
+    /// same limit. A host asking for longer than [`MAX_RETRY_AFTER`] fails
+    /// the request.
+    pub(crate) fn retry_delay(
+        &self,
+        backoff: Option<std::time::Duration>,
+    ) -> Option<std::time::Duration> {
+        let backoff = backoff?;
+        match self {
+            GitHostError::RateLimited {
+                retry_after: Some(wait),
+                ..
+            } => (*wait <= MAX_RETRY_AFTER).then_some(*wait),
+            GitHostError::RateLimited {
+                retry_after: None, ..
+            } => Some(backoff.clamp(RATE_LIMIT_MIN_DELAY, MAX_RETRY_AFTER)),
+            _ => Some(backoff),
+        }
+    }
+}
+
+/// Longest `Retry-After` we are willing to sleep through before retrying.
+pub(crate) const MAX_RETRY_AFTER: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Shortest wait after a rate limit that didn't say when it resets.
+pub(crate) const RATE_LIMIT_MIN_DELAY: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// Read the wait from a `Retry-After: <seconds>` header, or failing that an
+/// `X-RateLimit-Reset: <epoch seconds>` header, echoed in CLI output.
+fn parse_retry_after(msg: &str, now: i64) -> Option<std::time::Duration> {
+    let header_value = |name: &str| {
+        msg.lines().find_map(|line| {
+            let (key, value) = line.trim().split_once(':')?;
+            key.trim()
+                .eq_ignore_ascii_case(name)
+                .then(|| value.trim().parse::<i64>().ok())
+                .flatten()
+        })
+    };
+    let secs = header_value("retry-after")
+        .or_else(|| header_value("x-ratelimit-reset").map(|reset| reset - now))?;
+    Some(std::time::Duration::from_secs(secs.max(0) as u64))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -146,6 +240,7 @@ pub struct PullRequestDetail {
     pub status: MergeStatus,
     pub merged_at: Option<DateTime<Utc>>,
     pub merge_commit_sha: Option<String>,
+    pub checks_status: Option<ChecksStatus>,
     pub title: String,
     pub base_branch: String,
     pub head_branch: String,
@@ -159,6 +254,109 @@ impl From<PullRequestDetail> for PullRequestInfo {
             status: d.status,
             merged_at: d.merged_at,
             merge_commit_sha: d.merge_commit_sha,
+            checks_status: d.checks_status,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use backon::{ExponentialBuilder, Retryable};
+
+    use super::*;
+
+    #[test]
+    fn parses_rate_limit_headers() {
+        assert_eq!(
+            parse_retry_after("HTTP 429: Too Many Requests\nretry-after: 30", 0),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            parse_retry_after("API rate limit exceeded\nX-RateLimit-Reset: 1060", 1000),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(parse_retry_after("HTTP 429: Too Many Requests", 0), None);
+        assert!(GitHostError::rate_limited("HTTP 404: Not Found").is_none());
+    }
+
+    #[tokio::test]
+    async fn waits_for_retry_after_before_retrying() {
+        let delays = Arc::new(Mutex::new(Vec::new()));
+        let recorded = delays.clone();
+        let attempts = Mutex::new(0);
+
+        let result = (|| async {
+            let mut attempts = attempts.lock().unwrap();
+            *attempts += 1;
+            if *attempts == 1 {
+                Err(
+                    GitHostError::rate_limited("HTTP 429: Too Many Requests\nRetry-After: 30")
+                        .unwrap(),
+                )
+            } else {
+                Ok(*attempts)
+            }
+        })
+        .retry(
+            ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_times(3),
+        )
+        .sleep(move |dur: Duration| {
+            recorded.lock().unwrap().push(dur);
+            std::future::ready(())
+        })
+        .when(|e: &GitHostError| e.should_retry())
+        .adjust(|e: &GitHostError, dur| e.retry_delay(dur))
+        .await;
+
+        assert_eq!(result.unwrap(), 2);
+        assert_eq!(*delays.lock().unwrap(), vec![Duration::from_secs(30)]);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_without_reset_waits_the_long_backoff() {
+        let delays = Arc::new(Mutex::new(Vec::new()));
+        let recorded = delays.clone();
+
+        let result = (|| async {
+            Err::<(), _>(
+                GitHostError::rate_limited("HTTP 403: API rate limit exceeded for user").unwrap(),
+            )
+        })
+        .retry(
+            ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(600))
+                .with_factor(12.0)
+                .with_max_times(3),
+        )
+        .sleep(move |dur: Duration| {
+            recorded.lock().unwrap().push(dur);
+            std::future::ready(())
+        })
+        .when(|e: &GitHostError| e.should_retry())
+        .adjust(|e: &GitHostError, dur| e.retry_delay(dur))
+        .await;
+
+        assert!(matches!(result, Err(GitHostError::RateLimited { .. })));
+        // Backoff of 1s, 12s and 144s, raised to the minimum and capped.
+        assert_eq!(
+            *delays.lock().unwrap(),
+            vec![RATE_LIMIT_MIN_DELAY, RATE_LIMIT_MIN_DELAY, MAX_RETRY_AFTER]
+        );
+    }
+
+    #[test]
+    fn gives_up_when_the_host_asks_for_too_long() {
+        let error =
+            GitHostError::rate_limited("HTTP 429: Too Many Requests\nRetry-After: 3600").unwrap();
+        assert!(error.should_retry());
+        assert_eq!(error.retry_delay(Some(Duration::from_secs(1))), None);
+    }
+}
@@ -25,7 +25,7 @@ use std::{
 use thiserror::Error;
 use utils::{path::ALWAYS_SKIP_DIRS, shell::resolve_executable_path_blocking};
 
-use super::Commit;
+use super::{Commit, remote_throttle};
 
 #[derive(Debug, Error)]
 pub enum GitCliError {
@@ -357,6 +357,24 @@ impl GitCli {
             OsString::from(refspec),
         ];
 
+        let _permit = remote_throttle::acquire(remote_url);
+        match self.git_with_env(repo_path, args, &envs) {
+            Ok(_) => Ok(()),
+            Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Fetch the history missing from a shallow clone.
+    pub fn unshallow(&self, repo_path: &Path, remote_url: &str) -> Result<(), GitCliError> {
+        let envs = vec![(OsString::from("GIT_TERMINAL_PROMPT"), OsString::from("0"))];
+        let args = [
+            OsString::from("fetch"),
+            OsString::from("--unshallow"),
+            OsString::from(remote_url),
+        ];
+
+        let _permit = remote_throttle::acquire(remote_url);
         match self.git_with_env(repo_path, args, &envs) {
             Ok(_) => Ok(()),
             Err(GitCliError::CommandFailed(msg)) => Err(self.classify_cli_error(msg)),
@@ -661,6 +679,41 @@ impl GitCli {
         Ok(sha)
     }
 
+    /// Checkout base branch, merge from_branch with a merge commit, and return the new HEAD sha.
+    pub fn merge_no_ff_commit(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--no-ff", "-m", message, from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
+    /// Checkout base branch and fast-forward it to from_branch. Returns new HEAD sha.
+    pub fn merge_fast_forward(
+        &self,
+        repo_path: &Path,
+        base_branch: &str,
+        from_branch: &str,
+    ) -> Result<String, GitCliError> {
+        self.git(repo_path, ["checkout", base_branch]).map(|_| ())?;
+        self.git(repo_path, ["merge", "--ff-only", from_branch])
+            .map(|_| ())?;
+        let sha = self
+            .git(repo_path, ["rev-parse", "HEAD"])?
+            .trim()
+            .to_string();
+        Ok(sha)
+    }
+
     /// Update a ref to a specific sha in the repo.
     pub fn update_ref(
         &self,
@@ -672,6 +725,102 @@ impl GitCli {
             .map(|_| ())
     }
 
+    /// Move HEAD (and the branch it points at) to `sha`, updating the index and
+    /// files that differ while keeping uncommitted changes. Fails without
+    /// touching anything if an uncommitted change would be overwritten.
+    pub fn reset_keep(&self, worktree_path: &Path, sha: &str) -> Result<(), GitCliError> {
+        self.git(worktree_path, ["reset", "--keep", sha])
+            .map(|_| ())
+    }
+
+    /// Record tracked and untracked changes as a commit whose parent is HEAD and
+    /// point `refname` at it. HEAD, the index and the stash are left untouched.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        refname: &str,
+        message: &str,
+    ) -> Result<String, GitCliError> {
+        let tmp_dir = tempfile::TempDir::new()
+            .map_err(|e| GitCliError::CommandFailed(format!("temp dir create failed: {e}")))?;
+        let tmp_index = tmp_dir.path().join("index");
+        let mut envs = vec![(
+            OsString::from("GIT_INDEX_FILE"),
+            tmp_index.as_os_str().to_os_string(),
+        )];
+        self.git_with_env(worktree_path, ["read-tree", "HEAD"], &envs)?;
+        self.git_with_env(worktree_path, ["add", "-A"], &envs)?;
+        let tree = self
+            .git_with_env(worktree_path, ["write-tree"], &envs)?
+            .trim()
+            .to_string();
+
+        // Snapshot commits are internal objects; don't depend on a configured identity.
+        for (key, value) in [
+            ("GIT_AUTHOR_NAME", "Vibe Kanban"),
+            ("GIT_AUTHOR_EMAIL", "noreply@vibekanban.com"),
+            ("GIT_COMMITTER_NAME", "Vibe Kanban"),
+            ("GIT_COMMITTER_EMAIL", "noreply@vibekanban.com"),
+        ] {
+            envs.push((OsString::from(key), OsString::from(value)));
+        }
+        let commit = self
+            .git_with_env(
+                worktree_path,
+                ["commit-tree", &tree, "-p", "HEAD", "-m", message],
+                &envs,
+            )?
+            .trim()
+            .to_string();
+        self.update_ref(worktree_path, refname, &commit)?;
+        Ok(commit)
+    }
+
+    /// Reset HEAD, index and working tree to the state captured by
+    /// `snapshot_worktree`. Files untracked at snapshot time stay untracked and
+    /// files created since are removed; ignored files are left alone.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        snapshot: &str,
+    ) -> Result<(), GitCliError> {
+        let parent = format!("{snapshot}^");
+        self.git(worktree_path, ["reset", "--hard", snapshot])?;
+        self.git(worktree_path, ["clean", "-fd"])?;
+        self.git(worktree_path, ["reset", "--mixed", "-q", &parent])?;
+        Ok(())
+    }
+
+    /// Delete a ref in the repo.
+    pub fn delete_ref(&self, repo_path: &Path, refname: &str) -> Result<(), GitCliError> {
+        self.git(repo_path, ["update-ref", "-d", refname])
+            .map(|_| ())
+    }
+
+    /// List refs under `prefix` as `(refname, sha)` pairs, oldest first.
+    pub fn list_refs(
+        &self,
+        repo_path: &Path,
+        prefix: &str,
+    ) -> Result<Vec<(String, String)>, GitCliError> {
+        let out = self.git(
+            repo_path,
+            [
+                "for-each-ref",
+                "--sort=creatordate",
+                "--format=%(refname) %(objectname)",
+                prefix,
+            ],
+        )?;
+        Ok(out
+            .lines()
+            .filter_map(|line| {
+                let (name, sha) = line.split_once(' ')?;
+                Some((name.to_string(), sha.to_string()))
+            })
+            .collect())
+    }
+
     pub fn abort_merge(&self, worktree_path: &Path) -> Result<(), GitCliError> {
         if !self.is_merge_in_progress(worktree_path)? {
             return Ok(());
@@ -11,10 +11,13 @@ use ts_rs::TS;
 use utils::diff::{Diff, DiffChangeKind};
 
 mod cli;
+mod path_filter;
+mod remote_throttle;
 mod validation;
 
 use cli::{ChangeType, StatusDiffEntry, StatusDiffOptions};
 pub use cli::{GitCli, GitCliError, StatusEntry, WorktreeStatus};
+pub use path_filter::{PathFilter, PathFilterError};
 pub use utils::path::ALWAYS_SKIP_DIRS;
 pub use validation::is_valid_branch_prefix;
 
@@ -44,7 +47,7 @@ pub enum GitServiceError {
     #[error("Merge conflicts: {message}")]
     MergeConflicts {
         message: String,
-        conflicted_files: Vec<String>,
+        conflicts: Vec<ConflictedFile>,
     },
     #[error("Branches diverged: {0}")]
     BranchesDiverged(String),
@@ -52,6 +55,8 @@ pub enum GitServiceError {
     WorktreeDirty(String, String),
     #[error("Rebase in progress; resolve or abort it before retrying")]
     RebaseInProgress,
+    #[error("Remote not found: {0}")]
+    RemoteNotFound(String),
 }
 
 /// Service for managing Git operations in task execution workflows
@@ -62,6 +67,9 @@ pub struct GitService {}
 // their contents omitted from the diff stream to avoid UI crashes.
 const MAX_INLINE_DIFF_BYTES: usize = 2 * 1024 * 1024; // ~2MB
 
+/// Comment line written above each `info/exclude` entry the service adds.
+pub const EXCLUDE_MARKER: &str = "# Added by Vibe Kanban";
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -72,6 +80,58 @@ pub enum ConflictOp {
     Revert,
 }
 
+/// How a task branch is integrated into its base branch: a merge commit, a
+/// single squashed commit, or the task commits replayed onto the base.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum MergeMethod {
+    Merge,
+    #[default]
+    Squash,
+    Rebase,
+}
+
+impl MergeMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Merge => "merge",
+            Self::Squash => "squash",
+            Self::Rebase => "rebase",
+        }
+    }
+}
+
+impl std::str::FromStr for MergeMethod {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "merge" => Ok(Self::Merge),
+            "squash" => Ok(Self::Squash),
+            "rebase" => Ok(Self::Rebase),
+            _ => Err(()),
+        }
+    }
+}
+
+/// A file left unmerged by a merge or rebase.
+#[derive(Debug, Clone, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConflictedFile {
+    pub path: String,
+    /// Number of `<<<<<<<` conflict regions in the file; 0 when the conflict
+    /// isn't textual (e.g. modify/delete).
+    pub conflict_markers: usize,
+}
+
+/// Count the conflict regions git wrote into `content`.
+pub fn count_conflict_markers(content: &[u8]) -> usize {
+    content
+        .split(|b| *b == b'\n')
+        .filter(|line| line.starts_with(b"<<<<<<<"))
+        .count()
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct GitBranch {
     pub name: String,
@@ -179,6 +239,90 @@ impl GitService {
         Ok(repo.commondir().to_path_buf())
     }
 
+    /// Appends any missing `patterns` to the repository's `info/exclude`,
+    /// each after an [`EXCLUDE_MARKER`] line so they can be removed again.
+    ///
+    /// Git only reads the file from the common dir, so entries apply to every
+    /// worktree; see [`Self::remove_excluded_if_unused`].
+    pub fn ensure_excluded(
+        &self,
+        repo_path: &Path,
+        patterns: &[&str],
+    ) -> Result<(), GitServiceError> {
+        let exclude_path = self.exclude_path(repo_path)?;
+        let existing = match std::fs::read_to_string(&exclude_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let missing: Vec<&str> = patterns
+            .iter()
+            .copied()
+            .filter(|pattern| !existing.lines().any(|line| line.trim() == *pattern))
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+
+        let mut content = existing;
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        for pattern in missing {
+            content.push_str(EXCLUDE_MARKER);
+            content.push('\n');
+            content.push_str(pattern);
+            content.push('\n');
+        }
+        if let Some(parent) = exclude_path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&exclude_path, content)?;
+        Ok(())
+    }
+
+    /// Removes the `patterns` [`Self::ensure_excluded`] added, once the
+    /// repository at `repo_path` has no linked worktrees left. Entries the
+    /// user wrote themselves are kept.
+    pub fn remove_excluded_if_unused(
+        &self,
+        repo_path: &Path,
+        patterns: &[&str],
+    ) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if !repo.worktrees()?.is_empty() {
+            return Ok(());
+        }
+        let exclude_path = repo.commondir().join("info").join("exclude");
+        let existing = match std::fs::read_to_string(&exclude_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut kept: Vec<&str> = Vec::new();
+        for line in existing.lines() {
+            if patterns.contains(&line.trim()) && kept.last() == Some(&EXCLUDE_MARKER) {
+                kept.pop();
+            } else {
+                kept.push(line);
+            }
+        }
+        let mut content = kept.join("\n");
+        if !content.is_empty() {
+            content.push('\n');
+        }
+        if content != existing {
+            std::fs::write(&exclude_path, content)?;
+        }
+        Ok(())
+    }
+
+    fn exclude_path(&self, repo_path: &Path) -> Result<PathBuf, GitServiceError> {
+        Ok(self.get_common_dir(repo_path)?.join("info").join("exclude"))
+    }
+
     /// Checks if a named worktree is valid/registered in the repository.
     pub fn validate_worktree(
         &self,
@@ -329,6 +473,18 @@ impl GitService {
         worktree_path: &Path,
         base_commit: &Commit,
         path_filter: Option<&[&str]>,
+    ) -> Result<Vec<Diff>, GitServiceError> {
+        self.get_diffs_matching(worktree_path, base_commit, path_filter, |_| true)
+    }
+
+    /// Like [`Self::get_diffs`], but only loads content for files whose new
+    /// or old path passes `keep`.
+    pub fn get_diffs_matching(
+        &self,
+        worktree_path: &Path,
+        base_commit: &Commit,
+        path_filter: Option<&[&str]>,
+        keep: impl Fn(&str) -> bool,
     ) -> Result<Vec<Diff>, GitServiceError> {
         // Use Git CLI to compute diff vs base to avoid sparse false deletions
         let repo = Repository::open(worktree_path)?;
@@ -348,6 +504,7 @@ impl GitService {
             .map_err(|e| GitServiceError::InvalidRepository(format!("git diff failed: {e}")))?;
         Ok(entries
             .into_iter()
+            .filter(|e| keep(&e.path) || e.old_path.as_deref().is_some_and(&keep))
             .map(|e| Self::status_entry_to_diff(&repo, &base_tree, e))
             .collect())
     }
@@ -571,7 +728,7 @@ impl GitService {
         Ok(None)
     }
 
-    /// Merge changes from a task branch into the base branch.
+    /// Squash-merge changes from a task branch into the base branch.
     pub fn merge_changes(
         &self,
         base_worktree_path: &Path,
@@ -580,9 +737,31 @@ impl GitService {
         base_branch_name: &str,
         commit_message: &str,
     ) -> Result<String, GitServiceError> {
-        // Open the repositories
+        self.merge_changes_with_method(
+            base_worktree_path,
+            task_worktree_path,
+            task_branch_name,
+            base_branch_name,
+            commit_message,
+            MergeMethod::Squash,
+        )
+    }
+
+    /// Merge changes from a task branch into the base branch using `method`.
+    ///
+    /// The base must not be ahead of the task branch, so `Rebase` amounts to a
+    /// fast-forward of the base onto the task commits. `commit_message` is
+    /// unused in that case.
+    pub fn merge_changes_with_method(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        base_branch_name: &str,
+        commit_message: &str,
+        method: MergeMethod,
+    ) -> Result<String, GitServiceError> {
         let task_repo = self.open_repo(task_worktree_path)?;
-        let base_repo = self.open_repo(base_worktree_path)?;
 
         // Check if base branch is ahead of task branch - this indicates the base has moved
         // ahead since the task was created, which should block the merge
@@ -616,24 +795,48 @@ impl GitService {
 
                 // Use CLI merge in base context
                 self.ensure_cli_commit_identity(&base_checkout_path)?;
-                let sha = git_cli
-                    .merge_squash_commit(
+                let merged = match method {
+                    MergeMethod::Squash => git_cli.merge_squash_commit(
                         &base_checkout_path,
                         base_branch_name,
                         task_branch_name,
                         commit_message,
-                    )
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("CLI merge failed: {e}"))
+                    ),
+                    MergeMethod::Merge => git_cli.merge_no_ff_commit(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                        commit_message,
+                    ),
+                    MergeMethod::Rebase => git_cli.merge_fast_forward(
+                        &base_checkout_path,
+                        base_branch_name,
+                        task_branch_name,
+                    ),
+                };
+                let sha = merged
+                    .map_err(|e| match e {
+                        GitCliError::CommandFailed(ref out) if out.contains("CONFLICT") => {
+                            let conflicts =
+                                self.get_conflicts(&base_checkout_path).unwrap_or_default();
+                            GitServiceError::MergeConflicts {
+                                message: format!(
+                                    "Merge into '{base_branch_name}' stopped on conflicts.{} Resolve them in {} and commit.",
+                                    Self::conflicted_files_summary(&conflicts),
+                                    base_checkout_path.display()
+                                ),
+                                conflicts,
+                            }
+                        }
+                        _ => GitServiceError::InvalidRepository(format!("CLI merge failed: {e}")),
                     })?;
 
-                // Update task branch ref for continuity
-                let task_refname = format!("refs/heads/{task_branch_name}");
-                git_cli
-                    .update_ref(base_worktree_path, &task_refname, &sha)
-                    .map_err(|e| {
-                        GitServiceError::InvalidRepository(format!("git update-ref failed: {e}"))
-                    })?;
+                self.move_task_branch_to_merge(
+                    base_worktree_path,
+                    task_worktree_path,
+                    task_branch_name,
+                    &sha,
+                )?;
 
                 Ok(sha)
             }
@@ -646,30 +849,76 @@ impl GitService {
                 let base_commit = base_branch.get().peel_to_commit()?;
                 let task_commit = task_branch.get().peel_to_commit()?;
 
-                // Create the squash commit in-memory (no checkout) and update the base branch ref
-                let signature = self.signature_with_fallback(&task_repo)?;
-                let squash_commit_id = self.perform_squash_merge(
-                    &task_repo,
-                    &base_commit,
-                    &task_commit,
-                    &signature,
-                    commit_message,
-                    base_branch_name,
+                let merged_commit_id = match method {
+                    MergeMethod::Rebase => {
+                        // Base is an ancestor of the task branch: fast-forward it.
+                        let refname = format!("refs/heads/{base_branch_name}");
+                        task_repo.reference(
+                            &refname,
+                            task_commit.id(),
+                            true,
+                            "Fast-forward merge",
+                        )?;
+                        task_commit.id()
+                    }
+                    MergeMethod::Squash | MergeMethod::Merge => {
+                        // Create the commit in-memory (no checkout) and update the base branch ref
+                        let signature = self.signature_with_fallback(&task_repo)?;
+                        self.perform_squash_merge(
+                            &task_repo,
+                            &base_commit,
+                            &task_commit,
+                            &signature,
+                            commit_message,
+                            base_branch_name,
+                            method == MergeMethod::Merge,
+                        )?
+                    }
+                };
+
+                let sha = merged_commit_id.to_string();
+                self.move_task_branch_to_merge(
+                    base_worktree_path,
+                    task_worktree_path,
+                    task_branch_name,
+                    &sha,
                 )?;
 
-                // Update the task branch to the new squash commit so follow-up
-                // work can continue from the merged state without conflicts.
-                let task_refname = format!("refs/heads/{task_branch_name}");
-                base_repo.reference(
-                    &task_refname,
-                    squash_commit_id,
-                    true,
-                    "Reset task branch after squash merge",
-                )?;
+                Ok(sha)
+            }
+        }
+    }
 
-                Ok(squash_commit_id.to_string())
+    /// Point the task branch at the merge result so follow-up work continues
+    /// from the merged state without conflicts. Where the branch is checked
+    /// out, the worktree is synced with `reset --keep` so its index and files
+    /// match the new HEAD; uncommitted changes are kept.
+    fn move_task_branch_to_merge(
+        &self,
+        base_worktree_path: &Path,
+        task_worktree_path: &Path,
+        task_branch_name: &str,
+        sha: &str,
+    ) -> Result<(), GitServiceError> {
+        let git_cli = GitCli::new();
+        let checked_out = self
+            .get_head_info(task_worktree_path)
+            .is_ok_and(|head| head.branch == task_branch_name);
+        if checked_out {
+            match git_cli.reset_keep(task_worktree_path, sha) {
+                Ok(()) => return Ok(()),
+                // The merge already landed; still move the branch so it
+                // isn't left behind the base.
+                Err(e) => tracing::warn!(
+                    "Could not sync worktree {} to merge {sha}, moving the branch only: {e}",
+                    task_worktree_path.display()
+                ),
             }
         }
+        let task_refname = format!("refs/heads/{task_branch_name}");
+        git_cli
+            .update_ref(base_worktree_path, &task_refname, sha)
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git update-ref failed: {e}")))
     }
     fn get_branch_status_inner(
         &self,
@@ -713,18 +962,50 @@ impl GitService {
         base_branch_name: &str,
     ) -> Result<Commit, GitServiceError> {
         let repo = Repository::open(repo_path)?;
-        let branch = Self::find_branch(&repo, branch_name)?;
-        let base_branch = Self::find_branch(&repo, base_branch_name)?;
+        let branch = Self::find_branch(&repo, branch_name)?
+            .get()
+            .peel_to_commit()?
+            .id();
+        let base_branch = Self::find_branch(&repo, base_branch_name)?
+            .get()
+            .peel_to_commit()?
+            .id();
         // Find the common ancestor (merge base)
-        let oid = repo
-            .merge_base(
-                branch.get().peel_to_commit()?.id(),
-                base_branch.get().peel_to_commit()?.id(),
-            )
-            .map_err(GitServiceError::from)?;
+        let oid = match repo.merge_base(branch, base_branch) {
+            Ok(oid) => oid,
+            Err(_) if repo.is_shallow() => {
+                self.unshallow(repo_path)?;
+                Repository::open(repo_path)?.merge_base(branch, base_branch)?
+            }
+            Err(e) => return Err(e.into()),
+        };
         Ok(Commit::new(oid))
     }
 
+    /// Fetch full history into a shallow clone, e.g. when a merge base lies
+    /// beyond the shallow boundary. Does nothing for a complete repository.
+    pub fn unshallow(&self, repo_path: &Path) -> Result<(), GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        if !repo.is_shallow() {
+            return Ok(());
+        }
+        let remote = self.default_remote(&repo, repo_path)?;
+        tracing::info!(
+            "Fetching full history from {} into shallow repository {}",
+            remote.name,
+            repo_path.display()
+        );
+        GitCli::new().unshallow(repo_path, &remote.url)?;
+        Ok(())
+    }
+
+    /// Resolve a commit SHA (or any revspec) to a commit in `repo_path`.
+    pub fn resolve_commit(&self, repo_path: &Path, rev: &str) -> Result<Commit, GitServiceError> {
+        let repo = Repository::open(repo_path)?;
+        let commit = repo.revparse_single(rev)?.peel_to_commit()?;
+        Ok(Commit::new(commit.id()))
+    }
+
     pub fn get_remote_branch_status(
         &self,
         repo_path: &Path,
@@ -865,7 +1146,14 @@ impl GitService {
         task_branch: &str,
     ) -> Result<String, GitServiceError> {
         let git = GitCli::new();
-        Ok(git.merge_base(worktree_path, target_branch, task_branch)?)
+        match git.merge_base(worktree_path, target_branch, task_branch) {
+            Ok(base) => Ok(base),
+            Err(e) if !self.open_repo(worktree_path)?.is_shallow() => Err(e.into()),
+            Err(_) => {
+                self.unshallow(worktree_path)?;
+                Ok(git.merge_base(worktree_path, target_branch, task_branch)?)
+            }
+        }
     }
 
     /// Return the full worktree status including all entries
@@ -1078,7 +1366,10 @@ impl GitService {
         Ok(branches)
     }
 
-    /// Perform a squash merge of task branch into base branch, but fail on conflicts
+    /// Perform a squash merge of task branch into base branch, but fail on conflicts.
+    /// With `keep_task_parent` the commit also records the task commit as a
+    /// parent, making it a regular merge commit.
+    #[allow(clippy::too_many_arguments)]
     fn perform_squash_merge(
         &self,
         repo: &Repository,
@@ -1087,20 +1378,23 @@ impl GitService {
         signature: &git2::Signature,
         commit_message: &str,
         base_branch_name: &str,
+        keep_task_parent: bool,
     ) -> Result<git2::Oid, GitServiceError> {
         // In-memory merge to detect conflicts without touching the working tree
         let mut merge_opts = git2::MergeOptions::new();
         // Safety and correctness options
         merge_opts.find_renames(true); // improve rename handling
-        merge_opts.fail_on_conflict(true); // bail out instead of generating conflicted index
         let mut index = repo.merge_commits(base_commit, task_commit, Some(&merge_opts))?;
 
-        // If there are conflicts, return an error
+        // If there are conflicts, report them without writing anything
         if index.has_conflicts() {
+            let conflicts = Self::index_conflicts(repo, &index)?;
             return Err(GitServiceError::MergeConflicts {
-                message: "Merge failed due to conflicts. Please resolve conflicts manually."
-                    .to_string(),
-                conflicted_files: vec![],
+                message: format!(
+                    "Merge failed due to conflicts.{} Please resolve conflicts manually.",
+                    Self::conflicted_files_summary(&conflicts)
+                ),
+                conflicts,
             });
         }
 
@@ -1108,14 +1402,19 @@ impl GitService {
         let tree_id = index.write_tree_to(repo)?;
         let tree = repo.find_tree(tree_id)?;
 
-        // Create a squash commit: use merged tree with base_commit as sole parent
+        // Use the merged tree with base_commit as sole parent for a squash
+        let parents: &[&git2::Commit] = if keep_task_parent {
+            &[base_commit, task_commit]
+        } else {
+            &[base_commit]
+        };
         let squash_commit_id = repo.commit(
             None,           // Don't update any reference yet
             signature,      // Author
             signature,      // Committer
             commit_message, // Custom message
             &tree,          // Merged tree content
-            &[base_commit], // Single parent: base branch commit
+            parents,        // Base branch commit, plus the task commit for merges
         )?;
 
         // Update the base branch reference to point to the new commit
@@ -1177,32 +1476,14 @@ impl GitService {
                         .and_then(|h| h.shorthand().map(|s| s.to_string()))
                         .unwrap_or_else(|| "(unknown)".to_string());
                     // List conflicted files (best-effort)
-                    let conflicted_files =
-                        git.get_conflicted_files(worktree_path).unwrap_or_default();
-                    let files_part = if conflicted_files.is_empty() {
-                        "".to_string()
-                    } else {
-                        let mut sample = conflicted_files.clone();
-                        let total = sample.len();
-                        sample.truncate(10);
-                        let list = sample.join(", ");
-                        if total > sample.len() {
-                            format!(
-                                " Conflicted files (showing {} of {}): {}.",
-                                sample.len(),
-                                total,
-                                list
-                            )
-                        } else {
-                            format!(" Conflicted files: {list}.")
-                        }
-                    };
+                    let conflicts = self.get_conflicts(worktree_path).unwrap_or_default();
+                    let files_part = Self::conflicted_files_summary(&conflicts);
                     let msg = format!(
                         "Rebase encountered merge conflicts while rebasing '{attempt_branch}' onto '{new_base_branch}'.{files_part} Resolve conflicts and then continue or abort."
                     );
                     return Err(GitServiceError::MergeConflicts {
                         message: msg,
-                        conflicted_files,
+                        conflicts,
                     });
                 }
                 return Err(GitServiceError::InvalidRepository(format!(
@@ -1253,6 +1534,15 @@ impl GitService {
         }
     }
 
+    pub fn check_local_branch_exists(
+        &self,
+        repo_path: &Path,
+        branch_name: &str,
+    ) -> Result<bool, GitServiceError> {
+        let repo = self.open_repo(repo_path)?;
+        Ok(repo.find_branch(branch_name, BranchType::Local).is_ok())
+    }
+
     pub fn rename_local_branch(
         &self,
         worktree_path: &Path,
@@ -1314,6 +1604,166 @@ impl GitService {
         })
     }
 
+    /// List conflicted files in the worktree with the conflict markers left in
+    /// each.
+    pub fn get_conflicts(
+        &self,
+        worktree_path: &Path,
+    ) -> Result<Vec<ConflictedFile>, GitServiceError> {
+        let conflicts = self
+            .get_conflicted_files(worktree_path)?
+            .into_iter()
+            .map(|path| {
+                let conflict_markers = std::fs::read(worktree_path.join(&path))
+                    .map(|content| count_conflict_markers(&content))
+                    .unwrap_or(0);
+                ConflictedFile {
+                    path,
+                    conflict_markers,
+                }
+            })
+            .collect();
+        Ok(conflicts)
+    }
+
+    /// Conflicts in an in-memory merge index, with markers counted on the
+    /// merged text git would have written to the worktree.
+    fn index_conflicts(
+        repo: &Repository,
+        index: &git2::Index,
+    ) -> Result<Vec<ConflictedFile>, GitServiceError> {
+        let mut conflicts = Vec::new();
+        for conflict in index.conflicts()? {
+            let conflict = conflict?;
+            let Some(path) = [&conflict.our, &conflict.their, &conflict.ancestor]
+                .into_iter()
+                .flatten()
+                .next()
+                .map(|entry| String::from_utf8_lossy(&entry.path).to_string())
+            else {
+                continue;
+            };
+            let conflict_markers = match (&conflict.ancestor, &conflict.our, &conflict.their) {
+                (Some(ancestor), Some(ours), Some(theirs)) => repo
+                    .merge_file_from_index(ancestor, ours, theirs, None)
+                    .map(|result| count_conflict_markers(result.content()))
+                    .unwrap_or(0),
+                _ => 0,
+            };
+            conflicts.push(ConflictedFile {
+                path,
+                conflict_markers,
+            });
+        }
+        Ok(conflicts)
+    }
+
+    /// " Conflicted files: a, b." for error messages, capped at 10 files.
+    fn conflicted_files_summary(conflicts: &[ConflictedFile]) -> String {
+        if conflicts.is_empty() {
+            return String::new();
+        }
+        let total = conflicts.len();
+        let list = conflicts
+            .iter()
+            .take(10)
+            .map(|c| c.path.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        if total > 10 {
+            format!(" Conflicted files (showing 10 of {total}): {list}.")
+        } else {
+            format!(" Conflicted files: {list}.")
+        }
+    }
+
+    /// Snapshots kept per workspace; taking another drops the oldest.
+    pub const MAX_WORKTREE_SNAPSHOTS: usize = 20;
+
+    /// Ref under which a workspace snapshot is kept alive for a repo.
+    fn snapshot_ref(workspace_id: &str, snapshot_id: &str) -> String {
+        format!("refs/vibe-kanban/snapshots/{workspace_id}/{snapshot_id}")
+    }
+
+    /// Capture the current worktree state, including untracked files, so it can
+    /// be restored later with `restore_worktree_snapshot`. Returns the snapshot
+    /// commit. Only the newest `MAX_WORKTREE_SNAPSHOTS` are kept.
+    pub fn snapshot_worktree(
+        &self,
+        worktree_path: &Path,
+        workspace_id: &str,
+        snapshot_id: &str,
+    ) -> Result<String, GitServiceError> {
+        let git = GitCli::new();
+        let commit = git
+            .snapshot_worktree(
+                worktree_path,
+                &Self::snapshot_ref(workspace_id, snapshot_id),
+                &format!("vibe-kanban snapshot {snapshot_id}"),
+            )
+            .map_err(|e| GitServiceError::InvalidRepository(format!("git snapshot failed: {e}")))?;
+
+        let snapshots = self.list_worktree_snapshots(worktree_path, workspace_id)?;
+        let excess = snapshots.len().saturating_sub(Self::MAX_WORKTREE_SNAPSHOTS);
+        for old in snapshots.iter().take(excess) {
+            if let Err(e) = git.delete_ref(worktree_path, &Self::snapshot_ref(workspace_id, old)) {
+                tracing::warn!("Failed to drop old snapshot {}: {}", old, e);
+            }
+        }
+        Ok(commit)
+    }
+
+    /// Roll the worktree back to a snapshot taken with `snapshot_worktree`.
+    pub fn restore_worktree_snapshot(
+        &self,
+        worktree_path: &Path,
+        workspace_id: &str,
+        snapshot_id: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        git.restore_worktree_snapshot(
+            worktree_path,
+            &Self::snapshot_ref(workspace_id, snapshot_id),
+        )
+        .map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git snapshot restore failed: {e}"))
+        })
+    }
+
+    /// Drop every snapshot of a workspace, e.g. once the workspace is deleted.
+    pub fn delete_worktree_snapshots(
+        &self,
+        repo_path: &Path,
+        workspace_id: &str,
+    ) -> Result<(), GitServiceError> {
+        let git = GitCli::new();
+        for snapshot_id in self.list_worktree_snapshots(repo_path, workspace_id)? {
+            git.delete_ref(repo_path, &Self::snapshot_ref(workspace_id, &snapshot_id))
+                .map_err(|e| {
+                    GitServiceError::InvalidRepository(format!("git update-ref -d failed: {e}"))
+                })?;
+        }
+        Ok(())
+    }
+
+    /// Snapshot ids recorded for a workspace in this repo, oldest first. Refs
+    /// are shared between a repo and its worktrees, so either path works.
+    pub fn list_worktree_snapshots(
+        &self,
+        repo_path: &Path,
+        workspace_id: &str,
+    ) -> Result<Vec<String>, GitServiceError> {
+        let git = GitCli::new();
+        let prefix = Self::snapshot_ref(workspace_id, "");
+        let refs = git.list_refs(repo_path, &prefix).map_err(|e| {
+            GitServiceError::InvalidRepository(format!("git for-each-ref failed: {e}"))
+        })?;
+        Ok(refs
+            .into_iter()
+            .filter_map(|(name, _)| name.strip_prefix(&prefix).map(str::to_string))
+            .collect())
+    }
+
     /// Abort an in-progress rebase in this worktree (no-op if none).
     pub fn abort_rebase(&self, worktree_path: &Path) -> Result<(), GitServiceError> {
         let git = GitCli::new();
@@ -1420,6 +1870,14 @@ impl GitService {
         self.default_remote(&repo, repo_path)
     }
 
+    /// The remote called `name`, or [`GitServiceError::RemoteNotFound`].
+    pub fn find_remote(&self, repo_path: &Path, name: &str) -> Result<GitRemote, GitServiceError> {
+        self.list_remotes(repo_path)?
+            .into_iter()
+            .find(|remote| remote.name == name)
+            .ok_or_else(|| GitServiceError::RemoteNotFound(name.to_string()))
+    }
+
     pub fn list_remotes(&self, repo_path: &Path) -> Result<Vec<GitRemote>, GitServiceError> {
         let cli = GitCli::new();
         let remotes = cli.list_remotes(repo_path)?;
@@ -1476,17 +1934,23 @@ impl GitService {
         })
     }
 
+    /// Push `branch_name` to `remote_name`, or to the default remote when
+    /// `None`, and set it as the branch's upstream.
     pub fn push_to_remote(
         &self,
         worktree_path: &Path,
         branch_name: &str,
+        remote_name: Option<&str>,
         force: bool,
     ) -> Result<(), GitServiceError> {
         let repo = Repository::open(worktree_path)?;
         self.check_worktree_clean(&repo)?;
 
         // Get the remote
-        let remote = self.default_remote(&repo, worktree_path)?;
+        let remote = match remote_name {
+            Some(name) => self.find_remote(worktree_path, name)?,
+            None => self.default_remote(&repo, worktree_path)?,
+        };
 
         let git_cli = GitCli::new();
         if let Err(e) = git_cli.push(worktree_path, &remote.url, branch_name, force) {
@@ -1606,7 +2070,10 @@ impl GitService {
         let mut builder = git2::build::RepoBuilder::new();
         builder.fetch_options(fetch_opts);
 
-        let repo = builder.clone(clone_url, target_path)?;
+        let repo = {
+            let _permit = remote_throttle::acquire(clone_url);
+            builder.clone(clone_url, target_path)?
+        };
 
         tracing::info!(
             "Successfully cloned repository from {} to {}",
@@ -0,0 +1,103 @@
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+#[error("Invalid path pattern '{pattern}': {message}")]
+pub struct PathFilterError {
+    pub pattern: String,
+    pub message: String,
+}
+
+/// Comma-separated path globs such as `src/**,!**/*.lock`. A path matches
+/// when it matches any plain pattern (or there are none) and no `!` pattern.
+/// `*` stays within one path segment, as in git's `:(glob)` pathspecs.
+#[derive(Debug, Clone)]
+pub struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    pub fn parse(patterns: &str) -> Result<Self, PathFilterError> {
+        let mut include = GlobSetBuilder::new();
+        let mut exclude = GlobSetBuilder::new();
+        let mut has_include = false;
+
+        for pattern in patterns.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (negated, glob) = match pattern.strip_prefix('!') {
+                Some(rest) => (true, rest.trim()),
+                None => (false, pattern),
+            };
+            if glob.is_empty() {
+                return Err(PathFilterError {
+                    pattern: pattern.to_string(),
+                    message: "empty pattern".to_string(),
+                });
+            }
+            let glob = GlobBuilder::new(glob)
+                .literal_separator(true)
+                .build()
+                .map_err(|e| PathFilterError {
+                    pattern: pattern.to_string(),
+                    message: e.kind().to_string(),
+                })?;
+            if negated {
+                exclude.add(glob);
+            } else {
+                include.add(glob);
+                has_include = true;
+            }
+        }
+
+        let build = |builder: GlobSetBuilder| {
+            builder.build().map_err(|e| PathFilterError {
+                pattern: patterns.to_string(),
+                message: e.to_string(),
+            })
+        };
+        Ok(Self {
+            include: if has_include {
+                Some(build(include)?)
+            } else {
+                None
+            },
+            exclude: build(exclude)?,
+        })
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.include.as_ref().is_none_or(|set| set.is_match(path)) && !self.exclude.is_match(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn includes_and_negations() {
+        let filter = PathFilter::parse("src/**, !**/*.lock").unwrap();
+        assert!(filter.matches("src/main.rs"));
+        assert!(filter.matches("src/nested/mod.rs"));
+        assert!(!filter.matches("docs/readme.md"));
+        assert!(!filter.matches("src/Cargo.lock"));
+
+        let only_negated = PathFilter::parse("!**/*.lock").unwrap();
+        assert!(only_negated.matches("README.md"));
+        assert!(!only_negated.matches("Cargo.lock"));
+    }
+
+    #[test]
+    fn star_stays_within_a_segment() {
+        let filter = PathFilter::parse("src/*.rs").unwrap();
+        assert!(filter.matches("src/lib.rs"));
+        assert!(!filter.matches("src/nested/lib.rs"));
+    }
+
+    #[test]
+    fn rejects_malformed_patterns() {
+        assert!(PathFilter::parse("src/[abc").is_err());
+        assert!(PathFilter::parse("src/{a").is_err());
+        assert!(PathFilter::parse("!").is_err());
+    }
+}
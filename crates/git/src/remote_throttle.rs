@@ -0,0 +1,273 @@
+//! Per-host limit on concurrent git network operations, so bursts of workspace
+//! creation against one remote don't trip the host's rate limits.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Condvar, LazyLock, Mutex},
+    time::{Duration, Instant},
+};
+
+use tokio::runtime::{Handle, RuntimeFlavor};
+use utils::env::positive_or;
+
+const DEFAULT_CONCURRENCY_PER_HOST: usize = 4;
+
+/// Longest a git operation waits for a slot before running anyway, so a slot
+/// that is never released can't wedge every later fetch against the host.
+const MAX_WAIT: Duration = Duration::from_secs(300);
+
+static THROTTLE: LazyLock<RemoteThrottle> =
+    LazyLock::new(|| RemoteThrottle::new(concurrency_per_host(), MAX_WAIT));
+
+/// How many fetches/clones may run against the same host at once.
+/// Override with `VK_GIT_NETWORK_CONCURRENCY_PER_HOST`.
+fn concurrency_per_host() -> usize {
+    positive_or(
+        "VK_GIT_NETWORK_CONCURRENCY_PER_HOST",
+        DEFAULT_CONCURRENCY_PER_HOST,
+    )
+}
+
+/// Wait until a slot for the host of `remote_url` is free. Local paths and
+/// `file://` remotes are not throttled, and neither is an operation that has
+/// waited longer than [`MAX_WAIT`].
+pub(crate) fn acquire(remote_url: &str) -> Option<RemotePermit> {
+    THROTTLE.acquire(remote_url)
+}
+
+struct RemoteThrottle {
+    limit: usize,
+    max_wait: Duration,
+    hosts: Mutex<HashMap<String, Arc<HostSlots>>>,
+}
+
+/// Counting semaphore on std primitives: git operations are synchronous, so
+/// waiting never needs an async runtime.
+#[derive(Default)]
+struct HostSlots {
+    in_use: Mutex<usize>,
+    freed: Condvar,
+}
+
+impl HostSlots {
+    fn try_take(&self, limit: usize) -> bool {
+        let mut in_use = self.in_use.lock().unwrap();
+        if *in_use < limit {
+            *in_use += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Block until a slot is free or `max_wait` passes. Returns whether a slot
+    /// was taken.
+    fn take(&self, limit: usize, max_wait: Duration) -> bool {
+        let deadline = Instant::now() + max_wait;
+        let mut in_use = self.in_use.lock().unwrap();
+        while *in_use >= limit {
+            let Some(left) = deadline.checked_duration_since(Instant::now()) else {
+                return false;
+            };
+            in_use = self.freed.wait_timeout(in_use, left).unwrap().0;
+        }
+        *in_use += 1;
+        true
+    }
+
+    fn release(&self) {
+        *self.in_use.lock().unwrap() -= 1;
+        self.freed.notify_one();
+    }
+}
+
+/// Held for the duration of a network operation; dropping it frees the slot.
+pub(crate) struct RemotePermit {
+    slots: Option<Arc<HostSlots>>,
+}
+
+impl Drop for RemotePermit {
+    fn drop(&mut self) {
+        if let Some(slots) = self.slots.take() {
+            slots.release();
+        }
+    }
+}
+
+impl RemoteThrottle {
+    fn new(limit: usize, max_wait: Duration) -> Self {
+        Self {
+            limit,
+            max_wait,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn acquire(&self, remote_url: &str) -> Option<RemotePermit> {
+        let host = remote_host(remote_url)?;
+        let slots = self
+            .hosts
+            .lock()
+            .unwrap()
+            .entry(host.clone())
+            .or_default()
+            .clone();
+        if slots.try_take(self.limit) {
+            return Some(RemotePermit { slots: Some(slots) });
+        }
+
+        tracing::debug!("Waiting for a git network slot for {host}");
+        if wait_for_slot(|| slots.take(self.limit, self.max_wait)) {
+            Some(RemotePermit { slots: Some(slots) })
+        } else {
+            tracing::warn!(
+                "No git network slot for {host} after {}s; running without one",
+                self.max_wait.as_secs()
+            );
+            Some(RemotePermit { slots: None })
+        }
+    }
+}
+
+/// Git operations are synchronous but mostly called from async handlers. On a
+/// multi-threaded runtime worker the wait goes through `block_in_place`, so the
+/// worker's other tasks move to another thread instead of stalling behind it.
+/// Anywhere else the thread just blocks, bounded by the throttle's max wait.
+fn wait_for_slot(wait: impl FnOnce() -> bool) -> bool {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(wait)
+        }
+        _ => wait(),
+    }
+}
+
+/// Host part of a git remote URL, lowercased. Handles `scheme://[user@]host[:port]/…`
+/// and scp-style `user@host:path`. Returns `None` for local paths.
+fn remote_host(remote_url: &str) -> Option<String> {
+    let host = if let Some((scheme, rest)) = remote_url.split_once("://") {
+        if scheme.eq_ignore_ascii_case("file") {
+            return None;
+        }
+        let authority = rest.split('/').next().unwrap_or(rest);
+        let host_port = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+        if let Some(bracketed) = host_port.strip_prefix('[') {
+            bracketed.split(']').next().unwrap_or(bracketed)
+        } else {
+            host_port.split(':').next().unwrap_or(host_port)
+        }
+    } else {
+        // scp-style: a colon before any slash, e.g. git@github.com:owner/repo.git
+        let (before_colon, _) = remote_url.split_once(':')?;
+        if before_colon.contains('/') || before_colon.len() <= 1 {
+            // Relative/absolute path, or a Windows drive letter.
+            return None;
+        }
+        before_colon
+            .rsplit_once('@')
+            .map_or(before_colon, |(_, h)| h)
+    };
+
+    (!host.is_empty()).then(|| host.to_ascii_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    #[test]
+    fn parses_remote_hosts() {
+        assert_eq!(
+            remote_host("https://GitHub.com/owner/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(
+            remote_host("ssh://git@example.com:2222/owner/repo").as_deref(),
+            Some("example.com")
+        );
+        assert_eq!(
+            remote_host("git@github.com:owner/repo.git").as_deref(),
+            Some("github.com")
+        );
+        assert_eq!(remote_host("/tmp/repo.git"), None);
+        assert_eq!(remote_host("file:///tmp/repo.git"), None);
+        assert_eq!(remote_host("C:\\repos\\repo"), None);
+    }
+
+    #[test]
+    fn limits_concurrency_per_host_only() {
+        let throttle = Arc::new(RemoteThrottle::new(1, MAX_WAIT));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let active = Arc::new(AtomicUsize::new(0));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let throttle = throttle.clone();
+                let peak = peak.clone();
+                let active = active.clone();
+                thread::spawn(move || {
+                    let _permit = throttle.acquire("https://github.com/a/b.git");
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    thread::sleep(Duration::from_millis(10));
+                    active.fetch_sub(1, Ordering::SeqCst);
+                })
+            })
+            .collect();
+
+        // A different host is not blocked by the busy one.
+        let held = throttle.acquire("https://github.com/a/b.git");
+        assert!(throttle.acquire("https://gitlab.com/a/b.git").is_some());
+        drop(held);
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    async fn waiting_does_not_stall_the_runtime() {
+        let throttle = Arc::new(RemoteThrottle::new(1, MAX_WAIT));
+        let held = throttle.acquire("https://github.com/a/b.git");
+
+        // Both tasks share the only worker; the release can only run if the
+        // waiter hands the worker off while it waits.
+        let release = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(held);
+        });
+        let waiter = tokio::spawn({
+            let throttle = throttle.clone();
+            async move { throttle.acquire("https://github.com/a/b.git").is_some() }
+        });
+
+        release.await.unwrap();
+        assert!(waiter.await.unwrap());
+    }
+
+    #[test]
+    fn gives_up_waiting_after_max_wait() {
+        let throttle = RemoteThrottle::new(1, Duration::from_millis(20));
+        let held = throttle.acquire("https://github.com/a/b.git").unwrap();
+        assert!(held.slots.is_some());
+
+        let unthrottled = throttle.acquire("https://github.com/a/b.git").unwrap();
+        assert!(unthrottled.slots.is_none());
+
+        // Dropping the unthrottled permit must not free the held slot.
+        drop(unthrottled);
+        assert!(!throttle.hosts.lock().unwrap()["github.com"].try_take(1));
+        drop(held);
+        assert!(throttle.hosts.lock().unwrap()["github.com"].try_take(1));
+    }
+}
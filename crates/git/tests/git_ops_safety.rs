@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitCliError, GitService};
+use git::{ConflictedFile, GitCli, GitCliError, GitService, GitServiceError, MergeMethod};
 use git2::{PushOptions, Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 // Avoid direct git CLI usage in tests; exercise GitService instead.
@@ -681,6 +681,53 @@ fn libgit2_merge_updates_task_ref_and_feature_head_preserves_dirty() {
     assert_eq!(head.oid, sha);
 }
 
+#[test]
+fn libgit2_merge_methods_shape_base_history() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_repo_with_worktree(&td);
+    let s = GitService::new();
+    let base_before = s.get_branch_oid(&repo_path, "main").unwrap();
+    let feature_head = s.get_branch_oid(&repo_path, "feature").unwrap();
+
+    // Rebase fast-forwards the base onto the task commits
+    let sha = s
+        .merge_changes_with_method(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "unused",
+            MergeMethod::Rebase,
+        )
+        .expect("fast-forward should succeed");
+    assert_eq!(sha, feature_head);
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), feature_head);
+    assert_ne!(sha, base_before);
+    let base_before = sha;
+
+    // Merge records both the base and the task commit as parents
+    write_file(&worktree_path, "more.txt", "more\n");
+    let wt_repo = Repository::open(&worktree_path).unwrap();
+    commit_all(&wt_repo, "more feature work");
+    let feature_head = s.get_branch_oid(&repo_path, "feature").unwrap();
+    let sha = s
+        .merge_changes_with_method(
+            &repo_path,
+            &worktree_path,
+            "feature",
+            "main",
+            "merge feature",
+            MergeMethod::Merge,
+        )
+        .expect("merge commit should succeed");
+    let repo = Repository::open(&repo_path).unwrap();
+    let commit = repo
+        .find_commit(git2::Oid::from_str(&sha).unwrap())
+        .unwrap();
+    let parents: Vec<String> = commit.parent_ids().map(|id| id.to_string()).collect();
+    assert_eq!(parents, vec![base_before, feature_head]);
+}
+
 #[test]
 fn rebase_refuses_to_abort_existing_rebase() {
     let td = TempDir::new().unwrap();
@@ -711,6 +758,36 @@ fn rebase_refuses_to_abort_existing_rebase() {
     // Note: We do not auto-abort; user should resolve or abort explicitly
 }
 
+#[test]
+fn rebase_conflict_lists_files_with_markers() {
+    let td = TempDir::new().unwrap();
+    let (repo_path, worktree_path) = setup_conflict_repo_with_worktree(&td);
+
+    let g = GitService::new();
+    let err = g
+        .rebase_branch(
+            &repo_path,
+            &worktree_path,
+            "new-base",
+            "old-base",
+            "feature",
+        )
+        .expect_err("rebase should stop on conflicts");
+
+    match err {
+        GitServiceError::MergeConflicts { conflicts, .. } => {
+            assert_eq!(
+                conflicts,
+                vec![ConflictedFile {
+                    path: "conflict.txt".to_string(),
+                    conflict_markers: 1,
+                }]
+            );
+        }
+        other => panic!("expected MergeConflicts, got {other:?}"),
+    }
+}
+
 #[test]
 fn rebase_fast_forwards_when_no_unique_commits() {
     let td = TempDir::new().unwrap();
@@ -943,6 +1020,52 @@ fn merge_refreshes_main_worktree_when_on_base() {
     assert_eq!(oid, merge_sha);
 }
 
+#[test]
+fn kept_worktree_stays_in_sync_across_repeated_merges() {
+    let td = TempDir::new().unwrap();
+    let repo_path = td.path().join("repo_twice");
+    let s = GitService::new();
+    s.initialize_repo_with_main_branch(&repo_path).unwrap();
+    let repo = Repository::open(&repo_path).unwrap();
+    configure_user(&repo);
+    checkout_branch(&repo, "main");
+    write_file(&repo_path, "file.txt", "base\n");
+    let _ = s.commit(&repo_path, "add base").unwrap();
+
+    create_branch_from_head(&repo, "feature");
+    let wt = td.path().join("wt_twice");
+    s.add_worktree(&repo_path, &wt, "feature", false).unwrap();
+    write_file(&wt, "file.txt", "first change\n");
+    let _ = s.commit(&wt, "first change").unwrap();
+
+    // First merge; the workspace stays active
+    let first = s
+        .merge_changes(&repo_path, &wt, "feature", "main", "first")
+        .unwrap();
+    let git = GitCli::new();
+    assert_eq!(s.get_head_info(&wt).unwrap().oid, first);
+    assert!(!git.has_changes(&wt).unwrap());
+
+    // The base moves on, and the task branch is rebased onto it
+    write_file(&repo_path, "upstream.txt", "upstream\n");
+    let _ = s.commit(&repo_path, "upstream change").unwrap();
+    s.rebase_branch(&repo_path, &wt, "main", "main", "feature")
+        .unwrap();
+    write_file(&wt, "file.txt", "second change\n");
+    let _ = s.commit(&wt, "second change").unwrap();
+
+    // Second merge; the worktree matches the merge result
+    let second = s
+        .merge_changes(&repo_path, &wt, "feature", "main", "second")
+        .unwrap();
+    assert_eq!(s.get_branch_oid(&repo_path, "main").unwrap(), second);
+    assert_eq!(s.get_head_info(&wt).unwrap().oid, second);
+    assert!(!git.has_changes(&wt).unwrap());
+    let content = std::fs::read_to_string(wt.join("file.txt")).unwrap();
+    assert_eq!(content, "second change\n");
+    assert!(wt.join("upstream.txt").exists());
+}
+
 #[test]
 fn sparse_checkout_respected_in_worktree_diffs_and_commit() {
     let td = TempDir::new().unwrap();
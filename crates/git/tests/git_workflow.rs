@@ -4,7 +4,7 @@ use std::{
     path::{Path, PathBuf},
 };
 
-use git::{GitCli, GitService};
+use git::{GitCli, GitService, GitServiceError};
 use git2::{Repository, build::CheckoutBuilder};
 use tempfile::TempDir;
 use utils::diff::DiffChangeKind;
@@ -429,3 +429,230 @@ fn squash_merge_libgit2_sets_author_without_user() {
         assert_eq!(email.as_deref(), Some("noreply@vibekanban.com"));
     }
 }
+
+#[test]
+fn ensure_excluded_hides_agent_files_from_status() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let cli = GitCli::new();
+
+    write_file(&repo_path, ".claude/settings.local.json", "{}\n");
+    let status = cli.git(&repo_path, ["status", "--porcelain"]).unwrap();
+    assert!(status.contains(".claude/"));
+
+    s.ensure_excluded(&repo_path, &[".claude/settings.local.json"])
+        .unwrap();
+    s.ensure_excluded(&repo_path, &[".claude/settings.local.json"])
+        .unwrap();
+    let status = cli.git(&repo_path, ["status", "--porcelain"]).unwrap();
+    assert!(!status.contains(".claude/"));
+
+    let exclude = fs::read_to_string(repo_path.join(".git/info/exclude")).unwrap();
+    assert_eq!(exclude.matches(".claude/settings.local.json").count(), 1);
+}
+
+#[test]
+fn remove_excluded_keeps_user_entries_until_worktrees_are_gone() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let cli = GitCli::new();
+    let exclude_path = repo_path.join(".git/info/exclude");
+    fs::write(&exclude_path, "*.log\n").unwrap();
+
+    s.ensure_excluded(&repo_path, &[".claude/settings.local.json"])
+        .unwrap();
+    let worktree_path = td.path().join("wt");
+    cli.git(
+        &repo_path,
+        [
+            "worktree",
+            "add",
+            "-b",
+            "feature",
+            worktree_path.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+
+    // Still used by the linked worktree
+    s.remove_excluded_if_unused(&repo_path, &[".claude/settings.local.json"])
+        .unwrap();
+    let exclude = fs::read_to_string(&exclude_path).unwrap();
+    assert!(exclude.contains(".claude/settings.local.json"));
+
+    cli.git(
+        &repo_path,
+        ["worktree", "remove", worktree_path.to_str().unwrap()],
+    )
+    .unwrap();
+    s.remove_excluded_if_unused(&repo_path, &[".claude/settings.local.json"])
+        .unwrap();
+    assert_eq!(fs::read_to_string(&exclude_path).unwrap(), "*.log\n");
+}
+
+#[test]
+fn snapshot_and_restore_worktree() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let s = GitService::new();
+    let head_before = s.get_head_info(&repo_path).unwrap().oid;
+
+    write_file(&repo_path, "tracked.txt", "one\n");
+    add_path(&repo_path, "tracked.txt");
+    s.commit(&repo_path, "add tracked").unwrap();
+    let head_at_snapshot = s.get_head_info(&repo_path).unwrap().oid;
+    write_file(&repo_path, "tracked.txt", "two\n");
+    write_file(&repo_path, "untracked.txt", "keep\n");
+
+    s.snapshot_worktree(&repo_path, "ws", "snap1").unwrap();
+    assert_eq!(
+        s.list_worktree_snapshots(&repo_path, "ws").unwrap(),
+        vec!["snap1".to_string()]
+    );
+    // Taking a snapshot leaves the worktree untouched
+    assert_eq!(
+        fs::read_to_string(repo_path.join("tracked.txt")).unwrap(),
+        "two\n"
+    );
+
+    // Make a mess: commit, edit, add and delete files
+    s.commit(&repo_path, "agent commit").unwrap();
+    write_file(&repo_path, "tracked.txt", "three\n");
+    write_file(&repo_path, "new.txt", "remove me\n");
+    fs::remove_file(repo_path.join("untracked.txt")).unwrap();
+
+    s.restore_worktree_snapshot(&repo_path, "ws", "snap1")
+        .unwrap();
+
+    assert_eq!(s.get_head_info(&repo_path).unwrap().oid, head_at_snapshot);
+    assert_ne!(head_at_snapshot, head_before);
+    assert_eq!(
+        fs::read_to_string(repo_path.join("tracked.txt")).unwrap(),
+        "two\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo_path.join("untracked.txt")).unwrap(),
+        "keep\n"
+    );
+    assert!(!repo_path.join("new.txt").exists());
+    let status = GitCli::new()
+        .git(&repo_path, ["status", "--porcelain"])
+        .unwrap();
+    assert!(status.contains("?? untracked.txt"));
+}
+
+#[test]
+fn get_base_commit_unshallows_when_merge_base_is_missing() {
+    let td = TempDir::new().unwrap();
+    let origin = init_repo_main(&td);
+    let s = GitService::new();
+    write_file(&origin, "a.txt", "a\n");
+    s.commit(&origin, "fork point").unwrap();
+    let fork_point = s.get_branch_oid(&origin, "main").unwrap();
+
+    create_branch(&origin, "feature");
+    checkout_branch(&origin, "feature");
+    write_file(&origin, "b.txt", "b\n");
+    s.commit(&origin, "feature work").unwrap();
+    checkout_branch(&origin, "main");
+    write_file(&origin, "c.txt", "c\n");
+    s.commit(&origin, "main work").unwrap();
+
+    // Both tips are at depth 1, so the fork point isn't in the clone.
+    let clone = td.path().join("clone");
+    let git = GitCli::new();
+    git.git(
+        td.path(),
+        [
+            "clone",
+            "--depth",
+            "1",
+            "--no-single-branch",
+            &format!("file://{}", origin.display()),
+            clone.to_str().unwrap(),
+        ],
+    )
+    .unwrap();
+    git.git(&clone, ["branch", "feature", "origin/feature"])
+        .unwrap();
+    assert!(Repository::open(&clone).unwrap().is_shallow());
+
+    let base_commit = s.get_base_commit(&clone, "feature", "main").unwrap();
+    assert_eq!(base_commit.as_oid().to_string(), fork_point);
+    assert!(!Repository::open(&clone).unwrap().is_shallow());
+}
+
+fn init_bare(root: &TempDir, name: &str) -> PathBuf {
+    let path = root.path().join(name);
+    Repository::init_bare(&path).unwrap();
+    path
+}
+
+#[test]
+fn find_remote_by_name() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let fork = init_bare(&td, "fork.git");
+    let fork_url = format!("file://{}", fork.display());
+    let git = GitCli::new();
+    git.git(&repo_path, ["remote", "add", "fork", &fork_url])
+        .unwrap();
+
+    let s = GitService::new();
+    let remote = s.find_remote(&repo_path, "fork").unwrap();
+    assert_eq!(remote.name, "fork");
+    assert_eq!(remote.url, fork_url);
+    assert!(matches!(
+        s.find_remote(&repo_path, "upstream"),
+        Err(GitServiceError::RemoteNotFound(name)) if name == "upstream"
+    ));
+}
+
+#[test]
+fn push_to_named_remote() {
+    let td = TempDir::new().unwrap();
+    let repo_path = init_repo_main(&td);
+    let origin = init_bare(&td, "origin.git");
+    let fork = init_bare(&td, "fork.git");
+    let git = GitCli::new();
+    for (name, path) in [("origin", &origin), ("fork", &fork)] {
+        git.git(
+            &repo_path,
+            ["remote", "add", name, &format!("file://{}", path.display())],
+        )
+        .unwrap();
+    }
+
+    let s = GitService::new();
+    create_branch(&repo_path, "feature");
+    checkout_branch(&repo_path, "feature");
+    write_file(&repo_path, "f.txt", "f\n");
+    s.commit(&repo_path, "feature work").unwrap();
+    s.push_to_remote(&repo_path, "feature", Some("fork"), false)
+        .unwrap();
+
+    let feature_oid = s.get_branch_oid(&repo_path, "feature").unwrap();
+    let pushed = Repository::open_bare(&fork).unwrap();
+    assert_eq!(
+        pushed
+            .find_reference("refs/heads/feature")
+            .unwrap()
+            .target()
+            .unwrap()
+            .to_string(),
+        feature_oid
+    );
+    let untouched = Repository::open_bare(&origin).unwrap();
+    assert!(untouched.find_reference("refs/heads/feature").is_err());
+
+    let local = Repository::open(&repo_path).unwrap();
+    let branch = local
+        .find_branch("feature", git2::BranchType::Local)
+        .unwrap();
+    assert_eq!(
+        branch.upstream().unwrap().name().unwrap(),
+        Some("fork/feature")
+    );
+}
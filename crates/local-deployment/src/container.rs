@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     io,
     path::{Path, PathBuf},
     sync::Arc,
@@ -14,7 +14,8 @@ use db::{
     models::{
         coding_agent_turn::CodingAgentTurn,
         execution_process::{
-            ExecutionContext, ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionContext, ExecutionProcess, ExecutionProcessError,
+            ExecutionProcessResourceUsage, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::ExecutionProcessRepoState,
         repo::Repo,
@@ -33,29 +34,44 @@ use executors::{
     },
     approvals::{ExecutorApprovalService, NoopExecutorApprovalService},
     env::{ExecutionEnv, RepoContext},
-    executors::{BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal},
+    executors::{
+        BaseCodingAgent, CancellationToken, ExecutorExitResult, ExecutorExitSignal,
+        ExecutorMessageSender,
+    },
     logs::{NormalizedEntryType, utils::patch::extract_normalized_entry_from_patch},
+    profile::ExecutorConfigs,
 };
 use futures::{FutureExt, TryStreamExt, stream::select};
-use git::GitService;
+use git::{GitService, PathFilter};
 use serde_json::json;
 use services::services::{
+    agent_identity_template::{self, AgentIdentityContext},
+    agent_plan,
     analytics::AnalyticsContext,
     approvals::{Approvals, executor_approvals::ExecutorApprovalBridge},
-    config::{Config, DEFAULT_COMMIT_REMINDER_PROMPT},
-    container::{ContainerError, ContainerRef, ContainerService},
+    attempt_limiter::{AttemptLimiter, AttemptPermit},
+    branch_template::BranchTemplateConfig,
+    config::{Config, DEFAULT_AGENT_IDENTITY_TEMPLATE, DEFAULT_COMMIT_REMINDER_PROMPT},
+    container::{CancelExecutionOutcome, ContainerError, ContainerRef, ContainerService},
+    conventional_commit,
     diff_stream::{self, DiffStreamHandle},
     file::FileService,
     notification::NotificationService,
+    outcome_summary::{self, OutcomeSummary},
+    proposed_changes,
     queued_message::QueuedMessageService,
     remote_client::RemoteClient,
-    remote_sync,
+    remote_sync, template, token_usage,
+};
+use tokio::{
+    sync::{RwLock, broadcast::error::RecvError},
+    task::JoinHandle,
 };
-use tokio::{sync::RwLock, task::JoinHandle};
 use tokio_util::io::ReaderStream;
 use utils::{
     log_msg::LogMsg,
     msg_store::MsgStore,
+    process_usage,
     text::{git_branch_id, short_uuid, truncate_to_char_boundary},
 };
 use uuid::Uuid;
@@ -64,6 +80,22 @@ use workspace_manager::{RepoWorkspaceInput, WorkspaceError, WorkspaceManager};
 use crate::{command, copy};
 
 const WORKSPACE_TOUCH_DEBOUNCE: Duration = Duration::from_mins(2);
+const RESOURCE_SAMPLE_INTERVAL: Duration = Duration::from_secs(2);
+/// Peaks are written to the DB at most this often while a process runs, so a
+/// crash of the server itself still leaves recent numbers behind.
+const RESOURCE_PERSIST_INTERVAL: Duration = Duration::from_secs(30);
+/// How long an agent stopped for inactivity gets to exit after the interrupt
+/// before its process group is killed.
+const INACTIVITY_KILL_GRACE: Duration = Duration::from_secs(5);
+const DEFAULT_SHUTDOWN_GRACE: Duration = Duration::from_secs(3);
+/// How long an external log normalizer gets to print its last entries after
+/// the agent exits, before the log is marked finished without them.
+const LOG_FLUSH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Machine-local files coding agents write into repo checkouts. These are
+/// added to `.git/info/exclude` so they are never committed by accident, and
+/// removed again once the repo has no workspace worktrees left.
+const AGENT_LOCAL_FILES: [&str; 1] = [".claude/settings.local.json"];
 
 #[derive(Clone)]
 pub struct LocalContainerService {
@@ -71,18 +103,30 @@ pub struct LocalContainerService {
     workspace_manager: WorkspaceManager,
     child_store: Arc<RwLock<HashMap<Uuid, Arc<RwLock<AsyncGroupChild>>>>>,
     cancellation_tokens: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+    message_senders: Arc<RwLock<HashMap<Uuid, ExecutorMessageSender>>>,
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     /// Tracks background tasks that stream logs to the database.
     /// When stopping execution, we await these to ensure logs are fully persisted.
     db_stream_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     exit_monitor_handles: Arc<RwLock<HashMap<Uuid, JoinHandle<()>>>>,
     workspace_touch_times: Arc<RwLock<HashMap<Uuid, Instant>>>,
+    /// Per-repo concurrency slots held by running coding agents, keyed by execution id.
+    attempt_permits: Arc<RwLock<HashMap<Uuid, Vec<AttemptPermit>>>>,
+    /// Executions that haven't spawned yet, queued for attempt permits or
+    /// still starting. A stop in that window removes the entry: a queued one
+    /// never spawns, a starting one is stopped as soon as its child exists.
+    pending_starts: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+    /// Cancels git host requests per workspace when the workspace is stopped.
+    git_host_cancellations: Arc<RwLock<HashMap<Uuid, CancellationToken>>>,
+    /// Peak CPU/memory of running executions, updated by the resource sampler.
+    resource_usage: Arc<RwLock<HashMap<Uuid, ExecutionProcessResourceUsage>>>,
     config: Arc<RwLock<Config>>,
     git: GitService,
     file_service: FileService,
     analytics: Option<AnalyticsContext>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    attempt_limiter: AttemptLimiter,
     notification_service: NotificationService,
     remote_client: Option<RemoteClient>,
 }
@@ -99,30 +143,43 @@ impl LocalContainerService {
         analytics: Option<AnalyticsContext>,
         approvals: Approvals,
         queued_message_service: QueuedMessageService,
+        attempt_limiter: AttemptLimiter,
         remote_client: Option<RemoteClient>,
     ) -> Self {
         let child_store = Arc::new(RwLock::new(HashMap::new()));
         let cancellation_tokens = Arc::new(RwLock::new(HashMap::new()));
+        let message_senders = Arc::new(RwLock::new(HashMap::new()));
         let db_stream_handles = Arc::new(RwLock::new(HashMap::new()));
         let exit_monitor_handles = Arc::new(RwLock::new(HashMap::new()));
         let workspace_touch_times = Arc::new(RwLock::new(HashMap::new()));
-        let notification_service = NotificationService::new(config.clone());
+        let attempt_permits = Arc::new(RwLock::new(HashMap::new()));
+        let pending_starts = Arc::new(RwLock::new(HashMap::new()));
+        let git_host_cancellations = Arc::new(RwLock::new(HashMap::new()));
+        let resource_usage = Arc::new(RwLock::new(HashMap::new()));
+        let notification_service =
+            NotificationService::new(config.clone()).with_dead_letters(db.pool.clone());
 
         let container = LocalContainerService {
             db,
             workspace_manager,
             child_store,
             cancellation_tokens,
+            message_senders,
             msg_stores,
             db_stream_handles,
             exit_monitor_handles,
             workspace_touch_times,
+            attempt_permits,
+            pending_starts,
+            git_host_cancellations,
+            resource_usage,
             config,
             git,
             file_service,
             analytics,
             approvals,
             queued_message_service,
+            attempt_limiter,
             notification_service,
             remote_client,
         };
@@ -208,6 +265,341 @@ impl LocalContainerService {
         map.remove(id);
     }
 
+    /// Wait until every repo in the workspace has a free coding agent slot,
+    /// then for one of the machine-wide slots if a limit is configured. Repo
+    /// slots are taken in repo id order and the global slot last, so
+    /// concurrent starts cannot deadlock.
+    async fn acquire_attempt_permits(&self, repos: &[Repo]) -> Vec<AttemptPermit> {
+        let mut limited: Vec<&Repo> = repos
+            .iter()
+            .filter(|repo| repo.max_concurrent_attempts.is_some())
+            .collect();
+        limited.sort_by_key(|repo| repo.id);
+
+        let mut permits = Vec::with_capacity(limited.len());
+        for repo in limited {
+            if let Some(permit) = self
+                .attempt_limiter
+                .acquire(repo.id, repo.max_concurrent_attempts)
+                .await
+            {
+                permits.push(permit);
+            }
+        }
+
+        let max_concurrent = self.config.read().await.max_concurrent_executions;
+        if let Some(limit) = max_concurrent
+            && let Some(permit) = self.attempt_limiter.acquire_global(limit as usize).await
+        {
+            permits.push(permit);
+        }
+        permits
+    }
+
+    /// Permits for every limited repo and the global limit, or `None` as soon
+    /// as one of them is full.
+    async fn try_acquire_attempt_permits(&self, repos: &[Repo]) -> Option<Vec<AttemptPermit>> {
+        let mut permits = Vec::new();
+        for repo in repos {
+            if let Some(permit) = self
+                .attempt_limiter
+                .try_acquire(repo.id, repo.max_concurrent_attempts)
+                .ok()?
+            {
+                permits.push(permit);
+            }
+        }
+        if let Some(limit) = self.config.read().await.max_concurrent_executions {
+            permits.push(
+                self.attempt_limiter
+                    .try_acquire_global(limit as usize)
+                    .ok()?,
+            );
+        }
+        Some(permits)
+    }
+
+    /// Start a queued coding agent once its slots are free. Waiting happens in
+    /// a background task so the caller isn't held up; cancelling through
+    /// [`ContainerService::cancel_execution`] takes the execution out of the
+    /// queue.
+    async fn start_queued_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        token: CancellationToken,
+    ) -> Result<(), ContainerError> {
+        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        let id = execution_process.id;
+
+        let container = self.clone();
+        let workspace = workspace.clone();
+        let session_id = execution_process.session_id;
+        let executor_action = executor_action.clone();
+        tokio::spawn(async move {
+            let started: Result<bool, ContainerError> = async {
+                let permits = tokio::select! {
+                    biased;
+                    _ = token.cancelled() => return Ok(false),
+                    permits = container.acquire_attempt_permits(&repos) => permits,
+                };
+
+                // Marking the process running happens under the lock, so a
+                // concurrent cancel either dequeues it first or stops it once
+                // it has spawned.
+                let pending = container.pending_starts.read().await;
+                if !pending.contains_key(&id)
+                    || !ExecutionProcess::mark_started(&container.db.pool, id).await?
+                {
+                    return Ok(false);
+                }
+                drop(pending);
+
+                let execution_process = ExecutionProcess::find_by_id(&container.db.pool, id)
+                    .await?
+                    .ok_or(ContainerError::Other(anyhow!(
+                        "Execution process {id} not found"
+                    )))?;
+                container
+                    .spawn_execution(&workspace, &execution_process, &executor_action, permits)
+                    .await?;
+                Ok(true)
+            }
+            .await;
+
+            match started {
+                Ok(true) => {}
+                Ok(false) => {
+                    tracing::info!("Execution process {} cancelled while queued", id);
+                    if let Some(msg) = container.msg_stores.write().await.remove(&id) {
+                        msg.push_finished();
+                    }
+                }
+                Err(e) => {
+                    tracing::error!("Failed to start queued execution process {}: {}", id, e);
+                    container.pending_starts.write().await.remove(&id);
+                    if let Some(msg) = container.msg_stores.write().await.remove(&id) {
+                        msg.push_finished();
+                    }
+                    container.record_start_failure(session_id, id, &e).await;
+                }
+            }
+        });
+        Ok(())
+    }
+
+    /// Spawn the executor for a process that is ready to run. `attempt_permits`
+    /// are held until it exits.
+    async fn spawn_execution(
+        &self,
+        workspace: &Workspace,
+        execution_process: &ExecutionProcess,
+        executor_action: &ExecutorAction,
+        attempt_permits: Vec<AttemptPermit>,
+    ) -> Result<(), ContainerError> {
+        // Get the worktree path
+        let container_ref = workspace
+            .container_ref
+            .as_ref()
+            .ok_or(ContainerError::Other(anyhow!(
+                "Container ref not found for workspace"
+            )))?;
+        let current_dir = PathBuf::from(container_ref);
+
+        let approvals_service: Arc<dyn ExecutorApprovalService> =
+            match executor_action.base_executor() {
+                Some(
+                    BaseCodingAgent::Codex
+                    | BaseCodingAgent::ClaudeCode
+                    | BaseCodingAgent::Gemini
+                    | BaseCodingAgent::QwenCode
+                    | BaseCodingAgent::Opencode,
+                ) => ExecutorApprovalBridge::new(
+                    self.approvals.clone(),
+                    self.db.clone(),
+                    self.notification_service.clone(),
+                    execution_process.id,
+                ),
+                _ => Arc::new(NoopExecutorApprovalService {}),
+            };
+
+        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        let repo_names: Vec<String> = repos.iter().map(|r| r.name.clone()).collect();
+        // Denials are only kept when a follow-up will report them
+        if execution_process.run_reason == ExecutionProcessRunReason::CodingAgent
+            && repos.iter().any(|repo| {
+                repo.denied_tool_note_template
+                    .as_deref()
+                    .is_some_and(|template| !template.trim().is_empty())
+            })
+        {
+            self.approvals
+                .track_denials(execution_process.id, workspace.id);
+        }
+
+        let config = self.config.read().await;
+        let commit_reminder_enabled = config.commit_reminder_enabled;
+        let disallow_permission_bypass = config.disallow_permission_bypass;
+        let disable_api_key_default = config.claude_disable_api_key;
+        let commit_reminder_prompt = config
+            .commit_reminder_prompt
+            .clone()
+            .unwrap_or_else(|| DEFAULT_COMMIT_REMINDER_PROMPT.to_string());
+        // Identity context is only added when a new agent session starts;
+        // follow-ups resume a conversation that already has it.
+        let system_context = (config.agent_identity_enabled
+            && matches!(
+                executor_action.typ(),
+                ExecutorActionType::CodingAgentInitialRequest(_)
+            ))
+        .then(|| {
+            agent_identity_template::render(
+                config
+                    .agent_identity_template
+                    .as_deref()
+                    .unwrap_or(DEFAULT_AGENT_IDENTITY_TEMPLATE),
+                &AgentIdentityContext {
+                    user: config.github.username.as_deref().unwrap_or("the user"),
+                    workspace_name: workspace.name.as_deref().unwrap_or(&workspace.branch),
+                    workspace_id: &workspace.id.to_string(),
+                    branch: &workspace.branch,
+                    repos: &repo_names.join(", "),
+                },
+            )
+        });
+        drop(config);
+        // Denials from earlier runs are cleared once the follow-up that
+        // reports them has started.
+        let mut reported_denials = Vec::new();
+        let system_context = match executor_action.typ() {
+            ExecutorActionType::CodingAgentFollowUpRequest(_) => {
+                reported_denials = self.other_session_process_ids(execution_process).await;
+                self.denied_tool_notes(&reported_denials, &repos)
+            }
+            _ => system_context,
+        };
+
+        let repo_context = RepoContext::new(current_dir.clone(), repo_names);
+        let mut env = ExecutionEnv::new(
+            repo_context,
+            commit_reminder_enabled,
+            commit_reminder_prompt,
+        );
+        env.system_context = system_context;
+        env.disallow_permission_bypass = disallow_permission_bypass;
+        env.disable_api_key_default = disable_api_key_default;
+
+        // Always inject workspace/session context
+        env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
+        env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
+        env.set_attempt_overrides(
+            Workspace::find_env_overrides(&self.db.pool, workspace.id).await?,
+        );
+
+        // Create the child and stream, add to execution tracker with timeout
+        let mut spawned = tokio::time::timeout(
+            Duration::from_secs(30),
+            executor_action.spawn(&current_dir, approvals_service, &env),
+        )
+        .await
+        .map_err(|_| {
+            ContainerError::Other(anyhow!(
+                "Timeout: process took more than 30 seconds to start"
+            ))
+        })??;
+
+        if let Err(e) = self
+            .track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+            .await
+        {
+            let _ = command::kill_process_group(&mut spawned.child).await;
+            return Err(e);
+        }
+
+        self.approvals.clear_denials(&reported_denials);
+        if let Err(e) = ExecutionProcess::mark_spawned(&self.db.pool, execution_process.id).await {
+            tracing::warn!(
+                "Failed to record spawn time for execution process {}: {}",
+                execution_process.id,
+                e
+            );
+        }
+
+        let pgid = spawned.child.id();
+        self.add_child_to_store(execution_process.id, spawned.child)
+            .await;
+        if let Some(pgid) = pgid {
+            self.spawn_resource_sampler(execution_process.id, pgid);
+        }
+        if !attempt_permits.is_empty() {
+            self.attempt_permits
+                .write()
+                .await
+                .insert(execution_process.id, attempt_permits);
+        }
+
+        // Store cancellation token for graceful shutdown
+        if let Some(cancel) = spawned.cancel {
+            self.add_cancellation_token(execution_process.id, cancel)
+                .await;
+        }
+
+        if let Some(message_tx) = spawned.message_tx {
+            self.message_senders
+                .write()
+                .await
+                .insert(execution_process.id, message_tx);
+        }
+
+        let inactivity_timeout = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                Some(request.executor_config.profile_id())
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
+                Some(request.executor_config.profile_id())
+            }
+            ExecutorActionType::ReviewRequest(request) => {
+                Some(request.executor_config.profile_id())
+            }
+            _ => None,
+        }
+        .and_then(|profile_id| ExecutorConfigs::get_cached().get_coding_agent(&profile_id))
+        .and_then(|agent| agent.inactivity_timeout());
+
+        // Spawn unified exit monitor: watches OS exit, optional executor signal
+        // and the optional inactivity watchdog
+        let hn = self.spawn_exit_monitor(
+            &execution_process.id,
+            spawned.exit_signal,
+            inactivity_timeout,
+        );
+        self.add_exit_monitor_handle(execution_process.id, hn).await;
+
+        // A stop that came in while the process was starting had no child to
+        // kill, so it only removed the pending start.
+        let stopped_while_starting = self
+            .pending_starts
+            .write()
+            .await
+            .remove(&execution_process.id)
+            .is_none();
+        if stopped_while_starting {
+            self.stop_execution(execution_process, ExecutionProcessStatus::Killed)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    async fn release_attempt_permits(&self, id: &Uuid) {
+        self.attempt_permits.write().await.remove(id);
+    }
+
+    async fn remove_message_sender(&self, id: &Uuid) {
+        self.message_senders.write().await.remove(id);
+    }
+
     async fn add_cancellation_token(&self, id: Uuid, token: CancellationToken) {
         let mut map = self.cancellation_tokens.write().await;
         map.insert(id, token);
@@ -268,11 +660,110 @@ impl LocalContainerService {
                         e
                     );
                 });
+            for repo in &repositories {
+                if let Err(e) = self
+                    .git
+                    .remove_excluded_if_unused(&repo.path, &AGENT_LOCAL_FILES)
+                {
+                    tracing::warn!(
+                        "Failed to remove agent local file excludes in {}: {}",
+                        repo.path.display(),
+                        e
+                    );
+                }
+            }
         }
 
         let _ = Workspace::mark_worktree_deleted(&self.db.pool, workspace.id).await;
     }
 
+    /// Remove the worktree of a workspace whose first coding agent run was
+    /// cancelled before it started, so a dequeued attempt leaves nothing
+    /// behind. Workspaces that other runs have used are left alone.
+    async fn cleanup_unused_worktree(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<(), ContainerError> {
+        let ctx = ExecutionProcess::load_context(&self.db.pool, execution_process.id).await?;
+        let workspace_id = ctx.workspace.id;
+        let coding_runs = ExecutionProcess::find_finished_coding_agent_ids(
+            &self.db.pool,
+            Some(workspace_id),
+            None,
+        )
+        .await?;
+        if coding_runs != [execution_process.id]
+            || ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+                &self.db.pool,
+                workspace_id,
+            )
+            .await?
+            || !ExecutionProcess::find_running_dev_servers_by_workspace(&self.db.pool, workspace_id)
+                .await?
+                .is_empty()
+        {
+            return Ok(());
+        }
+
+        self.cleanup_workspace(&ctx.workspace).await;
+        Ok(())
+    }
+
+    /// Make room for a new worktree of each repo that has `max_worktrees` set.
+    /// Depending on the repo, this prunes the least recently used idle
+    /// worktrees or rejects the new one.
+    async fn enforce_worktree_limits(
+        &self,
+        workspace_id: Uuid,
+        repos: &[Repo],
+    ) -> Result<(), ContainerError> {
+        for repo in repos {
+            let Some(limit) = repo.max_worktrees else {
+                continue;
+            };
+            let existing =
+                Workspace::count_with_worktree_for_repo(&self.db.pool, repo.id, workspace_id)
+                    .await?;
+            if existing < limit {
+                continue;
+            }
+
+            let excess = (existing - limit + 1) as usize;
+            let candidates = if repo.prune_worktrees_at_limit {
+                Workspace::find_prunable_with_worktree_for_repo(
+                    &self.db.pool,
+                    repo.id,
+                    workspace_id,
+                )
+                .await?
+            } else {
+                Vec::new()
+            };
+            // Worktrees with uncommitted or untracked changes are never pruned.
+            let pruned = pick_clean(candidates, excess, async |workspace: &Workspace| {
+                self.is_container_clean(workspace).await.unwrap_or(false)
+            })
+            .await;
+            let Some(pruned) = pruned else {
+                return Err(ContainerError::WorktreeLimitReached {
+                    repo: repo.display_name.clone(),
+                    limit,
+                });
+            };
+
+            for workspace in &pruned {
+                tracing::info!(
+                    "Pruning worktree for workspace {} to stay within the {} worktree limit of repo {}",
+                    workspace.id,
+                    limit,
+                    repo.name
+                );
+                self.cleanup_workspace(workspace).await;
+            }
+        }
+        Ok(())
+    }
+
     async fn cleanup_expired_workspaces(&self) -> Result<(), DeploymentError> {
         if std::env::var("DISABLE_WORKTREE_CLEANUP").is_ok() {
             tracing::info!(
@@ -340,6 +831,44 @@ impl LocalContainerService {
         }
     }
 
+    /// Ids of the other execution processes in the session.
+    async fn other_session_process_ids(&self, execution_process: &ExecutionProcess) -> Vec<Uuid> {
+        ExecutionProcess::find_by_session_id(&self.db.pool, execution_process.session_id, true)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|process| process.id != execution_process.id)
+            .map(|process| process.id)
+            .collect()
+    }
+
+    /// Notes about tool calls the user denied during these processes,
+    /// rendered with the first repo template that is set.
+    fn denied_tool_notes(&self, execution_process_ids: &[Uuid], repos: &[Repo]) -> Option<String> {
+        let template = repos
+            .iter()
+            .find_map(|repo| repo.denied_tool_note_template.as_deref())
+            .filter(|template| !template.trim().is_empty())?;
+        let denials = self.approvals.denials(execution_process_ids);
+        if denials.is_empty() {
+            return None;
+        }
+
+        let notes: Vec<String> = denials
+            .iter()
+            .map(|denial| {
+                let reason = denial
+                    .reason
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|reason| !reason.is_empty())
+                    .unwrap_or("no reason given");
+                template::render(template, &[("tool", &denial.tool_name), ("reason", reason)])
+            })
+            .collect();
+        Some(notes.join("\n"))
+    }
+
     /// Get the commit message based on the execution run reason.
     async fn get_commit_message(&self, ctx: &ExecutionContext) -> String {
         match ctx.execution_process.run_reason {
@@ -358,10 +887,7 @@ impl LocalContainerService {
                             "No summary found for execution process {}, using default message",
                             ctx.execution_process.id
                         );
-                        format!(
-                            "Commit changes from coding agent for workspace {}",
-                            ctx.workspace.id
-                        )
+                        Self::default_commit_message(ctx)
                     }
                     Err(e) => {
                         tracing::debug!(
@@ -369,13 +895,21 @@ impl LocalContainerService {
                             ctx.execution_process.id,
                             e
                         );
-                        format!(
-                            "Commit changes from coding agent for workspace {}",
-                            ctx.workspace.id
-                        )
+                        Self::default_commit_message(ctx)
                     }
                 }
             }
+            _ => Self::default_commit_message(ctx),
+        }
+    }
+
+    /// The commit message used when no better one is available.
+    fn default_commit_message(ctx: &ExecutionContext) -> String {
+        match ctx.execution_process.run_reason {
+            ExecutionProcessRunReason::CodingAgent => format!(
+                "Commit changes from coding agent for workspace {}",
+                ctx.workspace.id
+            ),
             ExecutionProcessRunReason::CleanupScript => {
                 format!("Cleanup script changes for workspace {}", ctx.workspace.id)
             }
@@ -447,6 +981,35 @@ impl LocalContainerService {
         Ok(false)
     }
 
+    /// Put each repo back where it was before a preview run, dropping any
+    /// files the agent wrote and any commits it made.
+    async fn discard_preview_changes(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
+        let workspace_root = self.workspace_to_current_dir(&ctx.workspace);
+
+        let repo_states = ExecutionProcessRepoState::find_by_execution_process_id(
+            &self.db.pool,
+            ctx.execution_process.id,
+        )
+        .await?;
+
+        for repo in &ctx.repos {
+            let Some(before_head) = repo_states
+                .iter()
+                .find(|s| s.repo_id == repo.id)
+                .and_then(|s| s.before_head_commit.as_deref())
+            else {
+                continue;
+            };
+            self.git().reset_worktree_to_commit(
+                &workspace_root.join(&repo.name),
+                before_head,
+                true,
+            )?;
+        }
+
+        Ok(())
+    }
+
     /// Commit changes to each repo. Logs failures but continues with other repos.
     fn commit_repos(&self, repos_with_changes: Vec<(Repo, PathBuf)>, message: &str) -> bool {
         let mut any_committed = false;
@@ -476,11 +1039,13 @@ impl LocalContainerService {
     }
 
     /// Spawn a background task that polls the child process for completion and
-    /// cleans up the execution entry when it exits.
+    /// cleans up the execution entry when it exits. With an `inactivity_timeout`
+    /// the process is also stopped once its log has been quiet that long.
     fn spawn_exit_monitor(
         &self,
         exec_id: &Uuid,
         exit_signal: Option<ExecutorExitSignal>,
+        inactivity_timeout: Option<Duration>,
     ) -> JoinHandle<()> {
         let exec_id = *exec_id;
         let child_store = self.child_store.clone();
@@ -489,6 +1054,7 @@ impl LocalContainerService {
         let config = self.config.clone();
         let container = self.clone();
         let analytics = self.analytics.clone();
+        let approvals = self.approvals.clone();
 
         let mut process_exit_rx = self.spawn_os_exit_watcher(exec_id);
 
@@ -497,6 +1063,18 @@ impl LocalContainerService {
                 .map(|rx| rx.boxed()) // wait for result
                 .unwrap_or_else(|| std::future::pending().boxed()); // no signal, stall forever
 
+            let msg_store = msg_stores.read().await.get(&exec_id).cloned();
+            let mut inactivity_future = match (inactivity_timeout, msg_store) {
+                (Some(timeout), Some(store)) => wait_for_inactivity(store, timeout, move || {
+                    !approvals
+                        .get_pending_execution_process_ids(&[exec_id])
+                        .is_empty()
+                })
+                .boxed(),
+                _ => std::future::pending().boxed(), // watchdog off
+            };
+            let mut timed_out = false;
+
             let status_result: std::io::Result<std::process::ExitStatus>;
 
             // Wait for process to exit, or exit signal from executor
@@ -524,6 +1102,16 @@ impl LocalContainerService {
                 exit_status_result = &mut process_exit_rx => {
                     status_result = exit_status_result.unwrap_or_else(|e| Err(std::io::Error::other(e)));
                 }
+                // No output for too long: interrupt, then kill
+                _ = &mut inactivity_future => {
+                    tracing::warn!(
+                        "Execution {} produced no output for {:?}, stopping it",
+                        exec_id,
+                        inactivity_timeout.unwrap_or_default()
+                    );
+                    timed_out = true;
+                    status_result = container.stop_inactive_child(exec_id, &mut process_exit_rx).await;
+                }
             }
 
             let (exit_code, status) = match status_result {
@@ -539,18 +1127,53 @@ impl LocalContainerService {
                 Err(_) => (None, ExecutionProcessStatus::Failed),
             };
 
-            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await
-                && let Err(e) =
+            if !ExecutionProcess::was_stopped(&db.pool, exec_id).await {
+                let result = if timed_out {
+                    ExecutionProcess::mark_timed_out(&db.pool, exec_id, exit_code).await
+                } else {
                     ExecutionProcess::update_completion(&db.pool, exec_id, status, exit_code).await
-            {
-                tracing::error!("Failed to update execution process completion: {}", e);
+                };
+                if let Err(e) = result {
+                    tracing::error!("Failed to update execution process completion: {}", e);
+                }
             }
 
+            // Free repo slots before starting any follow-on action that may need them.
+            container.release_attempt_permits(&exec_id).await;
+            container.remove_message_sender(&exec_id).await;
+            container.approvals.clear_auto_deny_rules(&exec_id);
+
             if let Ok(ctx) = ExecutionProcess::load_context(&db.pool, exec_id).await {
+                // A follow-up only reports the denials of the latest coding
+                // agent run, so older ones are dropped
+                if ctx.execution_process.run_reason == ExecutionProcessRunReason::CodingAgent {
+                    let other_ids = container
+                        .other_session_process_ids(&ctx.execution_process)
+                        .await;
+                    container.approvals.clear_denials(&other_ids);
+                }
+
                 // Update executor session summary if available
                 if let Err(e) = container.update_executor_session_summary(&exec_id).await {
                     tracing::warn!("Failed to update executor session summary: {}", e);
                 }
+                if let Err(e) = container.record_outcome_summary(&ctx).await {
+                    tracing::warn!("Failed to record outcome summary: {}", e);
+                }
+
+                // Preview runs only report what they would change
+                let preview = ctx
+                    .execution_process
+                    .executor_action()
+                    .is_ok_and(|action| action.is_preview());
+                if preview {
+                    if let Err(e) = container.record_proposed_changes(&ctx).await {
+                        tracing::warn!("Failed to record proposed changes: {}", e);
+                    }
+                    if let Err(e) = container.discard_preview_changes(&ctx).await {
+                        tracing::error!("Failed to discard preview run changes: {}", e);
+                    }
+                }
 
                 let success = matches!(
                     ctx.execution_process.status,
@@ -567,7 +1190,7 @@ impl LocalContainerService {
 
                 let mut already_finalized = false;
 
-                if success || cleanup_done {
+                if (success || cleanup_done) && !preview {
                     // Commit changes (if any) and get feedback about whether changes were made
                     let changes_committed = match container.try_commit_changes(&ctx).await {
                         Ok(committed) => committed,
@@ -772,6 +1395,12 @@ impl LocalContainerService {
                             .ok()
                             .flatten()
                             .and_then(|ws| ws.workspace.name);
+                    let time_spent_ms = ExecutionProcess::time_spent_for_workspace(
+                        &container.db.pool,
+                        ctx.workspace.id,
+                    )
+                    .await
+                    .ok();
                     let client = client.clone();
                     let workspace_id = ctx.workspace.id;
                     let archived = ctx.workspace.archived;
@@ -782,6 +1411,7 @@ impl LocalContainerService {
                             workspace_name.map(Some),
                             Some(archived),
                             stats.as_ref(),
+                            time_spent_ms,
                         )
                         .await;
                     });
@@ -794,8 +1424,9 @@ impl LocalContainerService {
 
             // Wait for DB persistence to complete before cleaning up MsgStore
             let db_stream_handle = container.take_db_stream_handle(&exec_id).await;
-            if let Some(msg_arc) = msg_stores.write().await.remove(&exec_id) {
-                msg_arc.push_finished();
+            let msg_store = msg_stores.write().await.remove(&exec_id);
+            if let Some(msg_arc) = msg_store {
+                msg_arc.finish(LOG_FLUSH_TIMEOUT).await;
             }
             if let Some(handle) = db_stream_handle {
                 let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
@@ -812,6 +1443,75 @@ impl LocalContainerService {
         })
     }
 
+    /// Periodically sample the execution's process group and keep the peaks,
+    /// until the child is removed from the store.
+    fn spawn_resource_sampler(&self, exec_id: Uuid, pgid: u32) {
+        let child_store = self.child_store.clone();
+        let resource_usage = self.resource_usage.clone();
+        let db = self.db.clone();
+        tokio::spawn(async move {
+            let mut peak = ExecutionProcessResourceUsage::default();
+            let mut persisted = peak;
+            let mut last_persist = Instant::now();
+
+            while child_store.read().await.contains_key(&exec_id) {
+                let sample =
+                    tokio::task::spawn_blocking(move || process_usage::sample_process_group(pgid))
+                        .await
+                        .ok()
+                        .flatten();
+                if let Some(sample) = sample {
+                    peak.peak_rss_bytes = peak.peak_rss_bytes.max(Some(sample.rss_bytes as i64));
+                    // Exited members drop out of the sum, so keep the highest total.
+                    peak.cpu_time_ms = peak.cpu_time_ms.max(Some(sample.cpu_time_ms as i64));
+                    resource_usage.write().await.insert(exec_id, peak);
+                }
+
+                if peak != persisted && last_persist.elapsed() >= RESOURCE_PERSIST_INTERVAL {
+                    if let Err(e) =
+                        ExecutionProcess::update_resource_usage(&db.pool, exec_id, peak).await
+                    {
+                        tracing::warn!("Failed to persist resource usage for {}: {}", exec_id, e);
+                    }
+                    persisted = peak;
+                    last_persist = Instant::now();
+                }
+                tokio::time::sleep(RESOURCE_SAMPLE_INTERVAL).await;
+            }
+
+            if peak != persisted
+                && let Err(e) =
+                    ExecutionProcess::update_resource_usage(&db.pool, exec_id, peak).await
+            {
+                tracing::warn!("Failed to persist resource usage for {}: {}", exec_id, e);
+            }
+            resource_usage.write().await.remove(&exec_id);
+        });
+    }
+
+    /// Interrupt an agent that went quiet through its cancellation token, and
+    /// kill its process group if it hasn't exited after a grace period.
+    async fn stop_inactive_child(
+        &self,
+        exec_id: Uuid,
+        process_exit_rx: &mut tokio::sync::oneshot::Receiver<
+            std::io::Result<std::process::ExitStatus>,
+        >,
+    ) -> std::io::Result<std::process::ExitStatus> {
+        let cancel = self.take_cancellation_token(&exec_id).await;
+        if let Some(exit_status_result) = interrupt_and_wait(cancel, process_exit_rx).await {
+            return exit_status_result;
+        }
+
+        if let Some(child_lock) = self.child_store.read().await.get(&exec_id).cloned() {
+            let mut child = child_lock.write().await;
+            if let Err(err) = command::kill_process_group(&mut child).await {
+                tracing::error!("Failed to kill inactive process group: {} {}", exec_id, err);
+            }
+        }
+        Ok(failure_exit_status())
+    }
+
     fn spawn_os_exit_watcher(
         &self,
         exec_id: Uuid,
@@ -943,6 +1643,38 @@ impl LocalContainerService {
         Ok(())
     }
 
+    /// Summarize a finished coding agent run, and keep its last plan and token
+    /// usage, from its normalized log.
+    async fn record_outcome_summary(&self, ctx: &ExecutionContext) -> Result<(), anyhow::Error> {
+        if ctx.execution_process.run_reason != ExecutionProcessRunReason::CodingAgent {
+            return Ok(());
+        }
+        let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await else {
+            return Ok(());
+        };
+        let messages = store.get_history();
+        let summary =
+            OutcomeSummary::from_messages(&messages, ctx.execution_process.status.clone());
+        outcome_summary::record(&self.db.pool, ctx.execution_process.id, &summary).await?;
+        agent_plan::record(&self.db.pool, ctx.execution_process.id, &messages).await?;
+        token_usage::record(&self.db.pool, ctx.execution_process.id, &messages).await?;
+        Ok(())
+    }
+
+    /// Keep what a preview run changed before its worktree is reset.
+    async fn record_proposed_changes(&self, ctx: &ExecutionContext) -> Result<(), anyhow::Error> {
+        let Some(store) = self.get_msg_store_by_id(&ctx.execution_process.id).await else {
+            return Ok(());
+        };
+        proposed_changes::record(
+            &self.db.pool,
+            ctx.execution_process.id,
+            &store.get_history(),
+        )
+        .await?;
+        Ok(())
+    }
+
     /// Copy project files and workspace attachments to the workspace.
     /// Skips files that already exist (fast no-op if all exist).
     async fn copy_files_and_images(
@@ -1044,6 +1776,22 @@ impl LocalContainerService {
         Ok(())
     }
 
+    async fn exclude_agent_local_files(&self, workspace_dir: &Path, repos: &[Repo]) {
+        if !self.config.read().await.exclude_agent_local_files {
+            return;
+        }
+        for repo in repos {
+            let repo_path = workspace_dir.join(&repo.name);
+            if let Err(e) = self.git.ensure_excluded(&repo_path, &AGENT_LOCAL_FILES) {
+                tracing::warn!(
+                    "Failed to exclude agent local files in {}: {}",
+                    repo_path.display(),
+                    e
+                );
+            }
+        }
+    }
+
     /// Start a follow-up execution from a queued message
     async fn start_queued_follow_up(
         &self,
@@ -1103,6 +1851,7 @@ impl LocalContainerService {
                 prompt: queued_data.message.clone(),
                 executor_config: queued_data.executor_config.clone(),
                 working_dir,
+                preview: false,
             })
         };
 
@@ -1118,6 +1867,73 @@ impl LocalContainerService {
     }
 }
 
+/// The first `count` of `candidates` that `is_clean` accepts, checked in
+/// order and only as far as needed. `None` if fewer than `count` are clean.
+async fn pick_clean<T>(
+    candidates: Vec<T>,
+    count: usize,
+    is_clean: impl AsyncFn(&T) -> bool,
+) -> Option<Vec<T>> {
+    let mut picked = Vec::with_capacity(count);
+    for candidate in candidates {
+        if picked.len() == count {
+            break;
+        }
+        if is_clean(&candidate).await {
+            picked.push(candidate);
+        }
+    }
+    (picked.len() == count).then_some(picked)
+}
+
+/// Resolves once `store` has gone `timeout` without a new message. Time spent
+/// while `approval_pending` returns true doesn't count as inactivity.
+async fn wait_for_inactivity(
+    store: Arc<MsgStore>,
+    timeout: Duration,
+    approval_pending: impl Fn() -> bool,
+) {
+    let mut rx = store.get_receiver();
+    loop {
+        match tokio::time::timeout(timeout, rx.recv()).await {
+            Ok(Ok(_)) | Ok(Err(RecvError::Lagged(_))) => {}
+            Ok(Err(RecvError::Closed)) => return std::future::pending().await,
+            Err(_) => {
+                if !approval_pending() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Interrupt an agent through its cancellation token and wait up to
+/// [`INACTIVITY_KILL_GRACE`] for it to exit. `None` means it is still running
+/// and has to be killed.
+async fn interrupt_and_wait(
+    cancel: Option<CancellationToken>,
+    process_exit_rx: &mut tokio::sync::oneshot::Receiver<std::io::Result<std::process::ExitStatus>>,
+) -> Option<std::io::Result<std::process::ExitStatus>> {
+    let cancel = cancel?;
+    cancel.cancel();
+    tokio::time::timeout(INACTIVITY_KILL_GRACE, &mut *process_exit_rx)
+        .await
+        .ok()
+        .map(|exit_status_result| exit_status_result.unwrap_or_else(|e| Err(io::Error::other(e))))
+}
+
+/// Send a user message to a running executor. False when the executor never
+/// took messages or has stopped reading them, so the caller can queue it.
+fn deliver_message(
+    senders: &HashMap<Uuid, ExecutorMessageSender>,
+    execution_process_id: &Uuid,
+    message: String,
+) -> bool {
+    senders
+        .get(execution_process_id)
+        .is_some_and(|tx| tx.send(message).is_ok())
+}
+
 fn failure_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -1149,6 +1965,14 @@ impl ContainerService for LocalContainerService {
         &self.notification_service
     }
 
+    fn config(&self) -> &Arc<RwLock<Config>> {
+        &self.config
+    }
+
+    fn approvals(&self) -> &Approvals {
+        &self.approvals
+    }
+
     async fn touch(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         let now = Instant::now();
 
@@ -1194,6 +2018,14 @@ impl ContainerService for LocalContainerService {
         self.config.read().await.git_branch_prefix.clone()
     }
 
+    async fn git_branch_template(&self) -> Option<BranchTemplateConfig> {
+        let config = self.config.read().await;
+        Some(BranchTemplateConfig {
+            template: config.git_branch_template.clone()?,
+            author: config.github.username.clone(),
+        })
+    }
+
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf {
         PathBuf::from(workspace.container_ref.clone().unwrap_or_default())
     }
@@ -1205,6 +2037,8 @@ impl ContainerService for LocalContainerService {
         let workspace_dir = WorkspaceManager::get_workspace_base_dir().join(&workspace_dir_name);
 
         let (repositories, workspace_inputs) = self.workspace_repo_inputs(workspace.id).await?;
+        self.enforce_worktree_limits(workspace.id, &repositories)
+            .await?;
 
         let created_workspace = WorkspaceManager::create_workspace(
             &workspace_dir,
@@ -1220,6 +2054,8 @@ impl ContainerService for LocalContainerService {
 
         Self::create_workspace_config_files(&created_workspace.workspace_dir, &repositories)
             .await?;
+        self.exclude_agent_local_files(&created_workspace.workspace_dir, &repositories)
+            .await;
 
         Workspace::update_container_ref(
             &self.db.pool,
@@ -1237,6 +2073,23 @@ impl ContainerService for LocalContainerService {
     async fn delete(&self, workspace: &Workspace) -> Result<(), ContainerError> {
         self.try_stop(workspace, true).await;
         self.cleanup_workspace(workspace).await;
+
+        let repositories =
+            WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        let workspace_id = workspace.id.to_string();
+        for repo in &repositories {
+            if let Err(e) = self
+                .git
+                .delete_worktree_snapshots(&repo.path, &workspace_id)
+            {
+                tracing::warn!(
+                    "Failed to remove snapshots of workspace {} in {}: {}",
+                    workspace.id,
+                    repo.path.display(),
+                    e
+                );
+            }
+        }
         Ok(())
     }
 
@@ -1246,6 +2099,10 @@ impl ContainerService for LocalContainerService {
     ) -> Result<ContainerRef, ContainerError> {
         self.touch(workspace).await?;
         let (repositories, workspace_inputs) = self.workspace_repo_inputs(workspace.id).await?;
+        if workspace.container_ref.is_none() || workspace.worktree_deleted {
+            self.enforce_worktree_limits(workspace.id, &repositories)
+                .await?;
+        }
 
         let workspace_dir = if let Some(container_ref) = &workspace.container_ref {
             PathBuf::from(container_ref)
@@ -1282,6 +2139,8 @@ impl ContainerService for LocalContainerService {
             .await?;
 
         Self::create_workspace_config_files(&workspace_dir, &repositories).await?;
+        self.exclude_agent_local_files(&workspace_dir, &repositories)
+            .await;
 
         Ok(workspace_dir.to_string_lossy().to_string())
     }
@@ -1318,88 +2177,41 @@ impl ContainerService for LocalContainerService {
         workspace: &Workspace,
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
+        attempt_permits: Option<Vec<AttemptPermit>>,
     ) -> Result<(), ContainerError> {
-        // Get the worktree path
-        let container_ref = workspace
-            .container_ref
-            .as_ref()
-            .ok_or(ContainerError::Other(anyhow!(
-                "Container ref not found for workspace"
-            )))?;
-        let current_dir = PathBuf::from(container_ref);
-
-        let approvals_service: Arc<dyn ExecutorApprovalService> =
-            match executor_action.base_executor() {
-                Some(
-                    BaseCodingAgent::Codex
-                    | BaseCodingAgent::ClaudeCode
-                    | BaseCodingAgent::Gemini
-                    | BaseCodingAgent::QwenCode
-                    | BaseCodingAgent::Opencode,
-                ) => ExecutorApprovalBridge::new(
-                    self.approvals.clone(),
-                    self.db.clone(),
-                    self.notification_service.clone(),
-                    execution_process.id,
-                ),
-                _ => Arc::new(NoopExecutorApprovalService {}),
-            };
-
-        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
-        let repo_names: Vec<String> = repos.iter().map(|r| r.name.clone()).collect();
-        let repo_context = RepoContext::new(current_dir.clone(), repo_names);
-
-        let config = self.config.read().await;
-        let commit_reminder_enabled = config.commit_reminder_enabled;
-        let commit_reminder_prompt = config
-            .commit_reminder_prompt
-            .clone()
-            .unwrap_or_else(|| DEFAULT_COMMIT_REMINDER_PROMPT.to_string());
-        drop(config);
-        let mut env = ExecutionEnv::new(
-            repo_context,
-            commit_reminder_enabled,
-            commit_reminder_prompt,
-        );
-
-        // Always inject workspace/session context
-        env.insert("VK_WORKSPACE_ID", workspace.id.to_string());
-        env.insert("VK_WORKSPACE_BRANCH", &workspace.branch);
-
-        // Create the child and stream, add to execution tracker with timeout
-        let mut spawned = tokio::time::timeout(
-            Duration::from_secs(30),
-            executor_action.spawn(&current_dir, approvals_service, &env),
-        )
-        .await
-        .map_err(|_| {
-            ContainerError::Other(anyhow!(
-                "Timeout: process took more than 30 seconds to start"
-            ))
-        })??;
-
-        if let Err(e) = self
-            .track_child_msgs_in_store(execution_process.id, &mut spawned.child)
+        let token = CancellationToken::new();
+        self.pending_starts
+            .write()
             .await
-        {
-            let _ = command::kill_process_group(&mut spawned.child).await;
-            return Err(e);
-        }
-
-        self.add_child_to_store(execution_process.id, spawned.child)
-            .await;
-
-        // Store cancellation token for graceful shutdown
-        if let Some(cancel) = spawned.cancel {
-            self.add_cancellation_token(execution_process.id, cancel)
+            .insert(execution_process.id, token.clone());
+        let Some(attempt_permits) = attempt_permits else {
+            return self
+                .start_queued_execution(workspace, execution_process, executor_action, token)
                 .await;
+        };
+        let spawned = self
+            .spawn_execution(
+                workspace,
+                execution_process,
+                executor_action,
+                attempt_permits,
+            )
+            .await;
+        if spawned.is_err() {
+            self.pending_starts
+                .write()
+                .await
+                .remove(&execution_process.id);
         }
+        spawned
+    }
 
-        // Spawn unified exit monitor: watches OS exit and optional executor signal
-        let hn = self.spawn_exit_monitor(&execution_process.id, spawned.exit_signal);
-        self.add_exit_monitor_handle(execution_process.id, hn).await;
-
-        Ok(())
+    async fn try_reserve_attempt_slots(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Option<Vec<AttemptPermit>>, ContainerError> {
+        let repos = WorkspaceRepo::find_repos_for_workspace(&self.db.pool, workspace.id).await?;
+        Ok(self.try_acquire_attempt_permits(&repos).await)
     }
 
     async fn stop_execution(
@@ -1407,12 +2219,20 @@ impl ContainerService for LocalContainerService {
         execution_process: &ExecutionProcess,
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError> {
-        let child = self
-            .get_child_from_store(&execution_process.id)
-            .await
-            .ok_or_else(|| {
-                ContainerError::Other(anyhow!("Child process not found for execution"))
-            })?;
+        let Some(child) = self.get_child_from_store(&execution_process.id).await else {
+            // Queued or still starting: nothing to kill yet.
+            if self
+                .pending_starts
+                .read()
+                .await
+                .contains_key(&execution_process.id)
+            {
+                return self.cancel_execution(execution_process).await.map(|_| ());
+            }
+            return Err(ContainerError::Other(anyhow!(
+                "Child process not found for execution"
+            )));
+        };
         let exit_code = if status == ExecutionProcessStatus::Completed {
             Some(0)
         } else {
@@ -1455,11 +2275,15 @@ impl ContainerService for LocalContainerService {
             }
         }
         self.remove_child_from_store(&execution_process.id).await;
+        self.release_attempt_permits(&execution_process.id).await;
+        self.remove_message_sender(&execution_process.id).await;
+        self.approvals.clear_auto_deny_rules(&execution_process.id);
 
         // Mark the process finished in the MsgStore and wait for DB persistence
         let db_stream_handle = self.take_db_stream_handle(&execution_process.id).await;
-        if let Some(msg) = self.msg_stores.write().await.remove(&execution_process.id) {
-            msg.push_finished();
+        let msg_store = self.msg_stores.write().await.remove(&execution_process.id);
+        if let Some(msg) = msg_store {
+            msg.finish(LOG_FLUSH_TIMEOUT).await;
         }
         if let Some(handle) = db_stream_handle {
             let _ = tokio::time::timeout(Duration::from_secs(5), handle).await;
@@ -1476,12 +2300,112 @@ impl ContainerService for LocalContainerService {
         Ok(())
     }
 
+    async fn cancel_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<CancelExecutionOutcome, ContainerError> {
+        let mut pending = self.pending_starts.write().await;
+        if let Some(token) = pending.remove(&execution_process.id) {
+            let dequeued =
+                ExecutionProcess::mark_dequeued(&self.db.pool, execution_process.id).await?;
+            if !dequeued {
+                // Already spawning; it is stopped once its child exists.
+                ExecutionProcess::update_completion(
+                    &self.db.pool,
+                    execution_process.id,
+                    ExecutionProcessStatus::Killed,
+                    None,
+                )
+                .await?;
+            }
+            drop(pending);
+            token.cancel();
+            if !dequeued {
+                return Ok(CancelExecutionOutcome::Killed);
+            }
+            self.cleanup_unused_worktree(execution_process).await?;
+            return Ok(CancelExecutionOutcome::Dequeued);
+        }
+        drop(pending);
+
+        self.stop_execution(execution_process, ExecutionProcessStatus::Killed)
+            .await?;
+        Ok(CancelExecutionOutcome::Killed)
+    }
+
+    async fn git_host_cancellation(&self, workspace_id: Uuid) -> CancellationToken {
+        self.git_host_cancellations
+            .write()
+            .await
+            .entry(workspace_id)
+            .or_default()
+            .clone()
+    }
+
+    async fn cancel_git_host_requests(&self, workspace_id: Uuid) {
+        // Removed so requests started after the stop get a fresh token
+        if let Some(token) = self
+            .git_host_cancellations
+            .write()
+            .await
+            .remove(&workspace_id)
+        {
+            token.cancel();
+        }
+    }
+
+    async fn live_resource_usage(
+        &self,
+        execution_process_id: &Uuid,
+    ) -> Option<ExecutionProcessResourceUsage> {
+        self.resource_usage
+            .read()
+            .await
+            .get(execution_process_id)
+            .copied()
+    }
+
+    async fn inject_message(&self, execution_process_id: &Uuid, message: String) -> bool {
+        deliver_message(
+            &*self.message_senders.read().await,
+            execution_process_id,
+            message,
+        )
+    }
+
     async fn stream_diff(
         &self,
         workspace: &Workspace,
         stats_only: bool,
+        since_execution_id: Option<Uuid>,
+        path_filter: Option<PathFilter>,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>
     {
+        // Starting HEAD per repo for the requested execution, if any
+        let pinned_bases: Option<HashMap<Uuid, Option<String>>> = match since_execution_id {
+            Some(execution_id) => {
+                let process = ExecutionProcess::find_by_id(&self.db.pool, execution_id)
+                    .await?
+                    .ok_or(ExecutionProcessError::ExecutionProcessNotFound)?;
+                let session = Session::find_by_id(&self.db.pool, process.session_id).await?;
+                if session.is_none_or(|s| s.workspace_id != workspace.id) {
+                    return Err(ExecutionProcessError::ExecutionProcessNotFound.into());
+                }
+                let states = ExecutionProcessRepoState::find_by_execution_process_id(
+                    &self.db.pool,
+                    execution_id,
+                )
+                .await?;
+                Some(
+                    states
+                        .into_iter()
+                        .map(|state| (state.repo_id, state.before_head_commit))
+                        .collect(),
+                )
+            }
+            None => None,
+        };
+
         let workspace_repos =
             WorkspaceRepo::find_by_workspace_id(&self.db.pool, workspace.id).await?;
         let target_branches: HashMap<_, _> = workspace_repos
@@ -1509,10 +2433,26 @@ impl ContainerService for LocalContainerService {
                 continue;
             };
 
-            let base_commit = match self
-                .git()
-                .get_base_commit(&repo.path, branch, target_branch)
-            {
+            let pinned_base = match pinned_bases.as_ref().map(|bases| bases.get(&repo.id)) {
+                Some(Some(Some(sha))) => Some(sha.as_str()),
+                // The execution didn't record a starting HEAD for this repo
+                Some(_) => {
+                    tracing::warn!(
+                        "Skipping diff stream for repo {}: no starting commit recorded for execution",
+                        repo.name
+                    );
+                    continue;
+                }
+                None => None,
+            };
+
+            let base_commit = match pinned_base {
+                Some(sha) => self.git().resolve_commit(&repo.path, sha),
+                None => self
+                    .git()
+                    .get_base_commit(&repo.path, branch, target_branch),
+            };
+            let base_commit = match base_commit {
                 Ok(c) => c,
                 Err(e) => {
                     tracing::warn!(
@@ -1535,8 +2475,10 @@ impl ContainerService for LocalContainerService {
                     branch: branch.to_string(),
                     target_branch: target_branch.clone(),
                     base_commit: base_commit.clone(),
+                    pin_base: pinned_base.is_some(),
                     stats_only,
                     path_prefix: Some(repo.name.clone()),
+                    path_filter: path_filter.clone(),
                 })
                 .await?;
 
@@ -1560,6 +2502,15 @@ impl ContainerService for LocalContainerService {
         }
 
         let message = self.get_commit_message(ctx).await;
+        let conventional_commits = self.config.read().await.conventional_commits.clone();
+        let commit_type = match ctx.execution_process.run_reason {
+            ExecutionProcessRunReason::CleanupScript => "chore",
+            _ => "feat",
+        };
+        let message =
+            conventional_commit::enforce(&message, &conventional_commits, commit_type, || {
+                Self::default_commit_message(ctx)
+            });
 
         let container_ref = ctx
             .workspace
@@ -1609,14 +2560,70 @@ impl ContainerService for LocalContainerService {
             running_processes.len()
         );
 
-        for process in running_processes {
+        // Interrupt every process first so agents can flush their logs and
+        // session files, sharing one grace period instead of one each.
+        let mut exit_monitors = Vec::new();
+        for process in &running_processes {
+            // Marked killed up front so a process that exits on the interrupt
+            // isn't treated as finished and doesn't start follow-up actions.
+            if let Err(e) = ExecutionProcess::update_completion(
+                &self.db.pool,
+                process.id,
+                ExecutionProcessStatus::Killed,
+                None,
+            )
+            .await
+            {
+                tracing::warn!("Failed to mark process {} killed: {}", process.id, e);
+            }
+            if let Some(cancel) = self.take_cancellation_token(&process.id).await {
+                cancel.cancel();
+            }
+            if let Some(handle) = self.take_exit_monitor_handle(&process.id).await {
+                exit_monitors.push((process.id, handle));
+            }
+        }
+
+        let grace = shutdown_grace();
+        if !exit_monitors.is_empty()
+            && tokio::time::timeout(
+                grace,
+                futures::future::join_all(exit_monitors.iter_mut().map(|(_, handle)| handle)),
+            )
+            .await
+            .is_err()
+        {
+            tracing::info!(
+                "Some processes did not exit within {:?} of the interrupt, killing them",
+                grace
+            );
+        }
+        let exited: HashSet<Uuid> = exit_monitors
+            .iter()
+            .filter(|(_, handle)| handle.is_finished())
+            .map(|(id, _)| *id)
+            .collect();
+
+        // Only processes still running are killed: ones that exited on the
+        // interrupt have been cleaned up by their exit monitor, and queued
+        // ones never spawned. Survivors are killed together so shutdown time
+        // doesn't grow with the number of processes.
+        let mut survivors = Vec::new();
+        for process in &running_processes {
+            if !exited.contains(&process.id)
+                && self.get_child_from_store(&process.id).await.is_some()
+            {
+                survivors.push(process);
+            }
+        }
+        futures::future::join_all(survivors.into_iter().map(|process| async move {
             tracing::info!(
                 "Killing process: id={}, run_reason={:?}",
                 process.id,
                 process.run_reason
             );
             if let Err(error) = self
-                .stop_execution(&process, ExecutionProcessStatus::Killed)
+                .stop_execution(process, ExecutionProcessStatus::Killed)
                 .await
             {
                 tracing::error!(
@@ -1627,11 +2634,23 @@ impl ContainerService for LocalContainerService {
             } else {
                 tracing::info!("Successfully killed process: id={}", process.id);
             }
-        }
+        }))
+        .await;
 
         Ok(())
     }
 }
+
+/// How long running processes get to exit after the shutdown interrupt
+/// before they are killed. Set with `VK_SHUTDOWN_GRACE_SECS`.
+fn shutdown_grace() -> Duration {
+    std::env::var("VK_SHUTDOWN_GRACE_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_SHUTDOWN_GRACE)
+}
+
 fn success_exit_status() -> std::process::ExitStatus {
     #[cfg(unix)]
     {
@@ -1644,3 +2663,124 @@ fn success_exit_status() -> std::process::ExitStatus {
         ExitStatusExt::from_raw(0)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn dirty_worktrees_are_not_picked_for_pruning() {
+        // Candidate 2 has uncommitted changes.
+        let picked = pick_clean(vec![1, 2, 3, 4], 2, async |c: &i32| *c != 2).await;
+        assert_eq!(picked, Some(vec![1, 3]));
+
+        let picked = pick_clean(vec![1, 2, 3], 3, async |c: &i32| *c != 2).await;
+        assert_eq!(picked, None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn inactivity_fires_after_timeout() {
+        let store = Arc::new(MsgStore::new());
+        let started = tokio::time::Instant::now();
+
+        wait_for_inactivity(store, TIMEOUT, || false).await;
+
+        assert_eq!(started.elapsed(), TIMEOUT);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn output_resets_inactivity() {
+        let store = Arc::new(MsgStore::new());
+        let started = tokio::time::Instant::now();
+        let wait = tokio::spawn(wait_for_inactivity(store.clone(), TIMEOUT, || false));
+        // Let the watchdog subscribe before any output.
+        tokio::task::yield_now().await;
+
+        tokio::time::sleep(TIMEOUT / 2).await;
+        store.push_stdout("still working");
+        tokio::time::sleep(TIMEOUT * 3 / 4).await;
+        assert!(!wait.is_finished());
+
+        wait.await.unwrap();
+        assert_eq!(started.elapsed(), TIMEOUT * 3 / 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn pending_approval_suspends_inactivity() {
+        let store = Arc::new(MsgStore::new());
+        let pending = Arc::new(AtomicBool::new(true));
+        let started = tokio::time::Instant::now();
+        let wait = tokio::spawn({
+            let pending = pending.clone();
+            wait_for_inactivity(store, TIMEOUT, move || pending.load(Ordering::SeqCst))
+        });
+
+        tokio::time::sleep(TIMEOUT * 5 / 2).await;
+        assert!(!wait.is_finished());
+
+        pending.store(false, Ordering::SeqCst);
+        wait.await.unwrap();
+        assert_eq!(started.elapsed(), TIMEOUT * 3);
+    }
+
+    #[test]
+    fn messages_only_reach_executors_still_reading_them() {
+        let (live, dropped, missing) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let (live_tx, mut live_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (dropped_tx, dropped_rx) = tokio::sync::mpsc::unbounded_channel();
+        drop(dropped_rx);
+        let senders = HashMap::from([(live, live_tx), (dropped, dropped_tx)]);
+
+        assert!(deliver_message(
+            &senders,
+            &live,
+            "also run the tests".into()
+        ));
+        assert_eq!(live_rx.try_recv().unwrap(), "also run the tests");
+
+        // Both fall back to queueing a follow-up
+        assert!(!deliver_message(&senders, &dropped, "too late".into()));
+        assert!(!deliver_message(&senders, &missing, "no stdin".into()));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn interrupted_agent_that_exits_is_not_killed() {
+        let cancel = CancellationToken::new();
+        let (exit_tx, mut exit_rx) = oneshot::channel();
+        tokio::spawn({
+            let cancel = cancel.clone();
+            async move {
+                cancel.cancelled().await;
+                let _ = exit_tx.send(Ok(success_exit_status()));
+            }
+        });
+
+        let status = interrupt_and_wait(Some(cancel), &mut exit_rx).await;
+
+        assert!(status.unwrap().unwrap().success());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn agent_ignoring_the_interrupt_is_killed_after_grace() {
+        let cancel = CancellationToken::new();
+        let (_exit_tx, mut exit_rx) = oneshot::channel();
+        let started = tokio::time::Instant::now();
+
+        assert!(
+            interrupt_and_wait(Some(cancel.clone()), &mut exit_rx)
+                .await
+                .is_none()
+        );
+        assert!(cancel.is_cancelled());
+        assert_eq!(started.elapsed(), INACTIVITY_KILL_GRACE);
+
+        // Without a token there is nothing to wait for.
+        assert!(interrupt_and_wait(None, &mut exit_rx).await.is_none());
+    }
+}
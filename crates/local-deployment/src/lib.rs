@@ -18,6 +18,7 @@ use remote_info::RemoteInfo;
 use services::services::{
     analytics::{AnalyticsConfig, AnalyticsContext, AnalyticsService, generate_user_id},
     approvals::Approvals,
+    attempt_limiter::AttemptLimiter,
     auth::AuthContext,
     config::{Config, load_config_from_file, save_config_to_file},
     container::ContainerService,
@@ -26,10 +27,12 @@ use services::services::{
     file_search::FileSearchCache,
     filesystem::FilesystemService,
     oauth_credentials::OAuthCredentials,
-    pr_monitor::PrMonitorService,
+    pr_monitor::{PrMonitorControl, PrMonitorService},
     queued_message::QueuedMessageService,
     remote_client::{RemoteClient, RemoteClientError},
+    renormalize_jobs::RenormalizeJobs,
     repo::RepoService,
+    webhooks::WebhookService,
 };
 use tokio::sync::{Notify, RwLock};
 use tokio_util::sync::CancellationToken;
@@ -64,6 +67,7 @@ pub struct LocalDeployment {
     file_search_cache: Arc<FileSearchCache>,
     approvals: Approvals,
     queued_message_service: QueuedMessageService,
+    attempt_limiter: AttemptLimiter,
     remote_client: Result<RemoteClient, RemoteClientNotConfigured>,
     auth_context: AuthContext,
     oauth_handoffs: Arc<RwLock<HashMap<Uuid, PendingHandoff>>>,
@@ -79,6 +83,8 @@ pub struct LocalDeployment {
     ssh_config: Arc<russh::server::Config>,
     pty: PtyService,
     pr_sync_notify: Arc<Notify>,
+    pr_monitor: PrMonitorControl,
+    renormalize_jobs: RenormalizeJobs,
 }
 
 #[derive(Debug, Clone)]
@@ -124,6 +130,14 @@ impl Deployment for LocalDeployment {
             WorktreeManager::set_workspace_dir_override(path);
         }
 
+        if let Some(base_path) = &raw_config.base_path {
+            utils::set_base_path(base_path);
+        }
+
+        executors::executors::claude::set_disable_api_key_default(
+            raw_config.claude_disable_api_key,
+        );
+
         let config = Arc::new(RwLock::new(raw_config));
         let user_id = generate_user_id();
         let analytics = AnalyticsConfig::new().map(AnalyticsService::new);
@@ -159,6 +173,7 @@ impl Deployment for LocalDeployment {
 
         let approvals = Approvals::new();
         let queued_message_service = QueuedMessageService::new();
+        let attempt_limiter = AttemptLimiter::new();
 
         let oauth_credentials = Arc::new(OAuthCredentials::new(credentials_path()));
         if let Err(e) = oauth_credentials.load().await {
@@ -230,11 +245,14 @@ impl Deployment for LocalDeployment {
             analytics_ctx,
             approvals.clone(),
             queued_message_service.clone(),
+            attempt_limiter.clone(),
             remote_client.clone().ok(),
         )
         .await;
 
         let events = EventService::new(db.clone(), events_msg_store, events_entry_count);
+        let renormalize_jobs = RenormalizeJobs::new(events.msg_store().clone());
+        WebhookService::spawn(config.clone(), events.msg_store().clone());
 
         let file_search_cache = Arc::new(FileSearchCache::new());
 
@@ -252,6 +270,7 @@ impl Deployment for LocalDeployment {
             None => None,
         };
         let pr_sync_notify = Arc::new(Notify::new());
+        let pr_monitor = PrMonitorControl::new();
         {
             let db = db.clone();
             let analytics = analytics.as_ref().map(|s| AnalyticsContext {
@@ -260,7 +279,15 @@ impl Deployment for LocalDeployment {
             });
             let container = container.clone();
             let rc = remote_client.clone().ok();
-            PrMonitorService::spawn(db, analytics, container, rc, pr_sync_notify.clone()).await;
+            PrMonitorService::spawn(
+                db,
+                analytics,
+                container,
+                rc,
+                pr_sync_notify.clone(),
+                pr_monitor.clone(),
+            )
+            .await;
         }
 
         let deployment = Self {
@@ -278,6 +305,7 @@ impl Deployment for LocalDeployment {
             file_search_cache,
             approvals,
             queued_message_service,
+            attempt_limiter,
             remote_client,
             auth_context,
             oauth_handoffs,
@@ -293,6 +321,8 @@ impl Deployment for LocalDeployment {
             ssh_config,
             pty,
             pr_sync_notify,
+            pr_monitor,
+            renormalize_jobs,
         };
 
         Ok(deployment)
@@ -350,6 +380,10 @@ impl Deployment for LocalDeployment {
         &self.queued_message_service
     }
 
+    fn attempt_limiter(&self) -> &AttemptLimiter {
+        &self.attempt_limiter
+    }
+
     fn auth_context(&self) -> &AuthContext {
         &self.auth_context
     }
@@ -485,4 +519,12 @@ impl LocalDeployment {
     pub fn trigger_pr_sync(&self) {
         self.pr_sync_notify.notify_one();
     }
+
+    pub fn pr_monitor(&self) -> &PrMonitorControl {
+        &self.pr_monitor
+    }
+
+    pub fn renormalize_jobs(&self) -> &RenormalizeJobs {
+        &self.renormalize_jobs
+    }
 }
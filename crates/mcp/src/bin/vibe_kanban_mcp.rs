@@ -2,7 +2,7 @@ use mcp::task_server::McpServer;
 use rmcp::{ServiceExt, transport::stdio};
 use tracing_subscriber::{EnvFilter, prelude::*};
 use utils::{
-    port_file::read_port_file,
+    port_file::read_port_info,
     sentry::{self as sentry_utils, SentrySource, sentry_layer},
 };
 
@@ -111,24 +111,30 @@ async fn resolve_base_url(log_prefix: &str) -> anyhow::Result<String> {
         .or_else(|_| std::env::var("HOST"))
         .unwrap_or_else(|_| "127.0.0.1".to_string());
 
-    let port = match std::env::var(PORT_ENV)
+    let (port, base_path) = match std::env::var(PORT_ENV)
         .or_else(|_| std::env::var("BACKEND_PORT"))
         .or_else(|_| std::env::var("PORT"))
     {
         Ok(port_str) => {
             tracing::info!("[{}] Using port from environment: {}", log_prefix, port_str);
-            port_str
+            let port = port_str
                 .parse::<u16>()
-                .map_err(|error| anyhow::anyhow!("Invalid port value '{}': {}", port_str, error))?
+                .map_err(|error| anyhow::anyhow!("Invalid port value '{}': {}", port_str, error))?;
+            // The API is also served without the base path prefix.
+            (port, String::new())
         }
         Err(_) => {
-            let port = read_port_file("vibe-kanban").await?;
-            tracing::info!("[{}] Using port from port file: {}", log_prefix, port);
-            port
+            let info = read_port_info("vibe-kanban").await?;
+            tracing::info!(
+                "[{}] Using port from port file: {}",
+                log_prefix,
+                info.main_port
+            );
+            (info.main_port, info.base_path.unwrap_or_default())
         }
     };
 
-    let url = format!("http://{}:{}", host, port);
+    let url = format!("http://{}:{}{}", host, port, base_path);
     tracing::info!("[{}] Using backend URL: {}", log_prefix, url);
     Ok(url)
 }
@@ -338,7 +338,10 @@ impl McpServer {
             return Ok(Self::tool_error(error_result));
         }
 
-        let is_finished = execution_process.status != ExecutionProcessStatus::Running;
+        let is_finished = !matches!(
+            execution_process.status,
+            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+        );
 
         let execution_process_value = match Self::serialize_execution_process(&execution_process) {
             Ok(value) => value,
@@ -1,6 +1,6 @@
 use db::models::requests::{
     CreateAndStartWorkspaceRequest, CreateAndStartWorkspaceResponse, LinkedIssueInfo,
-    WorkspaceRepoInput,
+    WorkspaceRepoInput, WorkspaceStartMode,
 };
 use executors::profile::ExecutorConfig;
 use rmcp::{
@@ -25,7 +25,7 @@ struct StartWorkspaceRequest {
     #[schemars(description = "Name for the workspace")]
     name: String,
     #[schemars(
-        description = "Optional prompt for the first workspace session. If omitted/empty, the linked issue title/description is used."
+        description = "Optional prompt for the first workspace session. If omitted/empty, the prompt is built from the linked issue using the issue prompt template."
     )]
     prompt: Option<String>,
     #[schemars(
@@ -40,6 +40,10 @@ struct StartWorkspaceRequest {
         description = "Optional issue ID to link the workspace to. When provided, the workspace will be associated with this remote issue."
     )]
     issue_id: Option<Uuid>,
+    #[schemars(
+        description = "'run' (default) or 'preview'. A preview run keeps none of the agent's file changes; fetch them afterwards with `get_workspace_preview`."
+    )]
+    mode: Option<String>,
 }
 
 #[derive(Debug, Serialize, schemars::JsonSchema)]
@@ -65,30 +69,6 @@ struct LinkWorkspaceIssueResponse {
     issue_id: String,
 }
 
-fn build_workspace_prompt_from_issue(issue: &api_types::Issue) -> Option<String> {
-    let title = issue.title.trim();
-    let description = issue
-        .description
-        .as_deref()
-        .map(str::trim)
-        .filter(|d| !d.is_empty())
-        .unwrap_or_default();
-
-    if title.is_empty() && description.is_empty() {
-        return None;
-    }
-
-    if description.is_empty() {
-        return Some(title.to_string());
-    }
-
-    if title.is_empty() {
-        return Some(description.to_string());
-    }
-
-    Some(format!("{title}\n\n{description}"))
-}
-
 #[tool_router(router = task_attempts_tools_router, vis = "pub")]
 impl McpServer {
     #[tool(description = "Create a new workspace and start its first session.")]
@@ -101,6 +81,7 @@ impl McpServer {
             variant,
             repositories,
             issue_id,
+            mode,
         }): Parameters<StartWorkspaceRequest>,
     ) -> Result<CallToolResult, ErrorData> {
         if repositories.is_empty() {
@@ -131,6 +112,17 @@ impl McpServer {
             }
         };
 
+        let mode = match mode.as_deref().map(str::trim).unwrap_or_default() {
+            "" | "run" => WorkspaceStartMode::Run,
+            "preview" => WorkspaceStartMode::Preview,
+            other => {
+                return Self::err(
+                    format!("Unknown mode '{other}'. Use 'run' or 'preview'."),
+                    None::<String>,
+                );
+            }
+        };
+
         let variant = variant.and_then(|v| {
             let trimmed = v.trim();
             if trimmed.is_empty() {
@@ -148,33 +140,27 @@ impl McpServer {
             })
             .collect();
 
-        let (linked_issue, issue_prompt) = if let Some(issue_id) = issue_id {
+        let linked_issue = if let Some(issue_id) = issue_id {
             let issue_url = self.url(&format!("/api/remote/issues/{issue_id}"));
             let issue: api_types::Issue = match self.send_json(self.client.get(&issue_url)).await {
                 Ok(issue) => issue,
                 Err(e) => return Ok(Self::tool_error(e)),
             };
 
-            (
-                Some(LinkedIssueInfo {
-                    remote_project_id: issue.project_id,
-                    issue_id,
-                }),
-                build_workspace_prompt_from_issue(&issue),
-            )
+            Some(LinkedIssueInfo {
+                remote_project_id: issue.project_id,
+                issue_id,
+            })
         } else {
-            (None, None)
+            None
         };
 
-        let workspace_prompt = match prompt.or(issue_prompt) {
-            Some(prompt) => prompt,
-            None => {
-                return Self::err(
-                    "Provide `prompt`, or `issue_id` that has a non-empty title/description.",
-                    None::<&str>,
-                );
-            }
-        };
+        // Without a prompt, the server composes one from the linked issue using
+        // the configured issue prompt template.
+        let prompt_from_issue = prompt.is_none();
+        if prompt_from_issue && linked_issue.is_none() {
+            return Self::err("Provide `prompt`, or an `issue_id`.", None::<&str>);
+        }
 
         let create_and_start_payload = CreateAndStartWorkspaceRequest {
             name: Some(name.clone()),
@@ -188,8 +174,11 @@ impl McpServer {
                 reasoning_id: None,
                 permission_policy: None,
             },
-            prompt: workspace_prompt,
+            prompt: prompt.unwrap_or_default(),
+            prompt_from_issue,
             attachment_ids: None,
+            env_overrides: Default::default(),
+            mode,
         };
 
         let create_and_start_url = self.url("/api/workspaces/start");
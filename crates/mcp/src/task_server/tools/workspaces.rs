@@ -96,6 +96,12 @@ struct McpDeleteWorkspaceResponse {
     delete_branches: bool,
 }
 
+#[derive(Debug, Deserialize, schemars::JsonSchema)]
+struct McpGetWorkspacePreviewRequest {
+    #[schemars(description = "Workspace ID. Optional if running inside that workspace context.")]
+    workspace_id: Option<Uuid>,
+}
+
 #[tool_router(router = workspaces_tools_router, vis = "pub")]
 impl McpServer {
     #[tool(description = "List local workspaces with optional filters and pagination.")]
@@ -247,4 +253,33 @@ impl McpServer {
             delete_branches,
         })
     }
+
+    #[tool(
+        description = "Get the file changes proposed by a workspace's latest preview run, grouped by path. The list is complete once `status` is no longer 'running'. The workspace must have been started with mode 'preview'."
+    )]
+    async fn get_workspace_preview(
+        &self,
+        Parameters(McpGetWorkspacePreviewRequest { workspace_id }): Parameters<
+            McpGetWorkspacePreviewRequest,
+        >,
+    ) -> Result<CallToolResult, ErrorData> {
+        let workspace_id = match self.resolve_workspace_id(workspace_id) {
+            Ok(id) => id,
+            Err(error_result) => return Ok(Self::tool_error(error_result)),
+        };
+        if let Err(error_result) = self.scope_allows_workspace(workspace_id) {
+            return Ok(Self::tool_error(error_result));
+        }
+
+        let url = self.url(&format!(
+            "/api/workspaces/{}/proposed-changes",
+            workspace_id
+        ));
+        let preview: serde_json::Value = match self.send_json(self.client.get(&url)).await {
+            Ok(preview) => preview,
+            Err(e) => return Ok(Self::tool_error(e)),
+        };
+
+        McpServer::success(&preview)
+    }
 }
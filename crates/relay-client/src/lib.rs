@@ -150,6 +150,7 @@ impl RelayApiClient {
             timestamp,
             nonce,
             signature_b64,
+            rotate_server_key: false,
         };
 
         self.post_session_api(
@@ -1,8 +1,8 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, VecDeque},
     fs, io,
     path::Path,
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
@@ -118,17 +118,102 @@ impl RelaySignatureValidationError {
             Self::InvalidSignature => "invalid signature",
         }
     }
+
+    /// Stable identifier for metrics.
+    pub fn code(self) -> &'static str {
+        match self {
+            Self::TimestampOutOfDrift => "timestamp_out_of_drift",
+            Self::MissingSigningSession => "missing_signing_session",
+            Self::InvalidNonce => "invalid_nonce",
+            Self::ReplayNonce => "replay_nonce",
+            Self::InvalidSignature => "invalid_signature",
+        }
+    }
+}
+
+/// Snapshot of the signing sessions and recent verification failures.
+#[derive(Debug, Clone)]
+pub struct RelaySigningSessionStats {
+    pub active_sessions: usize,
+    /// Nonces currently remembered for replay protection, across sessions.
+    pub seen_nonces: usize,
+    /// How far back `failures` reaches.
+    pub failure_window: Duration,
+    /// Verification failures inside the window, by reason, most common first.
+    pub failures: Vec<(RelaySignatureValidationError, usize)>,
 }
 
 const RELAY_SIGNATURE_MAX_TIMESTAMP_DRIFT_SECS: i64 = 30;
 const RELAY_SIGNING_SESSION_TTL: Duration = Duration::from_secs(60 * 60);
 const RELAY_SIGNING_SESSION_IDLE_TTL: Duration = Duration::from_secs(15 * 60);
 const RELAY_NONCE_TTL: Duration = Duration::from_secs(2 * 60);
+const RELAY_SESSION_KEY_ROTATION_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const RELAY_SESSION_KEY_GRACE: Duration = RELAY_NONCE_TTL;
+const RELAY_FAILURE_WINDOW: Duration = Duration::from_secs(15 * 60);
+/// Upper bound on failures kept for stats, so a flood of bad requests
+/// cannot grow the log without limit.
+const RELAY_FAILURE_LOG_CAPACITY: usize = 1024;
+
+/// Server key used for a single signing session in place of the long-term
+/// server key. It is replaced every [`RELAY_SESSION_KEY_ROTATION_INTERVAL`];
+/// retired keys stay listed for [`RELAY_SESSION_KEY_GRACE`] so messages
+/// signed just before a rotation still verify.
+struct RotatingSessionKey {
+    current: SigningKey,
+    rotated_at: Instant,
+    retired: Vec<(VerifyingKey, Instant)>,
+}
+
+impl RotatingSessionKey {
+    fn new(now: Instant) -> Self {
+        Self {
+            current: SigningKey::generate(&mut OsRng),
+            rotated_at: now,
+            retired: Vec::new(),
+        }
+    }
+
+    fn rotate_if_due(&mut self, now: Instant) {
+        self.retired
+            .retain(|(_, retired_at)| now.duration_since(*retired_at) <= RELAY_SESSION_KEY_GRACE);
+        if now.duration_since(self.rotated_at) < RELAY_SESSION_KEY_ROTATION_INTERVAL {
+            return;
+        }
+        let previous = std::mem::replace(&mut self.current, SigningKey::generate(&mut OsRng));
+        self.retired.push((previous.verifying_key(), now));
+        self.rotated_at = now;
+    }
+}
+
+/// Public half of a session's rotating server keys.
+#[derive(Debug, Clone)]
+pub struct SessionServerKeys {
+    pub current: VerifyingKey,
+    /// Recently retired keys, newest first, still accepted by verifiers.
+    pub previous: Vec<VerifyingKey>,
+}
+
+/// Message the long-term server key signs to vouch for a session key, so a
+/// client that pinned the server key at pairing can trust rotated keys.
+pub fn build_session_key_endorsement_message(
+    signing_session_id: Uuid,
+    session_public_key: &VerifyingKey,
+) -> String {
+    format!(
+        "v1|session-key|{signing_session_id}|{}",
+        BASE64_STANDARD.encode(session_public_key.as_bytes())
+    )
+}
 
 #[derive(Clone)]
 pub struct RelaySigningService {
     sessions: Arc<RwLock<HashMap<Uuid, RelaySigningSession>>>,
     server_signing_key: Arc<SigningKey>,
+    /// Sessions that opted into rotating server keys. Kept behind a sync lock
+    /// because WebSocket frame signing is synchronous.
+    session_keys: Arc<Mutex<HashMap<Uuid, RotatingSessionKey>>>,
+    /// Recent verification failures, oldest first.
+    failures: Arc<Mutex<VecDeque<(Instant, RelaySignatureValidationError)>>>,
 }
 
 impl RelaySigningService {
@@ -136,6 +221,8 @@ impl RelaySigningService {
         Self {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             server_signing_key: Arc::new(server_signing_key),
+            session_keys: Arc::new(Mutex::new(HashMap::new())),
+            failures: Arc::new(Mutex::new(VecDeque::new())),
         }
     }
 
@@ -201,6 +288,41 @@ impl RelaySigningService {
         self.server_signing_key.sign(message)
     }
 
+    /// Sign on behalf of a signing session. Sessions created with
+    /// [`Self::create_session_with_rotating_key`] use their current session
+    /// key; all others use the long-term server key.
+    pub fn sign_for_session(&self, signing_session_id: Uuid, message: &[u8]) -> Signature {
+        let mut session_keys = self.session_keys.lock().unwrap();
+        match session_keys.get_mut(&signing_session_id) {
+            Some(key) => {
+                key.rotate_if_due(Instant::now());
+                key.current.sign(message)
+            }
+            None => self.server_signing_key.sign(message),
+        }
+    }
+
+    /// Current and recently retired public keys for a session with a rotating
+    /// server key, or `None` if the session signs with the long-term key.
+    pub fn session_server_keys(&self, signing_session_id: Uuid) -> Option<SessionServerKeys> {
+        let mut session_keys = self.session_keys.lock().unwrap();
+        let key = session_keys.get_mut(&signing_session_id)?;
+        key.rotate_if_due(Instant::now());
+        Some(SessionServerKeys {
+            current: key.current.verifying_key(),
+            previous: key.retired.iter().rev().map(|(k, _)| *k).collect(),
+        })
+    }
+
+    pub async fn create_session_with_rotating_key(&self, peer_public_key: VerifyingKey) -> Uuid {
+        let signing_session_id = self.create_session(peer_public_key).await;
+        self.session_keys
+            .lock()
+            .unwrap()
+            .insert(signing_session_id, RotatingSessionKey::new(Instant::now()));
+        signing_session_id
+    }
+
     pub async fn create_session(&self, peer_public_key: VerifyingKey) -> Uuid {
         let signing_session_id = Uuid::new_v4();
         self.register_session(signing_session_id, peer_public_key)
@@ -231,6 +353,22 @@ impl RelaySigningService {
         method: &str,
         path_and_query: &str,
         body: &[u8],
+    ) -> Result<(), RelaySignatureValidationError> {
+        let result = self
+            .verify_request_inner(request_signature, method, path_and_query, body)
+            .await;
+        if let Err(error) = result {
+            self.record_failure(error, Instant::now());
+        }
+        result
+    }
+
+    async fn verify_request_inner(
+        &self,
+        request_signature: &RequestSignature,
+        method: &str,
+        path_and_query: &str,
+        body: &[u8],
     ) -> Result<(), RelaySignatureValidationError> {
         validate_timestamp(request_signature.timestamp)?;
 
@@ -239,9 +377,6 @@ impl RelaySigningService {
             .get_valid_session(request_signature.signing_session_id)
             .await?;
 
-        session
-            .seen_nonces
-            .retain(|_, seen_at| Instant::now().duration_since(*seen_at) <= RELAY_NONCE_TTL);
         if session.seen_nonces.contains_key(&request_signature.nonce) {
             return Err(RelaySignatureValidationError::ReplayNonce);
         }
@@ -288,6 +423,54 @@ impl RelaySigningService {
         })
     }
 
+    /// Live session and nonce counts, plus verification failures from the
+    /// last [`RELAY_FAILURE_WINDOW`]. Only takes the read lock.
+    pub async fn relay_signing_session_stats(&self) -> RelaySigningSessionStats {
+        let now = Instant::now();
+        let (active_sessions, seen_nonces) = {
+            let sessions = self.sessions.read().await;
+            sessions
+                .values()
+                .filter(|session| {
+                    now.duration_since(session.created_at) <= RELAY_SIGNING_SESSION_TTL
+                        && now.duration_since(session.last_used_at)
+                            <= RELAY_SIGNING_SESSION_IDLE_TTL
+                })
+                .fold((0, 0), |(sessions, nonces), session| {
+                    (sessions + 1, nonces + session.seen_nonces.len())
+                })
+        };
+
+        let mut failures: Vec<(RelaySignatureValidationError, usize)> = Vec::new();
+        {
+            let mut log = self.failures.lock().unwrap();
+            prune_failures(&mut log, now);
+            for (_, error) in log.iter() {
+                match failures.iter_mut().find(|(e, _)| e == error) {
+                    Some((_, count)) => *count += 1,
+                    None => failures.push((*error, 1)),
+                }
+            }
+        }
+        failures.sort_by(|a, b| b.1.cmp(&a.1));
+
+        RelaySigningSessionStats {
+            active_sessions,
+            seen_nonces,
+            failure_window: RELAY_FAILURE_WINDOW,
+            failures,
+        }
+    }
+
+    fn record_failure(&self, error: RelaySignatureValidationError, now: Instant) {
+        let mut log = self.failures.lock().unwrap();
+        prune_failures(&mut log, now);
+        if log.len() >= RELAY_FAILURE_LOG_CAPACITY {
+            log.pop_front();
+        }
+        log.push_back((now, error));
+    }
+
     async fn get_valid_session(
         &self,
         signing_session_id: Uuid,
@@ -296,14 +479,32 @@ impl RelaySigningService {
         let mut sessions = self.sessions.write().await;
         let now = Instant::now();
         sessions.retain(|_, session| {
+            // Drop expired nonces from every session, not just the one being
+            // verified, so idle sessions don't hold on to them.
+            session
+                .seen_nonces
+                .retain(|_, seen_at| now.duration_since(*seen_at) <= RELAY_NONCE_TTL);
             now.duration_since(session.created_at) <= RELAY_SIGNING_SESSION_TTL
                 && now.duration_since(session.last_used_at) <= RELAY_SIGNING_SESSION_IDLE_TTL
         });
+        self.session_keys
+            .lock()
+            .unwrap()
+            .retain(|id, _| sessions.contains_key(id));
         RwLockWriteGuard::try_map(sessions, |sessions| sessions.get_mut(&signing_session_id))
             .map_err(|_| RelaySignatureValidationError::MissingSigningSession)
     }
 }
 
+fn prune_failures(log: &mut VecDeque<(Instant, RelaySignatureValidationError)>, now: Instant) {
+    while log
+        .front()
+        .is_some_and(|(at, _)| now.duration_since(*at) > RELAY_FAILURE_WINDOW)
+    {
+        log.pop_front();
+    }
+}
+
 fn validate_timestamp(timestamp: i64) -> Result<(), RelaySignatureValidationError> {
     let now_secs = i64::try_from(
         SystemTime::now()
@@ -326,3 +527,80 @@ fn parse_signature_b64(signature_b64: &str) -> Result<Signature, RelaySignatureV
         .map_err(|_| RelaySignatureValidationError::InvalidSignature)?;
     Signature::from_slice(&sig_bytes).map_err(|_| RelaySignatureValidationError::InvalidSignature)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rotated_session_key_stays_listed_for_grace_window() {
+        let start = Instant::now();
+        let mut key = RotatingSessionKey::new(start);
+        let first = key.current.verifying_key();
+
+        key.rotate_if_due(start + RELAY_SESSION_KEY_ROTATION_INTERVAL);
+        assert_ne!(key.current.verifying_key(), first);
+        assert_eq!(key.retired.len(), 1);
+        assert_eq!(key.retired[0].0, first);
+
+        key.rotate_if_due(
+            start + RELAY_SESSION_KEY_ROTATION_INTERVAL + RELAY_SESSION_KEY_GRACE * 2,
+        );
+        assert!(key.retired.is_empty());
+    }
+
+    #[tokio::test]
+    async fn stats_count_failures_by_reason() {
+        let server = RelaySigningService::new(SigningKey::generate(&mut OsRng));
+        let client_key = SigningKey::generate(&mut OsRng);
+        let session_id = server.create_session(client_key.verifying_key()).await;
+
+        let sig = build_request_signature(&client_key, session_id, "GET", "/api/info", b"");
+        assert!(
+            server
+                .verify_request(&sig, "GET", "/api/info", b"")
+                .await
+                .is_ok()
+        );
+        assert_eq!(
+            server.verify_request(&sig, "GET", "/api/info", b"").await,
+            Err(RelaySignatureValidationError::ReplayNonce)
+        );
+        let stale = RequestSignature {
+            timestamp: sig.timestamp - 600,
+            ..sig.clone()
+        };
+        for _ in 0..2 {
+            let _ = server.verify_request(&stale, "GET", "/api/info", b"").await;
+        }
+
+        let stats = server.relay_signing_session_stats().await;
+        assert_eq!(stats.active_sessions, 1);
+        assert_eq!(stats.seen_nonces, 1);
+        assert_eq!(
+            stats.failures,
+            vec![
+                (RelaySignatureValidationError::TimestampOutOfDrift, 2),
+                (RelaySignatureValidationError::ReplayNonce, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn failures_outside_window_are_dropped() {
+        let server = RelaySigningService::new(SigningKey::generate(&mut OsRng));
+        let start = Instant::now();
+        server.record_failure(RelaySignatureValidationError::InvalidSignature, start);
+        server.record_failure(
+            RelaySignatureValidationError::ReplayNonce,
+            start + RELAY_FAILURE_WINDOW,
+        );
+        let mut log = server.failures.lock().unwrap();
+        prune_failures(
+            &mut log,
+            start + RELAY_FAILURE_WINDOW + Duration::from_secs(1),
+        );
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].1, RelaySignatureValidationError::ReplayNonce);
+    }
+}
@@ -15,7 +15,7 @@ use tokio_util::sync::CancellationToken;
 use tokio_yamux::Session;
 use ws_bridge::tungstenite_ws_stream_io;
 
-use crate::{tls::ws_connector, yamux_config};
+use crate::{KEEPALIVE_PATH, tls::ws_connector, yamux_config};
 
 pub struct RelayClientConfig {
     pub ws_url: String,
@@ -95,6 +95,10 @@ async fn proxy_to_local(
     mut request: Request<Incoming>,
     local_addr: SocketAddr,
 ) -> Result<Response<Body>, Infallible> {
+    if request.uri().path() == KEEPALIVE_PATH {
+        return Ok(simple_response(StatusCode::OK, "pong"));
+    }
+
     request
         .headers_mut()
         .insert("x-vk-relayed", http::HeaderValue::from_static("1"));
@@ -18,6 +18,10 @@ pub(crate) fn yamux_config() -> YamuxConfig {
     }
 }
 
+/// Path the relay client answers itself, used by the server to check that the
+/// control channel is still alive.
+pub const KEEPALIVE_PATH: &str = "/__relay/ping";
+
 /// Convert an HTTP(S) URL to its WebSocket equivalent (ws:// or wss://).
 pub fn http_to_ws_url(http_url: &str) -> anyhow::Result<String> {
     if let Some(rest) = http_url.strip_prefix("https://") {
@@ -1,4 +1,4 @@
-use std::{future::Future, sync::Arc};
+use std::{future::Future, sync::Arc, time::Duration};
 
 use axum::{
     body::Body,
@@ -13,15 +13,32 @@ use tokio::sync::Mutex;
 use tokio_yamux::{Control, Session};
 use ws_bridge::axum_ws_stream_io;
 
-use crate::yamux_config;
+use crate::{KEEPALIVE_PATH, yamux_config};
 
 pub type SharedControl = Arc<Mutex<Control>>;
 
+/// Application-level keepalive for the control channel.
+#[derive(Debug, Clone, Copy)]
+pub struct KeepaliveConfig {
+    /// How long to wait between pings.
+    pub interval: Duration,
+    /// How long a ping may take before the relay is considered dead.
+    pub timeout: Duration,
+}
+
 /// Runs the server-side control channel over an upgraded WebSocket.
 ///
 /// The provided callback is invoked once, after yamux is initialized, with a
 /// shared control handle that can be used to proxy requests over new streams.
-pub async fn run_control_channel<F, Fut>(socket: WebSocket, on_connected: F) -> anyhow::Result<()>
+///
+/// With `keepalive` set, the client is pinged periodically and the channel is
+/// closed with an error as soon as a ping fails or times out, so dead relays
+/// behind NAT or idle timeouts don't linger until the next proxied request.
+pub async fn run_control_channel<F, Fut>(
+    socket: WebSocket,
+    keepalive: Option<KeepaliveConfig>,
+    on_connected: F,
+) -> anyhow::Result<()>
 where
     F: FnOnce(SharedControl) -> Fut,
     Fut: Future<Output = ()>,
@@ -30,19 +47,77 @@ where
     let mut session = Session::new_server(ws_io, yamux_config());
     let control = Arc::new(Mutex::new(session.control()));
 
-    on_connected(control).await;
+    on_connected(control.clone()).await;
 
-    while let Some(stream_result) = session.next().await {
-        match stream_result {
-            Ok(_stream) => {
-                // The client side does not currently open server-initiated streams.
-            }
-            Err(error) => {
-                return Err(anyhow::anyhow!("relay session error: {error}"));
+    // The session has to keep being polled for pings to make progress, so the
+    // keepalive loop runs alongside it rather than in between.
+    let keepalive_loop = async {
+        match keepalive {
+            Some(keepalive) => run_keepalive(&control, keepalive).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(keepalive_loop);
+
+    loop {
+        tokio::select! {
+            stream_result = session.next() => match stream_result {
+                Some(Ok(_stream)) => {
+                    // The client side does not currently open server-initiated streams.
+                }
+                Some(Err(error)) => {
+                    return Err(anyhow::anyhow!("relay session error: {error}"));
+                }
+                None => return Ok(()),
+            },
+            // Returning drops the session, which closes the WebSocket.
+            error = &mut keepalive_loop => return Err(error),
+        }
+    }
+}
+
+/// Pings the client until a ping fails, returning the failure.
+async fn run_keepalive(control: &Mutex<Control>, keepalive: KeepaliveConfig) -> anyhow::Error {
+    let mut ticker = tokio::time::interval(keepalive.interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    // The first tick completes immediately; the channel was just established.
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+        match tokio::time::timeout(keepalive.timeout, ping(control)).await {
+            Ok(Ok(())) => {}
+            Ok(Err(error)) => return error.context("relay keepalive ping failed"),
+            Err(_) => {
+                return anyhow::anyhow!(
+                    "relay keepalive ping timed out after {:?}",
+                    keepalive.timeout
+                );
             }
         }
     }
+}
+
+/// Sends one ping over a fresh stream. Any HTTP response counts as a pong, so
+/// clients that forward the ping to their local server still pass.
+async fn ping(control: &Mutex<Control>) -> anyhow::Result<()> {
+    let stream = control
+        .lock()
+        .await
+        .open_stream()
+        .await
+        .map_err(|error| anyhow::anyhow!("failed to open keepalive stream: {error}"))?;
+    let (mut sender, connection) = client_http1::Builder::new()
+        .handshake(TokioIo::new(stream))
+        .await?;
+    tokio::spawn(async move {
+        if let Err(error) = connection.await {
+            tracing::debug!(?error, "relay keepalive stream closed");
+        }
+    });
 
+    let request = axum::http::Request::get(KEEPALIVE_PATH).body(Body::empty())?;
+    sender.send_request(request).await?;
     Ok(())
 }
 
@@ -1,6 +1,7 @@
-use std::env;
+use std::{env, time::Duration};
 
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use relay_tunnel_core::server::KeepaliveConfig;
 use secrecy::SecretString;
 
 #[derive(Debug, Clone)]
@@ -8,8 +9,13 @@ pub struct RelayServerConfig {
     pub database_url: String,
     pub listen_addr: String,
     pub jwt_secret: SecretString,
+    /// Control channel keepalive; `None` when `RELAY_KEEPALIVE_INTERVAL_SECS=0`.
+    pub keepalive: Option<KeepaliveConfig>,
 }
 
+const DEFAULT_KEEPALIVE_INTERVAL_SECS: u64 = 30;
+const DEFAULT_KEEPALIVE_TIMEOUT_SECS: u64 = 10;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("environment variable `{0}` is not set")]
@@ -32,14 +38,41 @@ impl RelayServerConfig {
         validate_jwt_secret(&jwt_secret_str)?;
         let jwt_secret = SecretString::new(jwt_secret_str.into());
 
+        let keepalive_interval = env_secs(
+            "RELAY_KEEPALIVE_INTERVAL_SECS",
+            DEFAULT_KEEPALIVE_INTERVAL_SECS,
+        )?;
+        let keepalive_timeout = env_secs(
+            "RELAY_KEEPALIVE_TIMEOUT_SECS",
+            DEFAULT_KEEPALIVE_TIMEOUT_SECS,
+        )?;
+        if keepalive_timeout == 0 {
+            return Err(ConfigError::InvalidVar("RELAY_KEEPALIVE_TIMEOUT_SECS"));
+        }
+        let keepalive = (keepalive_interval > 0).then(|| KeepaliveConfig {
+            interval: Duration::from_secs(keepalive_interval),
+            timeout: Duration::from_secs(keepalive_timeout),
+        });
+
         Ok(Self {
             database_url,
             listen_addr,
             jwt_secret,
+            keepalive,
         })
     }
 }
 
+fn env_secs(name: &'static str, default: u64) -> Result<u64, ConfigError> {
+    match env::var(name) {
+        Ok(value) => value
+            .trim()
+            .parse()
+            .map_err(|_| ConfigError::InvalidVar(name)),
+        Err(_) => Ok(default),
+    }
+}
+
 fn validate_jwt_secret(secret: &str) -> Result<(), ConfigError> {
     let decoded = BASE64_STANDARD
         .decode(secret.as_bytes())
@@ -8,7 +8,7 @@ use axum::{
     http::StatusCode,
     response::{IntoResponse, Response},
 };
-use relay_tunnel_core::server::run_control_channel;
+use relay_tunnel_core::server::{KeepaliveConfig, run_control_channel};
 use serde::Deserialize;
 use uuid::Uuid;
 
@@ -62,9 +62,10 @@ pub async fn relay_connect(
 
     let registry = state.relay_registry.clone();
     let pool = state.pool.clone();
+    let keepalive = state.config.keepalive;
 
     ws.on_upgrade(move |socket| async move {
-        handle_control_channel(socket, pool, registry, host_id).await;
+        handle_control_channel(socket, pool, registry, host_id, keepalive).await;
     })
 }
 
@@ -73,11 +74,12 @@ async fn handle_control_channel(
     pool: sqlx::PgPool,
     registry: RelayRegistry,
     host_id: Uuid,
+    keepalive: Option<KeepaliveConfig>,
 ) {
     let registry_for_connect = registry.clone();
     let connected_relay = Arc::new(tokio::sync::Mutex::new(None::<Arc<ActiveRelay>>));
     let connected_relay_for_connect = connected_relay.clone();
-    let run_result = run_control_channel(socket, move |control| {
+    let run_result = run_control_channel(socket, keepalive, move |control| {
         let registry_for_connect = registry_for_connect.clone();
         let connected_relay_for_connect = connected_relay_for_connect.clone();
         async move {
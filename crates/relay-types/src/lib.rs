@@ -76,6 +76,10 @@ pub struct RefreshRelaySigningSessionRequest {
     pub timestamp: i64,
     pub nonce: String,
     pub signature_b64: String,
+    /// Sign this session with a periodically rotated server key instead of
+    /// the long-term one. Fetch the keys from the session keys endpoint.
+    #[serde(default)]
+    pub rotate_server_key: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -83,6 +87,37 @@ pub struct RefreshRelaySigningSessionResponse {
     pub signing_session_id: Uuid,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RelaySessionServerKey {
+    pub public_key_b64: String,
+    /// Signature by the long-term server key over the session id and this key.
+    pub endorsement_b64: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RelaySessionServerKeysResponse {
+    pub current: RelaySessionServerKey,
+    /// Retired keys still inside the grace window, newest first.
+    pub previous: Vec<RelaySessionServerKey>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RelaySignatureFailureCount {
+    /// Validation error code, e.g. `replay_nonce`.
+    pub reason: String,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RelaySigningSessionStatsResponse {
+    pub active_sessions: u32,
+    pub seen_nonces: u32,
+    /// How far back `failures` reaches, in seconds.
+    pub failure_window_secs: u32,
+    /// Rejected signed requests inside the window, most common reason first.
+    pub failures: Vec<RelaySignatureFailureCount>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct PairRelayHostRequest {
     pub host_id: Uuid,
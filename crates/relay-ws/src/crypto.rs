@@ -45,7 +45,10 @@ impl WsFrameSigner {
             frame.msg_type,
             &frame.payload,
         );
-        let signature = self.signing.sign_bytes(signing_input.as_bytes());
+        let signature = self.signing.sign_for_session(
+            self.request_signature.signing_session_id,
+            signing_input.as_bytes(),
+        );
         let signature_b64 = BASE64_STANDARD.encode(signature.to_bytes());
         let envelope = SignedWsEnvelope {
             version: ENVELOPE_VERSION,
@@ -0,0 +1,13 @@
+-- Device-code logins for headless clients. The user code is entered in a
+-- browser on another device; the app code is parked here until the client
+-- polls for it and redeems it with its PKCE verifier.
+CREATE TABLE IF NOT EXISTS oauth_device_codes (
+    user_code       TEXT PRIMARY KEY,
+    handoff_id      UUID NOT NULL UNIQUE REFERENCES oauth_handoffs(id) ON DELETE CASCADE,
+    app_code        TEXT,
+    expires_at      TIMESTAMPTZ NOT NULL,
+    created_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_oauth_device_codes_expires_at
+    ON oauth_device_codes (expires_at);
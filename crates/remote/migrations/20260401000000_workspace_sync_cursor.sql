@@ -0,0 +1,35 @@
+-- Let clients fetch only the workspaces that changed since their last sync.
+
+-- Bump updated_at on every change, including ones made by cascades such as
+-- issue_id being cleared when the issue is deleted.
+CREATE TRIGGER trg_workspaces_updated_at
+    BEFORE UPDATE ON workspaces
+    FOR EACH ROW
+    EXECUTE FUNCTION set_updated_at();
+
+CREATE INDEX idx_workspaces_project_updated_at ON workspaces (project_id, updated_at);
+
+-- Deleted workspaces, so a delta sync can tell clients to drop them.
+CREATE TABLE workspace_deletions (
+    workspace_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL,
+    deleted_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_workspace_deletions_project_deleted_at
+    ON workspace_deletions (project_id, deleted_at);
+
+CREATE OR REPLACE FUNCTION record_workspace_deletion()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO workspace_deletions (workspace_id, project_id)
+    VALUES (OLD.id, OLD.project_id)
+    ON CONFLICT (workspace_id) DO UPDATE SET deleted_at = NOW();
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_workspaces_record_deletion
+    AFTER DELETE ON workspaces
+    FOR EACH ROW
+    EXECUTE FUNCTION record_workspace_deletion();
@@ -0,0 +1,69 @@
+-- Key the workspace sync cursor on a change sequence instead of updated_at.
+--
+-- NOW() is the transaction start time, so a slow transaction can commit rows
+-- stamped before a cursor a client already holds. change_seq is the writing
+-- transaction's id, which only grows, and the cursor handed out is the oldest
+-- transaction still in flight, so anything committed later is at or above it.
+
+ALTER TABLE workspaces
+    ADD COLUMN change_seq BIGINT NOT NULL DEFAULT 0;
+
+CREATE OR REPLACE FUNCTION set_workspace_change_seq()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.change_seq = pg_current_xact_id()::text::bigint;
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trg_workspaces_change_seq
+    BEFORE INSERT OR UPDATE ON workspaces
+    FOR EACH ROW
+    EXECUTE FUNCTION set_workspace_change_seq();
+
+DROP INDEX IF EXISTS idx_workspaces_project_updated_at;
+CREATE INDEX idx_workspaces_project_change_seq ON workspaces (project_id, change_seq);
+
+ALTER TABLE workspace_deletions
+    ADD COLUMN change_seq BIGINT NOT NULL DEFAULT 0;
+
+DROP INDEX IF EXISTS idx_workspace_deletions_project_deleted_at;
+CREATE INDEX idx_workspace_deletions_project_change_seq
+    ON workspace_deletions (project_id, change_seq);
+CREATE INDEX idx_workspace_deletions_deleted_at ON workspace_deletions (deleted_at);
+
+-- Highest change_seq of the pruned deletions per project. Clients whose
+-- cursor is at or below it may have missed a deletion and get a full sync.
+CREATE TABLE workspace_sync_horizons (
+    project_id UUID PRIMARY KEY,
+    pruned_through BIGINT NOT NULL
+);
+
+-- Record the deletion and drop deletions older than 30 days.
+CREATE OR REPLACE FUNCTION record_workspace_deletion()
+RETURNS TRIGGER AS $$
+BEGIN
+    INSERT INTO workspace_deletions (workspace_id, project_id, change_seq)
+    VALUES (OLD.id, OLD.project_id, pg_current_xact_id()::text::bigint)
+    ON CONFLICT (workspace_id) DO UPDATE
+        SET deleted_at = NOW(),
+            change_seq = EXCLUDED.change_seq;
+
+    WITH pruned AS (
+        DELETE FROM workspace_deletions
+        WHERE deleted_at < NOW() - INTERVAL '30 days'
+        RETURNING project_id, change_seq
+    )
+    INSERT INTO workspace_sync_horizons (project_id, pruned_through)
+    SELECT project_id, MAX(change_seq)
+    FROM pruned
+    GROUP BY project_id
+    ON CONFLICT (project_id) DO UPDATE
+        SET pruned_through = GREATEST(
+            workspace_sync_horizons.pruned_through,
+            EXCLUDED.pruned_through
+        );
+
+    RETURN OLD;
+END;
+$$ LANGUAGE plpgsql;
@@ -0,0 +1,3 @@
+-- Time spent running agents and scripts in the local workspace, pushed by the
+-- client alongside its diff stats.
+ALTER TABLE workspaces ADD COLUMN time_spent_ms BIGINT;
@@ -1,4 +1,7 @@
+use std::sync::Arc;
+
 use image::{DynamicImage, imageops::FilterType};
+use tokio::sync::{Semaphore, SemaphorePermit};
 
 const THUMBNAIL_MAX_WIDTH: u32 = 200;
 const THUMBNAIL_MAX_HEIGHT: u32 = 150;
@@ -22,11 +25,47 @@ pub enum ThumbnailError {
     DecodeError(String),
     #[error("image encode error: {0}")]
     EncodeError(String),
+    #[error("thumbnail task failed: {0}")]
+    TaskFailed(String),
 }
 
-pub struct ThumbnailService;
+/// Decodes and re-encodes images on the blocking thread pool, with at most
+/// `concurrency` thumbnail jobs in flight so a burst of uploads can't tie up
+/// every blocking thread or hold every original in memory at once.
+#[derive(Clone)]
+pub struct ThumbnailService {
+    permits: Arc<Semaphore>,
+}
 
 impl ThumbnailService {
+    pub fn new(concurrency: usize) -> Self {
+        Self {
+            permits: Arc::new(Semaphore::new(concurrency.max(1))),
+        }
+    }
+
+    /// Wait for a free job slot. Hold it for the whole job, from downloading
+    /// the original to uploading the thumbnail.
+    pub async fn acquire_slot(&self) -> Result<SemaphorePermit<'_>, ThumbnailError> {
+        self.permits
+            .acquire()
+            .await
+            .map_err(|e| ThumbnailError::TaskFailed(e.to_string()))
+    }
+
+    /// [`Self::generate`] off the async runtime. Callers hold a slot from
+    /// [`Self::acquire_slot`].
+    pub async fn generate_blocking(
+        &self,
+        _slot: &SemaphorePermit<'_>,
+        data: Vec<u8>,
+        mime_type: Option<String>,
+    ) -> Result<Option<ThumbnailResult>, ThumbnailError> {
+        tokio::task::spawn_blocking(move || Self::generate(&data, mime_type.as_deref()))
+            .await
+            .map_err(|e| ThumbnailError::TaskFailed(e.to_string()))?
+    }
+
     /// Generate a thumbnail from image bytes.
     /// Returns None for non-image MIME types.
     pub fn generate(
@@ -130,4 +169,29 @@ mod tests {
         let result = ThumbnailService::generate(b"not an image", Some("application/pdf")).unwrap();
         assert!(result.is_none());
     }
+
+    #[tokio::test]
+    async fn test_blocking_generation_holds_a_slot() {
+        let service = ThumbnailService::new(1);
+        let slot = service.acquire_slot().await.unwrap();
+        assert!(service.permits.try_acquire().is_err());
+
+        let result = service
+            .generate_blocking(
+                &slot,
+                b"not an image".to_vec(),
+                Some("application/pdf".into()),
+            )
+            .await
+            .unwrap();
+        assert!(result.is_none());
+
+        let corrupt = service
+            .generate_blocking(&slot, b"not a png".to_vec(), Some("image/png".into()))
+            .await;
+        assert!(matches!(corrupt, Err(ThumbnailError::DecodeError(_))));
+
+        drop(slot);
+        assert!(service.permits.try_acquire().is_ok());
+    }
 }
@@ -26,6 +26,7 @@ use crate::{
             OAuthHandoffRepository,
         },
         oauth_accounts::{OAuthAccountError, OAuthAccountInsert, OAuthAccountRepository},
+        oauth_device_codes::OAuthDeviceCodeRepository,
         organizations::OrganizationRepository,
         users::{UpsertUser, UserRepository},
     },
@@ -36,6 +37,10 @@ const APP_CODE_LENGTH: usize = 48;
 const HANDOFF_TTL: i64 = 10; // minutes
 const USER_FETCH_MAX_ATTEMPTS: usize = 5;
 const USER_FETCH_RETRY_DELAY_MS: u64 = 500;
+const DEVICE_USER_CODE_LENGTH: usize = 8;
+const DEVICE_POLL_INTERVAL_SECS: u64 = 5;
+/// Uppercase alphabet without characters that are easy to confuse (0/O, 1/I/L).
+const DEVICE_USER_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
 
 #[derive(Debug, Error)]
 pub enum HandoffError {
@@ -98,6 +103,24 @@ pub struct RedeemResponse {
     pub email: String,
 }
 
+#[derive(Debug, Clone)]
+pub struct DeviceInitResult {
+    pub handoff_id: Uuid,
+    pub user_code: String,
+    pub verification_uri: String,
+    pub verification_uri_complete: String,
+    pub expires_at: DateTime<Utc>,
+    pub interval_secs: u64,
+}
+
+#[derive(Debug, Clone)]
+pub enum DevicePollResult {
+    Pending,
+    Authorized { app_code: String },
+    Denied,
+    Expired,
+}
+
 pub struct OAuthHandoffService {
     pool: PgPool,
     providers: Arc<ProviderRegistry>,
@@ -389,6 +412,99 @@ impl OAuthHandoffService {
         })
     }
 
+    /// Start a device-code login. The handoff's return URL points back at this
+    /// server so the app code can be parked until the headless client polls.
+    pub async fn initiate_device(
+        &self,
+        provider: &str,
+        app_challenge: &str,
+    ) -> Result<DeviceInitResult, HandoffError> {
+        let return_to = format!("{}/v1/oauth/device/complete", self.public_origin);
+        let handoff = self.initiate(provider, &return_to, app_challenge).await?;
+
+        let user_code = generate_user_code();
+        OAuthDeviceCodeRepository::new(&self.pool)
+            .create(&user_code, handoff.handoff_id, handoff.expires_at)
+            .await?;
+
+        let verification_uri = format!("{}/v1/oauth/device", self.public_origin);
+        let verification_uri_complete = format!("{verification_uri}?user_code={user_code}");
+
+        Ok(DeviceInitResult {
+            handoff_id: handoff.handoff_id,
+            user_code,
+            verification_uri,
+            verification_uri_complete,
+            expires_at: handoff.expires_at,
+            interval_secs: DEVICE_POLL_INTERVAL_SECS,
+        })
+    }
+
+    /// Resolve a user-entered device code to the provider authorization URL.
+    pub async fn device_authorize_url(&self, user_code: &str) -> Result<String, HandoffError> {
+        let device = OAuthDeviceCodeRepository::new(&self.pool)
+            .find_by_user_code(&normalize_user_code(user_code))
+            .await?
+            .ok_or(HandoffError::NotFound)?;
+
+        if device.expires_at <= Utc::now() {
+            return Err(HandoffError::Expired);
+        }
+
+        let handoff = OAuthHandoffRepository::new(&self.pool)
+            .get(device.handoff_id)
+            .await?;
+        self.authorize_url(&handoff.provider, handoff.id).await
+    }
+
+    /// Store the app code delivered by the provider callback redirect.
+    pub async fn complete_device(
+        &self,
+        handoff_id: Uuid,
+        app_code: &str,
+    ) -> Result<(), HandoffError> {
+        let handoff = OAuthHandoffRepository::new(&self.pool)
+            .get(handoff_id)
+            .await?;
+        if handoff.app_code_hash.as_deref() != Some(hash_sha256_hex(app_code).as_str()) {
+            return Err(HandoffError::Failed("invalid_app_code".into()));
+        }
+
+        let stored = OAuthDeviceCodeRepository::new(&self.pool)
+            .set_app_code(handoff_id, app_code)
+            .await?;
+        if !stored {
+            return Err(HandoffError::Failed("already_completed".into()));
+        }
+
+        Ok(())
+    }
+
+    /// Poll a device-code login. The app code is handed out exactly once; the
+    /// client still needs its PKCE verifier to redeem it.
+    pub async fn poll_device(&self, handoff_id: Uuid) -> Result<DevicePollResult, HandoffError> {
+        let device_repo = OAuthDeviceCodeRepository::new(&self.pool);
+        let device = device_repo
+            .find_by_handoff(handoff_id)
+            .await?
+            .ok_or(HandoffError::NotFound)?;
+
+        if let Some(app_code) = device.app_code {
+            device_repo.delete(handoff_id).await?;
+            return Ok(DevicePollResult::Authorized { app_code });
+        }
+
+        let handoff = OAuthHandoffRepository::new(&self.pool)
+            .get(handoff_id)
+            .await?;
+        Ok(match handoff.status() {
+            Some(AuthorizationStatus::Error) => DevicePollResult::Denied,
+            Some(AuthorizationStatus::Expired) => DevicePollResult::Expired,
+            _ if is_expired(&handoff) => DevicePollResult::Expired,
+            _ => DevicePollResult::Pending,
+        })
+    }
+
     async fn fetch_user_with_retries(
         &self,
         provider: &Arc<dyn AuthorizationProvider>,
@@ -534,6 +650,25 @@ fn generate_app_code() -> String {
         .collect()
 }
 
+fn generate_user_code() -> String {
+    let mut rng = rand::rng();
+    (0..DEVICE_USER_CODE_LENGTH)
+        .map(|_| {
+            let idx = rng.random_range(0..DEVICE_USER_CODE_ALPHABET.len());
+            char::from(DEVICE_USER_CODE_ALPHABET[idx])
+        })
+        .collect()
+}
+
+/// Accept codes typed with lowercase letters, spaces or a separating dash.
+fn normalize_user_code(input: &str) -> String {
+    input
+        .chars()
+        .filter(|ch| ch.is_ascii_alphanumeric())
+        .map(|ch| ch.to_ascii_uppercase())
+        .collect()
+}
+
 fn ensure_email(provider: &str, profile: &ProviderUser) -> String {
     if let Some(email) = profile.email.clone() {
         return email;
@@ -604,4 +739,17 @@ mod tests {
         assert!(!is_valid_challenge("not-hex"));
         assert!(!is_valid_challenge(""));
     }
+
+    #[test]
+    fn user_codes_use_unambiguous_alphabet() {
+        let code = generate_user_code();
+        assert_eq!(code.len(), DEVICE_USER_CODE_LENGTH);
+        assert!(code.bytes().all(|b| DEVICE_USER_CODE_ALPHABET.contains(&b)));
+    }
+
+    #[test]
+    fn user_code_normalization() {
+        assert_eq!(normalize_user_code("abcd-efgh"), "ABCDEFGH");
+        assert_eq!(normalize_user_code(" AB CD EF GH "), "ABCDEFGH");
+    }
 }
@@ -13,6 +13,7 @@ pub struct RemoteServerConfig {
     pub refresh_token_overlap_secs: i64,
     pub electric_url: String,
     pub electric_secret: Option<SecretString>,
+    pub electric_upstream_auth: Option<ElectricUpstreamAuth>,
     pub electric_role_password: Option<SecretString>,
     pub electric_publication_names: Vec<String>,
     pub r2: Option<R2Config>,
@@ -20,6 +21,52 @@ pub struct RemoteServerConfig {
     pub review_worker_base_url: Option<String>,
     pub review_disabled: bool,
     pub github_app: Option<GitHubAppConfig>,
+    /// How many attachment images may be decoded and re-encoded at once.
+    pub image_processing_concurrency: usize,
+}
+
+/// Credentials the Electric proxy attaches to every upstream request, for
+/// Electric deployments that sit behind an authenticating gateway.
+#[derive(Debug, Clone)]
+pub enum ElectricUpstreamAuth {
+    /// Sent as `Authorization: Bearer <token>`.
+    Bearer(SecretString),
+    /// Sent as `<name>: <value>`, e.g. an API key header.
+    Header { name: String, value: SecretString },
+}
+
+impl ElectricUpstreamAuth {
+    fn from_env() -> Result<Option<Self>, ConfigError> {
+        let token = env::var("ELECTRIC_AUTH_TOKEN")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+        let header_name = env::var("ELECTRIC_AUTH_HEADER_NAME")
+            .ok()
+            .filter(|v| !v.trim().is_empty());
+
+        match (token, header_name) {
+            (Some(_), Some(_)) => Err(ConfigError::InvalidVar("ELECTRIC_AUTH_HEADER_NAME")),
+            (Some(token), None) => Ok(Some(Self::Bearer(SecretString::new(token.into())))),
+            (None, Some(name)) => {
+                let name = name.trim().to_ascii_lowercase();
+                if axum::http::HeaderName::from_bytes(name.as_bytes()).is_err() {
+                    return Err(ConfigError::InvalidVar("ELECTRIC_AUTH_HEADER_NAME"));
+                }
+                let value = env::var("ELECTRIC_AUTH_HEADER_VALUE")
+                    .ok()
+                    .filter(|v| !v.trim().is_empty())
+                    .ok_or(ConfigError::MissingVar("ELECTRIC_AUTH_HEADER_VALUE"))?;
+                if axum::http::HeaderValue::from_str(&value).is_err() {
+                    return Err(ConfigError::InvalidVar("ELECTRIC_AUTH_HEADER_VALUE"));
+                }
+                Ok(Some(Self::Header {
+                    name,
+                    value: SecretString::new(value.into()),
+                }))
+            }
+            (None, None) => Ok(None),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -238,6 +285,8 @@ impl RemoteServerConfig {
             .map(|s| SecretString::new(s.into()))
             .ok();
 
+        let electric_upstream_auth = ElectricUpstreamAuth::from_env()?;
+
         let electric_role_password = env::var("ELECTRIC_ROLE_PASSWORD")
             .ok()
             .map(|s| SecretString::new(s.into()));
@@ -257,6 +306,12 @@ impl RemoteServerConfig {
 
         let github_app = GitHubAppConfig::from_env()?;
 
+        let image_processing_concurrency = env::var("IMAGE_PROCESSING_CONCURRENCY")
+            .ok()
+            .and_then(|value| value.parse::<usize>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(2);
+
         Ok(Self {
             database_url,
             listen_addr,
@@ -265,6 +320,7 @@ impl RemoteServerConfig {
             refresh_token_overlap_secs,
             electric_url,
             electric_secret,
+            electric_upstream_auth,
             electric_role_password,
             electric_publication_names,
             r2,
@@ -272,6 +328,7 @@ impl RemoteServerConfig {
             review_worker_base_url,
             review_disabled,
             github_app,
+            image_processing_concurrency,
         })
     }
 }
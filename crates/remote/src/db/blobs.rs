@@ -136,6 +136,35 @@ impl BlobRepository {
         Ok(data)
     }
 
+    /// Attach a thumbnail generated after the blob was stored. Returns
+    /// `false` if the blob no longer exists.
+    pub async fn set_thumbnail(
+        pool: &PgPool,
+        id: Uuid,
+        thumbnail_blob_path: &str,
+        width: i32,
+        height: i32,
+    ) -> Result<bool, BlobError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE blobs
+            SET thumbnail_blob_path = $2,
+                width = $3,
+                height = $4,
+                updated_at = NOW()
+            WHERE id = $1
+            "#,
+            id,
+            thumbnail_blob_path,
+            width,
+            height
+        )
+        .execute(pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     pub async fn delete(pool: &PgPool, id: Uuid) -> Result<Option<Blob>, BlobError> {
         let record = sqlx::query_as!(
             Blob,
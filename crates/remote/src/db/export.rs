@@ -124,6 +124,34 @@ impl ExportRepository {
         Ok(issues)
     }
 
+    /// Total time spent per issue, summed over the workspaces linked to it.
+    /// Issues without reported time are left out.
+    pub async fn list_time_spent_by_projects(
+        pool: &PgPool,
+        project_ids: &[Uuid],
+    ) -> Result<Vec<(Uuid, i64)>, ExportError> {
+        let records = sqlx::query!(
+            r#"
+            SELECT
+                issue_id            AS "issue_id!: Uuid",
+                SUM(time_spent_ms)::BIGINT AS "time_spent_ms!"
+            FROM workspaces
+            WHERE project_id = ANY($1)
+              AND issue_id IS NOT NULL
+              AND time_spent_ms IS NOT NULL
+            GROUP BY issue_id
+            "#,
+            project_ids
+        )
+        .fetch_all(pool)
+        .await?;
+
+        Ok(records
+            .into_iter()
+            .map(|r| (r.issue_id, r.time_spent_ms))
+            .collect())
+    }
+
     /// Fetch all statuses for the given project IDs.
     pub async fn list_statuses_by_projects(
         pool: &PgPool,
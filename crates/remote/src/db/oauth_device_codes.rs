@@ -0,0 +1,124 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct OAuthDeviceCode {
+    pub user_code: String,
+    pub handoff_id: Uuid,
+    pub app_code: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub created_at: DateTime<Utc>,
+}
+
+pub struct OAuthDeviceCodeRepository<'a> {
+    pool: &'a PgPool,
+}
+
+impl<'a> OAuthDeviceCodeRepository<'a> {
+    pub fn new(pool: &'a PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        user_code: &str,
+        handoff_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<OAuthDeviceCode, sqlx::Error> {
+        sqlx::query_as!(
+            OAuthDeviceCode,
+            r#"
+            INSERT INTO oauth_device_codes (user_code, handoff_id, expires_at)
+            VALUES ($1, $2, $3)
+            RETURNING
+                user_code   AS "user_code!",
+                handoff_id  AS "handoff_id!: Uuid",
+                app_code    AS "app_code?",
+                expires_at  AS "expires_at!",
+                created_at  AS "created_at!"
+            "#,
+            user_code,
+            handoff_id,
+            expires_at
+        )
+        .fetch_one(self.pool)
+        .await
+    }
+
+    pub async fn find_by_user_code(
+        &self,
+        user_code: &str,
+    ) -> Result<Option<OAuthDeviceCode>, sqlx::Error> {
+        sqlx::query_as!(
+            OAuthDeviceCode,
+            r#"
+            SELECT
+                user_code   AS "user_code!",
+                handoff_id  AS "handoff_id!: Uuid",
+                app_code    AS "app_code?",
+                expires_at  AS "expires_at!",
+                created_at  AS "created_at!"
+            FROM oauth_device_codes
+            WHERE user_code = $1
+            "#,
+            user_code
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    pub async fn find_by_handoff(
+        &self,
+        handoff_id: Uuid,
+    ) -> Result<Option<OAuthDeviceCode>, sqlx::Error> {
+        sqlx::query_as!(
+            OAuthDeviceCode,
+            r#"
+            SELECT
+                user_code   AS "user_code!",
+                handoff_id  AS "handoff_id!: Uuid",
+                app_code    AS "app_code?",
+                expires_at  AS "expires_at!",
+                created_at  AS "created_at!"
+            FROM oauth_device_codes
+            WHERE handoff_id = $1
+            "#,
+            handoff_id
+        )
+        .fetch_optional(self.pool)
+        .await
+    }
+
+    /// Park the app code for the polling client. Only the first callback wins.
+    pub async fn set_app_code(
+        &self,
+        handoff_id: Uuid,
+        app_code: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE oauth_device_codes
+            SET app_code = $2
+            WHERE handoff_id = $1
+              AND app_code IS NULL
+            "#,
+            handoff_id,
+            app_code
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Remove the row once the client has collected its app code.
+    pub async fn delete(&self, handoff_id: Uuid) -> Result<(), sqlx::Error> {
+        sqlx::query!(
+            r#"DELETE FROM oauth_device_codes WHERE handoff_id = $1"#,
+            handoff_id
+        )
+        .execute(self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use api_types::Workspace;
+use api_types::{Workspace, WorkspaceChangesResponse};
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use thiserror::Error;
@@ -20,6 +20,7 @@ pub struct CreateWorkspaceParams {
     pub files_changed: Option<i32>,
     pub lines_added: Option<i32>,
     pub lines_removed: Option<i32>,
+    pub time_spent_ms: Option<i64>,
 }
 
 pub struct WorkspaceRepository;
@@ -43,6 +44,7 @@ impl WorkspaceRepository {
                 files_changed       AS "files_changed: i32",
                 lines_added         AS "lines_added: i32",
                 lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             FROM workspaces
@@ -73,6 +75,7 @@ impl WorkspaceRepository {
                 files_changed       AS "files_changed: i32",
                 lines_added         AS "lines_added: i32",
                 lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             FROM workspaces
@@ -85,6 +88,92 @@ impl WorkspaceRepository {
         Ok(records)
     }
 
+    /// Workspaces in `project_id` changed or deleted at or after the `since`
+    /// cursor, or all of them when `since` is `None` or older than the
+    /// retained deletions.
+    ///
+    /// The returned cursor is the oldest transaction still in flight, so rows
+    /// it commits later are picked up next time.
+    pub async fn list_changes(
+        pool: &PgPool,
+        project_id: Uuid,
+        since: Option<i64>,
+    ) -> Result<WorkspaceChangesResponse, WorkspaceError> {
+        let mut tx = pool.begin().await?;
+
+        let cursor = sqlx::query_scalar!(
+            r#"SELECT pg_snapshot_xmin(pg_current_snapshot())::text::bigint AS "cursor!: i64""#
+        )
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let pruned_through = sqlx::query_scalar!(
+            r#"
+            SELECT pruned_through AS "pruned_through!: i64"
+            FROM workspace_sync_horizons
+            WHERE project_id = $1
+            "#,
+            project_id
+        )
+        .fetch_optional(&mut *tx)
+        .await?;
+        let since = since.filter(|since| pruned_through.is_none_or(|pruned| *since > pruned));
+
+        let workspaces = sqlx::query_as!(
+            Workspace,
+            r#"
+            SELECT
+                id                  AS "id!: Uuid",
+                project_id          AS "project_id!: Uuid",
+                owner_user_id       AS "owner_user_id!: Uuid",
+                issue_id            AS "issue_id: Uuid",
+                local_workspace_id  AS "local_workspace_id: Uuid",
+                name                AS "name: String",
+                archived            AS "archived!: bool",
+                files_changed       AS "files_changed: i32",
+                lines_added         AS "lines_added: i32",
+                lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
+                created_at          AS "created_at!: DateTime<Utc>",
+                updated_at          AS "updated_at!: DateTime<Utc>"
+            FROM workspaces
+            WHERE project_id = $1
+              AND ($2::bigint IS NULL OR change_seq >= $2)
+            ORDER BY change_seq
+            "#,
+            project_id,
+            since
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let deleted_workspace_ids = match since {
+            Some(since) => {
+                sqlx::query_scalar!(
+                    r#"
+                    SELECT workspace_id AS "workspace_id!: Uuid"
+                    FROM workspace_deletions
+                    WHERE project_id = $1 AND change_seq >= $2
+                    "#,
+                    project_id,
+                    since
+                )
+                .fetch_all(&mut *tx)
+                .await?
+            }
+            None => Vec::new(),
+        };
+
+        tx.commit().await?;
+
+        Ok(WorkspaceChangesResponse {
+            workspaces,
+            deleted_workspace_ids,
+            full_sync: since.is_none(),
+            cursor,
+        })
+    }
+
     pub async fn create(
         pool: &PgPool,
         params: CreateWorkspaceParams,
@@ -99,13 +188,14 @@ impl WorkspaceRepository {
             files_changed,
             lines_added,
             lines_removed,
+            time_spent_ms,
         } = params;
         let archived = archived.unwrap_or(false);
         let record = sqlx::query_as!(
             Workspace,
             r#"
-            INSERT INTO workspaces (project_id, owner_user_id, local_workspace_id, issue_id, name, archived, files_changed, lines_added, lines_removed)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            INSERT INTO workspaces (project_id, owner_user_id, local_workspace_id, issue_id, name, archived, files_changed, lines_added, lines_removed, time_spent_ms)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             RETURNING
                 id                  AS "id!: Uuid",
                 project_id          AS "project_id!: Uuid",
@@ -117,6 +207,7 @@ impl WorkspaceRepository {
                 files_changed       AS "files_changed: i32",
                 lines_added         AS "lines_added: i32",
                 lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             "#,
@@ -128,7 +219,8 @@ impl WorkspaceRepository {
             archived,
             files_changed,
             lines_added,
-            lines_removed
+            lines_removed,
+            time_spent_ms
         )
         .fetch_one(pool)
         .await?;
@@ -150,6 +242,7 @@ impl WorkspaceRepository {
                 files_changed       AS "files_changed: i32",
                 lines_added         AS "lines_added: i32",
                 lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             FROM workspaces
@@ -181,6 +274,7 @@ impl WorkspaceRepository {
                 files_changed       AS "files_changed: i32",
                 lines_added         AS "lines_added: i32",
                 lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             FROM workspaces
@@ -245,6 +339,7 @@ impl WorkspaceRepository {
         files_changed: Option<Option<i32>>,
         lines_added: Option<Option<i32>>,
         lines_removed: Option<Option<i32>>,
+        time_spent_ms: Option<Option<i64>>,
     ) -> Result<Workspace, WorkspaceError> {
         let update_name = name.is_some();
         let name_value = name.flatten();
@@ -261,6 +356,9 @@ impl WorkspaceRepository {
         let update_lines_removed = lines_removed.is_some();
         let lines_removed_value = lines_removed.flatten();
 
+        let update_time_spent_ms = time_spent_ms.is_some();
+        let time_spent_ms_value = time_spent_ms.flatten();
+
         let record = sqlx::query_as!(
             Workspace,
             r#"
@@ -270,8 +368,9 @@ impl WorkspaceRepository {
                 files_changed = CASE WHEN $5 THEN $6 ELSE files_changed END,
                 lines_added = CASE WHEN $7 THEN $8 ELSE lines_added END,
                 lines_removed = CASE WHEN $9 THEN $10 ELSE lines_removed END,
+                time_spent_ms = CASE WHEN $11 THEN $12 ELSE time_spent_ms END,
                 updated_at = NOW()
-            WHERE id = $11
+            WHERE id = $13
             RETURNING
                 id                  AS "id!: Uuid",
                 project_id          AS "project_id!: Uuid",
@@ -283,6 +382,7 @@ impl WorkspaceRepository {
                 files_changed       AS "files_changed: i32",
                 lines_added         AS "lines_added: i32",
                 lines_removed       AS "lines_removed: i32",
+                time_spent_ms       AS "time_spent_ms: i64",
                 created_at          AS "created_at!: DateTime<Utc>",
                 updated_at          AS "updated_at!: DateTime<Utc>"
             "#,
@@ -296,6 +396,8 @@ impl WorkspaceRepository {
             lines_added_value,
             update_lines_removed,
             lines_removed_value,
+            update_time_spent_ms,
+            time_spent_ms_value,
             id
         )
         .fetch_one(pool)
@@ -19,7 +19,6 @@ use super::organization_members::{
 };
 use crate::{
     AppState,
-    attachments::thumbnail::ThumbnailService,
     auth::RequestContext,
     azure_blob::AzureBlobError,
     db::{
@@ -120,8 +119,6 @@ pub enum RouteError {
     UploadNotFound,
     #[error("pending upload error: {0}")]
     PendingUpload(#[from] PendingUploadError),
-    #[error("thumbnail generation failed: {0}")]
-    ThumbnailError(String),
 }
 
 impl IntoResponse for RouteError {
@@ -154,13 +151,6 @@ impl IntoResponse for RouteError {
                 tracing::error!(error = %e, "Pending upload error");
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error")
             }
-            RouteError::ThumbnailError(e) => {
-                tracing::error!(error = %e, "Thumbnail generation failed");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    "Thumbnail generation failed",
-                )
-            }
         };
 
         let body = serde_json::json!({ "error": message });
@@ -267,42 +257,34 @@ async fn confirm_upload(
             return Err(RouteError::FileTooLarge);
         }
 
-        let blob_data = azure.download_blob(blob_path).await?;
-        let thumbnail_result =
-            ThumbnailService::generate(&blob_data, payload.content_type.as_deref())
-                .map_err(|e| RouteError::ThumbnailError(e.to_string()))?;
-
         let _ = PendingUploadRepository::delete(state.pool(), pending.id).await;
 
-        let (thumbnail_blob_path, width, height) = match thumbnail_result {
-            Some(thumb) => {
-                let thumb_path = format!("thumbnails/{}", blob_path);
-                azure
-                    .upload_blob(&thumb_path, thumb.bytes, thumb.mime_type)
-                    .await?;
-                (
-                    Some(thumb_path),
-                    Some(thumb.original_width as i32),
-                    Some(thumb.original_height as i32),
-                )
-            }
-            None => (None, None, None),
-        };
-
-        BlobRepository::create(
+        let blob = BlobRepository::create(
             state.pool(),
             None,
             payload.project_id,
             blob_path.clone(),
-            thumbnail_blob_path,
+            None,
             payload.filename.clone(),
             payload.content_type.clone(),
             payload.size_bytes,
             payload.hash.clone(),
-            width,
-            height,
+            None,
+            None,
         )
-        .await?
+        .await?;
+
+        // Decoding large images is slow, so the thumbnail is attached once
+        // it's ready and the attachment is shown without a preview until then.
+        if blob.thumbnail_blob_path.is_none() {
+            tokio::spawn(attach_thumbnail(
+                state.clone(),
+                blob.id,
+                blob.blob_path.clone(),
+                payload.content_type.clone(),
+            ));
+        }
+        blob
     };
 
     let expires_at = if payload.issue_id.is_some() || payload.comment_id.is_some() {
@@ -328,6 +310,76 @@ async fn confirm_upload(
     Ok(Json(result))
 }
 
+/// Generate and store the thumbnail of a newly uploaded blob. Failures are
+/// only logged: the original is already stored.
+async fn attach_thumbnail(
+    state: AppState,
+    blob_id: Uuid,
+    blob_path: String,
+    content_type: Option<String>,
+) {
+    let Some(azure) = state.azure_blob() else {
+        return;
+    };
+    // Held until the thumbnail is uploaded, so only a bounded number of
+    // originals are downloaded and in memory at once.
+    let thumbnails = state.thumbnails();
+    let slot = match thumbnails.acquire_slot().await {
+        Ok(slot) => slot,
+        Err(e) => {
+            tracing::warn!(error = %e, blob_path = %blob_path, "Thumbnail generation failed");
+            return;
+        }
+    };
+    let blob_data = match azure.download_blob(&blob_path).await {
+        Ok(data) => data,
+        Err(e) => {
+            tracing::warn!(error = %e, blob_path = %blob_path, "Failed to download blob for thumbnail");
+            return;
+        }
+    };
+    let thumb = match thumbnails
+        .generate_blocking(&slot, blob_data, content_type)
+        .await
+    {
+        Ok(Some(thumb)) => thumb,
+        Ok(None) => return,
+        Err(e) => {
+            tracing::warn!(error = %e, blob_path = %blob_path, "Thumbnail generation failed");
+            return;
+        }
+    };
+
+    let thumb_path = format!("thumbnails/{}", blob_path);
+    if let Err(e) = azure
+        .upload_blob(&thumb_path, thumb.bytes, thumb.mime_type)
+        .await
+    {
+        tracing::warn!(error = %e, blob_path = %thumb_path, "Failed to upload thumbnail");
+        return;
+    }
+    drop(slot);
+    match BlobRepository::set_thumbnail(
+        state.pool(),
+        blob_id,
+        &thumb_path,
+        thumb.original_width as i32,
+        thumb.original_height as i32,
+    )
+    .await
+    {
+        Ok(true) => {}
+        // The blob was deleted while the thumbnail was generated
+        Ok(false) => {
+            let _ = azure.delete_blob(&thumb_path).await;
+        }
+        Err(e) => {
+            tracing::warn!(error = %e, blob_id = %blob_id, "Failed to store thumbnail");
+            let _ = azure.delete_blob(&thumb_path).await;
+        }
+    }
+}
+
 #[instrument(name = "attachments.commit_issue", skip(state, ctx, payload), fields(issue_id = %issue_id, user_id = %ctx.user.id))]
 async fn commit_issue_attachments(
     State(state): State<AppState>,
@@ -12,7 +12,7 @@ use serde::Deserialize;
 use tracing::error;
 use uuid::Uuid;
 
-use crate::{AppState, shape_definition::ShapeExport};
+use crate::{AppState, config::ElectricUpstreamAuth, shape_definition::ShapeExport};
 
 #[derive(Deserialize)]
 pub(crate) struct OrgShapeQuery {
@@ -49,6 +49,8 @@ pub(crate) async fn proxy_table(
     electric_params: &[String],
     session_id: Uuid,
 ) -> Result<Response, ProxyError> {
+    validate_shape_params(shape, electric_params)?;
+
     // Build the Electric URL
     let mut origin_url = url::Url::parse(&state.config.electric_url)
         .map_err(|e| ProxyError::InvalidConfig(format!("invalid electric_url: {e}")))?;
@@ -85,13 +87,22 @@ pub(crate) async fn proxy_table(
             .append_pair("secret", secret.expose_secret());
     }
 
-    let response = state
+    let mut request = state
         .http_client
         .get(origin_url.as_str())
-        .header(ELECTRIC_STICKY_HEADER, session_id.to_string())
-        .send()
-        .await
-        .map_err(ProxyError::Connection)?;
+        .header(ELECTRIC_STICKY_HEADER, session_id.to_string());
+
+    match &state.config.electric_upstream_auth {
+        Some(ElectricUpstreamAuth::Bearer(token)) => {
+            request = request.bearer_auth(token.expose_secret());
+        }
+        Some(ElectricUpstreamAuth::Header { name, value }) => {
+            request = request.header(name.as_str(), value.expose_secret());
+        }
+        None => {}
+    }
+
+    let response = request.send().await.map_err(ProxyError::Connection)?;
 
     let status = response.status();
     let mut headers = HeaderMap::new();
@@ -115,6 +126,65 @@ pub(crate) async fn proxy_table(
     Ok((status, headers, body).into_response())
 }
 
+/// Ensure the authorized scope values line up with the shape's where clause.
+///
+/// Every placeholder must be bound to a value the route derived from the
+/// requester's authorization, and every value must be a UUID, so a miswired
+/// route can never widen a shape beyond what the caller was checked against.
+fn validate_shape_params(
+    shape: &dyn ShapeExport,
+    electric_params: &[String],
+) -> Result<(), ProxyError> {
+    if electric_params.len() != shape.params().len() {
+        return Err(ProxyError::InvalidConfig(format!(
+            "shape {} expects {} params, got {}",
+            shape.name(),
+            shape.params().len(),
+            electric_params.len()
+        )));
+    }
+
+    let placeholders = where_clause_placeholders(shape.where_clause());
+    let all_bound = (1..=electric_params.len()).all(|i| placeholders.contains(&i));
+    if !all_bound
+        || placeholders
+            .iter()
+            .any(|&i| i == 0 || i > electric_params.len())
+    {
+        return Err(ProxyError::InvalidConfig(format!(
+            "shape {} where clause placeholders do not match its params",
+            shape.name()
+        )));
+    }
+
+    if let Some(param) = electric_params
+        .iter()
+        .find(|param| Uuid::parse_str(param).is_err())
+    {
+        return Err(ProxyError::Authorization(format!(
+            "shape {} received non-UUID scope value {param:?}",
+            shape.name()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Collect the `$N` placeholder indices referenced by a where clause.
+fn where_clause_placeholders(where_clause: &str) -> Vec<usize> {
+    let mut indices = Vec::new();
+    let mut rest = where_clause;
+    while let Some(pos) = rest.find('$') {
+        rest = &rest[pos + 1..];
+        let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+        if let Ok(index) = rest[..digits].parse() {
+            indices.push(index);
+        }
+        rest = &rest[digits..];
+    }
+    indices
+}
+
 #[derive(Debug)]
 pub(crate) enum ProxyError {
     Connection(reqwest::Error),
@@ -144,3 +214,19 @@ impl IntoResponse for ProxyError {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::where_clause_placeholders;
+
+    #[test]
+    fn collects_placeholders_from_where_clause() {
+        assert_eq!(
+            where_clause_placeholders(
+                r#""organization_id" = $1 AND ("user_id" = $2 OR "owner_id" = $2)"#
+            ),
+            vec![1, 2, 2]
+        );
+        assert!(where_clause_placeholders(r#""cost" = '$'"#).is_empty());
+    }
+}
@@ -12,7 +12,7 @@ use axum::{
     response::{IntoResponse, Response},
     routing::post,
 };
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use tracing::instrument;
 use uuid::Uuid;
 use zip::{ZipWriter, write::SimpleFileOptions};
@@ -77,7 +77,7 @@ async fn export_data(
     let project_ids: Vec<Uuid> = projects.iter().map(|p| p.id).collect();
 
     // Fetch all data in parallel
-    let (issues, users, all_statuses, assignees, attachments) = tokio::try_join!(
+    let (issues, users, all_statuses, assignees, attachments, time_spent) = tokio::try_join!(
         async {
             ExportRepository::list_all_issues_by_projects(pool, &project_ids)
                 .await
@@ -98,6 +98,11 @@ async fn export_data(
                 .await
                 .map_err(|e| e.to_string())
         },
+        async {
+            ExportRepository::list_time_spent_by_projects(pool, &project_ids)
+                .await
+                .map_err(|e| e.to_string())
+        },
         async {
             if payload.include_attachments {
                 ExportRepository::list_attachments_by_projects(pool, &project_ids)
@@ -137,6 +142,8 @@ async fn export_data(
         .map(|i| (i.id, i.simple_id.as_str()))
         .collect();
 
+    let time_spent_map: HashMap<Uuid, i64> = time_spent.into_iter().collect();
+
     // Build assignee map: issue_id -> comma-separated names
     let mut assignee_map: HashMap<Uuid, Vec<&str>> = HashMap::new();
     for a in &assignees {
@@ -173,6 +180,7 @@ async fn export_data(
                 "Due Date",
                 "Completed",
                 "Parent Issue",
+                "Time Spent (min)",
             ])
             .map_err(|e| csv_error(&e))?;
 
@@ -198,6 +206,10 @@ async fn export_data(
                     .as_ref()
                     .map(|p| format!("{p:?}"))
                     .unwrap_or_default();
+                let time_spent = time_spent_map
+                    .get(&issue.id)
+                    .map(|ms| (ms / 60_000).to_string())
+                    .unwrap_or_default();
 
                 wtr.write_record([
                     &issue.simple_id,
@@ -208,12 +220,13 @@ async fn export_data(
                     project_name,
                     &assignees_str,
                     creator,
-                    &issue.created_at.to_rfc3339(),
-                    &issue.updated_at.to_rfc3339(),
+                    &format_date(issue.created_at),
+                    &format_date(issue.updated_at),
                     &optional_date(issue.start_date),
                     &optional_date(issue.target_date),
                     &optional_date(issue.completed_at),
                     parent,
+                    &time_spent,
                 ])
                 .map_err(|e| csv_error(&e))?;
             }
@@ -237,8 +250,8 @@ async fn export_data(
             for project in &projects {
                 wtr.write_record([
                     &project.name,
-                    &project.created_at.to_rfc3339(),
-                    &project.updated_at.to_rfc3339(),
+                    &format_date(project.created_at),
+                    &format_date(project.updated_at),
                 ])
                 .map_err(|e| csv_error(&e))?;
             }
@@ -385,8 +398,14 @@ fn format_user_name(
     }
 }
 
-fn optional_date(date: Option<chrono::DateTime<Utc>>) -> String {
-    date.map(|d| d.to_rfc3339()).unwrap_or_default()
+/// Exported timestamps are always UTC with an explicit `Z`, so files from
+/// different members read the same.
+fn format_date(date: DateTime<Utc>) -> String {
+    date.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn optional_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(format_date).unwrap_or_default()
 }
 
 fn csv_error(e: &csv::Error) -> ErrorResponse {
@@ -1,14 +1,15 @@
 use std::borrow::Cow;
 
 use api_types::{
-    AuthMethodsResponse, HandoffInitRequest, HandoffInitResponse, HandoffRedeemRequest,
+    AuthMethodsResponse, DeviceInitRequest, DeviceInitResponse, DevicePollRequest,
+    DevicePollResponse, HandoffInitRequest, HandoffInitResponse, HandoffRedeemRequest,
     HandoffRedeemResponse, LocalLoginRequest, LocalLoginResponse, ProfileResponse, ProviderProfile,
 };
 use axum::{
     Json, Router,
     extract::{Extension, Path, Query, State},
     http::StatusCode,
-    response::{IntoResponse, Redirect, Response},
+    response::{Html, IntoResponse, Redirect, Response},
     routing::{get, post},
 };
 use serde::Deserialize;
@@ -20,8 +21,8 @@ use crate::{
     AppState,
     audit::{self, AuditAction, AuditEvent},
     auth::{
-        CallbackResult, HandoffError, LocalAuthError, RequestContext, auth_methods_response,
-        login as local_login_flow,
+        CallbackResult, DevicePollResult, HandoffError, LocalAuthError, RequestContext,
+        auth_methods_response, login as local_login_flow,
     },
     db::{oauth::OAuthHandoffError, oauth_accounts::OAuthAccountRepository},
 };
@@ -32,6 +33,10 @@ pub(super) fn public_router() -> Router<AppState> {
         .route("/auth/local/login", post(local_login))
         .route("/oauth/web/init", post(web_init))
         .route("/oauth/web/redeem", post(web_redeem))
+        .route("/oauth/device", get(device_verify))
+        .route("/oauth/device/init", post(device_init))
+        .route("/oauth/device/complete", get(device_complete))
+        .route("/oauth/device/poll", post(device_poll))
         .route("/oauth/{provider}/start", get(authorize_start))
         .route("/oauth/{provider}/callback", get(authorize_callback))
 }
@@ -111,6 +116,138 @@ async fn web_redeem(
     }
 }
 
+async fn device_init(
+    State(state): State<AppState>,
+    Json(payload): Json<DeviceInitRequest>,
+) -> Response {
+    let handoff = state.handoff();
+
+    match handoff
+        .initiate_device(&payload.provider, &payload.app_challenge)
+        .await
+    {
+        Ok(result) => (
+            StatusCode::OK,
+            Json(DeviceInitResponse {
+                handoff_id: result.handoff_id,
+                user_code: result.user_code,
+                verification_uri: result.verification_uri,
+                verification_uri_complete: result.verification_uri_complete,
+                expires_at: result.expires_at,
+                interval_secs: result.interval_secs,
+            }),
+        )
+            .into_response(),
+        Err(error) => init_error_response(error),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceVerifyQuery {
+    user_code: Option<String>,
+}
+
+async fn device_verify(
+    State(state): State<AppState>,
+    Query(query): Query<DeviceVerifyQuery>,
+) -> Response {
+    let Some(user_code) = query.user_code.filter(|code| !code.trim().is_empty()) else {
+        return Html(DEVICE_CODE_FORM).into_response();
+    };
+
+    match state.handoff().device_authorize_url(&user_code).await {
+        Ok(url) => Redirect::temporary(&url).into_response(),
+        Err(error) => {
+            let (status, message) = classify_handoff_error(&error);
+            (
+                status,
+                format!("Device login failed: {}", message.into_owned()),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct DeviceCompleteQuery {
+    handoff_id: Option<Uuid>,
+    app_code: Option<String>,
+    error: Option<String>,
+}
+
+async fn device_complete(
+    State(state): State<AppState>,
+    Query(query): Query<DeviceCompleteQuery>,
+) -> Response {
+    if let Some(error) = query.error {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("Device login failed: {error}"),
+        )
+            .into_response();
+    }
+
+    let (Some(handoff_id), Some(app_code)) = (query.handoff_id, query.app_code) else {
+        return (
+            StatusCode::BAD_REQUEST,
+            "Device login failed: missing handoff_id or app_code",
+        )
+            .into_response();
+    };
+
+    match state.handoff().complete_device(handoff_id, &app_code).await {
+        Ok(()) => (
+            StatusCode::OK,
+            "Signed in. You can close this tab and return to your terminal.",
+        )
+            .into_response(),
+        Err(error) => {
+            let (status, message) = classify_handoff_error(&error);
+            (
+                status,
+                format!("Device login failed: {}", message.into_owned()),
+            )
+                .into_response()
+        }
+    }
+}
+
+async fn device_poll(
+    State(state): State<AppState>,
+    Json(payload): Json<DevicePollRequest>,
+) -> Response {
+    match state.handoff().poll_device(payload.handoff_id).await {
+        Ok(result) => {
+            let response = match result {
+                DevicePollResult::Pending => DevicePollResponse::Pending,
+                DevicePollResult::Authorized { app_code } => {
+                    DevicePollResponse::Authorized { app_code }
+                }
+                DevicePollResult::Denied => DevicePollResponse::Denied,
+                DevicePollResult::Expired => DevicePollResponse::Expired,
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(error) => redeem_error_response(error),
+    }
+}
+
+const DEVICE_CODE_FORM: &str = r#"<!doctype html>
+<html>
+  <head>
+    <meta charset="utf-8">
+    <meta name="viewport" content="width=device-width, initial-scale=1">
+    <title>Vibe Kanban device login</title>
+  </head>
+  <body>
+    <form method="get" action="/v1/oauth/device">
+      <label for="user_code">Enter the code shown in your terminal</label>
+      <input id="user_code" name="user_code" autocomplete="off" autofocus>
+      <button type="submit">Continue</button>
+    </form>
+  </body>
+</html>"#;
+
 async fn local_login(
     State(state): State<AppState>,
     Json(payload): Json<LocalLoginRequest>,
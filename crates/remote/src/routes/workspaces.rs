@@ -1,7 +1,10 @@
-use api_types::{DeleteWorkspaceRequest, UpdateWorkspaceRequest, Workspace};
+use api_types::{
+    DeleteWorkspaceRequest, ListWorkspaceChangesQuery, UpdateWorkspaceRequest, Workspace,
+    WorkspaceChangesResponse,
+};
 use axum::{
     Json, Router,
-    extract::{Extension, Path, State},
+    extract::{Extension, Path, Query, State},
     http::StatusCode,
     routing::{delete, get, head, post},
 };
@@ -32,6 +35,7 @@ struct CreateWorkspaceRequest {
     pub files_changed: Option<i32>,
     pub lines_added: Option<i32>,
     pub lines_removed: Option<i32>,
+    pub time_spent_ms: Option<i64>,
 }
 
 pub(super) fn router() -> Router<AppState> {
@@ -42,6 +46,7 @@ pub(super) fn router() -> Router<AppState> {
                 .patch(update_workspace)
                 .delete(delete_workspace),
         )
+        .route("/workspaces/changes", get(list_workspace_changes))
         .route("/workspaces/{workspace_id}", delete(unlink_workspace))
         .route(
             "/workspaces/{local_workspace_id}/sync_issue_status_from_local_merge",
@@ -81,6 +86,7 @@ async fn create_workspace(
             files_changed: payload.files_changed,
             lines_added: payload.lines_added,
             lines_removed: payload.lines_removed,
+            time_spent_ms: payload.time_spent_ms,
         },
     )
     .await
@@ -113,6 +119,31 @@ async fn create_workspace(
     Ok(Json(workspace))
 }
 
+#[instrument(
+    name = "workspaces.list_workspace_changes",
+    skip(state, ctx, query),
+    fields(project_id = %query.project_id, user_id = %ctx.user.id)
+)]
+async fn list_workspace_changes(
+    State(state): State<AppState>,
+    Extension(ctx): Extension<RequestContext>,
+    Query(query): Query<ListWorkspaceChangesQuery>,
+) -> Result<Json<WorkspaceChangesResponse>, ErrorResponse> {
+    ensure_project_access(state.pool(), ctx.user.id, query.project_id).await?;
+
+    let changes = WorkspaceRepository::list_changes(state.pool(), query.project_id, query.since)
+        .await
+        .map_err(|error| {
+            tracing::error!(?error, project_id = %query.project_id, "failed to list workspace changes");
+            ErrorResponse::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "failed to list workspace changes",
+            )
+        })?;
+
+    Ok(Json(changes))
+}
+
 #[instrument(
     name = "workspaces.update_workspace",
     skip(state, ctx, payload),
@@ -141,6 +172,7 @@ async fn update_workspace(
         payload.files_changed,
         payload.lines_added,
         payload.lines_removed,
+        payload.time_spent_ms,
     )
     .await
     .map_err(|error| {
@@ -4,6 +4,7 @@ use sqlx::PgPool;
 
 use crate::{
     analytics::AnalyticsService,
+    attachments::thumbnail::ThumbnailService,
     auth::{JwtService, OAuthHandoffService, OAuthTokenValidator, ProviderRegistry},
     azure_blob::AzureBlobService,
     billing::BillingService,
@@ -28,6 +29,7 @@ pub struct AppState {
     github_app: Option<Arc<GitHubAppService>>,
     billing: BillingService,
     analytics: Option<AnalyticsService>,
+    thumbnails: ThumbnailService,
 }
 
 impl AppState {
@@ -47,6 +49,7 @@ impl AppState {
         billing: BillingService,
         analytics: Option<AnalyticsService>,
     ) -> Self {
+        let thumbnails = ThumbnailService::new(config.image_processing_concurrency);
         Self {
             pool,
             config,
@@ -61,6 +64,7 @@ impl AppState {
             github_app,
             billing,
             analytics,
+            thumbnails,
         }
     }
 
@@ -107,4 +111,8 @@ impl AppState {
     pub fn analytics(&self) -> Option<&AnalyticsService> {
         self.analytics.as_ref()
     }
+
+    pub fn thumbnails(&self) -> &ThumbnailService {
+        &self.thumbnails
+    }
 }
@@ -1,7 +1,17 @@
 use std::{collections::HashMap, env, fs, path::Path};
 
 use schemars::{JsonSchema, Schema, SchemaGenerator, generate::SchemaSettings};
-use services::services::config::{DEFAULT_COMMIT_REMINDER_PROMPT, DEFAULT_PR_DESCRIPTION_PROMPT};
+use services::services::{
+    config::{
+        DEFAULT_AGENT_IDENTITY_TEMPLATE, DEFAULT_COMMIT_REMINDER_PROMPT,
+        DEFAULT_DENIED_TOOL_NOTE_TEMPLATE, DEFAULT_PR_DESCRIPTION_PROMPT,
+    },
+    issue_prompt_template::DEFAULT_ISSUE_PROMPT_TEMPLATE,
+    notification_template::{
+        DEFAULT_NOTIFICATION_BODY_TEMPLATE, DEFAULT_NOTIFICATION_TITLE_TEMPLATE,
+    },
+    pr_template::{DEFAULT_PR_BODY_TEMPLATE, DEFAULT_PR_TITLE_TEMPLATE},
+};
 use ts_rs::TS;
 
 fn generate_types_content() -> String {
@@ -16,6 +26,7 @@ fn generate_types_content() -> String {
         db::models::repo::UpdateRepo::decl(),
         db::models::repo::SearchResult::decl(),
         db::models::repo::SearchMatchType::decl(),
+        db::maintenance::AppliedMigration::decl(),
         db::models::workspace_repo::WorkspaceRepo::decl(),
         db::models::workspace_repo::CreateWorkspaceRepo::decl(),
         db::models::workspace_repo::RepoWithTargetBranch::decl(),
@@ -46,14 +57,22 @@ fn generate_types_content() -> String {
         db::models::workspace::Workspace::decl(),
         db::models::workspace::WorkspaceWithStatus::decl(),
         db::models::session::Session::decl(),
+        db::models::coding_agent_turn::CodingAgentTurnSearchResult::decl(),
         db::models::execution_process::ExecutionProcess::decl(),
         db::models::execution_process::ExecutionProcessStatus::decl(),
+        db::models::execution_process::ExecutionProcessExitReason::decl(),
         db::models::execution_process::ExecutionProcessRunReason::decl(),
+        db::models::execution_process::ExecutionProcessResourceUsage::decl(),
         db::models::execution_process_repo_state::ExecutionProcessRepoState::decl(),
+        services::services::container::CancelExecutionOutcome::decl(),
         db::models::merge::Merge::decl(),
         db::models::merge::DirectMerge::decl(),
         db::models::merge::PrMerge::decl(),
         db::models::merge::MergeStatus::decl(),
+        db::models::merge::ChecksStatus::decl(),
+        db::models::merge_operation::MergeOperationKind::decl(),
+        db::models::failed_notification::FailedNotification::decl(),
+        db::models::failed_notification::NotificationChannel::decl(),
         db::models::merge::PullRequestInfo::decl(),
         services::services::approvals::ApprovalInfo::decl(),
         utils::approvals::ApprovalStatus::decl(),
@@ -68,6 +87,7 @@ fn generate_types_content() -> String {
         api_types::ProfileResponse::decl(),
         api_types::ProviderProfile::decl(),
         api_types::StatusResponse::decl(),
+        api_types::DeviceInitResponse::decl(),
         api_types::MemberRole::decl(),
         api_types::InvitationStatus::decl(),
         api_types::Organization::decl(),
@@ -102,7 +122,12 @@ fn generate_types_content() -> String {
         server::routes::config::CheckEditorAvailabilityResponse::decl(),
         server::routes::config::CheckAgentAvailabilityQuery::decl(),
         server::routes::config::AgentPresetOptionsQuery::decl(),
+        server::routes::config::ResolvedProfile::decl(),
+        server::routes::config::DetectGitHostRequest::decl(),
+        server::routes::config::DetectedGitHost::decl(),
         server::routes::oauth::CurrentUserResponse::decl(),
+        server::routes::oauth::RemoteAccount::decl(),
+        server::routes::oauth::SwitchAccountRequest::decl(),
         relay_types::StartSpake2EnrollmentRequest::decl(),
         relay_types::FinishSpake2EnrollmentRequest::decl(),
         relay_types::StartSpake2EnrollmentResponse::decl(),
@@ -112,6 +137,10 @@ fn generate_types_content() -> String {
         relay_types::RemoveRelayPairedClientResponse::decl(),
         relay_types::RefreshRelaySigningSessionRequest::decl(),
         relay_types::RefreshRelaySigningSessionResponse::decl(),
+        relay_types::RelaySessionServerKey::decl(),
+        relay_types::RelaySessionServerKeysResponse::decl(),
+        relay_types::RelaySignatureFailureCount::decl(),
+        relay_types::RelaySigningSessionStatsResponse::decl(),
         server::routes::sessions::CreateFollowUpAttempt::decl(),
         server::routes::sessions::ResetProcessRequest::decl(),
         server::routes::workspaces::git::ChangeTargetBranchRequest::decl(),
@@ -124,6 +153,9 @@ fn generate_types_content() -> String {
         server::routes::workspaces::git::RenameBranchResponse::decl(),
         server::routes::sessions::review::StartReviewRequest::decl(),
         server::routes::sessions::review::ReviewError::decl(),
+        server::routes::sessions::inject::InjectMessageRequest::decl(),
+        server::routes::sessions::inject::InjectMessageResponse::decl(),
+        server::routes::sessions::inject::InjectMessageError::decl(),
         server::routes::workspaces::integration::OpenEditorRequest::decl(),
         server::routes::workspaces::integration::OpenEditorResponse::decl(),
         desktop_bridge::service::OpenRemoteEditorResponse::decl(),
@@ -148,6 +180,7 @@ fn generate_types_content() -> String {
         server::routes::workspaces::git::GitOperationError::decl(),
         server::routes::workspaces::git::PushError::decl(),
         server::routes::workspaces::pr::PrError::decl(),
+        server::routes::workspaces::pr::CreatePrResponse::decl(),
         server::routes::workspaces::execution::RunScriptError::decl(),
         server::routes::workspaces::attachments::AssociateWorkspaceAttachmentsRequest::decl(),
         server::routes::workspaces::attachments::ImportIssueAttachmentsRequest::decl(),
@@ -155,19 +188,33 @@ fn generate_types_content() -> String {
         server::routes::workspaces::pr::AttachPrResponse::decl(),
         server::routes::workspaces::pr::AttachExistingPrRequest::decl(),
         server::routes::workspaces::pr::PrCommentsResponse::decl(),
+        server::routes::workspaces::pr::PrDescriptionResponse::decl(),
+        server::routes::workspaces::snapshots::WorktreeSnapshot::decl(),
+        services::services::notification::TestNotificationResult::decl(),
         server::routes::workspaces::pr::GetPrCommentsError::decl(),
         server::routes::workspaces::pr::GetPrCommentsQuery::decl(),
+        server::routes::workspaces::pr::UpdatePrBaseRequest::decl(),
+        server::routes::workspaces::pr::UpdatePrBaseError::decl(),
         db::models::requests::CreateAndStartWorkspaceRequest::decl(),
+        db::models::requests::WorkspaceStartMode::decl(),
         db::models::requests::CreateAndStartWorkspaceResponse::decl(),
         git_host::UnifiedPrComment::decl(),
         git_host::ProviderKind::decl(),
+        git_host::ProviderCapabilities::decl(),
         git_host::PullRequestDetail::decl(),
         git::GitRemote::decl(),
         server::routes::repo::ListPrsError::decl(),
+        server::routes::repo::ProviderCapabilitiesResponse::decl(),
         server::routes::remote::pull_requests::LinkPrToIssueRequest::decl(),
         server::routes::workspaces::pr::CreateWorkspaceFromPrBody::decl(),
         server::routes::workspaces::pr::CreateWorkspaceFromPrResponse::decl(),
         server::routes::workspaces::pr::CreateFromPrError::decl(),
+        server::routes::workspaces::create::CreateWorkspaceFromBranchBody::decl(),
+        server::routes::workspaces::create::CreateWorkspaceFromBranchResponse::decl(),
+        server::routes::workspaces::create::PreviewBranchNameRequest::decl(),
+        server::routes::workspaces::create::PreviewBranchNameResponse::decl(),
+        server::routes::workspaces::create::BranchNameConflict::decl(),
+        server::routes::workspaces::create::CreateFromBranchError::decl(),
         server::routes::workspaces::git::RepoBranchStatus::decl(),
         db::models::requests::UpdateWorkspace::decl(),
         db::models::requests::UpdateSession::decl(),
@@ -189,10 +236,31 @@ fn generate_types_content() -> String {
         services::services::config::UiLanguage::decl(),
         services::services::config::ShowcaseState::decl(),
         services::services::config::SendMessageShortcut::decl(),
+        services::services::config::ConventionalCommitConfig::decl(),
+        services::services::config::ConventionalCommitMode::decl(),
+        services::services::config::WebhookConfig::decl(),
+        services::services::config::WebhookEventType::decl(),
         git::GitBranch::decl(),
         services::services::queued_message::QueuedMessage::decl(),
         services::services::queued_message::QueueStatus::decl(),
+        services::services::attempt_limiter::RepoConcurrencyStatus::decl(),
+        services::services::attempt_limiter::TaskAttemptUsage::decl(),
+        services::services::pr_monitor::PrMonitorStatus::decl(),
+        services::services::agent_plan::AgentPlan::decl(),
+        services::services::proposed_changes::ProposedFileChange::decl(),
+        services::services::proposed_changes::WorkspacePreview::decl(),
+        services::services::outcome_summary::OutcomeSummary::decl(),
+        services::services::outcome_summary::TestRunner::decl(),
+        services::services::outcome_summary::TestRunSummary::decl(),
+        server::routes::workspaces::plan::RespondToPlanRequest::decl(),
+        services::services::renormalize_jobs::RenormalizeJob::decl(),
+        services::services::renormalize_jobs::RenormalizeJobStatus::decl(),
+        server::routes::execution_processes::RenormalizeProcessesRequest::decl(),
+        services::services::repo_ecosystem::Ecosystem::decl(),
+        services::services::repo_ecosystem::DetectedEcosystem::decl(),
         git::ConflictOp::decl(),
+        git::ConflictedFile::decl(),
+        git::MergeMethod::decl(),
         executors::actions::ExecutorAction::decl(),
         executors::mcp_config::McpConfig::decl(),
         executors::actions::ExecutorActionType::decl(),
@@ -212,6 +280,7 @@ fn generate_types_content() -> String {
         executors::executors::BaseAgentCapability::decl(),
         executors::executors::claude::ClaudeEffort::decl(),
         executors::executors::claude::ClaudeCode::decl(),
+        executors::approvals::AutoDenyRule::decl(),
         executors::executors::gemini::Gemini::decl(),
         executors::executors::amp::Amp::decl(),
         executors::executors::codex::Codex::decl(),
@@ -238,6 +307,7 @@ fn generate_types_content() -> String {
         executors::logs::NormalizedEntry::decl(),
         executors::logs::NormalizedEntryType::decl(),
         executors::logs::TokenUsageInfo::decl(),
+        executors::logs::TokenUsageTotals::decl(),
         executors::logs::FileChange::decl(),
         executors::logs::ActionType::decl(),
         executors::logs::AnsweredQuestion::decl(),
@@ -285,9 +355,16 @@ fn generate_types_content() -> String {
 
     // Append exported constants
     let constants = format!(
-        "export const DEFAULT_PR_DESCRIPTION_PROMPT = {};\n\nexport const DEFAULT_COMMIT_REMINDER_PROMPT = {};",
+        "export const DEFAULT_PR_DESCRIPTION_PROMPT = {};\n\nexport const DEFAULT_COMMIT_REMINDER_PROMPT = {};\n\nexport const DEFAULT_AGENT_IDENTITY_TEMPLATE = {};\n\nexport const DEFAULT_DENIED_TOOL_NOTE_TEMPLATE = {};\n\nexport const DEFAULT_PR_TITLE_TEMPLATE = {};\n\nexport const DEFAULT_PR_BODY_TEMPLATE = {};\n\nexport const DEFAULT_NOTIFICATION_TITLE_TEMPLATE = {};\n\nexport const DEFAULT_NOTIFICATION_BODY_TEMPLATE = {};\n\nexport const DEFAULT_ISSUE_PROMPT_TEMPLATE = {};",
         serde_json::to_string(DEFAULT_PR_DESCRIPTION_PROMPT).unwrap(),
-        serde_json::to_string(DEFAULT_COMMIT_REMINDER_PROMPT).unwrap()
+        serde_json::to_string(DEFAULT_COMMIT_REMINDER_PROMPT).unwrap(),
+        serde_json::to_string(DEFAULT_AGENT_IDENTITY_TEMPLATE).unwrap(),
+        serde_json::to_string(DEFAULT_DENIED_TOOL_NOTE_TEMPLATE).unwrap(),
+        serde_json::to_string(DEFAULT_PR_TITLE_TEMPLATE).unwrap(),
+        serde_json::to_string(DEFAULT_PR_BODY_TEMPLATE).unwrap(),
+        serde_json::to_string(DEFAULT_NOTIFICATION_TITLE_TEMPLATE).unwrap(),
+        serde_json::to_string(DEFAULT_NOTIFICATION_BODY_TEMPLATE).unwrap(),
+        serde_json::to_string(DEFAULT_ISSUE_PROMPT_TEMPLATE).unwrap()
     );
 
     format!("{HEADER}\n\n{body}\n\n{constants}")
@@ -0,0 +1,95 @@
+//! `vibe-kanban db check|repair`: diagnose the local database without starting
+//! the server.
+
+use db::maintenance::{self, DbCheckReport};
+
+const USAGE: &str = "Usage: vibe-kanban db <check|repair>\n\n  \
+    check            Run an integrity check and verify migrations (read-only)\n  \
+    check --repair   Same as `repair`\n  \
+    repair           Apply pending migrations and VACUUM the database";
+
+/// Handle `db ...` arguments. Returns `None` when `args` isn't a db command,
+/// otherwise the process exit code.
+pub async fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = match args {
+        [db, command, rest @ ..] if db == "db" => (command.as_str(), rest),
+        [db] if db == "db" => {
+            eprintln!("{USAGE}");
+            return Some(2);
+        }
+        _ => return None,
+    };
+    let repair = match (command, rest) {
+        ("check", []) => false,
+        ("check", [flag]) if flag == "--repair" => true,
+        ("repair", []) => true,
+        _ => {
+            eprintln!("{USAGE}");
+            return Some(2);
+        }
+    };
+
+    match run_inner(repair).await {
+        Ok(healthy) => Some(if healthy { 0 } else { 1 }),
+        Err(e) => {
+            eprintln!("Database error: {e}");
+            Some(1)
+        }
+    }
+}
+
+async fn run_inner(repair: bool) -> Result<bool, sqlx::Error> {
+    let pool = maintenance::connect().await?;
+    let report = maintenance::check(&pool).await?;
+    print_report(&report);
+
+    if !repair {
+        if !report.is_healthy() {
+            println!("\nRun `vibe-kanban db repair` to apply fixes.");
+        }
+        return Ok(report.is_healthy());
+    }
+
+    println!("\nRepairing...");
+    maintenance::repair(&pool, &report).await?;
+    let report = maintenance::check(&pool).await?;
+    println!("Database compacted. Re-checking:");
+    print_report(&report);
+    Ok(report.is_healthy())
+}
+
+fn print_report(report: &DbCheckReport) {
+    if report.integrity_errors.is_empty() {
+        println!("Integrity check: ok");
+    } else {
+        println!(
+            "Integrity check: {} problem(s)",
+            report.integrity_errors.len()
+        );
+        for line in &report.integrity_errors {
+            println!("  {line}");
+        }
+    }
+
+    if report.pending_migrations.is_empty() {
+        println!("Pending migrations: none");
+    } else {
+        println!("Pending migrations:");
+        for (version, description) in &report.pending_migrations {
+            println!("  {version} {description}");
+        }
+    }
+    for (label, versions) in [
+        ("Failed migrations", &report.failed_migrations),
+        ("Checksum mismatches", &report.checksum_mismatches),
+        (
+            "Migrations from a newer version",
+            &report.unknown_migrations,
+        ),
+    ] {
+        if !versions.is_empty() {
+            let versions: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+            println!("{label}: {}", versions.join(", "));
+        }
+    }
+}
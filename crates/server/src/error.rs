@@ -386,6 +386,13 @@ impl IntoResponse for ApiError {
                     branch
                 ),
             ),
+            ApiError::GitService(GitServiceError::RemoteNotFound(remote)) => ErrorInfo::not_found(
+                "GitServiceError",
+                format!(
+                    "Remote '{}' not found. Check the repo's push and PR base remotes.",
+                    remote
+                ),
+            ),
             ApiError::GitService(GitServiceError::BranchesDiverged(msg)) => ErrorInfo::conflict(
                 "GitServiceError",
                 format!(
@@ -417,6 +424,15 @@ impl IntoResponse for ApiError {
                 "GitServiceError",
                 format!("Git operation failed: {}", e),
             ),
+            ApiError::GitHost(GitHostError::Cancelled) => ErrorInfo::conflict(
+                "GitHostRequestCancelled",
+                "The request was cancelled because the workspace was stopped.",
+            ),
+            ApiError::GitHost(GitHostError::RateLimited { .. }) => ErrorInfo::with_status(
+                StatusCode::TOO_MANY_REQUESTS,
+                "GitHostRateLimited",
+                "The git host is rate limiting requests. Try again in a few minutes.",
+            ),
             ApiError::GitHost(_) => ErrorInfo::internal("GitHostError"),
 
             ApiError::File(FileError::TooLarge(size, max)) => ErrorInfo::with_status(
@@ -483,6 +499,11 @@ impl IntoResponse for ApiError {
             ),
 
             ApiError::Deployment(_) => ErrorInfo::internal("DeploymentError"),
+            ApiError::Container(
+                err @ (ContainerError::WorktreeLimitReached { .. }
+                | ContainerError::AttemptLimitReached { .. }
+                | ContainerError::PreviewFollowUp),
+            ) => ErrorInfo::conflict("ContainerError", err.to_string()),
             ApiError::Container(_) => ErrorInfo::internal("ContainerError"),
             ApiError::Executor(_) => ErrorInfo::internal("ExecutorError"),
             ApiError::CommandBuilder(_) => ErrorInfo::internal("CommandBuildError"),
@@ -1,3 +1,4 @@
+pub mod db_cli;
 pub mod error;
 pub mod middleware;
 pub mod relay_pairing;
@@ -1,8 +1,13 @@
+use std::{io::Write, net::SocketAddr};
+
 use anyhow::{self, Error as AnyhowError};
 use axum::Router;
 use deployment::{Deployment, DeploymentError};
 use server::{
-    DeploymentImpl, middleware::origin::validate_origin, routes, runtime::relay_registration,
+    DeploymentImpl,
+    middleware::origin::validate_origin,
+    routes,
+    runtime::{idle_shutdown, relay_registration},
 };
 use services::services::container::ContainerService;
 use sqlx::Error as SqlxError;
@@ -10,7 +15,7 @@ use strip_ansi_escapes::strip;
 use thiserror::Error;
 use tokio_util::sync::CancellationToken;
 use tower_http::validate_request::ValidateRequestHeaderLayer;
-use tracing_subscriber::{EnvFilter, prelude::*};
+use tracing_subscriber::{EnvFilter, fmt::writer::BoxMakeWriter, prelude::*};
 use utils::{
     assets::asset_dir,
     port_file::write_port_file_with_proxy,
@@ -44,8 +49,21 @@ async fn main() -> Result<(), VibeKanbanError> {
         level = log_level
     );
     let env_filter = EnvFilter::try_new(filter_string).expect("Failed to create tracing filter");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let print_info = args.iter().any(|arg| arg == "--print-info");
+    // With `--print-info` stdout carries only the info line, so wrappers can
+    // parse it.
+    let log_writer = if print_info {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
     tracing_subscriber::registry()
-        .with(tracing_subscriber::fmt::layer().with_filter(env_filter))
+        .with(
+            tracing_subscriber::fmt::layer()
+                .with_writer(log_writer)
+                .with_filter(env_filter),
+        )
         .with(sentry_layer())
         .init();
 
@@ -67,6 +85,10 @@ async fn main() -> Result<(), VibeKanbanError> {
         tracing::info!("Database copy complete");
     }
 
+    if let Some(code) = server::db_cli::run(&args).await {
+        std::process::exit(code);
+    }
+
     let shutdown_token = CancellationToken::new();
 
     let deployment = DeploymentImpl::new(shutdown_token.clone()).await?;
@@ -141,18 +163,28 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     let app_router = routes::router(deployment.clone());
 
+    // Printed before either server is started so wrappers can read it and
+    // then connect.
+    if print_info {
+        print_server_info(main_listener.local_addr()?)?;
+    }
+
     // Production only: open browser
     if !cfg!(debug_assertions) {
         tracing::info!("Opening browser...");
         let browser_port = actual_main_port;
         tokio::spawn(async move {
-            if let Err(e) =
-                utils::browser::open_browser(&format!("http://127.0.0.1:{browser_port}")).await
+            if let Err(e) = utils::browser::open_browser(&format!(
+                "http://127.0.0.1:{browser_port}{}/",
+                utils::base_path()
+            ))
+            .await
             {
                 tracing::warn!(
-                    "Failed to open browser automatically: {}. Please open http://127.0.0.1:{} manually.",
+                    "Failed to open browser automatically: {}. Please open http://127.0.0.1:{}{}/ manually.",
                     e,
-                    browser_port
+                    browser_port,
+                    utils::base_path()
                 );
             }
         });
@@ -164,8 +196,14 @@ async fn main() -> Result<(), VibeKanbanError> {
     let main_shutdown = shutdown_token.clone();
     let proxy_shutdown = shutdown_token.clone();
 
-    let main_server = axum::serve(main_listener, app_router)
-        .with_graceful_shutdown(async move { main_shutdown.cancelled().await });
+    let activity = idle_shutdown::ActivityTracker::new();
+    let main_listener = idle_shutdown::TrackedListener::new(main_listener, activity.clone());
+
+    let main_server = axum::serve(
+        main_listener,
+        app_router.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(async move { main_shutdown.cancelled().await });
     let proxy_server = axum::serve(proxy_listener, proxy_router)
         .with_graceful_shutdown(async move { proxy_shutdown.cancelled().await });
 
@@ -182,6 +220,10 @@ async fn main() -> Result<(), VibeKanbanError> {
 
     relay_registration::spawn_relay(&deployment).await;
 
+    if let Some(timeout) = idle_shutdown::idle_timeout() {
+        idle_shutdown::spawn_idle_shutdown(&deployment, activity, timeout, shutdown_token.clone());
+    }
+
     tokio::select! {
         _ = shutdown_signal() => {
             tracing::info!("Shutdown signal received");
@@ -197,6 +239,19 @@ async fn main() -> Result<(), VibeKanbanError> {
     Ok(())
 }
 
+/// `--print-info`: one line of JSON describing where the server listens.
+fn print_server_info(addr: SocketAddr) -> std::io::Result<()> {
+    let info = serde_json::json!({
+        "url": format!("http://{addr}{}", utils::base_path()),
+        "port": addr.port(),
+        "host": addr.ip().to_string(),
+        "version": env!("CARGO_PKG_VERSION"),
+    });
+    let mut stdout = std::io::stdout().lock();
+    writeln!(stdout, "{info}")?;
+    stdout.flush()
+}
+
 pub async fn shutdown_signal() {
     // Always wait for Ctrl+C
     let ctrl_c = async {
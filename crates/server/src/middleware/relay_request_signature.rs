@@ -98,7 +98,9 @@ pub async fn sign_relay_response(
         &body_bytes,
     );
 
-    let signature = deployment.relay_signing().sign_bytes(message.as_bytes());
+    let signature = deployment
+        .relay_signing()
+        .sign_for_session(request_signature.signing_session_id, message.as_bytes());
     let response_signature = BASE64_STANDARD.encode(signature.to_bytes());
 
     insert_header(
@@ -1,13 +1,16 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
+    net::{IpAddr, Ipv4Addr, SocketAddr},
     pin::Pin,
+    sync::{Arc, Mutex, OnceLock},
     task::{Context, Poll},
 };
 
 use axum::{
     extract::{
-        FromRef, FromRequestParts,
-        ws::{Message, WebSocket, WebSocketUpgrade},
+        ConnectInfo, FromRef, FromRequestParts,
+        ws::{CloseFrame, Message, WebSocket, WebSocketUpgrade, close_code},
     },
     http::request::Parts,
     response::IntoResponse,
@@ -16,6 +19,8 @@ use deployment::Deployment;
 use futures_util::{Sink, SinkExt, Stream, StreamExt};
 use relay_control::signing::{RelaySigningService, RequestSignature};
 use relay_ws::{SignedAxumSocket, signed_axum_websocket};
+use utils::env::positive_or;
+use uuid::Uuid;
 
 use crate::{DeploymentImpl, middleware::RelayRequestSignatureContext};
 
@@ -30,9 +35,95 @@ enum SigningMode {
     RelayMissingSession,
 }
 
+const DEFAULT_MAX_WEBSOCKETS_PER_CLIENT: usize = 64;
+/// Every tab of the user's own browser shares one local address, so local
+/// clients get more room than relayed ones.
+const DEFAULT_MAX_WEBSOCKETS_PER_LOCAL_CLIENT: usize = 256;
+
+/// Who a WebSocket is counted against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ClientKey {
+    /// A relayed client, told apart by its signing session.
+    Relay(Uuid),
+    /// A direct connection, told apart by its remote address.
+    Local(IpAddr),
+}
+
+/// Caps how many WebSockets one client keeps open, so many tabs each
+/// replaying history can't exhaust the server.
+struct WsLimiter {
+    max_per_client: usize,
+    max_per_local_client: usize,
+    open: Mutex<HashMap<ClientKey, usize>>,
+}
+
+impl WsLimiter {
+    fn new(max_per_client: usize, max_per_local_client: usize) -> Arc<Self> {
+        Arc::new(Self {
+            max_per_client,
+            max_per_local_client,
+            open: Mutex::new(HashMap::new()),
+        })
+    }
+
+    fn try_acquire(self: &Arc<Self>, client: ClientKey) -> Option<WsSlot> {
+        let max = match client {
+            ClientKey::Relay(_) => self.max_per_client,
+            ClientKey::Local(_) => self.max_per_local_client,
+        };
+        let mut open = self.open.lock().unwrap();
+        let count = open.entry(client).or_default();
+        if *count >= max {
+            return None;
+        }
+        *count += 1;
+        Some(WsSlot {
+            limiter: self.clone(),
+            client,
+        })
+    }
+}
+
+/// An open WebSocket counted against its client; released on drop.
+struct WsSlot {
+    limiter: Arc<WsLimiter>,
+    client: ClientKey,
+}
+
+impl Drop for WsSlot {
+    fn drop(&mut self) {
+        let mut open = self.limiter.open.lock().unwrap();
+        if let Some(count) = open.get_mut(&self.client) {
+            *count -= 1;
+            if *count == 0 {
+                open.remove(&self.client);
+            }
+        }
+    }
+}
+
+/// The process-wide limiter, sized by `VK_MAX_WEBSOCKETS_PER_CLIENT` and
+/// `VK_MAX_WEBSOCKETS_PER_LOCAL_CLIENT`.
+fn ws_limiter() -> &'static Arc<WsLimiter> {
+    static LIMITER: OnceLock<Arc<WsLimiter>> = OnceLock::new();
+    LIMITER.get_or_init(|| {
+        WsLimiter::new(
+            positive_or(
+                "VK_MAX_WEBSOCKETS_PER_CLIENT",
+                DEFAULT_MAX_WEBSOCKETS_PER_CLIENT,
+            ),
+            positive_or(
+                "VK_MAX_WEBSOCKETS_PER_LOCAL_CLIENT",
+                DEFAULT_MAX_WEBSOCKETS_PER_LOCAL_CLIENT,
+            ),
+        )
+    })
+}
+
 pub struct SignedWsUpgrade {
     ws: WebSocketUpgrade,
     signing_mode: SigningMode,
+    client: ClientKey,
 }
 
 impl<S> FromRequestParts<S> for SignedWsUpgrade
@@ -50,6 +141,17 @@ where
             .get::<RelayRequestSignatureContext>()
             .cloned();
 
+        let client = match &relay_ctx {
+            Some(ctx) => ClientKey::Relay(ctx.signing_session_id),
+            // Routers served without connect info (e.g. in tests) count every
+            // direct connection together.
+            None => ClientKey::Local(
+                parts
+                    .extensions
+                    .get::<ConnectInfo<SocketAddr>>()
+                    .map_or(IpAddr::V4(Ipv4Addr::LOCALHOST), |info| info.0.ip()),
+            ),
+        };
         let signing_mode = if let Some(ctx) = relay_ctx {
             let peer_verify_key = deployment
                 .relay_signing()
@@ -67,7 +169,11 @@ where
             SigningMode::LocalPlain
         };
 
-        Ok(Self { ws, signing_mode })
+        Ok(Self {
+            ws,
+            signing_mode,
+            client,
+        })
     }
 }
 
@@ -102,7 +208,25 @@ impl SignedWsUpgrade {
             }
         };
 
-        self.ws.on_upgrade(move |socket| async move {
+        let slot = ws_limiter().try_acquire(self.client);
+        self.ws.on_upgrade(move |mut socket| async move {
+            // Held until the handler returns.
+            let _slot = match slot {
+                Some(slot) => slot,
+                None => {
+                    tracing::warn!(
+                        "Rejecting WebSocket: too many open connections for this client"
+                    );
+                    let _ = socket
+                        .send(Message::Close(Some(CloseFrame {
+                            code: close_code::AGAIN,
+                            reason: "Too many open connections".into(),
+                        })))
+                        .await;
+                    return;
+                }
+            };
+
             let inner = match relay_signing {
                 Some(ctx) => {
                     match signed_axum_websocket(&ctx.signing, &ctx.request_signature, socket).await
@@ -207,3 +331,35 @@ impl Sink<Message> for MaybeSignedWebSocket {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits_open_websockets_per_client() {
+        let limiter = WsLimiter::new(2, 3);
+        let client = ClientKey::Relay(Uuid::new_v4());
+        let other = ClientKey::Relay(Uuid::new_v4());
+
+        let first = limiter.try_acquire(client).unwrap();
+        let _second = limiter.try_acquire(client).unwrap();
+        assert!(limiter.try_acquire(client).is_none());
+        assert!(limiter.try_acquire(other).is_some());
+
+        drop(first);
+        assert!(limiter.try_acquire(client).is_some());
+    }
+
+    #[test]
+    fn local_connections_are_capped_by_address() {
+        let limiter = WsLimiter::new(1, 2);
+        let local = ClientKey::Local(IpAddr::V4(Ipv4Addr::LOCALHOST));
+        let lan = ClientKey::Local(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 20)));
+
+        let _first = limiter.try_acquire(local).unwrap();
+        let _second = limiter.try_acquire(local).unwrap();
+        assert!(limiter.try_acquire(local).is_none());
+        assert!(limiter.try_acquire(lan).is_some());
+    }
+}
@@ -1,12 +1,14 @@
 use std::{sync::Arc, time::Duration};
 
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64_STANDARD};
+use ed25519_dalek::VerifyingKey;
 use http::HeaderMap;
-use relay_control::signing::RelaySigningService;
+use relay_control::signing::{RelaySigningService, build_session_key_endorsement_message};
 use relay_types::{
     FinishSpake2EnrollmentRequest, FinishSpake2EnrollmentResponse,
     RefreshRelaySigningSessionRequest, RefreshRelaySigningSessionResponse, RelayPairedClient,
-    StartSpake2EnrollmentRequest, StartSpake2EnrollmentResponse,
+    RelaySessionServerKey, RelaySessionServerKeysResponse, StartSpake2EnrollmentRequest,
+    StartSpake2EnrollmentResponse,
 };
 use services::services::{analytics::AnalyticsService, config::Config};
 use tokio::sync::RwLock;
@@ -263,10 +265,46 @@ impl RelayPairingServer {
             build_refresh_message(payload.timestamp, &payload.nonce, payload.client_id);
         verify_refresh_signature(&client_public_key, &refresh_message, &payload.signature_b64)?;
 
-        let signing_session_id = self.relay_signing.create_session(client_public_key).await;
+        let signing_session_id = if payload.rotate_server_key {
+            self.relay_signing
+                .create_session_with_rotating_key(client_public_key)
+                .await
+        } else {
+            self.relay_signing.create_session(client_public_key).await
+        };
 
         Ok(RefreshRelaySigningSessionResponse { signing_session_id })
     }
+
+    /// Public keys for a session that signs with a rotating server key, each
+    /// endorsed by the long-term server key the client pinned when pairing.
+    pub fn session_server_keys(
+        &self,
+        signing_session_id: Uuid,
+    ) -> Result<RelaySessionServerKeysResponse, ApiError> {
+        let keys = self
+            .relay_signing
+            .session_server_keys(signing_session_id)
+            .ok_or_else(|| {
+                ApiError::BadRequest(
+                    "Signing session does not use a rotating server key".to_string(),
+                )
+            })?;
+
+        let endorse = |key: &VerifyingKey| {
+            let message = build_session_key_endorsement_message(signing_session_id, key);
+            RelaySessionServerKey {
+                public_key_b64: BASE64_STANDARD.encode(key.as_bytes()),
+                endorsement_b64: BASE64_STANDARD
+                    .encode(self.relay_signing.sign_bytes(message.as_bytes()).to_bytes()),
+            }
+        };
+
+        Ok(RelaySessionServerKeysResponse {
+            current: endorse(&keys.current),
+            previous: keys.previous.iter().map(endorse).collect(),
+        })
+    }
 }
 
 pub fn is_relay_request(headers: &HeaderMap) -> bool {
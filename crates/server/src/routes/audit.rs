@@ -0,0 +1,49 @@
+use axum::{
+    Router,
+    extract::{Query, State},
+    response::Json as ResponseJson,
+    routing::get,
+};
+use db::models::coding_agent_turn::{CodingAgentTurn, CodingAgentTurnSearchResult};
+use deployment::Deployment;
+use serde::Deserialize;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+const DEFAULT_AUDIT_SEARCH_LIMIT: i64 = 50;
+const MAX_AUDIT_SEARCH_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct AuditSearchQuery {
+    pub q: String,
+    /// Only return turns from workspaces that include this repository.
+    pub repo_id: Option<Uuid>,
+    pub limit: Option<i64>,
+}
+
+pub async fn search_audit(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<AuditSearchQuery>,
+) -> Result<ResponseJson<ApiResponse<Vec<CodingAgentTurnSearchResult>>>, ApiError> {
+    if query.q.trim().is_empty() {
+        return Ok(ResponseJson(ApiResponse::error(
+            "Query parameter 'q' is required and cannot be empty",
+        )));
+    }
+
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_AUDIT_SEARCH_LIMIT)
+        .clamp(1, MAX_AUDIT_SEARCH_LIMIT);
+
+    let results =
+        CodingAgentTurn::search(&deployment.db().pool, &query.q, query.repo_id, limit).await?;
+
+    Ok(ResponseJson(ApiResponse::success(results)))
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new().route("/audit/search", get(search_audit))
+}
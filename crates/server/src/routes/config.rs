@@ -7,25 +7,31 @@ use axum::{
     extract::{Path, Query, State, ws::Message},
     http,
     response::{IntoResponse, Json as ResponseJson, Response},
-    routing::{get, put},
+    routing::{get, post, put},
 };
+use db::models::failed_notification::FailedNotification;
 use deployment::{Deployment, DeploymentError};
 use executors::{
     executors::{
-        AvailabilityInfo, BaseAgentCapability, BaseCodingAgent, StandardCodingAgentExecutor,
+        AvailabilityInfo, BaseAgentCapability, BaseCodingAgent, CodingAgent,
+        StandardCodingAgentExecutor,
     },
     mcp_config::{McpConfig, read_agent_config, write_agent_config},
     profile::{ExecutorConfigs, ExecutorProfileId},
 };
+use git_host::{GitHostService, ProviderKind, parse_remote_url};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use services::services::{
+    agent_identity_template, branch_template,
     config::{
         Config, ConfigError, SoundFile,
         editor::{EditorConfig, EditorType},
         save_config_to_file,
     },
     container::ContainerService,
+    issue_prompt_template,
+    notification::{RedriveError, TestNotificationResult},
     remote_client::RemoteClientError,
 };
 use tokio::fs;
@@ -45,6 +51,12 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/info", get(get_user_system_info))
         .route("/config", put(update_config))
         .route("/sounds/{sound}", get(get_sound))
+        .route("/notifications/test", post(send_test_notification))
+        .route("/notifications/failed", get(list_failed_notifications))
+        .route(
+            "/notifications/failed/{id}/redrive",
+            post(redrive_failed_notification),
+        )
         .route("/mcp-config", get(get_mcp_servers).post(update_mcp_servers))
         .route("/profiles", get(get_profiles).put(update_profiles))
         .route(
@@ -53,6 +65,8 @@ pub fn router() -> Router<DeploymentImpl> {
         )
         .route("/agents/check-availability", get(check_agent_availability))
         .route("/agents/preset-options", get(get_agent_preset_options))
+        .route("/config/profiles", get(list_resolved_profiles))
+        .route("/config/git-hosts/detect", post(detect_git_host))
         .route(
             "/agents/discovered-options/ws",
             get(stream_executor_discovered_options_ws),
@@ -190,6 +204,33 @@ async fn update_config(
         ));
     }
 
+    if let Some(template) = &new_config.git_branch_template
+        && let Err(e) = branch_template::validate(template)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+
+    if let Some(template) = &new_config.issue_prompt_template
+        && let Err(e) = issue_prompt_template::validate(template)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+
+    if let Some(template) = &new_config.agent_identity_template
+        && let Err(e) = agent_identity_template::validate(template)
+    {
+        return ResponseJson(ApiResponse::error(&e.to_string()));
+    }
+
+    if let Some(webhook) = new_config.webhooks.iter().find(|webhook| {
+        !url::Url::parse(&webhook.url).is_ok_and(|url| matches!(url.scheme(), "http" | "https"))
+    }) {
+        return ResponseJson(ApiResponse::error(&format!(
+            "Invalid webhook URL `{}`. Use an http or https URL.",
+            webhook.url
+        )));
+    }
+
     // Get old config state before updating
     let old_config = deployment.config().read().await.clone();
 
@@ -242,6 +283,7 @@ async fn track_config_events(deployment: &DeploymentImpl, old: &Config, new: &Co
 
 async fn handle_config_events(deployment: &DeploymentImpl, old: &Config, new: &Config) {
     track_config_events(deployment, old, new).await;
+    executors::executors::claude::set_disable_api_key_default(new.claude_disable_api_key);
 
     let old_host_nickname = relay_registration::clean_host_nickname(old, deployment.user_id());
     let new_host_nickname = relay_registration::clean_host_nickname(new, deployment.user_id());
@@ -271,6 +313,43 @@ async fn get_sound(Path(sound): Path<SoundFile>) -> Result<Response, ApiError> {
     Ok(response)
 }
 
+async fn send_test_notification(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<TestNotificationResult>> {
+    let result = deployment
+        .container()
+        .notification_service()
+        .send_test_notification()
+        .await;
+    if !result.sound && !result.push {
+        return ResponseJson(ApiResponse::error("No notification channels are enabled"));
+    }
+    ResponseJson(ApiResponse::success(result))
+}
+
+async fn list_failed_notifications(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<FailedNotification>>>, ApiError> {
+    let failed = FailedNotification::list(&deployment.db().pool).await?;
+    Ok(ResponseJson(ApiResponse::success(failed)))
+}
+
+async fn redrive_failed_notification(
+    State(deployment): State<DeploymentImpl>,
+    Path(id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    match deployment
+        .container()
+        .notification_service()
+        .redrive(id)
+        .await
+    {
+        Ok(()) => Ok(ResponseJson(ApiResponse::success(()))),
+        Err(RedriveError::Database(e)) => Err(e.into()),
+        Err(e) => Ok(ResponseJson(ApiResponse::error(&e.to_string()))),
+    }
+}
+
 #[derive(TS, Debug, Deserialize)]
 pub struct McpServerQuery {
     executor: BaseCodingAgent,
@@ -478,13 +557,95 @@ async fn get_profiles(
     }))
 }
 
+/// A profile variant with the settings it actually runs with.
+#[derive(Debug, Serialize, TS)]
+pub struct ResolvedProfile {
+    /// `EXECUTOR` or `EXECUTOR:VARIANT`, e.g. `CLAUDE_CODE:PLAN`
+    pub name: String,
+    pub executor: BaseCodingAgent,
+    pub variant: String,
+    /// The variant's configuration, with environment variable values redacted
+    pub config: Value,
+}
+
+const REDACTED: &str = "<redacted>";
+
+async fn list_resolved_profiles() -> ResponseJson<ApiResponse<Vec<ResolvedProfile>>> {
+    let profiles = ExecutorConfigs::get_cached();
+    let mut resolved: Vec<ResolvedProfile> = profiles
+        .executors
+        .iter()
+        .flat_map(|(executor, profile)| {
+            profile
+                .configurations
+                .iter()
+                .map(move |(variant, agent)| ResolvedProfile {
+                    name: ExecutorProfileId::with_variant(*executor, variant.clone()).to_string(),
+                    executor: *executor,
+                    variant: variant.clone(),
+                    config: redacted_config(agent),
+                })
+        })
+        .collect();
+    resolved.sort_by(|a, b| {
+        (a.executor.to_string(), a.variant != "DEFAULT", &a.variant).cmp(&(
+            b.executor.to_string(),
+            b.variant != "DEFAULT",
+            &b.variant,
+        ))
+    });
+
+    ResponseJson(ApiResponse::success(resolved))
+}
+
+/// The agent's settings without the executor tag. Env values often hold API
+/// keys, so only their names are kept.
+fn redacted_config(agent: &CodingAgent) -> Value {
+    let mut config = match serde_json::to_value(agent) {
+        Ok(Value::Object(tagged)) => tagged
+            .into_iter()
+            .next()
+            .map(|(_, v)| v)
+            .unwrap_or_default(),
+        Ok(other) => other,
+        Err(e) => {
+            tracing::error!("Failed to serialize profile config: {}", e);
+            Value::Null
+        }
+    };
+    if let Some(Value::Object(env)) = config.get_mut("env") {
+        for value in env.values_mut() {
+            *value = Value::String(REDACTED.to_string());
+        }
+    }
+    config
+}
+
 async fn update_profiles(
-    State(_deployment): State<DeploymentImpl>,
+    State(deployment): State<DeploymentImpl>,
     body: String,
 ) -> ResponseJson<ApiResponse<String>> {
     // Try to parse as ExecutorProfileConfigs format
     match serde_json::from_str::<ExecutorConfigs>(&body) {
         Ok(executor_profiles) => {
+            // Rejected here as well as at spawn time, so a profile that can
+            // never run isn't saved
+            if deployment.config().read().await.disallow_permission_bypass {
+                let violation = executor_profiles
+                    .executors
+                    .values()
+                    .flat_map(|profile| profile.configurations.iter())
+                    .find_map(|(name, config)| match config {
+                        CodingAgent::ClaudeCode(claude) => claude
+                            .check_permission_policy()
+                            .err()
+                            .map(|e| format!("{name}: {e}")),
+                        _ => None,
+                    });
+                if let Some(violation) = violation {
+                    return ResponseJson(ApiResponse::error(&violation));
+                }
+            }
             // Save the profiles to file
             match executor_profiles.save_overrides() {
                 Ok(_) => {
@@ -560,6 +721,48 @@ async fn check_agent_availability(
     ResponseJson(ApiResponse::success(info))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct DetectGitHostRequest {
+    pub remote_url: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct DetectedGitHost {
+    pub provider: ProviderKind,
+    pub host: String,
+    pub owner: String,
+    pub repo: String,
+    /// Whether the provider's CLI is signed in for this host; `None` when it
+    /// can't be checked (generic hosts, or the CLI isn't installed).
+    pub auth_configured: Option<bool>,
+}
+
+async fn detect_git_host(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DetectGitHostRequest>,
+) -> ResponseJson<ApiResponse<DetectedGitHost>> {
+    let Some(location) = parse_remote_url(&payload.remote_url) else {
+        return ResponseJson(ApiResponse::error(
+            "Remote URL must include a host, owner and repository",
+        ));
+    };
+
+    let generic_fallback = deployment.config().read().await.generic_git_fallback;
+    let provider = match GitHostService::detect_provider(&payload.remote_url) {
+        ProviderKind::Unknown if generic_fallback => ProviderKind::Generic,
+        provider => provider,
+    };
+    let auth_configured = GitHostService::is_authenticated(provider, &location.host).await;
+
+    ResponseJson(ApiResponse::success(DetectedGitHost {
+        provider,
+        host: location.host,
+        owner: location.owner,
+        repo: location.repo,
+        auth_configured,
+    }))
+}
+
 #[derive(Debug, Deserialize, TS)]
 pub struct AgentPresetOptionsQuery {
     pub executor: BaseCodingAgent,
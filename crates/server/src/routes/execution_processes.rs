@@ -7,13 +7,20 @@ use axum::{
     routing::{get, post},
 };
 use db::models::{
-    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    execution_process::{ExecutionProcess, ExecutionProcessResourceUsage},
     execution_process_repo_state::ExecutionProcessRepoState,
 };
 use deployment::Deployment;
+use executors::logs::TokenUsageTotals;
 use futures_util::{StreamExt, TryStreamExt};
 use serde::Deserialize;
-use services::services::container::ContainerService;
+use services::services::{
+    container::{CancelExecutionOutcome, ContainerService},
+    outcome_summary::{self, OutcomeSummary},
+    renormalize_jobs::{DEFAULT_RENORMALIZE_CONCURRENCY, RenormalizeJob},
+    token_usage,
+};
+use ts_rs::TS;
 use utils::{log_msg::LogMsg, response::ApiResponse};
 use uuid::Uuid;
 
@@ -135,16 +142,25 @@ async fn handle_raw_logs_ws(
     Ok(())
 }
 
+#[derive(Debug, Deserialize)]
+struct NormalizedLogsQuery {
+    /// Replay only the last N entries before streaming live updates.
+    #[serde(default)]
+    tail: Option<usize>,
+}
+
 async fn stream_normalized_logs_ws(
     ws: SignedWsUpgrade,
     State(deployment): State<DeploymentImpl>,
     Path(exec_id): Path<Uuid>,
+    Query(query): Query<NormalizedLogsQuery>,
 ) -> impl IntoResponse {
     ws.on_upgrade(move |socket| async move {
-        let stream = deployment
-            .container()
-            .stream_normalized_logs(&exec_id)
-            .await;
+        let container = deployment.container();
+        let stream = match query.tail {
+            Some(tail) => container.stream_normalized_logs_tail(&exec_id, tail).await,
+            None => container.stream_normalized_logs(&exec_id).await,
+        };
 
         match stream {
             Some(stream) => {
@@ -204,14 +220,29 @@ async fn stop_execution_process(
     Extension(execution_process): Extension<ExecutionProcess>,
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    // Also covers runs that are queued or haven't spawned yet.
     deployment
         .container()
-        .stop_execution(&execution_process, ExecutionProcessStatus::Killed)
+        .cancel_execution(&execution_process)
         .await?;
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
 
+/// Cancel a coding agent run. A run still queued behind a concurrency
+/// limit is dropped without starting; a running one is killed.
+async fn cancel_execution_process(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<CancelExecutionOutcome>>, ApiError> {
+    let outcome = deployment
+        .container()
+        .cancel_execution(&execution_process)
+        .await?;
+
+    Ok(ResponseJson(ApiResponse::success(outcome)))
+}
+
 async fn stream_execution_processes_by_session_ws(
     ws: SignedWsUpgrade,
     State(deployment): State<DeploymentImpl>,
@@ -283,11 +314,130 @@ async fn get_execution_process_repo_states(
     Ok(ResponseJson(ApiResponse::success(repo_states)))
 }
 
+/// Files changed and test results of a finished coding agent run; `None`
+/// until the run completes.
+async fn get_execution_process_outcome(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<OutcomeSummary>>>, ApiError> {
+    let summary = outcome_summary::find(&deployment.db().pool, execution_process.id).await?;
+    Ok(ResponseJson(ApiResponse::success(summary)))
+}
+
+/// Tokens the run was billed for; zero when the agent didn't report usage.
+async fn get_execution_process_token_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TokenUsageTotals>>, ApiError> {
+    let usage = token_usage::execution_token_usage(
+        deployment.container(),
+        &deployment.db().pool,
+        execution_process.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+async fn get_execution_process_usage(
+    Extension(execution_process): Extension<ExecutionProcess>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcessResourceUsage>>, ApiError> {
+    if let Some(usage) = deployment
+        .container()
+        .live_resource_usage(&execution_process.id)
+        .await
+    {
+        return Ok(ResponseJson(ApiResponse::success(usage)));
+    }
+
+    let usage = ExecutionProcess::find_resource_usage(&deployment.db().pool, execution_process.id)
+        .await?
+        .unwrap_or_default();
+    Ok(ResponseJson(ApiResponse::success(usage)))
+}
+
+/// Scope of a bulk renormalize job. Exactly one of `workspace_id` and
+/// `repo_id` must be set.
+#[derive(Debug, Deserialize, TS)]
+pub struct RenormalizeProcessesRequest {
+    #[serde(default)]
+    #[ts(optional)]
+    pub workspace_id: Option<Uuid>,
+    #[serde(default)]
+    #[ts(optional)]
+    pub repo_id: Option<Uuid>,
+    /// Processes renormalized at once. Defaults to 2, capped at 8.
+    #[serde(default)]
+    #[ts(optional)]
+    pub concurrency: Option<usize>,
+}
+
+/// Renormalize every finished coding agent run in a workspace, or in all
+/// workspaces using a repo, as a background job. Progress is published on the
+/// events stream under `/renormalize_jobs/{id}`.
+async fn start_renormalize_job(
+    State(deployment): State<DeploymentImpl>,
+    ResponseJson(payload): ResponseJson<RenormalizeProcessesRequest>,
+) -> Result<ResponseJson<ApiResponse<RenormalizeJob>>, ApiError> {
+    if payload.workspace_id.is_some() == payload.repo_id.is_some() {
+        return Err(ApiError::BadRequest(
+            "Specify exactly one of workspace_id or repo_id".to_string(),
+        ));
+    }
+    // Renormalized output is only kept in the session replay cache.
+    if !deployment.config().read().await.cache_session_replays {
+        return Err(ApiError::BadRequest(
+            "Enable session replay caching to renormalize logs".to_string(),
+        ));
+    }
+
+    let process_ids = ExecutionProcess::find_finished_coding_agent_ids(
+        &deployment.db().pool,
+        payload.workspace_id,
+        payload.repo_id,
+    )
+    .await?;
+    let job = deployment.renormalize_jobs().create(
+        payload.workspace_id,
+        payload.repo_id,
+        payload
+            .concurrency
+            .unwrap_or(DEFAULT_RENORMALIZE_CONCURRENCY),
+        process_ids.len(),
+    );
+    tracing::info!(
+        "Renormalizing {} execution processes (job {}, concurrency {})",
+        job.total,
+        job.id,
+        job.concurrency
+    );
+
+    let jobs = deployment.renormalize_jobs().clone();
+    let container = deployment.container().clone();
+    let job_id = job.id;
+    tokio::spawn(async move {
+        jobs.run(job_id, process_ids, |id| container.renormalize_process(id))
+            .await;
+    });
+
+    Ok(ResponseJson(ApiResponse::success(job)))
+}
+
+async fn list_renormalize_jobs(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<RenormalizeJob>>> {
+    ResponseJson(ApiResponse::success(deployment.renormalize_jobs().list()))
+}
+
 pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
     let workspace_id_router = Router::new()
         .route("/", get(get_execution_process_by_id))
         .route("/stop", post(stop_execution_process))
+        .route("/cancel", post(cancel_execution_process))
         .route("/repo-states", get(get_execution_process_repo_states))
+        .route("/usage", get(get_execution_process_usage))
+        .route("/outcome", get(get_execution_process_outcome))
+        .route("/token-usage", get(get_execution_process_token_usage))
         .route("/raw-logs/ws", get(stream_raw_logs_ws))
         .route("/normalized-logs/ws", get(stream_normalized_logs_ws))
         .layer(from_fn_with_state(
@@ -300,6 +450,10 @@ pub(super) fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
             "/stream/session/ws",
             get(stream_execution_processes_by_session_ws),
         )
+        .route(
+            "/renormalize",
+            get(list_renormalize_jobs).post(start_renormalize_job),
+        )
         .nest("/{id}", workspace_id_router);
 
     Router::new().nest("/execution-processes", workspaces_router)
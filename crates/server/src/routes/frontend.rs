@@ -19,7 +19,77 @@ pub(super) async fn serve_frontend_root() -> impl IntoResponse {
     serve_file("index.html").await
 }
 
+/// Tags whose `href`/`src` point at bundled assets.
+const ASSET_TAGS: [&str; 4] = ["<base", "<link", "<script", "<img"];
+
+/// Point `<base href>` and the root-relative asset URLs of `index.html` at
+/// `base_path`, and expose it to the SPA as `window.__VK_BASE_PATH__`. Inline
+/// script bodies and other attributes are left alone.
+fn rewrite_index_html(html: &str, base_path: &str) -> String {
+    if base_path.is_empty() {
+        return html.to_string();
+    }
+
+    let mut out = String::with_capacity(html.len() + 256);
+    let mut rest = html;
+    while let Some(open) = rest.find('<') {
+        out.push_str(&rest[..open]);
+        rest = &rest[open..];
+        let end = rest.find('>').map_or(rest.len(), |i| i + 1);
+        let tag = &rest[..end];
+        let is_asset_tag = ASSET_TAGS.iter().any(|name| {
+            tag.strip_prefix(*name)
+                .is_some_and(|after| after.starts_with(|c: char| c.is_ascii_whitespace()))
+        });
+        if is_asset_tag {
+            out.push_str(&prefix_asset_urls(tag, base_path));
+        } else {
+            out.push_str(tag);
+        }
+        rest = &rest[end..];
+    }
+    out.push_str(rest);
+
+    let script = format!(
+        "<script>window.__VK_BASE_PATH__={};</script></head>",
+        serde_json::Value::from(base_path)
+    );
+    out.replacen("</head>", &script, 1)
+}
+
+/// Prefix root-relative `href` and `src` values in one tag. Protocol-relative
+/// `//host` URLs are external and kept as written.
+fn prefix_asset_urls(tag: &str, base_path: &str) -> String {
+    let mut tag = tag.to_string();
+    for attr in [" href=\"/", " src=\"/"] {
+        let mut from = 0;
+        while let Some(offset) = tag[from..].find(attr) {
+            let slash = from + offset + attr.len() - 1;
+            if !tag[slash + 1..].starts_with('/') {
+                tag.insert_str(slash, base_path);
+            }
+            from = slash + 1;
+        }
+    }
+    tag
+}
+
+fn index_response(index: &[u8]) -> Response {
+    let html = rewrite_index_html(&String::from_utf8_lossy(index), utils::base_path());
+    Response::builder()
+        .status(StatusCode::OK)
+        .header(header::CONTENT_TYPE, HeaderValue::from_static("text/html"))
+        .body(Body::from(html))
+        .unwrap()
+}
+
 async fn serve_file(path: &str) -> impl IntoResponse + use<> {
+    if path == "index.html"
+        && let Some(index) = Assets::get(path)
+    {
+        return index_response(&index.data);
+    }
+
     let file = Assets::get(path);
 
     match file {
@@ -38,11 +108,7 @@ async fn serve_file(path: &str) -> impl IntoResponse + use<> {
         None => {
             // For SPA routing, serve index.html for unknown routes
             if let Some(index) = Assets::get("index.html") {
-                Response::builder()
-                    .status(StatusCode::OK)
-                    .header(header::CONTENT_TYPE, HeaderValue::from_static("text/html"))
-                    .body(Body::from(index.data.into_owned()))
-                    .unwrap()
+                index_response(&index.data)
             } else {
                 Response::builder()
                     .status(StatusCode::NOT_FOUND)
@@ -52,3 +118,33 @@ async fn serve_file(path: &str) -> impl IntoResponse + use<> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::rewrite_index_html;
+
+    #[test]
+    fn index_html_is_unchanged_at_root() {
+        let html = r#"<head><script src="/assets/index.js"></script></head>"#;
+        assert_eq!(rewrite_index_html(html, ""), html);
+    }
+
+    #[test]
+    fn index_html_assets_are_prefixed_with_base_path() {
+        let html =
+            r#"<head><link href="/favicon.png"><script src="/assets/index.js"></script></head>"#;
+        assert_eq!(
+            rewrite_index_html(html, "/vibe"),
+            r#"<head><link href="/vibe/favicon.png"><script src="/vibe/assets/index.js"></script><script>window.__VK_BASE_PATH__="/vibe";</script></head>"#
+        );
+    }
+
+    #[test]
+    fn index_html_only_rewrites_asset_attributes() {
+        let html = r#"<head><base href="/"><meta content="/keep"><link href="//cdn.example/x.css"></head><body><a href="/keep"></a><script>var a = x=="/keep";</script></body>"#;
+        assert_eq!(
+            rewrite_index_html(html, "/vibe"),
+            r#"<head><base href="/vibe/"><meta content="/keep"><link href="//cdn.example/x.css"><script>window.__VK_BASE_PATH__="/vibe";</script></head><body><a href="/keep"></a><script>var a = x=="/keep";</script></body>"#
+        );
+    }
+}
@@ -1,6 +1,18 @@
-use axum::response::Json;
+use axum::{extract::State, response::Json};
+use db::maintenance::{self, AppliedMigration};
+use deployment::Deployment;
 use utils::response::ApiResponse;
 
+use crate::{DeploymentImpl, error::ApiError};
+
 pub(super) async fn health_check() -> Json<ApiResponse<String>> {
     Json(ApiResponse::success("OK".to_string()))
 }
+
+/// Migrations recorded in the local database, for debugging schema mismatches.
+pub(super) async fn list_migrations(
+    State(deployment): State<DeploymentImpl>,
+) -> Result<Json<ApiResponse<Vec<AppliedMigration>>>, ApiError> {
+    let migrations = maintenance::applied_migrations(&deployment.db().pool).await?;
+    Ok(Json(ApiResponse::success(migrations)))
+}
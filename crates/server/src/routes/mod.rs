@@ -12,6 +12,7 @@ pub mod containers;
 pub mod filesystem;
 // pub mod github;
 pub mod attachments;
+pub mod audit;
 pub mod events;
 pub mod execution_processes;
 pub mod frontend;
@@ -19,6 +20,7 @@ pub mod health;
 pub mod host_relay;
 pub mod oauth;
 pub mod organizations;
+pub mod pr_monitor;
 pub mod preview;
 pub mod relay_auth;
 pub mod releases;
@@ -36,6 +38,7 @@ pub mod workspaces;
 pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
     let relay_signed_routes = Router::new()
         .route("/health", get(health::health_check))
+        .route("/health/migrations", get(health::list_migrations))
         .merge(config::router())
         .merge(containers::router(&deployment))
         .merge(workspaces::router(&deployment))
@@ -49,6 +52,8 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .merge(approvals::router())
         .merge(scratch::router(&deployment))
         .merge(search::router(&deployment))
+        .merge(audit::router())
+        .merge(pr_monitor::router())
         .merge(preview::api_router())
         .merge(releases::router())
         .merge(sessions::router(&deployment))
@@ -77,10 +82,23 @@ pub fn router(deployment: DeploymentImpl) -> IntoMakeService<Router> {
         .layer(axum::middleware::from_fn(middleware::log_server_errors))
         .with_state(deployment);
 
-    Router::new()
+    let app_routes = Router::new()
         .route("/", get(frontend::serve_frontend_root))
-        .route("/{*path}", get(frontend::serve_frontend))
-        .nest("/api", api_routes)
+        .route("/{*path}", get(frontend::serve_frontend));
+
+    let base_path = utils::base_path();
+    let app_routes = if base_path.is_empty() {
+        app_routes.nest("/api", api_routes)
+    } else {
+        Router::new()
+            .route(&format!("{base_path}/"), get(frontend::serve_frontend_root))
+            .nest(base_path, app_routes.nest("/api", api_routes.clone()))
+            // Loopback clients (relay tunnel, WebRTC bridge) forward root-relative
+            // API paths, so the API stays reachable without the prefix.
+            .nest("/api", api_routes)
+    };
+
+    app_routes
         .layer(CompressionLayer::new())
         .into_make_service()
 }
@@ -1,5 +1,8 @@
+use std::time::Duration;
+
 use api_types::{
-    AuthMethodsResponse, HandoffInitRequest, HandoffRedeemRequest, LocalLoginRequest,
+    AuthMethodsResponse, DeviceInitRequest, DeviceInitResponse, DevicePollRequest,
+    DevicePollResponse, HandoffInitRequest, HandoffRedeemRequest, LocalLoginRequest,
     ProfileResponse, StatusResponse,
 };
 use axum::{
@@ -79,14 +82,31 @@ pub struct CurrentUserResponse {
     pub user_id: String,
 }
 
+/// A remote account signed in on this machine.
+#[derive(Debug, Serialize, TS)]
+pub struct RemoteAccount {
+    pub user_id: Uuid,
+    pub email: Option<String>,
+    /// Whether remote requests are currently made as this account.
+    pub active: bool,
+}
+
+#[derive(Debug, Deserialize, TS)]
+pub struct SwitchAccountRequest {
+    pub user_id: Uuid,
+}
+
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/auth/methods", get(auth_methods))
         .route("/auth/handoff/init", post(handoff_init))
         .route("/auth/handoff/complete", get(handoff_complete))
+        .route("/auth/device/init", post(device_init))
         .route("/auth/local/login", post(local_login))
         .route("/auth/logout", post(logout))
         .route("/auth/status", get(status))
+        .route("/auth/accounts", get(list_accounts))
+        .route("/auth/accounts/switch", post(switch_account))
         .route("/auth/token", get(get_token))
         .route("/auth/user", get(get_current_user))
 }
@@ -212,6 +232,116 @@ async fn handoff_complete(
     ))
 }
 
+#[derive(Debug, Deserialize)]
+struct DeviceInitPayload {
+    provider: String,
+}
+
+/// Starts a device-code login for installs without a local browser. The user
+/// code and verification URL are returned and logged; completion is polled in
+/// the background and surfaces through `/auth/status`.
+async fn device_init(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<DeviceInitPayload>,
+) -> Result<ResponseJson<ApiResponse<DeviceInitResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+
+    let app_verifier = generate_secret();
+    let request = DeviceInitRequest {
+        provider: payload.provider,
+        app_challenge: hash_sha256_hex(&app_verifier),
+    };
+
+    let response = client.device_init(&request).await?;
+
+    tracing::info!(
+        "To sign in, open {} and enter the code {}",
+        response.verification_uri,
+        response.user_code
+    );
+
+    let poll_deployment = deployment.clone();
+    let handoff_id = response.handoff_id;
+    let interval = Duration::from_secs(response.interval_secs.max(1));
+    let expires_at = response.expires_at;
+    tokio::spawn(async move {
+        poll_device_login(
+            poll_deployment,
+            handoff_id,
+            app_verifier,
+            interval,
+            expires_at,
+        )
+        .await;
+    });
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
+
+async fn poll_device_login(
+    deployment: DeploymentImpl,
+    handoff_id: Uuid,
+    app_verifier: String,
+    interval: Duration,
+    expires_at: DateTime<Utc>,
+) {
+    let Ok(client) = deployment.remote_client() else {
+        return;
+    };
+
+    let app_code = loop {
+        if Utc::now() >= expires_at {
+            tracing::warn!(%handoff_id, "device login expired before it was completed");
+            return;
+        }
+
+        tokio::time::sleep(interval).await;
+
+        match client.device_poll(&DevicePollRequest { handoff_id }).await {
+            Ok(DevicePollResponse::Pending) => continue,
+            Ok(DevicePollResponse::Authorized { app_code }) => break app_code,
+            Ok(DevicePollResponse::Denied) => {
+                tracing::warn!(%handoff_id, "device login was denied");
+                return;
+            }
+            Ok(DevicePollResponse::Expired) => {
+                tracing::warn!(%handoff_id, "device login expired before it was completed");
+                return;
+            }
+            Err(err) => {
+                tracing::debug!(?err, %handoff_id, "device login poll failed, retrying");
+            }
+        }
+    };
+
+    let redeem_request = HandoffRedeemRequest {
+        handoff_id,
+        app_code,
+        app_verifier,
+    };
+    let redeem = match client.handoff_redeem(&redeem_request).await {
+        Ok(redeem) => redeem,
+        Err(err) => {
+            tracing::error!(?err, %handoff_id, "failed to redeem device login");
+            return;
+        }
+    };
+
+    match finalize_login(
+        &deployment,
+        Credentials {
+            access_token: Some(redeem.access_token),
+            refresh_token: redeem.refresh_token,
+            expires_at: None,
+        },
+    )
+    .await
+    {
+        Ok(profile) => tracing::info!("Device login complete for {}", profile.email),
+        Err(err) => tracing::error!(?err, %handoff_id, "failed to finalize device login"),
+    }
+}
+
 async fn local_login(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<LocalLoginRequest>,
@@ -253,6 +383,12 @@ async fn logout(State(deployment): State<DeploymentImpl>) -> Result<StatusCode,
 async fn status(
     State(deployment): State<DeploymentImpl>,
 ) -> Result<ResponseJson<ApiResponse<StatusResponse>>, ApiError> {
+    Ok(ResponseJson(ApiResponse::success(
+        status_response(&deployment).await,
+    )))
+}
+
+async fn status_response(deployment: &DeploymentImpl) -> StatusResponse {
     use api_types::LoginStatus;
 
     let login_status = deployment.get_login_status().await;
@@ -263,21 +399,66 @@ async fn status(
         .map(|_| true);
 
     match login_status {
-        LoginStatus::LoggedOut => Ok(ResponseJson(ApiResponse::success(StatusResponse {
+        LoginStatus::LoggedOut => StatusResponse {
             logged_in: false,
             profile: None,
             degraded,
-        }))),
-        LoginStatus::LoggedIn { profile } => {
-            Ok(ResponseJson(ApiResponse::success(StatusResponse {
-                logged_in: true,
-                profile,
-                degraded,
-            })))
-        }
+        },
+        LoginStatus::LoggedIn { profile } => StatusResponse {
+            logged_in: true,
+            profile,
+            degraded,
+        },
     }
 }
 
+async fn list_accounts(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<Vec<RemoteAccount>>> {
+    let accounts = deployment
+        .auth_context()
+        .accounts()
+        .await
+        .into_iter()
+        .map(|account| RemoteAccount {
+            user_id: account.user_id,
+            email: account.email,
+            active: account.active,
+        })
+        .collect();
+    ResponseJson(ApiResponse::success(accounts))
+}
+
+/// Make another signed-in account active and report its login status.
+async fn switch_account(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<SwitchAccountRequest>,
+) -> Result<ResponseJson<ApiResponse<StatusResponse>>, ApiError> {
+    let switched = deployment
+        .auth_context()
+        .switch_account(payload.user_id)
+        .await
+        .map_err(|e| {
+            tracing::error!(?e, "failed to switch account");
+            ApiError::Io(e)
+        })?;
+    if !switched {
+        return Err(ApiError::BadRequest(
+            "No stored credentials for this account".to_string(),
+        ));
+    }
+
+    let status = status_response(&deployment).await;
+    if status.logged_in {
+        deployment.trigger_pr_sync();
+        relay_registration::spawn_relay(&deployment).await;
+    } else {
+        relay_registration::stop_relay(&deployment).await;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
 /// Returns the current access token (auto-refreshes if needed)
 async fn get_token(
     State(deployment): State<DeploymentImpl>,
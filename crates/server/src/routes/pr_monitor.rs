@@ -0,0 +1,38 @@
+//! Operator controls for the background PR monitor.
+
+use axum::{
+    Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use services::services::pr_monitor::PrMonitorStatus;
+use utils::response::ApiResponse;
+
+use crate::DeploymentImpl;
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/pr-monitor", get(get_pr_monitor_status))
+        .route("/pr-monitor/pause", post(pause_pr_monitor))
+        .route("/pr-monitor/resume", post(resume_pr_monitor))
+}
+
+async fn get_pr_monitor_status(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<PrMonitorStatus>> {
+    ResponseJson(ApiResponse::success(deployment.pr_monitor().status()))
+}
+
+/// Stop polling git hosts for PR status until resumed.
+async fn pause_pr_monitor(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<PrMonitorStatus>> {
+    ResponseJson(ApiResponse::success(deployment.pr_monitor().pause()))
+}
+
+async fn resume_pr_monitor(
+    State(deployment): State<DeploymentImpl>,
+) -> ResponseJson<ApiResponse<PrMonitorStatus>> {
+    ResponseJson(ApiResponse::success(deployment.pr_monitor().resume()))
+}
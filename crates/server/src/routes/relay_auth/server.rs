@@ -4,9 +4,11 @@ use axum::{
     http::HeaderMap,
     routing::{delete, get, post},
 };
+use deployment::Deployment;
 use relay_types::{
     FinishSpake2EnrollmentRequest, FinishSpake2EnrollmentResponse, ListRelayPairedClientsResponse,
     RefreshRelaySigningSessionRequest, RefreshRelaySigningSessionResponse,
+    RelaySessionServerKeysResponse, RelaySignatureFailureCount, RelaySigningSessionStatsResponse,
     RemoveRelayPairedClientResponse, StartSpake2EnrollmentRequest, StartSpake2EnrollmentResponse,
 };
 use serde::Serialize;
@@ -47,6 +49,14 @@ pub fn router() -> Router<DeploymentImpl> {
             "/relay-auth/server/signing-session/refresh",
             post(refresh_relay_signing_session),
         )
+        .route(
+            "/relay-auth/server/signing-session/stats",
+            get(get_relay_signing_session_stats),
+        )
+        .route(
+            "/relay-auth/server/signing-session/{signing_session_id}/keys",
+            get(get_relay_session_server_keys),
+        )
 }
 
 async fn generate_enrollment_code(
@@ -125,3 +135,45 @@ async fn refresh_relay_signing_session(
 
     Ok(Json(ApiResponse::success(response)))
 }
+
+async fn get_relay_session_server_keys(
+    State(deployment): State<DeploymentImpl>,
+    Path(signing_session_id): Path<Uuid>,
+) -> Result<Json<ApiResponse<RelaySessionServerKeysResponse>>, ApiError> {
+    let response =
+        build_relay_pairing_server(&deployment).session_server_keys(signing_session_id)?;
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+async fn get_relay_signing_session_stats(
+    State(deployment): State<DeploymentImpl>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<RelaySigningSessionStatsResponse>>, ApiError> {
+    if is_relay_request(&headers) {
+        return Err(ApiError::Forbidden(
+            "Signing session stats cannot be fetched over relay.".to_string(),
+        ));
+    }
+
+    let stats = deployment
+        .relay_signing()
+        .relay_signing_session_stats()
+        .await;
+
+    Ok(Json(ApiResponse::success(
+        RelaySigningSessionStatsResponse {
+            active_sessions: stats.active_sessions as u32,
+            seen_nonces: stats.seen_nonces as u32,
+            failure_window_secs: stats.failure_window.as_secs() as u32,
+            failures: stats
+                .failures
+                .into_iter()
+                .map(|(reason, count)| RelaySignatureFailureCount {
+                    reason: reason.code().to_string(),
+                    count: count as u32,
+                })
+                .collect(),
+        },
+    )))
+}
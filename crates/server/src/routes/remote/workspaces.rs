@@ -1,7 +1,7 @@
-use api_types::Workspace;
+use api_types::{ListWorkspaceChangesQuery, Workspace, WorkspaceChangesResponse};
 use axum::{
     Router,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::Json as ResponseJson,
     routing::get,
 };
@@ -11,10 +11,23 @@ use uuid::Uuid;
 use crate::{DeploymentImpl, error::ApiError};
 
 pub(super) fn router() -> Router<DeploymentImpl> {
-    Router::new().route(
-        "/workspaces/by-local-id/{local_workspace_id}",
-        get(get_workspace_by_local_id),
-    )
+    Router::new()
+        .route("/workspaces/changes", get(list_workspace_changes))
+        .route(
+            "/workspaces/by-local-id/{local_workspace_id}",
+            get(get_workspace_by_local_id),
+        )
+}
+
+async fn list_workspace_changes(
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<ListWorkspaceChangesQuery>,
+) -> Result<ResponseJson<ApiResponse<WorkspaceChangesResponse>>, ApiError> {
+    let client = deployment.remote_client()?;
+    let changes = client
+        .list_workspace_changes(query.project_id, query.since)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(changes)))
 }
 
 async fn get_workspace_by_local_id(
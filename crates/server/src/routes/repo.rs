@@ -9,10 +9,20 @@ use axum::{
 };
 use db::models::repo::{Repo, SearchResult, UpdateRepo};
 use deployment::Deployment;
-use git::{GitBranch, GitRemote};
-use git_host::{GitHostError, GitHostProvider, GitHostService, ProviderKind, PullRequestDetail};
+use git::{GitBranch, GitRemote, GitServiceError, MergeMethod};
+use git_host::{
+    GitHostError, GitHostProvider, GitHostService, ProviderCapabilities, ProviderKind,
+    PullRequestDetail,
+};
 use serde::{Deserialize, Serialize};
-use services::services::file_search::SearchQuery;
+use services::services::{
+    approvals::DENIED_TOOL_NOTE_PLACEHOLDERS,
+    attempt_limiter::RepoConcurrencyStatus,
+    file_search::SearchQuery,
+    notification_template, pr_template,
+    repo_ecosystem::{DetectedEcosystem, detect_ecosystems},
+    template,
+};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -144,10 +154,114 @@ pub async fn update_repo(
     Path(repo_id): Path<Uuid>,
     ResponseJson(payload): ResponseJson<UpdateRepo>,
 ) -> Result<ResponseJson<ApiResponse<Repo>>, ApiError> {
+    if let Some(Some(template)) = &payload.pr_title_template {
+        pr_template::validate_title(template).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    if let Some(Some(template)) = &payload.pr_body_template {
+        pr_template::validate_body(template).map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    if let Some(Some(template)) = &payload.notification_title_template {
+        notification_template::validate_title(template)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    if let Some(Some(template)) = &payload.notification_body_template {
+        notification_template::validate_body(template)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+    if let Some(Some(note)) = &payload.denied_tool_note_template {
+        template::validate(note, &DENIED_TOOL_NOTE_PLACEHOLDERS)
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    }
+
+    // An empty remote name clears the setting, like `null`.
+    let remotes: Vec<&String> = [&payload.push_remote, &payload.pr_base_remote]
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|name| !name.trim().is_empty())
+        .collect();
+    if !remotes.is_empty() {
+        let repo = deployment
+            .repo()
+            .get_by_id(&deployment.db().pool, repo_id)
+            .await?;
+        for remote in remotes {
+            deployment
+                .git()
+                .find_remote(&repo.path, remote)
+                .map_err(|e| match e {
+                    GitServiceError::RemoteNotFound(name) => {
+                        ApiError::BadRequest(format!("Remote '{name}' does not exist in this repo"))
+                    }
+                    e => e.into(),
+                })?;
+        }
+    }
+
+    if let Some(Some(method)) = &payload.default_merge_method {
+        let repo = deployment
+            .repo()
+            .get_by_id(&deployment.db().pool, repo_id)
+            .await?;
+        validate_merge_method(&deployment, &repo, method)?;
+    }
+
     let repo = Repo::update(&deployment.db().pool, repo_id, &payload).await?;
     Ok(ResponseJson(ApiResponse::success(repo)))
 }
 
+/// Reject merge methods the repo's git host can't perform. Repos without a
+/// known host only merge locally, which supports every method.
+fn validate_merge_method(
+    deployment: &DeploymentImpl,
+    repo: &Repo,
+    method: &str,
+) -> Result<(), ApiError> {
+    let method: MergeMethod = method
+        .parse()
+        .map_err(|_| ApiError::BadRequest(format!("Unknown merge method '{method}'")))?;
+    let Ok(remote) = deployment.git().get_default_remote(&repo.path) else {
+        return Ok(());
+    };
+    let Ok(git_host) = GitHostService::from_url(&remote.url) else {
+        return Ok(());
+    };
+    if method == MergeMethod::Squash && !git_host.capabilities().squash_merge {
+        return Err(ApiError::BadRequest(format!(
+            "{} does not support squash merges",
+            git_host.provider_kind()
+        )));
+    }
+    Ok(())
+}
+
+pub async fn get_repo_concurrency(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<RepoConcurrencyStatus>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    let status = deployment
+        .attempt_limiter()
+        .status(repo.id, repo.max_concurrent_attempts);
+    Ok(ResponseJson(ApiResponse::success(status)))
+}
+
+pub async fn get_repo_ecosystems(
+    State(deployment): State<DeploymentImpl>,
+    Path(repo_id): Path<Uuid>,
+) -> Result<ResponseJson<ApiResponse<Vec<DetectedEcosystem>>>, ApiError> {
+    let repo = deployment
+        .repo()
+        .get_by_id(&deployment.db().pool, repo_id)
+        .await?;
+    Ok(ResponseJson(ApiResponse::success(detect_ecosystems(
+        &repo.path,
+    ))))
+}
+
 pub async fn open_repo_in_editor(
     State(deployment): State<DeploymentImpl>,
     Path(repo_id): Path<Uuid>,
@@ -263,7 +377,8 @@ pub async fn list_open_prs(
         None => deployment.git().get_default_remote(&repo.path)?,
     };
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    let generic_git_fallback = deployment.config().read().await.generic_git_fallback;
+    let git_host = match GitHostService::from_url_with_config(&remote.url, generic_git_fallback) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -334,6 +449,41 @@ pub async fn get_pr_info(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct ProviderCapabilitiesQuery {
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct ProviderCapabilitiesResponse {
+    pub provider: ProviderKind,
+    pub capabilities: ProviderCapabilities,
+}
+
+pub async fn get_provider_capabilities(
+    Query(query): Query<ProviderCapabilitiesQuery>,
+) -> Result<ResponseJson<ApiResponse<ProviderCapabilitiesResponse, ListPrsError>>, ApiError> {
+    let git_host = match GitHostService::from_url(&query.url) {
+        Ok(host) => host,
+        Err(GitHostError::UnsupportedProvider) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                ListPrsError::UnsupportedProvider,
+            )));
+        }
+        Err(e) => {
+            tracing::error!("Failed to create git host service: {}", e);
+            return Ok(ResponseJson(ApiResponse::error(&e.to_string())));
+        }
+    };
+
+    Ok(ResponseJson(ApiResponse::success(
+        ProviderCapabilitiesResponse {
+            provider: git_host.provider_kind(),
+            capabilities: git_host.capabilities(),
+        },
+    )))
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct DeleteRepoConflict {
     pub message: String,
@@ -378,7 +528,13 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/repos/{repo_id}/branches", get(get_repo_branches))
         .route("/repos/{repo_id}/remotes", get(get_repo_remotes))
         .route("/repos/{repo_id}/prs", get(list_open_prs))
+        .route("/repos/{repo_id}/concurrency", get(get_repo_concurrency))
+        .route("/repos/{repo_id}/ecosystems", get(get_repo_ecosystems))
         .route("/repos/pr-info", get(get_pr_info))
+        .route(
+            "/repos/provider-capabilities",
+            get(get_provider_capabilities),
+        )
         .route("/repos/{repo_id}/search", get(search_repo))
         .route("/repos/{repo_id}/open-editor", post(open_repo_in_editor))
 }
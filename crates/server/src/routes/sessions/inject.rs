@@ -0,0 +1,99 @@
+use axum::{Extension, Json, extract::State, response::Json as ResponseJson};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    scratch::DraftFollowUpData,
+    session::Session,
+};
+use deployment::Deployment;
+use executors::profile::ExecutorConfig;
+use serde::{Deserialize, Serialize};
+use services::services::{container::ContainerService, queued_message::QueuedMessage};
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Deserialize, TS)]
+pub struct InjectMessageRequest {
+    pub message: String,
+    /// Used when the running executor cannot take messages mid-run and the
+    /// message is queued as a follow-up instead.
+    pub executor_config: ExecutorConfig,
+}
+
+/// How an injected message was delivered.
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "delivery", rename_all = "snake_case")]
+#[ts(tag = "delivery", rename_all = "snake_case")]
+pub enum InjectMessageResponse {
+    /// Sent to the running agent's stdin immediately.
+    Injected { execution_process_id: Uuid },
+    /// Queued to run as a follow-up once the current execution finishes.
+    Queued { message: QueuedMessage },
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum InjectMessageError {
+    NoRunningAgent,
+}
+
+/// Nudge a running coding agent with an extra user message.
+pub async fn inject_message(
+    Extension(session): Extension<Session>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<InjectMessageRequest>,
+) -> Result<ResponseJson<ApiResponse<InjectMessageResponse, InjectMessageError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let Some(running) = ExecutionProcess::find_by_session_id(pool, session.id, false)
+        .await?
+        .into_iter()
+        .rev()
+        .find(|p| {
+            p.run_reason == ExecutionProcessRunReason::CodingAgent
+                && p.status == ExecutionProcessStatus::Running
+        })
+    else {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            InjectMessageError::NoRunningAgent,
+        )));
+    };
+
+    let response = if deployment
+        .container()
+        .inject_message(&running.id, payload.message.clone())
+        .await
+    {
+        InjectMessageResponse::Injected {
+            execution_process_id: running.id,
+        }
+    } else {
+        let message = deployment.queued_message_service().queue_message(
+            session.id,
+            DraftFollowUpData {
+                message: payload.message,
+                executor_config: payload.executor_config,
+            },
+        );
+        InjectMessageResponse::Queued { message }
+    };
+
+    deployment
+        .track_if_analytics_allowed(
+            "message_injected",
+            serde_json::json!({
+                "session_id": session.id.to_string(),
+                "workspace_id": session.workspace_id.to_string(),
+                "delivery": match response {
+                    InjectMessageResponse::Injected { .. } => "injected",
+                    InjectMessageResponse::Queued { .. } => "queued",
+                },
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(response)))
+}
@@ -1,3 +1,4 @@
+pub mod inject;
 pub mod queue;
 pub mod review;
 
@@ -203,6 +204,7 @@ pub async fn follow_up(
                 prompt,
                 executor_config: payload.executor_config.clone(),
                 working_dir,
+                preview: false,
             },
         )
     };
@@ -318,6 +320,7 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .route("/reset", post(reset_process))
         .route("/setup", post(run_setup_script))
         .route("/review", post(review::start_review))
+        .route("/inject", post(inject::inject_message))
         .layer(from_fn_with_state(
             deployment.clone(),
             load_session_middleware,
@@ -75,6 +75,7 @@ pub async fn update_workspace(
                 name.map(Some),
                 archived,
                 stats.as_ref(),
+                None,
             )
             .await;
         });
@@ -145,6 +146,7 @@ pub async fn delete_workspace(
     if rows_affected == 0 {
         return Err(ApiError::Database(SqlxError::RowNotFound));
     }
+    deployment.approvals().clear_workspace_denials(workspace_id);
 
     deployment
         .track_if_analytics_allowed(
@@ -1,37 +1,51 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, path::Path};
 
 use axum::{Json, extract::State, response::Json as ResponseJson};
 use db::models::{
+    repo::{Repo, RepoError},
     requests::{
         CreateAndStartWorkspaceRequest, CreateAndStartWorkspaceResponse, CreateWorkspaceApiRequest,
+        LinkedIssueInfo, WorkspaceRepoInput, WorkspaceStartMode,
     },
-    workspace::{CreateWorkspace, Workspace},
+    workspace::{CreateWorkspace, Workspace, WorkspaceError},
+    workspace_repo::{CreateWorkspaceRepo, WorkspaceRepo},
 };
 use deployment::Deployment;
-use services::services::container::ContainerService;
+use executors::env::validate_env_key;
+use git::GitService;
+use serde::{Deserialize, Serialize};
+use services::services::{
+    attempt_limiter::TaskAttemptUsage,
+    container::{ContainerError, ContainerService},
+    issue_prompt_template::{self, DEFAULT_ISSUE_PROMPT_TEMPLATE, IssuePromptContext},
+};
+use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
     error::ApiError,
-    routes::workspaces::attachments::{
-        ImportedIssueAttachment, import_issue_attachments_from_remote,
+    routes::workspaces::{
+        attachments::{ImportedIssueAttachment, import_issue_attachments_from_remote},
+        pr::cleanup_failed_pr_workspace,
     },
 };
 
+fn branch_label(name: Option<&str>) -> &str {
+    name.filter(|branch_label| !branch_label.is_empty())
+        .unwrap_or("workspace")
+}
+
 pub(crate) async fn create_workspace_record(
     deployment: &DeploymentImpl,
     name: Option<String>,
+    task_id: Option<Uuid>,
 ) -> Result<Workspace, ApiError> {
     let workspace_id = Uuid::new_v4();
-    let branch_label = name
-        .as_deref()
-        .filter(|branch_label| !branch_label.is_empty())
-        .unwrap_or("workspace");
     let git_branch_name = deployment
         .container()
-        .git_branch_from_workspace(&workspace_id, branch_label)
+        .git_branch_from_workspace(&workspace_id, branch_label(name.as_deref()))
         .await;
 
     let workspace = Workspace::create(
@@ -39,6 +53,7 @@ pub(crate) async fn create_workspace_record(
         &CreateWorkspace {
             branch: git_branch_name,
             name: name.filter(|workspace_name| !workspace_name.is_empty()),
+            task_id,
         },
         workspace_id,
     )
@@ -51,7 +66,7 @@ pub async fn create_workspace(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateWorkspaceApiRequest>,
 ) -> Result<ResponseJson<ApiResponse<Workspace>>, ApiError> {
-    let workspace = create_workspace_record(&deployment, payload.name).await?;
+    let workspace = create_workspace_record(&deployment, payload.name, None).await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -65,6 +80,75 @@ pub async fn create_workspace(
     Ok(ResponseJson(ApiResponse::success(workspace)))
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct PreviewBranchNameRequest {
+    pub name: Option<String>,
+    /// Repos to check for existing branches that would block the name.
+    #[serde(default)]
+    pub repo_ids: Vec<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PreviewBranchNameResponse {
+    /// Example branch name. The short id after the prefix is generated per
+    /// workspace, so the created branch differs only in that segment.
+    pub branch: String,
+    pub valid: bool,
+    pub conflicts: Vec<BranchNameConflict>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct BranchNameConflict {
+    pub repo_id: Uuid,
+    /// Existing branch that clashes, either the same name (locally or on a
+    /// remote) or a local branch named like the prefix, which git can't nest
+    /// branches under.
+    pub existing_branch: String,
+}
+
+pub async fn preview_branch_name(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<PreviewBranchNameRequest>,
+) -> Result<ResponseJson<ApiResponse<PreviewBranchNameResponse>>, ApiError> {
+    let branch = deployment
+        .container()
+        .git_branch_from_workspace(&Uuid::new_v4(), branch_label(payload.name.as_deref()))
+        .await;
+    let prefix = deployment.container().git_branch_prefix().await;
+
+    let mut conflicts = Vec::new();
+    for repo_id in payload.repo_ids {
+        let repo = Repo::find_by_id(&deployment.db().pool, repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+        for existing in deployment.git().get_all_branches(&repo.path)? {
+            let local_name = if existing.is_remote {
+                existing
+                    .name
+                    .split_once('/')
+                    .map_or(existing.name.as_str(), |(_, name)| name)
+            } else {
+                existing.name.as_str()
+            };
+            let blocks_prefix = !existing.is_remote && !prefix.is_empty() && local_name == prefix;
+            if local_name == branch || blocks_prefix {
+                conflicts.push(BranchNameConflict {
+                    repo_id,
+                    existing_branch: existing.name,
+                });
+            }
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(
+        PreviewBranchNameResponse {
+            valid: deployment.git().is_branch_name_valid(&branch),
+            branch,
+            conflicts,
+        },
+    )))
+}
+
 fn normalize_prompt(prompt: &str) -> Option<String> {
     let trimmed = prompt.trim();
     if trimmed.is_empty() {
@@ -74,6 +158,47 @@ fn normalize_prompt(prompt: &str) -> Option<String> {
     }
 }
 
+/// Render the configured issue prompt template for `linked_issue`, followed by
+/// any extra instructions from the request's own prompt.
+async fn compose_prompt_from_issue(
+    deployment: &DeploymentImpl,
+    linked_issue: Option<&LinkedIssueInfo>,
+    extra: &str,
+) -> Result<String, ApiError> {
+    let linked_issue = linked_issue.ok_or_else(|| {
+        ApiError::BadRequest("`prompt_from_issue` requires a `linked_issue`.".to_string())
+    })?;
+    let issue = deployment
+        .remote_client()?
+        .get_issue(linked_issue.issue_id)
+        .await?;
+
+    let template = deployment
+        .config()
+        .read()
+        .await
+        .issue_prompt_template
+        .clone()
+        .unwrap_or_else(|| DEFAULT_ISSUE_PROMPT_TEMPLATE.to_string());
+    let mut prompt = issue_prompt_template::render(
+        &template,
+        &IssuePromptContext {
+            title: &issue.title,
+            description: issue.description.as_deref().unwrap_or_default(),
+            simple_id: &issue.simple_id,
+        },
+    );
+
+    let extra = extra.trim();
+    if !extra.is_empty() {
+        if !prompt.is_empty() {
+            prompt.push_str("\n\n");
+        }
+        prompt.push_str(extra);
+    }
+    Ok(prompt)
+}
+
 fn escape_markdown_label(label: &str) -> String {
     let mut escaped = String::with_capacity(label.len());
     for ch in label.chars() {
@@ -209,6 +334,32 @@ fn rewrite_imported_issue_attachments_markdown(
     rewritten
 }
 
+/// Refuse another workspace for `task_id` once the lowest
+/// `max_attempts_per_task` among `repos` is used up.
+async fn ensure_task_attempts_left(
+    deployment: &DeploymentImpl,
+    task_id: Uuid,
+    repos: &[WorkspaceRepoInput],
+) -> Result<(), ApiError> {
+    let pool = &deployment.db().pool;
+    let mut limits = Vec::with_capacity(repos.len());
+    for input in repos {
+        limits.push(
+            Repo::find_by_id(pool, input.repo_id)
+                .await?
+                .ok_or(RepoError::NotFound)?,
+        );
+    }
+    let attempts = Workspace::count_for_task(pool, task_id).await?;
+    let usage = TaskAttemptUsage::new(attempts, &limits);
+    if usage.is_exhausted()
+        && let (Some(repo), Some(limit)) = (usage.limited_by, usage.max_attempts)
+    {
+        return Err(ContainerError::AttemptLimitReached { repo, limit }.into());
+    }
+    Ok(())
+}
+
 pub async fn create_and_start_workspace(
     State(deployment): State<DeploymentImpl>,
     Json(payload): Json<CreateAndStartWorkspaceRequest>,
@@ -219,9 +370,18 @@ pub async fn create_and_start_workspace(
         linked_issue,
         executor_config,
         prompt,
+        prompt_from_issue,
         attachment_ids,
+        env_overrides,
+        mode,
     } = payload;
 
+    let prompt = if prompt_from_issue {
+        compose_prompt_from_issue(&deployment, linked_issue.as_ref(), &prompt).await?
+    } else {
+        prompt
+    };
+
     let mut workspace_prompt = normalize_prompt(&prompt).ok_or_else(|| {
         ApiError::BadRequest(
             "A workspace prompt is required. Provide a non-empty `prompt`.".to_string(),
@@ -234,9 +394,20 @@ pub async fn create_and_start_workspace(
         ));
     }
 
+    for key in env_overrides.keys() {
+        validate_env_key(key).map_err(ApiError::BadRequest)?;
+    }
+
+    let task_id = linked_issue
+        .as_ref()
+        .map(|linked_issue| linked_issue.issue_id);
+    if let Some(task_id) = task_id {
+        ensure_task_attempts_left(&deployment, task_id, &repos).await?;
+    }
+
     let mut managed_workspace = deployment
         .workspace_manager()
-        .load_managed_workspace(create_workspace_record(&deployment, name).await?)
+        .load_managed_workspace(create_workspace_record(&deployment, name, task_id).await?)
         .await?;
 
     for repo in &repos {
@@ -295,9 +466,25 @@ pub async fn create_and_start_workspace(
     let workspace = managed_workspace.workspace.clone();
     tracing::info!("Created workspace {}", workspace.id);
 
+    if !env_overrides.is_empty() {
+        Workspace::set_env_overrides(&deployment.db().pool, workspace.id, &env_overrides).await?;
+        let mut keys: Vec<_> = env_overrides.keys().collect();
+        keys.sort();
+        tracing::info!(
+            "Workspace {} sets environment overrides for {:?}",
+            workspace.id,
+            keys
+        );
+    }
+
     let execution_process = deployment
         .container()
-        .start_workspace(&workspace, executor_config.clone(), workspace_prompt)
+        .start_workspace(
+            &workspace,
+            executor_config.clone(),
+            workspace_prompt,
+            mode == WorkspaceStartMode::Preview,
+        )
         .await?;
 
     deployment
@@ -307,6 +494,7 @@ pub async fn create_and_start_workspace(
                 "executor": &executor_config.executor,
                 "variant": &executor_config.variant,
                 "workspace_id": workspace.id.to_string(),
+                "mode": mode,
             }),
         )
         .await;
@@ -319,13 +507,167 @@ pub async fn create_and_start_workspace(
     )))
 }
 
+#[derive(Debug, Serialize, Deserialize, TS)]
+pub struct CreateWorkspaceFromBranchBody {
+    pub repo_id: Uuid,
+    /// Existing local branch to manage. No new branch is created.
+    pub branch: String,
+    /// Branch the work will be merged into. Defaults to the repo's default
+    /// target branch, then to the branch checked out in the main repository.
+    pub target_branch: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct CreateWorkspaceFromBranchResponse {
+    pub workspace: Workspace,
+    /// Merge-base of the branch with the target branch, recorded on the
+    /// workspace repo.
+    pub base_commit: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum CreateFromBranchError {
+    BranchNotFound,
+    BranchAlreadyManaged,
+    BranchCheckedOut,
+    NoMergeBase { target_branch: String },
+}
+
+/// Where `branch` forked from `target_branch`. `None` when they share no
+/// history, so the work could never be merged back.
+fn branch_merge_base(
+    git: &GitService,
+    repo_path: &Path,
+    target_branch: &str,
+    branch: &str,
+) -> Option<String> {
+    git.get_fork_point(repo_path, target_branch, branch)
+        .ok()
+        .filter(|sha| !sha.is_empty())
+}
+
+pub async fn create_workspace_from_branch(
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<CreateWorkspaceFromBranchBody>,
+) -> Result<
+    ResponseJson<ApiResponse<CreateWorkspaceFromBranchResponse, CreateFromBranchError>>,
+    ApiError,
+> {
+    let pool = &deployment.db().pool;
+    let git = deployment.git();
+
+    let repo = Repo::find_by_id(pool, payload.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    if !git.check_local_branch_exists(&repo.path, &payload.branch)? {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            CreateFromBranchError::BranchNotFound,
+        )));
+    }
+
+    if Workspace::branch_in_use(pool, repo.id, &payload.branch).await? {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            CreateFromBranchError::BranchAlreadyManaged,
+        )));
+    }
+
+    // Git refuses to check a branch out in two worktrees at once.
+    let current_branch = git.get_current_branch(&repo.path)?;
+    if current_branch == payload.branch {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            CreateFromBranchError::BranchCheckedOut,
+        )));
+    }
+
+    let target_branch = payload
+        .target_branch
+        .or_else(|| repo.default_target_branch.clone())
+        .unwrap_or(current_branch);
+
+    let Some(base_commit) = branch_merge_base(git, &repo.path, &target_branch, &payload.branch)
+    else {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            CreateFromBranchError::NoMergeBase { target_branch },
+        )));
+    };
+
+    let workspace = Workspace::create(
+        pool,
+        &CreateWorkspace {
+            branch: payload.branch.clone(),
+            name: payload
+                .name
+                .filter(|name| !name.is_empty())
+                .or_else(|| Some(payload.branch.clone())),
+            task_id: None,
+        },
+        Uuid::new_v4(),
+    )
+    .await?;
+
+    WorkspaceRepo::create_many(
+        pool,
+        workspace.id,
+        &[CreateWorkspaceRepo {
+            repo_id: repo.id,
+            target_branch: target_branch.clone(),
+        }],
+    )
+    .await?;
+    WorkspaceRepo::set_base_commit(pool, workspace.id, repo.id, &base_commit).await?;
+
+    // The branch already exists, so this only attaches a worktree to it.
+    if let Err(e) = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await
+    {
+        cleanup_failed_pr_workspace(pool, &workspace).await;
+        return Err(e.into());
+    }
+
+    deployment
+        .track_if_analytics_allowed(
+            "workspace_created_from_branch",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+            }),
+        )
+        .await;
+
+    tracing::info!(
+        "Created workspace {} from existing branch '{}' (base {})",
+        workspace.id,
+        payload.branch,
+        base_commit
+    );
+
+    let workspace = Workspace::find_by_id(pool, workspace.id)
+        .await?
+        .ok_or(WorkspaceError::WorkspaceNotFound)?;
+
+    Ok(ResponseJson(ApiResponse::success(
+        CreateWorkspaceFromBranchResponse {
+            workspace,
+            base_commit,
+        },
+    )))
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::Utc;
     use db::models::file::File;
+    use git::{GitCli, GitService};
     use uuid::Uuid;
 
-    use super::{ImportedIssueAttachment, rewrite_imported_issue_attachments_markdown};
+    use super::{
+        ImportedIssueAttachment, branch_merge_base, rewrite_imported_issue_attachments_markdown,
+    };
 
     fn imported_file(
         attachment_id: Uuid,
@@ -469,4 +811,35 @@ mod tests {
             "See [doc.pdf](.vibe-attachments/doc_file.pdf) and ![shot.png](.vibe-attachments/shot_file.png). https://example.com"
         );
     }
+
+    #[test]
+    fn branch_merge_base_is_where_the_branch_forked() {
+        let td = tempfile::TempDir::new().unwrap();
+        let repo = td.path();
+        let git = GitService::new();
+        git.initialize_repo_with_main_branch(repo).unwrap();
+        let cli = GitCli::new();
+        cli.git(repo, ["config", "user.name", "Test User"]).unwrap();
+        cli.git(repo, ["config", "user.email", "test@example.com"])
+            .unwrap();
+        let fork = git.get_head_info(repo).unwrap().oid;
+
+        cli.git(repo, ["checkout", "-b", "existing-work"]).unwrap();
+        std::fs::write(repo.join("work.txt"), "work\n").unwrap();
+        git.commit(repo, "work").unwrap();
+        cli.git(repo, ["checkout", "main"]).unwrap();
+        std::fs::write(repo.join("main.txt"), "main\n").unwrap();
+        git.commit(repo, "main moves on").unwrap();
+
+        assert_eq!(
+            branch_merge_base(&git, repo, "main", "existing-work"),
+            Some(fork)
+        );
+
+        cli.git(repo, ["checkout", "--orphan", "unrelated"])
+            .unwrap();
+        git.commit(repo, "unrelated").unwrap();
+        cli.git(repo, ["checkout", "main"]).unwrap();
+        assert_eq!(branch_merge_base(&git, repo, "main", "unrelated"), None);
+    }
 }
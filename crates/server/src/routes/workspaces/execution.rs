@@ -1,4 +1,9 @@
-use axum::{Extension, Router, extract::State, response::Json as ResponseJson, routing::post};
+use axum::{
+    Extension, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
 use db::models::{
     execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
     session::{CreateSession, Session},
@@ -11,7 +16,7 @@ use executors::actions::{
     script::{ScriptContext, ScriptRequest, ScriptRequestLanguage},
 };
 use serde::{Deserialize, Serialize};
-use services::services::container::ContainerService;
+use services::services::{attempt_limiter::TaskAttemptUsage, container::ContainerService};
 use ts_rs::TS;
 use utils::response::ApiResponse;
 use uuid::Uuid;
@@ -24,14 +29,35 @@ use crate::{DeploymentImpl, error::ApiError};
 pub enum RunScriptError {
     NoScriptConfigured,
     ProcessAlreadyRunning,
+    NoFailedSetup,
 }
 
 pub fn router() -> Router<DeploymentImpl> {
     Router::new()
         .route("/dev-server/start", post(start_dev_server))
         .route("/cleanup", post(run_cleanup_script))
+        .route("/rerun-setup", post(rerun_failed_setup))
         .route("/archive", post(run_archive_script))
         .route("/stop", post(stop_workspace_execution))
+        .route("/attempts", get(get_attempt_usage))
+}
+
+/// How many more workspaces the workspace's task may start under its repos'
+/// caps. Workspaces not started for a task are never capped.
+pub async fn get_attempt_usage(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<TaskAttemptUsage>>, ApiError> {
+    let pool = &deployment.db().pool;
+    let usage = match workspace.task_id {
+        Some(task_id) => {
+            let repos = WorkspaceRepo::find_repos_for_workspace(pool, workspace.id).await?;
+            let attempts = Workspace::count_for_task(pool, task_id).await?;
+            TaskAttemptUsage::new(attempts, &repos)
+        }
+        None => TaskAttemptUsage::new(0, &[]),
+    };
+    Ok(ResponseJson(ApiResponse::success(usage)))
 }
 
 #[axum::debug_handler]
@@ -136,6 +162,72 @@ pub async fn start_dev_server(
     Ok(ResponseJson(ApiResponse::success(execution_processes)))
 }
 
+/// Re-run the workspace's latest setup script if it failed, in the existing
+/// worktree. The stored action still chains into the remaining setup steps
+/// and the coding agent, so a successful run carries on where it stopped.
+pub async fn rerun_failed_setup(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<ExecutionProcess, RunScriptError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(pool, workspace.id)
+        .await?
+    {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::ProcessAlreadyRunning,
+        )));
+    }
+
+    let failed_setup = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+        pool,
+        workspace.id,
+        &ExecutionProcessRunReason::SetupScript,
+    )
+    .await?
+    .filter(|process| process.status == ExecutionProcessStatus::Failed);
+    let Some(failed_setup) = failed_setup else {
+        return Ok(ResponseJson(ApiResponse::error_with_data(
+            RunScriptError::NoFailedSetup,
+        )));
+    };
+
+    let executor_action = failed_setup
+        .executor_action()
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?
+        .clone();
+    let session = Session::find_by_id(pool, failed_setup.session_id)
+        .await?
+        .ok_or_else(|| ApiError::BadRequest("Setup script session not found".to_string()))?;
+
+    deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+
+    let execution_process = deployment
+        .container()
+        .start_execution(
+            &workspace,
+            &session,
+            &executor_action,
+            &ExecutionProcessRunReason::SetupScript,
+        )
+        .await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "setup_script_rerun",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "failed_execution_process_id": failed_setup.id.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(execution_process)))
+}
+
 pub async fn stop_workspace_execution(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
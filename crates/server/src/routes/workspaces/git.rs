@@ -11,12 +11,13 @@ use axum::{
 };
 use db::models::{
     merge::{Merge, MergeStatus, PrMerge, PullRequestInfo},
+    merge_operation::{MergeOperation, MergeOperationKind},
     repo::{Repo, RepoError},
     workspace::Workspace,
     workspace_repo::WorkspaceRepo,
 };
 use deployment::Deployment;
-use git::{ConflictOp, GitCliError, GitServiceError};
+use git::{ConflictOp, ConflictedFile, GitCliError, GitServiceError, MergeMethod};
 use serde::{Deserialize, Serialize};
 use services::services::{container::ContainerService, diff_stream, remote_sync};
 use ts_rs::TS;
@@ -51,14 +52,42 @@ pub enum GitOperationError {
         message: String,
         op: ConflictOp,
         conflicted_files: Vec<String>,
+        /// Per-file detail for `conflicted_files`, in the same order.
+        conflicts: Vec<ConflictedFile>,
         target_branch: String,
     },
     RebaseInProgress,
 }
 
+impl GitOperationError {
+    fn merge_conflicts(
+        message: String,
+        op: ConflictOp,
+        conflicts: Vec<ConflictedFile>,
+        target_branch: String,
+    ) -> Self {
+        Self::MergeConflicts {
+            message,
+            op,
+            conflicted_files: conflicts.iter().map(|c| c.path.clone()).collect(),
+            conflicts,
+            target_branch,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, TS)]
 pub struct MergeWorkspaceRequest {
     pub repo_id: Uuid,
+    /// Overrides the repo's default merge method for this merge.
+    #[serde(default)]
+    #[ts(optional)]
+    pub merge_method: Option<MergeMethod>,
+    /// Leave the workspace active after merging so the agent can keep working
+    /// and merge again later. Otherwise unpinned workspaces are archived.
+    #[serde(default)]
+    #[ts(optional)]
+    pub keep_active: Option<bool>,
 }
 
 #[derive(Debug, Deserialize, Serialize, TS)]
@@ -89,6 +118,9 @@ pub struct BranchStatus {
     pub conflict_op: Option<ConflictOp>,
     pub conflicted_files: Vec<String>,
     pub is_target_remote: bool,
+    /// Set when a merge or rebase was cut short by a server restart and
+    /// could not be rolled back automatically.
+    pub interrupted_operation: Option<MergeOperationKind>,
 }
 
 #[derive(Debug, Clone, Serialize, TS)]
@@ -139,6 +171,7 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/status", get(get_workspace_branch_status))
         .route("/diff/ws", get(stream_diff_ws))
         .route("/merge", post(merge_workspace))
+        .route("/merges", get(get_workspace_merges))
         .route("/push", post(push_workspace_branch))
         .route("/push/force", post(force_push_workspace_branch))
         .route("/rebase", post(rebase_workspace))
@@ -175,12 +208,22 @@ pub async fn stream_diff_ws(
     stream_workspace_diff_ws(ws, query, workspace, deployment).await
 }
 
+/// Every merge of the workspace, newest first: direct merges with their
+/// commit, and pull requests with their status.
+pub async fn get_workspace_merges(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<Merge>>>, ApiError> {
+    let merges = Merge::find_by_workspace_id(&deployment.db().pool, workspace.id).await?;
+    Ok(ResponseJson(ApiResponse::success(merges)))
+}
+
 #[axum::debug_handler]
 pub async fn merge_workspace(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
     Json(request): Json<MergeWorkspaceRequest>,
-) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<(), GitOperationError>>, ApiError> {
     let pool = &deployment.db().pool;
 
     let workspace_repo =
@@ -212,24 +255,75 @@ pub async fn merge_workspace(
         ));
     }
 
+    // Merging resets the branch to the merge commit, so anything ahead of the
+    // target was added since the last merge.
+    let (ahead, _) = deployment.git().get_branch_status(
+        &repo.path,
+        &workspace.branch,
+        &workspace_repo.target_branch,
+    )?;
+    if ahead == 0 && merges.iter().any(|m| matches!(m, Merge::Direct(_))) {
+        return Err(ApiError::BadRequest(
+            "Nothing to merge: no commits were added since the last merge.".to_string(),
+        ));
+    }
+
     let container_ref = deployment
         .container()
         .ensure_container_exists(&workspace)
         .await?;
     let workspace_path = Path::new(&container_ref);
-    let worktree_path = workspace_path.join(repo.name);
+    let worktree_path = workspace_path.join(&repo.name);
 
     let workspace_label = workspace.name.as_deref().unwrap_or(&workspace.branch);
     let vk_id = resolve_vibe_kanban_identifier(&deployment, workspace.id).await;
     let commit_message = format!("{} (vibe-kanban {})", workspace_label, vk_id);
 
-    let merge_commit_id = deployment.git().merge_changes(
+    let base_head = deployment
+        .git()
+        .get_branch_oid(&repo.path, &workspace_repo.target_branch)?;
+    let operation = MergeOperation::start(
+        pool,
+        workspace.id,
+        workspace_repo.repo_id,
+        MergeOperationKind::Merge,
+        &base_head,
+        &workspace_repo.target_branch,
+    )
+    .await?;
+
+    let merge_method = match request.merge_method {
+        Some(method) => method,
+        None => repo
+            .default_merge_method
+            .as_deref()
+            .and_then(|method| method.parse().ok())
+            .unwrap_or_default(),
+    };
+    let merge_result = deployment.git().merge_changes_with_method(
         &repo.path,
         &worktree_path,
         &workspace.branch,
         &workspace_repo.target_branch,
         &commit_message,
-    )?;
+        merge_method,
+    );
+    MergeOperation::finish(pool, operation.id).await?;
+    let merge_commit_id = match merge_result {
+        Ok(sha) => sha,
+        Err(GitServiceError::MergeConflicts { message, conflicts }) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                GitOperationError::merge_conflicts(
+                    message,
+                    ConflictOp::Merge,
+                    conflicts,
+                    workspace_repo.target_branch.clone(),
+                ),
+            )));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    MergeOperation::clear_interrupted(pool, workspace.id, workspace_repo.repo_id).await?;
 
     Merge::create_direct(
         pool,
@@ -248,16 +342,32 @@ pub async fn merge_workspace(
     }
 
     if !workspace.pinned
+        && !request.keep_active.unwrap_or(false)
         && let Err(e) = deployment.container().archive_workspace(workspace.id).await
     {
         tracing::error!("Failed to archive workspace {}: {}", workspace.id, e);
     }
 
+    // Runs after archiving so it doesn't block the archive script. A failure
+    // here leaves the merge in place.
+    if let Err(e) = deployment
+        .container()
+        .try_run_post_merge_script(workspace.id, &repo)
+        .await
+    {
+        tracing::warn!(
+            "Failed to run post-merge script for workspace {}: {}",
+            workspace.id,
+            e
+        );
+    }
+
     deployment
         .track_if_analytics_allowed(
             "task_attempt_merged",
             serde_json::json!({
                 "workspace_id": workspace.id.to_string(),
+                "merge_method": merge_method.as_str(),
             }),
         )
         .await;
@@ -288,10 +398,12 @@ pub async fn push_workspace_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    match deployment
-        .git()
-        .push_to_remote(&worktree_path, &workspace.branch, false)
-    {
+    match deployment.git().push_to_remote(
+        &worktree_path,
+        &workspace.branch,
+        repo.push_remote.as_deref(),
+        false,
+    ) {
         Ok(_) => {
             if let Ok(client) = deployment.remote_client() {
                 let pool = deployment.db().pool.clone();
@@ -306,6 +418,7 @@ pub async fn push_workspace_branch(
                         None,
                         None,
                         stats.as_ref(),
+                        None,
                     )
                     .await;
                 });
@@ -342,9 +455,12 @@ pub async fn force_push_workspace_branch(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
-    deployment
-        .git()
-        .push_to_remote(&worktree_path, &workspace.branch, true)?;
+    deployment.git().push_to_remote(
+        &worktree_path,
+        &workspace.branch,
+        repo.push_remote.as_deref(),
+        true,
+    )?;
 
     if let Ok(client) = deployment.remote_client() {
         let pool = deployment.db().pool.clone();
@@ -353,7 +469,8 @@ pub async fn force_push_workspace_branch(
         ws.container_ref = Some(container_ref.clone());
         tokio::spawn(async move {
             let stats = diff_stream::compute_diff_stats(&pool, &git, &ws).await;
-            remote_sync::sync_workspace_to_remote(&client, ws.id, None, None, stats.as_ref()).await;
+            remote_sync::sync_workspace_to_remote(&client, ws.id, None, None, stats.as_ref(), None)
+                .await;
         });
     }
 
@@ -392,6 +509,13 @@ pub async fn get_workspace_branch_status(
                 acc
             });
 
+    let interrupted_by_repo: HashMap<Uuid, MergeOperationKind> =
+        MergeOperation::find_interrupted_by_workspace_id(pool, workspace.id)
+            .await?
+            .into_iter()
+            .map(|op| (op.repo_id, op.operation))
+            .collect();
+
     let mut results = Vec::with_capacity(repositories.len());
 
     for repo in repositories {
@@ -494,6 +618,7 @@ pub async fn get_workspace_branch_status(
                 conflict_op,
                 conflicted_files,
                 is_target_remote,
+                interrupted_operation: interrupted_by_repo.get(&repo.id).copied(),
             },
         });
     }
@@ -746,6 +871,17 @@ pub async fn rebase_workspace(
     let workspace_path = Path::new(&container_ref);
     let worktree_path = workspace_path.join(&repo.name);
 
+    let base_head = deployment.git().get_head_info(&worktree_path)?.oid;
+    let operation = MergeOperation::start(
+        pool,
+        workspace.id,
+        repo.id,
+        MergeOperationKind::Rebase,
+        &base_head,
+        &new_base_branch,
+    )
+    .await?;
+
     let result = deployment.git().rebase_branch(
         &repo.path,
         &worktree_path,
@@ -753,19 +889,17 @@ pub async fn rebase_workspace(
         &old_base_branch,
         &workspace.branch.clone(),
     );
+    MergeOperation::finish(pool, operation.id).await?;
     if let Err(e) = result {
         return match e {
-            GitServiceError::MergeConflicts {
-                message,
-                conflicted_files,
-            } => Ok(ResponseJson(
+            GitServiceError::MergeConflicts { message, conflicts } => Ok(ResponseJson(
                 ApiResponse::<(), GitOperationError>::error_with_data(
-                    GitOperationError::MergeConflicts {
+                    GitOperationError::merge_conflicts(
                         message,
-                        op: ConflictOp::Rebase,
-                        conflicted_files,
-                        target_branch: new_base_branch.clone(),
-                    },
+                        ConflictOp::Rebase,
+                        conflicts,
+                        new_base_branch.clone(),
+                    ),
                 ),
             )),
             GitServiceError::RebaseInProgress => Ok(ResponseJson(ApiResponse::<
@@ -777,6 +911,7 @@ pub async fn rebase_workspace(
             other => Err(ApiError::GitService(other)),
         };
     }
+    MergeOperation::clear_interrupted(pool, workspace.id, repo.id).await?;
 
     deployment
         .track_if_analytics_allowed(
@@ -811,6 +946,7 @@ pub async fn abort_workspace_conflicts(
     let worktree_path = workspace_path.join(&repo.name);
 
     deployment.git().abort_conflicts(&worktree_path)?;
+    MergeOperation::clear_interrupted(pool, workspace.id, repo.id).await?;
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
@@ -835,6 +971,7 @@ pub async fn continue_workspace_rebase(
     let worktree_path = workspace_path.join(&repo.name);
 
     deployment.git().continue_rebase(&worktree_path)?;
+    MergeOperation::clear_interrupted(pool, workspace.id, repo.id).await?;
 
     Ok(ResponseJson(ApiResponse::success(())))
 }
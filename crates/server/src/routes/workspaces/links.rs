@@ -6,7 +6,10 @@ use axum::{
     response::Json as ResponseJson,
     routing::{delete, post},
 };
-use db::models::{merge::MergeStatus, pull_request::PullRequest, workspace::Workspace};
+use db::models::{
+    execution_process::ExecutionProcess, merge::MergeStatus, pull_request::PullRequest,
+    workspace::Workspace,
+};
 use deployment::Deployment;
 use serde::Deserialize;
 use services::services::{diff_stream, remote_client::RemoteClientError, remote_sync};
@@ -30,6 +33,10 @@ pub async fn link_workspace(
 
     let stats =
         diff_stream::compute_diff_stats(&deployment.db().pool, deployment.git(), &workspace).await;
+    let time_spent_ms =
+        ExecutionProcess::time_spent_for_workspace(&deployment.db().pool, workspace.id)
+            .await
+            .ok();
 
     client
         .create_workspace(CreateWorkspaceRequest {
@@ -41,6 +48,7 @@ pub async fn link_workspace(
             files_changed: stats.as_ref().map(|s| s.files_changed as i32),
             lines_added: stats.as_ref().map(|s| s.lines_added as i32),
             lines_removed: stats.as_ref().map(|s| s.lines_removed as i32),
+            time_spent_ms,
         })
         .await?;
 
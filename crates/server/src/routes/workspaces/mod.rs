@@ -8,8 +8,11 @@ pub mod gh_cli_setup;
 pub mod git;
 pub mod integration;
 pub mod links;
+pub mod plan;
 pub mod pr;
+pub mod proposed_changes;
 pub mod repos;
+pub mod snapshots;
 pub mod streams;
 pub mod workspace_summary;
 
@@ -35,7 +38,13 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         .nest("/execution", execution::router())
         .nest("/integration", integration::router())
         .nest("/repos", repos::router())
+        .nest("/plan", plan::router())
+        .route(
+            "/proposed-changes",
+            get(proposed_changes::get_proposed_changes),
+        )
         .nest("/pull-requests", pr::router())
+        .nest("/snapshots", snapshots::router())
         .layer(from_fn_with_state(
             deployment.clone(),
             load_workspace_middleware,
@@ -48,6 +57,8 @@ pub fn router(deployment: &DeploymentImpl) -> Router<DeploymentImpl> {
         )
         .route("/start", post(create::create_and_start_workspace))
         .route("/from-pr", post(pr::create_workspace_from_pr))
+        .route("/from-branch", post(create::create_workspace_from_branch))
+        .route("/preview-branch-name", post(create::preview_branch_name))
         .route("/streams/ws", get(streams::stream_workspaces_ws))
         .route(
             "/summaries",
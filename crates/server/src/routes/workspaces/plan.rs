@@ -0,0 +1,114 @@
+//! The latest plan an agent proposed in a workspace, and explicit
+//! approve/reject for it while the agent is waiting.
+
+use axum::{
+    Extension, Router,
+    extract::State,
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::workspace::Workspace;
+use deployment::Deployment;
+use executors::logs::ToolStatus;
+use serde::Deserialize;
+use services::services::agent_plan::{self, AgentPlan};
+use ts_rs::TS;
+use utils::{
+    approvals::{ApprovalOutcome, ApprovalResponse},
+    response::ApiResponse,
+};
+
+use crate::{DeploymentImpl, error::ApiError};
+
+/// Sent to the agent when a plan is rejected without feedback.
+const DEFAULT_PLAN_REJECTION: &str =
+    "The user rejected this plan. Stay in plan mode and propose a revised plan.";
+
+#[derive(Debug, Deserialize, TS)]
+pub struct RespondToPlanRequest {
+    pub approved: bool,
+    /// What to change, sent to the agent when the plan is rejected.
+    #[serde(default)]
+    #[ts(optional)]
+    pub feedback: Option<String>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(get_latest_plan))
+        .route("/respond", post(respond_to_plan))
+}
+
+pub async fn get_latest_plan(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<AgentPlan>>>, ApiError> {
+    let plan = agent_plan::latest_workspace_plan(
+        deployment.container(),
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(plan)))
+}
+
+/// Answer the approval the agent is waiting on for its latest plan.
+pub async fn respond_to_plan(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    ResponseJson(payload): ResponseJson<RespondToPlanRequest>,
+) -> Result<ResponseJson<ApiResponse<ApprovalOutcome>>, ApiError> {
+    let plan = agent_plan::latest_workspace_plan(
+        deployment.container(),
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?;
+    let Some(AgentPlan {
+        execution_process_id,
+        status: ToolStatus::PendingApproval { approval_id },
+        ..
+    }) = plan
+    else {
+        return Err(ApiError::Conflict(
+            "The agent isn't waiting for a plan approval".to_string(),
+        ));
+    };
+
+    let status = if payload.approved {
+        ApprovalOutcome::Approved
+    } else {
+        let feedback = payload
+            .feedback
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .unwrap_or_else(|| DEFAULT_PLAN_REJECTION.to_string());
+        ApprovalOutcome::Denied {
+            reason: Some(feedback),
+        }
+    };
+
+    let (outcome, _) = deployment
+        .approvals()
+        .respond(
+            &approval_id,
+            ApprovalResponse {
+                execution_process_id,
+                status,
+            },
+        )
+        .await
+        .map_err(|e| ApiError::Conflict(e.to_string()))?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "plan_responded",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "approved": payload.approved,
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(outcome)))
+}
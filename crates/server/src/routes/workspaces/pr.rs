@@ -22,14 +22,18 @@ use executors::actions::{
     ExecutorAction, ExecutorActionType, coding_agent_follow_up::CodingAgentFollowUpRequest,
     coding_agent_initial::CodingAgentInitialRequest,
 };
-use git::{GitCliError, GitRemote, GitServiceError};
+use git::{GitCliError, GitRemote, GitService, GitServiceError};
 use git_host::{
-    CreatePrRequest, GitHostError, GitHostProvider, GitHostService, ProviderKind, UnifiedPrComment,
-    github::GhCli,
+    CreatePrRequest, GitHostError, GitHostProvider, GitHostService, ProviderKind,
+    PullRequestDetail, UnifiedPrComment, github::GhCli,
 };
 use serde::{Deserialize, Serialize};
 use services::services::{
-    config::DEFAULT_PR_DESCRIPTION_PROMPT, container::ContainerService, remote_sync,
+    config::DEFAULT_PR_DESCRIPTION_PROMPT,
+    container::ContainerService,
+    pr_description,
+    pr_template::{self, DEFAULT_PR_BODY_TEMPLATE, DEFAULT_PR_TITLE_TEMPLATE, PrTemplateContext},
+    remote_sync,
 };
 use ts_rs::TS;
 use utils::response::ApiResponse;
@@ -47,6 +51,9 @@ pub struct CreatePrApiRequest {
     pub repo_id: Uuid,
     #[serde(default)]
     pub auto_generate_description: bool,
+    /// Added to the repo's default PR labels.
+    #[serde(default)]
+    pub labels: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, TS)]
@@ -61,6 +68,21 @@ pub enum PrError {
     UnsupportedProvider,
 }
 
+/// What creating a pull request did.
+#[derive(Debug, Serialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum CreatePrResponse {
+    PrCreated {
+        url: String,
+    },
+    /// The remote has no pull request API, so pushing the branch was all
+    /// there was to do.
+    BranchPushed {
+        branch: String,
+    },
+}
+
 #[derive(Debug, Serialize, TS)]
 pub struct AttachPrResponse {
     pub pr_attached: bool,
@@ -93,6 +115,94 @@ pub struct GetPrCommentsQuery {
     pub repo_id: Uuid,
 }
 
+#[derive(Debug, Deserialize, TS)]
+pub struct UpdatePrBaseRequest {
+    pub repo_id: Uuid,
+    pub new_base: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+#[ts(tag = "type", rename_all = "snake_case")]
+pub enum UpdatePrBaseError {
+    NoPrAttached,
+    CliNotInstalled { provider: ProviderKind },
+    CliNotLoggedIn { provider: ProviderKind },
+    UnsupportedByProvider { provider: ProviderKind },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PrDescriptionQuery {
+    /// Render this repo's PR templates instead of the defaults.
+    pub repo_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize, TS)]
+pub struct PrDescriptionResponse {
+    pub title: String,
+    pub body: String,
+}
+
+/// The remote the repo's PRs are opened against: the configured PR base
+/// remote, else the remote `target_branch` tracks, else the default remote.
+fn pr_base_remote(
+    git: &GitService,
+    repo: &Repo,
+    target_branch: &str,
+) -> Result<GitRemote, GitServiceError> {
+    match repo.pr_base_remote.as_deref() {
+        Some(name) => git.find_remote(&repo.path, name),
+        None => git.resolve_remote_for_branch(&repo.path, target_branch),
+    }
+}
+
+/// Render PR title and body templates for the workspace, falling back to the
+/// defaults for any that are unset. The conversation summary is only built
+/// when a template uses it.
+async fn render_pr_templates(
+    deployment: &DeploymentImpl,
+    workspace: &Workspace,
+    title_template: Option<&str>,
+    body_template: Option<&str>,
+) -> PrDescriptionResponse {
+    let title_template = title_template.unwrap_or(DEFAULT_PR_TITLE_TEMPLATE);
+    let body_template = body_template.unwrap_or(DEFAULT_PR_BODY_TEMPLATE);
+
+    let summary =
+        if pr_template::uses_summary(title_template) || pr_template::uses_summary(body_template) {
+            match pr_description::load_workspace_entries(
+                deployment.container(),
+                &deployment.db().pool,
+                workspace.id,
+            )
+            .await
+            {
+                Ok(entries) => pr_description::build_pr_description(&entries),
+                Err(e) => {
+                    tracing::warn!(
+                        "Failed to generate PR description for workspace {}: {}",
+                        workspace.id,
+                        e
+                    );
+                    String::new()
+                }
+            }
+        } else {
+            String::new()
+        };
+
+    let ctx = PrTemplateContext {
+        task_title: workspace.name.as_deref().unwrap_or(&workspace.branch),
+        branch: &workspace.branch,
+        summary: &summary,
+        attempt_id: workspace.id,
+    };
+    PrDescriptionResponse {
+        title: pr_template::render_title(title_template, &ctx),
+        body: pr_template::render(body_template, &ctx),
+    }
+}
+
 async fn trigger_pr_description_follow_up(
     deployment: &DeploymentImpl,
     workspace: &Workspace,
@@ -167,6 +277,7 @@ async fn trigger_pr_description_follow_up(
             prompt,
             executor_config: executors::profile::ExecutorConfig::from(executor_profile_id.clone()),
             working_dir,
+            preview: false,
         })
     };
 
@@ -189,7 +300,7 @@ pub async fn create_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
     Json(request): Json<CreatePrApiRequest>,
-) -> Result<ResponseJson<ApiResponse<String, PrError>>, ApiError> {
+) -> Result<ResponseJson<ApiResponse<CreatePrResponse, PrError>>, ApiError> {
     let pool = &deployment.db().pool;
 
     let workspace_repo =
@@ -216,20 +327,22 @@ pub async fn create_pr(
     let worktree_path = workspace_path.join(&repo.name);
 
     let git = deployment.git();
-    let push_remote = git.resolve_remote_for_branch(&repo_path, &workspace.branch)?;
-
-    // Try to get the remote from the branch name (works for remote-tracking branches like "upstream/main").
-    // Fall back to push_remote if the branch doesn't exist locally or isn't a remote-tracking branch.
-    let (target_remote, base_branch) =
-        match git.get_remote_from_branch_name(&repo_path, &target_branch) {
-            Ok(remote) => {
-                let branch = target_branch
-                    .strip_prefix(&format!("{}/", remote.name))
-                    .unwrap_or(&target_branch);
-                (remote, branch.to_string())
-            }
-            Err(_) => (push_remote.clone(), target_branch.clone()),
-        };
+    // Fork workflows push to one remote and open the PR against another.
+    // Configured remotes must exist before anything is pushed.
+    let push_remote = match repo.push_remote.as_deref() {
+        Some(name) => git.find_remote(&repo_path, name)?,
+        None => git.resolve_remote_for_branch(&repo_path, &workspace.branch)?,
+    };
+
+    // Remote-tracking target branches like "upstream/main" name the base branch without the remote prefix.
+    let base_branch = git
+        .get_remote_from_branch_name(&repo_path, &target_branch)
+        .ok()
+        .as_ref()
+        .and_then(|remote| target_branch.strip_prefix(&format!("{}/", remote.name)))
+        .unwrap_or(&target_branch)
+        .to_string();
+    let target_remote = pr_base_remote(git, &repo, &target_branch)?;
 
     match git.check_remote_branch_exists(&repo_path, &target_remote.url, &base_branch) {
         Ok(false) => {
@@ -253,7 +366,12 @@ pub async fn create_pr(
         Ok(true) => {}
     }
 
-    if let Err(e) = git.push_to_remote(&worktree_path, &workspace.branch, false) {
+    if let Err(e) = git.push_to_remote(
+        &worktree_path,
+        &workspace.branch,
+        repo.push_remote.as_deref(),
+        false,
+    ) {
         tracing::error!("Failed to push branch to remote: {}", e);
         match e {
             GitServiceError::GitCLI(GitCliError::AuthFailed(_)) => {
@@ -269,33 +387,78 @@ pub async fn create_pr(
             _ => return Err(ApiError::GitService(e)),
         }
     }
+    WorkspaceRepo::mark_branch_pushed(pool, workspace.id, workspace_repo.repo_id).await?;
 
-    let git_host = match GitHostService::from_url(&target_remote.url) {
-        Ok(host) => host,
-        Err(GitHostError::UnsupportedProvider) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::UnsupportedProvider,
-            )));
-        }
-        Err(GitHostError::CliNotInstalled { provider }) => {
-            return Ok(ResponseJson(ApiResponse::error_with_data(
-                PrError::CliNotInstalled { provider },
-            )));
-        }
-        Err(e) => return Err(ApiError::GitHost(e)),
-    };
+    let generic_git_fallback = deployment.config().read().await.generic_git_fallback;
+    let git_host =
+        match GitHostService::from_url_with_config(&target_remote.url, generic_git_fallback) {
+            Ok(host) => host,
+            Err(GitHostError::UnsupportedProvider) => {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    PrError::UnsupportedProvider,
+                )));
+            }
+            Err(GitHostError::CliNotInstalled { provider }) => {
+                return Ok(ResponseJson(ApiResponse::error_with_data(
+                    PrError::CliNotInstalled { provider },
+                )));
+            }
+            Err(e) => return Err(ApiError::GitHost(e)),
+        };
+
+    // Stopping the workspace aborts PR creation instead of letting it retry
+    let git_host = git_host.with_cancellation(
+        deployment
+            .container()
+            .git_host_cancellation(workspace.id)
+            .await,
+    );
 
     let provider = git_host.provider_kind();
 
+    // Plain git remotes have nothing to open a PR against; the push is the result
+    if provider == ProviderKind::Generic {
+        return Ok(ResponseJson(ApiResponse::success(
+            CreatePrResponse::BranchPushed {
+                branch: workspace.branch.clone(),
+            },
+        )));
+    }
+
+    // Whatever the request leaves out comes from the repo's templates
+    let title_missing = request.title.trim().is_empty();
+    let (title, body) = if title_missing || request.body.is_none() {
+        let rendered = render_pr_templates(
+            &deployment,
+            &workspace,
+            repo.pr_title_template.as_deref(),
+            repo.pr_body_template.as_deref(),
+        )
+        .await;
+        let title = if title_missing {
+            rendered.title
+        } else {
+            request.title.clone()
+        };
+        let body = request
+            .body
+            .clone()
+            .or_else(|| Some(rendered.body).filter(|body| !body.trim().is_empty()));
+        (title, body)
+    } else {
+        (request.title.clone(), request.body.clone())
+    };
+
     // Create the PR
     let pr_request = CreatePrRequest {
-        title: request.title.clone(),
-        body: request.body.clone(),
+        title,
+        body,
         head_branch: workspace.branch.clone(),
         base_branch: base_branch.clone(),
         draft: request.draft,
         head_repo_url: Some(push_remote.url.clone()),
     };
+    let labels = repo.pr_labels_with(&request.labels);
 
     match git_host
         .create_pr(&repo_path, &target_remote.url, &pr_request)
@@ -316,6 +479,41 @@ pub async fn create_pr(
                 tracing::error!("Failed to create local PR record: {}", e);
             }
 
+            // Label and reviewer problems shouldn't fail an otherwise successful PR
+            if !labels.is_empty() {
+                match git_host.add_labels(&pr_info.url, &labels).await {
+                    Ok(rejected) if !rejected.is_empty() => {
+                        tracing::warn!("Could not add labels {:?} to {}", rejected, pr_info.url);
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!("Failed to add labels to {}: {}", pr_info.url, e);
+                    }
+                }
+            }
+            if !repo.default_reviewers.is_empty() {
+                match git_host
+                    .request_reviewers(&pr_info.url, &repo.default_reviewers)
+                    .await
+                {
+                    Ok(rejected) if !rejected.is_empty() => {
+                        tracing::warn!(
+                            "Could not request default reviewers {:?} on {}",
+                            rejected,
+                            pr_info.url
+                        );
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        tracing::warn!(
+                            "Failed to request default reviewers on {}: {}",
+                            pr_info.url,
+                            e
+                        );
+                    }
+                }
+            }
+
             if let Ok(client) = deployment.remote_client() {
                 let request = UpsertPullRequestRequest {
                     url: pr_info.url.clone(),
@@ -363,7 +561,9 @@ pub async fn create_pr(
                 );
             }
 
-            Ok(ResponseJson(ApiResponse::success(pr_info.url)))
+            Ok(ResponseJson(ApiResponse::success(
+                CreatePrResponse::PrCreated { url: pr_info.url },
+            )))
         }
         Err(e) => {
             tracing::error!(
@@ -387,6 +587,29 @@ pub async fn create_pr(
     }
 }
 
+pub async fn get_pr_description(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Query(query): Query<PrDescriptionQuery>,
+) -> Result<ResponseJson<ApiResponse<PrDescriptionResponse>>, ApiError> {
+    let repo = match query.repo_id {
+        Some(repo_id) => Some(
+            Repo::find_by_id(&deployment.db().pool, repo_id)
+                .await?
+                .ok_or(RepoError::NotFound)?,
+        ),
+        None => None,
+    };
+    let rendered = render_pr_templates(
+        &deployment,
+        &workspace,
+        repo.as_ref().and_then(|r| r.pr_title_template.as_deref()),
+        repo.as_ref().and_then(|r| r.pr_body_template.as_deref()),
+    )
+    .await;
+    Ok(ResponseJson(ApiResponse::success(rendered)))
+}
+
 pub async fn attach_existing_pr(
     Extension(workspace): Extension<Workspace>,
     State(deployment): State<DeploymentImpl>,
@@ -414,10 +637,11 @@ pub async fn attach_existing_pr(
         })));
     }
 
-    let git = deployment.git();
-    let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
+    let remote = pr_base_remote(deployment.git(), &repo, &workspace_repo.target_branch)?;
 
-    let git_host = match GitHostService::from_url(&remote.url) {
+    // Plain git remotes have no PRs to attach; the generic provider finds none
+    let generic_git_fallback = deployment.config().read().await.generic_git_fallback;
+    let git_host = match GitHostService::from_url_with_config(&remote.url, generic_git_fallback) {
         Ok(host) => host,
         Err(GitHostError::UnsupportedProvider) => {
             return Ok(ResponseJson(ApiResponse::error_with_data(
@@ -570,8 +794,7 @@ pub async fn get_pr_comments(
         }
     };
 
-    let git = deployment.git();
-    let remote = git.resolve_remote_for_branch(&repo.path, &workspace_repo.target_branch)?;
+    let remote = pr_base_remote(deployment.git(), &repo, &workspace_repo.target_branch)?;
 
     let git_host = match GitHostService::from_url(&remote.url) {
         Ok(host) => host,
@@ -614,6 +837,85 @@ pub async fn get_pr_comments(
     }
 }
 
+/// Re-target the workspace's PR for a repo at another base branch. The
+/// workspace's target branch for that repo follows the PR.
+pub async fn update_pr_base(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Json(payload): Json<UpdatePrBaseRequest>,
+) -> Result<ResponseJson<ApiResponse<PullRequestDetail, UpdatePrBaseError>>, ApiError> {
+    let pool = &deployment.db().pool;
+
+    let workspace_repo =
+        WorkspaceRepo::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id)
+            .await?
+            .ok_or(RepoError::NotFound)?;
+    let repo = Repo::find_by_id(pool, workspace_repo.repo_id)
+        .await?
+        .ok_or(RepoError::NotFound)?;
+
+    let merges = Merge::find_by_workspace_and_repo_id(pool, workspace.id, payload.repo_id).await?;
+    let pr_info = match merges.into_iter().next() {
+        Some(Merge::Pr(pr_merge)) => pr_merge.pr_info,
+        _ => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                UpdatePrBaseError::NoPrAttached,
+            )));
+        }
+    };
+
+    let remote = pr_base_remote(deployment.git(), &repo, &workspace_repo.target_branch)?;
+    let git_host = match GitHostService::from_url(&remote.url) {
+        Ok(host) => host,
+        Err(GitHostError::CliNotInstalled { provider }) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                UpdatePrBaseError::CliNotInstalled { provider },
+            )));
+        }
+        Err(e) => return Err(ApiError::GitHost(e)),
+    };
+    let provider = git_host.provider_kind();
+
+    let pr = match git_host
+        .update_pr_base(&pr_info.url, &payload.new_base)
+        .await
+    {
+        Ok(pr) => pr,
+        Err(GitHostError::CliNotInstalled { provider }) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                UpdatePrBaseError::CliNotInstalled { provider },
+            )));
+        }
+        Err(GitHostError::AuthFailed(_)) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                UpdatePrBaseError::CliNotLoggedIn { provider },
+            )));
+        }
+        Err(GitHostError::UnsupportedOperation { provider, .. }) => {
+            return Ok(ResponseJson(ApiResponse::error_with_data(
+                UpdatePrBaseError::UnsupportedByProvider { provider },
+            )));
+        }
+        Err(e) => return Err(ApiError::GitHost(e)),
+    };
+
+    PullRequest::update_target_branch(pool, &pr.url, &pr.base_branch).await?;
+    WorkspaceRepo::update_target_branch(pool, workspace.id, repo.id, &pr.base_branch).await?;
+
+    deployment
+        .track_if_analytics_allowed(
+            "pr_base_updated",
+            serde_json::json!({
+                "workspace_id": workspace.id.to_string(),
+                "repo_id": repo.id.to_string(),
+                "provider": provider.to_string(),
+            }),
+        )
+        .await;
+
+    Ok(ResponseJson(ApiResponse::success(pr)))
+}
+
 #[derive(Debug, Serialize, Deserialize, TS)]
 pub struct CreateWorkspaceFromPrBody {
     pub repo_id: Uuid,
@@ -648,7 +950,7 @@ pub enum CreateFromPrError {
 ///
 /// DB records are deleted synchronously (fast). Filesystem cleanup is spawned
 /// as a background task to avoid blocking the error response.
-async fn cleanup_failed_pr_workspace(pool: &sqlx::SqlitePool, workspace: &Workspace) {
+pub(super) async fn cleanup_failed_pr_workspace(pool: &sqlx::SqlitePool, workspace: &Workspace) {
     let workspace_id = workspace.id;
 
     // Gather data needed for background filesystem cleanup before deleting DB records
@@ -719,6 +1021,7 @@ pub async fn create_workspace_from_pr(
         &CreateWorkspace {
             branch: target_branch_ref.clone(),
             name: Some(payload.pr_title.clone()),
+            task_id: None,
         },
         workspace_id,
     )
@@ -848,4 +1151,6 @@ pub fn router() -> Router<DeploymentImpl> {
         .route("/", post(create_pr))
         .route("/attach", post(attach_existing_pr))
         .route("/comments", get(get_pr_comments))
+        .route("/description", get(get_pr_description))
+        .route("/base", post(update_pr_base))
 }
@@ -0,0 +1,22 @@
+//! What the workspace's latest preview run would have changed.
+
+use axum::{Extension, extract::State, response::Json as ResponseJson};
+use db::models::workspace::Workspace;
+use deployment::Deployment;
+use services::services::proposed_changes::{self, WorkspacePreview};
+use utils::response::ApiResponse;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+pub async fn get_proposed_changes(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Option<WorkspacePreview>>>, ApiError> {
+    let preview = proposed_changes::latest_workspace_preview(
+        deployment.container(),
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?;
+    Ok(ResponseJson(ApiResponse::success(preview)))
+}
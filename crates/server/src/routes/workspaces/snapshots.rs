@@ -0,0 +1,152 @@
+//! Local undo points for a workspace's worktrees.
+//!
+//! A snapshot records HEAD plus every tracked and untracked change in each repo
+//! under `refs/vibe-kanban/snapshots/`, without touching the index or stash.
+//! Unlike merges these never leave the workspace.
+
+use std::path::PathBuf;
+
+use axum::{
+    Extension, Router,
+    extract::{Path, State},
+    response::Json as ResponseJson,
+    routing::{get, post},
+};
+use db::models::{
+    execution_process::ExecutionProcess, workspace::Workspace, workspace_repo::WorkspaceRepo,
+};
+use deployment::Deployment;
+use serde::Serialize;
+use services::services::container::ContainerService;
+use ts_rs::TS;
+use utils::response::ApiResponse;
+use uuid::Uuid;
+
+use crate::{DeploymentImpl, error::ApiError};
+
+#[derive(Debug, Serialize, TS)]
+pub struct WorktreeSnapshot {
+    pub id: Uuid,
+    /// Repos the snapshot covers; restoring only touches these.
+    pub repo_ids: Vec<Uuid>,
+}
+
+pub fn router() -> Router<DeploymentImpl> {
+    Router::new()
+        .route("/", get(list_snapshots).post(create_snapshot))
+        .route("/{snapshot_id}/restore", post(restore_snapshot))
+}
+
+async fn ensure_idle(deployment: &DeploymentImpl, workspace: &Workspace) -> Result<(), ApiError> {
+    if ExecutionProcess::has_running_non_dev_server_processes_for_workspace(
+        &deployment.db().pool,
+        workspace.id,
+    )
+    .await?
+    {
+        return Err(ApiError::Conflict(
+            "Stop running processes before snapshotting or restoring the workspace".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn create_snapshot(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<WorktreeSnapshot>>, ApiError> {
+    ensure_idle(&deployment, &workspace).await?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_dir = PathBuf::from(&container_ref);
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+
+    let id = Uuid::new_v4();
+    let workspace_id = workspace.id.to_string();
+    for repo in &repos {
+        deployment.git().snapshot_worktree(
+            &workspace_dir.join(&repo.name),
+            &workspace_id,
+            &id.to_string(),
+        )?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(WorktreeSnapshot {
+        id,
+        repo_ids: repos.iter().map(|r| r.id).collect(),
+    })))
+}
+
+pub async fn list_snapshots(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+) -> Result<ResponseJson<ApiResponse<Vec<WorktreeSnapshot>>>, ApiError> {
+    // Snapshot refs live in each main repo, so listing doesn't need the worktree.
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+
+    let workspace_id = workspace.id.to_string();
+    let mut snapshots: Vec<WorktreeSnapshot> = Vec::new();
+    for repo in &repos {
+        let ids = deployment
+            .git()
+            .list_worktree_snapshots(&repo.path, &workspace_id)?;
+        for id in ids.iter().filter_map(|id| Uuid::parse_str(id).ok()) {
+            match snapshots.iter_mut().find(|s| s.id == id) {
+                Some(snapshot) => snapshot.repo_ids.push(repo.id),
+                None => snapshots.push(WorktreeSnapshot {
+                    id,
+                    repo_ids: vec![repo.id],
+                }),
+            }
+        }
+    }
+
+    Ok(ResponseJson(ApiResponse::success(snapshots)))
+}
+
+pub async fn restore_snapshot(
+    Extension(workspace): Extension<Workspace>,
+    State(deployment): State<DeploymentImpl>,
+    Path((_, snapshot_id)): Path<(Uuid, Uuid)>,
+) -> Result<ResponseJson<ApiResponse<()>>, ApiError> {
+    ensure_idle(&deployment, &workspace).await?;
+
+    let container_ref = deployment
+        .container()
+        .ensure_container_exists(&workspace)
+        .await?;
+    let workspace_dir = PathBuf::from(&container_ref);
+    let repos =
+        WorkspaceRepo::find_repos_for_workspace(&deployment.db().pool, workspace.id).await?;
+
+    let workspace_id = workspace.id.to_string();
+    let snapshot_id = snapshot_id.to_string();
+    let mut targets = Vec::new();
+    for repo in &repos {
+        if deployment
+            .git()
+            .list_worktree_snapshots(&repo.path, &workspace_id)?
+            .contains(&snapshot_id)
+        {
+            targets.push(workspace_dir.join(&repo.name));
+        }
+    }
+    if targets.is_empty() {
+        return Err(ApiError::BadRequest(format!(
+            "Snapshot {snapshot_id} not found for this workspace"
+        )));
+    }
+
+    for worktree_path in targets {
+        deployment
+            .git()
+            .restore_worktree_snapshot(&worktree_path, &workspace_id, &snapshot_id)?;
+    }
+
+    Ok(ResponseJson(ApiResponse::success(())))
+}
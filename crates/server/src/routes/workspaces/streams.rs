@@ -1,14 +1,17 @@
 use axum::{
     Extension,
     extract::{Query, State, ws::Message},
-    response::IntoResponse,
+    response::{IntoResponse, Response},
 };
 use deployment::Deployment;
+use git::PathFilter;
 use serde::Deserialize;
 use services::services::container::ContainerService;
+use uuid::Uuid;
 
 use crate::{
     DeploymentImpl,
+    error::ApiError,
     middleware::signed_ws::{MaybeSignedWebSocket, SignedWsUpgrade},
 };
 
@@ -16,6 +19,12 @@ use crate::{
 pub struct DiffStreamQuery {
     #[serde(default)]
     pub stats_only: bool,
+    /// Diff against the HEAD this execution started from instead of the
+    /// target branch, to watch a single run's edits live.
+    pub execution_process_id: Option<Uuid>,
+    /// Comma-separated globs limiting which files are streamed, e.g.
+    /// `src/**,!**/*.lock`.
+    pub paths: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -42,14 +51,29 @@ pub async fn stream_workspace_diff_ws(
     Query(params): Query<DiffStreamQuery>,
     Extension(workspace): Extension<db::models::workspace::Workspace>,
     State(deployment): State<DeploymentImpl>,
-) -> impl IntoResponse {
+) -> Response {
+    let path_filter = match params.paths.as_deref().map(PathFilter::parse).transpose() {
+        Ok(filter) => filter,
+        Err(e) => return ApiError::BadRequest(e.to_string()).into_response(),
+    };
     let _ = deployment.container().touch(&workspace).await;
     let stats_only = params.stats_only;
+    let since_execution_id = params.execution_process_id;
     ws.on_upgrade(move |socket| async move {
-        if let Err(e) = handle_workspace_diff_ws(socket, deployment, workspace, stats_only).await {
+        if let Err(e) = handle_workspace_diff_ws(
+            socket,
+            deployment,
+            workspace,
+            stats_only,
+            since_execution_id,
+            path_filter,
+        )
+        .await
+        {
             tracing::warn!("diff WS closed: {}", e);
         }
     })
+    .into_response()
 }
 
 async fn handle_workspace_diff_ws(
@@ -57,13 +81,15 @@ async fn handle_workspace_diff_ws(
     deployment: DeploymentImpl,
     workspace: db::models::workspace::Workspace,
     stats_only: bool,
+    since_execution_id: Option<Uuid>,
+    path_filter: Option<PathFilter>,
 ) -> anyhow::Result<()> {
     use futures_util::{StreamExt, TryStreamExt};
     use utils::log_msg::LogMsg;
 
     let stream = deployment
         .container()
-        .stream_diff(&workspace, stats_only)
+        .stream_diff(&workspace, stats_only, since_execution_id, path_filter)
         .await?;
 
     let mut stream = stream.map_ok(|msg: LogMsg| msg.to_ws_message_unchecked());
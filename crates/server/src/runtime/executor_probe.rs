@@ -0,0 +1,85 @@
+//! Optional startup check that the default coding agent is installed, so a
+//! server deployment reports a missing agent at boot rather than on the first
+//! workspace.
+//!
+//! `VK_EXECUTOR_PROBE=warn` logs a warning when the agent is unavailable;
+//! `strict` refuses to start instead. Unset or `off` skips the probe.
+
+use deployment::{Deployment, DeploymentError};
+use executors::{executors::StandardCodingAgentExecutor, profile::ExecutorConfigs};
+
+use crate::DeploymentImpl;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProbeMode {
+    Off,
+    Warn,
+    Strict,
+}
+
+impl ProbeMode {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "" | "off" | "0" | "false" => Some(Self::Off),
+            "warn" | "1" | "true" => Some(Self::Warn),
+            "strict" => Some(Self::Strict),
+            _ => None,
+        }
+    }
+}
+
+/// The configured probe mode. Unrecognized values fall back to `warn`.
+pub fn probe_mode() -> ProbeMode {
+    let Ok(value) = std::env::var("VK_EXECUTOR_PROBE") else {
+        return ProbeMode::Off;
+    };
+    ProbeMode::parse(&value).unwrap_or_else(|| {
+        tracing::warn!("Unknown VK_EXECUTOR_PROBE value {value:?}, using `warn`");
+        ProbeMode::Warn
+    })
+}
+
+pub async fn probe_default_executor(
+    deployment: &DeploymentImpl,
+    mode: ProbeMode,
+) -> Result<(), DeploymentError> {
+    if mode == ProbeMode::Off {
+        return Ok(());
+    }
+
+    let profile_id = deployment.config().read().await.executor_profile.clone();
+    let available = ExecutorConfigs::get_cached()
+        .get_coding_agent(&profile_id)
+        .is_some_and(|agent| agent.get_availability_info().is_available());
+    if available {
+        tracing::info!("Default coding agent {} is available", profile_id.executor);
+        return Ok(());
+    }
+
+    let message = format!(
+        "Default coding agent {} was not found on this machine",
+        profile_id.executor
+    );
+    match mode {
+        ProbeMode::Strict => Err(DeploymentError::Other(anyhow::anyhow!(
+            "{message} (VK_EXECUTOR_PROBE=strict)"
+        ))),
+        _ => {
+            tracing::warn!("{message}; workspaces using it will fail to start");
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProbeMode;
+
+    #[test]
+    fn parses_probe_modes() {
+        assert_eq!(ProbeMode::parse(""), Some(ProbeMode::Off));
+        assert_eq!(ProbeMode::parse("Warn"), Some(ProbeMode::Warn));
+        assert_eq!(ProbeMode::parse(" strict "), Some(ProbeMode::Strict));
+        assert_eq!(ProbeMode::parse("loud"), None);
+    }
+}
@@ -0,0 +1,200 @@
+//! Optional idle shutdown for the local server.
+//!
+//! When `VK_IDLE_SHUTDOWN_SECS` is set, the server cancels its shutdown token
+//! once no client connection has been open and no execution process has run
+//! for that long. The regular graceful shutdown path then drains the servers
+//! and cleans up, exactly as for Ctrl+C. Disabled by default.
+
+use std::{
+    io,
+    pin::Pin,
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicUsize, Ordering},
+    },
+    task::{Context, Poll},
+    time::{Duration, Instant},
+};
+
+use axum::serve::Listener;
+use db::models::execution_process::ExecutionProcess;
+use deployment::Deployment;
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    net::{TcpListener, TcpStream},
+};
+use tokio_util::sync::CancellationToken;
+
+use crate::DeploymentImpl;
+
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The configured idle period, or `None` when idle shutdown is disabled.
+pub fn idle_timeout() -> Option<Duration> {
+    std::env::var("VK_IDLE_SHUTDOWN_SECS")
+        .ok()
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .map(Duration::from_secs)
+}
+
+/// Tracks open client connections and when the server was last in use.
+#[derive(Debug)]
+pub struct ActivityTracker {
+    open_connections: AtomicUsize,
+    last_active: Mutex<Instant>,
+}
+
+impl ActivityTracker {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            open_connections: AtomicUsize::new(0),
+            last_active: Mutex::new(Instant::now()),
+        })
+    }
+
+    fn touch(&self) {
+        *self.last_active.lock().unwrap() = Instant::now();
+    }
+
+    fn idle_for(&self) -> Option<Duration> {
+        if self.open_connections.load(Ordering::SeqCst) > 0 {
+            return None;
+        }
+        Some(self.last_active.lock().unwrap().elapsed())
+    }
+}
+
+/// A [`TcpListener`] that reports each accepted connection to an
+/// [`ActivityTracker`] for as long as the connection stays open. Long-lived
+/// WebSocket and keep-alive connections from an open UI therefore count as
+/// activity even when no requests are in flight.
+pub struct TrackedListener {
+    inner: TcpListener,
+    tracker: Arc<ActivityTracker>,
+}
+
+impl TrackedListener {
+    pub fn new(inner: TcpListener, tracker: Arc<ActivityTracker>) -> Self {
+        Self { inner, tracker }
+    }
+}
+
+impl Listener for TrackedListener {
+    type Io = TrackedStream;
+    type Addr = std::net::SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        let (stream, addr) = Listener::accept(&mut self.inner).await;
+        self.tracker.open_connections.fetch_add(1, Ordering::SeqCst);
+        self.tracker.touch();
+        let stream = TrackedStream {
+            inner: stream,
+            tracker: self.tracker.clone(),
+        };
+        (stream, addr)
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        Listener::local_addr(&self.inner)
+    }
+}
+
+pub struct TrackedStream {
+    inner: TcpStream,
+    tracker: Arc<ActivityTracker>,
+}
+
+impl Drop for TrackedStream {
+    fn drop(&mut self) {
+        self.tracker.touch();
+        self.tracker.open_connections.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl AsyncRead for TrackedStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for TrackedStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write_vectored(cx, bufs)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.inner.is_write_vectored()
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+/// Cancel `shutdown` once the server has been idle for `timeout`. Running
+/// execution processes count as activity, so agents are never cut off.
+pub fn spawn_idle_shutdown(
+    deployment: &DeploymentImpl,
+    tracker: Arc<ActivityTracker>,
+    timeout: Duration,
+    shutdown: CancellationToken,
+) {
+    let pool = deployment.db().pool.clone();
+    let check_interval = (timeout / 4).clamp(Duration::from_secs(1), MAX_CHECK_INTERVAL);
+    tracing::info!(
+        "Idle shutdown enabled: server stops after {}s without clients or executions",
+        timeout.as_secs()
+    );
+
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(check_interval);
+        loop {
+            tokio::select! {
+                _ = shutdown.cancelled() => return,
+                _ = interval.tick() => {}
+            }
+
+            match ExecutionProcess::find_running(&pool).await {
+                Ok(running) if !running.is_empty() => {
+                    tracker.touch();
+                    continue;
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!("Idle shutdown: failed to check running executions: {}", e);
+                    continue;
+                }
+            }
+
+            if tracker.idle_for().is_some_and(|idle| idle >= timeout) {
+                tracing::info!(
+                    "No clients or executions for {}s, shutting down",
+                    timeout.as_secs()
+                );
+                shutdown.cancel();
+                return;
+            }
+        }
+    });
+}
@@ -1 +1,3 @@
+pub mod executor_probe;
+pub mod idle_shutdown;
 pub mod relay_registration;
@@ -1,6 +1,7 @@
 use std::{
     collections::HashSet,
     fs, io,
+    net::SocketAddr,
     path::{Path, PathBuf},
 };
 
@@ -11,7 +12,10 @@ use tower_http::validate_request::ValidateRequestHeaderLayer;
 use utils::assets::asset_dir;
 
 use crate::{
-    DeploymentImpl, middleware::origin::validate_origin, routes, runtime::relay_registration,
+    DeploymentImpl,
+    middleware::origin::validate_origin,
+    routes,
+    runtime::{executor_probe, idle_shutdown, relay_registration},
 };
 
 /// A running server instance. Callers can read the port, then call `serve()`
@@ -33,7 +37,7 @@ impl ServerHandle {
     /// the Tauri desktop app — IP address literals aren't reliably
     /// matched by ATS, which causes WebSocket connections to fail.
     pub fn url(&self) -> String {
-        format!("http://localhost:{}", self.port)
+        format!("http://localhost:{}{}", self.port, utils::base_path())
     }
 
     /// Run both the main and proxy servers until the shutdown token is cancelled.
@@ -58,8 +62,23 @@ impl ServerHandle {
         let main_shutdown = self.shutdown_token.clone();
         let proxy_shutdown = self.shutdown_token.clone();
 
-        let main_server = axum::serve(self.main_listener, app_router)
-            .with_graceful_shutdown(async move { main_shutdown.cancelled().await });
+        let activity = idle_shutdown::ActivityTracker::new();
+        let main_listener =
+            idle_shutdown::TrackedListener::new(self.main_listener, activity.clone());
+        if let Some(timeout) = idle_shutdown::idle_timeout() {
+            idle_shutdown::spawn_idle_shutdown(
+                &self.deployment,
+                activity,
+                timeout,
+                self.shutdown_token.clone(),
+            );
+        }
+
+        let main_server = axum::serve(
+            main_listener,
+            app_router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(async move { main_shutdown.cancelled().await });
         let proxy_server = axum::serve(self.proxy_listener, proxy_router)
             .with_graceful_shutdown(async move { proxy_shutdown.cancelled().await });
 
@@ -170,6 +189,7 @@ pub async fn initialize_deployment(
         .backfill_repo_names()
         .await
         .map_err(DeploymentError::from)?;
+    executor_probe::probe_default_executor(&deployment, executor_probe::probe_mode()).await?;
     deployment
         .track_if_analytics_allowed("session_start", serde_json::json!({}))
         .await;
@@ -0,0 +1,82 @@
+//! Template for the identity context added when a new agent session starts.
+//!
+//! Placeholders are those of [`super::template`]; the default lives in
+//! [`super::config::DEFAULT_AGENT_IDENTITY_TEMPLATE`].
+
+use thiserror::Error;
+
+use super::template;
+
+const PLACEHOLDERS: [&str; 5] = ["user", "workspace_name", "workspace_id", "branch", "repos"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum AgentIdentityTemplateError {
+    #[error(
+        "Unknown placeholder `{{{0}}}`. Use {{user}}, {{workspace_name}}, {{workspace_id}}, {{branch}} or {{repos}}."
+    )]
+    UnknownPlaceholder(String),
+}
+
+/// Values substituted into a template.
+pub struct AgentIdentityContext<'a> {
+    /// GitHub username, or a generic stand-in when signed out.
+    pub user: &'a str,
+    pub workspace_name: &'a str,
+    pub workspace_id: &'a str,
+    pub branch: &'a str,
+    /// Comma-separated repo names.
+    pub repos: &'a str,
+}
+
+pub fn validate(template: &str) -> Result<(), AgentIdentityTemplateError> {
+    template::validate(template, &PLACEHOLDERS)
+        .map_err(|e| AgentIdentityTemplateError::UnknownPlaceholder(e.0))
+}
+
+pub fn render(template: &str, ctx: &AgentIdentityContext<'_>) -> String {
+    template::render(
+        template,
+        &[
+            ("user", ctx.user),
+            ("workspace_name", ctx.workspace_name),
+            ("workspace_id", ctx.workspace_id),
+            ("branch", ctx.branch),
+            ("repos", ctx.repos),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::config::DEFAULT_AGENT_IDENTITY_TEMPLATE;
+
+    #[test]
+    fn renders_values_once() {
+        let ctx = AgentIdentityContext {
+            user: "octocat",
+            workspace_name: "Fix {branch}",
+            workspace_id: "42",
+            branch: "vk/fix",
+            repos: "api, web",
+        };
+        assert_eq!(
+            render(
+                "{user} in {workspace_name} ({workspace_id}) on {branch}: {repos}",
+                &ctx
+            ),
+            "octocat in Fix {branch} (42) on vk/fix: api, web"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        assert_eq!(validate(DEFAULT_AGENT_IDENTITY_TEMPLATE), Ok(()));
+        assert_eq!(
+            validate("{user} {workspace}"),
+            Err(AgentIdentityTemplateError::UnknownPlaceholder(
+                "workspace".into()
+            ))
+        );
+    }
+}
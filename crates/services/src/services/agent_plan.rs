@@ -0,0 +1,171 @@
+//! The latest plan an agent presented in a workspace, e.g. via Claude's
+//! `ExitPlanMode`, so it can be reviewed outside the conversation.
+//!
+//! In plan mode the agent waits on a tool approval for the plan; the plan's
+//! status carries that approval id while it is pending. Running agents are
+//! read from their live log; finished runs store their last plan when they
+//! exit, so it is never re-normalized.
+
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason},
+    session::Session,
+};
+use executors::logs::{ActionType, NormalizedEntryType, ToolStatus, utils::patch};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct AgentPlan {
+    pub execution_process_id: Uuid,
+    pub plan: String,
+    pub status: ToolStatus,
+}
+
+/// Find the most recent plan across the workspace's coding agent runs.
+pub async fn latest_workspace_plan(
+    container: &impl ContainerService,
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Option<AgentPlan>, ContainerError> {
+    let mut processes = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace_id).await? {
+        processes.extend(
+            ExecutionProcess::find_by_session_id(pool, session.id, false)
+                .await?
+                .into_iter()
+                .filter(|p| p.run_reason == ExecutionProcessRunReason::CodingAgent),
+        );
+    }
+    processes.sort_by_key(|p| std::cmp::Reverse(p.created_at));
+
+    for process in processes {
+        // A pending plan keeps the agent running, so this must not wait for
+        // the process to exit.
+        let plan = match container.get_msg_store_by_id(&process.id).await {
+            Some(store) => latest_plan(process.id, &store.get_history()),
+            None => find(pool, process.id).await?,
+        };
+        if plan.is_some() {
+            return Ok(plan);
+        }
+    }
+    Ok(None)
+}
+
+/// Store the last plan a finished run presented, if any.
+pub async fn record(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    messages: &[LogMsg],
+) -> Result<(), sqlx::Error> {
+    let Some(plan) = latest_plan(execution_process_id, messages) else {
+        return Ok(());
+    };
+    let json = serde_json::to_string(&plan).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    CodingAgentTurn::update_agent_plan(pool, execution_process_id, &json).await
+}
+
+async fn find(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+) -> Result<Option<AgentPlan>, sqlx::Error> {
+    let Some(json) = CodingAgentTurn::find_agent_plan(pool, execution_process_id).await? else {
+        return Ok(None);
+    };
+    match serde_json::from_str(&json) {
+        Ok(plan) => Ok(Some(plan)),
+        Err(e) => {
+            tracing::warn!(
+                "Ignoring unreadable agent plan for {}: {}",
+                execution_process_id,
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+fn latest_plan(execution_process_id: Uuid, messages: &[LogMsg]) -> Option<AgentPlan> {
+    patch::latest_entries(messages)
+        .into_values()
+        .rev()
+        .find_map(|entry| match entry.entry_type {
+            NormalizedEntryType::ToolUse {
+                action_type: ActionType::PlanPresentation { plan },
+                status,
+                ..
+            } => Some(AgentPlan {
+                execution_process_id,
+                plan,
+                status,
+            }),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{NormalizedEntry, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn entry(index: usize, entry_type: NormalizedEntryType) -> LogMsg {
+        LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            index,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type,
+                content: String::new(),
+                metadata: None,
+            },
+        ))
+    }
+
+    fn plan(index: usize, plan: &str, status: ToolStatus) -> LogMsg {
+        entry(
+            index,
+            NormalizedEntryType::ToolUse {
+                tool_name: "ExitPlanMode".to_string(),
+                action_type: ActionType::PlanPresentation {
+                    plan: plan.to_string(),
+                },
+                status,
+            },
+        )
+    }
+
+    #[test]
+    fn picks_the_last_plan_in_its_latest_state() {
+        let id = Uuid::new_v4();
+        let messages = [
+            plan(0, "first", ToolStatus::Denied { reason: None }),
+            plan(
+                1,
+                "second",
+                ToolStatus::PendingApproval {
+                    approval_id: "a1".to_string(),
+                },
+            ),
+            entry(2, NormalizedEntryType::UserMessage),
+            // The approval resolved, replacing entry 1.
+            plan(1, "second", ToolStatus::Success),
+        ];
+
+        let latest = latest_plan(id, &messages).unwrap();
+        assert_eq!(latest.execution_process_id, id);
+        assert_eq!(latest.plan, "second");
+        assert!(matches!(latest.status, ToolStatus::Success));
+    }
+
+    #[test]
+    fn no_plan_without_a_plan_presentation() {
+        let messages = [entry(0, NormalizedEntryType::UserMessage)];
+        assert!(latest_plan(Uuid::new_v4(), &messages).is_none());
+    }
+}
@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
 use db::{self, DBService, models::execution_process::ExecutionProcess};
-use executors::approvals::{ExecutorApprovalError, ExecutorApprovalService};
+use executors::approvals::{AutoDenyRule, ExecutorApprovalError, ExecutorApprovalService};
 use tokio::sync::Mutex;
 use tokio_util::sync::CancellationToken;
 use utils::approvals::{ApprovalOutcome, ApprovalRequest, ApprovalStatus, QuestionStatus};
@@ -129,6 +129,16 @@ impl ExecutorApprovalBridge {
 
 #[async_trait]
 impl ExecutorApprovalService for ExecutorApprovalBridge {
+    fn set_auto_deny_rules(&self, rules: &[AutoDenyRule]) {
+        self.approvals
+            .set_auto_deny_rules(self.execution_process_id, rules);
+    }
+
+    fn check_auto_deny(&self, tool_name: &str, tool_input: &serde_json::Value) -> Option<String> {
+        self.approvals
+            .check_auto_deny(self.execution_process_id, tool_name, tool_input)
+    }
+
     async fn create_tool_approval(&self, tool_name: &str) -> Result<String, ExecutorApprovalError> {
         self.create_internal(tool_name, false, None).await
     }
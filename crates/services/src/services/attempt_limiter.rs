@@ -0,0 +1,348 @@
+use std::sync::{
+    Arc, Mutex,
+    atomic::{AtomicUsize, Ordering},
+};
+
+use dashmap::DashMap;
+use db::models::repo::Repo;
+use serde::Serialize;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use ts_rs::TS;
+use uuid::Uuid;
+
+/// Current coding agent load for a repo.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, TS)]
+pub struct RepoConcurrencyStatus {
+    #[ts(type = "number | null")]
+    pub max_concurrent_attempts: Option<i64>,
+    /// Runs currently holding a slot.
+    pub running: usize,
+    /// Runs waiting for a slot to free up.
+    pub queued: usize,
+}
+
+/// Workspaces a task has started against its repos' per-task cap.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct TaskAttemptUsage {
+    /// Workspaces started for the task, including archived ones.
+    #[ts(type = "number")]
+    pub attempts: i64,
+    /// Lowest `max_attempts_per_task` among the repos.
+    #[ts(type = "number | null")]
+    pub max_attempts: Option<i64>,
+    #[ts(type = "number | null")]
+    pub remaining: Option<i64>,
+    /// Display name of the repo whose cap applies.
+    pub limited_by: Option<String>,
+}
+
+impl TaskAttemptUsage {
+    pub fn new(attempts: i64, repos: &[Repo]) -> Self {
+        let limiting = repos
+            .iter()
+            .filter_map(|repo| Some((repo.max_attempts_per_task?, repo)))
+            .min_by_key(|(limit, _)| *limit);
+        Self {
+            attempts,
+            max_attempts: limiting.map(|(limit, _)| limit),
+            remaining: limiting.map(|(limit, _)| (limit - attempts).max(0)),
+            limited_by: limiting.map(|(_, repo)| repo.display_name.clone()),
+        }
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining == Some(0)
+    }
+}
+
+#[derive(Clone)]
+struct Slots {
+    limit: usize,
+    semaphore: Arc<Semaphore>,
+    running: Arc<AtomicUsize>,
+    queued: Arc<AtomicUsize>,
+    /// Permits still to retire after the limit was lowered while more runs
+    /// than the new limit held one.
+    excess: Arc<AtomicUsize>,
+}
+
+impl Slots {
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            semaphore: Arc::new(Semaphore::new(limit)),
+            running: Arc::new(AtomicUsize::new(0)),
+            queued: Arc::new(AtomicUsize::new(0)),
+            excess: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Resize the one semaphore every run shares. Lowering the limit retires
+    /// free permits right away and held ones as their runs finish, so running
+    /// plus new runs never exceed the new limit.
+    fn set_limit(&mut self, limit: usize) {
+        if limit > self.limit {
+            let mut grow = limit - self.limit;
+            // Permits still waiting to be retired are kept instead.
+            let kept = self
+                .excess
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                    Some(n.saturating_sub(grow))
+                })
+                .unwrap_or_default()
+                .min(grow);
+            grow -= kept;
+            self.semaphore.add_permits(grow);
+        } else if limit < self.limit {
+            let shrink = self.limit - limit;
+            let forgotten = self.semaphore.forget_permits(shrink);
+            self.excess.fetch_add(shrink - forgotten, Ordering::SeqCst);
+        }
+        self.limit = limit;
+    }
+
+    async fn acquire(self) -> Option<AttemptPermit> {
+        let permit = {
+            let _queued = QueuedGuard::new(self.queued);
+            self.semaphore.acquire_owned().await
+        };
+
+        // The semaphore is never closed, so acquiring only fails if that changes.
+        let permit = permit.ok()?;
+        self.running.fetch_add(1, Ordering::Relaxed);
+        Some(AttemptPermit {
+            permit: Some(permit),
+            running: self.running,
+            excess: self.excess,
+        })
+    }
+
+    fn try_acquire(&self) -> Result<AttemptPermit, SlotsFull> {
+        let permit = self
+            .semaphore
+            .clone()
+            .try_acquire_owned()
+            .map_err(|_| SlotsFull)?;
+        self.running.fetch_add(1, Ordering::Relaxed);
+        Ok(AttemptPermit {
+            permit: Some(permit),
+            running: self.running.clone(),
+            excess: self.excess.clone(),
+        })
+    }
+}
+
+/// Every slot of a limit is in use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SlotsFull;
+
+/// Held while a coding agent runs against a repo; dropping it frees the slot.
+pub struct AttemptPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    running: Arc<AtomicUsize>,
+    excess: Arc<AtomicUsize>,
+}
+
+impl Drop for AttemptPermit {
+    fn drop(&mut self) {
+        self.running.fetch_sub(1, Ordering::Relaxed);
+        let retire = self
+            .excess
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+            .is_ok();
+        if retire && let Some(permit) = self.permit.take() {
+            permit.forget();
+        }
+    }
+}
+
+/// Counts a waiter as queued, including when its start request is dropped mid-wait.
+struct QueuedGuard(Arc<AtomicUsize>);
+
+impl QueuedGuard {
+    fn new(queued: Arc<AtomicUsize>) -> Self {
+        queued.fetch_add(1, Ordering::Relaxed);
+        Self(queued)
+    }
+}
+
+impl Drop for QueuedGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// In-memory limits on concurrently running coding agents: per repo, and
+/// across the whole machine.
+///
+/// Repos without `max_concurrent_attempts` are never tracked here.
+#[derive(Clone, Default)]
+pub struct AttemptLimiter {
+    repos: Arc<DashMap<Uuid, Slots>>,
+    global: Arc<Mutex<Option<Slots>>>,
+}
+
+impl AttemptLimiter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wait for a free slot on `repo_id`. Returns `None` when the repo has no limit.
+    pub async fn acquire(&self, repo_id: Uuid, limit: Option<i64>) -> Option<AttemptPermit> {
+        let limit = limit.filter(|l| *l > 0)? as usize;
+
+        let slots = {
+            let mut slots = self
+                .repos
+                .entry(repo_id)
+                .or_insert_with(|| Slots::new(limit));
+            slots.set_limit(limit);
+            slots.clone()
+        };
+        slots.acquire().await
+    }
+
+    /// Wait for one of the `limit` slots shared by all coding agent runs.
+    pub async fn acquire_global(&self, limit: usize) -> Option<AttemptPermit> {
+        let limit = limit.max(1);
+        let slots = {
+            let mut global = self.global.lock().unwrap();
+            let slots = global.get_or_insert_with(|| Slots::new(limit));
+            slots.set_limit(limit);
+            slots.clone()
+        };
+        slots.acquire().await
+    }
+
+    /// Take a free slot on `repo_id` without waiting. `Ok(None)` when the repo
+    /// has no limit.
+    pub fn try_acquire(
+        &self,
+        repo_id: Uuid,
+        limit: Option<i64>,
+    ) -> Result<Option<AttemptPermit>, SlotsFull> {
+        let Some(limit) = limit.filter(|l| *l > 0) else {
+            return Ok(None);
+        };
+        let mut slots = self
+            .repos
+            .entry(repo_id)
+            .or_insert_with(|| Slots::new(limit as usize));
+        slots.set_limit(limit as usize);
+        slots.try_acquire().map(Some)
+    }
+
+    /// Take one of the `limit` shared slots without waiting.
+    pub fn try_acquire_global(&self, limit: usize) -> Result<AttemptPermit, SlotsFull> {
+        let limit = limit.max(1);
+        let mut global = self.global.lock().unwrap();
+        let slots = global.get_or_insert_with(|| Slots::new(limit));
+        slots.set_limit(limit);
+        slots.try_acquire()
+    }
+
+    pub fn status(&self, repo_id: Uuid, limit: Option<i64>) -> RepoConcurrencyStatus {
+        let (running, queued) = self
+            .repos
+            .get(&repo_id)
+            .map(|slots| {
+                (
+                    slots.running.load(Ordering::Relaxed),
+                    slots.queued.load(Ordering::Relaxed),
+                )
+            })
+            .unwrap_or_default();
+        RepoConcurrencyStatus {
+            max_concurrent_attempts: limit,
+            running,
+            queued,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn unlimited_repos_are_not_tracked() {
+        let limiter = AttemptLimiter::new();
+        let repo_id = Uuid::new_v4();
+        assert!(limiter.acquire(repo_id, None).await.is_none());
+        assert_eq!(limiter.status(repo_id, None).running, 0);
+    }
+
+    #[tokio::test]
+    async fn waits_for_slot_when_at_limit() {
+        let limiter = AttemptLimiter::new();
+        let repo_id = Uuid::new_v4();
+        let first = limiter.acquire(repo_id, Some(1)).await;
+        assert!(first.is_some());
+
+        let waiter = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire(repo_id, Some(1)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        let status = limiter.status(repo_id, Some(1));
+        assert_eq!((status.running, status.queued), (1, 1));
+
+        drop(first);
+        let second = waiter.await.unwrap();
+        assert!(second.is_some());
+        let status = limiter.status(repo_id, Some(1));
+        assert_eq!((status.running, status.queued), (1, 0));
+    }
+
+    #[tokio::test]
+    async fn try_acquire_fails_without_waiting_when_full() {
+        let limiter = AttemptLimiter::new();
+        let repo_id = Uuid::new_v4();
+        assert!(limiter.try_acquire(repo_id, None).unwrap().is_none());
+
+        let first = limiter.try_acquire(repo_id, Some(1)).unwrap();
+        assert!(first.is_some());
+        assert_eq!(limiter.try_acquire(repo_id, Some(1)).err(), Some(SlotsFull));
+
+        drop(first);
+        assert!(limiter.try_acquire(repo_id, Some(1)).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn lowering_the_limit_counts_runs_already_holding_a_slot() {
+        let limiter = AttemptLimiter::new();
+        let repo_id = Uuid::new_v4();
+        let first = limiter.try_acquire(repo_id, Some(2)).unwrap();
+        let second = limiter.try_acquire(repo_id, Some(2)).unwrap();
+
+        assert_eq!(limiter.try_acquire(repo_id, Some(1)).err(), Some(SlotsFull));
+        drop(first);
+        assert_eq!(limiter.try_acquire(repo_id, Some(1)).err(), Some(SlotsFull));
+        drop(second);
+        let third = limiter.try_acquire(repo_id, Some(1)).unwrap();
+        assert!(third.is_some());
+        assert_eq!(limiter.try_acquire(repo_id, Some(1)).err(), Some(SlotsFull));
+
+        // Raising it again frees a slot while the run is still going.
+        assert!(limiter.try_acquire(repo_id, Some(2)).unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn global_slots_are_shared_across_repos() {
+        let limiter = AttemptLimiter::new();
+        let first = limiter.acquire_global(1).await;
+        assert!(first.is_some());
+
+        let waiter = {
+            let limiter = limiter.clone();
+            tokio::spawn(async move { limiter.acquire_global(1).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiter.is_finished());
+
+        drop(first);
+        assert!(waiter.await.unwrap().is_some());
+    }
+}
@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use api_types::ProfileResponse;
 use tokio::sync::{Mutex as TokioMutex, OwnedMutexGuard, RwLock};
+use uuid::Uuid;
 
-use super::oauth_credentials::{Credentials, OAuthCredentials};
+use super::oauth_credentials::{Credentials, OAuthCredentials, StoredAccount};
 
 #[derive(Clone)]
 pub struct AuthContext {
@@ -38,6 +39,30 @@ impl AuthContext {
         self.oauth.clear().await
     }
 
+    pub async fn accounts(&self) -> Vec<StoredAccount> {
+        self.oauth.accounts().await
+    }
+
+    pub async fn active_account(&self) -> Option<Uuid> {
+        self.oauth.active_account().await
+    }
+
+    /// Make another stored account active. Waits for any in-flight token
+    /// refresh so it can't re-activate the previous account afterwards.
+    /// Returns `false` if no credentials are stored for `user_id`.
+    pub async fn switch_account(&self, user_id: Uuid) -> std::io::Result<bool> {
+        let _refresh_guard = self.refresh_guard().await;
+        if self.oauth.active_account().await == Some(user_id) {
+            return Ok(true);
+        }
+        if !self.oauth.set_active(user_id).await? {
+            return Ok(false);
+        }
+        self.clear_profile().await;
+        self.clear_remote_auth_degraded_slug().await;
+        Ok(true)
+    }
+
     pub async fn remote_auth_degraded_slug(&self) -> Option<String> {
         self.remote_auth_degraded_slug.read().await.clone()
     }
@@ -54,7 +79,15 @@ impl AuthContext {
         self.profile.read().await.clone()
     }
 
+    /// Cache the profile of the active account. Profiles fetched for an account
+    /// that has since been switched away from are not cached.
     pub async fn set_profile(&self, profile: ProfileResponse) {
+        if let Err(e) = self.oauth.set_email(profile.user_id, &profile.email).await {
+            tracing::warn!(?e, "failed to store account email");
+        }
+        if self.oauth.active_account().await != Some(profile.user_id) {
+            return;
+        }
         *self.profile.write().await = Some(profile)
     }
 
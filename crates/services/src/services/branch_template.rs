@@ -0,0 +1,141 @@
+//! Template for the branch names of new workspaces.
+//!
+//! Placeholders are those of [`super::template`]. The
+//! rendered name is reduced to a git-safe slug, and the configured branch
+//! prefix is still added in front of it.
+
+use chrono::NaiveDate;
+use thiserror::Error;
+use utils::text::{git_branch_id, short_uuid};
+use uuid::Uuid;
+
+use super::template::{self, placeholders};
+
+const PLACEHOLDERS: [&str; 4] = ["task_id", "task_title_slug", "date", "author"];
+
+/// Longest branch name a template renders to, before the prefix.
+const MAX_BRANCH_NAME_LEN: usize = 60;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BranchTemplateError {
+    #[error(
+        "Unknown placeholder `{{{0}}}`. Use {{task_id}}, {{task_title_slug}}, {{date}} or {{author}}."
+    )]
+    UnknownPlaceholder(String),
+    #[error(
+        "Branch name templates must include {{task_id}} so every workspace gets its own branch."
+    )]
+    MissingTaskId,
+}
+
+/// What a deployment names branches with.
+pub struct BranchTemplateConfig {
+    pub template: String,
+    pub author: Option<String>,
+}
+
+/// Values substituted into a template.
+pub struct BranchTemplateContext<'a> {
+    /// Rendered as the same short id default branch names start with.
+    pub task_id: Uuid,
+    pub task_title: &'a str,
+    /// GitHub username from the config, if signed in.
+    pub author: Option<&'a str>,
+    pub date: NaiveDate,
+}
+
+pub fn validate(template: &str) -> Result<(), BranchTemplateError> {
+    template::validate(template, &PLACEHOLDERS)
+        .map_err(|e| BranchTemplateError::UnknownPlaceholder(e.0))?;
+    if !placeholders(template)
+        .iter()
+        .any(|(_, _, name)| *name == "task_id")
+    {
+        return Err(BranchTemplateError::MissingTaskId);
+    }
+    Ok(())
+}
+
+/// Substitute placeholders, then lowercase the result, collapse every run of
+/// other characters to `-` and cut it to 60 characters. When the cut drops
+/// the task id it is appended again, so names stay unique.
+pub fn render_branch_name(
+    template: &str,
+    ctx: &BranchTemplateContext<'_>,
+) -> Result<String, BranchTemplateError> {
+    validate(template)?;
+
+    let task_id = short_uuid(&ctx.task_id);
+    let task_title_slug = git_branch_id(ctx.task_title);
+    let date = ctx.date.format("%Y-%m-%d").to_string();
+    let out = template::render(
+        template,
+        &[
+            ("task_id", &task_id),
+            ("task_title_slug", &task_title_slug),
+            ("date", &date),
+            ("author", ctx.author.unwrap_or_default()),
+        ],
+    );
+
+    let mut slug = String::with_capacity(out.len());
+    for c in out.to_lowercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.len() > MAX_BRANCH_NAME_LEN {
+        slug.truncate(MAX_BRANCH_NAME_LEN);
+        if !slug.contains(&task_id) {
+            slug.truncate(MAX_BRANCH_NAME_LEN - task_id.len() - 1);
+            slug = format!("{}-{task_id}", slug.trim_end_matches('-'));
+        }
+    }
+    Ok(slug.trim_end_matches('-').to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_git_safe_names() {
+        let ctx = BranchTemplateContext {
+            task_id: Uuid::parse_str("1a2b3c4d-0000-0000-0000-000000000000").unwrap(),
+            task_title: "Fix the Login page!",
+            author: Some("Octo_Cat"),
+            date: NaiveDate::from_ymd_opt(2026, 3, 9).unwrap(),
+        };
+        assert_eq!(
+            render_branch_name("{author}/{date}/{task_id}_{task_title_slug}", &ctx),
+            Ok("octo-cat-2026-03-09-1a2b-fix-the-login-pa".to_string())
+        );
+
+        let long = render_branch_name(&format!("{}-{{task_id}}", "x".repeat(70)), &ctx).unwrap();
+        assert_eq!(long, format!("{}-1a2b", "x".repeat(55)));
+
+        let anonymous = BranchTemplateContext {
+            author: None,
+            ..ctx
+        };
+        assert_eq!(
+            render_branch_name("{author}-{task_id}", &anonymous),
+            Ok("1a2b".to_string())
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        assert_eq!(validate("{task_id}-{task_title_slug}"), Ok(()));
+        assert_eq!(
+            validate("{task_id}-{foo}"),
+            Err(BranchTemplateError::UnknownPlaceholder("foo".into()))
+        );
+        assert_eq!(
+            validate("{author}/{task_title_slug}"),
+            Err(BranchTemplateError::MissingTaskId)
+        );
+    }
+}
@@ -22,6 +22,17 @@ Use the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos p
 
 pub const DEFAULT_COMMIT_REMINDER_PROMPT: &str = "There are uncommitted changes. Please stage and commit them now with a descriptive commit message.";
 
+pub const DEFAULT_AGENT_IDENTITY_TEMPLATE: &str = r#"<vibe-kanban-context>
+You are working inside Vibe Kanban on behalf of {user}.
+Workspace: {workspace_name} ({workspace_id})
+Branch: {branch}
+Repositories: {repos}
+Use this context when writing commit messages and pull request descriptions.
+</vibe-kanban-context>"#;
+
+pub const DEFAULT_DENIED_TOOL_NOTE_TEMPLATE: &str =
+    "The previous {tool} call was denied by the user. Reason: {reason}. Do not retry it.";
+
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error(transparent)]
@@ -42,6 +53,10 @@ pub type GitHubConfig = versions::v8::GitHubConfig;
 pub type UiLanguage = versions::v8::UiLanguage;
 pub type ShowcaseState = versions::v8::ShowcaseState;
 pub type SendMessageShortcut = versions::v8::SendMessageShortcut;
+pub type ConventionalCommitConfig = versions::v8::ConventionalCommitConfig;
+pub type ConventionalCommitMode = versions::v8::ConventionalCommitMode;
+pub type WebhookConfig = versions::v8::WebhookConfig;
+pub type WebhookEventType = versions::v8::WebhookEventType;
 
 /// Will always return config, trying old schemas or eventually returning default
 pub async fn load_config_from_file(config_path: &PathBuf) -> Config {
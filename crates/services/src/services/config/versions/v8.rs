@@ -25,6 +25,10 @@ fn default_relay_enabled() -> bool {
     true
 }
 
+fn default_exclude_agent_local_files() -> bool {
+    true
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
 pub enum SendMessageShortcut {
     #[default]
@@ -32,6 +36,71 @@ pub enum SendMessageShortcut {
     Enter,
 }
 
+// What to do with agent commit messages that aren't conventional commits
+#[derive(Clone, Debug, Default, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub enum ConventionalCommitMode {
+    #[default]
+    Off,
+    Reject,     // Discard the message and fall back to the default commit message
+    AutoPrefix, // Prefix a type derived from what produced the commit
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct ConventionalCommitConfig {
+    #[serde(default)]
+    pub mode: ConventionalCommitMode,
+    #[serde(default = "default_conventional_commit_types")]
+    pub allowed_types: Vec<String>,
+    #[serde(default = "default_conventional_commit_max_subject_length")]
+    pub max_subject_length: usize,
+}
+
+fn default_conventional_commit_types() -> Vec<String> {
+    [
+        "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore",
+        "revert",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+fn default_conventional_commit_max_subject_length() -> usize {
+    72
+}
+
+impl Default for ConventionalCommitConfig {
+    fn default() -> Self {
+        Self {
+            mode: ConventionalCommitMode::default(),
+            allowed_types: default_conventional_commit_types(),
+            max_subject_length: default_conventional_commit_max_subject_length(),
+        }
+    }
+}
+
+/// Lifecycle events a webhook can subscribe to.
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookEventType {
+    WorkspaceCreated,
+    ExecutionStarted,
+    ExecutionCompleted,
+    ExecutionFailed,
+    WorkspaceMerged,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, TS, PartialEq, Eq)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Signs each delivery with HMAC-SHA256 in the `X-VK-Signature` header.
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Events to deliver. Empty delivers all of them.
+    #[serde(default)]
+    pub events: Vec<WebhookEventType>,
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, TS)]
 pub struct Config {
     pub config_version: String,
@@ -45,6 +114,8 @@ pub struct Config {
     pub editor: EditorConfig,
     pub github: GitHubConfig,
     pub analytics_enabled: bool,
+    #[serde(default)]
+    pub analytics_event_allowlist: Option<Vec<String>>,
     pub workspace_dir: Option<String>,
     pub last_app_version: Option<String>,
     pub show_release_notes: bool,
@@ -52,6 +123,10 @@ pub struct Config {
     pub language: UiLanguage,
     #[serde(default = "default_git_branch_prefix")]
     pub git_branch_prefix: String,
+    /// Names new workspace branches, after the prefix. Must contain
+    /// `{task_id}`. Unset keeps the `{task_id}-{task_title_slug}` naming.
+    #[serde(default)]
+    pub git_branch_template: Option<String>,
     #[serde(default)]
     pub showcases: ShowcaseState,
     #[serde(default = "default_pr_auto_description_enabled")]
@@ -63,11 +138,47 @@ pub struct Config {
     #[serde(default)]
     pub commit_reminder_prompt: Option<String>,
     #[serde(default)]
+    pub agent_identity_enabled: bool,
+    #[serde(default)]
+    pub agent_identity_template: Option<String>,
+    /// First prompt for workspaces started from a linked issue. Unset uses
+    /// `{title}\n\n{description}`.
+    #[serde(default)]
+    pub issue_prompt_template: Option<String>,
+    #[serde(default)]
+    pub conventional_commits: ConventionalCommitConfig,
+    /// Push branches to remotes that aren't a supported host instead of
+    /// refusing, leaving the pull request to be opened by hand.
+    #[serde(default)]
+    pub generic_git_fallback: bool,
+    #[serde(default = "default_exclude_agent_local_files")]
+    pub exclude_agent_local_files: bool,
+    #[serde(default)]
+    pub disallow_permission_bypass: bool,
+    /// Default for Claude Code's `disable_api_key` in profiles that don't
+    /// set it, so subscription users aren't billed through an API key.
+    #[serde(default)]
+    pub claude_disable_api_key: bool,
+    #[serde(default)]
+    pub strip_ansi_from_stored_logs: bool,
+    #[serde(default)]
+    pub cache_session_replays: bool,
+    #[serde(default)]
     pub send_message_shortcut: SendMessageShortcut,
     #[serde(default = "default_relay_enabled")]
     pub relay_enabled: bool,
     #[serde(default)]
     pub host_nickname: Option<String>,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Most coding agent runs at once across all workspaces; further runs
+    /// wait for a slot. Unset means no machine-wide limit.
+    #[serde(default)]
+    pub max_concurrent_executions: Option<u32>,
+    /// Path prefix to serve the app and API under, e.g. `/vibe` behind a
+    /// reverse proxy. Applied on the next start.
+    #[serde(default)]
+    pub base_path: Option<String>,
 }
 
 impl Config {
@@ -86,19 +197,34 @@ impl Config {
             editor: old_config.editor,
             github: old_config.github,
             analytics_enabled,
+            analytics_event_allowlist: None,
             workspace_dir: old_config.workspace_dir,
             last_app_version: old_config.last_app_version,
             show_release_notes: old_config.show_release_notes,
             language: old_config.language,
             git_branch_prefix: old_config.git_branch_prefix,
+            git_branch_template: None,
             showcases: old_config.showcases,
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
             commit_reminder_enabled: true,
             commit_reminder_prompt: None,
+            agent_identity_enabled: false,
+            agent_identity_template: None,
+            issue_prompt_template: None,
+            conventional_commits: ConventionalCommitConfig::default(),
+            generic_git_fallback: false,
+            exclude_agent_local_files: true,
+            disallow_permission_bypass: false,
+            claude_disable_api_key: false,
+            strip_ansi_from_stored_logs: false,
+            cache_session_replays: false,
             send_message_shortcut: SendMessageShortcut::default(),
             relay_enabled: true,
             host_nickname: None,
+            webhooks: Vec::new(),
+            max_concurrent_executions: None,
+            base_path: None,
         }
     }
 
@@ -142,19 +268,34 @@ impl Default for Config {
             editor: EditorConfig::default(),
             github: GitHubConfig::default(),
             analytics_enabled: true,
+            analytics_event_allowlist: None,
             workspace_dir: None,
             last_app_version: None,
             show_release_notes: false,
             language: UiLanguage::default(),
             git_branch_prefix: default_git_branch_prefix(),
+            git_branch_template: None,
             showcases: ShowcaseState::default(),
             pr_auto_description_enabled: true,
             pr_auto_description_prompt: None,
             commit_reminder_enabled: true,
             commit_reminder_prompt: None,
+            agent_identity_enabled: false,
+            agent_identity_template: None,
+            issue_prompt_template: None,
+            conventional_commits: ConventionalCommitConfig::default(),
+            generic_git_fallback: false,
+            exclude_agent_local_files: true,
+            disallow_permission_bypass: false,
+            claude_disable_api_key: false,
+            strip_ansi_from_stored_logs: false,
+            cache_session_replays: false,
             send_message_shortcut: SendMessageShortcut::default(),
             relay_enabled: true,
             host_nickname: None,
+            webhooks: Vec::new(),
+            max_concurrent_executions: None,
+            base_path: None,
         }
     }
 }
@@ -1,22 +1,26 @@
 use std::{
     collections::{HashMap, HashSet},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Weak},
 };
 
 use anyhow::{Error as AnyhowError, anyhow};
 use async_trait::async_trait;
+use chrono::Utc;
+use dashmap::{DashMap, mapref::entry::Entry};
 use db::{
     DBService,
     models::{
         coding_agent_turn::{CodingAgentTurn, CreateCodingAgentTurn},
         execution_process::{
             CreateExecutionProcess, ExecutionContext, ExecutionProcess, ExecutionProcessError,
-            ExecutionProcessRunReason, ExecutionProcessStatus,
+            ExecutionProcessResourceUsage, ExecutionProcessRunReason, ExecutionProcessStatus,
         },
         execution_process_repo_state::{
             CreateExecutionProcessRepoState, ExecutionProcessRepoState,
         },
+        merge_operation::{MergeOperation, MergeOperationKind},
+        pull_request::PullRequest,
         repo::Repo,
         session::{CreateSession, Session, SessionError},
         workspace::{Workspace, WorkspaceError},
@@ -38,18 +42,23 @@ use executors::{
         NormalizedEntry, NormalizedEntryError, NormalizedEntryType,
         utils::{
             ConversationPatch,
-            patch::{fix_patch_ops, is_add_or_replace, patch_entry_path},
+            patch::{TailBuffer, fix_patch_ops, is_add_or_replace, patch_entry_path},
         },
     },
     profile::{ExecutorConfig, ExecutorProfileId},
 };
 use futures::{StreamExt, future, stream::BoxStream};
-use git::{GitService, GitServiceError};
+use git::{GitService, GitServiceError, PathFilter};
 use json_patch::Patch;
+use once_cell::sync::Lazy;
+use serde::Serialize;
 use sqlx::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::RwLock, task::JoinHandle};
+use tokio_util::sync::CancellationToken;
+use ts_rs::TS;
 use utils::{
+    env::positive_or,
     log_msg::LogMsg,
     msg_store::MsgStore,
     text::{git_branch_id, short_uuid},
@@ -57,9 +66,127 @@ use utils::{
 use uuid::Uuid;
 use worktree_manager::WorktreeError;
 
-use crate::services::{execution_process, notification::NotificationService};
+use crate::services::{
+    approvals::Approvals,
+    attempt_limiter::AttemptPermit,
+    branch_template::{self, BranchTemplateConfig, BranchTemplateContext},
+    config::Config,
+    execution_process,
+    notification::NotificationService,
+    notification_template::{
+        self, DEFAULT_NOTIFICATION_BODY_TEMPLATE, DEFAULT_NOTIFICATION_TITLE_TEMPLATE,
+        NotificationTemplateContext,
+    },
+    outcome_summary::{self, OutcomeSummary},
+    proposed_changes,
+    renormalize_jobs::RenormalizeOutcome,
+    replay_cache,
+};
 pub type ContainerRef = String;
 
+const DEFAULT_ORPHAN_CLEANUP_CONCURRENCY: usize = 8;
+
+/// Raw-log normalizations in progress, by execution process id.
+static ACTIVE_REPLAYS: Lazy<DashMap<Uuid, Weak<MsgStore>>> = Lazy::new(DashMap::new);
+
+/// The replay of `id` already in progress, or `None` after registering
+/// `store` as it. Done under the map entry's lock, so concurrent replays can't
+/// both start normalizing.
+fn join_or_register_replay(id: Uuid, store: &Arc<MsgStore>) -> Option<Arc<MsgStore>> {
+    match ACTIVE_REPLAYS.entry(id) {
+        Entry::Occupied(mut entry) => {
+            let active = entry.get().upgrade();
+            if active.is_none() {
+                entry.insert(Arc::downgrade(store));
+            }
+            active
+        }
+        Entry::Vacant(entry) => {
+            entry.insert(Arc::downgrade(store));
+            None
+        }
+    }
+}
+
+fn unregister_replay(id: Uuid, store: &Arc<MsgStore>) {
+    ACTIVE_REPLAYS.remove_if(&id, |_, active| {
+        std::ptr::eq(active.as_ptr(), Arc::as_ptr(store))
+    });
+}
+
+/// The normalized patches in `store` up to [`LogMsg::Ready`], deduplicating
+/// consecutive patches that target the same path (only the final state matters
+/// for historical replay).
+fn replay_patches(store: Arc<MsgStore>) -> BoxStream<'static, Patch> {
+    enum PatchOrDone {
+        Patch(Patch),
+        Done,
+    }
+
+    let stream = store.history_plus_stream().filter_map(|msg| async move {
+        match msg {
+            Ok(LogMsg::JsonPatch(patch)) => Some(PatchOrDone::Patch(patch)),
+            Ok(LogMsg::Ready) => Some(PatchOrDone::Done),
+            _ => None,
+        }
+    });
+
+    futures::stream::unfold(
+        (stream.boxed(), None::<Patch>, HashSet::<String>::new()),
+        |(mut stream, buffered, mut sent_paths)| async move {
+            match stream.next().await {
+                Some(PatchOrDone::Patch(patch)) => {
+                    let Some(prev) = buffered else {
+                        // First patch — just buffer it
+                        return Some((None, (stream, Some(patch), sent_paths)));
+                    };
+                    if patch_entry_path(&patch) == patch_entry_path(&prev)
+                        && is_add_or_replace(&patch)
+                        && is_add_or_replace(&prev)
+                    {
+                        // Same path, both add/replace — replace buffer
+                        Some((None, (stream, Some(patch), sent_paths)))
+                    } else {
+                        // Different — emit prev, buffer new
+                        let prev = fix_patch_ops(prev, &mut sent_paths);
+                        Some((Some(prev), (stream, Some(patch), sent_paths)))
+                    }
+                }
+                Some(PatchOrDone::Done) | None => {
+                    // Sentinel or stream end: flush buffer and terminate
+                    if let Some(prev) = buffered {
+                        let prev = fix_patch_ops(prev, &mut sent_paths);
+                        return Some((Some(prev), (stream, None, sent_paths)));
+                    }
+                    None
+                }
+            }
+        },
+    )
+    .filter_map(|opt| async move { opt })
+    .boxed()
+}
+
+/// How many orphaned processes are finalised in parallel at startup.
+/// Override with `VK_ORPHAN_CLEANUP_CONCURRENCY`.
+fn orphan_cleanup_concurrency() -> usize {
+    positive_or(
+        "VK_ORPHAN_CLEANUP_CONCURRENCY",
+        DEFAULT_ORPHAN_CLEANUP_CONCURRENCY,
+    )
+}
+
+/// What [`ContainerService::cancel_execution`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CancelExecutionOutcome {
+    /// The process was still waiting for a concurrency slot and never started.
+    /// A worktree created only for it has been removed.
+    Dequeued,
+    /// The process was running and has been killed.
+    Killed,
+}
+
 #[derive(Debug, Error)]
 pub enum ContainerError {
     #[error(transparent)]
@@ -80,6 +207,18 @@ pub enum ContainerError {
     Io(#[from] std::io::Error),
     #[error("Failed to kill process: {0}")]
     KillFailed(std::io::Error),
+    #[error(
+        "Repository '{repo}' already has {limit} worktrees and none can be pruned. Delete or archive a workspace, or raise the limit in the repository settings."
+    )]
+    WorktreeLimitReached { repo: String, limit: i64 },
+    #[error(
+        "This task already has {limit} workspaces, the most repository '{repo}' allows. Continue in one of them with a follow-up, or raise the limit in the repository settings."
+    )]
+    AttemptLimitReached { repo: String, limit: i64 },
+    #[error(
+        "Preview workspaces only report proposed changes and can't take follow-ups. Start a new workspace to make the changes."
+    )]
+    PreviewFollowUp,
     #[error(transparent)]
     Other(#[from] AnyhowError), // Catches any unclassified errors
 }
@@ -94,6 +233,10 @@ pub trait ContainerService {
 
     fn notification_service(&self) -> &NotificationService;
 
+    fn config(&self) -> &Arc<RwLock<Config>>;
+
+    fn approvals(&self) -> &Approvals;
+
     async fn touch(&self, workspace: &Workspace) -> Result<(), ContainerError>;
 
     fn workspace_to_current_dir(&self, workspace: &Workspace) -> PathBuf;
@@ -241,21 +384,9 @@ pub trait ContainerService {
             return;
         }
 
-        let workspace_name = ctx
-            .workspace
-            .name
-            .as_deref()
-            .unwrap_or(&ctx.workspace.branch);
-        let title = format!("Workspace Complete: {}", workspace_name);
-        let message = match ctx.execution_process.status {
-            ExecutionProcessStatus::Completed => format!(
-                "✅ '{}' completed successfully\nBranch: {:?}\nExecutor: {:?}",
-                workspace_name, ctx.workspace.branch, ctx.session.executor
-            ),
-            ExecutionProcessStatus::Failed => format!(
-                "❌ '{}' execution failed\nBranch: {:?}\nExecutor: {:?}",
-                workspace_name, ctx.workspace.branch, ctx.session.executor
-            ),
+        let outcome = match ctx.execution_process.status {
+            ExecutionProcessStatus::Completed => "✅ Completed",
+            ExecutionProcessStatus::Failed => "❌ Failed",
             _ => {
                 tracing::warn!(
                     "Tried to notify workspace completion for {} but process is still running!",
@@ -264,67 +395,216 @@ pub trait ContainerService {
                 return;
             }
         };
-        self.notification_service()
-            .notify(&title, &message, Some(ctx.workspace.id))
-            .await;
+        let workspace_name = ctx
+            .workspace
+            .name
+            .as_deref()
+            .unwrap_or(&ctx.workspace.branch);
+        let duration = ctx
+            .execution_process
+            .completed_at
+            .and_then(|done| (done - ctx.execution_process.started_at).to_std().ok());
+        let pr_url =
+            match PullRequest::find_by_workspace_id(&self.db().pool, ctx.workspace.id).await {
+                Ok(prs) => prs.into_iter().next().map(|pr| pr.pr_url),
+                Err(e) => {
+                    tracing::warn!("Failed to load PRs for notification: {}", e);
+                    None
+                }
+            };
+        let summary = self.latest_outcome_summary(ctx).await;
+        let summary = summary.as_ref().map(OutcomeSummary::describe);
+        let template_ctx = NotificationTemplateContext {
+            task: workspace_name,
+            outcome,
+            duration,
+            pr_url: pr_url.as_deref(),
+            branch: &ctx.workspace.branch,
+            executor: ctx.session.executor.as_deref().unwrap_or("unknown"),
+            summary: summary.as_deref().unwrap_or_default(),
+        };
+
+        // Multi-repo workspaces use the first repo that customizes each part
+        let title_template = ctx
+            .repos
+            .iter()
+            .find_map(|repo| repo.notification_title_template.as_deref())
+            .unwrap_or(DEFAULT_NOTIFICATION_TITLE_TEMPLATE);
+        let body_template = ctx
+            .repos
+            .iter()
+            .find_map(|repo| repo.notification_body_template.as_deref())
+            .unwrap_or(DEFAULT_NOTIFICATION_BODY_TEMPLATE);
+        let title = notification_template::render_title(title_template, &template_ctx);
+        let message = notification_template::render(body_template, &template_ctx);
+
+        // Push delivery retries with backoff, so don't hold up finalization on it
+        let notifications = self.notification_service().clone();
+        let workspace_id = ctx.workspace.id;
+        tokio::spawn(async move {
+            notifications
+                .notify(&title, message.trim_end(), Some(workspace_id))
+                .await;
+        });
+    }
+
+    /// Outcome summary of the latest coding agent run in the finished session.
+    async fn latest_outcome_summary(&self, ctx: &ExecutionContext) -> Option<OutcomeSummary> {
+        let pool = &self.db().pool;
+        let process = ExecutionProcess::find_latest_by_workspace_and_run_reason(
+            pool,
+            ctx.workspace.id,
+            &ExecutionProcessRunReason::CodingAgent,
+        )
+        .await
+        .ok()
+        .flatten()
+        .filter(|process| process.session_id == ctx.session.id)?;
+        match outcome_summary::find(pool, process.id).await {
+            Ok(summary) => summary,
+            Err(e) => {
+                tracing::warn!("Failed to load outcome summary for notification: {}", e);
+                None
+            }
+        }
     }
 
     /// Cleanup executions marked as running in the db, call at startup
     async fn cleanup_orphan_executions(&self) -> Result<(), ContainerError> {
+        let started = std::time::Instant::now();
         let running_processes = ExecutionProcess::find_running(&self.db().pool).await?;
-        for process in running_processes {
+        if !running_processes.is_empty() {
+            let ids: Vec<Uuid> = running_processes.iter().map(|p| p.id).collect();
+            ExecutionProcess::mark_orphans_failed(&self.db().pool, &ids).await?;
+
+            // Capturing after-head commits hits git, so do several processes at a time.
+            futures::stream::iter(&running_processes)
+                .for_each_concurrent(orphan_cleanup_concurrency(), |process| {
+                    self.record_orphan_after_head_commits(process)
+                })
+                .await;
+
             tracing::info!(
-                "Found orphaned execution process {} for session {}",
-                process.id,
-                process.session_id
+                "Marked {} orphaned execution processes as failed in {:?}",
+                running_processes.len(),
+                started.elapsed()
             );
-            // Update the execution process status first
-            if let Err(e) = ExecutionProcess::update_completion(
-                &self.db().pool,
-                process.id,
-                ExecutionProcessStatus::Failed,
-                None, // No exit code for orphaned processes
-            )
-            .await
+        }
+
+        self.recover_interrupted_merge_operations().await?;
+        Ok(())
+    }
+
+    /// Capture the after-head commit OID per repository for an orphaned process.
+    async fn record_orphan_after_head_commits(&self, process: &ExecutionProcess) {
+        let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, process.id).await else {
+            return;
+        };
+        let Some(container_ref) = ctx.workspace.container_ref else {
+            return;
+        };
+        let workspace_root = PathBuf::from(container_ref);
+        for repo in &ctx.repos {
+            let git = self.git().clone();
+            let repo_path = workspace_root.join(&repo.name);
+            let head = tokio::task::spawn_blocking(move || git.get_head_info(&repo_path)).await;
+            if let Ok(Ok(head)) = head
+                && let Err(err) = ExecutionProcessRepoState::update_after_head_commit(
+                    &self.db().pool,
+                    process.id,
+                    repo.id,
+                    &head.oid,
+                )
+                .await
             {
-                tracing::error!(
-                    "Failed to update orphaned execution process {} status: {}",
+                tracing::warn!(
+                    "Failed to update after_head_commit for repo {} on process {}: {}",
+                    repo.id,
                     process.id,
-                    e
+                    err
                 );
-                continue;
             }
-            // Capture after-head commit OID per repository
-            if let Ok(ctx) = ExecutionProcess::load_context(&self.db().pool, process.id).await
-                && let Some(ref container_ref) = ctx.workspace.container_ref
-            {
-                let workspace_root = PathBuf::from(container_ref);
-                for repo in &ctx.repos {
-                    let repo_path = workspace_root.join(&repo.name);
-                    if let Ok(head) = self.git().get_head_info(&repo_path)
-                        && let Err(err) = ExecutionProcessRepoState::update_after_head_commit(
-                            &self.db().pool,
-                            process.id,
-                            repo.id,
-                            &head.oid,
-                        )
-                        .await
-                    {
-                        tracing::warn!(
-                            "Failed to update after_head_commit for repo {} on process {}: {}",
-                            repo.id,
-                            process.id,
-                            err
-                        );
-                    }
-                }
+        }
+    }
+
+    /// Resolve merge/rebase operations left in progress by a previous run.
+    /// Rebases only touch managed worktrees, so they are aborted to restore
+    /// the pre-rebase HEAD. Merges may have modified the user's own checkout
+    /// of the target branch, so they are flagged for the user to resolve.
+    async fn recover_interrupted_merge_operations(&self) -> Result<(), ContainerError> {
+        let pool = &self.db().pool;
+        for operation in MergeOperation::find_in_progress(pool).await? {
+            let recovered = match operation.operation {
+                MergeOperationKind::Rebase => self.abort_interrupted_rebase(&operation).await,
+                MergeOperationKind::Merge => false,
+            };
+
+            if recovered {
+                tracing::info!(
+                    "Rolled back interrupted rebase for workspace {} (repo {})",
+                    operation.workspace_id,
+                    operation.repo_id
+                );
+                MergeOperation::finish(pool, operation.id).await?;
+            } else {
+                tracing::warn!(
+                    "Interrupted {:?} into '{}' for workspace {} (repo {}) needs manual resolution; HEAD before the operation was {}",
+                    operation.operation,
+                    operation.target_branch,
+                    operation.workspace_id,
+                    operation.repo_id,
+                    operation.base_head
+                );
+                MergeOperation::mark_interrupted(pool, operation.id).await?;
             }
-            // Process marked as failed
-            tracing::info!("Marked orphaned execution process {} as failed", process.id);
         }
         Ok(())
     }
 
+    /// Abort a rebase left running in a workspace worktree. Returns false if
+    /// the worktree could not be restored.
+    async fn abort_interrupted_rebase(&self, operation: &MergeOperation) -> bool {
+        let pool = &self.db().pool;
+        let Ok(Some(workspace)) = Workspace::find_by_id(pool, operation.workspace_id).await else {
+            return false;
+        };
+        let Ok(Some(repo)) = Repo::find_by_id(pool, operation.repo_id).await else {
+            return false;
+        };
+        let Some(container_ref) = workspace.container_ref else {
+            // No worktree was ever created, so there is nothing to restore.
+            return true;
+        };
+
+        let worktree_path = PathBuf::from(container_ref).join(&repo.name);
+        if !worktree_path.exists() {
+            return true;
+        }
+
+        match self.git().is_rebase_in_progress(&worktree_path) {
+            Ok(false) => true,
+            Ok(true) => match self.git().abort_rebase(&worktree_path) {
+                Ok(()) => true,
+                Err(e) => {
+                    tracing::error!(
+                        "Failed to abort interrupted rebase in {}: {}",
+                        worktree_path.display(),
+                        e
+                    );
+                    false
+                }
+            },
+            Err(e) => {
+                tracing::error!(
+                    "Failed to inspect rebase state in {}: {}",
+                    worktree_path.display(),
+                    e
+                );
+                false
+            }
+        }
+    }
+
     /// Backfill before_head_commit for legacy execution processes.
     /// Rules:
     /// - If a process has after_head_commit and missing before_head_commit,
@@ -523,11 +803,62 @@ pub trait ContainerService {
         Ok(())
     }
 
+    /// Run the repo's post-merge script in its main checkout, if one is configured.
+    /// Its output shows up in the workspace's latest session like other scripts.
+    async fn try_run_post_merge_script(
+        &self,
+        workspace_id: Uuid,
+        repo: &Repo,
+    ) -> Result<(), ContainerError> {
+        let Some(script) = repo.post_merge_script.clone() else {
+            return Ok(());
+        };
+        let pool = &self.db().pool;
+        let workspace = Workspace::find_by_id(pool, workspace_id)
+            .await?
+            .ok_or(ContainerError::Other(anyhow!("Workspace not found")))?;
+        let action = ExecutorAction::new(
+            ExecutorActionType::ScriptRequest(ScriptRequest {
+                script,
+                language: ScriptRequestLanguage::Bash,
+                context: ScriptContext::PostMergeScript,
+                // Absolute, so it replaces the workspace dir rather than joining it.
+                working_dir: Some(repo.path.to_string_lossy().into_owned()),
+            }),
+            None,
+        );
+        let session = match Session::find_latest_by_workspace_id(pool, workspace.id).await? {
+            Some(s) => s,
+            None => {
+                Session::create(
+                    pool,
+                    &CreateSession {
+                        executor: None,
+                        name: None,
+                    },
+                    Uuid::new_v4(),
+                    workspace.id,
+                )
+                .await?
+            }
+        };
+        self.start_execution(
+            &workspace,
+            &session,
+            &action,
+            &ExecutionProcessRunReason::PostMergeScript,
+        )
+        .await?;
+
+        Ok(())
+    }
+
     /// Archive a workspace: set archived flag, stop running dev servers, and run archive script.
     async fn archive_workspace(&self, workspace_id: Uuid) -> Result<(), ContainerError> {
         let pool = &self.db().pool;
 
         Workspace::set_archived(pool, workspace_id, true).await?;
+        self.approvals().clear_workspace_denials(workspace_id);
 
         // Stop running dev servers
         if let Ok(dev_servers) =
@@ -705,6 +1036,8 @@ pub trait ContainerService {
     }
 
     async fn try_stop(&self, workspace: &Workspace, include_dev_server: bool) {
+        self.cancel_git_host_requests(workspace.id).await;
+
         // stop execution processes for this workspace's sessions
         let sessions = match Session::find_by_workspace_id(&self.db().pool, workspace.id).await {
             Ok(s) => s,
@@ -722,18 +1055,24 @@ pub trait ContainerService {
                     {
                         continue;
                     }
-                    if process.status == ExecutionProcessStatus::Running {
-                        self.stop_execution(&process, ExecutionProcessStatus::Killed)
-                            .await
-                            .unwrap_or_else(|e| {
-                                tracing::debug!(
-                                    "Failed to stop execution process {} for workspace {}: {}",
-                                    process.id,
-                                    workspace.id,
-                                    e
-                                );
-                            });
-                    }
+                    let stopped = match process.status {
+                        ExecutionProcessStatus::Running => {
+                            self.stop_execution(&process, ExecutionProcessStatus::Killed)
+                                .await
+                        }
+                        ExecutionProcessStatus::Queued => {
+                            self.cancel_execution(&process).await.map(|_| ())
+                        }
+                        _ => Ok(()),
+                    };
+                    stopped.unwrap_or_else(|e| {
+                        tracing::debug!(
+                            "Failed to stop execution process {} for workspace {}: {}",
+                            process.id,
+                            workspace.id,
+                            e
+                        );
+                    });
                 }
             }
         }
@@ -746,11 +1085,22 @@ pub trait ContainerService {
 
     async fn is_container_clean(&self, workspace: &Workspace) -> Result<bool, ContainerError>;
 
+    /// Take the coding agent concurrency slots of the workspace's repos and
+    /// the global limit without waiting. `None` if any of them is full.
+    async fn try_reserve_attempt_slots(
+        &self,
+        workspace: &Workspace,
+    ) -> Result<Option<Vec<AttemptPermit>>, ContainerError>;
+
+    /// Start the process for a freshly created execution. With
+    /// `attempt_permits` it spawns right away and returns any spawn error;
+    /// without them it is `Queued` and starts once a concurrency slot frees up.
     async fn start_execution_inner(
         &self,
         workspace: &Workspace,
         execution_process: &ExecutionProcess,
         executor_action: &ExecutorAction,
+        attempt_permits: Option<Vec<AttemptPermit>>,
     ) -> Result<(), ContainerError>;
 
     async fn stop_execution(
@@ -759,6 +1109,32 @@ pub trait ContainerService {
         status: ExecutionProcessStatus,
     ) -> Result<(), ContainerError>;
 
+    /// Cancel an execution whether it is still queued behind the repo's
+    /// concurrency limit or already running.
+    async fn cancel_execution(
+        &self,
+        execution_process: &ExecutionProcess,
+    ) -> Result<CancelExecutionOutcome, ContainerError>;
+
+    /// Token for git host requests (e.g. PR creation) made on behalf of a
+    /// workspace. It fires when the workspace is stopped.
+    async fn git_host_cancellation(&self, workspace_id: Uuid) -> CancellationToken;
+
+    /// Abort the workspace's in-flight git host requests, including ones
+    /// waiting to retry.
+    async fn cancel_git_host_requests(&self, workspace_id: Uuid);
+
+    /// Peak CPU/memory sampled so far for a running execution. Returns `None`
+    /// once the execution has finished; the final values are on its record.
+    async fn live_resource_usage(
+        &self,
+        execution_process_id: &Uuid,
+    ) -> Option<ExecutionProcessResourceUsage>;
+
+    /// Deliver an extra user message to a running execution without waiting for it
+    /// to finish. Returns `false` if the executor cannot accept messages mid-run.
+    async fn inject_message(&self, execution_process_id: &Uuid, message: String) -> bool;
+
     async fn try_commit_changes(&self, ctx: &ExecutionContext) -> Result<bool, ContainerError>;
 
     async fn copy_project_files(
@@ -769,10 +1145,16 @@ pub trait ContainerService {
     ) -> Result<(), ContainerError>;
 
     /// Stream diff updates as LogMsg for WebSocket endpoints.
+    ///
+    /// With `since_execution_id`, each repo is diffed against the HEAD that
+    /// execution started from, so the stream shows only that run's edits.
+    /// With `path_filter`, only files matching it are streamed.
     async fn stream_diff(
         &self,
         workspace: &Workspace,
         stats_only: bool,
+        since_execution_id: Option<Uuid>,
+        path_filter: Option<PathFilter>,
     ) -> Result<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>, ContainerError>;
 
     /// Fetch the MsgStore for a given execution ID, panicking if missing.
@@ -783,14 +1165,34 @@ pub trait ContainerService {
 
     async fn git_branch_prefix(&self) -> String;
 
+    /// The configured branch name template and the author it renders with.
+    async fn git_branch_template(&self) -> Option<BranchTemplateConfig>;
+
     async fn git_branch_from_workspace(&self, workspace_id: &Uuid, task_title: &str) -> String {
-        let task_title_id = git_branch_id(task_title);
+        let templated = self.git_branch_template().await.and_then(|config| {
+            let ctx = BranchTemplateContext {
+                task_id: *workspace_id,
+                task_title,
+                author: config.author.as_deref(),
+                date: Utc::now().date_naive(),
+            };
+            match branch_template::render_branch_name(&config.template, &ctx) {
+                Ok(name) => Some(name).filter(|name| !name.is_empty()),
+                Err(e) => {
+                    tracing::warn!("Ignoring branch name template: {e}");
+                    None
+                }
+            }
+        });
+        let name = templated.unwrap_or_else(|| {
+            format!("{}-{}", short_uuid(workspace_id), git_branch_id(task_title))
+        });
         let prefix = self.git_branch_prefix().await;
 
         if prefix.is_empty() {
-            format!("{}-{}", short_uuid(workspace_id), task_title_id)
+            name
         } else {
-            format!("{}/{}-{}", prefix, short_uuid(workspace_id), task_title_id)
+            format!("{}/{}", prefix, name)
         }
     }
 
@@ -843,212 +1245,349 @@ pub trait ContainerService {
                     .boxed(),
             )
         } else {
-            let raw_messages =
-                execution_process::load_raw_log_messages(&self.db().pool, *id).await?;
-
-            // Create temporary store and populate
-            // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
-            let temp_store = Arc::new(MsgStore::new());
-            for msg in raw_messages {
-                if matches!(
-                    msg,
-                    LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
-                ) {
-                    temp_store.push(msg);
+            let cache_replays = self.config().read().await.cache_session_replays;
+            if cache_replays && let Some(cached) = replay_cache::open(*id).await {
+                return Some(
+                    cached
+                        .map(|patch| Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch)))
+                        .chain(futures::stream::once(async {
+                            Ok::<_, std::io::Error>(LogMsg::Finished)
+                        }))
+                        .boxed(),
+                );
+            }
+
+            // Concurrent replays of the same process, e.g. from several open
+            // tabs, share one normalization run instead of each re-reading
+            // and re-normalizing the raw log.
+            let store = Arc::new(MsgStore::new());
+            let (temp_store, write_cache) = match join_or_register_replay(*id, &store) {
+                Some(active) => (active, false),
+                None => {
+                    let Some(status) = self.normalize_raw_logs(id, store.clone()).await else {
+                        // Release anyone who joined in the meantime.
+                        store.push(LogMsg::Ready);
+                        unregister_replay(*id, &store);
+                        return None;
+                    };
+                    (
+                        store,
+                        cache_replays
+                            && !matches!(
+                                status,
+                                ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+                            ),
+                    )
+                }
+            };
+
+            let deduped = replay_patches(temp_store);
+
+            // Tee the replay into the cache. Only finished processes are cached,
+            // and a replay the client abandons midway is never published.
+            let cache_writer = if write_cache {
+                match replay_cache::ReplayCacheWriter::create(*id).await {
+                    Ok(writer) => Some(writer),
+                    Err(e) => {
+                        tracing::warn!("Failed to create replay cache for {}: {}", id, e);
+                        None
+                    }
                 }
+            } else {
+                None
+            };
+            let cache_writer = Arc::new(tokio::sync::Mutex::new(cache_writer));
+
+            let deduped = deduped
+                .then({
+                    let cache_writer = cache_writer.clone();
+                    move |patch| {
+                        let cache_writer = cache_writer.clone();
+                        async move {
+                            let mut guard = cache_writer.lock().await;
+                            if let Some(writer) = guard.as_mut()
+                                && let Err(e) = writer.append(&patch).await
+                            {
+                                tracing::warn!("Failed to write replay cache: {}", e);
+                                *guard = None;
+                            }
+                            Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch))
+                        }
+                    }
+                })
+                .chain(futures::stream::once(async move {
+                    if let Some(writer) = cache_writer.lock().await.take()
+                        && let Err(e) = writer.finish().await
+                    {
+                        tracing::warn!("Failed to publish replay cache: {}", e);
+                    }
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }));
+
+            Some(deduped.boxed())
+        }
+    }
+
+    /// Normalize a finished process's stored raw log into `temp_store`, which
+    /// receives [`LogMsg::Ready`] once normalization completes. Returns the
+    /// process status.
+    async fn normalize_raw_logs(
+        &self,
+        id: &Uuid,
+        temp_store: Arc<MsgStore>,
+    ) -> Option<ExecutionProcessStatus> {
+        let raw_messages = execution_process::load_raw_log_messages(&self.db().pool, *id).await?;
+
+        // Populate the store
+        // Include JsonPatch messages (already normalized) and Stdout/Stderr (need normalization)
+        for msg in raw_messages {
+            if matches!(
+                msg,
+                LogMsg::Stdout(_) | LogMsg::Stderr(_) | LogMsg::JsonPatch(_)
+            ) {
+                temp_store.push(msg);
             }
-            temp_store.push_finished();
+        }
+        temp_store.push_finished();
 
-            let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
-                Ok(Some(process)) => process,
+        let process = match ExecutionProcess::find_by_id(&self.db().pool, *id).await {
+            Ok(Some(process)) => process,
+            Ok(None) => {
+                tracing::error!("No execution process found for ID: {}", id);
+                return None;
+            }
+            Err(e) => {
+                tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                return None;
+            }
+        };
+
+        // Get the workspace to determine correct directory
+        let (workspace, _session) =
+            match process.parent_workspace_and_session(&self.db().pool).await {
+                Ok(Some((workspace, session))) => (workspace, session),
                 Ok(None) => {
-                    tracing::error!("No execution process found for ID: {}", id);
+                    tracing::error!(
+                        "No workspace/session found for session ID: {}",
+                        process.session_id
+                    );
                     return None;
                 }
                 Err(e) => {
-                    tracing::error!("Failed to fetch execution process {}: {}", id, e);
+                    tracing::error!(
+                        "Failed to fetch workspace for session {}: {}",
+                        process.session_id,
+                        e
+                    );
                     return None;
                 }
             };
 
-            // Get the workspace to determine correct directory
-            let (workspace, _session) =
-                match process.parent_workspace_and_session(&self.db().pool).await {
-                    Ok(Some((workspace, session))) => (workspace, session),
-                    Ok(None) => {
-                        tracing::error!(
-                            "No workspace/session found for session ID: {}",
-                            process.session_id
-                        );
-                        return None;
-                    }
-                    Err(e) => {
-                        tracing::error!(
-                            "Failed to fetch workspace for session {}: {}",
-                            process.session_id,
-                            e
-                        );
-                        return None;
-                    }
-                };
-
-            if let Err(err) = self.ensure_container_exists(&workspace).await {
-                tracing::warn!(
-                    "Failed to recreate worktree before log normalization for workspace {}: {}",
-                    workspace.id,
-                    err
-                );
-            }
+        if let Err(err) = self.ensure_container_exists(&workspace).await {
+            tracing::warn!(
+                "Failed to recreate worktree before log normalization for workspace {}: {}",
+                workspace.id,
+                err
+            );
+        }
 
-            let current_dir = self.workspace_to_current_dir(&workspace);
+        let current_dir = self.workspace_to_current_dir(&workspace);
 
-            let executor_action = if let Ok(executor_action) = process.executor_action() {
-                executor_action
-            } else {
-                tracing::error!(
-                    "Failed to parse executor action: {:?}",
-                    process.executor_action()
-                );
-                return None;
-            };
+        let executor_action = if let Ok(executor_action) = process.executor_action() {
+            executor_action
+        } else {
+            tracing::error!(
+                "Failed to parse executor action: {:?}",
+                process.executor_action()
+            );
+            return None;
+        };
 
-            // Spawn normalizer on populated store and collect JoinHandles
-            let handles = match executor_action.typ() {
-                ExecutorActionType::CodingAgentInitialRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_config.profile_id());
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
-                    }
+        // Spawn normalizer on populated store and collect JoinHandles
+        let handles = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(request) => {
+                #[cfg(feature = "qa-mode")]
+                {
+                    let executor = QaMockExecutor;
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir))
                 }
-                ExecutorActionType::CodingAgentFollowUpRequest(request) => {
-                    #[cfg(feature = "qa-mode")]
-                    {
-                        let executor = QaMockExecutor;
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
-                    }
-                    #[cfg(not(feature = "qa-mode"))]
-                    {
-                        let executor = ExecutorConfigs::get_cached()
-                            .get_coding_agent_or_default(&request.executor_config.profile_id());
-                        executor.normalize_logs(
-                            temp_store.clone(),
-                            &request.effective_dir(&current_dir),
-                        )
-                    }
+                #[cfg(not(feature = "qa-mode"))]
+                {
+                    let executor = ExecutorConfigs::get_cached()
+                        .get_coding_agent_or_default(&request.executor_config.profile_id());
+                    executor.normalize_logs_with_profile(
+                        temp_store.clone(),
+                        &request.effective_dir(&current_dir),
+                    )
                 }
+            }
+            ExecutorActionType::CodingAgentFollowUpRequest(request) => {
                 #[cfg(feature = "qa-mode")]
-                ExecutorActionType::ReviewRequest(_request) => {
+                {
                     let executor = QaMockExecutor;
-                    executor.normalize_logs(temp_store.clone(), &current_dir)
+                    executor
+                        .normalize_logs(temp_store.clone(), &request.effective_dir(&current_dir))
                 }
                 #[cfg(not(feature = "qa-mode"))]
-                ExecutorActionType::ReviewRequest(request) => {
+                {
                     let executor = ExecutorConfigs::get_cached()
                         .get_coding_agent_or_default(&request.executor_config.profile_id());
-                    executor.normalize_logs(temp_store.clone(), &current_dir)
+                    executor.normalize_logs_with_profile(
+                        temp_store.clone(),
+                        &request.effective_dir(&current_dir),
+                    )
                 }
-                _ => {
-                    tracing::debug!(
-                        "Executor action doesn't support log normalization: {:?}",
-                        process.executor_action()
-                    );
-                    return None;
+            }
+            #[cfg(feature = "qa-mode")]
+            ExecutorActionType::ReviewRequest(_request) => {
+                let executor = QaMockExecutor;
+                executor.normalize_logs(temp_store.clone(), &current_dir)
+            }
+            #[cfg(not(feature = "qa-mode"))]
+            ExecutorActionType::ReviewRequest(request) => {
+                let executor = ExecutorConfigs::get_cached()
+                    .get_coding_agent_or_default(&request.executor_config.profile_id());
+                executor.normalize_logs_with_profile(temp_store.clone(), &current_dir)
+            }
+            _ => {
+                tracing::debug!(
+                    "Executor action doesn't support log normalization: {:?}",
+                    process.executor_action()
+                );
+                return None;
+            }
+        };
+
+        // Await all normalizer tasks, then push Ready so the dedup
+        // stream knows when to flush its buffer and terminate.
+        {
+            let store = temp_store.clone();
+            let id = *id;
+            tokio::spawn(async move {
+                for handle in handles {
+                    let _ = handle.await;
                 }
-            };
+                store.push(LogMsg::Ready);
+                unregister_replay(id, &store);
+            });
+        }
 
-            // Await all normalizer tasks, then push Ready so the dedup
-            // stream knows when to flush its buffer and terminate.
-            {
-                let store = temp_store.clone();
-                tokio::spawn(async move {
-                    for handle in handles {
-                        let _ = handle.await;
-                    }
-                    store.push(LogMsg::Ready);
-                });
-            }
+        Some(process.status)
+    }
 
-            // Stream normalized patches, deduplicating consecutive patches
-            // that target the same path (only the final state matters for
-            // historical replay). The Ready sentinel flushes the buffer.
-            enum PatchOrDone {
-                Patch(Patch),
-                Done,
-            }
+    /// Re-run normalization for a finished process from its raw log, replacing
+    /// its replay cache entry. Processes that haven't finished are skipped.
+    async fn renormalize_process(&self, id: Uuid) -> Result<RenormalizeOutcome, ContainerError> {
+        let process = ExecutionProcess::find_by_id(&self.db().pool, id)
+            .await?
+            .ok_or_else(|| ContainerError::Other(anyhow!("Execution process {id} not found")))?;
+        if matches!(
+            process.status,
+            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+        ) || self.get_msg_store_by_id(&id).await.is_some()
+        {
+            return Ok(RenormalizeOutcome::Skipped);
+        }
 
-            let stream = temp_store
-                .history_plus_stream()
-                .filter_map(|msg| async move {
+        let store = Arc::new(MsgStore::new());
+        self.normalize_raw_logs(&id, store.clone())
+            .await
+            .ok_or_else(|| {
+                ContainerError::Other(anyhow!("No logs found for execution process {id}"))
+            })?;
+        replay_cache::invalidate(id).await;
+        let mut writer = replay_cache::ReplayCacheWriter::create(id).await?;
+        let mut patches = replay_patches(store);
+        while let Some(patch) = patches.next().await {
+            writer.append(&patch).await?;
+        }
+        writer.finish().await?;
+        Ok(RenormalizeOutcome::Renormalized)
+    }
+
+    /// Like [`Self::stream_normalized_logs`], but replays only the last `tail`
+    /// entries before switching to live updates. Entry indices are shifted so
+    /// the replayed tail starts at 0.
+    async fn stream_normalized_logs_tail(
+        &self,
+        id: &Uuid,
+        tail: usize,
+    ) -> Option<futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>> {
+        let mut buffer = TailBuffer::new(tail);
+        let live = match self.get_msg_store_by_id(id).await {
+            Some(store) => {
+                let (history, live) = store.history_and_live();
+                let mut finished = false;
+                for msg in history {
                     match msg {
-                        Ok(LogMsg::JsonPatch(patch)) => Some(PatchOrDone::Patch(patch)),
-                        Ok(LogMsg::Ready) => Some(PatchOrDone::Done),
-                        _ => None,
-                    }
-                });
-
-            let deduped = futures::stream::unfold(
-                (stream.boxed(), None::<Patch>, HashSet::<String>::new()),
-                |(mut stream, buffered, mut sent_paths)| async move {
-                    match stream.next().await {
-                        Some(PatchOrDone::Patch(patch)) => {
-                            let Some(prev) = buffered else {
-                                // First patch — just buffer it
-                                return Some((None, (stream, Some(patch), sent_paths)));
-                            };
-                            if patch_entry_path(&patch) == patch_entry_path(&prev)
-                                && is_add_or_replace(&patch)
-                                && is_add_or_replace(&prev)
-                            {
-                                // Same path, both add/replace — replace buffer
-                                Some((None, (stream, Some(patch), sent_paths)))
-                            } else {
-                                // Different — emit prev, buffer new
-                                let prev = fix_patch_ops(prev, &mut sent_paths);
-                                Some((Some(prev), (stream, Some(patch), sent_paths)))
-                            }
-                        }
-                        Some(PatchOrDone::Done) | None => {
-                            // Sentinel or stream end: flush buffer and terminate
-                            if let Some(prev) = buffered {
-                                let prev = fix_patch_ops(prev, &mut sent_paths);
-                                return Some((Some(prev), (stream, None, sent_paths)));
-                            }
-                            None
+                        LogMsg::JsonPatch(patch) => buffer.push(patch),
+                        LogMsg::Finished => {
+                            finished = true;
+                            break;
                         }
+                        _ => {}
                     }
-                },
-            )
-            .filter_map(|opt| async move { opt })
-            .map(|p| Ok::<_, std::io::Error>(LogMsg::JsonPatch(p)))
-            .chain(futures::stream::once(async {
-                Ok::<_, std::io::Error>(LogMsg::Finished)
-            }));
+                }
+                (!finished).then_some(live)
+            }
+            // Replays of finished processes end on their own; only the patches
+            // the tail needs are kept while reading them.
+            None => {
+                let mut replay = self.stream_normalized_logs(id).await?;
+                while let Some(msg) = replay.next().await {
+                    match msg {
+                        Ok(LogMsg::JsonPatch(patch)) => buffer.push(patch),
+                        Ok(LogMsg::Finished) => break,
+                        _ => {}
+                    }
+                }
+                None
+            }
+        };
+        let (entries_tail, patches) = buffer.finish();
 
-            Some(deduped.boxed())
-        }
+        let replay = futures::stream::iter(
+            patches
+                .into_iter()
+                .filter_map(move |patch| entries_tail.apply(patch))
+                .map(|patch| Ok::<_, std::io::Error>(LogMsg::JsonPatch(patch))),
+        );
+        let live = match live {
+            Some(live) => live
+                .take_while(|msg| future::ready(!matches!(msg, Ok(LogMsg::Finished))))
+                .filter_map(move |msg| {
+                    future::ready(match msg {
+                        Ok(LogMsg::JsonPatch(patch)) => {
+                            entries_tail.apply(patch).map(|p| Ok(LogMsg::JsonPatch(p)))
+                        }
+                        Ok(_) => None,
+                        Err(e) => Some(Err(e)),
+                    })
+                })
+                .boxed(),
+            _ => futures::stream::empty().boxed(),
+        };
+
+        Some(
+            replay
+                .chain(live)
+                .chain(futures::stream::once(async {
+                    Ok::<_, std::io::Error>(LogMsg::Finished)
+                }))
+                .boxed(),
+        )
     }
 
+    /// Set up the workspace and start its first coding agent run. A
+    /// `preview` run skips the cleanup script and its worktree changes are
+    /// discarded once it exits.
     async fn start_workspace(
         &self,
         workspace: &Workspace,
         executor_config: ExecutorConfig,
         prompt: String,
+        preview: bool,
     ) -> Result<ExecutionProcess, ContainerError> {
         // Create container
         self.create(workspace).await?;
@@ -1075,7 +1614,11 @@ pub trait ContainerService {
 
         let all_parallel = repos_with_setup.iter().all(|r| r.parallel_setup_script);
 
-        let cleanup_action = self.cleanup_actions_for_repos(&repos);
+        let cleanup_action = if preview {
+            None
+        } else {
+            self.cleanup_actions_for_repos(&repos)
+        };
 
         let working_dir = session
             .agent_working_dir
@@ -1088,6 +1631,7 @@ pub trait ContainerService {
                 prompt,
                 executor_config: executor_config.clone(),
                 working_dir,
+                preview,
             }),
             cleanup_action.map(Box::new),
         );
@@ -1146,6 +1690,12 @@ pub trait ContainerService {
                 "Workspace has no repositories configured"
             )));
         }
+        if matches!(run_reason, ExecutionProcessRunReason::CodingAgent)
+            && !executor_action.is_preview()
+            && proposed_changes::is_preview_workspace(&self.db().pool, workspace.id).await?
+        {
+            return Err(ContainerError::PreviewFollowUp);
+        }
 
         let workspace_root = workspace
             .container_ref
@@ -1164,10 +1714,25 @@ pub trait ContainerService {
                 merge_commit: None,
             });
         }
+        // Coding agents only wait when a concurrency limit is saturated.
+        let attempt_permits = match executor_action.typ() {
+            ExecutorActionType::CodingAgentInitialRequest(_)
+            | ExecutorActionType::CodingAgentFollowUpRequest(_)
+            | ExecutorActionType::ReviewRequest(_) => {
+                self.try_reserve_attempt_slots(workspace).await?
+            }
+            ExecutorActionType::ScriptRequest(_) => Some(Vec::new()),
+        };
+        let status = if attempt_permits.is_some() {
+            ExecutionProcessStatus::Running
+        } else {
+            ExecutionProcessStatus::Queued
+        };
         let create_execution_process = CreateExecutionProcess {
             session_id: session.id,
             executor_action: executor_action.clone(),
             run_reason: run_reason.clone(),
+            status,
         };
 
         let execution_process = ExecutionProcess::create(
@@ -1181,8 +1746,10 @@ pub trait ContainerService {
             .write()
             .await
             .insert(execution_process.id, Arc::new(MsgStore::new()));
-        if *run_reason != ExecutionProcessRunReason::ArchiveScript
-            && let Err(e) = Workspace::set_archived(&self.db().pool, workspace.id, false).await
+        if !matches!(
+            run_reason,
+            ExecutionProcessRunReason::ArchiveScript | ExecutionProcessRunReason::PostMergeScript
+        ) && let Err(e) = Workspace::set_archived(&self.db().pool, workspace.id, false).await
         {
             self.msg_stores()
                 .write()
@@ -1226,72 +1793,20 @@ pub trait ContainerService {
         }
 
         if let Err(start_error) = self
-            .start_execution_inner(workspace, &execution_process, executor_action)
+            .start_execution_inner(
+                workspace,
+                &execution_process,
+                executor_action,
+                attempt_permits,
+            )
             .await
         {
             self.msg_stores()
                 .write()
                 .await
                 .remove(&execution_process.id);
-            // Mark process as failed
-            if let Err(update_error) = ExecutionProcess::update_completion(
-                &self.db().pool,
-                execution_process.id,
-                ExecutionProcessStatus::Failed,
-                None,
-            )
-            .await
-            {
-                tracing::error!(
-                    "Failed to mark execution process {} as failed after start error: {}",
-                    execution_process.id,
-                    update_error
-                );
-            }
-            // Emit stderr error message
-            let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
-            if let Err(e) = execution_process::append_log_message(
-                session.id,
-                execution_process.id,
-                &log_message,
-            )
-            .await
-            {
-                tracing::error!(
-                    "Failed to write error log for execution {}: {}",
-                    execution_process.id,
-                    e
-                );
-            }
-
-            // Emit NextAction with failure context for coding agent requests
-            if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
-                &start_error
-            {
-                let help_text = format!("The required executable `{program}` is not installed.");
-                let error_message = NormalizedEntry {
-                    timestamp: None,
-                    entry_type: NormalizedEntryType::ErrorMessage {
-                        error_type: NormalizedEntryError::SetupRequired,
-                    },
-                    content: help_text,
-                    metadata: None,
-                };
-                let patch = ConversationPatch::add_normalized_entry(2, error_message);
-                if let Err(e) = execution_process::append_log_message(
-                    session.id,
-                    execution_process.id,
-                    &LogMsg::JsonPatch(patch),
-                )
-                .await
-                {
-                    tracing::error!(
-                        "Failed to write setup-required log for execution {}: {}",
-                        execution_process.id,
-                        e
-                    );
-                }
-            };
+            self.record_start_failure(session.id, execution_process.id, &start_error)
+                .await;
             return Err(start_error);
         }
 
@@ -1336,7 +1851,7 @@ pub trait ContainerService {
                 if let Some(executor) =
                     ExecutorConfigs::get_cached().get_coding_agent(&executor_profile_id)
                 {
-                    let _ = executor.normalize_logs(msg_store, &working_dir);
+                    let _ = executor.normalize_logs_with_profile(msg_store, &working_dir);
                 } else {
                     tracing::error!(
                         "Failed to resolve profile '{:?}' for normalization",
@@ -1346,15 +1861,83 @@ pub trait ContainerService {
             }
         }
 
+        let strip_ansi = self.config().read().await.strip_ansi_from_stored_logs;
         execution_process::spawn_stream_raw_logs_to_storage(
             self.msg_stores().clone(),
             self.db().clone(),
             execution_process.id,
             session.id,
+            strip_ansi,
         );
         Ok(execution_process)
     }
 
+    /// Mark an execution that failed to start as failed and explain why in
+    /// its log.
+    async fn record_start_failure(
+        &self,
+        session_id: Uuid,
+        execution_process_id: Uuid,
+        start_error: &ContainerError,
+    ) {
+        // Mark process as failed
+        if let Err(update_error) = ExecutionProcess::update_completion(
+            &self.db().pool,
+            execution_process_id,
+            ExecutionProcessStatus::Failed,
+            None,
+        )
+        .await
+        {
+            tracing::error!(
+                "Failed to mark execution process {} as failed after start error: {}",
+                execution_process_id,
+                update_error
+            );
+        }
+        // Emit stderr error message
+        let log_message = LogMsg::Stderr(format!("Failed to start execution: {start_error}"));
+        if let Err(e) =
+            execution_process::append_log_message(session_id, execution_process_id, &log_message)
+                .await
+        {
+            tracing::error!(
+                "Failed to write error log for execution {}: {}",
+                execution_process_id,
+                e
+            );
+        }
+
+        // Emit NextAction with failure context for coding agent requests
+        if let ContainerError::ExecutorError(ExecutorError::ExecutableNotFound { program }) =
+            start_error
+        {
+            let help_text = format!("The required executable `{program}` is not installed.");
+            let error_message = NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ErrorMessage {
+                    error_type: NormalizedEntryError::SetupRequired,
+                },
+                content: help_text,
+                metadata: None,
+            };
+            let patch = ConversationPatch::add_normalized_entry(2, error_message);
+            if let Err(e) = execution_process::append_log_message(
+                session_id,
+                execution_process_id,
+                &LogMsg::JsonPatch(patch),
+            )
+            .await
+            {
+                tracing::error!(
+                    "Failed to write setup-required log for execution {}: {}",
+                    execution_process_id,
+                    e
+                );
+            }
+        }
+    }
+
     async fn try_start_next_action(&self, ctx: &ExecutionContext) -> Result<(), ContainerError> {
         let action = ctx.execution_process.executor_action()?;
         let next_action = if let Some(next_action) = action.next_action() {
@@ -0,0 +1,225 @@
+//! Conventional-commit checks for commit messages written on behalf of the
+//! agent, driven by [`ConventionalCommitConfig`].
+
+use thiserror::Error;
+
+use crate::services::config::{ConventionalCommitConfig, ConventionalCommitMode};
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ConventionalCommitViolation {
+    #[error("subject line is not in `type(scope): subject` form")]
+    Malformed,
+    #[error("commit type `{0}` is not allowed")]
+    DisallowedType(String),
+    #[error("subject line is {len} characters, more than the allowed {max}")]
+    SubjectTooLong { len: usize, max: usize },
+}
+
+/// Split a subject line into its type and description, if it is a
+/// conventional-commit header.
+fn parse_header(line: &str) -> Option<(&str, &str)> {
+    let (prefix, description) = line.split_once(": ")?;
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+    let commit_type = match prefix.split_once('(') {
+        Some((commit_type, scope)) => {
+            let scope = scope.strip_suffix(')')?;
+            if scope.is_empty() || scope.contains(['(', ')']) || scope.contains(char::is_whitespace)
+            {
+                return None;
+            }
+            commit_type
+        }
+        None => prefix,
+    };
+
+    let valid_type = !commit_type.is_empty()
+        && commit_type
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c == '-');
+    if !valid_type || description.trim().is_empty() {
+        return None;
+    }
+    Some((commit_type, description))
+}
+
+/// Configured types, ignoring blank entries. Empty means any type is allowed.
+fn allowed_types(config: &ConventionalCommitConfig) -> Vec<&str> {
+    config
+        .allowed_types
+        .iter()
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .collect()
+}
+
+/// Check `message` against the configured rules, regardless of mode.
+pub fn validate(
+    message: &str,
+    config: &ConventionalCommitConfig,
+) -> Result<(), ConventionalCommitViolation> {
+    let subject = message.lines().next().unwrap_or_default().trim_end();
+    let (commit_type, _) = parse_header(subject).ok_or(ConventionalCommitViolation::Malformed)?;
+    let allowed = allowed_types(config);
+    if !allowed.is_empty() && !allowed.contains(&commit_type) {
+        return Err(ConventionalCommitViolation::DisallowedType(
+            commit_type.to_string(),
+        ));
+    }
+    let len = subject.chars().count();
+    if config.max_subject_length > 0 && len > config.max_subject_length {
+        return Err(ConventionalCommitViolation::SubjectTooLong {
+            len,
+            max: config.max_subject_length,
+        });
+    }
+    Ok(())
+}
+
+/// Use `preferred` if allowed, otherwise the first allowed type.
+fn pick_type<'a>(preferred: &'a str, config: &'a ConventionalCommitConfig) -> &'a str {
+    let allowed = allowed_types(config);
+    match allowed.first() {
+        Some(first) if !allowed.contains(&preferred) => first,
+        _ => preferred,
+    }
+}
+
+/// Rewrite `message` into a valid header using `commit_type`. A subject that
+/// had to be shortened is kept in full at the top of the body.
+fn conform(message: &str, config: &ConventionalCommitConfig, commit_type: &str) -> String {
+    let (subject, body) = match message.split_once('\n') {
+        Some((subject, body)) => (subject.trim_end(), body.trim_start_matches('\n')),
+        None => (message.trim_end(), ""),
+    };
+    let description = match parse_header(subject) {
+        Some((_, description)) => description,
+        None => subject.trim(),
+    };
+    let commit_type = pick_type(commit_type, config);
+    let header = format!("{commit_type}: {description}");
+
+    let max = config.max_subject_length;
+    if max == 0 || header.chars().count() <= max {
+        return match body {
+            "" => header,
+            body => format!("{header}\n\n{body}"),
+        };
+    }
+
+    let ellipsis = "...";
+    let keep = max.saturating_sub(ellipsis.len());
+    let cut: String = header.chars().take(keep).collect();
+    let cut = match cut.rfind(' ') {
+        Some(idx) if idx > commit_type.len() + 1 => &cut[..idx],
+        _ => cut.as_str(),
+    };
+    let mut out = format!("{}{ellipsis}\n\n{description}", cut.trim_end());
+    if !body.is_empty() {
+        out.push_str("\n\n");
+        out.push_str(body);
+    }
+    out
+}
+
+/// Apply the configured conventional-commit mode to `message`.
+///
+/// `commit_type` is the type to use when one has to be added, and `fallback`
+/// produces the message used when a non-conforming one is rejected.
+pub fn enforce(
+    message: &str,
+    config: &ConventionalCommitConfig,
+    commit_type: &str,
+    fallback: impl FnOnce() -> String,
+) -> String {
+    if config.mode == ConventionalCommitMode::Off {
+        return message.to_string();
+    }
+    let Err(violation) = validate(message, config) else {
+        return message.to_string();
+    };
+
+    match config.mode {
+        ConventionalCommitMode::Off => message.to_string(),
+        ConventionalCommitMode::Reject => {
+            tracing::info!("Rejected commit message ({violation}), using the default message");
+            conform(&fallback(), config, commit_type)
+        }
+        ConventionalCommitMode::AutoPrefix => {
+            tracing::debug!("Rewriting commit message ({violation})");
+            conform(message, config, commit_type)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(mode: ConventionalCommitMode) -> ConventionalCommitConfig {
+        ConventionalCommitConfig {
+            mode,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn validates_headers() {
+        let config = config(ConventionalCommitMode::Reject);
+        assert_eq!(validate("feat(api): add endpoint", &config), Ok(()));
+        assert_eq!(validate("fix!: drop legacy flag\n\nbody", &config), Ok(()));
+        assert_eq!(
+            validate("Add endpoint", &config),
+            Err(ConventionalCommitViolation::Malformed)
+        );
+        assert_eq!(
+            validate("wip: stuff", &config),
+            Err(ConventionalCommitViolation::DisallowedType("wip".into()))
+        );
+        assert!(matches!(
+            validate(&format!("feat: {}", "x".repeat(80)), &config),
+            Err(ConventionalCommitViolation::SubjectTooLong { .. })
+        ));
+    }
+
+    #[test]
+    fn enforces_by_mode() {
+        let fallback = || "Commit changes from coding agent".to_string();
+
+        let off = config(ConventionalCommitMode::Off);
+        assert_eq!(
+            enforce("Add endpoint", &off, "feat", fallback),
+            "Add endpoint"
+        );
+
+        let reject = config(ConventionalCommitMode::Reject);
+        assert_eq!(
+            enforce("Add endpoint", &reject, "feat", fallback),
+            "feat: Commit changes from coding agent"
+        );
+
+        let prefix = config(ConventionalCommitMode::AutoPrefix);
+        assert_eq!(
+            enforce("wip: Add endpoint\n\nDetails", &prefix, "feat", fallback),
+            "feat: Add endpoint\n\nDetails"
+        );
+    }
+
+    #[test]
+    fn shortens_long_subjects_and_keeps_them_in_body() {
+        let config = ConventionalCommitConfig {
+            mode: ConventionalCommitMode::AutoPrefix,
+            max_subject_length: 20,
+            ..Default::default()
+        };
+        let message = enforce(
+            "Add a fairly long subject line",
+            &config,
+            "feat",
+            String::new,
+        );
+        let (subject, body) = message.split_once("\n\n").unwrap();
+        assert_eq!(subject, "feat: Add a...");
+        assert_eq!(body, "Add a fairly long subject line");
+        assert_eq!(validate(&message, &config), Ok(()));
+    }
+}
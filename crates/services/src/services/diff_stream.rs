@@ -15,7 +15,7 @@ use db::{
 };
 use executors::logs::utils::ConversationPatch;
 use futures::StreamExt;
-use git::{Commit, GitService, GitServiceError, compute_line_change_counts};
+use git::{Commit, GitService, GitServiceError, PathFilter, compute_line_change_counts};
 use json_patch::Patch;
 use notify::{RecommendedWatcher, RecursiveMode};
 use notify_debouncer_full::{
@@ -162,8 +162,28 @@ pub struct DiffStreamArgs {
     pub branch: String,
     pub target_branch: String,
     pub base_commit: Commit,
+    /// Keep diffing against `base_commit` (e.g. the HEAD an execution started
+    /// from) instead of following the target branch's merge base.
+    pub pin_base: bool,
     pub stats_only: bool,
     pub path_prefix: Option<String>,
+    /// Only stream files matching these globs.
+    pub path_filter: Option<PathFilter>,
+}
+
+impl DiffStreamArgs {
+    /// Whether `path` (relative to the worktree) passes the path filter.
+    /// Patterns may be written with or without the repo name prefix.
+    fn includes(&self, path: &str) -> bool {
+        let Some(filter) = &self.path_filter else {
+            return true;
+        };
+        filter.matches(path)
+            || self
+                .path_prefix
+                .as_deref()
+                .is_some_and(|prefix| filter.matches(&format!("{prefix}/{path}")))
+    }
 }
 
 struct DiffStreamManager {
@@ -363,9 +383,11 @@ impl DiffStreamManager {
         let base = self.current_base_commit.clone();
         let stats_only = self.args.stats_only;
         let cumulative = self.cumulative.clone();
+        let args = self.args.clone();
 
         tokio::task::spawn_blocking(move || {
-            let diffs = git.get_diffs(&worktree, &base, None)?;
+            let diffs =
+                git.get_diffs_matching(&worktree, &base, None, |path| args.includes(path))?;
             let mut processed_diffs = Vec::with_capacity(diffs.len());
             for mut diff in diffs {
                 apply_stream_omit_policy(&mut diff, &cumulative, stats_only);
@@ -381,8 +403,11 @@ impl DiffStreamManager {
         events: Vec<DebouncedEvent>,
         canonical_worktree: &Path,
     ) -> Result<(), DiffStreamError> {
-        let changed_paths =
-            extract_changed_paths(&events, canonical_worktree, &self.args.worktree_path);
+        let changed_paths: Vec<String> =
+            extract_changed_paths(&events, canonical_worktree, &self.args.worktree_path)
+                .into_iter()
+                .filter(|path| self.args.includes(path))
+                .collect();
 
         if changed_paths.is_empty() {
             return Ok(());
@@ -441,6 +466,10 @@ impl DiffStreamManager {
     }
 
     async fn handle_target_check(&mut self) -> Result<(), DiffStreamError> {
+        if self.args.pin_base {
+            return Ok(());
+        }
+
         let Ok(Some(repo)) = WorkspaceRepo::find_by_workspace_and_repo_id(
             &self.args.db.pool,
             self.args.workspace_id,
@@ -555,8 +584,9 @@ impl DiffStreamManager {
         let git = self.args.git_service.clone();
         let wt = self.args.worktree_path.clone();
         let base = self.current_base_commit.clone();
-        let fresh_paths =
+        let mut fresh_paths =
             tokio::task::spawn_blocking(move || git.get_diff_file_paths(&wt, &base)).await??;
+        fresh_paths.retain(|path| self.args.includes(path));
         self.needs_post_reset_discovery = false;
 
         // Batch remove ops
@@ -629,6 +659,10 @@ impl DiffStreamManager {
     }
 
     async fn recompute_base_commit(&self, target_branch: &str) -> Option<Commit> {
+        if self.args.pin_base {
+            return Some(self.args.base_commit.clone());
+        }
+
         let git = self.args.git_service.clone();
         let repo_path = self.args.repo_path.clone();
         let branch = self.args.branch.clone();
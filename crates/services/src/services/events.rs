@@ -3,7 +3,11 @@ use std::{str::FromStr, sync::Arc};
 use db::{
     DBService,
     models::{
-        execution_process::ExecutionProcess, scratch::Scratch, session::Session,
+        execution_process::ExecutionProcess,
+        merge::{Merge, MergeStatus},
+        pull_request::PullRequest,
+        scratch::Scratch,
+        session::Session,
         workspace::Workspace,
     },
 };
@@ -163,6 +167,36 @@ impl EventService {
                                         }
                                     }
                                 }
+                                (HookTables::Merges, SqliteOperation::Insert) => {
+                                    match Merge::find_direct_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(merge)) => RecordTypes::Merge(Merge::Direct(merge)),
+                                        Ok(None) => return,
+                                        Err(e) => {
+                                            tracing::error!("Failed to fetch merge: {:?}", e);
+                                            return;
+                                        }
+                                    }
+                                }
+                                (HookTables::PullRequests, SqliteOperation::Update) => {
+                                    match PullRequest::find_by_rowid(&db.pool, rowid).await {
+                                        Ok(Some(pr))
+                                            if matches!(pr.pr_status, MergeStatus::Merged) =>
+                                        {
+                                            RecordTypes::Merge(pr.to_merge())
+                                        }
+                                        Ok(_) => return,
+                                        Err(e) => {
+                                            tracing::error!(
+                                                "Failed to fetch pull request: {:?}",
+                                                e
+                                            );
+                                            return;
+                                        }
+                                    }
+                                }
+                                (HookTables::Merges, _) | (HookTables::PullRequests, _) => {
+                                    return;
+                                }
                                 (HookTables::Scratch, _) => {
                                     match Scratch::find_by_rowid(&db.pool, rowid).await {
                                         Ok(Some(scratch)) => RecordTypes::Scratch(scratch),
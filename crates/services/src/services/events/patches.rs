@@ -182,3 +182,27 @@ pub mod approvals_patch {
         })])
     }
 }
+
+/// Helper functions for creating renormalize job patches.
+pub mod renormalize_job_patch {
+    use super::*;
+    use crate::services::renormalize_jobs::RenormalizeJob;
+
+    fn job_path(job_id: Uuid) -> String {
+        format!(
+            "/renormalize_jobs/{}",
+            escape_pointer_segment(&job_id.to_string())
+        )
+    }
+
+    /// Create patch for publishing the current state of a job
+    pub fn replace(job: &RenormalizeJob) -> Patch {
+        Patch(vec![PatchOperation::Replace(ReplaceOperation {
+            path: job_path(job.id)
+                .try_into()
+                .expect("Renormalize job path should be valid"),
+            value: serde_json::to_value(job)
+                .expect("Renormalize job serialization should not fail"),
+        })])
+    }
+}
@@ -1,5 +1,7 @@
 use anyhow::Error as AnyhowError;
-use db::models::{execution_process::ExecutionProcess, scratch::Scratch, workspace::Workspace};
+use db::models::{
+    execution_process::ExecutionProcess, merge::Merge, scratch::Scratch, workspace::Workspace,
+};
 use serde::{Deserialize, Serialize};
 use sqlx::Error as SqlxError;
 use strum_macros::{Display, EnumString};
@@ -25,6 +27,10 @@ pub enum HookTables {
     ExecutionProcesses,
     #[strum(to_string = "scratch")]
     Scratch,
+    #[strum(to_string = "merges")]
+    Merges,
+    #[strum(to_string = "pull_requests")]
+    PullRequests,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -33,6 +39,8 @@ pub enum RecordTypes {
     Workspace(Workspace),
     ExecutionProcess(ExecutionProcess),
     Scratch(Scratch),
+    /// A direct merge, or a pull request that was just merged.
+    Merge(Merge),
     DeletedWorkspace {
         rowid: i64,
     },
@@ -168,7 +168,7 @@ pub async fn migrate_execution_logs_to_files() -> Result<()> {
     };
 
     ExecutionProcessLogs::delete_all(&pool).await?;
-    sqlx::query("VACUUM").execute(&pool).await?;
+    db::maintenance::vacuum(&pool).await?;
 
     if let Some(pb) = vacuum_pb {
         pb.finish_and_clear();
@@ -253,11 +253,22 @@ pub async fn append_log_message(session_id: Uuid, execution_id: Uuid, msg: &LogM
     Ok(())
 }
 
+/// Remove terminal escape sequences from stdout/stderr before it is written to
+/// the log file. The in-memory stream feeding live views keeps its colours.
+fn strip_ansi_from_output(msg: LogMsg) -> LogMsg {
+    match msg {
+        LogMsg::Stdout(s) => LogMsg::Stdout(strip_ansi_escapes::strip_str(&s)),
+        LogMsg::Stderr(s) => LogMsg::Stderr(strip_ansi_escapes::strip_str(&s)),
+        other => other,
+    }
+}
+
 pub fn spawn_stream_raw_logs_to_storage(
     msg_stores: Arc<RwLock<HashMap<Uuid, Arc<MsgStore>>>>,
     db: DBService,
     execution_id: Uuid,
     session_id: Uuid,
+    strip_ansi: bool,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
         let mut log_writer =
@@ -282,6 +293,11 @@ pub fn spawn_stream_raw_logs_to_storage(
             let mut stream = store.history_plus_stream();
 
             while let Some(Ok(msg)) = stream.next().await {
+                let msg = if strip_ansi {
+                    strip_ansi_from_output(msg)
+                } else {
+                    msg
+                };
                 match &msg {
                     LogMsg::Stdout(_) | LogMsg::Stderr(_) => match serde_json::to_string(&msg) {
                         Ok(jsonl_line) => {
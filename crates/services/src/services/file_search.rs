@@ -1,21 +1,27 @@
 use std::{
+    collections::HashMap,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{Arc, Mutex},
     time::{Duration, Instant},
 };
 
 use db::models::repo::{SearchMatchType, SearchResult};
 use fst::{Map, MapBuilder};
+use futures::StreamExt;
 use git::GitService;
 use ignore::WalkBuilder;
-use moka::future::Cache;
+use moka::{future::Cache, notification::RemovalCause};
+use notify::event::{EventKind, ModifyKind};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 use ts_rs::TS;
 
-use super::file_ranker::{FileRanker, FileStats};
+use super::{
+    file_ranker::{FileRanker, FileStats},
+    filesystem_watcher::{self, WatcherHandle},
+};
 
 /// Search mode for different use cases
 #[derive(Debug, Clone, Serialize, Deserialize, TS)]
@@ -82,7 +88,10 @@ pub enum CacheError {
     BuildError(String),
 }
 
-/// File search cache with FST indexing
+type Watchers = Arc<Mutex<HashMap<PathBuf, WatcherHandle>>>;
+
+/// File search cache with FST indexing. Each cached repo is watched so
+/// created and deleted files are re-indexed as they happen.
 pub struct FileSearchCache {
     cache: Cache<PathBuf, CachedRepo>,
     git_service: GitService,
@@ -94,10 +103,19 @@ impl FileSearchCache {
     pub fn new() -> Self {
         let (build_sender, build_receiver) = mpsc::unbounded_channel();
 
+        let watchers: Watchers = Arc::default();
+
         // Create cache with 100MB limit and 1 hour TTL
+        let evicted_watchers = watchers.clone();
         let cache = Cache::builder()
             .max_capacity(50) // Max 50 repos
             .time_to_live(Duration::from_secs(3600)) // 1 hour TTL
+            .eviction_listener(move |repo_path: Arc<PathBuf>, _, cause| {
+                // Refreshes replace the entry; only stop watching once it's gone
+                if cause.was_evicted() || matches!(cause, RemovalCause::Explicit) {
+                    evicted_watchers.lock().unwrap().remove(&*repo_path);
+                }
+            })
             .build();
 
         let cache_for_worker = cache.clone();
@@ -113,6 +131,7 @@ impl FileSearchCache {
                 cache_for_worker,
                 worker_git_service,
                 worker_file_ranker,
+                watchers,
             )
             .await;
         });
@@ -381,40 +400,50 @@ impl FileSearchCache {
 
     /// Build FST index from filesystem traversal using superset approach
     fn build_file_index(repo_path: &Path) -> Result<FileIndex, FileIndexError> {
+        let files = Self::index_subtree(repo_path, repo_path)?;
+        let map = Self::build_fst(&files)?;
+        Ok(FileIndex { files, map })
+    }
+
+    /// Index `target` and everything below it. The walk starts at the parent
+    /// directory so that ignore rules apply to `target` itself too.
+    fn index_subtree(repo_path: &Path, target: &Path) -> Result<Vec<IndexedFile>, FileIndexError> {
         let mut indexed_files = Vec::new();
-        let mut fst_keys = Vec::new();
+        let walk_root = if target == repo_path {
+            repo_path
+        } else {
+            match target.parent() {
+                Some(parent) => parent,
+                None => return Ok(indexed_files),
+            }
+        };
+        let target_buf = target.to_path_buf();
 
         // Build superset walker - include ignored files but exclude .git and performance killers
-        let mut builder = WalkBuilder::new(repo_path);
+        let mut builder = WalkBuilder::new(walk_root);
+        let superset_target = target_buf.clone();
         builder
             .git_ignore(false) // Include all files initially
             .git_global(false)
             .git_exclude(false)
             .hidden(false) // Show hidden files like .env
-            .filter_entry(|entry| {
+            .filter_entry(move |entry| {
                 let name = entry.file_name().to_string_lossy();
-                // Always exclude .git directories
-                if name == ".git" {
-                    return false;
-                }
-                // Exclude performance killers even when including ignored files
-                if name == "node_modules" || name == "target" || name == "dist" || name == "build" {
-                    return false;
-                }
-                true
+                // Exclude .git and performance killers even when including ignored files
+                !is_excluded_dir(&name) && entry.path().starts_with(&superset_target)
             });
 
         let walker = builder.build();
 
         // Create a second walker for checking ignore status
-        let ignore_walker = WalkBuilder::new(repo_path)
+        let ignore_walker = WalkBuilder::new(walk_root)
             .git_ignore(true) // This will tell us what's ignored
             .git_global(true)
             .git_exclude(true)
             .hidden(false)
-            .filter_entry(|entry| {
+            .filter_entry(move |entry| {
                 let name = entry.file_name().to_string_lossy();
-                name != ".git"
+                name != ".git" && entry.path().starts_with(&target_buf)
             })
             .build();
 
@@ -433,7 +462,7 @@ impl FileSearchCache {
             let entry = result?;
             let path = entry.path();
 
-            if path == repo_path {
+            if path == repo_path || !path.starts_with(target) {
                 continue;
             }
 
@@ -469,22 +498,28 @@ impl FileSearchCache {
                 SearchMatchType::FullPath
             };
 
-            let indexed_file = IndexedFile {
+            indexed_files.push(IndexedFile {
                 path: relative_path_str,
                 is_file: path.is_file(),
                 match_type,
                 path_lowercase: Arc::from(relative_path_lower.as_str()),
                 is_ignored,
-            };
-
-            // Store the key for FST along with file index
-            let file_index = indexed_files.len() as u64;
-            fst_keys.push((relative_path_lower, file_index));
-            indexed_files.push(indexed_file);
+            });
         }
 
+        Ok(indexed_files)
+    }
+
+    /// Build the FST over lowercased paths, mapping each to its file index
+    fn build_fst(indexed_files: &[IndexedFile]) -> Result<Map<Vec<u8>>, FileIndexError> {
+        let mut fst_keys: Vec<(&str, u64)> = indexed_files
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (&*file.path_lowercase, i as u64))
+            .collect();
+
         // Sort keys for FST (required for building)
-        fst_keys.sort_by(|a, b| a.0.cmp(&b.0));
+        fst_keys.sort_by(|a, b| a.0.cmp(b.0));
 
         // Remove duplicates (keep first occurrence)
         fst_keys.dedup_by(|a, b| a.0 == b.0);
@@ -492,14 +527,90 @@ impl FileSearchCache {
         // Build FST
         let mut fst_builder = MapBuilder::memory();
         for (key, value) in fst_keys {
-            fst_builder.insert(&key, value)?;
+            fst_builder.insert(key, value)?;
         }
 
-        let fst_map = fst_builder.into_map();
-        Ok(FileIndex {
-            files: indexed_files,
-            map: fst_map,
-        })
+        Ok(fst_builder.into_map())
+    }
+
+    /// Re-index only the given paths of a cached repository, so files created
+    /// or deleted since the last build show up without a full rebuild. Paths
+    /// may be absolute or relative to `repo_path`. Does nothing if the repo is
+    /// not cached.
+    pub async fn refresh_paths(&self, repo_path: &Path, changed: &[PathBuf]) {
+        Self::refresh_cached(&self.cache, repo_path, changed).await;
+    }
+
+    async fn refresh_cached(
+        cache: &Cache<PathBuf, CachedRepo>,
+        repo_path: &Path,
+        changed: &[PathBuf],
+    ) {
+        let Some(mut cached) = cache.get(repo_path).await else {
+            return;
+        };
+        if let Err(e) = Self::apply_changes(&mut cached, repo_path, changed) {
+            warn!("Failed to refresh cache for {:?}: {}", repo_path, e);
+            // Drop the entry so the next search rebuilds it from scratch
+            cache.invalidate(repo_path).await;
+            return;
+        }
+        cache.insert(repo_path.to_path_buf(), cached).await;
+    }
+
+    fn apply_changes(
+        cached: &mut CachedRepo,
+        repo_path: &Path,
+        changed: &[PathBuf],
+    ) -> Result<(), FileIndexError> {
+        let canonical_root = dunce::canonicalize(repo_path).ok();
+        let mut targets: Vec<PathBuf> = changed
+            .iter()
+            .filter_map(|path| {
+                if path.is_relative() {
+                    return Some(path.clone());
+                }
+                path.strip_prefix(repo_path)
+                    .ok()
+                    .or_else(|| {
+                        canonical_root
+                            .as_deref()
+                            .and_then(|root| path.strip_prefix(root).ok())
+                    })
+                    .map(Path::to_path_buf)
+            })
+            .filter(|relative| {
+                !relative.as_os_str().is_empty()
+                    && !relative
+                        .components()
+                        .any(|c| is_excluded_dir(&c.as_os_str().to_string_lossy()))
+            })
+            .collect();
+        if targets.is_empty() {
+            return Ok(());
+        }
+
+        // A changed directory is re-indexed as a whole, which covers any
+        // changes reported beneath it
+        targets.sort();
+        targets.dedup_by(|child, parent| child.starts_with(&*parent));
+
+        let mut files = std::mem::take(&mut cached.indexed_files);
+        files.retain(|file| {
+            !targets
+                .iter()
+                .any(|target| Path::new(&file.path).starts_with(target))
+        });
+        for target in &targets {
+            let absolute = repo_path.join(target);
+            if absolute.symlink_metadata().is_ok() {
+                files.extend(Self::index_subtree(repo_path, &absolute)?);
+            }
+        }
+
+        cached.fst_index = Self::build_fst(&files)?;
+        cached.indexed_files = files;
+        Ok(())
     }
 
     /// Background worker for cache building
@@ -508,6 +619,7 @@ impl FileSearchCache {
         cache: Cache<PathBuf, CachedRepo>,
         git_service: GitService,
         file_ranker: FileRanker,
+        watchers: Watchers,
     ) {
         while let Some(repo_path) = build_receiver.recv().await {
             if !repo_path.exists() {
@@ -529,6 +641,7 @@ impl FileSearchCache {
                 Ok(cached_repo) => {
                     cache.insert(repo_path.clone(), cached_repo).await;
                     info!("Successfully cached repo: {:?}", repo_path);
+                    Self::watch_repo(&cache, &watchers, repo_path);
                 }
                 Err(e) => {
                     error!("Failed to cache repo {:?}: {}", repo_path, e);
@@ -536,6 +649,52 @@ impl FileSearchCache {
             }
         }
     }
+
+    /// Start watching a freshly cached repo, unless it's already watched.
+    /// The watcher stops when the repo's cache entry is evicted.
+    fn watch_repo(cache: &Cache<PathBuf, CachedRepo>, watchers: &Watchers, repo_path: PathBuf) {
+        if watchers.lock().unwrap().contains_key(&repo_path) {
+            return;
+        }
+        let (handle, mut rx, _) = match filesystem_watcher::async_watcher(repo_path.clone()) {
+            Ok(components) => components,
+            Err(e) => {
+                warn!("Failed to watch {:?} for search cache: {}", repo_path, e);
+                return;
+            }
+        };
+        watchers.lock().unwrap().insert(repo_path.clone(), handle);
+
+        let cache = cache.clone();
+        tokio::spawn(async move {
+            while let Some(result) = rx.next().await {
+                let Ok(events) = result else {
+                    continue;
+                };
+                // Content edits don't change the index; only names coming and going do
+                let changed: Vec<PathBuf> = events
+                    .into_iter()
+                    .filter(|event| {
+                        matches!(
+                            event.kind,
+                            EventKind::Create(_)
+                                | EventKind::Remove(_)
+                                | EventKind::Modify(ModifyKind::Name(_))
+                        )
+                    })
+                    .flat_map(|event| event.event.paths)
+                    .collect();
+                if !changed.is_empty() {
+                    Self::refresh_cached(&cache, &repo_path, &changed).await;
+                }
+            }
+        });
+    }
+}
+
+/// Directories never indexed, even in settings mode
+fn is_excluded_dir(name: &str) -> bool {
+    matches!(name, ".git" | "node_modules" | "target" | "dist" | "build")
 }
 
 impl Default for FileSearchCache {
@@ -543,3 +702,69 @@ impl Default for FileSearchCache {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn indexed_paths(cached: &CachedRepo) -> Vec<&str> {
+        let mut paths: Vec<&str> = cached
+            .indexed_files
+            .iter()
+            .map(|f| f.path.as_str())
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn refresh_reindexes_only_changed_paths() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir(root.join("src")).unwrap();
+        fs::write(root.join("src/lib.rs"), "").unwrap();
+        fs::write(root.join("old.txt"), "").unwrap();
+
+        let index = FileSearchCache::build_file_index(root).unwrap();
+        let mut cached = CachedRepo {
+            head_sha: String::new(),
+            fst_index: index.map,
+            indexed_files: index.files,
+            stats: Arc::default(),
+            build_ts: Instant::now(),
+        };
+
+        fs::remove_file(root.join("old.txt")).unwrap();
+        fs::create_dir_all(root.join("docs/guide")).unwrap();
+        fs::write(root.join("docs/guide/intro.md"), "").unwrap();
+        fs::create_dir(root.join("node_modules")).unwrap();
+        fs::write(root.join("node_modules/pkg.js"), "").unwrap();
+
+        FileSearchCache::apply_changes(
+            &mut cached,
+            root,
+            &[
+                root.join("old.txt"),
+                root.join("docs"),
+                PathBuf::from("docs/guide/intro.md"),
+                root.join("node_modules/pkg.js"),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            indexed_paths(&cached),
+            [
+                "docs",
+                "docs/guide",
+                "docs/guide/intro.md",
+                "src",
+                "src/lib.rs"
+            ]
+        );
+        assert!(cached.fst_index.contains_key("docs/guide/intro.md"));
+        assert!(!cached.fst_index.contains_key("old.txt"));
+    }
+}
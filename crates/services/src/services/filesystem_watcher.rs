@@ -23,11 +23,11 @@ use notify_debouncer_full::{
 use thiserror::Error;
 use utils::path::ALWAYS_SKIP_DIRS;
 
-pub type WatcherComponents = (
-    Arc<Mutex<Debouncer<RecommendedWatcher, RecommendedCache>>>,
-    Receiver<DebounceEventResult>,
-    PathBuf,
-);
+/// Keeps the watcher running; dropping the last handle stops it and closes
+/// the event receiver.
+pub type WatcherHandle = Arc<Mutex<Debouncer<RecommendedWatcher, RecommendedCache>>>;
+
+pub type WatcherComponents = (WatcherHandle, Receiver<DebounceEventResult>, PathBuf);
 
 #[derive(Debug, Error)]
 pub enum FilesystemWatcherError {
@@ -0,0 +1,84 @@
+//! Template for the first prompt of a workspace started from a linked issue.
+//!
+//! Placeholders are those of [`super::template`].
+
+use thiserror::Error;
+
+use super::template;
+
+pub const DEFAULT_ISSUE_PROMPT_TEMPLATE: &str = "{title}\n\n{description}";
+
+const PLACEHOLDERS: [&str; 3] = ["title", "description", "simple_id"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum IssuePromptTemplateError {
+    #[error("Unknown placeholder `{{{0}}}`. Use {{title}}, {{description}} or {{simple_id}}.")]
+    UnknownPlaceholder(String),
+}
+
+/// Values substituted into a template.
+pub struct IssuePromptContext<'a> {
+    pub title: &'a str,
+    /// Empty when the issue has no description.
+    pub description: &'a str,
+    /// Human-readable issue id, e.g. `VK-42`.
+    pub simple_id: &'a str,
+}
+
+pub fn validate(template: &str) -> Result<(), IssuePromptTemplateError> {
+    template::validate(template, &PLACEHOLDERS)
+        .map_err(|e| IssuePromptTemplateError::UnknownPlaceholder(e.0))
+}
+
+/// Substitute placeholders and trim the result, so an issue without a
+/// description doesn't leave trailing blank lines.
+pub fn render(template: &str, ctx: &IssuePromptContext<'_>) -> String {
+    template::render(
+        template,
+        &[
+            ("title", ctx.title.trim()),
+            ("description", ctx.description.trim()),
+            ("simple_id", ctx.simple_id),
+        ],
+    )
+    .trim()
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_issue_fields() {
+        let ctx = IssuePromptContext {
+            title: " Fix login ",
+            description: "Users see {title} on error.\n",
+            simple_id: "VK-42",
+        };
+        assert_eq!(
+            render("[{simple_id}] {title}\n\n{description}", &ctx),
+            "[VK-42] Fix login\n\nUsers see {title} on error."
+        );
+
+        let no_description = IssuePromptContext {
+            description: "",
+            ..ctx
+        };
+        assert_eq!(
+            render(DEFAULT_ISSUE_PROMPT_TEMPLATE, &no_description),
+            "Fix login"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        assert_eq!(validate(DEFAULT_ISSUE_PROMPT_TEMPLATE), Ok(()));
+        assert_eq!(
+            validate("{title} {acceptance_criteria}"),
+            Err(IssuePromptTemplateError::UnknownPlaceholder(
+                "acceptance_criteria".into()
+            ))
+        );
+    }
+}
@@ -1,8 +1,13 @@
+pub mod agent_identity_template;
+pub mod agent_plan;
 pub mod analytics;
 pub mod approvals;
+pub mod attempt_limiter;
 pub mod auth;
+pub mod branch_template;
 pub mod config;
 pub mod container;
+pub mod conventional_commit;
 pub mod diff_stream;
 pub mod events;
 pub mod execution_process;
@@ -11,13 +16,25 @@ pub mod file_ranker;
 pub mod file_search;
 pub mod filesystem;
 pub mod filesystem_watcher;
+pub mod issue_prompt_template;
 pub mod notification;
+pub mod notification_template;
 pub mod oauth_credentials;
+pub mod outcome_summary;
+pub mod pr_description;
 pub mod pr_monitor;
+pub mod pr_template;
+pub mod proposed_changes;
 
 #[cfg(feature = "qa-mode")]
 pub mod qa_repos;
 pub mod queued_message;
 pub mod remote_client;
 pub mod remote_sync;
+pub mod renormalize_jobs;
+pub mod replay_cache;
 pub mod repo;
+pub mod repo_ecosystem;
+pub mod template;
+pub mod token_usage;
+pub mod webhooks;
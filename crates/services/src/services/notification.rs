@@ -1,7 +1,16 @@
-use std::sync::{Arc, OnceLock};
+use std::{
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
 use async_trait::async_trait;
+use backon::{ExponentialBuilder, Retryable};
+use db::models::failed_notification::{FailedNotification, NotificationChannel};
+use serde::Serialize;
+use sqlx::SqlitePool;
+use thiserror::Error;
 use tokio::sync::RwLock;
+use ts_rs::TS;
 use utils::{self, command_ext::NoWindowExt};
 use uuid::Uuid;
 
@@ -11,7 +20,13 @@ use crate::services::config::{Config, SoundFile};
 /// platform-specific OS commands, Tauri's notification plugin, etc.
 #[async_trait]
 pub trait PushNotifier: Send + Sync + 'static {
-    async fn send(&self, title: &str, message: &str, workspace_id: Option<Uuid>);
+    /// Returns an error when the notification could not be handed to the OS.
+    async fn send(
+        &self,
+        title: &str,
+        message: &str,
+        workspace_id: Option<Uuid>,
+    ) -> Result<(), String>;
 }
 
 /// Global push notifier set before server startup (e.g., by the Tauri app).
@@ -42,22 +57,53 @@ static WSL_ROOT_PATH_CACHE: OnceLock<Option<String>> = OnceLock::new();
 
 #[async_trait]
 impl PushNotifier for DefaultPushNotifier {
-    async fn send(&self, title: &str, message: &str, _workspace_id: Option<Uuid>) {
+    async fn send(
+        &self,
+        title: &str,
+        message: &str,
+        _workspace_id: Option<Uuid>,
+    ) -> Result<(), String> {
         if cfg!(target_os = "macos") {
-            send_macos_notification(title, message).await;
+            send_macos_notification(title, message).await
         } else if cfg!(target_os = "linux") && !utils::is_wsl2() {
-            send_linux_notification(title, message).await;
+            send_linux_notification(title, message).await
         } else if cfg!(target_os = "windows") || (cfg!(target_os = "linux") && utils::is_wsl2()) {
-            send_windows_notification(title, message).await;
+            send_windows_notification(title, message).await
+        } else {
+            Ok(())
         }
     }
 }
 
+/// Channels a test notification was sent through.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TestNotificationResult {
+    pub sound: bool,
+    pub push: bool,
+}
+
+#[derive(Debug, Error)]
+pub enum RedriveError {
+    #[error("Failed notification not found")]
+    NotFound,
+    #[error("Notifications can't be re-driven without a database")]
+    NoStore,
+    #[error("The {0:?} notification channel is disabled")]
+    ChannelDisabled(NotificationChannel),
+    #[error("Delivery failed again: {0}")]
+    Delivery(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
 /// Service for handling cross-platform notifications including sound alerts and push notifications
 #[derive(Clone)]
 pub struct NotificationService {
     config: Arc<RwLock<Config>>,
     push_notifier: Arc<dyn PushNotifier>,
+    /// Where push notifications that still fail after retries are kept.
+    /// Without it they are only logged.
+    dead_letters: Option<SqlitePool>,
 }
 
 impl std::fmt::Debug for NotificationService {
@@ -73,9 +119,16 @@ impl NotificationService {
         Self {
             config,
             push_notifier: get_global_push_notifier(),
+            dead_letters: None,
         }
     }
 
+    /// Keep push notifications that fail after retries in `pool`.
+    pub fn with_dead_letters(mut self, pool: SqlitePool) -> Self {
+        self.dead_letters = Some(pool);
+        self
+    }
+
     /// Send both sound and push notifications if enabled.
     /// `workspace_id` is forwarded to the push notifier so Tauri can emit a
     /// navigation event when the notification is clicked.
@@ -86,8 +139,100 @@ impl NotificationService {
             Self::play_sound_notification(&config.sound_file).await;
         }
 
-        if config.push_enabled {
-            self.push_notifier.send(title, message, workspace_id).await;
+        if config.push_enabled
+            && let Err(e) = self
+                .send_push_with_retry(title, message, workspace_id)
+                .await
+        {
+            tracing::warn!("Push notification '{}' failed: {}", title, e);
+            if let Some(pool) = &self.dead_letters
+                && let Err(db_err) = FailedNotification::create(
+                    pool,
+                    workspace_id,
+                    NotificationChannel::Push,
+                    title,
+                    message,
+                    &e,
+                )
+                .await
+            {
+                tracing::error!("Failed to store failed notification: {}", db_err);
+            }
+        }
+    }
+
+    /// Send a stored failed notification again, through its channel as it is
+    /// currently configured. It is removed once delivered.
+    pub async fn redrive(&self, id: Uuid) -> Result<(), RedriveError> {
+        let pool = self.dead_letters.as_ref().ok_or(RedriveError::NoStore)?;
+        let failed = FailedNotification::find_by_id(pool, id)
+            .await?
+            .ok_or(RedriveError::NotFound)?;
+
+        let config = self.config.read().await.notifications.clone();
+        let result = match failed.channel {
+            NotificationChannel::Push if !config.push_enabled => {
+                return Err(RedriveError::ChannelDisabled(failed.channel));
+            }
+            NotificationChannel::Push => {
+                self.send_push_with_retry(&failed.title, &failed.message, failed.workspace_id)
+                    .await
+            }
+        };
+
+        match result {
+            Ok(()) => {
+                FailedNotification::delete(pool, id).await?;
+                Ok(())
+            }
+            Err(e) => {
+                FailedNotification::record_failure(pool, id, &e).await?;
+                Err(RedriveError::Delivery(e))
+            }
+        }
+    }
+
+    async fn send_push_with_retry(
+        &self,
+        title: &str,
+        message: &str,
+        workspace_id: Option<Uuid>,
+    ) -> Result<(), String> {
+        (|| self.push_notifier.send(title, message, workspace_id))
+            .retry(
+                &ExponentialBuilder::default()
+                    .with_min_delay(Duration::from_millis(500))
+                    .with_max_delay(Duration::from_secs(4))
+                    .with_max_times(3)
+                    .with_jitter(),
+            )
+            .await
+    }
+
+    /// Send a canned message through every enabled channel so the user can
+    /// confirm their setup. Returns the channels that were used.
+    pub async fn send_test_notification(&self) -> TestNotificationResult {
+        let config = self.config.read().await.notifications.clone();
+
+        if config.sound_enabled {
+            Self::play_sound_notification(&config.sound_file).await;
+        }
+        if config.push_enabled
+            && let Err(e) = self
+                .push_notifier
+                .send(
+                    "Vibe Kanban test notification",
+                    "Notifications are working.",
+                    None,
+                )
+                .await
+        {
+            tracing::warn!("Test push notification failed: {}", e);
+        }
+
+        TestNotificationResult {
+            sound: config.sound_enabled,
+            push: config.push_enabled,
         }
     }
 
@@ -154,58 +299,59 @@ impl NotificationService {
 // --- Platform-specific push notification helpers (used by DefaultPushNotifier) ---
 
 /// Send macOS notification using osascript
-async fn send_macos_notification(title: &str, message: &str) {
+async fn send_macos_notification(title: &str, message: &str) -> Result<(), String> {
     let script = format!(
         r#"display notification "{message}" with title "{title}" sound name "Glass""#,
         message = message.replace('"', r#"\""#),
         title = title.replace('"', r#"\""#)
     );
 
-    let _ = tokio::process::Command::new("osascript")
+    tokio::process::Command::new("osascript")
         .arg("-e")
         .arg(script)
-        .spawn();
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("failed to run osascript: {e}"))
 }
 
-/// Send Linux notification using notify-rust
-async fn send_linux_notification(title: &str, message: &str) {
+/// Send Linux notification using notify-rust. A missing notification daemon
+/// isn't treated as a failure, since retrying can't help.
+async fn send_linux_notification(title: &str, message: &str) -> Result<(), String> {
     use notify_rust::Notification;
 
     let title = title.to_string();
     let message = message.to_string();
 
-    let _handle = tokio::task::spawn_blocking(move || {
+    tokio::task::spawn_blocking(move || {
         match Notification::new()
             .summary(&title)
             .body(&message)
             .timeout(10000)
             .show()
         {
-            Ok(_) => {}
+            Ok(_) => Ok(()),
             Err(e) => {
                 let err_str = e.to_string();
                 if err_str.contains("ServiceUnknown")
                     || err_str.contains("org.freedesktop.Notifications")
                 {
                     tracing::warn!("Linux notification daemon not available: {}", e);
+                    Ok(())
                 } else {
-                    tracing::warn!("Failed to send Linux notification: {}", e);
+                    Err(format!("failed to send Linux notification: {e}"))
                 }
             }
         }
-    });
-    drop(_handle); // Don't await, fire-and-forget
+    })
+    .await
+    .map_err(|e| format!("notification task failed: {e}"))?
 }
 
 /// Send Windows/WSL notification using PowerShell toast script
-async fn send_windows_notification(title: &str, message: &str) {
-    let script_path = match utils::get_powershell_script().await {
-        Ok(path) => path,
-        Err(e) => {
-            tracing::error!("Failed to get PowerShell script: {}", e);
-            return;
-        }
-    };
+async fn send_windows_notification(title: &str, message: &str) -> Result<(), String> {
+    let script_path = utils::get_powershell_script()
+        .await
+        .map_err(|e| format!("failed to get PowerShell script: {e}"))?;
 
     // Convert WSL path to Windows path if in WSL2
     let script_path_str = if utils::is_wsl2() {
@@ -218,7 +364,7 @@ async fn send_windows_notification(title: &str, message: &str) {
         script_path.to_string_lossy().to_string()
     };
 
-    let _ = tokio::process::Command::new("powershell.exe")
+    tokio::process::Command::new("powershell.exe")
         .arg("-NoProfile")
         .arg("-ExecutionPolicy")
         .arg("Bypass")
@@ -229,7 +375,9 @@ async fn send_windows_notification(title: &str, message: &str) {
         .arg("-Message")
         .arg(message)
         .no_window()
-        .spawn();
+        .spawn()
+        .map(|_| ())
+        .map_err(|e| format!("failed to run PowerShell: {e}"))
 }
 
 /// Get WSL root path via PowerShell (cached)
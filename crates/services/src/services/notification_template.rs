@@ -0,0 +1,147 @@
+//! Per-repo templates for the notification sent when a workspace run
+//! finishes.
+//!
+//! Placeholders are those of [`super::template`].
+
+use std::time::Duration;
+
+use thiserror::Error;
+
+use super::template;
+
+pub const DEFAULT_NOTIFICATION_TITLE_TEMPLATE: &str = "Workspace Complete: {task}";
+pub const DEFAULT_NOTIFICATION_BODY_TEMPLATE: &str =
+    "{outcome}: '{task}'\nBranch: {branch}\nExecutor: {executor}\nDuration: {duration}\n{summary}";
+
+const PLACEHOLDERS: [&str; 7] = [
+    "task", "outcome", "duration", "pr_url", "branch", "executor", "summary",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum NotificationTemplateError {
+    #[error(
+        "Unknown placeholder `{{{0}}}`. Use {{task}}, {{outcome}}, {{duration}}, {{pr_url}}, {{branch}}, {{executor}} or {{summary}}."
+    )]
+    UnknownPlaceholder(String),
+    #[error("The title template can't be empty.")]
+    EmptyTitle,
+}
+
+/// Values substituted into a template.
+pub struct NotificationTemplateContext<'a> {
+    pub task: &'a str,
+    /// `✅ Completed` or `❌ Failed`.
+    pub outcome: &'a str,
+    pub duration: Option<Duration>,
+    /// Most recent pull request opened from the workspace, if any.
+    pub pr_url: Option<&'a str>,
+    pub branch: &'a str,
+    pub executor: &'a str,
+    /// Files changed and test results of the run, e.g. `2 files changed;
+    /// tests: 12 passed, 0 failed`. Empty when there is nothing to report.
+    pub summary: &'a str,
+}
+
+fn validate(template: &str) -> Result<(), NotificationTemplateError> {
+    template::validate(template, &PLACEHOLDERS)
+        .map_err(|e| NotificationTemplateError::UnknownPlaceholder(e.0))
+}
+
+pub fn validate_title(template: &str) -> Result<(), NotificationTemplateError> {
+    if template.trim().is_empty() {
+        return Err(NotificationTemplateError::EmptyTitle);
+    }
+    validate(template)
+}
+
+pub fn validate_body(template: &str) -> Result<(), NotificationTemplateError> {
+    validate(template)
+}
+
+/// `1h 2m`, `3m 4s` or `5s`.
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (hours, minutes, seconds) = (secs / 3600, secs / 60 % 60, secs % 60);
+    if hours > 0 {
+        format!("{hours}h {minutes}m")
+    } else if minutes > 0 {
+        format!("{minutes}m {seconds}s")
+    } else {
+        format!("{seconds}s")
+    }
+}
+
+/// Substitute placeholders. Unknown placeholders are kept as written;
+/// missing values (no PR yet, unknown duration) render as empty.
+pub fn render(template: &str, ctx: &NotificationTemplateContext<'_>) -> String {
+    let duration = ctx.duration.map(format_duration).unwrap_or_default();
+    template::render(
+        template,
+        &[
+            ("task", ctx.task),
+            ("outcome", ctx.outcome),
+            ("duration", &duration),
+            ("pr_url", ctx.pr_url.unwrap_or_default()),
+            ("branch", ctx.branch),
+            ("executor", ctx.executor),
+            ("summary", ctx.summary),
+        ],
+    )
+}
+
+/// Render a title, falling back to the default when the template renders to
+/// nothing.
+pub fn render_title(template: &str, ctx: &NotificationTemplateContext<'_>) -> String {
+    let rendered = render(template, ctx);
+    match rendered.trim() {
+        "" => render(DEFAULT_NOTIFICATION_TITLE_TEMPLATE, ctx),
+        title => title.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> NotificationTemplateContext<'static> {
+        NotificationTemplateContext {
+            task: "Fix login",
+            outcome: "✅ Completed",
+            duration: Some(Duration::from_secs(192)),
+            pr_url: None,
+            branch: "vk/1234-fix-login",
+            executor: "CLAUDE_CODE",
+            summary: "2 files changed; tests: 12 passed, 0 failed",
+        }
+    }
+
+    #[test]
+    fn renders_placeholders() {
+        assert_eq!(
+            render(DEFAULT_NOTIFICATION_BODY_TEMPLATE, &ctx()),
+            "✅ Completed: 'Fix login'\nBranch: vk/1234-fix-login\nExecutor: CLAUDE_CODE\nDuration: 3m 12s\n2 files changed; tests: 12 passed, 0 failed"
+        );
+        assert_eq!(render("{task} {pr_url}", &ctx()), "Fix login ");
+        assert_eq!(
+            render_title(" {pr_url} ", &ctx()),
+            "Workspace Complete: Fix login"
+        );
+        assert_eq!(format_duration(Duration::from_secs(3725)), "1h 2m");
+    }
+
+    #[test]
+    fn validates_templates() {
+        assert_eq!(validate_title("{outcome} {task}"), Ok(()));
+        assert_eq!(validate_body("{task} {\"blocks\": []}"), Ok(()));
+        assert_eq!(
+            validate_body("{pr_link}"),
+            Err(NotificationTemplateError::UnknownPlaceholder(
+                "pr_link".into()
+            ))
+        );
+        assert_eq!(
+            validate_title(""),
+            Err(NotificationTemplateError::EmptyTitle)
+        );
+    }
+}
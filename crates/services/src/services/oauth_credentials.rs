@@ -1,8 +1,10 @@
-use std::path::PathBuf;
+use std::{collections::BTreeMap, path::PathBuf};
 
 use chrono::{DateTime, Duration as ChronoDuration, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::sync::RwLock;
+use utils::jwt::{TokenClaimsError, extract_subject};
+use uuid::Uuid;
 
 /// OAuth credentials containing the JWT tokens issued by the remote OAuth service.
 /// The `access_token` is short-lived; `refresh_token` allows minting a new pair.
@@ -25,6 +27,8 @@ impl Credentials {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct StoredCredentials {
     refresh_token: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    email: Option<String>,
 }
 
 impl From<StoredCredentials> for Credentials {
@@ -37,53 +41,252 @@ impl From<StoredCredentials> for Credentials {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct StoredAccounts {
+    active: Option<Uuid>,
+    accounts: BTreeMap<Uuid, StoredCredentials>,
+}
+
+/// On-disk format. Older installs stored a single account's refresh token at
+/// the top level.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum StoredFile {
+    Accounts(StoredAccounts),
+    Single(StoredCredentials),
+}
+
+/// A remote account with stored credentials.
+#[derive(Debug, Clone)]
+pub struct StoredAccount {
+    pub user_id: Uuid,
+    /// Last known email, recorded once the profile has been fetched.
+    pub email: Option<String>,
+    pub active: bool,
+}
+
+#[derive(Debug, Default)]
+struct Accounts {
+    active: Option<Uuid>,
+    credentials: BTreeMap<Uuid, Credentials>,
+    emails: BTreeMap<Uuid, String>,
+}
+
+impl Accounts {
+    fn to_stored(&self) -> StoredAccounts {
+        StoredAccounts {
+            active: self.active,
+            accounts: self
+                .credentials
+                .iter()
+                .map(|(id, creds)| {
+                    let stored = StoredCredentials {
+                        refresh_token: creds.refresh_token.clone(),
+                        email: self.emails.get(id).cloned(),
+                    };
+                    (*id, stored)
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The remote user a refresh token belongs to. Tokens issued before subjects
+/// were user ids get a stable placeholder id derived from their `sub`, which
+/// is replaced by the real account on the next refresh.
+fn account_id(refresh_token: &str) -> std::io::Result<Uuid> {
+    match extract_subject(refresh_token) {
+        Ok(id) => Ok(id),
+        Err(TokenClaimsError::InvalidSubject(sub)) => {
+            Ok(Uuid::new_v5(&Uuid::NAMESPACE_OID, sub.as_bytes()))
+        }
+        Err(e) => Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            e.to_string(),
+        )),
+    }
+}
+
+fn is_legacy_token(refresh_token: &str) -> bool {
+    matches!(
+        extract_subject(refresh_token),
+        Err(TokenClaimsError::InvalidSubject(_))
+    )
+}
+
 /// Service for managing OAuth credentials (JWT tokens) in memory and persistent storage.
-/// The token is loaded into memory on startup and persisted to disk on save.
+/// Credentials are kept per remote account, one of which is active at a time. They
+/// are loaded into memory on startup and persisted to disk on save.
 pub struct OAuthCredentials {
     path: PathBuf,
-    inner: RwLock<Option<Credentials>>,
+    inner: RwLock<Accounts>,
 }
 
 impl OAuthCredentials {
     pub fn new(path: PathBuf) -> Self {
         Self {
             path,
-            inner: RwLock::new(None),
+            inner: RwLock::new(Accounts::default()),
         }
     }
 
     pub async fn load(&self) -> std::io::Result<()> {
-        let creds = self.load_from_file().await?.map(Credentials::from);
-        *self.inner.write().await = creds;
+        let stored = match self.load_from_file().await? {
+            Some(StoredFile::Accounts(stored)) => stored,
+            Some(StoredFile::Single(creds)) => {
+                let id = account_id(&creds.refresh_token)?;
+                StoredAccounts {
+                    active: Some(id),
+                    accounts: BTreeMap::from([(id, creds)]),
+                }
+            }
+            None => StoredAccounts::default(),
+        };
+
+        let mut accounts = Accounts {
+            active: stored.active,
+            ..Default::default()
+        };
+        for (id, creds) in stored.accounts {
+            if let Some(email) = creds.email.clone() {
+                accounts.emails.insert(id, email);
+            }
+            accounts.credentials.insert(id, creds.into());
+        }
+        if accounts
+            .active
+            .is_some_and(|id| !accounts.credentials.contains_key(&id))
+        {
+            accounts.active = None;
+        }
+
+        *self.inner.write().await = accounts;
         Ok(())
     }
 
+    /// Store credentials for the account they belong to and make it active.
     pub async fn save(&self, creds: &Credentials) -> std::io::Result<()> {
-        let stored = StoredCredentials {
-            refresh_token: creds.refresh_token.clone(),
-        };
-        self.save_to_file(&stored).await?;
-        *self.inner.write().await = Some(creds.clone());
+        let id = account_id(&creds.refresh_token)?;
+        let mut accounts = self.inner.write().await;
+        let previous_active = accounts.active.replace(id);
+        let previous = accounts.credentials.insert(id, creds.clone());
+        // A refresh of a legacy token moves the placeholder account to the
+        // real user id.
+        let replaced = previous_active
+            .filter(|old| *old != id)
+            .filter(|old| {
+                accounts
+                    .credentials
+                    .get(old)
+                    .is_some_and(|c| is_legacy_token(&c.refresh_token))
+            })
+            .map(|old| {
+                let creds = accounts.credentials.remove(&old);
+                let email = accounts.emails.remove(&old);
+                if let Some(email) = email.clone() {
+                    accounts.emails.entry(id).or_insert(email);
+                }
+                (old, creds, email)
+            });
+        if let Err(e) = self.persist(&accounts).await {
+            accounts.active = previous_active;
+            match previous {
+                Some(previous) => accounts.credentials.insert(id, previous),
+                None => accounts.credentials.remove(&id),
+            };
+            if let Some((old, creds, email)) = replaced {
+                if let Some(creds) = creds {
+                    accounts.credentials.insert(old, creds);
+                }
+                if let Some(email) = email {
+                    accounts.emails.insert(old, email);
+                }
+            }
+            return Err(e);
+        }
         Ok(())
     }
 
+    /// Remove the active account's credentials. Other accounts stay stored and
+    /// can be switched to.
     pub async fn clear(&self) -> std::io::Result<()> {
-        let _ = std::fs::remove_file(&self.path);
-        *self.inner.write().await = None;
-        Ok(())
+        let mut accounts = self.inner.write().await;
+        if let Some(id) = accounts.active.take() {
+            accounts.credentials.remove(&id);
+            accounts.emails.remove(&id);
+        }
+        self.persist(&accounts).await
     }
 
+    /// Credentials of the active account.
     pub async fn get(&self) -> Option<Credentials> {
-        self.inner.read().await.clone()
+        let accounts = self.inner.read().await;
+        let id = accounts.active?;
+        accounts.credentials.get(&id).cloned()
     }
 
-    async fn load_from_file(&self) -> std::io::Result<Option<StoredCredentials>> {
+    pub async fn active_account(&self) -> Option<Uuid> {
+        self.inner.read().await.active
+    }
+
+    pub async fn accounts(&self) -> Vec<StoredAccount> {
+        let accounts = self.inner.read().await;
+        accounts
+            .credentials
+            .keys()
+            .map(|id| StoredAccount {
+                user_id: *id,
+                email: accounts.emails.get(id).cloned(),
+                active: accounts.active == Some(*id),
+            })
+            .collect()
+    }
+
+    /// Make `user_id` the active account. Returns `false` if there are no
+    /// credentials stored for it.
+    pub async fn set_active(&self, user_id: Uuid) -> std::io::Result<bool> {
+        let mut accounts = self.inner.write().await;
+        if !accounts.credentials.contains_key(&user_id) {
+            return Ok(false);
+        }
+        let previous = accounts.active.replace(user_id);
+        if previous != Some(user_id)
+            && let Err(e) = self.persist(&accounts).await
+        {
+            accounts.active = previous;
+            return Err(e);
+        }
+        Ok(true)
+    }
+
+    /// Remember the email for an account so it can be listed without a
+    /// network round trip.
+    pub async fn set_email(&self, user_id: Uuid, email: &str) -> std::io::Result<()> {
+        let mut accounts = self.inner.write().await;
+        if !accounts.credentials.contains_key(&user_id)
+            || accounts.emails.get(&user_id).map(String::as_str) == Some(email)
+        {
+            return Ok(());
+        }
+        accounts.emails.insert(user_id, email.to_string());
+        self.persist(&accounts).await
+    }
+
+    async fn persist(&self, accounts: &Accounts) -> std::io::Result<()> {
+        if accounts.credentials.is_empty() {
+            let _ = std::fs::remove_file(&self.path);
+            return Ok(());
+        }
+        self.save_to_file(&accounts.to_stored()).await
+    }
+
+    async fn load_from_file(&self) -> std::io::Result<Option<StoredFile>> {
         if !self.path.exists() {
             return Ok(None);
         }
 
         let bytes = std::fs::read(&self.path)?;
-        match serde_json::from_slice::<StoredCredentials>(&bytes) {
+        match serde_json::from_slice::<StoredFile>(&bytes) {
             Ok(creds) => Ok(Some(creds)),
             Err(e) => {
                 tracing::warn!(?e, "failed to parse credentials file, renaming to .bad");
@@ -94,7 +297,7 @@ impl OAuthCredentials {
         }
     }
 
-    async fn save_to_file(&self, creds: &StoredCredentials) -> std::io::Result<()> {
+    async fn save_to_file(&self, creds: &StoredAccounts) -> std::io::Result<()> {
         let tmp = self.path.with_extension("tmp");
 
         let file = {
@@ -118,3 +321,97 @@ impl OAuthCredentials {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Unsigned tokens; only the `sub` claim is read.
+    const TOKEN_A: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiI2ZjFjMmIxZS0zYTRkLTRlNWYtOGE5Yi0wYzFkMmUzZjRhNWIifQ.c2ln";
+    const TOKEN_B: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiIwZDRiOGY2ZS0yYzFhLTRiM2QtOWU4Zi03YTZiNWM0ZDNlMmYifQ.c2ln";
+    const LEGACY_TOKEN: &str =
+        "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJzdWIiOiJnaXRodWJ8MTIzNDUifQ.c2ln";
+
+    fn user_a() -> Uuid {
+        Uuid::parse_str("6f1c2b1e-3a4d-4e5f-8a9b-0c1d2e3f4a5b").unwrap()
+    }
+
+    fn user_b() -> Uuid {
+        Uuid::parse_str("0d4b8f6e-2c1a-4b3d-9e8f-7a6b5c4d3e2f").unwrap()
+    }
+
+    fn credentials(refresh_token: &str) -> Credentials {
+        Credentials {
+            access_token: None,
+            refresh_token: refresh_token.to_string(),
+            expires_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn loads_legacy_single_account_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+        std::fs::write(
+            &path,
+            format!(r#"{{"refresh_token": "{TOKEN_A}", "email": "a@example.com"}}"#),
+        )
+        .unwrap();
+
+        let oauth = OAuthCredentials::new(path);
+        oauth.load().await.unwrap();
+
+        assert_eq!(oauth.active_account().await, Some(user_a()));
+        assert_eq!(oauth.get().await.unwrap().refresh_token, TOKEN_A);
+        let accounts = oauth.accounts().await;
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].email.as_deref(), Some("a@example.com"));
+    }
+
+    #[tokio::test]
+    async fn accounts_file_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+
+        let oauth = OAuthCredentials::new(path.clone());
+        oauth.save(&credentials(TOKEN_A)).await.unwrap();
+        oauth.set_email(user_a(), "a@example.com").await.unwrap();
+        oauth.save(&credentials(TOKEN_B)).await.unwrap();
+
+        let reloaded = OAuthCredentials::new(path);
+        reloaded.load().await.unwrap();
+
+        assert_eq!(reloaded.active_account().await, Some(user_b()));
+        let accounts = reloaded.accounts().await;
+        let ids: Vec<_> = accounts.iter().map(|a| a.user_id).collect();
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&user_a()) && ids.contains(&user_b()));
+        let a = accounts.iter().find(|a| a.user_id == user_a()).unwrap();
+        assert_eq!(a.email.as_deref(), Some("a@example.com"));
+        assert!(!a.active);
+    }
+
+    #[tokio::test]
+    async fn legacy_subject_is_replaced_on_refresh() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.json");
+        std::fs::write(
+            &path,
+            format!(r#"{{"refresh_token": "{LEGACY_TOKEN}", "email": "a@example.com"}}"#),
+        )
+        .unwrap();
+
+        let oauth = OAuthCredentials::new(path);
+        oauth.load().await.unwrap();
+        let placeholder = oauth.active_account().await.unwrap();
+        assert_eq!(account_id(LEGACY_TOKEN).unwrap(), placeholder);
+
+        oauth.save(&credentials(TOKEN_A)).await.unwrap();
+
+        let accounts = oauth.accounts().await;
+        assert_eq!(accounts.len(), 1);
+        assert_eq!(accounts[0].user_id, user_a());
+        assert_eq!(accounts[0].email.as_deref(), Some("a@example.com"));
+        assert!(accounts[0].active);
+    }
+}
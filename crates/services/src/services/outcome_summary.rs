@@ -0,0 +1,250 @@
+//! A machine-readable summary of a finished coding agent run, built from its
+//! normalized log: the files the agent edited, the test commands it ran with
+//! pass/fail counts parsed from their output, and the final status.
+//!
+//! Test counts come from the runners' own summary lines (`cargo test`, Jest
+//! and pytest), so no extra model call is needed.
+
+use db::models::{coding_agent_turn::CodingAgentTurn, execution_process::ExecutionProcessStatus};
+use executors::logs::{ActionType, NormalizedEntry, NormalizedEntryType, utils::patch};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct OutcomeSummary {
+    pub status: ExecutionProcessStatus,
+    /// Paths the agent edited, in the order first touched.
+    pub files_changed: Vec<String>,
+    pub test_runs: Vec<TestRunSummary>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TestRunner {
+    Cargo,
+    Jest,
+    Pytest,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TS)]
+pub struct TestRunSummary {
+    pub command: String,
+    pub runner: TestRunner,
+    pub passed: u32,
+    pub failed: u32,
+    pub skipped: u32,
+}
+
+impl OutcomeSummary {
+    /// Summarize the normalized patches in a process's log history.
+    pub fn from_messages(messages: &[LogMsg], status: ExecutionProcessStatus) -> Self {
+        Self::from_entries(patch::latest_entries(messages).values(), status)
+    }
+
+    fn from_entries<'a>(
+        entries: impl IntoIterator<Item = &'a NormalizedEntry>,
+        status: ExecutionProcessStatus,
+    ) -> Self {
+        let mut files_changed: Vec<String> = Vec::new();
+        let mut test_runs = Vec::new();
+        for entry in entries {
+            let NormalizedEntryType::ToolUse { action_type, .. } = &entry.entry_type else {
+                continue;
+            };
+            match action_type {
+                ActionType::FileEdit { path, .. } => {
+                    if !files_changed.contains(path) {
+                        files_changed.push(path.clone());
+                    }
+                }
+                ActionType::CommandRun {
+                    command,
+                    result: Some(result),
+                    ..
+                } => {
+                    if let Some(run) = result
+                        .output
+                        .as_deref()
+                        .and_then(|output| parse_test_output(command, output))
+                    {
+                        test_runs.push(run);
+                    }
+                }
+                _ => {}
+            }
+        }
+        Self {
+            status,
+            files_changed,
+            test_runs,
+        }
+    }
+
+    /// One line for notifications, e.g. `3 files changed; tests: 12 passed,
+    /// 1 failed`. Empty when there is nothing to report.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        match self.files_changed.len() {
+            0 => {}
+            1 => parts.push("1 file changed".to_string()),
+            n => parts.push(format!("{n} files changed")),
+        }
+        if !self.test_runs.is_empty() {
+            let (passed, failed, skipped) =
+                self.test_runs.iter().fold((0, 0, 0), |(p, f, s), run| {
+                    (p + run.passed, f + run.failed, s + run.skipped)
+                });
+            let mut tests = format!("tests: {passed} passed, {failed} failed");
+            if skipped > 0 {
+                tests.push_str(&format!(", {skipped} skipped"));
+            }
+            parts.push(tests);
+        }
+        parts.join("; ")
+    }
+}
+
+/// Store the summary of a finished run on its coding agent turn.
+pub async fn record(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    summary: &OutcomeSummary,
+) -> Result<(), sqlx::Error> {
+    let json = serde_json::to_string(summary).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    CodingAgentTurn::update_outcome_summary(pool, execution_process_id, &json).await
+}
+
+/// The stored summary of a run, or `None` if it hasn't finished.
+pub async fn find(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+) -> Result<Option<OutcomeSummary>, sqlx::Error> {
+    let Some(json) = CodingAgentTurn::find_outcome_summary(pool, execution_process_id).await?
+    else {
+        return Ok(None);
+    };
+    match serde_json::from_str(&json) {
+        Ok(summary) => Ok(Some(summary)),
+        Err(e) => {
+            tracing::warn!(
+                "Ignoring unreadable outcome summary for {}: {}",
+                execution_process_id,
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// Parse a test runner's summary lines out of command output. Output with
+/// several summaries, e.g. one per cargo test binary, is added up.
+fn parse_test_output(command: &str, output: &str) -> Option<TestRunSummary> {
+    let mut run: Option<TestRunSummary> = None;
+    for line in output.lines().map(str::trim) {
+        let runner = if line.starts_with("test result:") {
+            TestRunner::Cargo
+        } else if line.starts_with("Tests:") {
+            TestRunner::Jest
+        } else if line.starts_with('=') && line.ends_with('=') && line.contains(" in ") {
+            TestRunner::Pytest
+        } else {
+            continue;
+        };
+        let Some((passed, failed, skipped)) = parse_counts(line) else {
+            continue;
+        };
+        let run = run.get_or_insert_with(|| TestRunSummary {
+            command: command.to_string(),
+            runner,
+            passed: 0,
+            failed: 0,
+            skipped: 0,
+        });
+        run.passed += passed;
+        run.failed += failed;
+        run.skipped += skipped;
+    }
+    run
+}
+
+/// Read `<count> <label>` pairs such as `12 passed;` or `1 failed,`.
+/// `None` when the line has no recognised counts.
+fn parse_counts(line: &str) -> Option<(u32, u32, u32)> {
+    let (mut passed, mut failed, mut skipped) = (0, 0, 0);
+    let mut found = false;
+    let words: Vec<&str> = line.split_whitespace().collect();
+    for pair in words.windows(2) {
+        let Ok(count) = pair[0].parse::<u32>() else {
+            continue;
+        };
+        let label = pair[1].trim_end_matches(|c: char| !c.is_ascii_alphabetic());
+        match label {
+            "passed" => passed += count,
+            "failed" | "error" | "errors" => failed += count,
+            "ignored" | "skipped" => skipped += count,
+            _ => continue,
+        }
+        found = true;
+    }
+    found.then_some((passed, failed, skipped))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_test_runner_summaries() {
+        let cargo = "running 3 tests\n...\ntest result: ok. 3 passed; 0 failed; 1 ignored; 0 measured; 0 filtered out; finished in 0.01s\n\ntest result: FAILED. 4 passed; 2 failed; 0 ignored; 0 measured; 0 filtered out";
+        assert_eq!(
+            parse_test_output("cargo test", cargo),
+            Some(TestRunSummary {
+                command: "cargo test".into(),
+                runner: TestRunner::Cargo,
+                passed: 7,
+                failed: 2,
+                skipped: 1,
+            })
+        );
+
+        let jest = "Test Suites: 1 failed, 3 passed, 4 total\nTests:       1 failed, 2 skipped, 11 passed, 14 total\nTime: 2.1 s";
+        let run = parse_test_output("npx jest", jest).unwrap();
+        assert_eq!(run.runner, TestRunner::Jest);
+        assert_eq!((run.passed, run.failed, run.skipped), (11, 1, 2));
+
+        let pytest = "===== 5 passed, 1 failed, 1 error in 0.42s =====";
+        let run = parse_test_output("pytest", pytest).unwrap();
+        assert_eq!(run.runner, TestRunner::Pytest);
+        assert_eq!((run.passed, run.failed, run.skipped), (5, 2, 0));
+
+        assert_eq!(parse_test_output("ls", "Cargo.toml\nsrc"), None);
+    }
+
+    #[test]
+    fn describes_the_outcome() {
+        let summary = OutcomeSummary {
+            status: ExecutionProcessStatus::Completed,
+            files_changed: vec!["src/lib.rs".into(), "src/main.rs".into()],
+            test_runs: vec![TestRunSummary {
+                command: "cargo test".into(),
+                runner: TestRunner::Cargo,
+                passed: 12,
+                failed: 1,
+                skipped: 0,
+            }],
+        };
+        assert_eq!(
+            summary.describe(),
+            "2 files changed; tests: 12 passed, 1 failed"
+        );
+        let empty = OutcomeSummary {
+            files_changed: vec![],
+            test_runs: vec![],
+            ..summary
+        };
+        assert_eq!(empty.describe(), "");
+    }
+}
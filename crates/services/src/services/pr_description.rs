@@ -0,0 +1,218 @@
+//! Pull request descriptions built from a workspace's stored agent conversation.
+//!
+//! The output only depends on persisted logs, so it is cheap to produce and
+//! works without network access or an extra model call.
+
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+};
+use executors::logs::{ActionType, FileChange, NormalizedEntry, NormalizedEntryType, utils::patch};
+use futures::StreamExt;
+use sqlx::SqlitePool;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+/// Maximum number of individual commands listed before the rest are summarised.
+const MAX_LISTED_COMMANDS: usize = 10;
+
+/// Collect the final normalized conversation for every finished coding agent
+/// run in the workspace, oldest first.
+pub async fn load_workspace_entries(
+    container: &impl ContainerService,
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Vec<NormalizedEntry>, ContainerError> {
+    let mut processes = Vec::new();
+    for session in Session::find_by_workspace_id(pool, workspace_id).await? {
+        processes.extend(
+            ExecutionProcess::find_by_session_id(pool, session.id, false)
+                .await?
+                .into_iter()
+                .filter(|p| {
+                    p.run_reason == ExecutionProcessRunReason::CodingAgent
+                        && !matches!(
+                            p.status,
+                            ExecutionProcessStatus::Queued | ExecutionProcessStatus::Running
+                        )
+                }),
+        );
+    }
+    processes.sort_by_key(|p| p.created_at);
+
+    let mut entries = Vec::new();
+    for process in processes {
+        let Some(mut stream) = container.stream_normalized_logs(&process.id).await else {
+            continue;
+        };
+        let mut messages = Vec::new();
+        while let Some(Ok(msg)) = stream.next().await {
+            if matches!(msg, LogMsg::Finished) {
+                break;
+            }
+            messages.push(msg);
+        }
+        entries.extend(patch::latest_entries(&messages).into_values());
+    }
+    Ok(entries)
+}
+
+/// Render a markdown PR body: the agent's final message, the files it
+/// changed and a tally of the tool actions it took.
+pub fn build_pr_description(entries: &[NormalizedEntry]) -> String {
+    let mut changed_files: Vec<String> = Vec::new();
+    let mut commands: Vec<&str> = Vec::new();
+    let mut files_read = 0usize;
+    let mut searches = 0usize;
+    let mut web_fetches = 0usize;
+    let mut other_tools = 0usize;
+    let mut final_message = None;
+
+    let mut note_file = |path: String| {
+        if !changed_files.contains(&path) {
+            changed_files.push(path);
+        }
+    };
+
+    for entry in entries {
+        match &entry.entry_type {
+            NormalizedEntryType::AssistantMessage if !entry.content.trim().is_empty() => {
+                final_message = Some(entry.content.trim());
+            }
+            NormalizedEntryType::ToolUse { action_type, .. } => match action_type {
+                ActionType::FileEdit { path, changes } => {
+                    note_file(path.clone());
+                    for change in changes {
+                        if let FileChange::Rename { new_path } = change {
+                            note_file(new_path.clone());
+                        }
+                    }
+                }
+                ActionType::CommandRun { command, .. } => commands.push(command),
+                ActionType::FileRead { .. } => files_read += 1,
+                ActionType::Search { .. } => searches += 1,
+                ActionType::WebFetch { .. } => web_fetches += 1,
+                ActionType::Tool { .. }
+                | ActionType::TaskCreate { .. }
+                | ActionType::Other { .. } => other_tools += 1,
+                ActionType::PlanPresentation { .. }
+                | ActionType::TodoManagement { .. }
+                | ActionType::AskUserQuestion { .. } => {}
+            },
+            _ => {}
+        }
+    }
+
+    let mut body = String::from("## Summary\n\n");
+    body.push_str(final_message.unwrap_or("_No summary was recorded for this change._"));
+
+    if !changed_files.is_empty() {
+        body.push_str("\n\n## Changed files\n\n");
+        for path in &changed_files {
+            body.push_str(&format!("- `{path}`\n"));
+        }
+        body.pop();
+    }
+
+    let tallies = [
+        (changed_files.len(), "file edited", "files edited"),
+        (commands.len(), "command run", "commands run"),
+        (files_read, "file read", "files read"),
+        (searches, "search", "searches"),
+        (web_fetches, "web fetch", "web fetches"),
+        (other_tools, "other tool call", "other tool calls"),
+    ];
+    if tallies.iter().any(|(count, _, _)| *count > 0) {
+        body.push_str("\n\n## Agent activity\n\n");
+        for (count, singular, plural) in tallies {
+            if count > 0 {
+                let label = if count == 1 { singular } else { plural };
+                body.push_str(&format!("- {count} {label}\n"));
+            }
+        }
+        if !commands.is_empty() {
+            body.push_str("\n<details>\n<summary>Commands</summary>\n\n");
+            for command in commands.iter().take(MAX_LISTED_COMMANDS) {
+                body.push_str(&format!("- `{}`\n", command.replace('`', "'")));
+            }
+            if commands.len() > MAX_LISTED_COMMANDS {
+                body.push_str(&format!(
+                    "- …and {} more\n",
+                    commands.len() - MAX_LISTED_COMMANDS
+                ));
+            }
+            body.push_str("\n</details>\n");
+        }
+        body.pop();
+    }
+
+    body
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::ToolStatus;
+
+    use super::*;
+
+    fn entry(entry_type: NormalizedEntryType, content: &str) -> NormalizedEntry {
+        NormalizedEntry {
+            timestamp: None,
+            entry_type,
+            content: content.to_string(),
+            metadata: None,
+        }
+    }
+
+    fn tool(action_type: ActionType) -> NormalizedEntry {
+        entry(
+            NormalizedEntryType::ToolUse {
+                tool_name: "tool".to_string(),
+                action_type,
+                status: ToolStatus::Success,
+            },
+            "",
+        )
+    }
+
+    #[test]
+    fn summarises_files_actions_and_final_message() {
+        let entries = vec![
+            entry(NormalizedEntryType::AssistantMessage, "Looking around"),
+            tool(ActionType::FileRead {
+                path: "src/lib.rs".to_string(),
+            }),
+            tool(ActionType::FileEdit {
+                path: "src/lib.rs".to_string(),
+                changes: vec![],
+            }),
+            tool(ActionType::FileEdit {
+                path: "src/lib.rs".to_string(),
+                changes: vec![],
+            }),
+            tool(ActionType::CommandRun {
+                command: "cargo test".to_string(),
+                result: None,
+                category: Default::default(),
+            }),
+            entry(NormalizedEntryType::AssistantMessage, "Fixed the bug."),
+        ];
+
+        assert_eq!(
+            build_pr_description(&entries),
+            "## Summary\n\nFixed the bug.\n\n## Changed files\n\n- `src/lib.rs`\n\n\
+             ## Agent activity\n\n- 1 file edited\n- 1 command run\n- 1 file read\n\n\
+             <details>\n<summary>Commands</summary>\n\n- `cargo test`\n\n</details>"
+        );
+    }
+
+    #[test]
+    fn empty_conversation_has_placeholder_summary() {
+        assert_eq!(
+            build_pr_description(&[]),
+            "## Summary\n\n_No summary was recorded for this change._"
+        );
+    }
+}
@@ -1,21 +1,27 @@
-use std::{sync::Arc, time::Duration};
+use std::{
+    sync::{Arc, RwLock},
+    time::Duration,
+};
 
 use api_types::{PullRequestStatus, UpdatePullRequestApiRequest, UpsertPullRequestRequest};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use db::{
     DBService,
     models::{
         merge::MergeStatus,
         pull_request::PullRequest,
+        repo::Repo,
         workspace::{Workspace, WorkspaceError},
     },
 };
 use git_host::{GitHostError, GitHostProvider, GitHostService};
+use serde::Serialize;
 use serde_json::json;
 use sqlx::error::Error as SqlxError;
 use thiserror::Error;
 use tokio::{sync::Notify, time::interval};
-use tracing::{debug, error, info, warn};
+use tracing::{Instrument, debug, error, info, info_span, warn};
+use ts_rs::TS;
 
 use crate::services::{
     analytics::AnalyticsContext,
@@ -45,14 +51,87 @@ impl PrMonitorError {
     }
 }
 
+/// Whether PR polling is paused, and when it last ran.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct PrMonitorStatus {
+    pub paused: bool,
+    pub paused_at: Option<DateTime<Utc>>,
+    pub last_polled_at: Option<DateTime<Utc>>,
+}
+
+/// Pauses and resumes polling in a running [`PrMonitorService`], e.g. while a
+/// git host is rate limiting or down. Pending changes are still pushed to the
+/// remote while paused; only requests to the git host stop.
+#[derive(Debug, Clone, Default)]
+pub struct PrMonitorControl {
+    status: Arc<RwLock<PrMonitorStatus>>,
+    resumed: Arc<Notify>,
+}
+
+impl PrMonitorControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn status(&self) -> PrMonitorStatus {
+        self.status.read().unwrap().clone()
+    }
+
+    pub fn pause(&self) -> PrMonitorStatus {
+        let mut status = self.status.write().unwrap();
+        if !status.paused {
+            info!("Pausing PR monitoring");
+            status.paused = true;
+            status.paused_at = Some(Utc::now());
+        }
+        status.clone()
+    }
+
+    /// Resume polling, checking open PRs straight away rather than waiting
+    /// for the next tick.
+    pub fn resume(&self) -> PrMonitorStatus {
+        let mut status = self.status.write().unwrap();
+        if status.paused {
+            info!("Resuming PR monitoring");
+            status.paused = false;
+            status.paused_at = None;
+            self.resumed.notify_one();
+        }
+        status.clone()
+    }
+
+    fn is_paused(&self) -> bool {
+        self.status.read().unwrap().paused
+    }
+
+    fn record_poll(&self) {
+        self.status.write().unwrap().last_polled_at = Some(Utc::now());
+    }
+}
+
+/// Set to check PRs without writing what changed, for debugging merge status
+/// detection: provider calls still run, but updates are only logged.
+const DRY_RUN_ENV: &str = "VK_PR_MONITOR_DRY_RUN";
+
+fn dry_run_from_env() -> bool {
+    std::env::var(DRY_RUN_ENV).is_ok_and(|value| {
+        value == "1"
+            || value.eq_ignore_ascii_case("true")
+            || value.eq_ignore_ascii_case("yes")
+            || value.eq_ignore_ascii_case("on")
+    })
+}
+
 /// Service to monitor PRs and update task status when they are merged
 pub struct PrMonitorService<C: ContainerService> {
     db: DBService,
     poll_interval: Duration,
+    dry_run: bool,
     analytics: Option<AnalyticsContext>,
     container: C,
     remote_client: Option<RemoteClient>,
     sync_notify: Arc<Notify>,
+    control: PrMonitorControl,
 }
 
 impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
@@ -62,14 +141,17 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         container: C,
         remote_client: Option<RemoteClient>,
         sync_notify: Arc<Notify>,
+        control: PrMonitorControl,
     ) -> tokio::task::JoinHandle<()> {
         let service = Self {
             db,
             poll_interval: Duration::from_secs(60),
+            dry_run: dry_run_from_env(),
             analytics,
             container,
             remote_client,
             sync_notify,
+            control,
         };
         tokio::spawn(async move {
             service.start().await;
@@ -81,16 +163,16 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
             "Starting PR monitoring service with interval {:?}",
             self.poll_interval
         );
+        if self.dry_run {
+            warn!("{DRY_RUN_ENV} is set: PR changes will be logged but not saved");
+        }
 
         let mut interval = interval(self.poll_interval);
 
         loop {
             tokio::select! {
-                _ = interval.tick() => {
-                    if let Err(e) = self.check_all_open_prs().await {
-                        error!("Error checking open PRs: {}", e);
-                    }
-                }
+                _ = interval.tick() => self.poll().await,
+                _ = self.control.resumed.notified() => self.poll().await,
                 _ = self.sync_notify.notified() => {
                     debug!("PR sync triggered externally");
                 }
@@ -99,6 +181,17 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         }
     }
 
+    async fn poll(&self) {
+        if self.control.is_paused() {
+            debug!("PR monitoring is paused, skipping poll");
+            return;
+        }
+        if let Err(e) = self.check_all_open_prs().await {
+            error!("Error checking open PRs: {}", e);
+        }
+        self.control.record_poll();
+    }
+
     /// Check all open PRs for updates
     async fn check_all_open_prs(&self) -> Result<(), PrMonitorError> {
         let open_prs = PullRequest::get_open(&self.db.pool).await?;
@@ -110,7 +203,14 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
 
         info!("Checking {} open PRs", open_prs.len());
         for pr in &open_prs {
-            if let Err(e) = self.check_open_pr(pr).await {
+            let span = info_span!(
+                "pr_poll",
+                pr_number = pr.pr_number,
+                dry_run = self.dry_run,
+                old_status = ?pr.pr_status,
+                new_status = tracing::field::Empty,
+            );
+            if let Err(e) = self.check_open_pr(pr).instrument(span).await {
                 if e.is_environmental() {
                     warn!(
                         "Skipping PR #{} due to environmental error: {}",
@@ -129,12 +229,38 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
     async fn check_open_pr(&self, pr: &PullRequest) -> Result<(), PrMonitorError> {
         let git_host = GitHostService::from_url(&pr.pr_url)?;
         let status = git_host.get_pr_status(&pr.pr_url).await?;
+        tracing::Span::current().record("new_status", tracing::field::debug(&status.status));
 
         debug!(
             "PR #{} status: {:?} (was open)",
             pr.pr_number, status.status
         );
 
+        if self.dry_run {
+            if status.checks_status != pr.checks_status {
+                info!(
+                    "Dry run: would change checks of PR #{} from {:?} to {:?}",
+                    pr.pr_number, pr.checks_status, status.checks_status
+                );
+            }
+            if !matches!(&status.status, MergeStatus::Open) {
+                info!(
+                    "Dry run: would change PR #{} from {:?} to {:?} (merged_at: {:?}, merge commit: {:?})",
+                    pr.pr_number,
+                    pr.pr_status,
+                    status.status,
+                    status.merged_at,
+                    status.merge_commit_sha
+                );
+            }
+            return Ok(());
+        }
+
+        if status.checks_status != pr.checks_status {
+            PullRequest::update_checks_status(&self.db.pool, &pr.pr_url, status.checks_status)
+                .await?;
+        }
+
         if matches!(&status.status, MergeStatus::Open) {
             return Ok(());
         }
@@ -160,6 +286,11 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         {
             self.try_archive_workspace(workspace_id, pr.pr_number)
                 .await?;
+            // After archiving, like a local merge
+            if let Some(repo_id) = pr.repo_id {
+                self.try_run_post_merge_script(workspace_id, repo_id)
+                    .await?;
+            }
         }
 
         info!("PR #{} status changed to {:?}", pr.pr_number, status.status);
@@ -210,11 +341,38 @@ impl<C: ContainerService + Send + Sync + 'static> PrMonitorService<C> {
         Ok(())
     }
 
+    /// Run the repo's post-merge script, if it has one. A failure is only
+    /// logged: the PR is merged either way.
+    async fn try_run_post_merge_script(
+        &self,
+        workspace_id: uuid::Uuid,
+        repo_id: uuid::Uuid,
+    ) -> Result<(), PrMonitorError> {
+        let Some(repo) = Repo::find_by_id(&self.db.pool, repo_id).await? else {
+            return Ok(());
+        };
+        if let Err(e) = self
+            .container
+            .try_run_post_merge_script(workspace_id, &repo)
+            .await
+        {
+            warn!(
+                "Failed to run post-merge script for workspace {}: {}",
+                workspace_id, e
+            );
+        }
+        Ok(())
+    }
+
     /// Sync pending PR status changes to remote server.
     async fn sync_pending_to_remote(&self) {
         let Some(client) = &self.remote_client else {
             return;
         };
+        // Syncing marks or deletes local records.
+        if self.dry_run {
+            return;
+        }
 
         let pending = match PullRequest::get_pending_sync(&self.db.pool).await {
             Ok(prs) => prs,
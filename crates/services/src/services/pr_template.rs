@@ -0,0 +1,124 @@
+//! Per-repo templates for the title and body of pull requests, using the
+//! placeholders of [`super::template`].
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use super::template::{self, placeholders};
+
+pub const DEFAULT_PR_TITLE_TEMPLATE: &str = "{task_title}";
+pub const DEFAULT_PR_BODY_TEMPLATE: &str = "{summary}";
+
+const PLACEHOLDERS: [&str; 4] = ["task_title", "branch", "summary", "attempt_id"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum PrTemplateError {
+    #[error(
+        "Unknown placeholder `{{{0}}}`. Use {{task_title}}, {{branch}}, {{summary}} or {{attempt_id}}."
+    )]
+    UnknownPlaceholder(String),
+    #[error("The title template can't be empty.")]
+    EmptyTitle,
+}
+
+/// Values substituted into a template.
+pub struct PrTemplateContext<'a> {
+    pub task_title: &'a str,
+    pub branch: &'a str,
+    /// Conversation summary; only needed when a template uses `{summary}`.
+    pub summary: &'a str,
+    pub attempt_id: Uuid,
+}
+
+fn validate(template: &str) -> Result<(), PrTemplateError> {
+    template::validate(template, &PLACEHOLDERS)
+        .map_err(|e| PrTemplateError::UnknownPlaceholder(e.0))
+}
+
+pub fn validate_title(template: &str) -> Result<(), PrTemplateError> {
+    if template.trim().is_empty() {
+        return Err(PrTemplateError::EmptyTitle);
+    }
+    validate(template)
+}
+
+pub fn validate_body(template: &str) -> Result<(), PrTemplateError> {
+    validate(template)
+}
+
+/// Whether rendering `template` needs the conversation summary, which is
+/// comparatively expensive to build.
+pub fn uses_summary(template: &str) -> bool {
+    placeholders(template)
+        .iter()
+        .any(|(_, _, name)| *name == "summary")
+}
+
+/// Substitute placeholders. Unknown placeholders are kept as written.
+pub fn render(template: &str, ctx: &PrTemplateContext<'_>) -> String {
+    let attempt_id = ctx.attempt_id.to_string();
+    template::render(
+        template,
+        &[
+            ("task_title", ctx.task_title),
+            ("branch", ctx.branch),
+            ("summary", ctx.summary),
+            ("attempt_id", &attempt_id),
+        ],
+    )
+}
+
+/// Render a title, keeping it to one line and falling back to the task title
+/// when the template renders to nothing.
+pub fn render_title(template: &str, ctx: &PrTemplateContext<'_>) -> String {
+    let rendered = render(template, ctx);
+    let title = rendered.lines().map(str::trim).find(|l| !l.is_empty());
+    title.unwrap_or(ctx.task_title).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> PrTemplateContext<'static> {
+        PrTemplateContext {
+            task_title: "Fix login",
+            branch: "vk/1234-fix-login",
+            summary: "Changed {branch} handling",
+            attempt_id: Uuid::nil(),
+        }
+    }
+
+    #[test]
+    fn renders_placeholders_once() {
+        assert_eq!(
+            render("[{branch}] {task_title}", &ctx()),
+            "[vk/1234-fix-login] Fix login"
+        );
+        assert_eq!(
+            render("{summary}\n\n```json\n{\"a\": 1}\n```", &ctx()),
+            "Changed {branch} handling\n\n```json\n{\"a\": 1}\n```"
+        );
+        assert_eq!(
+            render_title("  \n{summary}", &ctx()),
+            "Changed {branch} handling"
+        );
+        assert_eq!(render_title("", &ctx()), "Fix login");
+    }
+
+    #[test]
+    fn validates_templates() {
+        assert_eq!(validate_title("{task_title} ({attempt_id})"), Ok(()));
+        assert_eq!(
+            validate_body("{summary} {\"not\": \"a placeholder\"}"),
+            Ok(())
+        );
+        assert_eq!(
+            validate_body("{sumary}"),
+            Err(PrTemplateError::UnknownPlaceholder("sumary".into()))
+        );
+        assert_eq!(validate_title("  "), Err(PrTemplateError::EmptyTitle));
+        assert!(uses_summary("Body: {summary}"));
+        assert!(!uses_summary(DEFAULT_PR_TITLE_TEMPLATE));
+    }
+}
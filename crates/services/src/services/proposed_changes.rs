@@ -0,0 +1,186 @@
+//! File changes an agent made during a preview run, collected from its
+//! normalized log. The worktree itself is reset once the run exits, so the
+//! changes are stored just before that and are the only record of them.
+
+use db::models::{
+    coding_agent_turn::CodingAgentTurn,
+    execution_process::{ExecutionProcess, ExecutionProcessRunReason, ExecutionProcessStatus},
+    session::Session,
+};
+use executors::logs::{ActionType, FileChange, NormalizedEntryType, ToolStatus, utils::patch};
+use serde::{Deserialize, Serialize};
+use sqlx::SqlitePool;
+use ts_rs::TS;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::container::{ContainerError, ContainerService};
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProposedFileChange {
+    pub path: String,
+    /// In the order the agent made them.
+    pub changes: Vec<FileChange>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct WorkspacePreview {
+    pub execution_process_id: Uuid,
+    /// The list is complete once the run is no longer running.
+    pub status: ExecutionProcessStatus,
+    pub changes: Vec<ProposedFileChange>,
+}
+
+/// The proposed changes of the workspace's most recent preview run.
+pub async fn latest_workspace_preview(
+    container: &impl ContainerService,
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Option<WorkspacePreview>, ContainerError> {
+    let Some(process) = latest_preview_run(pool, workspace_id).await? else {
+        return Ok(None);
+    };
+
+    let changes = match container.get_msg_store_by_id(&process.id).await {
+        Some(store) => proposed_changes(&store.get_history()),
+        None => find(pool, process.id).await?,
+    };
+    Ok(Some(WorkspacePreview {
+        execution_process_id: process.id,
+        status: process.status,
+        changes,
+    }))
+}
+
+/// Whether the workspace was started as a preview. Its worktree is reset after
+/// every run, so it can't take follow-ups.
+pub async fn is_preview_workspace(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    Ok(latest_preview_run(pool, workspace_id).await?.is_some())
+}
+
+async fn latest_preview_run(
+    pool: &SqlitePool,
+    workspace_id: Uuid,
+) -> Result<Option<ExecutionProcess>, sqlx::Error> {
+    let mut latest: Option<ExecutionProcess> = None;
+    for session in Session::find_by_workspace_id(pool, workspace_id).await? {
+        for process in ExecutionProcess::find_by_session_id(pool, session.id, false).await? {
+            let is_preview = process.run_reason == ExecutionProcessRunReason::CodingAgent
+                && process.executor_action().is_ok_and(|a| a.is_preview());
+            if is_preview
+                && latest
+                    .as_ref()
+                    .is_none_or(|l| process.created_at > l.created_at)
+            {
+                latest = Some(process);
+            }
+        }
+    }
+    Ok(latest)
+}
+
+/// Store the changes a finished preview run proposed. Must run before its
+/// worktree is reset.
+pub async fn record(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    messages: &[LogMsg],
+) -> Result<(), sqlx::Error> {
+    let json = serde_json::to_string(&proposed_changes(messages))
+        .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    CodingAgentTurn::update_proposed_changes(pool, execution_process_id, &json).await
+}
+
+async fn find(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+) -> Result<Vec<ProposedFileChange>, sqlx::Error> {
+    let Some(json) = CodingAgentTurn::find_proposed_changes(pool, execution_process_id).await?
+    else {
+        return Ok(Vec::new());
+    };
+    match serde_json::from_str(&json) {
+        Ok(changes) => Ok(changes),
+        Err(e) => {
+            tracing::warn!(
+                "Ignoring unreadable proposed changes for {}: {}",
+                execution_process_id,
+                e
+            );
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// File edits from the log grouped by path, in the order each path was first
+/// touched. Edits that failed or were denied are left out.
+fn proposed_changes(messages: &[LogMsg]) -> Vec<ProposedFileChange> {
+    let mut proposed: Vec<ProposedFileChange> = Vec::new();
+    for entry in patch::latest_entries(messages).into_values() {
+        let NormalizedEntryType::ToolUse {
+            action_type: ActionType::FileEdit { path, changes },
+            status,
+            ..
+        } = entry.entry_type
+        else {
+            continue;
+        };
+        if matches!(
+            status,
+            ToolStatus::Failed | ToolStatus::Denied { .. } | ToolStatus::TimedOut
+        ) {
+            continue;
+        }
+        match proposed.iter_mut().find(|p| p.path == path) {
+            Some(existing) => existing.changes.extend(changes),
+            None => proposed.push(ProposedFileChange { path, changes }),
+        }
+    }
+    proposed
+}
+
+#[cfg(test)]
+mod tests {
+    use executors::logs::{NormalizedEntry, utils::patch::ConversationPatch};
+
+    use super::*;
+
+    fn edit(index: usize, path: &str, content: &str, status: ToolStatus) -> LogMsg {
+        LogMsg::JsonPatch(ConversationPatch::add_normalized_entry(
+            index,
+            NormalizedEntry {
+                timestamp: None,
+                entry_type: NormalizedEntryType::ToolUse {
+                    tool_name: "Write".to_string(),
+                    action_type: ActionType::FileEdit {
+                        path: path.to_string(),
+                        changes: vec![FileChange::Write {
+                            content: content.to_string(),
+                        }],
+                    },
+                    status,
+                },
+                content: String::new(),
+                metadata: None,
+            },
+        ))
+    }
+
+    #[test]
+    fn groups_edits_by_path_and_skips_failures() {
+        let messages = [
+            edit(0, "src/a.rs", "one", ToolStatus::Success),
+            edit(1, "src/b.rs", "denied", ToolStatus::Denied { reason: None }),
+            edit(2, "src/a.rs", "two", ToolStatus::Success),
+            edit(3, "README.md", "docs", ToolStatus::Created),
+        ];
+        let proposed = proposed_changes(&messages);
+
+        let paths: Vec<_> = proposed.iter().map(|p| p.path.as_str()).collect();
+        assert_eq!(paths, ["src/a.rs", "README.md"]);
+        assert_eq!(proposed[0].changes.len(), 2);
+    }
+}
@@ -7,6 +7,7 @@ use api_types::{
     CreateInvitationResponse, CreateIssueAssigneeRequest, CreateIssueRelationshipRequest,
     CreateIssueRequest, CreateIssueTagRequest, CreateOrganizationRequest,
     CreateOrganizationResponse, CreateWorkspaceRequest, DeleteResponse, DeleteWorkspaceRequest,
+    DeviceInitRequest, DeviceInitResponse, DevicePollRequest, DevicePollResponse,
     GetInvitationResponse, GetOrganizationResponse, HandoffInitRequest, HandoffInitResponse,
     HandoffRedeemRequest, HandoffRedeemResponse, Issue, IssueAssignee, IssueRelationship, IssueTag,
     ListAttachmentsResponse, ListInvitationsResponse, ListIssueAssigneesResponse,
@@ -17,6 +18,7 @@ use api_types::{
     SearchIssuesRequest, Tag, TokenRefreshRequest, TokenRefreshResponse, UpdateIssueRequest,
     UpdateMemberRoleRequest, UpdateMemberRoleResponse, UpdateOrganizationRequest,
     UpdatePullRequestApiRequest, UpdateWorkspaceRequest, UpsertPullRequestRequest, Workspace,
+    WorkspaceChangesResponse,
 };
 use backon::{ExponentialBuilder, Retryable};
 use chrono::Duration as ChronoDuration;
@@ -307,6 +309,26 @@ impl RemoteClient {
             .map_err(|e| self.map_api_error(e))
     }
 
+    /// Initiates a device-code login for clients without a local browser.
+    pub async fn device_init(
+        &self,
+        request: &DeviceInitRequest,
+    ) -> Result<DeviceInitResponse, RemoteClientError> {
+        self.post_public("/v1/oauth/device/init", Some(request))
+            .await
+            .map_err(|e| self.map_api_error(e))
+    }
+
+    /// Polls a device-code login; yields the app code once the user has signed in.
+    pub async fn device_poll(
+        &self,
+        request: &DevicePollRequest,
+    ) -> Result<DevicePollResponse, RemoteClientError> {
+        self.post_public("/v1/oauth/device/poll", Some(request))
+            .await
+            .map_err(|e| self.map_api_error(e))
+    }
+
     pub async fn local_login(
         &self,
         request: &LocalLoginRequest,
@@ -694,6 +716,20 @@ impl RemoteClient {
         }
     }
 
+    /// Lists workspaces in a project changed since `since`, the cursor from a
+    /// previous call, or all of them when `since` is `None`.
+    pub async fn list_workspace_changes(
+        &self,
+        project_id: Uuid,
+        since: Option<i64>,
+    ) -> Result<WorkspaceChangesResponse, RemoteClientError> {
+        let mut path = format!("/v1/workspaces/changes?project_id={project_id}");
+        if let Some(since) = since {
+            path.push_str(&format!("&since={since}"));
+        }
+        self.get_authed(&path).await
+    }
+
     /// Updates a workspace on the remote server.
     pub async fn update_workspace(
         &self,
@@ -703,6 +739,7 @@ impl RemoteClient {
         files_changed: Option<i32>,
         lines_added: Option<i32>,
         lines_removed: Option<i32>,
+        time_spent_ms: Option<i64>,
     ) -> Result<(), RemoteClientError> {
         self.send(
             reqwest::Method::PATCH,
@@ -715,6 +752,7 @@ impl RemoteClient {
                 files_changed: files_changed.map(Some),
                 lines_added: lines_added.map(Some),
                 lines_removed: lines_removed.map(Some),
+                time_spent_ms: time_spent_ms.map(Some),
             }),
         )
         .await?;
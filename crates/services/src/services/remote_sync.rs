@@ -1,5 +1,5 @@
 use api_types::UpsertPullRequestRequest;
-use db::models::workspace::Workspace;
+use db::models::{execution_process::ExecutionProcess, workspace::Workspace};
 use git::GitService;
 use sqlx::SqlitePool;
 use tracing::{debug, error};
@@ -16,6 +16,7 @@ async fn update_workspace_on_remote(
     name: Option<Option<String>>,
     archived: Option<bool>,
     stats: Option<&DiffStats>,
+    time_spent_ms: Option<i64>,
 ) {
     match client
         .update_workspace(
@@ -25,6 +26,7 @@ async fn update_workspace_on_remote(
             stats.map(|s| s.files_changed as i32),
             stats.map(|s| s.lines_added as i32),
             stats.map(|s| s.lines_removed as i32),
+            time_spent_ms,
         )
         .await
     {
@@ -48,12 +50,14 @@ async fn update_workspace_on_remote(
 
 /// Syncs workspace data to the remote server.
 /// First checks if the workspace exists on remote, then updates if it does.
+/// `None` fields are left unchanged on the remote.
 pub async fn sync_workspace_to_remote(
     client: &RemoteClient,
     workspace_id: Uuid,
     name: Option<Option<String>>,
     archived: Option<bool>,
     stats: Option<&DiffStats>,
+    time_spent_ms: Option<i64>,
 ) {
     // First check if workspace exists on remote
     match client.workspace_exists(workspace_id).await {
@@ -79,7 +83,7 @@ pub async fn sync_workspace_to_remote(
     }
 
     // Workspace exists, proceed with update
-    update_workspace_on_remote(client, workspace_id, name, archived, stats).await;
+    update_workspace_on_remote(client, workspace_id, name, archived, stats, time_spent_ms).await;
 }
 
 /// Syncs issue status to remote for a workspace merged locally without a PR.
@@ -208,12 +212,16 @@ pub async fn sync_all_linked_workspaces(
         }
 
         let stats = diff_stream::compute_diff_stats(pool, git, workspace).await;
+        let time_spent_ms = ExecutionProcess::time_spent_for_workspace(pool, workspace.id)
+            .await
+            .ok();
         update_workspace_on_remote(
             client,
             workspace.id,
             workspace.name.clone().map(Some),
             Some(workspace.archived),
             stats.as_ref(),
+            time_spent_ms,
         )
         .await;
     }
@@ -0,0 +1,220 @@
+//! Background jobs that renormalize the logs of many finished execution
+//! processes at once, e.g. after a normalizer upgrade.
+//!
+//! Jobs run a bounded number of processes at a time so a large backlog
+//! doesn't saturate the CPU or disk. Progress is published on the events
+//! stream under `/renormalize_jobs/{id}`.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, RwLock},
+};
+
+use chrono::{DateTime, Utc};
+use futures::StreamExt;
+use serde::Serialize;
+use ts_rs::TS;
+use utils::msg_store::MsgStore;
+use uuid::Uuid;
+
+use super::{container::ContainerError, events::patches::renormalize_job_patch};
+
+pub const DEFAULT_RENORMALIZE_CONCURRENCY: usize = 2;
+pub const MAX_RENORMALIZE_CONCURRENCY: usize = 8;
+
+/// Finished jobs kept around for `list`; older ones are dropped.
+const FINISHED_JOBS_KEPT: usize = 20;
+
+/// What renormalizing a single process did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenormalizeOutcome {
+    Renormalized,
+    /// The process hasn't finished, so its log isn't final yet.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum RenormalizeJobStatus {
+    Running,
+    Completed,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RenormalizeJob {
+    pub id: Uuid,
+    pub workspace_id: Option<Uuid>,
+    pub repo_id: Option<Uuid>,
+    pub concurrency: usize,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub status: RenormalizeJobStatus,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone)]
+pub struct RenormalizeJobs {
+    jobs: Arc<RwLock<HashMap<Uuid, RenormalizeJob>>>,
+    msg_store: Arc<MsgStore>,
+}
+
+impl RenormalizeJobs {
+    pub fn new(msg_store: Arc<MsgStore>) -> Self {
+        Self {
+            jobs: Arc::default(),
+            msg_store,
+        }
+    }
+
+    /// Register a job over `total` processes. `concurrency` is clamped to
+    /// `1..=MAX_RENORMALIZE_CONCURRENCY`.
+    pub fn create(
+        &self,
+        workspace_id: Option<Uuid>,
+        repo_id: Option<Uuid>,
+        concurrency: usize,
+        total: usize,
+    ) -> RenormalizeJob {
+        let job = RenormalizeJob {
+            id: Uuid::new_v4(),
+            workspace_id,
+            repo_id,
+            concurrency: concurrency.clamp(1, MAX_RENORMALIZE_CONCURRENCY),
+            total,
+            completed: 0,
+            failed: 0,
+            skipped: 0,
+            status: RenormalizeJobStatus::Running,
+            started_at: Utc::now(),
+            finished_at: None,
+        };
+        self.jobs.write().unwrap().insert(job.id, job.clone());
+        self.publish(&job);
+        job
+    }
+
+    /// All known jobs, newest first.
+    pub fn list(&self) -> Vec<RenormalizeJob> {
+        let mut jobs: Vec<_> = self.jobs.read().unwrap().values().cloned().collect();
+        jobs.sort_by(|a, b| b.started_at.cmp(&a.started_at));
+        jobs
+    }
+
+    pub fn get(&self, id: Uuid) -> Option<RenormalizeJob> {
+        self.jobs.read().unwrap().get(&id).cloned()
+    }
+
+    /// Renormalize `process_ids` for the job with `renormalize`, at most
+    /// `job.concurrency` at a time. A failed process is counted and logged;
+    /// the job carries on.
+    pub async fn run<F, Fut>(&self, job_id: Uuid, process_ids: Vec<Uuid>, renormalize: F)
+    where
+        F: Fn(Uuid) -> Fut,
+        Fut: Future<Output = Result<RenormalizeOutcome, ContainerError>>,
+    {
+        let Some(concurrency) = self.get(job_id).map(|job| job.concurrency) else {
+            return;
+        };
+
+        futures::stream::iter(process_ids)
+            .for_each_concurrent(concurrency, |id| {
+                let renormalized = renormalize(id);
+                async move {
+                    let result = renormalized.await;
+                    if let Err(e) = &result {
+                        tracing::warn!("Failed to renormalize execution process {}: {}", id, e);
+                    }
+                    self.update(job_id, |job| match result {
+                        Ok(RenormalizeOutcome::Renormalized) => job.completed += 1,
+                        Ok(RenormalizeOutcome::Skipped) => job.skipped += 1,
+                        Err(_) => job.failed += 1,
+                    });
+                }
+            })
+            .await;
+
+        self.update(job_id, |job| {
+            job.status = RenormalizeJobStatus::Completed;
+            job.finished_at = Some(Utc::now());
+        });
+        self.prune_finished();
+    }
+
+    fn update(&self, job_id: Uuid, f: impl FnOnce(&mut RenormalizeJob)) {
+        let job = {
+            let mut jobs = self.jobs.write().unwrap();
+            let Some(job) = jobs.get_mut(&job_id) else {
+                return;
+            };
+            f(job);
+            job.clone()
+        };
+        self.publish(&job);
+    }
+
+    fn publish(&self, job: &RenormalizeJob) {
+        self.msg_store
+            .push_patch(renormalize_job_patch::replace(job));
+    }
+
+    fn prune_finished(&self) {
+        let mut jobs = self.jobs.write().unwrap();
+        let mut finished: Vec<_> = jobs
+            .values()
+            .filter(|job| job.status == RenormalizeJobStatus::Completed)
+            .map(|job| (job.started_at, job.id))
+            .collect();
+        if finished.len() <= FINISHED_JOBS_KEPT {
+            return;
+        }
+        finished.sort();
+        for (_, id) in &finished[..finished.len() - FINISHED_JOBS_KEPT] {
+            jobs.remove(id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn run_counts_each_outcome() {
+        let jobs = RenormalizeJobs::new(Arc::new(MsgStore::new()));
+        let ids: Vec<Uuid> = (0..5).map(|_| Uuid::new_v4()).collect();
+        let job = jobs.create(None, None, 2, ids.len());
+
+        let (skipped, failed) = (ids[1], ids[3]);
+        jobs.run(job.id, ids, |id| async move {
+            if id == skipped {
+                Ok(RenormalizeOutcome::Skipped)
+            } else if id == failed {
+                Err(ContainerError::Other(anyhow!("no logs")))
+            } else {
+                Ok(RenormalizeOutcome::Renormalized)
+            }
+        })
+        .await;
+
+        let job = jobs.get(job.id).unwrap();
+        assert_eq!((job.completed, job.skipped, job.failed), (3, 1, 1));
+        assert_eq!(job.status, RenormalizeJobStatus::Completed);
+        assert!(job.finished_at.is_some());
+    }
+
+    #[test]
+    fn clamps_concurrency() {
+        let jobs = RenormalizeJobs::new(Arc::new(MsgStore::new()));
+        assert_eq!(jobs.create(None, None, 0, 1).concurrency, 1);
+        assert_eq!(
+            jobs.create(None, None, 100, 1).concurrency,
+            MAX_RENORMALIZE_CONCURRENCY
+        );
+    }
+}
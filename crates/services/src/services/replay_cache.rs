@@ -0,0 +1,289 @@
+//! Opt-in on-disk cache of normalized patches for finished execution processes,
+//! so reopening a historical session skips re-parsing the raw log.
+//!
+//! Entries are keyed by execution id and [`NORMALIZER_VERSION`]; bumping the
+//! version makes every existing entry a miss. Entries are streamed line by line
+//! in both directions so large sessions are never held in memory. The cache is
+//! pruned by age and total size whenever a new entry is published.
+
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use executors::logs::NORMALIZER_VERSION;
+use futures::{StreamExt, stream::BoxStream};
+use json_patch::Patch;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, BufWriter};
+use uuid::Uuid;
+
+const REPLAY_CACHE_DIRNAME: &str = "session-replays";
+/// Entries not rewritten for this long are removed.
+const MAX_ENTRY_AGE: Duration = Duration::from_secs(30 * 24 * 60 * 60);
+/// Oldest entries are removed once the cache grows past this.
+const MAX_CACHE_BYTES: u64 = 512 * 1024 * 1024;
+/// Temp files older than this were left behind by an interrupted replay.
+const STALE_TMP_AGE: Duration = Duration::from_secs(60 * 60);
+
+fn cache_root() -> PathBuf {
+    utils::cache_dir().join(REPLAY_CACHE_DIRNAME)
+}
+
+fn cache_path(root: &Path, execution_id: Uuid) -> PathBuf {
+    root.join(format!("{execution_id}.v{NORMALIZER_VERSION}.jsonl"))
+}
+
+/// Stream the cached patches for `execution_id`, or `None` on a miss.
+pub async fn open(execution_id: Uuid) -> Option<BoxStream<'static, Patch>> {
+    open_in(&cache_root(), execution_id).await
+}
+
+async fn open_in(root: &Path, execution_id: Uuid) -> Option<BoxStream<'static, Patch>> {
+    let path = cache_path(root, execution_id);
+    // A corrupt entry is a miss rather than a truncated replay, so check every
+    // line before handing out the stream.
+    if let Err(e) = validate(&path).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            tracing::warn!(
+                "Discarding corrupt replay cache for {}: {}",
+                execution_id,
+                e
+            );
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+        return None;
+    }
+    let file = tokio::fs::File::open(&path).await.ok()?;
+    let lines = BufReader::new(file).lines();
+
+    Some(
+        futures::stream::unfold(lines, move |mut lines| async move {
+            loop {
+                let line = match lines.next_line().await {
+                    Ok(Some(line)) => line,
+                    Ok(None) => return None,
+                    Err(e) => {
+                        tracing::warn!("Failed to read replay cache for {}: {}", execution_id, e);
+                        return None;
+                    }
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Patch>(&line) {
+                    Ok(patch) => return Some((patch, lines)),
+                    Err(e) => {
+                        tracing::warn!(
+                            "Replay cache for {} changed while read: {}",
+                            execution_id,
+                            e
+                        );
+                        return None;
+                    }
+                }
+            }
+        })
+        .boxed(),
+    )
+}
+
+/// Check that every line of the entry at `path` parses as a patch.
+async fn validate(path: &Path) -> std::io::Result<()> {
+    let file = tokio::fs::File::open(path).await?;
+    let mut lines = BufReader::new(file).lines();
+    while let Some(line) = lines.next_line().await? {
+        if !line.trim().is_empty() {
+            serde_json::from_str::<Patch>(&line)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove every cached entry for `execution_id`, e.g. after its log is renormalized.
+pub async fn invalidate(execution_id: Uuid) {
+    remove_entries(&cache_root(), execution_id, None).await;
+}
+
+/// Writes a new cache entry to a temp file; it only replaces the real entry on
+/// [`ReplayCacheWriter::finish`], so an interrupted replay leaves no partial cache.
+pub struct ReplayCacheWriter {
+    root: PathBuf,
+    execution_id: Uuid,
+    tmp_path: PathBuf,
+    file: BufWriter<tokio::fs::File>,
+}
+
+impl ReplayCacheWriter {
+    pub async fn create(execution_id: Uuid) -> std::io::Result<Self> {
+        Self::create_in(cache_root(), execution_id).await
+    }
+
+    async fn create_in(root: PathBuf, execution_id: Uuid) -> std::io::Result<Self> {
+        tokio::fs::create_dir_all(&root).await?;
+        // Unique per writer, so concurrent replays of one process don't
+        // interleave into the same temp file.
+        let tmp_path = root.join(format!(
+            "{execution_id}.v{NORMALIZER_VERSION}.{}.jsonl.tmp",
+            Uuid::new_v4()
+        ));
+        let file = tokio::fs::File::create(&tmp_path).await?;
+        Ok(Self {
+            root,
+            execution_id,
+            tmp_path,
+            file: BufWriter::new(file),
+        })
+    }
+
+    pub async fn append(&mut self, patch: &Patch) -> std::io::Result<()> {
+        let mut line = serde_json::to_string(patch)?;
+        line.push('\n');
+        self.file.write_all(line.as_bytes()).await
+    }
+
+    /// Publish the entry, drop entries from older normalizer versions and
+    /// prune the cache.
+    pub async fn finish(mut self) -> std::io::Result<()> {
+        self.file.flush().await?;
+        let path = cache_path(&self.root, self.execution_id);
+        tokio::fs::rename(&self.tmp_path, &path).await?;
+        remove_entries(&self.root, self.execution_id, Some(&path)).await;
+        prune(
+            &self.root,
+            MAX_CACHE_BYTES,
+            MAX_ENTRY_AGE,
+            SystemTime::now(),
+        )
+        .await;
+        Ok(())
+    }
+}
+
+async fn remove_entries(root: &Path, execution_id: Uuid, keep: Option<&PathBuf>) {
+    let Ok(mut entries) = tokio::fs::read_dir(root).await else {
+        return;
+    };
+    let prefix = format!("{execution_id}.");
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let is_entry = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.starts_with(&prefix) && name.ends_with(".jsonl"));
+        if is_entry && Some(&path) != keep {
+            let _ = tokio::fs::remove_file(&path).await;
+        }
+    }
+}
+
+/// Remove entries older than `max_age` and abandoned temp files, then the
+/// oldest entries until the rest fit in `max_bytes`.
+async fn prune(root: &Path, max_bytes: u64, max_age: Duration, now: SystemTime) {
+    let Ok(mut dir) = tokio::fs::read_dir(root).await else {
+        return;
+    };
+    let mut kept = Vec::new();
+    while let Ok(Some(entry)) = dir.next_entry().await {
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| now.duration_since(modified).ok())
+            .unwrap_or_default();
+        let path = entry.path();
+        let expired = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("jsonl") => age > max_age,
+            Some("tmp") => age > STALE_TMP_AGE,
+            _ => false,
+        };
+        if expired {
+            let _ = tokio::fs::remove_file(&path).await;
+        } else if path.extension().is_some_and(|ext| ext == "jsonl") {
+            kept.push((age, metadata.len(), path));
+        }
+    }
+
+    let mut total: u64 = kept.iter().map(|(_, len, _)| len).sum();
+    // Oldest first
+    kept.sort_by_key(|(age, _, _)| std::cmp::Reverse(*age));
+    for (_, len, path) in kept {
+        if total <= max_bytes {
+            break;
+        }
+        if tokio::fs::remove_file(&path).await.is_ok() {
+            total -= len;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use json_patch::{AddOperation, PatchOperation};
+
+    use super::*;
+
+    fn patch(value: u32) -> Patch {
+        Patch(vec![PatchOperation::Add(AddOperation {
+            path: "/entries/0".to_string().try_into().unwrap(),
+            value: serde_json::json!(value),
+        })])
+    }
+
+    #[tokio::test]
+    async fn corrupt_entry_is_a_miss_and_removed() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = Uuid::new_v4();
+        let path = cache_path(dir.path(), id);
+        let valid = serde_json::to_string(&patch(1)).unwrap();
+        tokio::fs::write(&path, format!("{valid}\n{{\"op\": trunc"))
+            .await
+            .unwrap();
+
+        assert!(open_in(dir.path(), id).await.is_none());
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn published_entry_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let id = Uuid::new_v4();
+        let mut writer = ReplayCacheWriter::create_in(dir.path().to_path_buf(), id)
+            .await
+            .unwrap();
+        writer.append(&patch(1)).await.unwrap();
+        writer.append(&patch(2)).await.unwrap();
+        writer.finish().await.unwrap();
+
+        let patches: Vec<Patch> = open_in(dir.path(), id).await.unwrap().collect().await;
+        assert_eq!(patches, [patch(1), patch(2)]);
+    }
+
+    #[tokio::test]
+    async fn prune_drops_expired_then_oldest_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let now = SystemTime::now();
+        let day = Duration::from_secs(24 * 60 * 60);
+        let write = |name: &str, age: Duration| {
+            let path = dir.path().join(name);
+            let file = std::fs::File::create(&path).unwrap();
+            file.set_len(100).unwrap();
+            file.set_modified(now - age).unwrap();
+            path
+        };
+        let expired = write("expired.jsonl", 40 * day);
+        let oldest = write("oldest.jsonl", 3 * day);
+        let newer = write("newer.jsonl", 2 * day);
+        let newest = write("newest.jsonl", day);
+        let stale_tmp = write("abandoned.jsonl.tmp", day);
+
+        prune(dir.path(), 200, 30 * day, now).await;
+
+        assert!(!expired.exists());
+        assert!(!oldest.exists());
+        assert!(newer.exists());
+        assert!(newest.exists());
+        assert!(!stale_tmp.exists());
+    }
+}
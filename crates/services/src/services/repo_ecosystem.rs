@@ -0,0 +1,152 @@
+//! Cheap language/framework detection from marker files at the repo root, used
+//! to suggest a setup script when a repo is configured.
+
+use std::path::Path;
+
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum Ecosystem {
+    Node,
+    Rust,
+    Python,
+    Go,
+    Ruby,
+    Java,
+    Php,
+    Dotnet,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
+pub struct DetectedEcosystem {
+    pub ecosystem: Ecosystem,
+    /// Top-level files that matched, in the order they were checked.
+    pub marker_files: Vec<String>,
+    /// Suggested setup script for this ecosystem.
+    pub setup_command: String,
+}
+
+/// Marker files per ecosystem. Earlier entries in the table win ties.
+const MARKERS: &[(Ecosystem, &[&str])] = &[
+    (
+        Ecosystem::Node,
+        &[
+            "package.json",
+            "pnpm-lock.yaml",
+            "yarn.lock",
+            "bun.lockb",
+            "bun.lock",
+            "package-lock.json",
+        ],
+    ),
+    (Ecosystem::Rust, &["Cargo.toml", "Cargo.lock"]),
+    (
+        Ecosystem::Python,
+        &[
+            "pyproject.toml",
+            "uv.lock",
+            "poetry.lock",
+            "requirements.txt",
+            "Pipfile",
+            "setup.py",
+        ],
+    ),
+    (Ecosystem::Go, &["go.mod", "go.sum"]),
+    (Ecosystem::Ruby, &["Gemfile", "Gemfile.lock"]),
+    (
+        Ecosystem::Java,
+        &["pom.xml", "build.gradle", "build.gradle.kts", "gradlew"],
+    ),
+    (Ecosystem::Php, &["composer.json", "composer.lock"]),
+    (Ecosystem::Dotnet, &["global.json", "Directory.Build.props"]),
+];
+
+/// Detect ecosystems from files in the top level of `repo_path`, ranked by how
+/// many marker files matched.
+pub fn detect_ecosystems(repo_path: &Path) -> Vec<DetectedEcosystem> {
+    let exists = |name: &str| repo_path.join(name).is_file();
+
+    let mut detected: Vec<DetectedEcosystem> = MARKERS
+        .iter()
+        .filter_map(|(ecosystem, files)| {
+            let marker_files: Vec<String> = files
+                .iter()
+                .filter(|f| exists(f))
+                .map(|f| f.to_string())
+                .collect();
+            (!marker_files.is_empty()).then(|| DetectedEcosystem {
+                ecosystem: *ecosystem,
+                setup_command: setup_command(*ecosystem, &marker_files),
+                marker_files,
+            })
+        })
+        .collect();
+
+    // .sln/.csproj names vary, so look for the extension instead.
+    if !detected.iter().any(|d| d.ecosystem == Ecosystem::Dotnet)
+        && let Ok(entries) = std::fs::read_dir(repo_path)
+    {
+        let marker_files: Vec<String> = entries
+            .flatten()
+            .filter_map(|e| e.file_name().into_string().ok())
+            .filter(|name| name.ends_with(".sln") || name.ends_with(".csproj"))
+            .collect();
+        if !marker_files.is_empty() {
+            detected.push(DetectedEcosystem {
+                ecosystem: Ecosystem::Dotnet,
+                setup_command: setup_command(Ecosystem::Dotnet, &marker_files),
+                marker_files,
+            });
+        }
+    }
+
+    // Stable sort keeps table order for ties.
+    detected.sort_by_key(|d| std::cmp::Reverse(d.marker_files.len()));
+    detected
+}
+
+fn setup_command(ecosystem: Ecosystem, markers: &[String]) -> String {
+    let has = |name: &str| markers.iter().any(|m| m == name);
+    let command = match ecosystem {
+        Ecosystem::Node if has("pnpm-lock.yaml") => "pnpm install",
+        Ecosystem::Node if has("yarn.lock") => "yarn install",
+        Ecosystem::Node if has("bun.lockb") || has("bun.lock") => "bun install",
+        Ecosystem::Node => "npm install",
+        Ecosystem::Rust => "cargo build",
+        Ecosystem::Python if has("uv.lock") => "uv sync",
+        Ecosystem::Python if has("poetry.lock") => "poetry install",
+        Ecosystem::Python if has("requirements.txt") => "pip install -r requirements.txt",
+        Ecosystem::Python if has("Pipfile") => "pipenv install",
+        Ecosystem::Python => "pip install -e .",
+        Ecosystem::Go => "go mod download",
+        Ecosystem::Ruby => "bundle install",
+        Ecosystem::Java if has("gradlew") => "./gradlew build -x test",
+        Ecosystem::Java if has("pom.xml") => "mvn -q install -DskipTests",
+        Ecosystem::Java => "gradle build -x test",
+        Ecosystem::Php => "composer install",
+        Ecosystem::Dotnet => "dotnet restore",
+    };
+    command.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_by_marker_count_and_picks_package_manager() {
+        let dir = tempfile::tempdir().unwrap();
+        for file in ["package.json", "pnpm-lock.yaml", "Cargo.toml"] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+
+        let detected = detect_ecosystems(dir.path());
+
+        assert_eq!(detected.len(), 2);
+        assert_eq!(detected[0].ecosystem, Ecosystem::Node);
+        assert_eq!(detected[0].setup_command, "pnpm install");
+        assert_eq!(detected[1].ecosystem, Ecosystem::Rust);
+    }
+}
@@ -0,0 +1,84 @@
+//! `{name}` placeholders shared by the user-editable templates: pull
+//! requests, notifications, branch names, issue prompts and denied tool
+//! notes.
+//!
+//! Braces that don't wrap a lowercase name (code samples, JSON) are left
+//! alone, so only misspelled placeholders are rejected.
+
+use thiserror::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Unknown placeholder `{{{0}}}`.")]
+pub struct UnknownPlaceholder(pub String);
+
+/// `(start, end, name)` for every `{name}` in `template`.
+pub(crate) fn placeholders(template: &str) -> Vec<(usize, usize, &str)> {
+    let mut found = Vec::new();
+    let mut from = 0;
+    while let Some(offset) = template[from..].find('{') {
+        let open = from + offset;
+        let rest = &template[open + 1..];
+        let len = rest
+            .find(|c: char| !(c.is_ascii_lowercase() || c == '_'))
+            .unwrap_or(rest.len());
+        if len > 0 && rest[len..].starts_with('}') {
+            let end = open + len + 2;
+            found.push((open, end, &rest[..len]));
+            from = end;
+        } else {
+            from = open + 1;
+        }
+    }
+    found
+}
+
+/// Reject the first placeholder that isn't in `allowed`.
+pub fn validate(template: &str, allowed: &[&str]) -> Result<(), UnknownPlaceholder> {
+    match placeholders(template)
+        .into_iter()
+        .find(|(_, _, name)| !allowed.contains(name))
+    {
+        Some((_, _, name)) => Err(UnknownPlaceholder(name.to_string())),
+        None => Ok(()),
+    }
+}
+
+/// Substitute the `(name, value)` pairs in one pass, so values containing
+/// `{...}` are never expanded again. Other placeholders are kept as written.
+pub fn render(template: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut last = 0;
+    for (start, end, name) in placeholders(template) {
+        let Some((_, value)) = values.iter().find(|(key, _)| *key == name) else {
+            continue;
+        };
+        out.push_str(&template[last..start]);
+        out.push_str(value);
+        last = end;
+    }
+    out.push_str(&template[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_known_placeholders_once() {
+        let values = [("tool", "Bash"), ("reason", "uses {tool}")];
+        assert_eq!(
+            render("{tool} denied: {reason} {other} {\"a\": 1}", &values),
+            "Bash denied: uses {tool} {other} {\"a\": 1}"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_placeholders() {
+        assert_eq!(validate("{tool} {\"a\": 1} {Tool}", &["tool"]), Ok(()));
+        assert_eq!(
+            validate("{tool}: {reasn}", &["tool", "reason"]),
+            Err(UnknownPlaceholder("reasn".into()))
+        );
+    }
+}
@@ -0,0 +1,70 @@
+//! Tokens a coding agent run was billed for, read from the usage totals its
+//! log normalizer records when the run ends. Finished runs store the totals
+//! when they exit. Only Claude Code reports them so far; other runs, and runs
+//! still in progress, read as zero.
+
+use db::models::coding_agent_turn::CodingAgentTurn;
+use executors::logs::{NormalizedEntryType, TokenUsageTotals, utils::patch};
+use sqlx::SqlitePool;
+use utils::log_msg::LogMsg;
+use uuid::Uuid;
+
+use crate::services::container::ContainerService;
+
+pub async fn execution_token_usage(
+    container: &impl ContainerService,
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+) -> Result<TokenUsageTotals, sqlx::Error> {
+    let totals = match container.get_msg_store_by_id(&execution_process_id).await {
+        Some(store) => session_totals(&store.get_history()),
+        None => find(pool, execution_process_id).await?,
+    };
+    Ok(totals.unwrap_or_default())
+}
+
+/// Store the totals a finished run reported, if any.
+pub async fn record(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+    messages: &[LogMsg],
+) -> Result<(), sqlx::Error> {
+    let Some(totals) = session_totals(messages) else {
+        return Ok(());
+    };
+    let json = serde_json::to_string(&totals).map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+    CodingAgentTurn::update_token_usage(pool, execution_process_id, &json).await
+}
+
+async fn find(
+    pool: &SqlitePool,
+    execution_process_id: Uuid,
+) -> Result<Option<TokenUsageTotals>, sqlx::Error> {
+    let Some(json) = CodingAgentTurn::find_token_usage(pool, execution_process_id).await? else {
+        return Ok(None);
+    };
+    match serde_json::from_str(&json) {
+        Ok(totals) => Ok(Some(totals)),
+        Err(e) => {
+            tracing::warn!(
+                "Ignoring unreadable token usage for {}: {}",
+                execution_process_id,
+                e
+            );
+            Ok(None)
+        }
+    }
+}
+
+/// The last totals reported in the log.
+fn session_totals(messages: &[LogMsg]) -> Option<TokenUsageTotals> {
+    messages.iter().rev().find_map(|msg| {
+        let LogMsg::JsonPatch(p) = msg else {
+            return None;
+        };
+        match patch::extract_normalized_entry_from_patch(p)?.1.entry_type {
+            NormalizedEntryType::TokenUsageInfo(info) => info.session_totals,
+            _ => None,
+        }
+    })
+}
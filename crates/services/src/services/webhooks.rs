@@ -0,0 +1,329 @@
+//! Posts workspace and execution lifecycle events to the webhooks in
+//! `Config::webhooks`. Events are read off the [`EventService`] stream, so
+//! they fire however the change was made.
+//!
+//! Each delivery is a JSON body such as `{"event": "execution_completed",
+//! "timestamp": "...", "data": {...}}`, where `data` is the record the event
+//! is about. Webhooks with a secret also get an `X-VK-Signature:
+//! sha256=<hex>` header holding the HMAC-SHA256 of the body.
+//!
+//! [`EventService`]: crate::services::events::EventService
+
+use std::{
+    collections::{HashSet, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use backon::{ExponentialBuilder, Retryable};
+use chrono::{DateTime, Utc};
+use db::models::{
+    execution_process::{ExecutionProcess, ExecutionProcessStatus},
+    merge::Merge,
+};
+use hmac::{Hmac, Mac};
+use json_patch::{Patch, PatchOperation};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::{RwLock, broadcast::error::RecvError};
+use utils::{log_msg::LogMsg, msg_store::MsgStore};
+use uuid::Uuid;
+
+use crate::services::{
+    config::{Config, WebhookConfig, WebhookEventType},
+    events::{EventPatchInner, RecordTypes},
+};
+
+pub const SIGNATURE_HEADER: &str = "X-VK-Signature";
+
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+/// How many ids of each kind [`LifecycleTracker`] remembers. Records are only
+/// replaced while they are recent, so the oldest are forgotten first.
+const TRACKED_IDS: usize = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WebhookPayload {
+    pub event: WebhookEventType,
+    pub timestamp: DateTime<Utc>,
+    pub data: serde_json::Value,
+}
+
+pub struct WebhookService;
+
+impl WebhookService {
+    /// Deliver events from the event stream until it closes. Webhooks are
+    /// read from the config for every event, so edits apply right away.
+    pub fn spawn(config: Arc<RwLock<Config>>, events: Arc<MsgStore>) {
+        let client = reqwest::Client::builder()
+            .timeout(DELIVERY_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        let mut rx = events.get_receiver();
+        tokio::spawn(async move {
+            let mut tracker = LifecycleTracker::default();
+            loop {
+                let patch = match rx.recv().await {
+                    Ok(LogMsg::JsonPatch(patch)) => patch,
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!("Webhooks missed {} events", skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => break,
+                };
+                let events = tracker.events(&patch);
+                if events.is_empty() {
+                    continue;
+                }
+                let webhooks = config.read().await.webhooks.clone();
+                for (event, data) in events {
+                    let payload = WebhookPayload {
+                        event,
+                        timestamp: Utc::now(),
+                        data,
+                    };
+                    for webhook in webhooks.iter().filter(|w| w.wants(event)) {
+                        tokio::spawn(deliver(client.clone(), webhook.clone(), payload.clone()));
+                    }
+                }
+            }
+        });
+    }
+}
+
+impl WebhookConfig {
+    fn wants(&self, event: WebhookEventType) -> bool {
+        self.events.is_empty() || self.events.contains(&event)
+    }
+}
+
+/// POST the payload, retrying failures a few times with backoff before
+/// giving up on it.
+pub async fn deliver(client: reqwest::Client, webhook: WebhookConfig, payload: WebhookPayload) {
+    let body = match serde_json::to_vec(&payload) {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to serialize webhook payload: {}", e);
+            return;
+        }
+    };
+    let send = || async {
+        let mut request = client
+            .post(&webhook.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(secret) = &webhook.secret {
+            request = request.header(SIGNATURE_HEADER, signature(secret, &body));
+        }
+        request.send().await?.error_for_status()?;
+        Ok::<_, reqwest::Error>(())
+    };
+    if let Err(e) = send
+        .retry(
+            &ExponentialBuilder::default()
+                .with_min_delay(Duration::from_secs(1))
+                .with_max_delay(Duration::from_secs(30))
+                .with_max_times(4)
+                .with_jitter(),
+        )
+        .await
+    {
+        tracing::warn!(
+            "Dropping {:?} webhook delivery to {}: {}",
+            payload.event,
+            webhook.url,
+            e
+        );
+    }
+}
+
+/// `sha256=` followed by the hex HMAC-SHA256 of `body`.
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts any key length");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Turns event patches into lifecycle events. Records are replaced many
+/// times over their life, so recent process starts, finished processes and
+/// merges are remembered to report each only once.
+#[derive(Default)]
+struct LifecycleTracker {
+    started: RecentIds,
+    finished: RecentIds,
+    merged: RecentIds,
+}
+
+/// A set that forgets its oldest ids once it holds more than [`TRACKED_IDS`].
+#[derive(Default)]
+struct RecentIds {
+    ids: HashSet<Uuid>,
+    order: VecDeque<Uuid>,
+}
+
+impl RecentIds {
+    /// Returns whether `id` was new.
+    fn insert(&mut self, id: Uuid) -> bool {
+        if !self.ids.insert(id) {
+            return false;
+        }
+        self.order.push_back(id);
+        if self.order.len() > TRACKED_IDS
+            && let Some(oldest) = self.order.pop_front()
+        {
+            self.ids.remove(&oldest);
+        }
+        true
+    }
+
+    fn remove(&mut self, id: &Uuid) {
+        if self.ids.remove(id) {
+            self.order.retain(|other| other != id);
+        }
+    }
+}
+
+impl LifecycleTracker {
+    fn events(&mut self, patch: &Patch) -> Vec<(WebhookEventType, serde_json::Value)> {
+        let mut events = Vec::new();
+        for op in &patch.0 {
+            let (value, added) = match op {
+                PatchOperation::Add(op) => (&op.value, true),
+                PatchOperation::Replace(op) => (&op.value, false),
+                _ => continue,
+            };
+            let path = op.path();
+            if path.starts_with("/workspaces/") && added {
+                events.push((WebhookEventType::WorkspaceCreated, value.clone()));
+            } else if path.starts_with("/execution_processes/") {
+                let Ok(process) = serde_json::from_value::<ExecutionProcess>(value.clone()) else {
+                    continue;
+                };
+                // Queued coding agents are added before they start, so the
+                // start is the first record seen as running.
+                let event = match process.status {
+                    ExecutionProcessStatus::Running => WebhookEventType::ExecutionStarted,
+                    ExecutionProcessStatus::Completed => WebhookEventType::ExecutionCompleted,
+                    ExecutionProcessStatus::Failed => WebhookEventType::ExecutionFailed,
+                    ExecutionProcessStatus::Killed => {
+                        self.started.remove(&process.id);
+                        continue;
+                    }
+                    _ => continue,
+                };
+                let first = if event == WebhookEventType::ExecutionStarted {
+                    self.started.insert(process.id)
+                } else {
+                    self.started.remove(&process.id);
+                    self.finished.insert(process.id)
+                };
+                if !first {
+                    continue;
+                }
+                events.push((event, value.clone()));
+            } else if path.starts_with("/entries/")
+                && let Ok(EventPatchInner {
+                    record: RecordTypes::Merge(merge),
+                    ..
+                }) = serde_json::from_value(value.clone())
+            {
+                let id = match &merge {
+                    Merge::Direct(direct) => direct.id,
+                    Merge::Pr(pr) => pr.id,
+                };
+                if self.merged.insert(id)
+                    && let Ok(data) = serde_json::to_value(&merge)
+                {
+                    events.push((WebhookEventType::WorkspaceMerged, data));
+                }
+            }
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{Router, body::Bytes, http::HeaderMap, routing::post};
+    use tokio::sync::mpsc;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn delivers_signed_payloads() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/hook",
+            post(move |headers: HeaderMap, body: Bytes| {
+                let tx = tx.clone();
+                async move {
+                    let signature = headers
+                        .get(SIGNATURE_HEADER)
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string);
+                    let _ = tx.send((signature, body));
+                }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let webhook = WebhookConfig {
+            url: format!("http://{addr}/hook"),
+            secret: Some("s3cret".into()),
+            events: vec![],
+        };
+        let payload = WebhookPayload {
+            event: WebhookEventType::ExecutionCompleted,
+            timestamp: Utc::now(),
+            data: serde_json::json!({ "id": "abc" }),
+        };
+        deliver(reqwest::Client::new(), webhook, payload).await;
+
+        let (signature, body) = rx.recv().await.unwrap();
+        let mut mac = Hmac::<Sha256>::new_from_slice(b"s3cret").unwrap();
+        mac.update(&body);
+        assert_eq!(
+            signature.unwrap(),
+            format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+        );
+        let received: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(received["event"], "execution_completed");
+        assert_eq!(received["data"]["id"], "abc");
+    }
+
+    #[test]
+    fn recent_ids_forget_the_oldest() {
+        let mut ids = RecentIds::default();
+        let first = Uuid::new_v4();
+        assert!(ids.insert(first));
+        assert!(!ids.insert(first));
+        for _ in 0..TRACKED_IDS {
+            ids.insert(Uuid::new_v4());
+        }
+        assert_eq!(ids.ids.len(), TRACKED_IDS);
+        assert!(ids.insert(first));
+
+        ids.remove(&first);
+        assert_eq!(ids.order.len(), TRACKED_IDS - 1);
+        assert!(ids.insert(first));
+    }
+
+    #[test]
+    fn filters_events() {
+        let webhook = WebhookConfig {
+            url: String::new(),
+            secret: None,
+            events: vec![WebhookEventType::WorkspaceMerged],
+        };
+        assert!(webhook.wants(WebhookEventType::WorkspaceMerged));
+        assert!(!webhook.wants(WebhookEventType::ExecutionStarted));
+        let all = WebhookConfig {
+            events: vec![],
+            ..webhook
+        };
+        assert!(all.wants(WebhookEventType::ExecutionStarted));
+    }
+}
@@ -11,6 +11,7 @@ use services::services::{
 #[cfg(target_os = "macos")]
 use tauri::Manager;
 use tauri::{Emitter, Listener};
+use tauri_plugin_dialog::{DialogExt, MessageDialogKind};
 use tauri_plugin_notification::NotificationExt;
 use tauri_plugin_opener::OpenerExt;
 use tauri_plugin_updater::UpdaterExt;
@@ -87,25 +88,27 @@ fn read_clipboard_text() -> Result<String, String> {
 
 #[async_trait]
 impl PushNotifier for TauriNotifier {
-    async fn send(&self, title: &str, message: &str, workspace_id: Option<Uuid>) {
+    async fn send(
+        &self,
+        title: &str,
+        message: &str,
+        workspace_id: Option<Uuid>,
+    ) -> Result<(), String> {
         let deeplink_path = workspace_id.map(|id| format!("/workspaces/{id}"));
 
         if use_native_notifications() {
             show_native_notification(title, message, deeplink_path.as_deref());
-            return;
+            return Ok(());
         }
 
         // Fallback: tauri-plugin-notification (no click handling).
-        if let Err(e) = self
-            .app_handle
+        self.app_handle
             .notification()
             .builder()
             .title(title)
             .body(message)
             .show()
-        {
-            tracing::warn!("Failed to send Tauri notification: {}", e);
-        }
+            .map_err(|e| format!("Failed to send Tauri notification: {e}"))
     }
 }
 
@@ -143,6 +146,7 @@ fn main() {
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_notification::init())
+        .plugin(tauri_plugin_dialog::init())
         .invoke_handler(tauri::generate_handler![
             show_system_notification,
             read_clipboard_text
@@ -231,17 +235,40 @@ fn main() {
                             // Wait for either the server to exit on its own or
                             // the external shutdown token to be cancelled.
                             let server_token = server_handle.shutdown_token();
+                            let app_token = token.clone();
                             tauri::async_runtime::spawn(async move {
                                 token.cancelled().await;
                                 server_token.cancel();
                             });
 
-                            if let Err(e) = server_handle.serve().await {
-                                tracing::error!("Server error: {e}");
+                            let result = server_handle.serve().await;
+                            if app_token.is_cancelled() {
+                                if let Err(e) = result {
+                                    tracing::error!("Server error: {e}");
+                                }
+                                return;
+                            }
+
+                            // The server stopped without the app exiting, so
+                            // don't leave a dead window behind.
+                            match result {
+                                // e.g. idle shutdown
+                                Ok(()) => app_handle.exit(0),
+                                Err(e) => {
+                                    tracing::error!("Server error: {e}");
+                                    exit_with_error(
+                                        &app_handle,
+                                        format!("The Vibe Kanban server stopped unexpectedly:\n\n{e}"),
+                                    );
+                                }
                             }
                         }
                         Err(e) => {
                             tracing::error!("Server failed to start: {e}");
+                            exit_with_error(
+                                &app_handle,
+                                format!("Vibe Kanban couldn't start its server:\n\n{e}"),
+                            );
                         }
                     }
                 });
@@ -374,6 +401,17 @@ fn show_window(app: &tauri::AppHandle) {
     }
 }
 
+/// Without its server the app has nothing to show, so tell the user why
+/// before quitting instead of disappearing.
+fn exit_with_error(app: &tauri::AppHandle, message: String) {
+    let exit_handle = app.clone();
+    app.dialog()
+        .message(message)
+        .title("Vibe Kanban")
+        .kind(MessageDialogKind::Error)
+        .show(move |_| exit_handle.exit(1));
+}
+
 fn create_window<R: tauri::Runtime, M: tauri::Manager<R>>(
     manager: &M,
     url: tauri::WebviewUrl,
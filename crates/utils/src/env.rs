@@ -0,0 +1,30 @@
+/// Read a positive number from the environment variable `key`, falling back
+/// to `default` when it is unset, not a number, or zero.
+pub fn positive_or<T>(key: &str, default: T) -> T
+where
+    T: std::str::FromStr + PartialOrd + Default,
+{
+    std::env::var(key)
+        .ok()
+        .and_then(|value| value.trim().parse::<T>().ok())
+        .filter(|value| *value > T::default())
+        .unwrap_or(default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn falls_back_unless_positive() {
+        let key = "VK_TEST_ENV_POSITIVE_OR";
+        assert_eq!(positive_or(key, 7usize), 7);
+
+        for (value, expected) in [(" 12 ", 12u64), ("0", 7), ("-3", 7), ("many", 7)] {
+            // SAFETY: the key is unique to this test.
+            unsafe { std::env::set_var(key, value) };
+            assert_eq!(positive_or(key, 7u64), expected);
+        }
+        unsafe { std::env::remove_var(key) };
+    }
+}
@@ -1,4 +1,4 @@
-use std::{env, sync::OnceLock};
+use std::sync::OnceLock;
 
 use directories::ProjectDirs;
 
@@ -7,6 +7,7 @@ pub mod assets;
 pub mod browser;
 pub mod command_ext;
 pub mod diff;
+pub mod env;
 pub mod execution_logs;
 pub mod http_headers;
 pub mod jwt;
@@ -15,6 +16,7 @@ pub mod msg_store;
 pub mod path;
 pub mod port_file;
 pub mod process;
+pub mod process_usage;
 pub mod response;
 pub mod sentry;
 pub mod shell;
@@ -48,6 +50,30 @@ pub fn is_wsl2() -> bool {
     })
 }
 
+static BASE_PATH: OnceLock<String> = OnceLock::new();
+
+/// Set the path prefix from the `base_path` setting. Only the first call at
+/// startup counts; the router is built once.
+pub fn set_base_path(raw: &str) {
+    let _ = BASE_PATH.set(normalize_base_path(raw));
+}
+
+/// Path prefix the server is mounted under, e.g. `/vibe` when hosted behind a
+/// reverse proxy. Empty when served from the root or not configured.
+pub fn base_path() -> &'static str {
+    BASE_PATH.get().map(String::as_str).unwrap_or_default()
+}
+
+/// Normalise to a leading slash and no trailing slash, or empty for the root.
+pub fn normalize_base_path(raw: &str) -> String {
+    let trimmed = raw.trim().trim_matches('/');
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("/{trimmed}")
+    }
+}
+
 pub fn cache_dir() -> std::path::PathBuf {
     let proj = if cfg!(debug_assertions) {
         ProjectDirs::from("ai", "bloop-dev", env!("CARGO_PKG_NAME"))
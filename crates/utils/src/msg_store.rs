@@ -1,10 +1,15 @@
 use std::{
     collections::VecDeque,
     sync::{Arc, RwLock},
+    time::Duration,
 };
 
 use futures::{StreamExt, future};
-use tokio::{sync::broadcast, task::JoinHandle};
+use json_patch::PatchOperation;
+use tokio::{
+    sync::{broadcast, watch},
+    task::JoinHandle,
+};
 use tokio_stream::wrappers::{BroadcastStream, errors::BroadcastStreamRecvError};
 
 use crate::{log_msg::LogMsg, stream_lines::LinesStreamExt};
@@ -21,11 +26,80 @@ struct StoredMsg {
 struct Inner {
     history: VecDeque<StoredMsg>,
     total_bytes: usize,
+    /// Sequence number of `history[0]`; increases as old messages are evicted.
+    front_seq: u64,
+    /// Cursor at the most recent patch, when it set a single path: its
+    /// sequence number and that path.
+    last_patch: Option<(u64, String)>,
+}
+
+impl Inner {
+    /// Fold a replace of the path the previous patch set into that patch,
+    /// so an entry rewritten on every output delta keeps one history slot
+    /// instead of one full copy per delta. Returns the patch back if it
+    /// can't be folded.
+    fn coalesce(&mut self, patch: json_patch::Patch) -> Result<(), json_patch::Patch> {
+        let Some(pos) = self.coalesce_target(&patch) else {
+            return Err(patch);
+        };
+        let Some(PatchOperation::Replace(replace)) = patch.0.into_iter().next() else {
+            unreachable!("coalesce_target only accepts a single replace");
+        };
+
+        let stored = &mut self.history[pos];
+        if let LogMsg::JsonPatch(previous) = &mut stored.msg {
+            match previous.0.as_mut_slice() {
+                [PatchOperation::Add(op)] => op.value = replace.value,
+                [PatchOperation::Replace(op)] => op.value = replace.value,
+                _ => unreachable!("the cursor only points at single add/replace patches"),
+            }
+        }
+        let bytes = stored.msg.approx_bytes();
+        self.total_bytes = self.total_bytes.saturating_sub(stored.bytes) + bytes;
+        stored.bytes = bytes;
+        Ok(())
+    }
+
+    /// History position of the patch a single replace can fold into.
+    fn coalesce_target(&self, patch: &json_patch::Patch) -> Option<usize> {
+        let [PatchOperation::Replace(replace)] = patch.0.as_slice() else {
+            return None;
+        };
+        let (seq, path) = self.last_patch.as_ref()?;
+        if replace.path.to_string() != *path {
+            return None;
+        }
+        seq.checked_sub(self.front_seq)
+            .and_then(|pos| usize::try_from(pos).ok())
+            .filter(|pos| *pos < self.history.len())
+    }
+}
+
+/// The path a patch sets, if it is a single add or replace.
+fn single_set_path(patch: &json_patch::Patch) -> Option<String> {
+    match patch.0.as_slice() {
+        [PatchOperation::Add(op)] => Some(op.path.to_string()),
+        [PatchOperation::Replace(op)] => Some(op.path.to_string()),
+        _ => None,
+    }
 }
 
 pub struct MsgStore {
     inner: RwLock<Inner>,
     sender: broadcast::Sender<LogMsg>,
+    /// Set by [`MsgStore::finish`] once the raw output is complete.
+    input_closed: watch::Sender<bool>,
+    /// Number of live [`FinishHold`]s.
+    finish_holds: watch::Sender<usize>,
+}
+
+/// Keeps [`MsgStore::finish`] from pushing [`LogMsg::Finished`] until dropped.
+pub struct FinishHold(Arc<MsgStore>);
+
+impl Drop for FinishHold {
+    fn drop(&mut self) {
+        self.0.finish_holds.send_modify(|holds| *holds -= 1);
+    }
 }
 
 impl Default for MsgStore {
@@ -41,19 +115,40 @@ impl MsgStore {
             inner: RwLock::new(Inner {
                 history: VecDeque::with_capacity(32),
                 total_bytes: 0,
+                front_seq: 0,
+                last_patch: None,
             }),
             sender,
+            input_closed: watch::Sender::new(false),
+            finish_holds: watch::Sender::new(0),
         }
     }
 
+    /// Live listeners get every message. History folds consecutive patches
+    /// to the same path into one, so a replay only carries the latest value
+    /// of an entry that was rewritten many times, e.g. streaming command
+    /// output. Other messages in between don't break the run.
     pub fn push(&self, msg: LogMsg) {
         let _ = self.sender.send(msg.clone()); // live listeners
-        let bytes = msg.approx_bytes();
 
         let mut inner = self.inner.write().unwrap();
+        let msg = match msg {
+            LogMsg::JsonPatch(patch) => match inner.coalesce(patch) {
+                Ok(()) => return,
+                Err(patch) => {
+                    let seq = inner.front_seq + inner.history.len() as u64;
+                    inner.last_patch = single_set_path(&patch).map(|path| (seq, path));
+                    LogMsg::JsonPatch(patch)
+                }
+            },
+            msg => msg,
+        };
+        let bytes = msg.approx_bytes();
+
         while inner.total_bytes.saturating_add(bytes) > HISTORY_BYTES {
             if let Some(front) = inner.history.pop_front() {
                 inner.total_bytes = inner.total_bytes.saturating_sub(front.bytes);
+                inner.front_seq += 1;
             } else {
                 break;
             }
@@ -83,6 +178,33 @@ impl MsgStore {
         self.push(LogMsg::Finished);
     }
 
+    /// Delay [`MsgStore::finish`] until the returned hold is dropped, for a
+    /// consumer that only pushes its last entries once the raw output ends.
+    pub fn hold_finish(self: &Arc<Self>) -> FinishHold {
+        self.finish_holds.send_modify(|holds| *holds += 1);
+        FinishHold(self.clone())
+    }
+
+    /// Resolves once [`MsgStore::finish`] has been called.
+    pub async fn input_closed(&self) {
+        let mut closed = self.input_closed.subscribe();
+        let _ = closed.wait_for(|closed| *closed).await;
+    }
+
+    /// Mark the raw output complete, give holders of a [`FinishHold`] up to
+    /// `timeout` to push their last entries, then push [`LogMsg::Finished`].
+    pub async fn finish(&self, timeout: Duration) {
+        self.input_closed.send_replace(true);
+        let mut holds = self.finish_holds.subscribe();
+        if tokio::time::timeout(timeout, holds.wait_for(|holds| *holds == 0))
+            .await
+            .is_err()
+        {
+            tracing::warn!("Log consumers did not finish within {timeout:?}, finishing anyway");
+        }
+        self.push_finished();
+    }
+
     pub fn get_receiver(&self) -> broadcast::Receiver<LogMsg> {
         self.sender.subscribe()
     }
@@ -101,9 +223,20 @@ impl MsgStore {
     pub fn history_plus_stream(
         &self,
     ) -> futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>> {
+        let (history, live) = self.history_and_live();
+        let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
+        Box::pin(hist.chain(live))
+    }
+
+    /// Snapshot of the history, plus a stream of messages pushed after it.
+    pub fn history_and_live(
+        &self,
+    ) -> (
+        Vec<LogMsg>,
+        futures::stream::BoxStream<'static, Result<LogMsg, std::io::Error>>,
+    ) {
         let (history, rx) = (self.get_history(), self.get_receiver());
 
-        let hist = futures::stream::iter(history.into_iter().map(Ok::<_, std::io::Error>));
         let live = BroadcastStream::new(rx).filter_map(|res| async move {
             match res {
                 Ok(msg) => Some(Ok(msg)),
@@ -117,7 +250,7 @@ impl MsgStore {
             }
         });
 
-        Box::pin(hist.chain(live))
+        (history, live.boxed())
     }
 
     pub fn stdout_chunked_stream(
@@ -172,3 +305,57 @@ impl MsgStore {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use json_patch::Patch;
+    use serde_json::{from_value, json};
+
+    use super::*;
+
+    fn set(op: &str, index: usize, output: &str) -> LogMsg {
+        let patch: Patch = from_value(json!([{
+            "op": op,
+            "path": format!("/entries/{index}"),
+            "value": { "output": output },
+        }]))
+        .unwrap();
+        LogMsg::JsonPatch(patch)
+    }
+
+    #[test]
+    fn history_folds_repeated_replaces_of_one_entry() {
+        let store = MsgStore::new();
+        let mut live = store.get_receiver();
+
+        store.push(set("add", 0, "a"));
+        store.push_stdout("raw line");
+        store.push(set("replace", 0, "ab"));
+        store.push(set("replace", 0, "abc"));
+        store.push(set("add", 1, "x"));
+        store.push(set("replace", 0, "abcd"));
+
+        let history: Vec<String> = store
+            .get_history()
+            .iter()
+            .map(|msg| serde_json::to_string(msg).unwrap())
+            .collect();
+        let expected: Vec<String> = [
+            set("add", 0, "abc"),
+            LogMsg::Stdout("raw line".into()),
+            set("add", 1, "x"),
+            set("replace", 0, "abcd"),
+        ]
+        .iter()
+        .map(|msg| serde_json::to_string(msg).unwrap())
+        .collect();
+        assert_eq!(history, expected);
+
+        // Live listeners still see every patch.
+        let mut received = 0;
+        while live.try_recv().is_ok() {
+            received += 1;
+        }
+        assert_eq!(received, 6);
+    }
+}
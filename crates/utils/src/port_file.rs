@@ -8,6 +8,9 @@ pub struct PortInfo {
     pub main_port: u16,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub preview_proxy_port: Option<u16>,
+    /// Path prefix the server is mounted under; see [`crate::base_path`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_path: Option<String>,
 }
 
 pub async fn write_port_file_with_proxy(
@@ -19,6 +22,7 @@ pub async fn write_port_file_with_proxy(
     let port_info = PortInfo {
         main_port,
         preview_proxy_port,
+        base_path: Some(crate::base_path().to_string()).filter(|p| !p.is_empty()),
     };
     let content = serde_json::to_string(&port_info)
         .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
@@ -51,5 +55,6 @@ pub async fn read_port_info(app_name: &str) -> std::io::Result<PortInfo> {
     Ok(PortInfo {
         main_port: port,
         preview_proxy_port: None,
+        base_path: None,
     })
 }
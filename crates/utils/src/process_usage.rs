@@ -0,0 +1,160 @@
+//! Best-effort resource usage sampling for a spawned process group.
+
+/// Point-in-time usage summed over every live process in a group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProcessGroupUsage {
+    /// Resident set size, in bytes.
+    pub rss_bytes: u64,
+    /// User + system CPU time, including reaped children, in milliseconds.
+    pub cpu_time_ms: u64,
+}
+
+/// Sample the current usage of process group `pgid`. Returns `None` when the
+/// group has no live processes or the platform is unsupported.
+pub fn sample_process_group(pgid: u32) -> Option<ProcessGroupUsage> {
+    sample(pgid)
+}
+
+#[cfg(target_os = "linux")]
+fn sample(pgid: u32) -> Option<ProcessGroupUsage> {
+    use nix::libc;
+
+    // SAFETY: sysconf has no preconditions.
+    let (page_size, ticks_per_sec) = unsafe {
+        (
+            libc::sysconf(libc::_SC_PAGESIZE),
+            libc::sysconf(libc::_SC_CLK_TCK),
+        )
+    };
+    if page_size <= 0 || ticks_per_sec <= 0 {
+        return None;
+    }
+
+    let mut usage = ProcessGroupUsage::default();
+    let mut found = false;
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let is_pid = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| name.bytes().all(|b| b.is_ascii_digit()));
+        if !is_pid {
+            continue;
+        }
+        let Ok(stat) = std::fs::read_to_string(entry.path().join("stat")) else {
+            continue;
+        };
+        let Some(fields) = parse_proc_stat(&stat) else {
+            continue;
+        };
+        if fields.pgrp != pgid {
+            continue;
+        }
+        found = true;
+        usage.rss_bytes += fields.rss_pages * page_size as u64;
+        usage.cpu_time_ms += fields.cpu_ticks * 1000 / ticks_per_sec as u64;
+    }
+
+    found.then_some(usage)
+}
+
+#[cfg(any(target_os = "linux", test))]
+struct ProcStatFields {
+    pgrp: u32,
+    /// utime + stime + cutime + cstime
+    cpu_ticks: u64,
+    rss_pages: u64,
+}
+
+/// Parse the fields we need from `/proc/<pid>/stat`. The command name can
+/// contain spaces and parentheses, so fields are counted from the last `)`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_proc_stat(stat: &str) -> Option<ProcStatFields> {
+    let (_, rest) = stat.rsplit_once(')')?;
+    // Index 0 is field 3 (state) in proc(5) numbering.
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    let field = |n: usize| fields.get(n - 3)?.parse::<u64>().ok();
+
+    Some(ProcStatFields {
+        pgrp: field(5)? as u32,
+        cpu_ticks: field(14)? + field(15)? + field(16)? + field(17)?,
+        rss_pages: field(24)?,
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn sample(pgid: u32) -> Option<ProcessGroupUsage> {
+    use std::{ffi::c_void, mem};
+
+    use mach2::{
+        kern_return::KERN_SUCCESS,
+        mach_time::{mach_timebase_info, mach_timebase_info_data_t},
+    };
+    use nix::libc;
+
+    let mut pids = vec![0 as libc::pid_t; 256];
+    // SAFETY: the buffer is valid for `pids.len()` pid_t values.
+    let bytes = unsafe {
+        libc::proc_listpids(
+            libc::PROC_PGRP_ONLY,
+            pgid,
+            pids.as_mut_ptr() as *mut c_void,
+            (pids.len() * mem::size_of::<libc::pid_t>()) as libc::c_int,
+        )
+    };
+    if bytes <= 0 {
+        return None;
+    }
+    pids.truncate(bytes as usize / mem::size_of::<libc::pid_t>());
+
+    let mut timebase = mach_timebase_info_data_t { numer: 0, denom: 0 };
+    // SAFETY: `timebase` is a valid out-pointer.
+    if unsafe { mach_timebase_info(&mut timebase) } != KERN_SUCCESS || timebase.denom == 0 {
+        return None;
+    }
+
+    let mut usage = ProcessGroupUsage::default();
+    let mut found = false;
+    for pid in pids.into_iter().filter(|pid| *pid > 0) {
+        // SAFETY: rusage_info_v2 is plain data; zeroed is a valid value.
+        let mut info: libc::rusage_info_v2 = unsafe { mem::zeroed() };
+        // SAFETY: `info` matches the RUSAGE_INFO_V2 flavor requested.
+        let rc = unsafe {
+            libc::proc_pid_rusage(
+                pid,
+                libc::RUSAGE_INFO_V2,
+                &mut info as *mut libc::rusage_info_v2 as *mut libc::rusage_info_t,
+            )
+        };
+        if rc != 0 {
+            continue;
+        }
+        found = true;
+        // CPU times are in mach absolute time units.
+        let cpu_abs = info.ri_user_time + info.ri_system_time;
+        let cpu_ns = cpu_abs as u128 * timebase.numer as u128 / timebase.denom as u128;
+        usage.rss_bytes += info.ri_resident_size;
+        usage.cpu_time_ms += (cpu_ns / 1_000_000) as u64;
+    }
+
+    found.then_some(usage)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn sample(_pgid: u32) -> Option<ProcessGroupUsage> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_proc_stat_with_parens_in_name() {
+        let stat = "1234 (node (worker) x) S 1 1200 1200 0 -1 4194560 500 0 0 0 \
+                    150 50 7 3 20 0 1 0 100 123456789 2048 18446744073709551615";
+        let fields = parse_proc_stat(stat).unwrap();
+        assert_eq!(fields.pgrp, 1200);
+        assert_eq!(fields.cpu_ticks, 210);
+        assert_eq!(fields.rss_pages, 2048);
+    }
+}
@@ -1,7 +1,11 @@
 import { createRouter } from '@tanstack/react-router';
+import { BASE_PATH } from '@/shared/lib/basePath';
 import { routeTree } from '@web/routeTree.gen';
 
-export const router = createRouter({ routeTree });
+export const router = createRouter({
+  routeTree,
+  basepath: BASE_PATH || undefined,
+});
 
 declare module '@tanstack/react-router' {
   interface Register {
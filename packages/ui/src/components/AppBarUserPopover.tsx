@@ -5,6 +5,7 @@ import {
   SignInIcon,
   SignOutIcon,
   UserIcon,
+  UserPlusIcon,
 } from '@phosphor-icons/react';
 import { useTranslation } from 'react-i18next';
 import { cn } from '../lib/cn';
@@ -22,6 +23,11 @@ export interface AppBarUserOrganization {
   name: string;
 }
 
+export interface AppBarUserAccount {
+  id: string;
+  label: string;
+}
+
 interface AppBarUserPopoverProps {
   isSignedIn: boolean;
   avatarUrl: string | null;
@@ -32,6 +38,10 @@ interface AppBarUserPopoverProps {
   onOpenChange: (open: boolean) => void;
   onOrgSelect: (orgId: string) => void;
   onOrgSettings?: (orgId: string) => void;
+  accounts?: AppBarUserAccount[];
+  activeAccountId?: string | null;
+  onAccountSelect?: (accountId: string) => void;
+  onAddAccount?: () => void;
   onSettings?: () => void;
   onSignIn: () => void;
   onLogout: () => void;
@@ -48,6 +58,10 @@ export function AppBarUserPopover({
   onOpenChange,
   onOrgSelect,
   onOrgSettings,
+  accounts,
+  activeAccountId,
+  onAccountSelect,
+  onAddAccount,
   onSettings,
   onSignIn,
   onLogout,
@@ -121,6 +135,30 @@ export function AppBarUserPopover({
         </button>
       </DropdownMenuTrigger>
       <DropdownMenuContent side="right" align="end" className="min-w-[200px]">
+        {accounts && onAccountSelect && (
+          <>
+            <DropdownMenuLabel>
+              {t('accountSwitcher.accounts')}
+            </DropdownMenuLabel>
+            <DropdownMenuSeparator />
+            {accounts.map((account) => (
+              <DropdownMenuItem
+                key={account.id}
+                icon={account.id === activeAccountId ? CheckIcon : UserIcon}
+                onClick={() => onAccountSelect(account.id)}
+                className={cn(account.id === activeAccountId && 'bg-brand/10')}
+              >
+                <span className="truncate">{account.label}</span>
+              </DropdownMenuItem>
+            ))}
+            {onAddAccount && (
+              <DropdownMenuItem icon={UserPlusIcon} onClick={onAddAccount}>
+                {t('accountSwitcher.addAccount')}
+              </DropdownMenuItem>
+            )}
+            <DropdownMenuSeparator />
+          </>
+        )}
         <DropdownMenuLabel>{t('orgSwitcher.organizations')}</DropdownMenuLabel>
         <DropdownMenuSeparator />
         {organizations.map((org) => (
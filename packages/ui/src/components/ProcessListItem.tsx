@@ -26,6 +26,8 @@ function getRunReasonLabel(runReason: string): string {
       return 'Cleanup Script';
     case 'archivescript':
       return 'Archive Script';
+    case 'postmergescript':
+      return 'Post-Merge Script';
     case 'devserver':
       return 'Dev Server';
     default:
@@ -40,6 +42,7 @@ function getRunReasonIcon(runReason: string): typeof TerminalIcon {
     case 'setupscript':
     case 'cleanupscript':
     case 'archivescript':
+    case 'postmergescript':
       return GearIcon;
     case 'devserver':
       return GlobeIcon;
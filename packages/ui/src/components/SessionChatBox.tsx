@@ -93,8 +93,13 @@ interface StatsProps {
   hasConflicts?: boolean;
   conflictedFilesCount?: number;
   onResolveConflicts?: () => void;
+  /** Workspaces the task may still start under the repo's cap; null if uncapped */
+  attemptsRemaining?: number | null;
 }
 
+/** Warn once this many agent runs or fewer are left in the workspace */
+const ATTEMPTS_WARNING_THRESHOLD = 2;
+
 interface FeedbackModeProps {
   isActive: boolean;
   onSubmitFeedback: () => void;
@@ -710,6 +715,20 @@ export function SessionChatBox<TExecutor extends string = string>({
                 </span>
               ) : (
                 <>
+                  {stats?.attemptsRemaining != null &&
+                    stats.attemptsRemaining <= ATTEMPTS_WARNING_THRESHOLD && (
+                      <span
+                        className="flex items-center gap-1 text-warning text-sm min-w-0"
+                        title={t('conversation.attemptsRemainingWarning')}
+                      >
+                        <WarningIcon className="size-icon-sm flex-shrink-0" />
+                        <span className="truncate">
+                          {t('conversation.attemptsRemaining', {
+                            count: stats.attemptsRemaining,
+                          })}
+                        </span>
+                      </span>
+                    )}
                   {stats?.hasConflicts && (
                     <button
                       type="button"
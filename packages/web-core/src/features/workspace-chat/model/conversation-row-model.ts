@@ -122,6 +122,7 @@ export function classifyRowFamily(entry: DisplayEntry): RowFamily {
     case 'assistant_message':
       return 'assistant_message';
     case 'system_message':
+    case 'approval_request':
       return 'system_message';
     case 'thinking':
       return 'thinking';
@@ -13,6 +13,7 @@ type ScriptTurnKind =
   | 'setup_script'
   | 'cleanup_script'
   | 'archive_script'
+  | 'post_merge_script'
   | 'tool_install_script';
 
 export interface ConversationAgentTurn {
@@ -204,6 +205,8 @@ function toScriptTurnKind(
       return 'cleanup_script';
     case 'ArchiveScript':
       return 'archive_script';
+    case 'PostMergeScript':
+      return 'post_merge_script';
     case 'ToolInstallScript':
       return 'tool_install_script';
     default:
@@ -219,6 +222,8 @@ function toScriptToolName(kind: ScriptTurnKind): string {
       return 'Cleanup Script';
     case 'archive_script':
       return 'Archive Script';
+    case 'post_merge_script':
+      return 'Post-Merge Script';
     case 'tool_install_script':
       return 'Tool Install Script';
   }
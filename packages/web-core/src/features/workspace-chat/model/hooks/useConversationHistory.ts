@@ -89,6 +89,7 @@ export const useConversationHistory = ({
         ep.run_reason === 'setupscript' ||
         ep.run_reason === 'cleanupscript' ||
         ep.run_reason === 'archivescript' ||
+        ep.run_reason === 'postmergescript' ||
         ep.run_reason === 'codingagent'
     );
   }, [executionProcessesRaw]);
@@ -159,7 +160,8 @@ export const useConversationHistory = ({
     return (
       executionProcesses?.current.filter(
         (p) =>
-          p.status === ExecutionProcessStatus.running &&
+          (p.status === ExecutionProcessStatus.running ||
+            p.status === ExecutionProcessStatus.queued) &&
           p.run_reason !== 'devserver'
       ) ?? []
     );
@@ -266,7 +268,10 @@ export const useConversationHistory = ({
       for (const executionProcess of [
         ...executionProcesses.current,
       ].reverse()) {
-        if (executionProcess.status === ExecutionProcessStatus.running)
+        if (
+          executionProcess.status === ExecutionProcessStatus.running ||
+          executionProcess.status === ExecutionProcessStatus.queued
+        )
           continue;
 
         const entries =
@@ -304,7 +309,8 @@ export const useConversationHistory = ({
         const current = displayedExecutionProcesses.current;
         if (
           current[executionProcess.id] ||
-          executionProcess.status === ExecutionProcessStatus.running
+          executionProcess.status === ExecutionProcessStatus.running ||
+          executionProcess.status === ExecutionProcessStatus.queued
         )
           continue;
 
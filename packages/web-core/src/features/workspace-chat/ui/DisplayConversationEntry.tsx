@@ -430,6 +430,14 @@ function DisplayConversationEntry(props: Props) {
         />
       );
 
+    case 'approval_request':
+      return (
+        <SystemMessageEntry
+          content={entry.content}
+          expansionKey={expansionKey}
+        />
+      );
+
     case 'loading':
       return <LoadingEntry />;
 
@@ -30,6 +30,7 @@ import { useSessionSend } from '../model/hooks/useSessionSend';
 import { useSessionAttachments } from '../model/hooks/useSessionAttachments';
 import { useMessageEditRetry } from '../model/hooks/useMessageEditRetry';
 import { useBranchStatus } from '@/shared/hooks/useBranchStatus';
+import { useAttemptUsage } from '@/shared/hooks/useAttemptUsage';
 import { useWorkspaceBranch } from '../model/hooks/useWorkspaceBranch';
 import { useApprovalMutation } from '../model/hooks/useApprovalMutation';
 import { useApprovals } from '@/shared/hooks/useApprovals';
@@ -334,6 +335,9 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
     );
   }, [branchStatus]);
 
+  // Workspaces the task may still start under the repos' per-task cap
+  const { data: attemptUsage } = useAttemptUsage(workspaceId);
+
   // Get workspace branch for conflict resolution dialog
   const { branch: attemptBranch } = useWorkspaceBranch(workspaceId);
 
@@ -1057,6 +1061,7 @@ export function SessionChatBoxContainer(props: SessionChatBoxContainerProps) {
         hasConflicts,
         conflictedFilesCount,
         onResolveConflicts: handleResolveConflicts,
+        attemptsRemaining: attemptUsage?.remaining,
       }}
       error={sendError}
       agent={effectiveExecutor}
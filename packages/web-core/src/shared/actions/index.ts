@@ -1010,8 +1010,24 @@ export const Actions = {
       });
 
       if (confirmResult === 'confirmed') {
-        await workspacesApi.merge(workspaceId, { repo_id: repoId });
+        const result = await workspacesApi.merge(workspaceId, {
+          repo_id: repoId,
+        });
         invalidateWorkspaceQueries(ctx.queryClient, workspaceId);
+        if (!result.success) {
+          if (result.error?.type !== 'merge_conflicts') {
+            throw new Error(result.message ?? 'Failed to merge');
+          }
+          const workspace = await getWorkspace(ctx.queryClient, workspaceId);
+          await ResolveConflictsDialog.show({
+            workspaceId,
+            conflictOp: result.error.op,
+            sourceBranch: workspace.branch,
+            targetBranch: result.error.target_branch,
+            conflictedFiles: result.error.conflicted_files,
+            repoName: repoStatus?.repo_name,
+          });
+        }
       }
     },
   },
@@ -1174,6 +1190,27 @@ export const Actions = {
     },
   },
 
+  RerunFailedSetup: {
+    id: 'rerun-failed-setup',
+    label: 'Retry Failed Setup',
+    icon: TerminalIcon,
+    requiresTarget: ActionTargetType.WORKSPACE,
+    isVisible: (ctx) => ctx.hasWorkspace,
+    isEnabled: (ctx) => !ctx.isAttemptRunning,
+    execute: async (_ctx, workspaceId) => {
+      const result = await workspacesApi.rerunFailedSetup(workspaceId);
+      if (!result.success) {
+        if (result.error?.type === 'no_failed_setup') {
+          throw new Error('The last setup script did not fail');
+        }
+        if (result.error?.type === 'process_already_running') {
+          throw new Error('Cannot run script while another process is running');
+        }
+        throw new Error('Failed to retry setup script');
+      }
+    },
+  },
+
   RunCleanupScript: {
     id: 'run-cleanup-script',
     label: 'Run Cleanup Script',
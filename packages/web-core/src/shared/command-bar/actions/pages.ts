@@ -73,6 +73,7 @@ export const Pages: Record<StaticPageId, CommandBarPage> = {
         label: 'Scripts',
         items: [
           { type: 'action', action: Actions.RunSetupScript },
+          { type: 'action', action: Actions.RerunFailedSetup },
           { type: 'action', action: Actions.RunCleanupScript },
           { type: 'action', action: Actions.RunArchiveScript },
         ],
@@ -16,6 +16,7 @@ import {
 } from '@/shared/lib/string';
 import type { BaseCodingAgent, Repo } from 'shared/types';
 import { CreateChatBox } from '@vibe/ui/components/CreateChatBox';
+import { Textarea } from '@vibe/ui/components/Textarea';
 import { SettingsDialog } from '@/shared/dialogs/settings/SettingsDialog';
 import { CreateModeRepoPickerBar } from './CreateModeRepoPickerBar';
 import { ModelSelectorContainer } from '@/shared/components/ModelSelectorContainer';
@@ -26,6 +27,28 @@ function getRepoDisplayName(repo: Repo) {
 
 const BRANCH_LABEL_MAX_CHARS = 15;
 
+const ENV_KEY_PATTERN = /^[A-Za-z_][A-Za-z0-9_]*$/;
+
+/** Parse `KEY=value` lines, skipping blanks and `#` comments. */
+function parseEnvOverrides(text: string) {
+  const vars: Record<string, string> = {};
+  const invalidKeys: string[] = [];
+  for (const line of text.split('\n')) {
+    const trimmed = line.trim();
+    if (!trimmed || trimmed.startsWith('#')) continue;
+    const separator = trimmed.indexOf('=');
+    const key = (
+      separator === -1 ? trimmed : trimmed.slice(0, separator)
+    ).trim();
+    if (separator === -1 || !ENV_KEY_PATTERN.test(key)) {
+      invalidKeys.push(key);
+      continue;
+    }
+    vars[key] = trimmed.slice(separator + 1);
+  }
+  return { vars, invalidKeys };
+}
+
 function truncateBranchLabel(branch: string) {
   return branch.length > BRANCH_LABEL_MAX_CHARS
     ? `${branch.slice(0, BRANCH_LABEL_MAX_CHARS)}...`
@@ -63,6 +86,12 @@ export function CreateChatBoxContainer({
   const [hasAttemptedSubmit, setHasAttemptedSubmit] = useState(false);
   const [hasInitializedStep, setHasInitializedStep] = useState(false);
   const [isSelectingRepos, setIsSelectingRepos] = useState(true);
+  const [showEnvOverrides, setShowEnvOverrides] = useState(false);
+  const [envOverridesText, setEnvOverridesText] = useState('');
+  const envOverrides = useMemo(
+    () => parseEnvOverrides(envOverridesText),
+    [envOverridesText]
+  );
 
   useEffect(() => {
     if (!hasInitialValue || hasInitializedStep) return;
@@ -170,6 +199,7 @@ export function CreateChatBoxContainer({
     hasSelectedRepos &&
     hasSelectedBranchesForAllRepos &&
     message.trim().length > 0 &&
+    envOverrides.invalidKeys.length === 0 &&
     effectiveExecutor !== null;
 
   const handlePresetSelect = (presetId: string | null) => {
@@ -241,6 +271,7 @@ export function CreateChatBoxContainer({
           }
         : null,
       attachment_ids: getAttachmentIds(),
+      env_overrides: envOverrides.vars,
     };
     const linkToIssue = linkedIssue
       ? {
@@ -265,6 +296,7 @@ export function CreateChatBoxContainer({
     }
 
     clearAttachments();
+    setEnvOverridesText('');
     await clearDraft();
   }, [
     canSubmit,
@@ -278,6 +310,7 @@ export function CreateChatBoxContainer({
     clearAttachments,
     clearDraft,
     linkedIssue,
+    envOverrides,
   ]);
 
   // Determine error to display
@@ -286,11 +319,15 @@ export function CreateChatBoxContainer({
       ? 'Add at least one repository to create a workspace'
       : hasAttemptedSubmit && !hasSelectedBranchesForAllRepos
         ? 'Select a branch for every repository before creating a workspace'
-        : createWorkspace.error
-          ? createWorkspace.error instanceof Error
-            ? createWorkspace.error.message
-            : 'Failed to create workspace'
-          : null;
+        : envOverrides.invalidKeys.length > 0
+          ? t('createMode.envOverrides.invalid', {
+              keys: envOverrides.invalidKeys.join(', '),
+            })
+          : createWorkspace.error
+            ? createWorkspace.error instanceof Error
+              ? createWorkspace.error.message
+              : 'Failed to create workspace'
+            : null;
 
   // Wait for initial value to be applied before rendering
   // This ensures the editor mounts with content ready, so autoFocus works correctly
@@ -402,6 +439,25 @@ export function CreateChatBoxContainer({
                   }
                 />
               </div>
+
+              <div className="flex flex-col gap-half">
+                <button
+                  type="button"
+                  className="self-start text-sm text-low hover:text-normal"
+                  onClick={() => setShowEnvOverrides((show) => !show)}
+                >
+                  {t('createMode.envOverrides.toggle')}
+                </button>
+                {showEnvOverrides && (
+                  <Textarea
+                    value={envOverridesText}
+                    onChange={(e) => setEnvOverridesText(e.target.value)}
+                    placeholder={t('createMode.envOverrides.placeholder')}
+                    disabled={createWorkspace.isPending}
+                    className="font-mono bg-secondary rounded-sm border-border"
+                  />
+                )}
+              </div>
             </>
           )}
         </div>
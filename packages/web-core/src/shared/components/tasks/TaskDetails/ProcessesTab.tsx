@@ -15,6 +15,7 @@ import { useExecutionProcesses } from '@/shared/hooks/useExecutionProcesses';
 import { useLogStream } from '@/shared/hooks/useLogStream';
 import { ProcessLogsViewerContent } from './ProcessLogsViewer';
 import type { ExecutionProcessStatus, ExecutionProcess } from 'shared/types';
+import { ExecutionProcessExitReason } from 'shared/types';
 
 import { useProcessSelection } from '@/shared/hooks/ProcessSelectionContext';
 import { useRetryUi } from '@/shared/hooks/useRetryUi';
@@ -251,6 +252,12 @@ function ProcessesTab({ sessionId }: ProcessesTabProps) {
                           })}
                         </p>
                       )}
+                      {process.exit_reason ===
+                        ExecutionProcessExitReason.timeout && (
+                        <p className="text-xs text-muted-foreground mt-1">
+                          {t('processes.timedOut')}
+                        </p>
+                      )}
                     </div>
                   </div>
                   <div className="mt-3 text-xs text-muted-foreground">
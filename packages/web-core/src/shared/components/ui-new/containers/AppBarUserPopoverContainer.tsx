@@ -1,4 +1,5 @@
 import { useState } from 'react';
+import { useQuery, useQueryClient } from '@tanstack/react-query';
 import type { OrganizationWithRole } from 'shared/types';
 import { AppBarUserPopover } from '@vibe/ui/components/AppBarUserPopover';
 import { SettingsDialog } from '@/shared/dialogs/settings/SettingsDialog';
@@ -7,6 +8,9 @@ import { useUserSystem } from '@/shared/hooks/useUserSystem';
 import { useOrganizationStore } from '@/shared/stores/useOrganizationStore';
 import { useActions } from '@/shared/hooks/useActions';
 import { Actions } from '@/shared/actions';
+import { oauthApi } from '@/shared/lib/api';
+import { tokenManager } from '@/shared/lib/auth/tokenManager';
+import { organizationKeys } from '@/shared/hooks/organizationKeys';
 
 interface AppBarUserPopoverContainerProps {
   organizations: OrganizationWithRole[];
@@ -21,8 +25,10 @@ export function AppBarUserPopoverContainer({
 }: AppBarUserPopoverContainerProps) {
   const { executeAction } = useActions();
   const { isSignedIn } = useAuth();
-  const { loginStatus } = useUserSystem();
+  const { loginStatus, reloadSystem } = useUserSystem();
+  const queryClient = useQueryClient();
   const setSelectedOrgId = useOrganizationStore((s) => s.setSelectedOrgId);
+  const clearSelectedOrgId = useOrganizationStore((s) => s.clearSelectedOrgId);
   const [open, setOpen] = useState(false);
   const [avatarError, setAvatarError] = useState(false);
 
@@ -32,6 +38,41 @@ export function AppBarUserPopoverContainer({
       ? (loginStatus.profile?.providers[0]?.avatar_url ?? null)
       : null;
 
+  const activeUserId =
+    loginStatus?.status === 'loggedin'
+      ? (loginStatus.profile?.user_id ?? null)
+      : null;
+
+  const { data: accounts } = useQuery({
+    queryKey: ['auth', 'accounts', activeUserId],
+    queryFn: () => oauthApi.listAccounts(),
+    enabled: isSignedIn,
+  });
+
+  const handleAccountSelect = async (userId: string) => {
+    setOpen(false);
+    if (userId === activeUserId) return;
+    await oauthApi.switchAccount(userId);
+    clearSelectedOrgId();
+    queryClient.removeQueries({ queryKey: organizationKeys.all });
+    await reloadSystem();
+    await tokenManager.triggerRefresh();
+  };
+
+  const handleAddAccount = async () => {
+    setOpen(false);
+    const { OAuthDialog } = await import(
+      '@/shared/dialogs/global/OAuthDialog'
+    );
+    const added = await OAuthDialog.show({
+      currentUserId: activeUserId ?? undefined,
+    });
+    if (added) {
+      clearSelectedOrgId();
+      queryClient.removeQueries({ queryKey: organizationKeys.all });
+    }
+  };
+
   const handleSignIn = async () => {
     await executeAction(Actions.SignIn);
   };
@@ -61,6 +102,13 @@ export function AppBarUserPopoverContainer({
       onOpenChange={setOpen}
       onOrgSelect={onOrgSelect}
       onOrgSettings={handleOrgSettings}
+      accounts={accounts?.map((account) => ({
+        id: account.user_id,
+        label: account.email ?? account.user_id,
+      }))}
+      activeAccountId={activeUserId}
+      onAccountSelect={handleAccountSelect}
+      onAddAccount={handleAddAccount}
       onSignIn={handleSignIn}
       onLogout={handleLogout}
       onAvatarError={() => setAvatarError(true)}
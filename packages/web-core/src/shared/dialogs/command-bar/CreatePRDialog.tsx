@@ -66,8 +66,10 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
     const [prTitle, setPrTitle] = useState('');
     const [prBody, setPrBody] = useState('');
     const [prBaseBranch, setPrBaseBranch] = useState('');
+    const [prLabels, setPrLabels] = useState('');
     const [creatingPR, setCreatingPR] = useState(false);
     const [error, setError] = useState<string | null>(null);
+    const [pushedBranch, setPushedBranch] = useState<string | null>(null);
     const [ghCliHelp, setGhCliHelp] = useState<GhCliSupportContent | null>(
       null
     );
@@ -112,17 +114,35 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
 
       const initializePRFields = async () => {
         try {
+          // Repos with PR templates get fields rendered from them; the rest
+          // fall back to the first prompt.
+          const repo = (await workspacesApi.getRepos(attempt.id)).find(
+            (r) => r.id === repoId
+          );
+          const rendered =
+            repo?.pr_title_template || repo?.pr_body_template
+              ? await workspacesApi.getPrDescription(attempt.id, repoId)
+              : null;
           const firstUserMessage = await workspacesApi.getFirstUserMessage(
             attempt.id
           );
 
           if (isCancelled) return;
 
-          if (firstUserMessage?.trim()) {
-            const { title, description } =
-              splitMessageToTitleDescription(firstUserMessage);
-            setPrTitle(appendPrTitleSuffix(title));
-            setPrBody(description ?? '');
+          const fromPrompt = firstUserMessage?.trim()
+            ? splitMessageToTitleDescription(firstUserMessage)
+            : null;
+          if (rendered || fromPrompt) {
+            setPrTitle(
+              rendered && repo?.pr_title_template
+                ? rendered.title
+                : appendPrTitleSuffix(fromPrompt?.title ?? '')
+            );
+            setPrBody(
+              rendered && repo?.pr_body_template
+                ? rendered.body
+                : (fromPrompt?.description ?? '')
+            );
             return;
           }
         } catch {
@@ -141,7 +161,7 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
       return () => {
         isCancelled = true;
       };
-    }, [attempt.id, modal.visible, isLoaded, issueIdentifier]);
+    }, [attempt.id, repoId, modal.visible, isLoaded, issueIdentifier]);
 
     // Set default base branch when branches are loaded
     useEffect(() => {
@@ -212,8 +232,19 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
         draft: isDraft,
         auto_generate_description: autoGenerateDescription,
         repo_id: repoId,
+        labels: prLabels
+          .split(',')
+          .map((label) => label.trim())
+          .filter(Boolean),
       });
 
+      if (result.success && result.data.type === 'branch_pushed') {
+        // No PR API on this remote; tell the user where things stand
+        setCreatingPR(false);
+        setPushedBranch(result.data.branch);
+        return;
+      }
+
       if (result.success) {
         setCreatingPR(false);
         modal.resolve({ success: true } as CreatePRDialogResult);
@@ -303,12 +334,14 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
 
     const handleCancelCreatePR = useCallback(() => {
       // Return error if one was set, otherwise just canceled
-      const result: CreatePRDialogResult = error
-        ? { success: false, error }
-        : { success: false };
+      const result: CreatePRDialogResult = pushedBranch
+        ? { success: true }
+        : error
+          ? { success: false, error }
+          : { success: false };
       modal.resolve(result);
       modal.hide();
-    }, [modal, error]);
+    }, [modal, error, pushedBranch]);
 
     return (
       <>
@@ -400,6 +433,17 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
                     }
                   />
                 </div>
+                <div className="space-y-2">
+                  <Label htmlFor="pr-labels">
+                    {t('createPrDialog.labelsLabel')}
+                  </Label>
+                  <Input
+                    id="pr-labels"
+                    value={prLabels}
+                    onChange={(e) => setPrLabels(e.target.value)}
+                    placeholder={t('createPrDialog.labelsPlaceholder')}
+                  />
+                </div>
                 <div className="flex items-center space-x-2">
                   <Checkbox
                     id="pr-draft"
@@ -425,6 +469,11 @@ const CreatePRDialogImpl = create<CreatePRDialogProps>(
                     </AlertDescription>
                   </Alert>
                 )}
+                {pushedBranch && (
+                  <Alert variant="default">
+                    {t('createPrDialog.branchPushed', { branch: pushedBranch })}
+                  </Alert>
+                )}
                 {error && <Alert variant="destructive">{error}</Alert>}
               </div>
             )}
@@ -24,7 +24,12 @@ import { useTranslation } from 'react-i18next';
 import { defineModal } from '@/shared/lib/modals';
 
 export type OAuthProvider = 'github' | 'google';
-type OAuthDialogProps = { initialProvider?: OAuthProvider };
+type OAuthDialogProps = {
+  initialProvider?: OAuthProvider;
+  /** When adding another account, the currently active user. Sign-in only
+   * completes once a different account becomes active. */
+  currentUserId?: string;
+};
 
 type OAuthState =
   | { type: 'select' }
@@ -32,7 +37,8 @@ type OAuthState =
   | { type: 'success'; displayName: string | null }
   | { type: 'error'; message: string };
 
-const OAuthDialogImpl = create<OAuthDialogProps>(({ initialProvider }) => {
+const OAuthDialogImpl = create<OAuthDialogProps>((props) => {
+  const { initialProvider, currentUserId } = props;
   const modal = useModal();
   const { t } = useTranslation('common');
   const queryClient = useQueryClient();
@@ -107,10 +113,16 @@ const OAuthDialogImpl = create<OAuthDialogProps>(({ initialProvider }) => {
   useEffect(() => {
     if (!isPolling || !statusData) return;
 
+    const signedIn =
+      statusData.logged_in &&
+      (!currentUserId ||
+        (statusData.profile != null &&
+          statusData.profile.user_id !== currentUserId));
+
     // Check if popup is closed
     if (popupRef.current?.closed) {
       setIsPolling(false);
-      if (!statusData.logged_in) {
+      if (!signedIn) {
         setState({
           type: 'error',
           message: 'OAuth window was closed before completing authentication',
@@ -119,7 +131,7 @@ const OAuthDialogImpl = create<OAuthDialogProps>(({ initialProvider }) => {
     }
 
     // If logged in, stop polling and trigger success
-    if (statusData.logged_in) {
+    if (signedIn) {
       setIsPolling(false);
       if (popupRef.current && !popupRef.current.closed) {
         popupRef.current.close();
@@ -145,7 +157,7 @@ const OAuthDialogImpl = create<OAuthDialogProps>(({ initialProvider }) => {
         modal.remove();
       }, 1500);
     }
-  }, [statusData, isPolling, modal, reloadSystem, queryClient]);
+  }, [statusData, isPolling, modal, reloadSystem, queryClient, currentUserId]);
 
   const handleProviderSelect = useCallback(
     (provider: OAuthProvider) => {
@@ -9,7 +9,9 @@ import {
 import { FolderPickerDialog } from '@/shared/dialogs/shared/FolderPickerDialog';
 import {
   type BaseCodingAgent,
+  type ConventionalCommitMode,
   DEFAULT_COMMIT_REMINDER_PROMPT,
+  DEFAULT_ISSUE_PROMPT_TEMPLATE,
   DEFAULT_PR_DESCRIPTION_PROMPT,
   EditorType,
   type ExecutorProfileId,
@@ -523,6 +525,44 @@ export function GeneralSettingsSection() {
             ) : null}
           </div>
         </SettingsField>
+
+        <SettingsCheckbox
+          id="claude-disable-api-key"
+          label={t('settings.general.taskExecution.claudeDisableApiKey.label')}
+          description={t(
+            'settings.general.taskExecution.claudeDisableApiKey.helper'
+          )}
+          checked={draft?.claude_disable_api_key ?? false}
+          onChange={(checked) =>
+            updateDraft({ claude_disable_api_key: checked })
+          }
+        />
+
+        <SettingsCheckbox
+          id="use-custom-issue-prompt"
+          label={t('settings.general.taskExecution.issuePrompt.useCustom')}
+          checked={draft?.issue_prompt_template != null}
+          onChange={(checked) =>
+            updateDraft({
+              issue_prompt_template: checked
+                ? DEFAULT_ISSUE_PROMPT_TEMPLATE
+                : null,
+            })
+          }
+        />
+
+        <SettingsField
+          label=""
+          description={t('settings.general.taskExecution.issuePrompt.helper')}
+        >
+          <SettingsTextarea
+            value={
+              draft?.issue_prompt_template ?? DEFAULT_ISSUE_PROMPT_TEMPLATE
+            }
+            onChange={(value) => updateDraft({ issue_prompt_template: value })}
+            disabled={draft?.issue_prompt_template == null}
+          />
+        </SettingsField>
       </SettingsCard>
 
       {/* Git */}
@@ -568,6 +608,19 @@ export function GeneralSettingsSection() {
           />
         </SettingsField>
 
+        <SettingsField
+          label={t('settings.general.git.branchTemplate.label')}
+          description={t('settings.general.git.branchTemplate.helper')}
+        >
+          <SettingsInput
+            value={draft?.git_branch_template ?? ''}
+            onChange={(value) =>
+              updateDraft({ git_branch_template: value.trim() || null })
+            }
+            placeholder={t('settings.general.git.branchTemplate.placeholder')}
+          />
+        </SettingsField>
+
         <SettingsField
           label={t('settings.general.git.workspaceDir.label')}
           description={t('settings.general.git.workspaceDir.helper')}
@@ -639,6 +692,16 @@ export function GeneralSettingsSection() {
             disabled={draft?.pr_auto_description_prompt == null}
           />
         </SettingsField>
+
+        <SettingsCheckbox
+          id="generic-git-fallback"
+          label={t('settings.general.pullRequests.genericGitFallback.label')}
+          description={t(
+            'settings.general.pullRequests.genericGitFallback.helper'
+          )}
+          checked={draft?.generic_git_fallback ?? false}
+          onChange={(checked) => updateDraft({ generic_git_fallback: checked })}
+        />
       </SettingsCard>
 
       {/* Commits */}
@@ -690,6 +753,85 @@ export function GeneralSettingsSection() {
             </SettingsField>
           </>
         )}
+
+        <SettingsField
+          label={t('settings.general.commits.conventional.label')}
+          description={t('settings.general.commits.conventional.helper')}
+        >
+          <SettingsSelect
+            value={draft?.conventional_commits.mode ?? 'Off'}
+            options={[
+              {
+                value: 'Off' as ConventionalCommitMode,
+                label: t('settings.general.commits.conventional.modes.off'),
+              },
+              {
+                value: 'Reject' as ConventionalCommitMode,
+                label: t('settings.general.commits.conventional.modes.reject'),
+              },
+              {
+                value: 'AutoPrefix' as ConventionalCommitMode,
+                label: t(
+                  'settings.general.commits.conventional.modes.autoPrefix'
+                ),
+              },
+            ]}
+            onChange={(value: ConventionalCommitMode) =>
+              updateDraft({
+                conventional_commits: {
+                  ...draft!.conventional_commits,
+                  mode: value,
+                },
+              })
+            }
+          />
+        </SettingsField>
+
+        {draft && draft.conventional_commits.mode !== 'Off' && (
+          <>
+            <SettingsField
+              label={t('settings.general.commits.conventional.typesLabel')}
+              description={t(
+                'settings.general.commits.conventional.typesHelper'
+              )}
+            >
+              <SettingsInput
+                value={draft.conventional_commits.allowed_types.join(', ')}
+                onChange={(value) =>
+                  updateDraft({
+                    conventional_commits: {
+                      ...draft.conventional_commits,
+                      allowed_types: value.split(',').map((t) => t.trim()),
+                    },
+                  })
+                }
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t(
+                'settings.general.commits.conventional.maxSubjectLengthLabel'
+              )}
+              description={t(
+                'settings.general.commits.conventional.maxSubjectLengthHelper'
+              )}
+            >
+              <SettingsInput
+                value={String(draft.conventional_commits.max_subject_length)}
+                onChange={(value) => {
+                  const length = Number.parseInt(value, 10);
+                  updateDraft({
+                    conventional_commits: {
+                      ...draft.conventional_commits,
+                      max_subject_length:
+                        Number.isInteger(length) && length > 0 ? length : 0,
+                    },
+                  });
+                }}
+              />
+            </SettingsField>
+          </>
+        )}
       </SettingsCard>
 
       {/* Notifications */}
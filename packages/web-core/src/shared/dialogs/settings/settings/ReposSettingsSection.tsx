@@ -11,7 +11,16 @@ import { useAllOrganizationProjects } from '@/shared/hooks/useAllOrganizationPro
 import { getProjectRepoDefaults } from '@/shared/hooks/useProjectRepoDefaults';
 import { ApiError } from '@/shared/lib/api';
 import { defineModal } from '@/shared/lib/modals';
-import type { Repo, UpdateRepo } from 'shared/types';
+import {
+  DEFAULT_DENIED_TOOL_NOTE_TEMPLATE,
+  DEFAULT_NOTIFICATION_BODY_TEMPLATE,
+  DEFAULT_NOTIFICATION_TITLE_TEMPLATE,
+  DEFAULT_PR_BODY_TEMPLATE,
+  DEFAULT_PR_TITLE_TEMPLATE,
+  type MergeMethod,
+  type Repo,
+  type UpdateRepo,
+} from 'shared/types';
 import { SearchableDropdownContainer } from '@/shared/components/ui-new/containers/SearchableDropdownContainer';
 import { FolderPickerDialog } from '@/shared/dialogs/shared/FolderPickerDialog';
 import { Button } from '@vibe/ui/components/Button';
@@ -37,6 +46,7 @@ import {
   SettingsInput,
   SettingsTextarea,
   SettingsCheckbox,
+  SettingsSelect,
   SettingsSaveBar,
 } from './SettingsComponents';
 import { useSettingsMachineClient } from './SettingsHostContext';
@@ -45,23 +55,60 @@ interface RepoScriptsFormState {
   display_name: string;
   default_working_dir: string;
   default_target_branch: string;
+  default_merge_method: MergeMethod | null;
+  default_reviewers: string;
+  default_pr_labels: string;
+  push_remote: string;
+  pr_base_remote: string;
+  pr_title_template: string;
+  pr_body_template: string;
+  notification_title_template: string;
+  notification_body_template: string;
+  max_worktrees: string;
+  prune_worktrees_at_limit: boolean;
+  max_attempts_per_task: string;
+  denied_tool_note_template: string | null;
   setup_script: string;
   parallel_setup_script: boolean;
   cleanup_script: string;
   archive_script: string;
+  post_merge_script: string;
   copy_files: string;
   dev_server_script: string;
 }
 
+const MERGE_METHODS: MergeMethod[] = ['squash', 'merge', 'rebase'];
+
+/** Positive whole numbers set a limit; anything else clears it. */
+function parseLimit(value: string): number | null {
+  const limit = Number.parseInt(value.trim(), 10);
+  return Number.isInteger(limit) && limit > 0 ? limit : null;
+}
+
 function repoToFormState(repo: Repo): RepoScriptsFormState {
   return {
     display_name: repo.display_name,
     default_working_dir: repo.default_working_dir ?? '',
     default_target_branch: repo.default_target_branch ?? '',
+    default_merge_method: repo.default_merge_method,
+    default_reviewers: repo.default_reviewers.join(', '),
+    default_pr_labels: repo.default_pr_labels.join(', '),
+    push_remote: repo.push_remote ?? '',
+    pr_base_remote: repo.pr_base_remote ?? '',
+    pr_title_template: repo.pr_title_template ?? '',
+    pr_body_template: repo.pr_body_template ?? '',
+    notification_title_template: repo.notification_title_template ?? '',
+    notification_body_template: repo.notification_body_template ?? '',
+    max_worktrees: repo.max_worktrees?.toString() ?? '',
+    prune_worktrees_at_limit: repo.prune_worktrees_at_limit,
+    max_attempts_per_task:
+      repo.max_attempts_per_task?.toString() ?? '',
+    denied_tool_note_template: repo.denied_tool_note_template,
     setup_script: repo.setup_script ?? '',
     parallel_setup_script: repo.parallel_setup_script,
     cleanup_script: repo.cleanup_script ?? '',
     archive_script: repo.archive_script ?? '',
+    post_merge_script: repo.post_merge_script ?? '',
     copy_files: repo.copy_files ?? '',
     dev_server_script: repo.dev_server_script ?? '',
   };
@@ -188,6 +235,20 @@ export function ReposSettingsSection({
   // Get OS-appropriate script placeholders
   const placeholders = useScriptPlaceholders();
 
+  // Suggest a setup script based on the repo's detected ecosystems
+  const { data: ecosystems } = useQuery({
+    queryKey: [...reposQueryKey, selectedRepoId, 'ecosystems'],
+    queryFn: () => {
+      if (!machineClient) {
+        throw new Error('Machine client is required');
+      }
+
+      return machineClient.getRepoEcosystems(selectedRepoId);
+    },
+    enabled: machineClient != null && !!selectedRepoId,
+  });
+  const setupPlaceholder = ecosystems?.[0]?.setup_command ?? placeholders.setup;
+
   // Linked projects: find which remote projects reference this repo
   const { data: allProjects, isLoading: projectsLoading } =
     useAllOrganizationProjects();
@@ -343,9 +404,34 @@ export function ReposSettingsSection({
         display_name: draft.display_name.trim() || null,
         default_working_dir: draft.default_working_dir.trim() || null,
         default_target_branch: draft.default_target_branch.trim() || null,
+        default_merge_method: draft.default_merge_method,
+        default_reviewers: draft.default_reviewers
+          .split(',')
+          .map((reviewer) => reviewer.trim())
+          .filter(Boolean),
+        default_pr_labels: draft.default_pr_labels
+          .split(',')
+          .map((label) => label.trim())
+          .filter(Boolean),
+        push_remote: draft.push_remote.trim() || null,
+        pr_base_remote: draft.pr_base_remote.trim() || null,
+        pr_title_template: draft.pr_title_template.trim() || null,
+        pr_body_template: draft.pr_body_template.trim() || null,
+        notification_title_template:
+          draft.notification_title_template.trim() || null,
+        notification_body_template:
+          draft.notification_body_template.trim() || null,
+        max_worktrees: parseLimit(draft.max_worktrees),
+        prune_worktrees_at_limit: draft.prune_worktrees_at_limit,
+        max_attempts_per_task: parseLimit(
+          draft.max_attempts_per_task
+        ),
+        denied_tool_note_template:
+          draft.denied_tool_note_template?.trim() || null,
         setup_script: draft.setup_script.trim() || null,
         cleanup_script: draft.cleanup_script.trim() || null,
         archive_script: draft.archive_script.trim() || null,
+        post_merge_script: draft.post_merge_script.trim() || null,
         copy_files: draft.copy_files.trim() || null,
         parallel_setup_script: draft.parallel_setup_script,
         dev_server_script: draft.dev_server_script.trim() || null,
@@ -566,6 +652,203 @@ export function ReposSettingsSection({
               />
             </SettingsField>
 
+            <SettingsField
+              label={t('settings.repos.general.defaultMergeMethod.label')}
+              description={t(
+                'settings.repos.general.defaultMergeMethod.helper'
+              )}
+            >
+              <SettingsSelect
+                value={draft.default_merge_method ?? 'squash'}
+                options={MERGE_METHODS.map((method) => ({
+                  value: method,
+                  label: t(
+                    `settings.repos.general.defaultMergeMethod.options.${method}`
+                  ),
+                }))}
+                onChange={(value) => updateDraft({ default_merge_method: value })}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.defaultReviewers.label')}
+              description={t('settings.repos.general.defaultReviewers.helper')}
+            >
+              <SettingsInput
+                value={draft.default_reviewers}
+                onChange={(value) => updateDraft({ default_reviewers: value })}
+                placeholder={t(
+                  'settings.repos.general.defaultReviewers.placeholder'
+                )}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.defaultPrLabels.label')}
+              description={t('settings.repos.general.defaultPrLabels.helper')}
+            >
+              <SettingsInput
+                value={draft.default_pr_labels}
+                onChange={(value) => updateDraft({ default_pr_labels: value })}
+                placeholder={t(
+                  'settings.repos.general.defaultPrLabels.placeholder'
+                )}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.pushRemote.label')}
+              description={t('settings.repos.general.pushRemote.helper')}
+            >
+              <SettingsInput
+                value={draft.push_remote}
+                onChange={(value) => updateDraft({ push_remote: value })}
+                placeholder={t('settings.repos.general.pushRemote.placeholder')}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.prBaseRemote.label')}
+              description={t('settings.repos.general.prBaseRemote.helper')}
+            >
+              <SettingsInput
+                value={draft.pr_base_remote}
+                onChange={(value) => updateDraft({ pr_base_remote: value })}
+                placeholder={t(
+                  'settings.repos.general.prBaseRemote.placeholder'
+                )}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.prTemplates.titleLabel')}
+              description={t('settings.repos.general.prTemplates.helper')}
+            >
+              <SettingsInput
+                value={draft.pr_title_template}
+                onChange={(value) => updateDraft({ pr_title_template: value })}
+                placeholder={DEFAULT_PR_TITLE_TEMPLATE}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.prTemplates.bodyLabel')}
+            >
+              <SettingsTextarea
+                value={draft.pr_body_template}
+                onChange={(value) => updateDraft({ pr_body_template: value })}
+                placeholder={DEFAULT_PR_BODY_TEMPLATE}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t(
+                'settings.repos.general.notificationTemplates.titleLabel'
+              )}
+              description={t(
+                'settings.repos.general.notificationTemplates.helper'
+              )}
+            >
+              <SettingsInput
+                value={draft.notification_title_template}
+                onChange={(value) =>
+                  updateDraft({ notification_title_template: value })
+                }
+                placeholder={DEFAULT_NOTIFICATION_TITLE_TEMPLATE}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.notificationTemplates.bodyLabel')}
+            >
+              <SettingsTextarea
+                value={draft.notification_body_template}
+                onChange={(value) =>
+                  updateDraft({ notification_body_template: value })
+                }
+                placeholder={DEFAULT_NOTIFICATION_BODY_TEMPLATE}
+              />
+            </SettingsField>
+
+            <SettingsField
+              label={t('settings.repos.general.maxWorktrees.label')}
+              description={t('settings.repos.general.maxWorktrees.helper')}
+            >
+              <SettingsInput
+                value={draft.max_worktrees}
+                onChange={(value) => updateDraft({ max_worktrees: value })}
+                placeholder={t(
+                  'settings.repos.general.maxWorktrees.placeholder'
+                )}
+                error={
+                  draft.max_worktrees.trim() !== '' &&
+                  parseLimit(draft.max_worktrees) === null
+                }
+              />
+            </SettingsField>
+
+            <SettingsCheckbox
+              id="prune-worktrees-at-limit"
+              label={t('settings.repos.general.maxWorktrees.pruneLabel')}
+              description={t('settings.repos.general.maxWorktrees.pruneHelper')}
+              checked={draft.prune_worktrees_at_limit}
+              onChange={(checked) =>
+                updateDraft({ prune_worktrees_at_limit: checked })
+              }
+              disabled={parseLimit(draft.max_worktrees) === null}
+            />
+
+            <SettingsField
+              label={t('settings.repos.general.maxAttemptsPerTask.label')}
+              description={t(
+                'settings.repos.general.maxAttemptsPerTask.helper'
+              )}
+            >
+              <SettingsInput
+                value={draft.max_attempts_per_task}
+                onChange={(value) =>
+                  updateDraft({ max_attempts_per_task: value })
+                }
+                placeholder={t(
+                  'settings.repos.general.maxAttemptsPerTask.placeholder'
+                )}
+                error={
+                  draft.max_attempts_per_task.trim() !== '' &&
+                  parseLimit(draft.max_attempts_per_task) === null
+                }
+              />
+            </SettingsField>
+
+            <SettingsCheckbox
+              id="denied-tool-note"
+              label={t('settings.repos.general.deniedToolNote.label')}
+              description={t('settings.repos.general.deniedToolNote.helper')}
+              checked={draft.denied_tool_note_template != null}
+              onChange={(checked) =>
+                updateDraft({
+                  denied_tool_note_template: checked
+                    ? DEFAULT_DENIED_TOOL_NOTE_TEMPLATE
+                    : null,
+                })
+              }
+            />
+
+            {draft.denied_tool_note_template != null && (
+              <SettingsField
+                label=""
+                description={t(
+                  'settings.repos.general.deniedToolNote.templateHelper'
+                )}
+              >
+                <SettingsTextarea
+                  value={draft.denied_tool_note_template}
+                  onChange={(value) =>
+                    updateDraft({ denied_tool_note_template: value })
+                  }
+                />
+              </SettingsField>
+            )}
+
             <div className="border-t border-primary pt-base mt-base">
               <div className="flex items-center justify-between">
                 <div>
@@ -647,7 +930,7 @@ export function ReposSettingsSection({
               <SettingsTextarea
                 value={draft.setup_script}
                 onChange={(value) => updateDraft({ setup_script: value })}
-                placeholder={placeholders.setup}
+                placeholder={setupPlaceholder}
                 monospace
               />
             </SettingsField>
@@ -687,6 +970,18 @@ export function ReposSettingsSection({
               />
             </SettingsField>
 
+            <SettingsField
+              label={t('settings.repos.scripts.postMerge.label')}
+              description={t('settings.repos.scripts.postMerge.helper')}
+            >
+              <SettingsTextarea
+                value={draft.post_merge_script}
+                onChange={(value) => updateDraft({ post_merge_script: value })}
+                placeholder={placeholders.postMerge}
+                monospace
+              />
+            </SettingsField>
+
             <SettingsField
               label={t('settings.repos.scripts.copyFiles.label')}
               description={t('settings.repos.scripts.copyFiles.helper')}
@@ -0,0 +1,11 @@
+import { useQuery } from '@tanstack/react-query';
+import { workspacesApi } from '@/shared/lib/api';
+
+export function useAttemptUsage(workspaceId?: string) {
+  return useQuery({
+    queryKey: ['attemptUsage', workspaceId],
+    queryFn: () => workspacesApi.getAttemptUsage(workspaceId!),
+    enabled: !!workspaceId,
+    refetchInterval: 5000,
+  });
+}
@@ -17,6 +17,10 @@ type DiffStreamEvent = {
 
 export interface UseDiffStreamOptions {
   statsOnly?: boolean;
+  /** Diff against the HEAD this execution started from, to watch one run. */
+  sinceExecutionProcessId?: string;
+  /** Comma-separated globs limiting the files streamed; `!` negates. */
+  paths?: string;
 }
 
 interface UseDiffStreamResult {
@@ -35,13 +39,18 @@ export const useDiffStream = (
     if (!workspaceId) return undefined;
     const apiBasePath = hostId ? `/api/host/${hostId}` : '/api';
     const query = `${apiBasePath}/workspaces/${workspaceId}/git/diff/ws`;
+    const params = new URLSearchParams();
     if (typeof options?.statsOnly === 'boolean') {
-      const params = new URLSearchParams();
       params.set('stats_only', String(options.statsOnly));
-      return `${query}?${params.toString()}`;
-    } else {
-      return query;
     }
+    if (options?.sinceExecutionProcessId) {
+      params.set('execution_process_id', options.sinceExecutionProcessId);
+    }
+    if (options?.paths) {
+      params.set('paths', options.paths);
+    }
+    const search = params.toString();
+    return search ? `${query}?${search}` : query;
   })();
 
   const initialData = useCallback(
@@ -78,7 +78,7 @@ export const useExecutionProcesses = (
         process.run_reason === 'setupscript' ||
         process.run_reason === 'cleanupscript' ||
         process.run_reason === 'archivescript') &&
-      process.status === 'running'
+      (process.status === 'running' || process.status === 'queued')
   );
   const isLoading = !!sessionId && !isInitialized && !error; // until first snapshot
 
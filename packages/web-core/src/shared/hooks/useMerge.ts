@@ -16,9 +16,16 @@ export function useMerge(
   return useMutation<void, unknown, MergeParams>({
     mutationFn: (params: MergeParams) => {
       if (!workspaceId) return Promise.resolve();
-      return workspacesApi.merge(workspaceId, {
-        repo_id: params.repoId,
-      });
+      return workspacesApi
+        .merge(workspaceId, {
+          repo_id: params.repoId,
+        })
+        .then((res) => {
+          if (!res.success) {
+            // Propagate typed failure Result so callers can show conflicts
+            return Promise.reject(res);
+          }
+        });
     },
     onSuccess: () => {
       // Refresh attempt-specific branch information
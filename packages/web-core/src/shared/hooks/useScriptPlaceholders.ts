@@ -21,5 +21,7 @@ export function useScriptPlaceholders(): ScriptPlaceholders {
       '#!/bin/bash\n# Add cleanup commands here...\n# This runs after coding agent execution',
     archive:
       '#!/bin/bash\n# Add archive commands here...\n# This runs when the workspace is archived',
+    postMerge:
+      '#!/bin/bash\n# Add post-merge commands here...\n# This runs in the main repository after a workspace is merged',
   };
 }
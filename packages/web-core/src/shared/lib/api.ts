@@ -3,6 +3,10 @@
 import {
   ApprovalStatus,
   ApiResponse,
+  CancelExecutionOutcome,
+  ExecutionProcessResourceUsage,
+  OutcomeSummary,
+  TokenUsageTotals,
   Config,
   CreateFollowUpAttempt,
   ResetProcessRequest,
@@ -13,6 +17,8 @@ import {
   DirectoryEntry,
   ExecutionProcess,
   ExecutionProcessRepoState,
+  RenormalizeJob,
+  RenormalizeProcessesRequest,
   GitBranch,
   Repo,
   RepoWithTargetBranch,
@@ -29,12 +35,19 @@ import {
   AttachmentResponse,
   GitOperationError,
   ApprovalResponse,
+  ApprovalOutcome,
+  AgentPlan,
+  RespondToPlanRequest,
+  WorkspacePreview,
   RebaseWorkspaceRequest,
   ChangeTargetBranchRequest,
   ChangeTargetBranchResponse,
   RenameBranchRequest,
   RenameBranchResponse,
   CheckEditorAvailabilityResponse,
+  DetectGitHostRequest,
+  DetectedGitHost,
+  FailedNotification,
   AvailabilityInfo,
   BaseCodingAgent,
   ExecutorConfig,
@@ -60,6 +73,7 @@ import {
   OpenEditorResponse,
   OpenEditorRequest,
   PrError,
+  CreatePrResponse,
   Scratch,
   ScratchType,
   CreateScratch,
@@ -67,11 +81,16 @@ import {
   PushError,
   TokenResponse,
   CurrentUserResponse,
+  RemoteAccount,
+  SwitchAccountRequest,
   QueueStatus,
   PrCommentsResponse,
+  PrDescriptionResponse,
+  Merge,
   MergeWorkspaceRequest,
   PushWorkspaceRequest,
   RepoBranchStatus,
+  TaskAttemptUsage,
   AbortConflictsRequest,
   ContinueRebaseRequest,
   Session,
@@ -84,6 +103,8 @@ import {
   LinkPrToIssueRequest,
   AttachExistingPrRequest,
   AttachPrResponse,
+  UpdatePrBaseRequest,
+  UpdatePrBaseError,
   CreateWorkspaceFromPrBody,
   CreateWorkspaceFromPrResponse,
   CreateFromPrError,
@@ -100,6 +121,7 @@ import {
   OpenRemoteWorkspaceInEditorRequest,
   OpenRemoteEditorResponse,
   ProfileResponse,
+  ResolvedProfile,
 } from 'shared/types';
 import type { Project as RemoteProject } from 'shared/remote-types';
 import type { WorkspaceWithSession } from '@/shared/types/attempt';
@@ -535,6 +557,15 @@ export const workspacesApi = {
     return handleApiResponse<RepoBranchStatus[]>(response);
   },
 
+  getAttemptUsage: async (
+    workspaceId: string
+  ): Promise<TaskAttemptUsage> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/execution/attempts`
+    );
+    return handleApiResponse<TaskAttemptUsage>(response);
+  },
+
   getRepos: async (workspaceId: string): Promise<RepoWithTargetBranch[]> => {
     const response = await makeRequest(`/api/workspaces/${workspaceId}/repos`);
     return handleApiResponse<RepoWithTargetBranch[]>(response);
@@ -550,7 +581,7 @@ export const workspacesApi = {
   merge: async (
     workspaceId: string,
     data: MergeWorkspaceRequest
-  ): Promise<void> => {
+  ): Promise<Result<void, GitOperationError>> => {
     const response = await makeRequest(
       `/api/workspaces/${workspaceId}/git/merge`,
       {
@@ -558,7 +589,14 @@ export const workspacesApi = {
         body: JSON.stringify(data),
       }
     );
-    return handleApiResponse<void>(response);
+    return handleApiResponseAsResult<void, GitOperationError>(response);
+  },
+
+  getMerges: async (workspaceId: string): Promise<Merge[]> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/git/merges`
+    );
+    return handleApiResponse<Merge[]>(response);
   },
 
   push: async (
@@ -665,7 +703,7 @@ export const workspacesApi = {
   createPR: async (
     workspaceId: string,
     data: CreatePrApiRequest
-  ): Promise<Result<string, PrError>> => {
+  ): Promise<Result<CreatePrResponse, PrError>> => {
     const response = await makeRequest(
       `/api/workspaces/${workspaceId}/pull-requests`,
       {
@@ -673,7 +711,7 @@ export const workspacesApi = {
         body: JSON.stringify(data),
       }
     );
-    return handleApiResponseAsResult<string, PrError>(response);
+    return handleApiResponseAsResult<CreatePrResponse, PrError>(response);
   },
 
   /** Try to auto-attach a PR by matching the workspace branch */
@@ -691,6 +729,23 @@ export const workspacesApi = {
     return handleApiResponseAsResult<AttachPrResponse, PrError>(response);
   },
 
+  /** Re-target the workspace's PR for a repo at another base branch */
+  updatePrBase: async (
+    workspaceId: string,
+    data: UpdatePrBaseRequest
+  ): Promise<Result<PullRequestDetail, UpdatePrBaseError>> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/pull-requests/base`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponseAsResult<PullRequestDetail, UpdatePrBaseError>(
+      response
+    );
+  },
+
   startDevServer: async (workspaceId: string): Promise<ExecutionProcess[]> => {
     const response = await makeRequest(
       `/api/workspaces/${workspaceId}/execution/dev-server/start`,
@@ -724,6 +779,20 @@ export const workspacesApi = {
     return sessionsApi.runSetupScript(session.id);
   },
 
+  rerunFailedSetup: async (
+    workspaceId: string
+  ): Promise<Result<ExecutionProcess, RunScriptError>> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/execution/rerun-setup`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponseAsResult<ExecutionProcess, RunScriptError>(
+      response
+    );
+  },
+
   runCleanupScript: async (
     workspaceId: string
   ): Promise<Result<ExecutionProcess, RunScriptError>> => {
@@ -752,6 +821,16 @@ export const workspacesApi = {
     );
   },
 
+  getPrDescription: async (
+    workspaceId: string,
+    repoId: string
+  ): Promise<PrDescriptionResponse> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/pull-requests/description?repo_id=${encodeURIComponent(repoId)}`
+    );
+    return handleApiResponse<PrDescriptionResponse>(response);
+  },
+
   getPrComments: async (
     workspaceId: string,
     repoId: string
@@ -770,6 +849,36 @@ export const workspacesApi = {
     return handleApiResponse<void>(response);
   },
 
+  /** Latest plan the agent proposed, if any */
+  getPlan: async (workspaceId: string): Promise<AgentPlan | null> => {
+    const response = await makeRequest(`/api/workspaces/${workspaceId}/plan`);
+    return handleApiResponse<AgentPlan | null>(response);
+  },
+
+  respondToPlan: async (
+    workspaceId: string,
+    data: RespondToPlanRequest
+  ): Promise<ApprovalOutcome> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/plan/respond`,
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<ApprovalOutcome>(response);
+  },
+
+  /** File changes proposed by the latest preview run, if any */
+  getProposedChanges: async (
+    workspaceId: string
+  ): Promise<WorkspacePreview | null> => {
+    const response = await makeRequest(
+      `/api/workspaces/${workspaceId}/proposed-changes`
+    );
+    return handleApiResponse<WorkspacePreview | null>(response);
+  },
+
   /** Create a workspace directly from a pull request */
   createFromPr: async (
     data: CreateWorkspaceFromPrBody
@@ -810,6 +919,61 @@ export const executionProcessesApi = {
     );
     return handleApiResponse<void>(response);
   },
+
+  cancelExecutionProcess: async (
+    processId: string
+  ): Promise<CancelExecutionOutcome> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/cancel`,
+      {
+        method: 'POST',
+      }
+    );
+    return handleApiResponse<CancelExecutionOutcome>(response);
+  },
+
+  getResourceUsage: async (
+    processId: string
+  ): Promise<ExecutionProcessResourceUsage> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/usage`
+    );
+    return handleApiResponse<ExecutionProcessResourceUsage>(response);
+  },
+
+  getOutcomeSummary: async (
+    processId: string
+  ): Promise<OutcomeSummary | null> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/outcome`
+    );
+    return handleApiResponse<OutcomeSummary | null>(response);
+  },
+
+  getTokenUsage: async (processId: string): Promise<TokenUsageTotals> => {
+    const response = await makeRequest(
+      `/api/execution-processes/${processId}/token-usage`
+    );
+    return handleApiResponse<TokenUsageTotals>(response);
+  },
+
+  startRenormalizeJob: async (
+    data: RenormalizeProcessesRequest
+  ): Promise<RenormalizeJob> => {
+    const response = await makeRequest(
+      '/api/execution-processes/renormalize',
+      {
+        method: 'POST',
+        body: JSON.stringify(data),
+      }
+    );
+    return handleApiResponse<RenormalizeJob>(response);
+  },
+
+  listRenormalizeJobs: async (): Promise<RenormalizeJob[]> => {
+    const response = await makeRequest('/api/execution-processes/renormalize');
+    return handleApiResponse<RenormalizeJob[]>(response);
+  },
 };
 
 // File System APIs
@@ -1020,6 +1184,26 @@ export const configApi = {
     );
     return handleApiResponse<AvailabilityInfo>(response);
   },
+  detectGitHost: async (
+    data: DetectGitHostRequest
+  ): Promise<DetectedGitHost> => {
+    const response = await makeRequest('/api/config/git-hosts/detect', {
+      method: 'POST',
+      body: JSON.stringify(data),
+    });
+    return handleApiResponse<DetectedGitHost>(response);
+  },
+  listFailedNotifications: async (): Promise<FailedNotification[]> => {
+    const response = await makeRequest('/api/notifications/failed');
+    return handleApiResponse<FailedNotification[]>(response);
+  },
+  redriveFailedNotification: async (id: string): Promise<void> => {
+    const response = await makeRequest(
+      `/api/notifications/failed/${id}/redrive`,
+      { method: 'POST' }
+    );
+    return handleApiResponse<void>(response);
+  },
 };
 
 // Task Tags APIs (all tags are global)
@@ -1119,6 +1303,13 @@ export const profilesApi = {
     });
     return handleApiResponse<string>(response);
   },
+  listResolved: async (hostId?: string | null): Promise<ResolvedProfile[]> => {
+    const response = await makeHostAwareRequest(
+      '/api/config/profiles',
+      hostId
+    );
+    return handleApiResponse<ResolvedProfile[]>(response);
+  },
 };
 
 // Workspace attachments API
@@ -1309,6 +1500,24 @@ export const oauthApi = {
     const response = await makeRequest('/api/auth/user');
     return handleApiResponse<CurrentUserResponse>(response);
   },
+
+  /** Lists the remote accounts signed in on this machine */
+  listAccounts: async (): Promise<RemoteAccount[]> => {
+    const response = await makeRequest('/api/auth/accounts', {
+      cache: 'no-store',
+    });
+    return handleApiResponse<RemoteAccount[]>(response);
+  },
+
+  /** Makes another signed-in account active */
+  switchAccount: async (userId: string): Promise<StatusResponse> => {
+    const body: SwitchAccountRequest = { user_id: userId };
+    const response = await makeRequest('/api/auth/accounts/switch', {
+      method: 'POST',
+      body: JSON.stringify(body),
+    });
+    return handleApiResponse<StatusResponse>(response);
+  },
 };
 
 /**
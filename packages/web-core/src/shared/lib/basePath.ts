@@ -0,0 +1,17 @@
+declare global {
+  interface Window {
+    __VK_BASE_PATH__?: string;
+  }
+}
+
+/**
+ * Path prefix the local server is mounted under (e.g. `/vibe` behind a reverse
+ * proxy). Injected into index.html by the server; empty when served from root.
+ */
+export const BASE_PATH: string =
+  typeof window === 'undefined' ? '' : (window.__VK_BASE_PATH__ ?? '');
+
+export function withBasePath(path: string): string {
+  if (!BASE_PATH || !path.startsWith('/') || path.startsWith('//')) return path;
+  return `${BASE_PATH}${path}`;
+}
@@ -1,3 +1,4 @@
+import { withBasePath } from '@/shared/lib/basePath';
 import { getCurrentHostId } from '@/shared/providers/HostIdProvider';
 
 export type LocalApiHostScope = 'current' | 'explicit' | 'none';
@@ -49,7 +50,7 @@ function toAbsoluteWsUrl(pathOrUrl: string): string {
 
   const protocol = window.location.protocol === 'https:' ? 'wss:' : 'ws:';
   const path = pathOrUrl.startsWith('/') ? pathOrUrl : `/${pathOrUrl}`;
-  return `${protocol}//${window.location.host}${path}`;
+  return `${protocol}//${window.location.host}${withBasePath(path)}`;
 }
 
 function scopeLocalApiPath(pathOrUrl: string, hostId: string | null): string {
@@ -96,7 +97,7 @@ const defaultTransport: LocalApiTransport = {
       relayHostId: _relayHostId,
       ...requestInit
     } = init;
-    return fetch(pathOrUrl, requestInit);
+    return fetch(withBasePath(pathOrUrl), requestInit);
   },
   openWebSocket: (pathOrUrl, _options = {}) =>
     new WebSocket(toAbsoluteWsUrl(pathOrUrl)),
@@ -1,5 +1,6 @@
 import type {
   Config,
+  DetectedEcosystem,
   GetMcpServerResponse,
   GitBranch,
   McpServerQuery,
@@ -42,6 +43,7 @@ export interface MachineClient {
     display_name?: string;
   }) => Promise<Repo>;
   getRepoBranches: (repoId: string) => Promise<GitBranch[]>;
+  getRepoEcosystems: (repoId: string) => Promise<DetectedEcosystem[]>;
   loadProfiles: () => Promise<{ content: string; path: string }>;
   saveProfiles: (content: string) => Promise<string>;
   loadMcpServers: (query: McpServerQuery) => Promise<GetMcpServerResponse>;
@@ -146,6 +148,14 @@ export function createMachineClient(
           `/api/repos/${repoId}/branches`
         )
       ),
+    getRepoEcosystems: async (repoId) =>
+      handleApiResponse<DetectedEcosystem[]>(
+        await makeMachineRequest(
+          runtime,
+          target,
+          `/api/repos/${repoId}/ecosystems`
+        )
+      ),
     loadProfiles: async () =>
       handleApiResponse<{ content: string; path: string }>(
         await makeMachineRequest(runtime, target, '/api/profiles')
@@ -3,6 +3,7 @@ interface ScriptPlaceholders {
   dev: string;
   cleanup: string;
   archive: string;
+  postMerge: string;
 }
 
 interface ScriptPlaceholderStrategy {
@@ -24,6 +25,9 @@ REM This runs after coding agent execution - only if changes were made`,
       archive: `@echo off
 REM Add archive commands here...
 REM This runs when the workspace is archived`,
+      postMerge: `@echo off
+REM Add post-merge commands here...
+REM This runs in the main repository after a workspace is merged`,
     };
   }
 }
@@ -39,6 +43,8 @@ class UnixScriptPlaceholderStrategy implements ScriptPlaceholderStrategy {
 # This runs after coding agent execution - only if changes were made`,
       archive: `# Add archive commands here...
 # This runs when the workspace is archived`,
+      postMerge: `# Add post-merge commands here...
+# This runs in the main repository after a workspace is merged`,
     };
   }
 }
@@ -36,7 +36,7 @@ export const ExecutionProcessesProvider: React.FC<{
           (process.run_reason === 'codingagent' ||
             process.run_reason === 'cleanupscript' ||
             process.run_reason === 'archivescript') &&
-          process.status === 'running'
+          (process.status === 'running' || process.status === 'queued')
       ),
     [visible]
   );
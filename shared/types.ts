@@ -4,11 +4,86 @@
 
 // If you are an AI, and you absolutely have to edit this file, please confirm with the user first.
 
-export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type Repo = { id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, 
+/**
+ * Runs in the main repo checkout after a workspace is merged, locally or
+ * through its pull request.
+ */
+post_merge_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, 
+/**
+ * Maximum number of coding agent runs allowed against this repo at once.
+ * `None` means no per-repo limit.
+ */
+max_concurrent_attempts: number | null, 
+/**
+ * Maximum number of workspaces that may keep a worktree of this repo on
+ * disk. `None` means no limit.
+ */
+max_worktrees: number | null, 
+/**
+ * When the worktree limit is reached, prune the least recently used idle
+ * worktrees instead of refusing to create a new one.
+ */
+prune_worktrees_at_limit: boolean, 
+/**
+ * Maximum number of workspaces a single task may start against this
+ * repo. Follow-ups inside a workspace don't count. `None` means no limit.
+ */
+max_attempts_per_task: number | null, 
+/**
+ * Added to the next follow-up after the user denies a tool call, once per
+ * denial. `{tool}` and `{reason}` are filled in. `None` disables it.
+ */
+denied_tool_note_template: string | null, 
+/**
+ * Title for pull requests created for this repo. Supports `{task_title}`,
+ * `{branch}`, `{summary}` and `{attempt_id}`. `None` uses the default.
+ */
+pr_title_template: string | null, 
+/**
+ * Body for pull requests created for this repo, with the same
+ * placeholders as the title. `None` uses the default.
+ */
+pr_body_template: string | null, 
+/**
+ * Title of the desktop notification sent when a workspace run finishes.
+ * Supports `{task}`, `{outcome}`, `{duration}`, `{pr_url}`, `{branch}`,
+ * `{executor}` and `{summary}`. `None` uses the default.
+ */
+notification_title_template: string | null, 
+/**
+ * Body of that notification, with the same placeholders as the title.
+ * `None` uses the default.
+ */
+notification_body_template: string | null, 
+/**
+ * How workspaces are merged when the merge request doesn't choose.
+ * `None` means squash.
+ */
+default_merge_method: MergeMethod | null, 
+/**
+ * Reviewers requested on every pull request created for this repo.
+ */
+default_reviewers: Array<string>, 
+/**
+ * Labels applied to every pull request created for this repo, in
+ * addition to any given when the PR is created.
+ */
+default_pr_labels: Array<string>, 
+/**
+ * Remote workspace branches are pushed to, e.g. a fork. `None` uses the
+ * branch's upstream or the default remote.
+ */
+push_remote: string | null, 
+/**
+ * Remote pull requests are opened against, e.g. `upstream`. `None` uses
+ * the target branch's remote, falling back to the push remote.
+ */
+pr_base_remote: string | null, created_at: Date, updated_at: Date, };
 
 export type Project = { id: string, name: string, default_agent_working_dir: string | null, remote_project_id: string | null, created_at: Date, updated_at: Date, };
 
-export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, };
+export type UpdateRepo = { display_name?: string | null, setup_script?: string | null, cleanup_script?: string | null, archive_script?: string | null, post_merge_script?: string | null, copy_files?: string | null, parallel_setup_script?: boolean | null, dev_server_script?: string | null, default_target_branch?: string | null, default_working_dir?: string | null, max_concurrent_attempts?: number | null, max_worktrees?: number | null, prune_worktrees_at_limit?: boolean, max_attempts_per_task?: number | null, denied_tool_note_template?: string | null, pr_title_template?: string | null, pr_body_template?: string | null, notification_title_template?: string | null, notification_body_template?: string | null, default_merge_method?: MergeMethod | null, default_reviewers?: Array<string>, default_pr_labels?: Array<string>, push_remote?: string | null, pr_base_remote?: string | null, };
 
 export type SearchResult = { path: string, is_file: boolean, match_type: SearchMatchType, 
 /**
@@ -18,11 +93,114 @@ score: bigint, };
 
 export type SearchMatchType = "FileName" | "DirectoryName" | "FullPath";
 
-export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, created_at: Date, updated_at: Date, };
+export type AppliedMigration = { version: bigint, description: string, installed_on: Date, 
+/**
+ * False if the migration started but never completed.
+ */
+success: boolean, 
+/**
+ * Hex-encoded checksum recorded when the migration ran.
+ */
+checksum: string, 
+/**
+ * Whether this build bundles a migration with the same version.
+ */
+known: boolean, 
+/**
+ * Whether the recorded checksum matches this build's migration file.
+ */
+checksum_matches: boolean, };
+
+export type WorkspaceRepo = { id: string, workspace_id: string, repo_id: string, target_branch: string, 
+/**
+ * Merge-base with the target branch when the workspace was created from
+ * an existing branch. `None` for workspaces that created their branch.
+ */
+base_commit: string | null, 
+/**
+ * Last time the workspace branch was pushed for a pull request, including
+ * to remotes with no pull request API.
+ */
+branch_pushed_at: string | null, created_at: Date, updated_at: Date, };
 
 export type CreateWorkspaceRepo = { repo_id: string, target_branch: string, };
 
-export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, created_at: Date, updated_at: Date, };
+export type RepoWithTargetBranch = { target_branch: string, id: string, path: string, name: string, display_name: string, setup_script: string | null, cleanup_script: string | null, archive_script: string | null, 
+/**
+ * Runs in the main repo checkout after a workspace is merged, locally or
+ * through its pull request.
+ */
+post_merge_script: string | null, copy_files: string | null, parallel_setup_script: boolean, dev_server_script: string | null, default_target_branch: string | null, default_working_dir: string | null, 
+/**
+ * Maximum number of coding agent runs allowed against this repo at once.
+ * `None` means no per-repo limit.
+ */
+max_concurrent_attempts: number | null, 
+/**
+ * Maximum number of workspaces that may keep a worktree of this repo on
+ * disk. `None` means no limit.
+ */
+max_worktrees: number | null, 
+/**
+ * When the worktree limit is reached, prune the least recently used idle
+ * worktrees instead of refusing to create a new one.
+ */
+prune_worktrees_at_limit: boolean, 
+/**
+ * Maximum number of workspaces a single task may start against this
+ * repo. Follow-ups inside a workspace don't count. `None` means no limit.
+ */
+max_attempts_per_task: number | null, 
+/**
+ * Added to the next follow-up after the user denies a tool call, once per
+ * denial. `{tool}` and `{reason}` are filled in. `None` disables it.
+ */
+denied_tool_note_template: string | null, 
+/**
+ * Title for pull requests created for this repo. Supports `{task_title}`,
+ * `{branch}`, `{summary}` and `{attempt_id}`. `None` uses the default.
+ */
+pr_title_template: string | null, 
+/**
+ * Body for pull requests created for this repo, with the same
+ * placeholders as the title. `None` uses the default.
+ */
+pr_body_template: string | null, 
+/**
+ * Title of the desktop notification sent when a workspace run finishes.
+ * Supports `{task}`, `{outcome}`, `{duration}`, `{pr_url}`, `{branch}`,
+ * `{executor}` and `{summary}`. `None` uses the default.
+ */
+notification_title_template: string | null, 
+/**
+ * Body of that notification, with the same placeholders as the title.
+ * `None` uses the default.
+ */
+notification_body_template: string | null, 
+/**
+ * How workspaces are merged when the merge request doesn't choose.
+ * `None` means squash.
+ */
+default_merge_method: MergeMethod | null, 
+/**
+ * Reviewers requested on every pull request created for this repo.
+ */
+default_reviewers: Array<string>, 
+/**
+ * Labels applied to every pull request created for this repo, in
+ * addition to any given when the PR is created.
+ */
+default_pr_labels: Array<string>, 
+/**
+ * Remote workspace branches are pushed to, e.g. a fork. `None` uses the
+ * branch's upstream or the default remote.
+ */
+push_remote: string | null, 
+/**
+ * Remote pull requests are opened against, e.g. `upstream`. `None` uses
+ * the target branch's remote, falling back to the push remote.
+ */
+pr_base_remote: string | null, created_at: Date, updated_at: Date, };
 
 export type Tag = { id: string, tag_name: string, content: string, created_at: string, updated_at: string, };
 
@@ -158,7 +336,9 @@ export type WorkspaceWithStatus = { is_running: boolean, is_errored: boolean, id
 
 export type Session = { id: string, workspace_id: string, name: string | null, executor: string | null, agent_working_dir: string | null, created_at: string, updated_at: string, };
 
-export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, 
+export type CodingAgentTurnSearchResult = { turn_id: string, execution_process_id: string, session_id: string, workspace_id: string, workspace_name: string | null, prompt_snippet: string | null, summary_snippet: string | null, created_at: string, };
+
+export type ExecutionProcess = { id: string, session_id: string, run_reason: ExecutionProcessRunReason, executor_action: ExecutorAction, status: ExecutionProcessStatus, exit_code: bigint | null, exit_reason: ExecutionProcessExitReason | null, 
 /**
  * dropped: true if this process is excluded from the current
  * history view (due to restore/trimming). Hidden from logs/timeline;
@@ -166,12 +346,26 @@ export type ExecutionProcess = { id: string, session_id: string, run_reason: Exe
  */
 dropped: boolean, started_at: string, completed_at: string | null, created_at: string, updated_at: string, };
 
-export enum ExecutionProcessStatus { running = "running", completed = "completed", failed = "failed", killed = "killed" }
+export enum ExecutionProcessStatus { queued = "queued", running = "running", completed = "completed", failed = "failed", killed = "killed" }
+
+export enum ExecutionProcessExitReason { timeout = "timeout", cancelled = "cancelled" }
 
-export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "codingagent" | "devserver";
+export type ExecutionProcessRunReason = "setupscript" | "cleanupscript" | "archivescript" | "postmergescript" | "codingagent" | "devserver";
+
+export type ExecutionProcessResourceUsage = { 
+/**
+ * Highest resident memory seen across the whole process group, in bytes.
+ */
+peak_rss_bytes: number | null, 
+/**
+ * Total user + system CPU time, in milliseconds.
+ */
+cpu_time_ms: number | null, };
 
 export type ExecutionProcessRepoState = { id: string, execution_process_id: string, repo_id: string, before_head_commit: string | null, after_head_commit: string | null, merge_commit: string | null, created_at: Date, updated_at: Date, };
 
+export type CancelExecutionOutcome = "dequeued" | "killed";
+
 export type Merge = { "type": "direct" } & DirectMerge | { "type": "pr" } & PrMerge;
 
 export type DirectMerge = { id: string, workspace_id: string, repo_id: string, merge_commit: string, target_branch_name: string, created_at: string, };
@@ -180,7 +374,31 @@ export type PrMerge = { id: string, workspace_id: string, repo_id: string, creat
 
 export type MergeStatus = "open" | "merged" | "closed" | "unknown";
 
-export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, };
+export type ChecksStatus = "pending" | "success" | "failure";
+
+export type MergeOperationKind = "merge" | "rebase";
+
+/**
+ * A notification that could not be delivered after retries. Kept until it is
+ * re-driven successfully or deleted.
+ */
+export type FailedNotification = { id: string, workspace_id: string | null, channel: NotificationChannel, title: string, message: string, 
+/**
+ * Error from the most recent delivery attempt.
+ */
+error: string, 
+/**
+ * Number of failed deliveries, counting re-drives.
+ */
+attempts: number, created_at: string, updated_at: string, };
+
+export type NotificationChannel = "push";
+
+export type PullRequestInfo = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, 
+/**
+ * `None` when the host doesn't report CI status or no checks ran.
+ */
+checks_status: ChecksStatus | null, };
 
 export type ApprovalInfo = { approval_id: string, tool_name: string, execution_process_id: string, is_question: boolean, created_at: string, timeout_at: string, };
 
@@ -216,6 +434,8 @@ export type ProviderProfile = { provider: string, username: string | null, displ
 
 export type StatusResponse = { logged_in: boolean, profile: ProfileResponse | null, degraded: boolean | null, };
 
+export type DeviceInitResponse = { handoff_id: string, user_code: string, verification_uri: string, verification_uri_complete: string, expires_at: string, interval_secs: bigint, };
+
 export enum MemberRole { ADMIN = "ADMIN", MEMBER = "MEMBER" }
 
 export enum InvitationStatus { PENDING = "PENDING", ACCEPTED = "ACCEPTED", DECLINED = "DECLINED", EXPIRED = "EXPIRED" }
@@ -288,8 +508,38 @@ export type CheckAgentAvailabilityQuery = { executor: BaseCodingAgent, };
 
 export type AgentPresetOptionsQuery = { executor: BaseCodingAgent, variant: string | null, };
 
+/**
+ * A profile variant with the settings it actually runs with.
+ */
+export type ResolvedProfile = { 
+/**
+ * `EXECUTOR` or `EXECUTOR:VARIANT`, e.g. `CLAUDE_CODE:PLAN`
+ */
+name: string, executor: BaseCodingAgent, variant: string, 
+/**
+ * The variant's configuration, with environment variable values redacted
+ */
+config: JsonValue, };
+
+export type DetectGitHostRequest = { remote_url: string, };
+
+export type DetectedGitHost = { provider: ProviderKind, host: string, owner: string, repo: string, 
+/**
+ * Whether the provider's CLI is signed in for this host; `None` when it
+ * can't be checked (generic hosts, or the CLI isn't installed).
+ */
+auth_configured: boolean | null, };
+
 export type CurrentUserResponse = { user_id: string, };
 
+export type RemoteAccount = { user_id: string, email: string | null, 
+/**
+ * Whether remote requests are currently made as this account.
+ */
+active: boolean, };
+
+export type SwitchAccountRequest = { user_id: string, };
+
 export type StartSpake2EnrollmentRequest = { enrollment_code: string, client_message_b64: string, };
 
 export type FinishSpake2EnrollmentRequest = { enrollment_id: string, client_id: string, client_name: string, client_browser: string, client_os: string, client_device: string, public_key_b64: string, client_proof_b64: string, };
@@ -304,10 +554,43 @@ export type ListRelayPairedClientsResponse = { clients: Array<RelayPairedClient>
 
 export type RemoveRelayPairedClientResponse = { removed: boolean, };
 
-export type RefreshRelaySigningSessionRequest = { client_id: string, timestamp: bigint, nonce: string, signature_b64: string, };
+export type RefreshRelaySigningSessionRequest = { client_id: string, timestamp: bigint, nonce: string, signature_b64: string, 
+/**
+ * Sign this session with a periodically rotated server key instead of
+ * the long-term one. Fetch the keys from the session keys endpoint.
+ */
+rotate_server_key: boolean, };
 
 export type RefreshRelaySigningSessionResponse = { signing_session_id: string, };
 
+export type RelaySessionServerKey = { public_key_b64: string, 
+/**
+ * Signature by the long-term server key over the session id and this key.
+ */
+endorsement_b64: string, };
+
+export type RelaySessionServerKeysResponse = { current: RelaySessionServerKey, 
+/**
+ * Retired keys still inside the grace window, newest first.
+ */
+previous: Array<RelaySessionServerKey>, };
+
+export type RelaySignatureFailureCount = { 
+/**
+ * Validation error code, e.g. `replay_nonce`.
+ */
+reason: string, count: number, };
+
+export type RelaySigningSessionStatsResponse = { active_sessions: number, seen_nonces: number, 
+/**
+ * How far back `failures` reaches, in seconds.
+ */
+failure_window_secs: number, 
+/**
+ * Rejected signed requests inside the window, most common reason first.
+ */
+failures: Array<RelaySignatureFailureCount>, };
+
 export type CreateFollowUpAttempt = { prompt: string, executor_config: ExecutorConfig, retry_process_id: string | null, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
 
 export type ResetProcessRequest = { process_id: string, force_when_dirty: boolean | null, perform_git_reset: boolean | null, };
@@ -320,7 +603,16 @@ export type AddWorkspaceRepoRequest = { repo_id: string, target_branch: string,
 
 export type AddWorkspaceRepoResponse = { workspace: Workspace, repo: RepoWithTargetBranch, };
 
-export type MergeWorkspaceRequest = { repo_id: string, };
+export type MergeWorkspaceRequest = { repo_id: string, 
+/**
+ * Overrides the repo's default merge method for this merge.
+ */
+merge_method?: MergeMethod, 
+/**
+ * Leave the workspace active after merging so the agent can keep working
+ * and merge again later. Otherwise unpinned workspaces are archived.
+ */
+keep_active?: boolean, };
 
 export type PushWorkspaceRequest = { repo_id: string, };
 
@@ -332,6 +624,17 @@ export type StartReviewRequest = { executor_config: ExecutorConfig, additional_p
 
 export type ReviewError = { "type": "process_already_running" };
 
+export type InjectMessageRequest = { message: string, 
+/**
+ * Used when the running executor cannot take messages mid-run and the
+ * message is queued as a follow-up instead.
+ */
+executor_config: ExecutorConfig, };
+
+export type InjectMessageResponse = { "delivery": "injected", execution_process_id: string, } | { "delivery": "queued", message: QueuedMessage, };
+
+export type InjectMessageError = { "type": "no_running_agent" };
+
 export type OpenEditorRequest = { editor_type: string | null, file_path: string | null, };
 
 export type OpenEditorResponse = { url: string | null, };
@@ -354,7 +657,11 @@ export type CreateWorkspaceApiRequest = { name: string | null, };
 
 export type LinkedIssueInfo = { remote_project_id: string, issue_id: string, };
 
-export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, };
+export type CreatePrApiRequest = { title: string, body: string | null, target_branch: string | null, draft: boolean | null, repo_id: string, auto_generate_description: boolean, 
+/**
+ * Added to the repo's default PR labels.
+ */
+labels: Array<string>, };
 
 export type AttachmentResponse = { id: string, file_path: string, original_name: string, mime_type: string | null, size_bytes: bigint, hash: string, created_at: string, updated_at: string, };
 
@@ -374,13 +681,22 @@ export type ContinueRebaseRequest = { repo_id: string, };
 
 export type AbortConflictsRequest = { repo_id: string, };
 
-export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, target_branch: string, } | { "type": "rebase_in_progress" };
+export type GitOperationError = { "type": "merge_conflicts", message: string, op: ConflictOp, conflicted_files: Array<string>, 
+/**
+ * Per-file detail for `conflicted_files`, in the same order.
+ */
+conflicts: Array<ConflictedFile>, target_branch: string, } | { "type": "rebase_in_progress" };
 
 export type PushError = { "type": "force_push_required" };
 
 export type PrError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "git_cli_not_logged_in" } | { "type": "git_cli_not_installed" } | { "type": "target_branch_not_found", branch: string, } | { "type": "unsupported_provider" };
 
-export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" };
+/**
+ * What creating a pull request did.
+ */
+export type CreatePrResponse = { "type": "pr_created", url: string, } | { "type": "branch_pushed", branch: string, };
+
+export type RunScriptError = { "type": "no_script_configured" } | { "type": "process_already_running" } | { "type": "no_failed_setup" };
 
 export type AssociateWorkspaceAttachmentsRequest = { attachment_ids: Array<string>, };
 
@@ -394,24 +710,57 @@ export type AttachExistingPrRequest = { repo_id: string, };
 
 export type PrCommentsResponse = { comments: Array<UnifiedPrComment>, };
 
+export type PrDescriptionResponse = { title: string, body: string, };
+
+export type WorktreeSnapshot = { id: string, 
+/**
+ * Repos the snapshot covers; restoring only touches these.
+ */
+repo_ids: Array<string>, };
+
+export type TestNotificationResult = { sound: boolean, push: boolean, };
+
 export type GetPrCommentsError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, };
 
 export type GetPrCommentsQuery = { repo_id: string, };
 
-export type CreateAndStartWorkspaceRequest = { name: string | null, repos: Array<WorkspaceRepoInput>, linked_issue: LinkedIssueInfo | null, executor_config: ExecutorConfig, prompt: string, attachment_ids: Array<string> | null, };
+export type UpdatePrBaseRequest = { repo_id: string, new_base: string, };
+
+export type UpdatePrBaseError = { "type": "no_pr_attached" } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "cli_not_logged_in", provider: ProviderKind, } | { "type": "unsupported_by_provider", provider: ProviderKind, };
+
+export type CreateAndStartWorkspaceRequest = { name: string | null, repos: Array<WorkspaceRepoInput>, linked_issue: LinkedIssueInfo | null, executor_config: ExecutorConfig, prompt: string, 
+/**
+ * Compose the prompt from the linked issue using the configured issue
+ * prompt template. A non-empty `prompt` is appended after it.
+ */
+prompt_from_issue?: boolean, attachment_ids: Array<string> | null, 
+/**
+ * Environment variables for this workspace's runs only. They override
+ * config and profile env.
+ */
+env_overrides?: { [key in string]?: string }, mode?: WorkspaceStartMode, };
+
+/**
+ * How the first coding agent run of a new workspace is carried out.
+ */
+export type WorkspaceStartMode = "run" | "preview";
 
 export type CreateAndStartWorkspaceResponse = { workspace: Workspace, execution_process: ExecutionProcess, };
 
 export type UnifiedPrComment = { "comment_type": "general", id: string, author: string, author_association: string | null, body: string, created_at: string, url: string | null, } | { "comment_type": "review", id: bigint, author: string, author_association: string | null, body: string, created_at: string, url: string | null, path: string, line: bigint | null, side: string | null, diff_hunk: string | null, };
 
-export type ProviderKind = "git_hub" | "azure_dev_ops" | "unknown";
+export type ProviderKind = "git_hub" | "azure_dev_ops" | "generic" | "unknown";
+
+export type ProviderCapabilities = { draft_prs: boolean, squash_merge: boolean, review_threads: boolean, cross_fork_prs: boolean, list_open_prs: boolean, edit_pr_base: boolean, };
 
-export type PullRequestDetail = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, title: string, base_branch: string, head_branch: string, };
+export type PullRequestDetail = { number: bigint, url: string, status: MergeStatus, merged_at: string | null, merge_commit_sha: string | null, checks_status: ChecksStatus | null, title: string, base_branch: string, head_branch: string, };
 
 export type GitRemote = { name: string, url: string, };
 
 export type ListPrsError = { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "auth_failed", message: string, } | { "type": "unsupported_provider" };
 
+export type ProviderCapabilitiesResponse = { provider: ProviderKind, capabilities: ProviderCapabilities, };
+
 export type LinkPrToIssueRequest = { pr_url: string, pr_number: number, base_branch: string, };
 
 export type CreateWorkspaceFromPrBody = { repo_id: string, pr_number: bigint, pr_title: string, pr_url: string, head_branch: string, base_branch: string, run_setup: boolean, remote_name: string | null, };
@@ -420,7 +769,53 @@ export type CreateWorkspaceFromPrResponse = { workspace: Workspace, };
 
 export type CreateFromPrError = { "type": "pr_not_found" } | { "type": "branch_fetch_failed", message: string, } | { "type": "cli_not_installed", provider: ProviderKind, } | { "type": "auth_failed", message: string, } | { "type": "unsupported_provider" };
 
-export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, is_rebase_in_progress: boolean, conflict_op: ConflictOp | null, conflicted_files: Array<string>, is_target_remote: boolean, };
+export type CreateWorkspaceFromBranchBody = { repo_id: string, 
+/**
+ * Existing local branch to manage. No new branch is created.
+ */
+branch: string, 
+/**
+ * Branch the work will be merged into. Defaults to the repo's default
+ * target branch, then to the branch checked out in the main repository.
+ */
+target_branch: string | null, name: string | null, };
+
+export type CreateWorkspaceFromBranchResponse = { workspace: Workspace, 
+/**
+ * Merge-base of the branch with the target branch, recorded on the
+ * workspace repo.
+ */
+base_commit: string, };
+
+export type PreviewBranchNameRequest = { name: string | null, 
+/**
+ * Repos to check for existing branches that would block the name.
+ */
+repo_ids: Array<string>, };
+
+export type PreviewBranchNameResponse = { 
+/**
+ * Example branch name. The short id after the prefix is generated per
+ * workspace, so the created branch differs only in that segment.
+ */
+branch: string, valid: boolean, conflicts: Array<BranchNameConflict>, };
+
+export type BranchNameConflict = { repo_id: string, 
+/**
+ * Existing branch that clashes, either the same name (locally or on a
+ * remote) or a local branch named like the prefix, which git can't nest
+ * branches under.
+ */
+existing_branch: string, };
+
+export type CreateFromBranchError = { "type": "branch_not_found" } | { "type": "branch_already_managed" } | { "type": "branch_checked_out" } | { "type": "no_merge_base", target_branch: string, };
+
+export type RepoBranchStatus = { repo_id: string, repo_name: string, commits_behind: number | null, commits_ahead: number | null, has_uncommitted_changes: boolean | null, head_oid: string | null, uncommitted_count: number | null, untracked_count: number | null, target_branch_name: string, remote_commits_behind: number | null, remote_commits_ahead: number | null, merges: Array<Merge>, is_rebase_in_progress: boolean, conflict_op: ConflictOp | null, conflicted_files: Array<string>, is_target_remote: boolean, 
+/**
+ * Set when a merge or rebase was cut short by a server restart and
+ * could not be rolled back automatically.
+ */
+interrupted_operation: MergeOperationKind | null, };
 
 export type UpdateWorkspace = { archived: boolean | null, pinned: boolean | null, name: string | null, };
 
@@ -476,7 +871,12 @@ pr_number: bigint | null,
 /**
  * PR URL for this workspace (if any PR exists)
  */
-pr_url: string | null, };
+pr_url: string | null, 
+/**
+ * Wall-clock milliseconds spent running the agent and scripts, across all
+ * sessions. Includes time elapsed so far for anything still running.
+ */
+time_spent_ms: number, };
 
 export type WorkspaceSummaryResponse = { summaries: Array<WorkspaceSummary>, };
 
@@ -488,7 +888,37 @@ export type DirectoryListResponse = { entries: Array<DirectoryEntry>, current_pa
 
 export type SearchMode = "taskform" | "settings";
 
-export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, remote_onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, send_message_shortcut: SendMessageShortcut, relay_enabled: boolean, host_nickname: string | null, };
+export type Config = { config_version: string, theme: ThemeMode, executor_profile: ExecutorProfileId, disclaimer_acknowledged: boolean, onboarding_acknowledged: boolean, remote_onboarding_acknowledged: boolean, notifications: NotificationConfig, editor: EditorConfig, github: GitHubConfig, analytics_enabled: boolean, analytics_event_allowlist: Array<string> | null, workspace_dir: string | null, last_app_version: string | null, show_release_notes: boolean, language: UiLanguage, git_branch_prefix: string, 
+/**
+ * Names new workspace branches, after the prefix. Must contain
+ * `{task_id}`. Unset keeps the `{task_id}-{task_title_slug}` naming.
+ */
+git_branch_template: string | null, showcases: ShowcaseState, pr_auto_description_enabled: boolean, pr_auto_description_prompt: string | null, commit_reminder_enabled: boolean, commit_reminder_prompt: string | null, agent_identity_enabled: boolean, agent_identity_template: string | null, 
+/**
+ * First prompt for workspaces started from a linked issue. Unset uses
+ * `{title}\n\n{description}`.
+ */
+issue_prompt_template: string | null, conventional_commits: ConventionalCommitConfig, 
+/**
+ * Push branches to remotes that aren't a supported host instead of
+ * refusing, leaving the pull request to be opened by hand.
+ */
+generic_git_fallback: boolean, exclude_agent_local_files: boolean, disallow_permission_bypass: boolean, 
+/**
+ * Default for Claude Code's `disable_api_key` in profiles that don't
+ * set it, so subscription users aren't billed through an API key.
+ */
+claude_disable_api_key: boolean, strip_ansi_from_stored_logs: boolean, cache_session_replays: boolean, send_message_shortcut: SendMessageShortcut, relay_enabled: boolean, host_nickname: string | null, webhooks: Array<WebhookConfig>, 
+/**
+ * Most coding agent runs at once across all workspaces; further runs
+ * wait for a slot. Unset means no machine-wide limit.
+ */
+max_concurrent_executions: number | null, 
+/**
+ * Path prefix to serve the app and API under, e.g. `/vibe` behind a
+ * reverse proxy. Applied on the next start.
+ */
+base_path: string | null, };
 
 export type NotificationConfig = { sound_enabled: boolean, push_enabled: boolean, sound_file: SoundFile, };
 
@@ -510,6 +940,22 @@ export type ShowcaseState = { seen_features: Array<string>, };
 
 export type SendMessageShortcut = "ModifierEnter" | "Enter";
 
+export type ConventionalCommitConfig = { mode: ConventionalCommitMode, allowed_types: Array<string>, max_subject_length: number, };
+
+export type ConventionalCommitMode = "Off" | "Reject" | "AutoPrefix";
+
+export type WebhookConfig = { url: string, 
+/**
+ * Signs each delivery with HMAC-SHA256 in the `X-VK-Signature` header.
+ */
+secret: string | null, 
+/**
+ * Events to deliver. Empty delivers all of them.
+ */
+events: Array<WebhookEventType>, };
+
+export type WebhookEventType = "workspace_created" | "execution_started" | "execution_completed" | "execution_failed" | "workspace_merged";
+
 export type GitBranch = { name: string, is_current: boolean, is_remote: boolean, last_commit_date: Date, };
 
 export type QueuedMessage = { 
@@ -528,8 +974,109 @@ queued_at: string, };
 
 export type QueueStatus = { "status": "empty" } | { "status": "queued", message: QueuedMessage, };
 
+export type RepoConcurrencyStatus = { max_concurrent_attempts: number | null, 
+/**
+ * Runs currently holding a slot.
+ */
+running: number, 
+/**
+ * Runs waiting for a slot to free up.
+ */
+queued: number, };
+
+/**
+ * Workspaces a task has started against its repos' per-task cap.
+ */
+export type TaskAttemptUsage = { 
+/**
+ * Workspaces started for the task, including archived ones.
+ */
+attempts: number, 
+/**
+ * Lowest `max_attempts_per_task` among the repos.
+ */
+max_attempts: number | null, remaining: number | null, 
+/**
+ * Display name of the repo whose cap applies.
+ */
+limited_by: string | null, };
+
+export type PrMonitorStatus = { paused: boolean, paused_at: string | null, last_polled_at: string | null, };
+
+export type AgentPlan = { execution_process_id: string, plan: string, status: ToolStatus, };
+
+export type ProposedFileChange = { path: string, 
+/**
+ * In the order the agent made them.
+ */
+changes: Array<FileChange>, };
+
+export type WorkspacePreview = { execution_process_id: string, 
+/**
+ * The list is complete once the run is no longer running.
+ */
+status: ExecutionProcessStatus, changes: Array<ProposedFileChange>, };
+
+export type OutcomeSummary = { status: ExecutionProcessStatus, 
+/**
+ * Paths the agent edited, in the order first touched.
+ */
+files_changed: Array<string>, test_runs: Array<TestRunSummary>, };
+
+export type TestRunner = "cargo" | "jest" | "pytest";
+
+export type TestRunSummary = { command: string, runner: TestRunner, passed: number, failed: number, skipped: number, };
+
+export type RespondToPlanRequest = { approved: boolean, 
+/**
+ * What to change, sent to the agent when the plan is rejected.
+ */
+feedback?: string, };
+
+export type RenormalizeJob = { id: string, workspace_id: string | null, repo_id: string | null, concurrency: number, total: number, completed: number, failed: number, skipped: number, status: RenormalizeJobStatus, started_at: string, finished_at: string | null, };
+
+export type RenormalizeJobStatus = "running" | "completed";
+
+/**
+ * Scope of a bulk renormalize job. Exactly one of `workspace_id` and
+ * `repo_id` must be set.
+ */
+export type RenormalizeProcessesRequest = { workspace_id?: string, repo_id?: string, 
+/**
+ * Processes renormalized at once. Defaults to 2, capped at 8.
+ */
+concurrency?: number, };
+
+export type Ecosystem = "node" | "rust" | "python" | "go" | "ruby" | "java" | "php" | "dotnet";
+
+export type DetectedEcosystem = { ecosystem: Ecosystem, 
+/**
+ * Top-level files that matched, in the order they were checked.
+ */
+marker_files: Array<string>, 
+/**
+ * Suggested setup script for this ecosystem.
+ */
+setup_command: string, };
+
 export type ConflictOp = "rebase" | "merge" | "cherry_pick" | "revert";
 
+/**
+ * A file left unmerged by a merge or rebase.
+ */
+export type ConflictedFile = { path: string, 
+/**
+ * Number of `<<<<<<<` conflict regions in the file; 0 when the conflict
+ * isn't textual (e.g. modify/delete).
+ */
+conflict_markers: number, };
+
+/**
+ * How a task branch is integrated into its base branch: a merge commit, a
+ * single squashed commit, or the task commits replayed onto the base.
+ */
+export type MergeMethod = "merge" | "squash" | "rebase";
+
 export type ExecutorAction = { typ: ExecutorActionType, next_action: ExecutorAction | null, };
 
 export type McpConfig = { servers: { [key in string]?: JsonValue }, servers_path: Array<string>, template: JsonValue, preconfigured: JsonValue, is_toml_config: boolean, };
@@ -562,7 +1109,7 @@ reasoning_id?: string | null,
  */
 permission_policy?: PermissionPolicy | null, };
 
-export type ScriptContext = "SetupScript" | "CleanupScript" | "ArchiveScript" | "DevServer" | "ToolInstallScript";
+export type ScriptContext = "SetupScript" | "CleanupScript" | "ArchiveScript" | "PostMergeScript" | "DevServer" | "ToolInstallScript";
 
 export type ScriptRequest = { script: string, language: ScriptRequestLanguage, context: ScriptContext, 
 /**
@@ -623,13 +1170,32 @@ export enum BaseAgentCapability { SESSION_FORK = "SESSION_FORK", SETUP_HELPER =
 
 export type ClaudeEffort = "low" | "medium" | "high" | "xhigh" | "max";
 
-export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, effort?: ClaudeEffort | null, agent?: string | null, dangerously_skip_permissions?: boolean | null, disable_api_key?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type ClaudeCode = { append_prompt: AppendPrompt, claude_code_router?: boolean | null, plan?: boolean | null, approvals?: boolean | null, model?: string | null, effort?: ClaudeEffort | null, agent?: string | null, dangerously_skip_permissions?: boolean | null, 
+/**
+ * Unset uses the global default from the server config.
+ */
+disable_api_key?: boolean | null, 
+/**
+ * Tool calls to deny without asking, checked before any approval.
+ * Unset uses a built-in list of destructive commands; empty turns it off.
+ */
+auto_deny_rules?: Array<AutoDenyRule> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
+
+export type AutoDenyRule = { 
+/**
+ * Regex matched against the Bash command, or the tool name for other tools
+ */
+pattern: string, 
+/**
+ * Reason sent to the agent when the rule denies a tool call
+ */
+reason: string, };
 
-export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Gemini = { append_prompt: AppendPrompt, model?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
-export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Amp = { append_prompt: AppendPrompt, dangerously_allow_all?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
-export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, plan: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Codex = { append_prompt: AppendPrompt, sandbox?: SandboxMode | null, ask_for_approval?: AskForApproval | null, oss?: boolean | null, model?: string | null, model_reasoning_effort?: ReasoningEffort | null, model_reasoning_summary?: ReasoningSummary | null, model_reasoning_summary_format?: ReasoningSummaryFormat | null, profile?: string | null, base_instructions?: string | null, include_apply_patch_tool?: boolean | null, model_provider?: string | null, compact_prompt?: string | null, developer_instructions?: string | null, plan: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
 export type SandboxMode = "auto" | "read-only" | "workspace-write" | "danger-full-access";
 
@@ -641,9 +1207,9 @@ export type ReasoningSummary = "auto" | "concise" | "detailed" | "none";
 
 export type ReasoningSummaryFormat = "none" | "experimental";
 
-export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, reasoning?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type CursorAgent = { append_prompt: AppendPrompt, force?: boolean | null, model?: string | null, reasoning?: string | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
-export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Copilot = { append_prompt: AppendPrompt, model?: string | null, allow_all_tools?: boolean | null, allow_tool?: string | null, deny_tool?: string | null, add_dir?: Array<string> | null, disable_mcp_server?: Array<string> | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
 export type Opencode = { append_prompt: AppendPrompt, model?: string | null, variant?: string | null, agent?: string | null, 
 /**
@@ -653,11 +1219,11 @@ auto_approve: boolean,
 /**
  * Enable auto-compaction when the context length approaches the model's context window limit
  */
-auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+auto_compact: boolean, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
-export type QwenCode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type QwenCode = { append_prompt: AppendPrompt, model?: string | null, agent?: string | null, yolo?: boolean | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
-export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, };
+export type Droid = { append_prompt: AppendPrompt, autonomy: Autonomy, model?: string | null, reasoning_effort?: DroidReasoningEffort | null, base_command_override?: string | null, additional_params?: Array<string> | null, env?: { [key in string]?: string } | null, log_normalizer?: string | null, inactivity_timeout_secs?: number | null, };
 
 export type Autonomy = "normal" | "low" | "medium" | "high" | "skip-permissions-unsafe";
 
@@ -674,7 +1240,11 @@ executor_config: ExecutorConfig,
  * Optional relative path to execute the agent in (relative to container_ref).
  * If None, uses the container_ref directory directly.
  */
-working_dir: string | null, };
+working_dir: string | null, 
+/**
+ * Dry run: the agent's file changes are reported but never kept.
+ */
+preview?: boolean, };
 
 export type CodingAgentFollowUpRequest = { prompt: string, session_id: string, reset_to_message_id: string | null, 
 /**
@@ -711,9 +1281,15 @@ export type CommandCategory = "read" | "search" | "edit" | "fetch" | "other";
 
 export type NormalizedEntry = { timestamp: string | null, entry_type: NormalizedEntryType, content: string, };
 
-export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo | { "type": "user_answered_questions", answers: Array<AnsweredQuestion>, };
+export type NormalizedEntryType = { "type": "user_message" } | { "type": "user_feedback", denied_tool: string, } | { "type": "assistant_message" } | { "type": "tool_use", tool_name: string, action_type: ActionType, status: ToolStatus, } | { "type": "system_message" } | { "type": "error_message", error_type: NormalizedEntryError, } | { "type": "thinking" } | { "type": "loading" } | { "type": "next_action", failed: boolean, execution_processes: number, needs_setup: boolean, } | { "type": "token_usage_info" } & TokenUsageInfo | { "type": "user_answered_questions", answers: Array<AnsweredQuestion>, } | { "type": "approval_request", tool_name: string, approval_id: string, status: ApprovalStatus, };
 
-export type TokenUsageInfo = { total_tokens: number, model_context_window: number, };
+export type TokenUsageInfo = { total_tokens: number, model_context_window: number, 
+/**
+ * Tokens billed over the whole run, set on the entry emitted when it ends.
+ */
+session_totals?: TokenUsageTotals, };
+
+export type TokenUsageTotals = { input_tokens: bigint, output_tokens: bigint, cache_read_input_tokens: bigint, cache_creation_input_tokens: bigint, };
 
 export type FileChange = { "action": "write", content: string, } | { "action": "delete" } | { "action": "rename", new_path: string, } | { "action": "edit", 
 /**
@@ -885,4 +1461,18 @@ session_id: string, };
 
 export const DEFAULT_PR_DESCRIPTION_PROMPT = "Update the PR that was just created with a better title and description.\nThe PR number is #{pr_number} and the URL is {pr_url}.\n\nAnalyze the changes in this branch and write:\n1. A concise, descriptive title that summarizes the changes, postfixed with \"(Vibe Kanban)\"\n2. A detailed description that explains:\n   - What changes were made\n   - Why they were made (based on the task context)\n   - Any important implementation details\n   - At the end, include a note: \"This PR was written using [Vibe Kanban](https://vibekanban.com)\"\n\nUse the appropriate CLI tool to update the PR (gh pr edit for GitHub, az repos pr update for Azure DevOps).";
 
-export const DEFAULT_COMMIT_REMINDER_PROMPT = "There are uncommitted changes. Please stage and commit them now with a descriptive commit message.";
\ No newline at end of file
+export const DEFAULT_COMMIT_REMINDER_PROMPT = "There are uncommitted changes. Please stage and commit them now with a descriptive commit message.";
+
+export const DEFAULT_AGENT_IDENTITY_TEMPLATE = "<vibe-kanban-context>\nYou are working inside Vibe Kanban on behalf of {user}.\nWorkspace: {workspace_name} ({workspace_id})\nBranch: {branch}\nRepositories: {repos}\nUse this context when writing commit messages and pull request descriptions.\n</vibe-kanban-context>";
+
+export const DEFAULT_DENIED_TOOL_NOTE_TEMPLATE = "The previous {tool} call was denied by the user. Reason: {reason}. Do not retry it.";
+
+export const DEFAULT_PR_TITLE_TEMPLATE = "{task_title}";
+
+export const DEFAULT_PR_BODY_TEMPLATE = "{summary}";
+
+export const DEFAULT_NOTIFICATION_TITLE_TEMPLATE = "Workspace Complete: {task}";
+
+export const DEFAULT_NOTIFICATION_BODY_TEMPLATE = "{outcome}: '{task}'\nBranch: {branch}\nExecutor: {executor}\nDuration: {duration}\n{summary}";
+
+export const DEFAULT_ISSUE_PROMPT_TEMPLATE = "{title}\n\n{description}";